@@ -4,10 +4,14 @@
 //! - Packages installed but not in Brewfile (untracked)
 //! - Packages in Brewfile but not installed (missing)
 //! - Packages with version mismatches
+//!
+//! Audits of formulas also report dependencies left behind by uninstalled
+//! formulas ([`AuditResult::removable`]).
 
 use crate::backend::Backend;
-use crate::error::Result;
-use crate::types::{AuditResult, Brewfile, Package, PackageType};
+use crate::error::{Error, Result};
+use crate::provider::{self, PackageProvider};
+use crate::types::{AuditResult, Brewfile, InstalledPackage, Package, PackageType};
 use std::collections::HashSet;
 
 /// Audit installed packages against a Brewfile.
@@ -21,17 +25,92 @@ pub fn audit(backend: &dyn Backend, brewfile: &Brewfile) -> Result<AuditResult>
     audit_type(backend, brewfile, PackageType::Brew, &mut result)?;
     audit_type(backend, brewfile, PackageType::Cask, &mut result)?;
 
-    // Optionally audit mas and vscode if there are entries
+    // mas only if there are entries: `mas list` includes every App Store
+    // app, most of which nobody tracks in a Brewfile
     if !brewfile.mas_apps().is_empty() {
         audit_type(backend, brewfile, PackageType::Mas, &mut result)?;
     }
-    if !brewfile.vscode_extensions().is_empty() {
-        audit_type(backend, brewfile, PackageType::Vscode, &mut result)?;
-    }
+    audit_type(backend, brewfile, PackageType::Vscode, &mut result)?;
+    result.removable = removable(backend, brewfile)?;
 
     Ok(result)
 }
 
+/// Orphaned dependencies, minus any the Brewfile declares.
+fn removable(backend: &dyn Backend, brewfile: &Brewfile) -> Result<Vec<String>> {
+    let declared: HashSet<String> = brewfile
+        .packages_of_type(PackageType::Brew)
+        .into_iter()
+        .map(declared_key)
+        .collect();
+    Ok(backend
+        .dependency_graph()?
+        .orphans()
+        .into_iter()
+        .filter(|name| !declared.contains(&name.to_lowercase()))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Audit only the packages in the given groups.
+///
+/// Missing and mismatched packages are restricted to the selected groups (plus
+/// taps), while untracked packages are still computed against the whole
+/// Brewfile so packages from other groups aren't reported as untracked.
+pub fn audit_groups(
+    backend: &dyn Backend,
+    brewfile: &Brewfile,
+    groups: &[impl AsRef<str>],
+) -> Result<AuditResult> {
+    let mut result = audit(backend, brewfile)?;
+    restrict_to_groups(&mut result, groups);
+    Ok(result)
+}
+
+/// Drop missing and mismatched packages outside `groups` (taps are kept).
+pub fn restrict_to_groups(result: &mut AuditResult, groups: &[impl AsRef<str>]) {
+    let selected = |p: &Package| p.package_type == PackageType::Tap || p.in_groups(groups);
+
+    result.missing.retain(|p| selected(p));
+    result.mismatched.retain(|(p, _)| selected(p));
+}
+
+/// Drop missing and mismatched packages that don't apply to a machine with
+/// `tags` (see [`Package::applies_to`]).
+pub fn restrict_to_tags(result: &mut AuditResult, tags: &[impl AsRef<str>]) {
+    result.missing.retain(|p| p.applies_to(tags));
+    result.mismatched.retain(|(p, _)| p.applies_to(tags));
+}
+
+/// Audit the packages of a custom provider, adding its drift to `result`.
+pub fn audit_provider(
+    provider: &dyn PackageProvider,
+    brewfile: &Brewfile,
+    result: &mut AuditResult,
+) -> Result<()> {
+    let installed = provider::installed_packages(provider)?;
+    compare(
+        &installed,
+        &brewfile.custom_packages(provider.directive()),
+        result,
+    );
+    Ok(())
+}
+
+/// List installed packages, treating a missing `mas` or `code` CLI as
+/// nothing installed.
+fn list_installed(
+    backend: &dyn Backend,
+    package_type: PackageType,
+) -> Result<Vec<InstalledPackage>> {
+    match backend.list_installed(package_type) {
+        Err(Error::Other(_)) if matches!(package_type, PackageType::Mas | PackageType::Vscode) => {
+            Ok(Vec::new())
+        }
+        result => result,
+    }
+}
+
 /// Audit a single package type.
 fn audit_type(
     backend: &dyn Backend,
@@ -39,40 +118,42 @@ fn audit_type(
     package_type: PackageType,
     result: &mut AuditResult,
 ) -> Result<()> {
-    // Get installed packages of this type
-    let installed = backend.list_installed(package_type)?;
-    let installed_names: HashSet<String> =
-        installed.iter().map(|p| p.name.to_lowercase()).collect();
+    // Get installed and declared packages of this type
+    let installed = list_installed(backend, package_type)?;
+    compare(&installed, &brewfile.packages_of_type(package_type), result);
+    Ok(())
+}
 
-    // Get Brewfile packages of this type
-    let declared: Vec<&Package> = brewfile.packages_of_type(package_type);
-    let declared_names: HashSet<String> = declared.iter().map(|p| p.name.to_lowercase()).collect();
+/// Compare installed packages of one type with their Brewfile entries.
+fn compare(installed: &[InstalledPackage], declared: &[&Package], result: &mut AuditResult) {
+    let installed_names: HashSet<String> = installed.iter().map(installed_key).collect();
+    let declared_names: HashSet<String> = declared.iter().map(|p| declared_key(p)).collect();
 
     // Find untracked (installed but not declared)
-    for pkg in &installed {
+    for pkg in installed {
         // For brew formulas, only report packages installed on request
-        if package_type == PackageType::Brew && !pkg.installed_on_request {
+        if pkg.package_type == PackageType::Brew && !pkg.installed_on_request {
             continue;
         }
 
-        if !declared_names.contains(&pkg.name.to_lowercase()) {
+        if !declared_names.contains(&installed_key(pkg)) {
             result.untracked.push(pkg.clone());
         }
     }
 
     // Find missing (declared but not installed)
-    for pkg in &declared {
-        if !installed_names.contains(&pkg.name.to_lowercase()) {
+    for pkg in declared {
+        if !installed_names.contains(&declared_key(pkg)) {
             result.missing.push((*pkg).clone());
         }
     }
 
     // Find version mismatches
-    for pkg in &declared {
+    for pkg in declared {
         if let Some(declared_version) = &pkg.version
             && let Some(installed_pkg) = installed
                 .iter()
-                .find(|i| i.name.to_lowercase() == pkg.name.to_lowercase())
+                .find(|i| installed_key(i) == declared_key(pkg))
             && !versions_match(declared_version, &installed_pkg.version)
         {
             result
@@ -80,8 +161,26 @@ fn audit_type(
                 .push(((*pkg).clone(), installed_pkg.clone()));
         }
     }
+}
 
-    Ok(())
+/// Key used to match an installed package against Brewfile entries.
+///
+/// mas apps are matched by App Store ID, since the name in the Brewfile is
+/// free-form; everything else by case-insensitive name.
+fn installed_key(pkg: &InstalledPackage) -> String {
+    match (pkg.package_type, &pkg.id) {
+        (PackageType::Mas, Some(id)) => id.clone(),
+        _ => pkg.name.to_lowercase(),
+    }
+}
+
+/// Key used to match a Brewfile entry against installed packages.
+///
+/// Namespaced formulas and casks (`owner/tap/name`) match by their short
+/// name, which is how brew lists them.
+fn declared_key(pkg: &Package) -> String {
+    pkg.mas_id()
+        .map_or_else(|| pkg.short_name().to_lowercase(), str::to_string)
 }
 
 /// Check if two versions match (allowing for some flexibility).
@@ -117,6 +216,9 @@ pub struct AuditOptions {
     pub include_dependencies: bool,
     /// Package types to audit (empty means all)
     pub package_types: Vec<PackageType>,
+    /// Only report missing and mismatched packages from these groups (empty
+    /// means all)
+    pub groups: Vec<String>,
 }
 
 /// Audit with options.
@@ -139,9 +241,16 @@ pub fn audit_with_options(
         options.package_types.clone()
     };
 
-    for package_type in types_to_audit {
+    // Custom packages are audited by their providers, not the backend
+    for &package_type in types_to_audit.iter().filter(|t| **t != PackageType::Custom) {
         audit_type_with_options(backend, brewfile, package_type, options, &mut result)?;
     }
+    if types_to_audit.contains(&PackageType::Brew) {
+        result.removable = removable(backend, brewfile)?;
+    }
+    if !options.groups.is_empty() {
+        restrict_to_groups(&mut result, &options.groups);
+    }
 
     Ok(result)
 }
@@ -153,12 +262,11 @@ fn audit_type_with_options(
     options: &AuditOptions,
     result: &mut AuditResult,
 ) -> Result<()> {
-    let installed = backend.list_installed(package_type)?;
-    let installed_names: HashSet<String> =
-        installed.iter().map(|p| p.name.to_lowercase()).collect();
+    let installed = list_installed(backend, package_type)?;
+    let installed_names: HashSet<String> = installed.iter().map(installed_key).collect();
 
     let declared: Vec<&Package> = brewfile.packages_of_type(package_type);
-    let declared_names: HashSet<String> = declared.iter().map(|p| p.name.to_lowercase()).collect();
+    let declared_names: HashSet<String> = declared.iter().map(|p| declared_key(p)).collect();
 
     // Find untracked
     for pkg in &installed {
@@ -170,24 +278,98 @@ fn audit_type_with_options(
             continue;
         }
 
-        if !declared_names.contains(&pkg.name.to_lowercase()) {
+        if !declared_names.contains(&installed_key(pkg)) {
             result.untracked.push(pkg.clone());
         }
     }
 
     // Find missing
     for pkg in &declared {
-        if !installed_names.contains(&pkg.name.to_lowercase()) {
+        if !installed_names.contains(&declared_key(pkg)) {
             result.missing.push((*pkg).clone());
         }
     }
 
+    // Find version mismatches
+    for pkg in &declared {
+        if let Some(declared_version) = &pkg.version
+            && let Some(installed_pkg) = installed
+                .iter()
+                .find(|i| installed_key(i) == declared_key(pkg))
+            && !versions_match(declared_version, &installed_pkg.version)
+        {
+            result
+                .mismatched
+                .push(((*pkg).clone(), installed_pkg.clone()));
+        }
+    }
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::mock::{MockBackend, installed};
+
+    #[test]
+    fn test_audit_matches_mas_apps_by_id() {
+        let mut brewfile = Brewfile::new();
+        // Name differs from the App Store name, the ID is what counts
+        brewfile.add(Package::mas("Xcode.app", "497799835"));
+        brewfile.add(Package::mas("Keynote", "409183694"));
+
+        let backend = MockBackend::default().with_installed([InstalledPackage {
+            id: Some("497799835".to_string()),
+            ..installed("Xcode", PackageType::Mas)
+        }]);
+        let result = audit(&backend, &brewfile).unwrap();
+        assert!(result.untracked.is_empty());
+        let missing: Vec<_> = result.missing.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(missing, vec!["Keynote"]);
+    }
+
+    #[test]
+    fn test_audit_reports_removable_dependencies() {
+        let mut brewfile = Brewfile::new();
+        brewfile.add(Package::brew("git"));
+        // Installed as a dependency, but wanted
+        brewfile.add(Package::brew("pcre2"));
+
+        let dependency = |name| InstalledPackage {
+            installed_on_request: false,
+            ..installed(name, PackageType::Brew)
+        };
+        let backend = MockBackend::default().with_installed([
+            installed("git", PackageType::Brew),
+            dependency("libyaml"),
+            dependency("pcre2"),
+        ]);
+        let result = audit(&backend, &brewfile).unwrap();
+        assert_eq!(result.removable, vec!["libyaml"]);
+        assert!(!result.has_drift());
+    }
+
+    #[test]
+    fn test_audit_reports_vscode_extensions() {
+        let backend = MockBackend::default().with_installed([
+            installed("rust-lang.rust-analyzer", PackageType::Vscode),
+            installed("GitHub.copilot", PackageType::Vscode),
+        ]);
+
+        // Untracked extensions show up even without vscode entries
+        let result = audit(&backend, &Brewfile::new()).unwrap();
+        assert_eq!(result.untracked.len(), 2);
+
+        let mut brewfile = Brewfile::new();
+        brewfile.add(Package::vscode("Rust-Lang.rust-analyzer"));
+        brewfile.add(Package::vscode("tamasfe.even-better-toml"));
+        let result = audit(&backend, &brewfile).unwrap();
+        let untracked: Vec<_> = result.untracked.iter().map(|p| p.name.as_str()).collect();
+        let missing: Vec<_> = result.missing.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(untracked, vec!["GitHub.copilot"]);
+        assert_eq!(missing, vec!["tamasfe.even-better-toml"]);
+    }
 
     #[test]
     fn test_versions_match_exact() {
@@ -1,15 +1,49 @@
 //! Real Homebrew CLI backend using `brew` commands.
+//!
+//! Macs moved from Intel can have two Homebrew installations: the native
+//! one in `/opt/homebrew` and an Intel one in `/usr/local`, run under
+//! Rosetta. [`detect_prefixes`] finds every installation and
+//! [`BrewBackend::with_prefix`] targets a specific one.
 
 use crate::backend::Backend;
+use crate::backend::cache::InfoCache;
+use crate::deps::DependencyGraph;
 use crate::error::{Error, Result};
-use crate::types::{BundleResult, InstalledPackage, Package, PackageType};
-use std::path::Path;
-use std::process::Command;
+use crate::progress::{BrewProgress, NoProgress};
+use crate::types::{
+    BundleResult, CleanupOptions, CleanupResult, InstalledPackage, OutdatedPackage, Package,
+    PackageType, UninstallOptions,
+};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::Arc;
+use std::thread;
+
+/// Where Homebrew installs itself, in the order `brew` is looked up.
+const KNOWN_PREFIXES: [&str; 3] = [
+    "/opt/homebrew",              // Apple Silicon
+    "/usr/local",                 // Intel
+    "/home/linuxbrew/.linuxbrew", // Linux
+];
+
+/// Homebrew prefixes installed on this machine, the default one first.
+pub fn detect_prefixes() -> Vec<PathBuf> {
+    KNOWN_PREFIXES
+        .iter()
+        .map(PathBuf::from)
+        .filter(|prefix| prefix.join("bin/brew").exists())
+        .collect()
+}
 
 /// Backend that executes real `brew` commands.
 pub struct BrewBackend {
     /// Path to the brew executable
     brew_path: String,
+    /// Run brew as an Intel binary (an `/usr/local` brew on Apple Silicon)
+    x86_64: bool,
+    /// Cached metadata of installed formulas and casks
+    info_cache: InfoCache,
 }
 
 impl BrewBackend {
@@ -17,13 +51,78 @@ impl BrewBackend {
     ///
     /// Returns an error if Homebrew is not installed.
     pub fn new() -> Result<Self> {
-        let brew_path = find_brew()?;
-        Ok(Self { brew_path })
+        Self::with_info_cache(InfoCache::default())
+    }
+
+    /// Create a BrewBackend with a custom metadata cache.
+    pub fn with_info_cache(info_cache: InfoCache) -> Result<Self> {
+        Ok(Self::at(find_brew()?, info_cache))
+    }
+
+    /// Create a BrewBackend for the Homebrew installed in `prefix`
+    /// (e.g. `/usr/local`), regardless of which `brew` comes first in `PATH`.
+    ///
+    /// Returns an error if there is no brew in that prefix.
+    pub fn with_prefix(prefix: &Path, info_cache: InfoCache) -> Result<Self> {
+        let brew_path = prefix.join("bin/brew");
+        if !brew_path.exists() {
+            return Err(Error::BrewNotFound);
+        }
+        Ok(Self::at(
+            brew_path.to_string_lossy().into_owned(),
+            info_cache,
+        ))
+    }
+
+    fn at(brew_path: String, info_cache: InfoCache) -> Self {
+        // An Intel brew refuses to run natively on Apple Silicon
+        let x86_64 = cfg!(all(target_os = "macos", target_arch = "aarch64"))
+            && brew_path.starts_with("/usr/local/");
+        Self {
+            brew_path,
+            x86_64,
+            info_cache,
+        }
+    }
+
+    /// Prefix of the Homebrew installation this backend runs.
+    pub fn prefix(&self) -> &Path {
+        Path::new(&self.brew_path)
+            .parent()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new("/"))
+    }
+
+    /// Command running this backend's brew.
+    fn command(&self) -> Command {
+        if self.x86_64 {
+            let mut command = Command::new("arch");
+            command.args(["-x86_64", &self.brew_path]);
+            command
+        } else {
+            Command::new(&self.brew_path)
+        }
+    }
+
+    /// `brew info --json=v2 --installed`, from the cache when fresh.
+    fn installed_info(&self) -> Result<Arc<serde_json::Value>> {
+        self.info_cache.get(|| {
+            let output = self.run_brew_checked(&["info", "--json=v2", "--installed"], None)?;
+            Ok(serde_json::from_str(&output)?)
+        })
+    }
+
+    /// Run a command that changes what is installed, invalidating the cache.
+    fn mutate<T>(&self, op: impl FnOnce() -> Result<T>) -> Result<T> {
+        let result = op();
+        self.info_cache.invalidate();
+        result
     }
 
     /// Run a brew command and return output.
     fn run_brew(&self, args: &[&str]) -> Result<std::process::Output> {
-        let output = Command::new(&self.brew_path)
+        let output = self
+            .command()
             .args(args)
             .output()
             .map_err(|e| Error::CommandFailed {
@@ -33,6 +132,48 @@ impl BrewBackend {
         Ok(output)
     }
 
+    /// Run a brew command, passing each stdout line to `on_line` as it is
+    /// printed.
+    ///
+    /// The returned output holds everything that was printed, as with
+    /// [`run_brew`](Self::run_brew).
+    fn run_brew_streaming(&self, args: &[&str], on_line: &mut dyn FnMut(&str)) -> Result<Output> {
+        let spawn_error = |e: std::io::Error| Error::CommandFailed {
+            message: format!("failed to execute brew: {e}"),
+            stderr: String::new(),
+        };
+        let mut child = self
+            .command()
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(spawn_error)?;
+
+        // Drain stderr on its own thread so a chatty brew can't block on it
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let stderr_reader = thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stderr.read_to_end(&mut buf);
+            buf
+        });
+
+        let mut stdout = String::new();
+        let reader = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        for line in reader.lines().map_while(std::io::Result::ok) {
+            on_line(&line);
+            stdout.push_str(&line);
+            stdout.push('\n');
+        }
+
+        let status = child.wait().map_err(spawn_error)?;
+        Ok(Output {
+            status,
+            stdout: stdout.into_bytes(),
+            stderr: stderr_reader.join().unwrap_or_default(),
+        })
+    }
+
     /// Run a brew command and check for success.
     fn run_brew_checked(&self, args: &[&str], package_name: Option<&str>) -> Result<String> {
         let output = self.run_brew(args)?;
@@ -52,10 +193,137 @@ impl Backend for BrewBackend {
     }
 
     fn install(&self, package: &Package) -> Result<()> {
+        self.install_with_progress(package, &NoProgress)
+    }
+
+    fn install_with_progress(&self, package: &Package, progress: &dyn BrewProgress) -> Result<()> {
+        self.mutate(|| self.install_streaming(package, progress))
+    }
+
+    fn uninstall(&self, package: &Package, options: &UninstallOptions) -> Result<()> {
+        self.mutate(|| self.uninstall_package(package, options))
+    }
+
+    fn is_installed(&self, package: &Package) -> Result<bool> {
+        if matches!(package.package_type, PackageType::Brew | PackageType::Cask)
+            && find_installed(&*self.installed_info()?, package).is_some()
+        {
+            return Ok(true);
+        }
+        self.query_installed(package)
+    }
+
+    fn list_installed(&self, package_type: PackageType) -> Result<Vec<InstalledPackage>> {
+        match package_type {
+            PackageType::Tap => {
+                let output = self.run_brew_checked(&["tap"], None)?;
+                Ok(output
+                    .lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(|l| InstalledPackage {
+                        name: l.trim().to_string(),
+                        package_type: PackageType::Tap,
+                        version: String::new(),
+                        installed_on_request: true,
+                        id: None,
+                        provider: None,
+                        tap: None,
+                        description: None,
+                    })
+                    .collect())
+            }
+            PackageType::Brew => parse_installed_formulas(&*self.installed_info()?),
+            PackageType::Cask => parse_installed_casks(&*self.installed_info()?),
+            PackageType::Mas => list_mas_installed(),
+            PackageType::Vscode => list_vscode_installed(),
+            // Custom packages are listed by their providers
+            PackageType::Custom => Ok(Vec::new()),
+        }
+    }
+
+    fn get_version(&self, package: &Package) -> Result<Option<String>> {
+        if matches!(package.package_type, PackageType::Brew | PackageType::Cask)
+            && let Some(info) = find_installed(&*self.installed_info()?, package)
+        {
+            return Ok(installed_version(info, package.package_type));
+        }
+        self.query_version(package)
+    }
+
+    fn bundle(&self, brewfile_path: &Path, verbose: bool) -> Result<BundleResult> {
+        self.bundle_with_progress(brewfile_path, verbose, &NoProgress)
+    }
+
+    fn bundle_with_progress(
+        &self,
+        brewfile_path: &Path,
+        verbose: bool,
+        progress: &dyn BrewProgress,
+    ) -> Result<BundleResult> {
+        self.mutate(|| self.run_bundle(brewfile_path, verbose, progress))
+    }
+
+    fn bundle_check(&self, brewfile_path: &Path) -> Result<Vec<Package>> {
+        let path = brewfile_path.to_str().unwrap_or("");
+        let output = self.run_brew(&[
+            "bundle",
+            "check",
+            "--verbose",
+            "--no-upgrade",
+            "--file",
+            path,
+        ])?;
+        let missing = parse_bundle_check(&String::from_utf8_lossy(&output.stdout));
+        // Exits non-zero whenever something is missing
+        if !output.status.success() && missing.is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_brew_output(&stderr, None));
+        }
+        Ok(missing)
+    }
+
+    fn update(&self) -> Result<()> {
+        // New versions change which packages are outdated
+        self.mutate(|| self.run_brew_checked(&["update"], None).map(drop))
+    }
+
+    fn upgrade(&self, package: Option<&Package>) -> Result<()> {
+        self.mutate(|| self.run_upgrade(package))
+    }
+
+    fn outdated(&self) -> Result<Vec<OutdatedPackage>> {
+        Ok(parse_outdated(&*self.installed_info()?))
+    }
+
+    fn dependency_graph(&self) -> Result<DependencyGraph> {
+        Ok(parse_dependency_graph(&*self.installed_info()?))
+    }
+
+    fn cleanup(&self, options: &CleanupOptions) -> Result<CleanupResult> {
+        if options.dry_run {
+            self.run_cleanup(options)
+        } else {
+            self.mutate(|| self.run_cleanup(options))
+        }
+    }
+
+    fn refresh_info(&self) -> Result<()> {
+        self.info_cache.invalidate();
+        self.installed_info().map(drop)
+    }
+}
+
+/// Uncached implementations of the [`Backend`] operations.
+impl BrewBackend {
+    fn install_streaming(&self, package: &Package, progress: &dyn BrewProgress) -> Result<()> {
+        let cask_flags = package.cask.install_flags();
         let args = match package.package_type {
             PackageType::Tap => vec!["tap", package.name.as_str()],
             PackageType::Brew => vec!["install", "--formula", package.name.as_str()],
-            PackageType::Cask => vec!["install", "--cask", package.name.as_str()],
+            PackageType::Cask => ["install", "--cask", package.name.as_str()]
+                .into_iter()
+                .chain(cask_flags.iter().map(String::as_str))
+                .collect(),
             PackageType::Mas => {
                 // mas install <app_id>
                 let id = package
@@ -67,30 +335,40 @@ impl Backend for BrewBackend {
                 // code --install-extension <ext_id>
                 return run_vscode_install(&package.name);
             }
+            PackageType::Custom => return Err(no_provider(package)),
         };
 
-        self.run_brew_checked(&args, Some(&package.name))?;
+        let output = self.run_brew_streaming(&args, &mut |line| progress.output(line))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::from_brew_output(&stderr, Some(&package.name)));
+        }
         Ok(())
     }
 
-    fn uninstall(&self, package: &Package) -> Result<()> {
-        let args = match package.package_type {
+    fn uninstall_package(&self, package: &Package, options: &UninstallOptions) -> Result<()> {
+        let mut args = match package.package_type {
             PackageType::Tap => vec!["untap", package.name.as_str()],
             PackageType::Brew => vec!["uninstall", "--formula", package.name.as_str()],
             PackageType::Cask => vec!["uninstall", "--cask", package.name.as_str()],
             PackageType::Mas => {
-                return Err(Error::Other("mas uninstall not supported".to_string()));
+                let id = package
+                    .mas_id()
+                    .ok_or_else(|| Error::Other("mas package missing id".to_string()))?;
+                return run_mas_uninstall(id);
             }
             PackageType::Vscode => {
                 return run_vscode_uninstall(&package.name);
             }
+            PackageType::Custom => return Err(no_provider(package)),
         };
+        args.extend(options.flags(package.package_type));
 
         self.run_brew_checked(&args, Some(&package.name))?;
         Ok(())
     }
 
-    fn is_installed(&self, package: &Package) -> Result<bool> {
+    fn query_installed(&self, package: &Package) -> Result<bool> {
         match package.package_type {
             PackageType::Tap => {
                 let output = self.run_brew(&["tap"])?;
@@ -135,46 +413,11 @@ impl Backend for BrewBackend {
                 is_mas_installed(id)
             }
             PackageType::Vscode => is_vscode_installed(&package.name),
+            PackageType::Custom => Err(no_provider(package)),
         }
     }
 
-    fn list_installed(&self, package_type: PackageType) -> Result<Vec<InstalledPackage>> {
-        match package_type {
-            PackageType::Tap => {
-                let output = self.run_brew_checked(&["tap"], None)?;
-                Ok(output
-                    .lines()
-                    .filter(|l| !l.trim().is_empty())
-                    .map(|l| InstalledPackage {
-                        name: l.trim().to_string(),
-                        package_type: PackageType::Tap,
-                        version: String::new(),
-                        installed_on_request: true,
-                    })
-                    .collect())
-            }
-            PackageType::Brew => {
-                let output = self.run_brew(&["info", "--json=v2", "--installed"])?;
-                if !output.status.success() {
-                    return Ok(Vec::new());
-                }
-                let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
-                parse_installed_formulas(&json)
-            }
-            PackageType::Cask => {
-                let output = self.run_brew(&["info", "--json=v2", "--cask", "--installed"])?;
-                if !output.status.success() {
-                    return Ok(Vec::new());
-                }
-                let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
-                parse_installed_casks(&json)
-            }
-            PackageType::Mas => list_mas_installed(),
-            PackageType::Vscode => list_vscode_installed(),
-        }
-    }
-
-    fn get_version(&self, package: &Package) -> Result<Option<String>> {
+    fn query_version(&self, package: &Package) -> Result<Option<String>> {
         match package.package_type {
             PackageType::Brew | PackageType::Cask => {
                 let type_flag = if package.package_type == PackageType::Cask {
@@ -213,28 +456,33 @@ impl Backend for BrewBackend {
         }
     }
 
-    fn bundle(&self, brewfile_path: &Path, verbose: bool) -> Result<BundleResult> {
+    fn run_bundle(
+        &self,
+        brewfile_path: &Path,
+        verbose: bool,
+        progress: &dyn BrewProgress,
+    ) -> Result<BundleResult> {
         let mut args = vec!["bundle", "--file", brewfile_path.to_str().unwrap_or("")];
         if verbose {
             args.push("--verbose");
         }
 
-        let output = self.run_brew(&args)?;
+        let mut tracker = BundleTracker::new(progress);
+        let output = self.run_brew_streaming(&args, &mut |line| tracker.line(line))?;
+        tracker.finish();
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
 
         parse_bundle_output(&stdout, &stderr, output.status.success())
     }
 
-    fn update(&self) -> Result<()> {
-        self.run_brew_checked(&["update"], None)?;
-        Ok(())
-    }
-
-    fn upgrade(&self, package: Option<&Package>) -> Result<()> {
+    fn run_upgrade(&self, package: Option<&Package>) -> Result<()> {
         let args = match package {
             Some(p) => match p.package_type {
                 PackageType::Brew => vec!["upgrade", "--formula", p.name.as_str()],
+                PackageType::Cask if p.cask.greedy => {
+                    vec!["upgrade", "--cask", "--greedy", p.name.as_str()]
+                }
                 PackageType::Cask => vec!["upgrade", "--cask", p.name.as_str()],
                 _ => return Ok(()),
             },
@@ -244,21 +492,55 @@ impl Backend for BrewBackend {
         self.run_brew_checked(&args, package.map(|p| p.name.as_str()))?;
         Ok(())
     }
+
+    fn run_cleanup(&self, options: &CleanupOptions) -> Result<CleanupResult> {
+        let mut result = CleanupResult {
+            dry_run: options.dry_run,
+            ..Default::default()
+        };
+
+        if options.autoremove {
+            let mut args = vec!["autoremove"];
+            if options.dry_run {
+                args.push("--dry-run");
+            }
+            let output = self.run_brew_checked(&args, None)?;
+            parse_cleanup_output(&output, &mut result);
+        }
+
+        let prune;
+        let mut args = vec!["cleanup"];
+        if options.dry_run {
+            args.push("--dry-run");
+        }
+        if let Some(days) = options.prune_days {
+            prune = format!("--prune={days}");
+            args.push(&prune);
+        }
+        if options.scrub {
+            args.push("-s");
+        }
+        let output = self.run_brew_checked(&args, None)?;
+        parse_cleanup_output(&output, &mut result);
+
+        Ok(result)
+    }
+}
+
+/// Error for a custom package reaching the brew backend.
+fn no_provider(package: &Package) -> Error {
+    Error::Other(format!(
+        "no provider registered for {} \"{}\"",
+        package.directive(),
+        package.name
+    ))
 }
 
 /// Find the brew executable path.
 fn find_brew() -> Result<String> {
     // Check common locations
-    let paths = [
-        "/opt/homebrew/bin/brew",              // Apple Silicon
-        "/usr/local/bin/brew",                 // Intel
-        "/home/linuxbrew/.linuxbrew/bin/brew", // Linux
-    ];
-
-    for path in &paths {
-        if std::path::Path::new(path).exists() {
-            return Ok((*path).to_string());
-        }
+    if let Some(prefix) = detect_prefixes().first() {
+        return Ok(prefix.join("bin/brew").to_string_lossy().into_owned());
     }
 
     // Try which
@@ -298,6 +580,10 @@ fn parse_installed_formulas(json: &serde_json::Value) -> Result<Vec<InstalledPac
                 package_type: PackageType::Brew,
                 version: version.to_string(),
                 installed_on_request: on_request,
+                id: None,
+                provider: None,
+                tap: third_party_tap(formula),
+                description: description(formula),
             });
         }
     }
@@ -305,6 +591,61 @@ fn parse_installed_formulas(json: &serde_json::Value) -> Result<Vec<InstalledPac
     Ok(installed)
 }
 
+/// Description of a formula or cask from brew info JSON.
+fn description(info: &serde_json::Value) -> Option<String> {
+    info["desc"]
+        .as_str()
+        .filter(|desc| !desc.is_empty())
+        .map(str::to_string)
+}
+
+/// Tap of a formula or cask from brew info JSON, unless it is a core tap.
+fn third_party_tap(info: &serde_json::Value) -> Option<String> {
+    info["tap"]
+        .as_str()
+        .filter(|tap| !matches!(*tap, "homebrew/core" | "homebrew/cask"))
+        .map(str::to_string)
+}
+
+/// Build the dependency graph from brew info JSON.
+///
+/// Uses each formula's declared runtime `dependencies` rather than
+/// `brew deps --installed`, which needs one lookup per formula. Formulas
+/// from third-party taps may be listed by their full `user/tap/name`.
+fn parse_dependency_graph(json: &serde_json::Value) -> DependencyGraph {
+    let mut graph = DependencyGraph::new();
+    for formula in json["formulae"].as_array().into_iter().flatten() {
+        let Some(name) = formula["name"].as_str() else {
+            continue;
+        };
+        let on_request = formula["installed"]
+            .as_array()
+            .and_then(|versions| versions.first())
+            .and_then(|first| first["installed_on_request"].as_bool())
+            .unwrap_or(false);
+        let dependencies = formula["dependencies"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(serde_json::Value::as_str)
+            .map(|dep| dep.rsplit('/').next().unwrap_or(dep));
+        graph.add(name, on_request, dependencies);
+    }
+    for cask in json["casks"].as_array().into_iter().flatten() {
+        let Some(name) = cask["token"].as_str() else {
+            continue;
+        };
+        let dependencies = cask["depends_on"]["formula"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(serde_json::Value::as_str)
+            .map(|dep| dep.rsplit('/').next().unwrap_or(dep));
+        graph.add_cask(name, dependencies);
+    }
+    graph
+}
+
 /// Parse installed casks from brew info JSON.
 fn parse_installed_casks(json: &serde_json::Value) -> Result<Vec<InstalledPackage>> {
     let empty = Vec::new();
@@ -321,6 +662,10 @@ fn parse_installed_casks(json: &serde_json::Value) -> Result<Vec<InstalledPackag
                 package_type: PackageType::Cask,
                 version: ver.to_string(),
                 installed_on_request: true, // Casks are always explicit
+                id: None,
+                provider: None,
+                tap: third_party_tap(cask),
+                description: description(cask),
             });
         }
     }
@@ -328,6 +673,199 @@ fn parse_installed_casks(json: &serde_json::Value) -> Result<Vec<InstalledPackag
     Ok(installed)
 }
 
+/// Outdated packages from brew info JSON.
+///
+/// Formulas report the newest installed version last; the latest version is
+/// the stable one, with its `_N` suffix when the formula was revised.
+fn parse_outdated(json: &serde_json::Value) -> Vec<OutdatedPackage> {
+    let formulas = json["formulae"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|f| f["outdated"].as_bool() == Some(true))
+        .filter_map(|formula| {
+            let stable = formula["versions"]["stable"].as_str().unwrap_or_default();
+            let latest_version = match formula["revision"].as_u64() {
+                Some(revision) if revision > 0 => format!("{stable}_{revision}"),
+                _ => stable.to_string(),
+            };
+            Some(OutdatedPackage {
+                name: formula["name"].as_str()?.to_string(),
+                package_type: PackageType::Brew,
+                installed_version: installed_version(formula, PackageType::Brew)
+                    .unwrap_or_default(),
+                latest_version,
+                pinned: formula["pinned"].as_bool().unwrap_or(false),
+            })
+        });
+    let casks = json["casks"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|c| c["outdated"].as_bool() == Some(true))
+        .filter_map(|cask| {
+            Some(OutdatedPackage {
+                name: cask["token"].as_str()?.to_string(),
+                package_type: PackageType::Cask,
+                installed_version: installed_version(cask, PackageType::Cask).unwrap_or_default(),
+                latest_version: cask["version"].as_str().unwrap_or_default().to_string(),
+                pinned: false,
+            })
+        });
+    formulas.chain(casks).collect()
+}
+
+/// Find an installed formula or cask in brew info JSON.
+///
+/// Matches the short name, the full `user/tap/name`, and former names, so
+/// renamed packages are still found.
+fn find_installed<'a>(
+    json: &'a serde_json::Value,
+    package: &Package,
+) -> Option<&'a serde_json::Value> {
+    let (key, fields, former) = match package.package_type {
+        PackageType::Cask => ("casks", ["token", "full_token"], "old_tokens"),
+        _ => ("formulae", ["name", "full_name"], "oldnames"),
+    };
+    let names = |entry: &'a serde_json::Value| {
+        fields
+            .into_iter()
+            .filter_map(|field| entry[field].as_str())
+            .chain(
+                [former, "aliases"]
+                    .into_iter()
+                    .flat_map(|field| entry[field].as_array().into_iter().flatten())
+                    .filter_map(serde_json::Value::as_str),
+            )
+    };
+    json[key]
+        .as_array()?
+        .iter()
+        .filter(|entry| installed_version(entry, package.package_type).is_some())
+        .find(|entry| names(entry).any(|name| name == package.name))
+}
+
+/// Installed version of a brew info entry, the newest if several are.
+fn installed_version(info: &serde_json::Value, package_type: PackageType) -> Option<String> {
+    let installed = &info["installed"];
+    let version = if package_type == PackageType::Cask {
+        installed.as_str()
+    } else {
+        installed
+            .as_array()
+            .and_then(|versions| versions.last())
+            .and_then(|v| v["version"].as_str())
+    };
+    version.map(str::to_string)
+}
+
+/// Parse `brew autoremove` and `brew cleanup` output into `result`.
+///
+/// Relevant lines look like:
+///
+/// ```text
+/// ==> Autoremoving 2 unneeded formulae:     (or "Would autoremove ...")
+/// libfoo
+/// Uninstalling /opt/homebrew/Cellar/libfoo/1.0... (12 files, 1.2MB)
+/// Removing: /Users/me/Library/Caches/Homebrew/wget--1.21.bottle.tar.gz... (1.5MB)
+/// Would remove: /opt/homebrew/Cellar/git/2.40.0 (1,402 files, 52.3MB)
+/// ```
+fn parse_cleanup_output(stdout: &str, result: &mut CleanupResult) {
+    let mut in_autoremove_list = false;
+
+    for line in stdout.lines().map(str::trim) {
+        if line.starts_with("==>") {
+            let lower = line.to_lowercase();
+            in_autoremove_list = lower.contains("autoremov");
+            continue;
+        }
+
+        if let Some(rest) = line
+            .strip_prefix("Removing: ")
+            .or_else(|| line.strip_prefix("Would remove: "))
+        {
+            let (path, size) = split_size_suffix(rest);
+            result.removed_files.push(PathBuf::from(path));
+            result.bytes_reclaimed += size;
+            in_autoremove_list = false;
+        } else if let Some(rest) = line.strip_prefix("Uninstalling ") {
+            result.bytes_reclaimed += split_size_suffix(rest).1;
+            in_autoremove_list = false;
+        } else if in_autoremove_list && !line.is_empty() && !line.contains(char::is_whitespace) {
+            result.autoremoved.push(line.to_string());
+        } else {
+            in_autoremove_list = false;
+        }
+    }
+}
+
+/// Split `path... (12 files, 1.5MB)` into the path and its size in bytes.
+fn split_size_suffix(text: &str) -> (&str, u64) {
+    let Some((path, details)) = text.rsplit_once(" (") else {
+        return (text.trim_end_matches("..."), 0);
+    };
+    let size = details
+        .trim_end_matches(')')
+        .rsplit(", ")
+        .next()
+        .and_then(parse_brew_size)
+        .unwrap_or(0);
+    (path.trim_end_matches("..."), size)
+}
+
+/// Parse a size printed by Homebrew (`345B`, `12KB`, `1.5MB`, `2GB`).
+///
+/// Homebrew uses binary multiples with decimal-looking unit names.
+fn parse_brew_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let unit_start = text.find(|c: char| c.is_ascii_alphabetic())?;
+    let (number, unit) = text.split_at(unit_start);
+    let number: f64 = number.replace(',', "").parse().ok()?;
+    let multiplier: u64 = match unit {
+        "B" => 1,
+        "KB" => 1 << 10,
+        "MB" => 1 << 20,
+        "GB" => 1 << 30,
+        "TB" => 1 << 40,
+        _ => return None,
+    };
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    Some((number * multiplier as f64) as u64)
+}
+
+/// Parse `brew bundle check --verbose` output into the missing entries.
+///
+/// Each one is reported on its own line:
+///
+/// ```text
+/// → Formula wget needs to be installed or updated.
+/// → Cask firefox needs to be installed or updated.
+/// → Tap hashicorp/tap needs to be tapped.
+/// ```
+///
+/// App Store apps are reported by name, so their packages have no id.
+fn parse_bundle_check(stdout: &str) -> Vec<Package> {
+    const KINDS: [(&str, PackageType); 5] = [
+        ("Formula ", PackageType::Brew),
+        ("Cask ", PackageType::Cask),
+        ("Tap ", PackageType::Tap),
+        ("App ", PackageType::Mas),
+        ("VSCode Extension ", PackageType::Vscode),
+    ];
+    stdout
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix('→'))
+        .filter_map(|entry| {
+            let entry = entry.trim_start();
+            let (rest, package_type) = KINDS
+                .iter()
+                .find_map(|(prefix, kind)| Some((entry.strip_prefix(prefix)?, *kind)))?;
+            let (name, _) = rest.split_once(" needs to be")?;
+            Some(Package::new(name, package_type))
+        })
+        .collect()
+}
+
 /// Parse bundle output to extract results.
 fn parse_bundle_output(stdout: &str, stderr: &str, success: bool) -> Result<BundleResult> {
     let mut result = BundleResult::default();
@@ -367,6 +905,57 @@ fn parse_bundle_output(stdout: &str, stderr: &str, success: bool) -> Result<Bund
     Ok(result)
 }
 
+/// Turns streamed `brew bundle` output into per-package progress events.
+///
+/// Bundle works through the Brewfile one entry at a time, announcing each
+/// with a line like `Installing git`; the entry is done when the next one
+/// is announced or the run ends.
+struct BundleTracker<'a> {
+    progress: &'a dyn BrewProgress,
+    current: Option<String>,
+}
+
+impl<'a> BundleTracker<'a> {
+    fn new(progress: &'a dyn BrewProgress) -> Self {
+        Self {
+            progress,
+            current: None,
+        }
+    }
+
+    fn line(&mut self, line: &str) {
+        self.progress.output(line);
+
+        let trimmed = line.trim();
+        if trimmed.contains("has failed") {
+            // "Installing foo has failed!" closes foo, announced or not
+            if let Some(name) = extract_package_name(trimmed) {
+                if self.current.as_deref() != Some(name.as_str()) {
+                    self.finish();
+                    self.progress.started(&name);
+                }
+                self.current = None;
+                self.progress.finished(&name, Some(trimmed));
+            }
+        } else if ["Installing ", "Tapping ", "Upgrading ", "Using "]
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix))
+            && let Some(name) = extract_package_name(trimmed)
+        {
+            self.finish();
+            self.progress.started(&name);
+            self.current = Some(name);
+        }
+    }
+
+    /// Mark the entry in progress, if any, as done.
+    fn finish(&mut self) {
+        if let Some(name) = self.current.take() {
+            self.progress.finished(&name, None);
+        }
+    }
+}
+
 /// Extract package name from a brew output line.
 fn extract_package_name(line: &str) -> Option<String> {
     // Patterns:
@@ -392,8 +981,17 @@ fn extract_package_name(line: &str) -> Option<String> {
 // =============================================================================
 
 fn run_mas_install(app_id: &str) -> Result<()> {
+    run_mas(&["install", app_id], "install", app_id)
+}
+
+fn run_mas_uninstall(app_id: &str) -> Result<()> {
+    run_mas(&["uninstall", app_id], "uninstall", app_id)
+}
+
+/// Run a mas command that acts on a single app.
+fn run_mas(args: &[&str], action: &str, app_id: &str) -> Result<()> {
     let output = Command::new("mas")
-        .args(["install", app_id])
+        .args(args)
         .output()
         .map_err(|e| Error::CommandFailed {
             message: format!("failed to execute mas: {e}"),
@@ -403,7 +1001,7 @@ fn run_mas_install(app_id: &str) -> Result<()> {
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(Error::CommandFailed {
-            message: format!("mas install failed for {app_id}"),
+            message: format!("mas {action} failed for {app_id}"),
             stderr: stderr.to_string(),
         });
     }
@@ -412,13 +1010,9 @@ fn run_mas_install(app_id: &str) -> Result<()> {
 }
 
 fn is_mas_installed(app_id: &str) -> Result<bool> {
-    let output = Command::new("mas")
-        .args(["list"])
-        .output()
-        .map_err(|_| Error::Other("mas not available".to_string()))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(stdout.lines().any(|l| l.starts_with(app_id)))
+    Ok(list_mas_installed()?
+        .iter()
+        .any(|app| app.id.as_deref() == Some(app_id)))
 }
 
 fn list_mas_installed() -> Result<Vec<InstalledPackage>> {
@@ -431,43 +1025,69 @@ fn list_mas_installed() -> Result<Vec<InstalledPackage>> {
         return Ok(Vec::new());
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut installed = Vec::new();
+    Ok(parse_mas_list(&String::from_utf8_lossy(&output.stdout)))
+}
 
-    for line in stdout.lines() {
-        // Format: "497799835 Xcode (14.3)"
-        let parts: Vec<&str> = line.splitn(2, ' ').collect();
-        if parts.len() >= 2 {
-            let id = parts[0];
-            let rest = parts[1];
+/// Parse `mas list` output.
+///
+/// Lines look like `497799835 Xcode (14.3)`; newer mas versions pad the
+/// columns, e.g. `  497799835  Xcode  (14.3)`.
+fn parse_mas_list(stdout: &str) -> Vec<InstalledPackage> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (id, rest) = line.trim().split_once(char::is_whitespace)?;
+            if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
 
             // Extract name and version
-            let (name, version) = if let Some(paren_pos) = rest.rfind('(') {
-                let name = rest[..paren_pos].trim();
-                let version = rest[paren_pos + 1..].trim_end_matches(')');
-                (name, version)
-            } else {
-                (rest.trim(), "")
+            let (name, version) = match rest.rfind('(') {
+                Some(paren_pos) => (
+                    rest[..paren_pos].trim(),
+                    rest[paren_pos + 1..].trim().trim_end_matches(')'),
+                ),
+                None => (rest.trim(), ""),
             };
 
-            installed.push(InstalledPackage {
-                name: format!("{name} ({id})"),
+            Some(InstalledPackage {
+                name: name.to_string(),
                 package_type: PackageType::Mas,
                 version: version.to_string(),
                 installed_on_request: true,
-            });
-        }
-    }
-
-    Ok(installed)
+                id: Some(id.to_string()),
+                provider: None,
+                tap: None,
+                description: None,
+            })
+        })
+        .collect()
 }
 
 // =============================================================================
 // VS Code extension helpers
 // =============================================================================
 
+/// Locate the VS Code CLI.
+///
+/// Prefers `code` on PATH; the shell command is often not installed on macOS,
+/// so fall back to the copy inside the app bundle.
+fn find_code() -> String {
+    const APP_CLI: &str = "/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code";
+
+    let on_path = Command::new("which")
+        .arg("code")
+        .output()
+        .is_ok_and(|o| o.status.success());
+    if !on_path && Path::new(APP_CLI).exists() {
+        APP_CLI.to_string()
+    } else {
+        "code".to_string()
+    }
+}
+
 fn run_vscode_install(extension: &str) -> Result<()> {
-    let output = Command::new("code")
+    let output = Command::new(find_code())
         .args(["--install-extension", extension])
         .output()
         .map_err(|e| Error::CommandFailed {
@@ -487,7 +1107,7 @@ fn run_vscode_install(extension: &str) -> Result<()> {
 }
 
 fn run_vscode_uninstall(extension: &str) -> Result<()> {
-    let output = Command::new("code")
+    let output = Command::new(find_code())
         .args(["--uninstall-extension", extension])
         .output()
         .map_err(|e| Error::CommandFailed {
@@ -507,7 +1127,7 @@ fn run_vscode_uninstall(extension: &str) -> Result<()> {
 }
 
 fn is_vscode_installed(extension: &str) -> Result<bool> {
-    let output = Command::new("code")
+    let output = Command::new(find_code())
         .args(["--list-extensions"])
         .output()
         .map_err(|_| Error::Other("code not available".to_string()))?;
@@ -518,7 +1138,7 @@ fn is_vscode_installed(extension: &str) -> Result<bool> {
 }
 
 fn list_vscode_installed() -> Result<Vec<InstalledPackage>> {
-    let output = Command::new("code")
+    let output = Command::new(find_code())
         .args(["--list-extensions", "--show-versions"])
         .output()
         .map_err(|_| Error::Other("code not available".to_string()))?;
@@ -527,26 +1147,35 @@ fn list_vscode_installed() -> Result<Vec<InstalledPackage>> {
         return Ok(Vec::new());
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut installed = Vec::new();
-
-    for line in stdout.lines() {
-        // Format: "ms-python.python@2024.0.1"
-        let (name, version) = if let Some(at_pos) = line.rfind('@') {
-            (&line[..at_pos], &line[at_pos + 1..])
-        } else {
-            (line, "")
-        };
-
-        installed.push(InstalledPackage {
-            name: name.to_string(),
-            package_type: PackageType::Vscode,
-            version: version.to_string(),
-            installed_on_request: true,
-        });
-    }
+    Ok(parse_vscode_list(&String::from_utf8_lossy(&output.stdout)))
+}
 
-    Ok(installed)
+/// Parse `code --list-extensions --show-versions` output.
+///
+/// Lines look like `ms-python.python@2024.0.1`. Anything that isn't a
+/// `publisher.name` extension ID (e.g. "Extensions installed on SSH: ...")
+/// is skipped.
+fn parse_vscode_list(stdout: &str) -> Vec<InstalledPackage> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            let (name, version) = line.rsplit_once('@').unwrap_or((line, ""));
+            let is_extension_id = name.split_once('.').is_some_and(|(publisher, ext)| {
+                !publisher.is_empty() && !ext.is_empty() && !name.contains(char::is_whitespace)
+            });
+            is_extension_id.then(|| InstalledPackage {
+                name: name.to_string(),
+                package_type: PackageType::Vscode,
+                version: version.to_string(),
+                installed_on_request: true,
+                id: None,
+                provider: None,
+                tap: None,
+                description: None,
+            })
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -580,6 +1209,206 @@ mod tests {
         assert!(result.failed.is_empty());
     }
 
+    #[test]
+    fn test_parse_mas_list() {
+        let stdout =
+            "497799835 Xcode (14.3)\n  1352778147  Bitwarden  (2024.6.1)\nNo installed apps\n";
+        let apps = parse_mas_list(stdout);
+
+        assert_eq!(apps.len(), 2);
+        assert_eq!(apps[0].name, "Xcode");
+        assert_eq!(apps[0].id.as_deref(), Some("497799835"));
+        assert_eq!(apps[0].version, "14.3");
+        assert_eq!(apps[1].name, "Bitwarden");
+        assert_eq!(apps[1].id.as_deref(), Some("1352778147"));
+        assert_eq!(apps[1].version, "2024.6.1");
+    }
+
+    #[test]
+    fn test_parse_vscode_list() {
+        let stdout = "Extensions installed on SSH: devbox:\nrust-lang.rust-analyzer@0.3.2029\nms-python.python\n\n";
+        let extensions = parse_vscode_list(stdout);
+
+        assert_eq!(extensions.len(), 2);
+        assert_eq!(extensions[0].name, "rust-lang.rust-analyzer");
+        assert_eq!(extensions[0].version, "0.3.2029");
+        assert_eq!(extensions[1].name, "ms-python.python");
+        assert_eq!(extensions[1].version, "");
+    }
+
+    #[test]
+    fn test_parse_outdated() {
+        let json = serde_json::json!({
+            "formulae": [
+                {"name": "git", "installed": [{"version": "2.40.0"}, {"version": "2.41.0"}],
+                 "versions": {"stable": "2.42.0"}, "revision": 0,
+                 "outdated": true, "pinned": false},
+                {"name": "node", "installed": [{"version": "20.1.0"}],
+                 "versions": {"stable": "21.0.0"}, "revision": 1,
+                 "outdated": true, "pinned": true},
+                {"name": "wget", "installed": [{"version": "1.21.4"}],
+                 "versions": {"stable": "1.21.4"}, "outdated": false}
+            ],
+            "casks": [
+                {"token": "firefox", "installed": "118.0", "version": "119.0", "outdated": true},
+                {"token": "slack", "installed": "4.35.0", "version": "4.35.0", "outdated": false}
+            ]
+        });
+        let outdated = parse_outdated(&json);
+
+        assert_eq!(outdated.len(), 3);
+        assert_eq!(outdated[0].name, "git");
+        assert_eq!(outdated[0].package_type, PackageType::Brew);
+        assert_eq!(outdated[0].installed_version, "2.41.0");
+        assert_eq!(outdated[0].latest_version, "2.42.0");
+        assert!(outdated[1].pinned);
+        assert_eq!(outdated[1].latest_version, "21.0.0_1");
+        assert_eq!(outdated[2].package_type, PackageType::Cask);
+        assert_eq!(outdated[2].installed_version, "118.0");
+        assert_eq!(outdated[2].latest_version, "119.0");
+    }
+
+    #[test]
+    fn test_find_installed() {
+        let json = serde_json::json!({
+            "formulae": [
+                {"name": "node@20", "full_name": "node@20", "aliases": [], "oldnames": [],
+                 "installed": [{"version": "20.1.0"}]},
+                {"name": "terraform", "full_name": "hashicorp/tap/terraform",
+                 "oldnames": ["terraform-cli"], "installed": [{"version": "1.6.0"}]},
+                {"name": "wget", "full_name": "wget", "installed": []}
+            ],
+            "casks": [
+                {"token": "visual-studio-code", "full_token": "visual-studio-code",
+                 "old_tokens": ["vscode"], "installed": "1.84.0"}
+            ]
+        });
+
+        let find = |package: Package| {
+            find_installed(&json, &package).and_then(|i| installed_version(i, package.package_type))
+        };
+        assert_eq!(find(Package::brew("node@20")).as_deref(), Some("20.1.0"));
+        assert_eq!(
+            find(Package::brew("hashicorp/tap/terraform")).as_deref(),
+            Some("1.6.0")
+        );
+        assert_eq!(
+            find(Package::brew("terraform-cli")).as_deref(),
+            Some("1.6.0")
+        );
+        assert_eq!(find(Package::cask("vscode")).as_deref(), Some("1.84.0"));
+        assert_eq!(find(Package::brew("wget")), None);
+        assert_eq!(find(Package::cask("node@20")), None);
+    }
+
+    #[test]
+    fn test_parse_dependency_graph() {
+        let json = serde_json::json!({
+            "formulae": [
+                {"name": "git", "dependencies": ["gettext", "pcre2"],
+                 "installed": [{"version": "2.42.0", "installed_on_request": true}]},
+                {"name": "gettext", "dependencies": [],
+                 "installed": [{"version": "0.22", "installed_on_request": false}]},
+                {"name": "tool", "dependencies": ["user/tap/helper"],
+                 "installed": [{"version": "1.0", "installed_on_request": true}]},
+                {"name": "helper", "dependencies": [],
+                 "installed": [{"version": "1.0", "installed_on_request": false}]},
+                {"name": "ffmpeg", "dependencies": [],
+                 "installed": [{"version": "7.0", "installed_on_request": false}]}
+            ],
+            "casks": [
+                {"token": "losslesscut", "installed": "3.61",
+                 "depends_on": {"formula": ["homebrew/core/ffmpeg"], "macos": {">=": ["11"]}}},
+                {"token": "vscode", "installed": "1.84.0", "depends_on": {}}
+            ]
+        });
+        let graph = parse_dependency_graph(&json);
+
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.dependencies("git"), vec!["gettext"]);
+        assert_eq!(graph.dependents("helper"), vec!["tool"]);
+        assert!(graph.orphans().is_empty());
+    }
+
+    #[test]
+    fn test_parse_cleanup_output() {
+        let autoremove = "==> Autoremoving 2 unneeded formulae:\nlibfoo\nlibbar\n\
+            Uninstalling /opt/homebrew/Cellar/libfoo/1.0... (12 files, 1MB)\n\
+            Uninstalling /opt/homebrew/Cellar/libbar/2.0... (3 files, 512KB)\n";
+        let cleanup = "Removing: /opt/homebrew/Cellar/git/2.40.0... (1,402 files, 52MB)\n\
+            Removing: /Users/me/Library/Caches/Homebrew/wget--1.21.bottle.tar.gz... (1.5MB)\n\
+            Pruned 0 symbolic links and 2 directories from /opt/homebrew\n";
+
+        let mut result = CleanupResult::default();
+        parse_cleanup_output(autoremove, &mut result);
+        parse_cleanup_output(cleanup, &mut result);
+
+        assert_eq!(result.autoremoved, vec!["libfoo", "libbar"]);
+        assert_eq!(
+            result.removed_files,
+            vec![
+                PathBuf::from("/opt/homebrew/Cellar/git/2.40.0"),
+                PathBuf::from("/Users/me/Library/Caches/Homebrew/wget--1.21.bottle.tar.gz"),
+            ]
+        );
+        assert_eq!(
+            result.bytes_reclaimed,
+            (1 << 20) + (512 << 10) + (52 << 20) + (3 << 19)
+        );
+    }
+
+    #[test]
+    fn test_parse_cleanup_output_dry_run() {
+        let stdout = "==> Would autoremove 1 unneeded formula:\nlibfoo\n\
+            Would remove: /opt/homebrew/Cellar/git/2.40.0 (1,402 files, 2GB)\n";
+        let mut result = CleanupResult::default();
+        parse_cleanup_output(stdout, &mut result);
+
+        assert_eq!(result.autoremoved, vec!["libfoo"]);
+        assert_eq!(result.removed_files.len(), 1);
+        assert_eq!(result.bytes_reclaimed, 2 << 30);
+        assert_eq!(parse_brew_size("345B"), Some(345));
+        assert_eq!(parse_brew_size("1.5 parsecs"), None);
+    }
+
+    #[test]
+    fn test_backend_prefix() {
+        let intel = BrewBackend::at("/usr/local/bin/brew".to_string(), InfoCache::disabled());
+        assert_eq!(intel.prefix(), Path::new("/usr/local"));
+        assert_eq!(
+            intel.x86_64,
+            cfg!(all(target_os = "macos", target_arch = "aarch64"))
+        );
+
+        let native = BrewBackend::at("/opt/homebrew/bin/brew".to_string(), InfoCache::disabled());
+        assert_eq!(native.prefix(), Path::new("/opt/homebrew"));
+        assert!(!native.x86_64);
+    }
+
+    #[test]
+    fn test_parse_bundle_check() {
+        let stdout = "brew bundle can't satisfy your Brewfile's dependencies.
+→ Tap hashicorp/tap needs to be tapped.
+→ Formula hashicorp/tap/terraform needs to be installed or updated.
+→ Cask firefox needs to be installed or updated.
+→ App Xcode needs to be installed or updated.
+→ VSCode Extension rust-lang.rust-analyzer needs to be installed.
+Satisfy missing dependencies with `brew bundle install`.
+";
+        let missing = parse_bundle_check(stdout);
+
+        assert_eq!(missing.len(), 5);
+        assert_eq!(missing[0].package_type, PackageType::Tap);
+        assert_eq!(missing[0].name, "hashicorp/tap");
+        assert_eq!(missing[1].name, "hashicorp/tap/terraform");
+        assert_eq!(missing[2].package_type, PackageType::Cask);
+        assert_eq!(missing[3].package_type, PackageType::Mas);
+        assert_eq!(missing[3].name, "Xcode");
+        assert_eq!(missing[4].name, "rust-lang.rust-analyzer");
+
+        assert!(parse_bundle_check("The Brewfile's dependencies are satisfied.\n").is_empty());
+    }
+
     #[test]
     fn test_parse_bundle_output_with_errors() {
         let stderr = "Error: foo: not found";
@@ -587,4 +1416,54 @@ mod tests {
 
         assert!(!result.failed.is_empty());
     }
+
+    #[test]
+    fn test_bundle_tracker_events() {
+        /// Records events as readable strings.
+        #[derive(Default)]
+        struct Recorder(std::sync::Mutex<Vec<String>>);
+
+        impl BrewProgress for Recorder {
+            fn started(&self, name: &str) {
+                self.0.lock().unwrap().push(format!("start {name}"));
+            }
+
+            fn finished(&self, name: &str, error: Option<&str>) {
+                let outcome = if error.is_some() { "failed" } else { "ok" };
+                self.0.lock().unwrap().push(format!("{outcome} {name}"));
+            }
+        }
+
+        let recorder = Recorder::default();
+        let mut tracker = BundleTracker::new(&recorder);
+        for line in [
+            "Tapping homebrew/cask-fonts",
+            "Using git",
+            "Installing ripgrep",
+            "==> Downloading https://ghcr.io/v2/homebrew/core/ripgrep",
+            "Installing nonexistent",
+            "Error: No available formula with the name \"nonexistent\".",
+            "Installing nonexistent has failed!",
+            "Installing jq",
+        ] {
+            tracker.line(line);
+        }
+        tracker.finish();
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![
+                "start homebrew/cask-fonts",
+                "ok homebrew/cask-fonts",
+                "start git",
+                "ok git",
+                "start ripgrep",
+                "ok ripgrep",
+                "start nonexistent",
+                "failed nonexistent",
+                "start jq",
+                "ok jq",
+            ]
+        );
+    }
 }
@@ -0,0 +1,191 @@
+//! Cache of installed package metadata.
+//!
+//! `brew info --json=v2 --installed` describes every installed formula and
+//! cask at once: versions, taps, dependencies and whether a newer version is
+//! available. It takes seconds to run, so [`InfoCache`] keeps its output in
+//! memory, and optionally on disk, until it is older than the cache's TTL.
+//! Audits, version lookups and outdated checks then share a single call.
+//!
+//! The cache is invalidated whenever the backend installs, removes or
+//! upgrades something.
+
+use crate::error::Result;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Cached `brew info --json=v2 --installed` output.
+pub struct InfoCache {
+    /// File the snapshot is persisted to, if any
+    path: Option<PathBuf>,
+    /// How long a snapshot stays valid
+    ttl: Duration,
+    /// Snapshot loaded in this process
+    snapshot: Mutex<Option<Snapshot>>,
+}
+
+/// Info JSON along with when it was fetched.
+struct Snapshot {
+    /// Seconds since the Unix epoch
+    fetched_at: u64,
+    info: Arc<serde_json::Value>,
+}
+
+impl Snapshot {
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        now().saturating_sub(self.fetched_at) < ttl.as_secs()
+    }
+}
+
+impl Default for InfoCache {
+    /// In memory only, valid for a minute.
+    fn default() -> Self {
+        Self::in_memory(Duration::from_secs(60))
+    }
+}
+
+impl InfoCache {
+    /// Cache kept only for the lifetime of the backend.
+    pub fn in_memory(ttl: Duration) -> Self {
+        Self {
+            path: None,
+            ttl,
+            snapshot: Mutex::new(None),
+        }
+    }
+
+    /// Cache persisted to `path`, so it is shared between processes.
+    pub fn on_disk(path: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            path: Some(path.into()),
+            ..Self::in_memory(ttl)
+        }
+    }
+
+    /// Disabled cache: every lookup runs `brew info`.
+    pub fn disabled() -> Self {
+        Self::in_memory(Duration::ZERO)
+    }
+
+    /// How long a snapshot stays valid.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// File the cache is persisted to, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Get the info JSON, calling `fetch` if there is no fresh snapshot.
+    pub(crate) fn get(
+        &self,
+        fetch: impl FnOnce() -> Result<serde_json::Value>,
+    ) -> Result<Arc<serde_json::Value>> {
+        let mut snapshot = self.snapshot.lock().unwrap();
+        if let Some(cached) = snapshot.as_ref().filter(|s| s.is_fresh(self.ttl)) {
+            return Ok(Arc::clone(&cached.info));
+        }
+        if let Some(stored) = self.load().filter(|s| s.is_fresh(self.ttl)) {
+            let info = Arc::clone(&stored.info);
+            *snapshot = Some(stored);
+            return Ok(info);
+        }
+
+        let fresh = Snapshot {
+            fetched_at: now(),
+            info: Arc::new(fetch()?),
+        };
+        // The cache only saves time, so failing to persist it is fine
+        let _ = self.store(&fresh);
+        let info = Arc::clone(&fresh.info);
+        *snapshot = Some(fresh);
+        Ok(info)
+    }
+
+    /// Drop the snapshot, in memory and on disk.
+    pub fn invalidate(&self) {
+        *self.snapshot.lock().unwrap() = None;
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    fn load(&self) -> Option<Snapshot> {
+        let content = std::fs::read(self.path.as_ref()?).ok()?;
+        let mut stored: serde_json::Value = serde_json::from_slice(&content).ok()?;
+        Some(Snapshot {
+            fetched_at: stored["fetched_at"].as_u64()?,
+            info: Arc::new(stored["info"].take()),
+        })
+    }
+
+    fn store(&self, snapshot: &Snapshot) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let stored = serde_json::json!({
+            "fetched_at": snapshot.fetched_at,
+            "info": *snapshot.info,
+        });
+        std::fs::write(path, serde_json::to_vec(&stored)?)?;
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_cache_fetches_once_until_invalidated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("info.json");
+        let cache = InfoCache::on_disk(&path, Duration::from_secs(300));
+        let fetches = Cell::new(0);
+        let fetch = || {
+            fetches.set(fetches.get() + 1);
+            Ok(serde_json::json!({ "formulae": [{ "name": "git" }] }))
+        };
+
+        let info = cache.get(fetch).unwrap();
+        assert_eq!(info["formulae"][0]["name"], "git");
+        cache.get(fetch).unwrap();
+        assert_eq!(fetches.get(), 1);
+
+        // Another process reads the snapshot from disk
+        let other = InfoCache::on_disk(&path, Duration::from_secs(300));
+        other.get(fetch).unwrap();
+        assert_eq!(fetches.get(), 1);
+
+        cache.invalidate();
+        assert!(!path.exists());
+        cache.get(fetch).unwrap();
+        assert_eq!(fetches.get(), 2);
+    }
+
+    #[test]
+    fn test_disabled_cache_always_fetches() {
+        let cache = InfoCache::disabled();
+        let fetches = Cell::new(0);
+        for _ in 0..3 {
+            cache
+                .get(|| {
+                    fetches.set(fetches.get() + 1);
+                    Ok(serde_json::Value::Null)
+                })
+                .unwrap();
+        }
+        assert_eq!(fetches.get(), 3);
+    }
+}
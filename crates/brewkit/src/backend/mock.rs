@@ -0,0 +1,213 @@
+//! Configurable backend for tests.
+//!
+//! [`MockBackend`] starts with nothing installed. Builder methods add
+//! installed packages and outdated versions, make operations on a package
+//! fail, or slow installs down to observe how they overlap.
+
+use super::Backend;
+use crate::deps::DependencyGraph;
+use crate::error::{Error, Result};
+use crate::types::{
+    BundleResult, InstalledPackage, OutdatedPackage, Package, PackageType, UninstallOptions,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// An installed package with version `1.0`, installed on request.
+pub(crate) fn installed(name: &str, package_type: PackageType) -> InstalledPackage {
+    InstalledPackage {
+        name: name.to_string(),
+        package_type,
+        version: "1.0".to_string(),
+        installed_on_request: true,
+        id: None,
+        provider: None,
+        tap: None,
+        description: None,
+    }
+}
+
+/// How many installs ran at once, overall and casks alone.
+#[derive(Debug, Default)]
+pub(crate) struct InstallStats {
+    running: AtomicUsize,
+    cask_running: AtomicUsize,
+    /// Most installs running at the same time
+    pub max_running: AtomicUsize,
+    /// Most cask installs running at the same time
+    pub max_cask_running: AtomicUsize,
+}
+
+/// Installs and upgrades of a package that fail.
+struct Failure {
+    remaining: AtomicUsize,
+    error: fn(&str) -> Error,
+}
+
+/// Backend serving packages from memory.
+#[derive(Default)]
+pub(crate) struct MockBackend {
+    installed: Mutex<Vec<InstalledPackage>>,
+    outdated: Vec<OutdatedPackage>,
+    failures: HashMap<String, Failure>,
+    install_delay: Duration,
+    installs: Arc<Mutex<Vec<String>>>,
+    stats: Arc<InstallStats>,
+}
+
+impl MockBackend {
+    /// Report `packages` as installed.
+    pub fn with_installed(self, packages: impl IntoIterator<Item = InstalledPackage>) -> Self {
+        self.installed.lock().unwrap().extend(packages);
+        self
+    }
+
+    /// Report `packages` as outdated.
+    pub fn with_outdated(mut self, packages: Vec<OutdatedPackage>) -> Self {
+        self.outdated = packages;
+        self
+    }
+
+    /// Fail the first `times` installs or upgrades of `name` with `error`.
+    pub fn failing(mut self, name: &str, times: usize, error: fn(&str) -> Error) -> Self {
+        self.failures.insert(
+            name.to_string(),
+            Failure {
+                remaining: AtomicUsize::new(times),
+                error,
+            },
+        );
+        self
+    }
+
+    /// Take `delay` for every install that succeeds.
+    pub fn with_install_delay(mut self, delay: Duration) -> Self {
+        self.install_delay = delay;
+        self
+    }
+
+    /// Names of the packages installed so far, in order.
+    pub fn installs(&self) -> Arc<Mutex<Vec<String>>> {
+        Arc::clone(&self.installs)
+    }
+
+    /// Overlap of the installs run so far.
+    pub fn stats(&self) -> Arc<InstallStats> {
+        Arc::clone(&self.stats)
+    }
+
+    /// The configured failure for `name`, while it has attempts left.
+    fn fail(&self, name: &str) -> Result<()> {
+        match self.failures.get(name) {
+            Some(failure)
+                if failure
+                    .remaining
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok() =>
+            {
+                Err((failure.error)(name))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Backend for MockBackend {
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn install(&self, package: &Package) -> Result<()> {
+        self.fail(&package.name)?;
+
+        let is_cask = package.package_type == PackageType::Cask;
+        let stats = &self.stats;
+        let running = stats.running.fetch_add(1, Ordering::SeqCst) + 1;
+        stats.max_running.fetch_max(running, Ordering::SeqCst);
+        if is_cask {
+            let casks = stats.cask_running.fetch_add(1, Ordering::SeqCst) + 1;
+            stats.max_cask_running.fetch_max(casks, Ordering::SeqCst);
+        }
+        thread::sleep(self.install_delay);
+        if is_cask {
+            stats.cask_running.fetch_sub(1, Ordering::SeqCst);
+        }
+        stats.running.fetch_sub(1, Ordering::SeqCst);
+
+        self.installs.lock().unwrap().push(package.name.clone());
+        self.installed
+            .lock()
+            .unwrap()
+            .push(installed(&package.name, package.package_type));
+        Ok(())
+    }
+
+    fn uninstall(&self, package: &Package, _options: &UninstallOptions) -> Result<()> {
+        self.installed
+            .lock()
+            .unwrap()
+            .retain(|p| !(p.package_type == package.package_type && p.name == package.name));
+        Ok(())
+    }
+
+    fn is_installed(&self, package: &Package) -> Result<bool> {
+        Ok(self.installed.lock().unwrap().iter().any(|p| {
+            p.package_type == package.package_type && p.name.eq_ignore_ascii_case(&package.name)
+        }))
+    }
+
+    fn list_installed(&self, package_type: PackageType) -> Result<Vec<InstalledPackage>> {
+        Ok(self
+            .installed
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| p.package_type == package_type)
+            .cloned()
+            .collect())
+    }
+
+    fn get_version(&self, package: &Package) -> Result<Option<String>> {
+        Ok(self
+            .list_installed(package.package_type)?
+            .into_iter()
+            .find(|p| p.name.eq_ignore_ascii_case(&package.name))
+            .map(|p| p.version))
+    }
+
+    fn bundle(&self, _brewfile_path: &Path, _verbose: bool) -> Result<BundleResult> {
+        Ok(BundleResult::default())
+    }
+
+    fn update(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn upgrade(&self, package: Option<&Package>) -> Result<()> {
+        match package {
+            Some(package) => self.fail(&package.name),
+            None => Ok(()),
+        }
+    }
+
+    fn outdated(&self) -> Result<Vec<OutdatedPackage>> {
+        Ok(self.outdated.clone())
+    }
+
+    /// No formula depends on another.
+    fn dependency_graph(&self) -> Result<DependencyGraph> {
+        let mut graph = DependencyGraph::new();
+        for formula in self.list_formulas()? {
+            graph.add(
+                formula.name,
+                formula.installed_on_request,
+                Vec::<String>::new(),
+            );
+        }
+        Ok(graph)
+    }
+}
@@ -4,9 +4,18 @@
 //! allowing for different implementations (real CLI, mock for testing).
 
 pub mod brew;
+pub mod cache;
+#[cfg(test)]
+pub(crate) mod mock;
 
-use crate::error::Result;
-use crate::types::{BundleResult, InstalledPackage, Package, PackageType};
+use crate::brewfile;
+use crate::deps::DependencyGraph;
+use crate::error::{Error, Result};
+use crate::progress::BrewProgress;
+use crate::types::{
+    BundleResult, CleanupOptions, CleanupResult, InstalledPackage, OutdatedPackage, Package,
+    PackageType, UninstallOptions,
+};
 use std::path::Path;
 
 /// Backend trait for Homebrew operations.
@@ -23,7 +32,7 @@ pub trait Backend: Send + Sync {
     fn install(&self, package: &Package) -> Result<()>;
 
     /// Uninstall a package.
-    fn uninstall(&self, package: &Package) -> Result<()>;
+    fn uninstall(&self, package: &Package, options: &UninstallOptions) -> Result<()>;
 
     /// Check if a package is installed.
     fn is_installed(&self, package: &Package) -> Result<bool>;
@@ -37,12 +46,77 @@ pub trait Backend: Send + Sync {
     /// Run `brew bundle` with a Brewfile.
     fn bundle(&self, brewfile_path: &Path, verbose: bool) -> Result<BundleResult>;
 
+    /// List Brewfile entries that are not installed, without installing
+    /// anything (`brew bundle check`).
+    ///
+    /// Defaults to checking each entry with [`is_installed`](Self::is_installed).
+    fn bundle_check(&self, brewfile_path: &Path) -> Result<Vec<Package>> {
+        let mut missing = Vec::new();
+        for package in brewfile::parse_file(brewfile_path)?.packages {
+            if !self.is_installed(&package)? {
+                missing.push(package);
+            }
+        }
+        Ok(missing)
+    }
+
+    /// Install a package, streaming its output to `progress`.
+    ///
+    /// Started and finished events are reported by the client. Defaults to
+    /// [`install`](Self::install) without any output.
+    fn install_with_progress(&self, package: &Package, _progress: &dyn BrewProgress) -> Result<()> {
+        self.install(package)
+    }
+
+    /// Run `brew bundle`, reporting each package and output line to `progress`.
+    ///
+    /// Defaults to [`bundle`](Self::bundle) without any events.
+    fn bundle_with_progress(
+        &self,
+        brewfile_path: &Path,
+        verbose: bool,
+        _progress: &dyn BrewProgress,
+    ) -> Result<BundleResult> {
+        self.bundle(brewfile_path, verbose)
+    }
+
     /// Run `brew update` to refresh package lists.
     fn update(&self) -> Result<()>;
 
     /// Run `brew upgrade` for a specific package or all packages.
     fn upgrade(&self, package: Option<&Package>) -> Result<()>;
 
+    /// List formulas and casks with newer versions available.
+    ///
+    /// Backends that can't tell report nothing outdated.
+    fn outdated(&self) -> Result<Vec<OutdatedPackage>> {
+        Ok(Vec::new())
+    }
+
+    /// Build the dependency graph of installed formulas.
+    ///
+    /// Backends that can't tell return an empty graph, so no formula looks
+    /// orphaned.
+    fn dependency_graph(&self) -> Result<DependencyGraph> {
+        Ok(DependencyGraph::new())
+    }
+
+    /// Run `brew autoremove` and `brew cleanup`.
+    ///
+    /// Fails unless the backend knows how to clean up.
+    fn cleanup(&self, _options: &CleanupOptions) -> Result<CleanupResult> {
+        Err(Error::Other(
+            "cleanup is not supported by this backend".to_string(),
+        ))
+    }
+
+    /// Discard cached package metadata and fetch it again.
+    ///
+    /// Backends without a cache have nothing to do.
+    fn refresh_info(&self) -> Result<()> {
+        Ok(())
+    }
+
     /// List all installed taps.
     fn list_taps(&self) -> Result<Vec<String>> {
         Ok(self
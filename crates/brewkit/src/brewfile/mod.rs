@@ -5,5 +5,5 @@
 pub mod parser;
 pub mod writer;
 
-pub use parser::{parse_file, parse_string};
+pub use parser::{parse_file, parse_file_with, parse_string, parse_string_with};
 pub use writer::{WriteOptions, write_file, write_string};
@@ -8,41 +8,108 @@
 //! mas "Xcode", id: 497799835
 //! vscode "ms-python.python"
 //! ```
+//!
+//! Cask `args: { ... }` and `greedy:` options become the package's
+//! [`CaskOptions`](crate::types::CaskOptions):
+//! ```text
+//! cask "firefox", args: { appdir: "~/Applications", no_quarantine: true }
+//! ```
+//!
+//! Packages can be organized into groups with comment markers. A group
+//! runs until the next marker or `# endgroup`:
+//! ```text
+//! # group: base
+//! brew "git"
+//! # group: gui
+//! cask "firefox"
+//! # endgroup
+//! ```
+//!
+//! Packages that only apply to some machines go in conditional blocks. A
+//! block lists tags (a hostname, a profile, ...) and runs until the next
+//! `# only:` marker or `# endonly`; see [`Brewfile::for_tags`]:
+//! ```text
+//! # only: work, studio-mac
+//! cask "slack"
+//! # endonly
+//! ```
 
 use crate::error::{Error, Result};
-use crate::types::{Brewfile, Package, PackageType};
+use crate::types::{Brewfile, CaskOptions, Package, PackageType};
 use std::collections::HashMap;
 use std::path::Path;
 
 /// Parse a Brewfile from a file path.
 pub fn parse_file(path: &Path) -> Result<Brewfile> {
+    parse_file_with(path, &[])
+}
+
+/// Parse a Brewfile from a file path, keeping entries for custom directives.
+///
+/// Lines whose directive is in `custom_directives` become
+/// [`PackageType::Custom`] packages instead of being ignored.
+pub fn parse_file_with(path: &Path, custom_directives: &[&str]) -> Result<Brewfile> {
     let content = std::fs::read_to_string(path)?;
-    let mut brewfile = parse_string(&content)?;
+    let mut brewfile = parse_string_with(&content, custom_directives)?;
     brewfile.path = Some(path.to_path_buf());
     Ok(brewfile)
 }
 
 /// Parse a Brewfile from a string.
 pub fn parse_string(content: &str) -> Result<Brewfile> {
+    parse_string_with(content, &[])
+}
+
+/// Parse a Brewfile from a string, keeping entries for custom directives.
+///
+/// See [`parse_file_with`].
+pub fn parse_string_with(content: &str, custom_directives: &[&str]) -> Result<Brewfile> {
     let mut brewfile = Brewfile::new();
+    let mut current_group: Option<String> = None;
+    let mut current_only: Vec<String> = Vec::new();
 
     for (line_num, line) in content.lines().enumerate() {
         let line = line.trim();
 
+        // Markers are comments, so check them before skipping comments
+        match parse_marker(line) {
+            Some(Marker::GroupStart(name)) => {
+                current_group = Some(name);
+                continue;
+            }
+            Some(Marker::GroupEnd) => {
+                current_group = None;
+                continue;
+            }
+            Some(Marker::OnlyStart(tags)) => {
+                current_only = tags;
+                continue;
+            }
+            Some(Marker::OnlyEnd) => {
+                current_only.clear();
+                continue;
+            }
+            None => {}
+        }
+
         // Skip empty lines and comments
         if line.is_empty() || line.starts_with('#') {
             continue;
         }
 
-        // Extract version comment if present (e.g., "# 2.40.0" at end of line)
-        let (line, version_comment) = extract_version_comment(line);
+        // Extract the trailing comments if present (e.g., "# Distributed
+        // revision control system # 2.40.0" at end of line)
+        let (line, version_comment, description) = extract_trailing_comment(line);
 
         // Parse the package entry
-        if let Some(mut package) = parse_line(line, line_num + 1)? {
+        if let Some(mut package) = parse_line(line, line_num + 1, custom_directives)? {
             // Apply version from comment if present
             if let Some(version) = version_comment {
                 package.version = Some(version);
             }
+            package.description = description;
+            package.group.clone_from(&current_group);
+            package.only.clone_from(&current_only);
             brewfile.add(package);
         }
     }
@@ -50,49 +117,169 @@ pub fn parse_string(content: &str) -> Result<Brewfile> {
     Ok(brewfile)
 }
 
+/// A comment that opens or closes a group or conditional block.
+#[derive(Debug, PartialEq, Eq)]
+enum Marker {
+    /// `# group: name`
+    GroupStart(String),
+    /// `# endgroup`
+    GroupEnd,
+    /// `# only: tag1, tag2`
+    OnlyStart(Vec<String>),
+    /// `# endonly`
+    OnlyEnd,
+}
+
+/// Parse a marker comment, returning `None` if the line is not one.
+fn parse_marker(line: &str) -> Option<Marker> {
+    let comment = line.strip_prefix('#')?.trim();
+
+    if comment.eq_ignore_ascii_case("endgroup") || comment.eq_ignore_ascii_case("end group") {
+        return Some(Marker::GroupEnd);
+    }
+    if comment.eq_ignore_ascii_case("endonly") || comment.eq_ignore_ascii_case("end only") {
+        return Some(Marker::OnlyEnd);
+    }
+
+    let (key, value) = comment.split_once(':')?;
+    let (key, value) = (key.trim(), value.trim());
+    if value.is_empty() {
+        return None;
+    }
+    if key.eq_ignore_ascii_case("group") {
+        Some(Marker::GroupStart(value.to_string()))
+    } else if key.eq_ignore_ascii_case("only") {
+        let tags = value
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect();
+        Some(Marker::OnlyStart(tags))
+    } else {
+        None
+    }
+}
+
+/// Extract the comment from the end of a line.
+/// Returns (line without comment, optional version, optional description).
+///
+/// The version is a last comment of one version-like token, as in
+/// `brew "git" # 2.40.0`; a description is a comment of its own before it,
+/// as in `brew "git" # Distributed revision control # 2.40.0`.
+fn extract_trailing_comment(line: &str) -> (&str, Option<String>, Option<String>) {
+    let (line, version) = extract_version_comment(line);
+    let Some(hash_pos) = comment_start(line) else {
+        return (line, version, None);
+    };
+    let description = Some(line[hash_pos + 1..].trim().to_string()).filter(|d| !d.is_empty());
+    (line[..hash_pos].trim(), version, description)
+}
+
 /// Extract version comment from end of line.
 /// Returns (line without comment, optional version).
 fn extract_version_comment(line: &str) -> (&str, Option<String>) {
-    // Look for pattern: ... # version or ... # some version comment
+    // Look for pattern: ... # version
     if let Some(hash_pos) = line.rfind('#') {
         let before = line[..hash_pos].trim();
         let comment = line[hash_pos + 1..].trim();
 
-        // Check if comment looks like a version (starts with digit or 'v')
-        if !comment.is_empty() {
-            let first_char = comment.chars().next().unwrap();
-            if first_char.is_ascii_digit() || (first_char == 'v' && comment.len() > 1) {
-                // Extract version (first word of comment)
-                let version = comment.split_whitespace().next().unwrap_or(comment);
-                return (before, Some(version.to_string()));
-            }
+        // A version is a single word starting with a digit or 'v' and a
+        // digit, so a description like "7-Zip file archiver" isn't one
+        let mut chars = comment.chars();
+        let looks_like_version = match chars.next() {
+            Some(c) if c.is_ascii_digit() => true,
+            Some('v') => chars.next().is_some_and(|c| c.is_ascii_digit()),
+            _ => false,
+        };
+        if looks_like_version && !comment.contains(char::is_whitespace) {
+            return (before, Some(comment.to_string()));
         }
     }
     (line, None)
 }
 
+/// Position of the `#` that starts a comment: the first one outside quotes
+/// and brackets, so `args: ["with-#"]` and `# a # b` are handled.
+fn comment_start(line: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut depth = 0usize;
+    for (i, c) in line.char_indices() {
+        if let Some(q) = quote {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                c if c == q => quote = None,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth = depth.saturating_sub(1),
+            '#' if depth == 0 => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
 /// Parse a single line of Brewfile.
-fn parse_line(line: &str, line_num: usize) -> Result<Option<Package>> {
+fn parse_line(line: &str, line_num: usize, custom_directives: &[&str]) -> Result<Option<Package>> {
     // Find the directive (tap, brew, cask, mas, vscode)
     let (directive, rest) = match line.split_once(char::is_whitespace) {
         Some((d, r)) => (d, r.trim()),
         None => return Ok(None), // Line with just a word, ignore
     };
 
+    let custom = custom_directives.contains(&directive);
     let package_type = match PackageType::from_directive(directive) {
         Some(t) => t,
+        None if custom => PackageType::Custom,
         None => return Ok(None), // Unknown directive, ignore
     };
 
     // Parse the rest of the line for name and options
     let (name, options) = parse_arguments(rest, line_num)?;
 
-    let mut package = Package::new(name, package_type);
+    let mut package = if package_type == PackageType::Custom {
+        Package::custom(directive, name)
+    } else {
+        Package::new(name, package_type)
+    };
     package.options = options;
+    if package_type == PackageType::Cask {
+        package.cask = take_cask_options(&mut package.options)?;
+    }
 
     Ok(Some(package))
 }
 
+/// Move the `args: { ... }` hash and `greedy:` of a cask out of its options.
+fn take_cask_options(options: &mut HashMap<String, String>) -> Result<CaskOptions> {
+    let mut cask = CaskOptions::default();
+    if let Some(hash) = options
+        .get("args")
+        .and_then(|args| args.strip_prefix('{')?.strip_suffix('}'))
+    {
+        let mut args = HashMap::new();
+        parse_options(hash, &mut args)?;
+        // Sorted so unknown args keep a stable order
+        let mut args: Vec<_> = args.into_iter().collect();
+        args.sort();
+        for (key, value) in args {
+            cask.set_arg(&key, &value);
+        }
+        options.remove("args");
+    }
+    if let Some(greedy) = options.remove("greedy") {
+        cask.greedy = greedy == "true";
+    }
+    Ok(cask)
+}
+
 /// Parse arguments from a Brewfile line.
 /// Handles: "name", key: value, key: :symbol
 fn parse_arguments(args: &str, line_num: usize) -> Result<(String, HashMap<String, String>)> {
@@ -215,6 +402,12 @@ fn parse_option_value(value_str: &str) -> (String, &str) {
         return (value.to_string(), rest);
     }
 
+    // Hash or array, kept as written
+    if value_str.starts_with(['{', '[']) {
+        let end = closing_bracket(value_str).map_or(value_str.len(), |i| i + 1);
+        return (value_str[..end].to_string(), &value_str[end..]);
+    }
+
     // Number or unquoted value
     let end = value_str
         .find(|c: char| c == ',' || c.is_whitespace())
@@ -225,6 +418,29 @@ fn parse_option_value(value_str: &str) -> (String, &str) {
     (value.to_string(), rest)
 }
 
+/// Index of the bracket closing the one `text` starts with, skipping
+/// brackets inside quoted strings.
+fn closing_bracket(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '{' | '[') => depth += 1,
+            (None, '}' | ']') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -273,6 +489,22 @@ mod tests {
         assert_eq!(brewfile.packages[0].mas_id(), Some("497799835"));
     }
 
+    #[test]
+    fn test_parse_custom_directives() {
+        let content = "pipx \"httpie\" # 3.2.2\nwhalebrew \"whalebrew/wget\"\n";
+
+        // Unregistered directives are ignored
+        assert!(parse_string(content).unwrap().packages.is_empty());
+
+        let brewfile = parse_string_with(content, &["pipx"]).unwrap();
+        assert_eq!(brewfile.packages.len(), 1);
+        let pkg = &brewfile.packages[0];
+        assert_eq!(pkg.package_type, PackageType::Custom);
+        assert_eq!(pkg.directive(), "pipx");
+        assert_eq!(pkg.name, "httpie");
+        assert_eq!(pkg.version.as_deref(), Some("3.2.2"));
+    }
+
     #[test]
     fn test_parse_vscode() {
         let brewfile = parse_string(r#"vscode "ms-python.python""#).unwrap();
@@ -357,6 +589,30 @@ brew "git"
         );
     }
 
+    #[test]
+    fn test_parse_cask_options() {
+        let brewfile = parse_string(
+            r#"cask "firefox", args: { appdir: "~/My Apps", no_quarantine: true, fontdir: "/f" }, greedy: true
+brew "vim", args: ["HEAD"]"#,
+        )
+        .unwrap();
+
+        let cask = &brewfile.packages[0];
+        assert!(cask.options.is_empty());
+        assert_eq!(cask.cask.appdir.as_deref(), Some("~/My Apps"));
+        assert!(cask.cask.no_quarantine);
+        assert!(cask.cask.greedy);
+        assert_eq!(
+            cask.cask.install_flags(),
+            vec!["--appdir=~/My Apps", "--no-quarantine", "--fontdir=/f"]
+        );
+
+        // Formula args are kept as written
+        let vim = &brewfile.packages[1];
+        assert_eq!(vim.options.get("args"), Some(&r#"["HEAD"]"#.to_string()));
+        assert!(vim.cask.is_empty());
+    }
+
     #[test]
     fn test_version_comment_with_v_prefix() {
         let brewfile = parse_string(r#"brew "node" # v18.16.0"#).unwrap();
@@ -370,6 +626,138 @@ brew "git"
         assert_eq!(brewfile.packages[0].version, None);
     }
 
+    #[test]
+    fn test_parse_groups() {
+        let content = r#"
+tap "homebrew/cask"
+
+# group: base
+brew "git"
+brew "jq"
+
+# group: gui
+cask "firefox"
+# endgroup
+
+brew "curl"
+"#;
+        let brewfile = parse_string(content).unwrap();
+        let groups: Vec<_> = brewfile
+            .packages
+            .iter()
+            .map(|p| (p.name.as_str(), p.group.as_deref()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("homebrew/cask", None),
+                ("git", Some("base")),
+                ("jq", Some("base")),
+                ("firefox", Some("gui")),
+                ("curl", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_marker() {
+        assert_eq!(
+            parse_marker("# group: work"),
+            Some(Marker::GroupStart("work".to_string()))
+        );
+        assert_eq!(
+            parse_marker("# Group:base"),
+            Some(Marker::GroupStart("base".to_string()))
+        );
+        assert_eq!(parse_marker("# endgroup"), Some(Marker::GroupEnd));
+        assert_eq!(
+            parse_marker("# only: work, studio-mac"),
+            Some(Marker::OnlyStart(vec![
+                "work".to_string(),
+                "studio-mac".to_string()
+            ]))
+        );
+        assert_eq!(parse_marker("# endonly"), Some(Marker::OnlyEnd));
+        assert_eq!(parse_marker("# group:"), None);
+        assert_eq!(parse_marker("# only:"), None);
+        assert_eq!(parse_marker("# CLI tools"), None);
+        assert_eq!(parse_marker(r#"brew "git""#), None);
+    }
+
+    #[test]
+    fn test_parse_conditional_blocks() {
+        let content = r#"
+brew "git"
+# group: gui
+# only: work
+cask "slack"
+# only: home, studio-mac
+cask "steam"
+# endonly
+cask "firefox"
+# endgroup
+"#;
+        let brewfile = parse_string(content).unwrap();
+        let only: Vec<_> = brewfile
+            .packages
+            .iter()
+            .map(|p| (p.name.as_str(), p.only.join(",")))
+            .collect();
+        assert_eq!(
+            only,
+            vec![
+                ("git", String::new()),
+                ("slack", "work".to_string()),
+                ("steam", "home,studio-mac".to_string()),
+                ("firefox", String::new()),
+            ]
+        );
+        assert_eq!(brewfile.packages[1].group.as_deref(), Some("gui"));
+
+        let names = |b: Brewfile| b.packages.into_iter().map(|p| p.name).collect::<Vec<_>>();
+        assert_eq!(
+            names(brewfile.for_tags(&["studio-mac"])),
+            vec!["git", "steam", "firefox"]
+        );
+        assert_eq!(
+            names(brewfile.for_tags(&[] as &[&str])),
+            vec!["git", "firefox"]
+        );
+        assert_eq!(brewfile.tags(), vec!["work", "home", "studio-mac"]);
+    }
+
+    #[test]
+    fn test_extract_trailing_comment() {
+        let (line, version, description) = extract_trailing_comment(r#"brew "git" # 2.40.0"#);
+        assert_eq!(line, r#"brew "git""#);
+        assert_eq!(version, Some("2.40.0".to_string()));
+        assert_eq!(description, None);
+
+        let (line, version, description) =
+            extract_trailing_comment(r#"brew "git" # Distributed revision control # v2.40.0"#);
+        assert_eq!(line, r#"brew "git""#);
+        assert_eq!(version, Some("v2.40.0".to_string()));
+        assert_eq!(description.as_deref(), Some("Distributed revision control"));
+
+        // When comment is not a version, it is the description
+        let (line, version, description) = extract_trailing_comment(r#"brew "vim" # vi improved"#);
+        assert_eq!(line, r#"brew "vim""#);
+        assert_eq!(version, None);
+        assert_eq!(description.as_deref(), Some("vi improved"));
+
+        // Only a comment of one word is a version
+        let (line, version, description) =
+            extract_trailing_comment(r#"cask "7zip" # 7-Zip file archiver"#);
+        assert_eq!(line, r#"cask "7zip""#);
+        assert_eq!(version, None);
+        assert_eq!(description.as_deref(), Some("7-Zip file archiver"));
+
+        let (line, version, description) = extract_trailing_comment(r#"brew "git""#);
+        assert_eq!(line, r#"brew "git""#);
+        assert_eq!(version, None);
+        assert_eq!(description, None);
+    }
+
     #[test]
     fn test_extract_version_comment() {
         let (line, version) = extract_version_comment(r#"brew "git" # 2.40.0"#);
@@ -385,4 +773,29 @@ brew "git"
         assert_eq!(line, r#"brew "git""#);
         assert_eq!(version, None);
     }
+
+    #[test]
+    fn test_extract_trailing_comment_with_hash_in_comment() {
+        // The first `#` starts the description; later ones belong to it
+        let (line, version, description) =
+            extract_trailing_comment(r#"brew "fsharp" # F# and C# tooling # 8.0"#);
+        assert_eq!(line, r#"brew "fsharp""#);
+        assert_eq!(version, Some("8.0".to_string()));
+        assert_eq!(description.as_deref(), Some("F# and C# tooling"));
+    }
+
+    #[test]
+    fn test_extract_trailing_comment_with_hash_in_value() {
+        // A `#` inside quotes or brackets is part of the entry
+        let (line, version, description) =
+            extract_trailing_comment(r#"brew "vim", args: ["with-#lua"] # 9.1"#);
+        assert_eq!(line, r#"brew "vim", args: ["with-#lua"]"#);
+        assert_eq!(version, Some("9.1".to_string()));
+        assert_eq!(description, None);
+
+        let (line, version, description) = extract_trailing_comment(r#"tap "user/c#-tools""#);
+        assert_eq!(line, r#"tap "user/c#-tools""#);
+        assert_eq!(version, None);
+        assert_eq!(description, None);
+    }
 }
@@ -1,6 +1,11 @@
 //! Writer for generating Brewfile content.
 //!
 //! Generates properly formatted Brewfile with optional version comments.
+//! Package descriptions are written as trailing comments, after the version.
+//! Grouped packages are written after ungrouped ones, each group wrapped in
+//! `# group: <name>` / `# endgroup` markers so they round-trip through the parser.
+//! Within a group, conditional packages follow the unconditional ones in
+//! `# only: <tags>` / `# endonly` blocks.
 
 use crate::types::{Brewfile, Package, PackageType};
 use std::fmt::Write;
@@ -15,6 +20,9 @@ pub struct WriteOptions {
     pub group_by_type: bool,
     /// Sort packages alphabetically within groups
     pub sort_packages: bool,
+    /// Write each third-party tap in its own section, followed by the
+    /// formulas and casks installed from it
+    pub group_by_tap: bool,
 }
 
 /// Write a Brewfile to a file.
@@ -27,23 +35,136 @@ pub fn write_file(brewfile: &Brewfile, path: &Path, options: &WriteOptions) -> s
 pub fn write_string(brewfile: &Brewfile, options: &WriteOptions) -> String {
     let mut output = String::new();
 
-    if options.group_by_type {
-        write_grouped(&mut output, brewfile, options);
-    } else {
-        write_flat(&mut output, brewfile, options);
+    write_conditional(&mut output, &packages_in_group(brewfile, None), options);
+
+    for group in brewfile.groups() {
+        if !output.is_empty() {
+            writeln!(output).unwrap();
+        }
+        writeln!(output, "# group: {group}").unwrap();
+        write_conditional(
+            &mut output,
+            &packages_in_group(brewfile, Some(group)),
+            options,
+        );
+        writeln!(output, "# endgroup").unwrap();
     }
 
     output
 }
 
+/// Write unconditional packages, then one block per distinct tag list.
+fn write_conditional(output: &mut String, brewfile: &Brewfile, options: &WriteOptions) {
+    let mut conditions: Vec<&[String]> = vec![&[]];
+    for package in &brewfile.packages {
+        if !conditions.contains(&package.only.as_slice()) {
+            conditions.push(&package.only);
+        }
+    }
+
+    for only in conditions {
+        let block = Brewfile {
+            path: None,
+            packages: brewfile
+                .packages
+                .iter()
+                .filter(|p| p.only == only)
+                .cloned()
+                .collect(),
+        };
+        if only.is_empty() {
+            write_section(output, &block, options);
+        } else {
+            writeln!(output, "# only: {}", only.join(", ")).unwrap();
+            write_section(output, &block, options);
+            writeln!(output, "# endonly").unwrap();
+        }
+    }
+}
+
+/// Collect the packages belonging to exactly one group (or to no group).
+fn packages_in_group(brewfile: &Brewfile, group: Option<&str>) -> Brewfile {
+    Brewfile {
+        path: None,
+        packages: brewfile
+            .packages
+            .iter()
+            .filter(|p| p.group.as_deref() == group)
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Write a set of packages, optionally grouped by tap and type.
+fn write_section(output: &mut String, brewfile: &Brewfile, options: &WriteOptions) {
+    if options.group_by_tap {
+        write_by_tap(output, brewfile, options);
+    } else if options.group_by_type {
+        write_grouped(output, brewfile, options);
+    } else {
+        write_flat(output, brewfile, options);
+    }
+}
+
+/// Write packages from core taps, then one section per listed tap.
+///
+/// A tap's section holds the tap and the namespaced (`owner/tap/name`)
+/// packages from it. Namespaced packages whose tap isn't listed stay with
+/// the core packages.
+fn write_by_tap(output: &mut String, brewfile: &Brewfile, options: &WriteOptions) {
+    let taps = brewfile.taps();
+    let from_listed_tap = |p: &Package| {
+        p.package_type == PackageType::Tap
+            || p.implied_tap()
+                .is_some_and(|tap| taps.iter().any(|t| t.name == tap))
+    };
+
+    let core = Brewfile {
+        path: None,
+        packages: brewfile
+            .packages
+            .iter()
+            .filter(|p| !from_listed_tap(p))
+            .cloned()
+            .collect(),
+    };
+    let inner = WriteOptions {
+        group_by_tap: false,
+        ..options.clone()
+    };
+    write_section(output, &core, &inner);
+
+    let mut taps = taps;
+    if options.sort_packages {
+        taps.sort_by_key(|t| &t.name);
+    }
+    for tap in taps {
+        if !output.is_empty() {
+            writeln!(output).unwrap();
+        }
+        writeln!(output, "# {}", tap.name).unwrap();
+        write_package(output, tap, options);
+
+        let packages = Brewfile {
+            path: None,
+            packages: brewfile
+                .packages
+                .iter()
+                .filter(|p| p.implied_tap() == Some(tap.name.as_str()))
+                .cloned()
+                .collect(),
+        };
+        write_flat(output, &packages, options);
+    }
+}
+
 /// Write all packages without grouping.
 fn write_flat(output: &mut String, brewfile: &Brewfile, options: &WriteOptions) {
     let packages = if options.sort_packages {
         let mut sorted = brewfile.packages.clone();
         sorted.sort_by(|a, b| {
-            a.package_type
-                .directive()
-                .cmp(b.package_type.directive())
+            a.directive()
+                .cmp(b.directive())
                 .then_with(|| a.name.cmp(&b.name))
         });
         sorted
@@ -68,9 +189,23 @@ fn write_grouped(output: &mut String, brewfile: &Brewfile, options: &WriteOption
 
     let mut first_section = true;
 
-    for (package_type, header) in sections {
-        let mut packages: Vec<_> = brewfile.packages_of_type(package_type);
+    // Custom packages get one section per provider, in order of appearance
+    let mut custom_directives: Vec<&str> = Vec::new();
+    for package in brewfile.packages_of_type(PackageType::Custom) {
+        if !custom_directives.contains(&package.directive()) {
+            custom_directives.push(package.directive());
+        }
+    }
+    let sections = sections
+        .into_iter()
+        .map(|(package_type, header)| (brewfile.packages_of_type(package_type), header))
+        .chain(
+            custom_directives
+                .into_iter()
+                .map(|directive| (brewfile.custom_packages(directive), directive)),
+        );
 
+    for (mut packages, header) in sections {
         if packages.is_empty() {
             continue;
         }
@@ -96,7 +231,7 @@ fn write_grouped(output: &mut String, brewfile: &Brewfile, options: &WriteOption
 
 /// Write a single package entry.
 fn write_package(output: &mut String, package: &Package, options: &WriteOptions) {
-    let directive = package.package_type.directive();
+    let directive = package.directive();
 
     // Start with directive and name
     write!(output, "{} \"{}\"", directive, package.name).unwrap();
@@ -111,16 +246,29 @@ fn write_package(output: &mut String, package: &Package, options: &WriteOptions)
             options_written = true;
         }
 
-        // Determine if value should be a symbol or string
-        if is_symbol_value(value) {
-            write!(output, "{key}: :{value}").unwrap();
-        } else if value.chars().all(|c| c.is_ascii_digit()) {
-            // Numeric value
-            write!(output, "{key}: {value}").unwrap();
-        } else {
-            // String value
-            write!(output, "{key}: \"{value}\"").unwrap();
+        write_option(output, key, value);
+    }
+
+    // Cask options
+    let args = package.cask.brewfile_args();
+    if !args.is_empty() {
+        write!(output, ", args: {{ ").unwrap();
+        for (i, (key, value)) in args.into_iter().enumerate() {
+            if i > 0 {
+                write!(output, ", ").unwrap();
+            }
+            write_option(output, key, value);
         }
+        write!(output, " }}").unwrap();
+    }
+    if package.cask.greedy {
+        write!(output, ", greedy: true").unwrap();
+    }
+
+    // The description is a comment of its own, so the version comment after
+    // it stays a single word
+    if let Some(description) = &package.description {
+        write!(output, " # {description}").unwrap();
     }
 
     // Add version comment if present and requested
@@ -133,6 +281,23 @@ fn write_package(output: &mut String, package: &Package, options: &WriteOptions)
     writeln!(output).unwrap();
 }
 
+/// Write a `key: value` option.
+fn write_option(output: &mut String, key: &str, value: &str) {
+    // Determine if value should be a symbol or string
+    if matches!(value, "true" | "false") || value.starts_with(['{', '[']) {
+        // Boolean, hash or array, written as parsed
+        write!(output, "{key}: {value}").unwrap();
+    } else if is_symbol_value(value) {
+        write!(output, "{key}: :{value}").unwrap();
+    } else if value.chars().all(|c| c.is_ascii_digit()) {
+        // Numeric value
+        write!(output, "{key}: {value}").unwrap();
+    } else {
+        // String value
+        write!(output, "{key}: \"{value}\"").unwrap();
+    }
+}
+
 /// Check if a value should be written as a Ruby symbol.
 fn is_symbol_value(value: &str) -> bool {
     // Common Ruby symbols used in Brewfiles
@@ -145,6 +310,7 @@ fn is_symbol_value(value: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::CaskOptions;
 
     #[test]
     fn test_write_simple_tap() {
@@ -207,6 +373,25 @@ mod tests {
         assert!(output.contains("# Casks"));
     }
 
+    #[test]
+    fn test_write_grouped_custom_packages() {
+        let mut brewfile = Brewfile::new();
+        brewfile.add(Package::custom("pipx", "httpie"));
+        brewfile.add(Package::brew("git"));
+        brewfile.add(Package::custom("go", "golang.org/x/tools/gopls"));
+
+        let options = WriteOptions {
+            group_by_type: true,
+            ..Default::default()
+        };
+        let output = write_string(&brewfile, &options);
+
+        assert_eq!(
+            output,
+            "# Formulae\nbrew \"git\"\n\n# pipx\npipx \"httpie\"\n\n# go\ngo \"golang.org/x/tools/gopls\"\n"
+        );
+    }
+
     #[test]
     fn test_write_sorted() {
         let mut brewfile = Brewfile::new();
@@ -249,6 +434,147 @@ mod tests {
         assert!(output.find("bash").unwrap() < output.find("zsh").unwrap());
     }
 
+    #[test]
+    fn test_write_groups_round_trip() {
+        let mut brewfile = Brewfile::new();
+        brewfile.add(Package::tap("homebrew/cask"));
+        brewfile.add(Package::brew("git").with_group("base"));
+        brewfile.add(Package::cask("firefox").with_group("gui"));
+        brewfile.add(Package::brew("jq").with_group("base"));
+
+        let output = write_string(&brewfile, &WriteOptions::default());
+        assert_eq!(
+            output,
+            "tap \"homebrew/cask\"\n\n\
+             # group: base\nbrew \"git\"\nbrew \"jq\"\n# endgroup\n\n\
+             # group: gui\ncask \"firefox\"\n# endgroup\n"
+        );
+
+        let parsed = crate::brewfile::parse_string(&output).unwrap();
+        assert_eq!(parsed.groups(), vec!["base", "gui"]);
+        assert_eq!(parsed.select_groups(&["base"]).packages.len(), 3);
+    }
+
+    #[test]
+    fn test_write_conditional_round_trip() {
+        let mut brewfile = Brewfile::new();
+        brewfile.add(Package::cask("slack").with_only(["work"]));
+        brewfile.add(Package::brew("git"));
+        brewfile.add(
+            Package::cask("steam")
+                .with_group("gui")
+                .with_only(["home", "studio"]),
+        );
+        brewfile.add(Package::cask("firefox").with_group("gui"));
+
+        let output = write_string(&brewfile, &WriteOptions::default());
+        assert_eq!(
+            output,
+            "brew \"git\"\n# only: work\ncask \"slack\"\n# endonly\n\n\
+             # group: gui\ncask \"firefox\"\n# only: home, studio\ncask \"steam\"\n# endonly\n# endgroup\n"
+        );
+
+        let parsed = crate::brewfile::parse_string(&output).unwrap();
+        assert_eq!(parsed.tags(), vec!["work", "home", "studio"]);
+        assert_eq!(parsed.for_tags(&["work"]).packages.len(), 3);
+    }
+
+    #[test]
+    fn test_write_cask_options_round_trip() {
+        let mut brewfile = Brewfile::new();
+        brewfile.add(Package::cask("firefox").with_cask_options(CaskOptions {
+            appdir: Some("~/Applications".to_string()),
+            no_quarantine: true,
+            greedy: true,
+            ..Default::default()
+        }));
+
+        let output = write_string(&brewfile, &WriteOptions::default());
+        assert_eq!(
+            output,
+            "cask \"firefox\", args: { appdir: \"~/Applications\", no_quarantine: true }, greedy: true\n"
+        );
+
+        let parsed = crate::brewfile::parse_string(&output).unwrap();
+        assert_eq!(parsed.packages, brewfile.packages);
+    }
+
+    #[test]
+    fn test_write_by_tap_with_descriptions() {
+        let mut brewfile = Brewfile::new();
+        brewfile.add(Package::tap("owner/tools"));
+        brewfile.add(Package::brew("git").with_version("2.40.0"));
+        brewfile.add(Package::cask("owner/tools/viewer").with_description("Views things"));
+        brewfile.add(
+            Package::brew("owner/tools/fd")
+                .with_version("9.0.0")
+                .with_description("Simple, fast find"),
+        );
+        brewfile.add(Package::brew("other/tap/jq"));
+
+        let options = WriteOptions {
+            include_versions: true,
+            sort_packages: true,
+            group_by_tap: true,
+            ..Default::default()
+        };
+        let output = write_string(&brewfile, &options);
+        assert_eq!(
+            output,
+            "brew \"git\" # 2.40.0\nbrew \"other/tap/jq\"\n\n\
+             # owner/tools\ntap \"owner/tools\"\n\
+             brew \"owner/tools/fd\" # Simple, fast find # 9.0.0\n\
+             cask \"owner/tools/viewer\" # Views things\n"
+        );
+
+        let parsed = crate::brewfile::parse_string(&output).unwrap();
+        assert_eq!(parsed.packages.len(), 5);
+        let fd = parsed.packages.iter().find(|p| p.name == "owner/tools/fd");
+        assert_eq!(
+            fd.unwrap().description.as_deref(),
+            Some("Simple, fast find")
+        );
+    }
+
+    #[test]
+    fn test_descriptions_round_trip_apart_from_versions() {
+        let mut brewfile = Brewfile::new();
+        brewfile.add(Package::cask("7zip").with_description("7-Zip file archiver"));
+        brewfile.add(
+            Package::brew("p7zip")
+                .with_version("17.05")
+                .with_description("7-Zip port"),
+        );
+        brewfile.add(Package::brew("git").with_version("2.40.0"));
+
+        let options = WriteOptions {
+            include_versions: true,
+            ..Default::default()
+        };
+        let output = write_string(&brewfile, &options);
+        assert_eq!(
+            output,
+            "cask \"7zip\" # 7-Zip file archiver\n\
+             brew \"p7zip\" # 7-Zip port # 17.05\n\
+             brew \"git\" # 2.40.0\n"
+        );
+
+        let parsed = crate::brewfile::parse_string(&output).unwrap();
+        let read: Vec<_> = parsed
+            .packages
+            .iter()
+            .map(|p| (p.version.as_deref(), p.description.as_deref()))
+            .collect();
+        assert_eq!(
+            read,
+            [
+                (None, Some("7-Zip file archiver")),
+                (Some("17.05"), Some("7-Zip port")),
+                (Some("2.40.0"), None),
+            ]
+        );
+    }
+
     #[test]
     fn test_write_vscode() {
         let mut brewfile = Brewfile::new();
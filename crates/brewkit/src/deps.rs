@@ -0,0 +1,214 @@
+//! Dependency graph of installed formulas.
+//!
+//! [`Client::dependency_graph`](crate::Client::dependency_graph) links every
+//! installed formula to the installed formulas it depends on, and records
+//! the formulas installed casks depend on. From that the
+//! graph answers which formulas were installed on purpose and nothing else
+//! needs ([`leaves`](DependencyGraph::leaves)), which dependencies are left
+//! over after their dependents were removed
+//! ([`orphans`](DependencyGraph::orphans)), and who still needs a formula
+//! ([`dependents`](DependencyGraph::dependents)).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// An installed formula in the graph.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Node {
+    installed_on_request: bool,
+    dependencies: BTreeSet<String>,
+}
+
+/// Installed formulas and their dependencies.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DependencyGraph {
+    formulas: BTreeMap<String, Node>,
+    /// Installed casks, by the formulas they depend on
+    #[serde(default)]
+    casks: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl DependencyGraph {
+    /// Create an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an installed formula with its direct dependencies.
+    ///
+    /// Dependencies that are not installed themselves are ignored by the
+    /// queries, so optional dependencies can be passed as-is.
+    pub fn add(
+        &mut self,
+        name: impl Into<String>,
+        installed_on_request: bool,
+        dependencies: impl IntoIterator<Item = impl Into<String>>,
+    ) {
+        self.formulas.insert(
+            name.into(),
+            Node {
+                installed_on_request,
+                dependencies: dependencies.into_iter().map(Into::into).collect(),
+            },
+        );
+    }
+
+    /// Add an installed cask with the formulas it depends on.
+    ///
+    /// Casks aren't nodes of the graph, but their dependencies are never
+    /// orphans.
+    pub fn add_cask(
+        &mut self,
+        name: impl Into<String>,
+        dependencies: impl IntoIterator<Item = impl Into<String>>,
+    ) {
+        self.casks.insert(
+            name.into(),
+            dependencies.into_iter().map(Into::into).collect(),
+        );
+    }
+
+    /// Number of installed formulas.
+    pub fn len(&self) -> usize {
+        self.formulas.len()
+    }
+
+    /// Check if the graph has no formulas.
+    pub fn is_empty(&self) -> bool {
+        self.formulas.is_empty()
+    }
+
+    /// Check if a formula is installed.
+    pub fn contains(&self, name: &str) -> bool {
+        self.formulas.contains_key(name)
+    }
+
+    /// Installed direct dependencies of a formula.
+    pub fn dependencies(&self, name: &str) -> Vec<&str> {
+        self.formulas
+            .get(name)
+            .into_iter()
+            .flat_map(|node| &node.dependencies)
+            .filter(|dep| self.contains(dep))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Installed formulas that depend directly on `name` (reverse dependencies).
+    pub fn dependents(&self, name: &str) -> Vec<&str> {
+        self.formulas
+            .iter()
+            .filter(|(_, node)| node.dependencies.contains(name))
+            .map(|(formula, _)| formula.as_str())
+            .collect()
+    }
+
+    /// Formulas installed on request that no other formula depends on.
+    pub fn leaves(&self) -> Vec<&str> {
+        self.formulas
+            .iter()
+            .filter(|(name, node)| node.installed_on_request && self.dependents(name).is_empty())
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Dependencies no formula installed on request, and no cask, needs
+    /// anymore.
+    ///
+    /// Returned in an order safe for uninstalling: every orphan comes before
+    /// the orphans it depends on.
+    pub fn orphans(&self) -> Vec<&str> {
+        // Everything reachable from a formula installed on request, or from
+        // a cask, is needed
+        let mut needed = BTreeSet::new();
+        let mut stack: Vec<&str> = self
+            .formulas
+            .iter()
+            .filter(|(_, node)| node.installed_on_request)
+            .map(|(name, _)| name.as_str())
+            .chain(self.casks.values().flatten().map(String::as_str))
+            .filter(|name| self.contains(name))
+            .collect();
+        while let Some(name) = stack.pop() {
+            if needed.insert(name) {
+                stack.extend(self.dependencies(name));
+            }
+        }
+
+        let mut remaining: BTreeSet<&str> = self
+            .formulas
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !needed.contains(name))
+            .collect();
+        let mut ordered = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let ready: Vec<&str> = remaining
+                .iter()
+                .copied()
+                .filter(|name| {
+                    self.dependents(name)
+                        .iter()
+                        .all(|dependent| !remaining.contains(dependent))
+                })
+                .collect();
+            // A dependency cycle: release the rest in name order
+            let ready = if ready.is_empty() {
+                remaining.iter().copied().collect()
+            } else {
+                ready
+            };
+            for name in &ready {
+                remaining.remove(name);
+            }
+            ordered.extend(ready);
+        }
+        ordered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        graph.add("git", true, ["gettext", "pcre2"]);
+        graph.add("gettext", false, Vec::<String>::new());
+        graph.add("pcre2", false, Vec::<String>::new());
+        // ffmpeg was uninstalled, leaving its dependency chain behind
+        graph.add("x264", false, ["nasm"]);
+        graph.add("nasm", false, Vec::<String>::new());
+        // Installed as a dependency, then used directly
+        graph.add("openssl@3", true, ["ca-certificates"]);
+        graph.add("ca-certificates", false, Vec::<String>::new());
+        graph.add("wget", true, ["openssl@3", "libidn2"]);
+        graph
+    }
+
+    #[test]
+    fn test_dependencies_and_dependents() {
+        let graph = graph();
+        assert_eq!(graph.len(), 8);
+        // libidn2 is not installed
+        assert_eq!(graph.dependencies("wget"), vec!["openssl@3"]);
+        assert_eq!(graph.dependents("openssl@3"), vec!["wget"]);
+        assert_eq!(graph.dependents("gettext"), vec!["git"]);
+        assert!(graph.dependents("git").is_empty());
+    }
+
+    #[test]
+    fn test_leaves_and_orphans() {
+        let graph = graph();
+        assert_eq!(graph.leaves(), vec!["git", "wget"]);
+        assert_eq!(graph.orphans(), vec!["x264", "nasm"]);
+    }
+
+    #[test]
+    fn test_cask_dependencies_are_not_orphans() {
+        let mut graph = graph();
+        graph.add_cask("handbrake", ["x264", "libdvdcss"]);
+        assert!(graph.orphans().is_empty());
+        assert_eq!(graph.len(), 8);
+    }
+}
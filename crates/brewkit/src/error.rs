@@ -15,6 +15,14 @@ use thiserror::Error;
 pub enum ErrorCategory {
     /// Network-related errors (transient, retryable)
     Network,
+    /// Another brew process holds a lock (transient, retryable)
+    Locked,
+    /// Downloaded file doesn't match its checksum
+    Checksum,
+    /// No bottle for this system and building from source isn't possible
+    NoBottle,
+    /// Package doesn't support this OS version or architecture
+    Unsupported,
     /// Package not found in any tap
     NotFound,
     /// Version or dependency conflict
@@ -32,7 +40,7 @@ pub enum ErrorCategory {
 impl ErrorCategory {
     /// Whether this error category is typically transient and worth retrying.
     pub fn is_retryable(&self) -> bool {
-        matches!(self, Self::Network)
+        matches!(self, Self::Network | Self::Locked)
     }
 
     /// Whether this error can be safely ignored (operation already done).
@@ -44,6 +52,10 @@ impl ErrorCategory {
     pub fn description(&self) -> &'static str {
         match self {
             Self::Network => "Network connectivity issue",
+            Self::Locked => "Another Homebrew process is running",
+            Self::Checksum => "Checksum mismatch",
+            Self::NoBottle => "No bottle available",
+            Self::Unsupported => "Unsupported on this system",
             Self::NotFound => "Package not found",
             Self::Conflict => "Package conflict",
             Self::Permission => "Permission denied",
@@ -57,6 +69,13 @@ impl ErrorCategory {
     pub fn advice(&self) -> &'static str {
         match self {
             Self::Network => "Check your internet connection and try again",
+            Self::Locked => "Wait for the other brew process to finish and try again",
+            Self::Checksum => {
+                "Remove the cached download (brew cleanup) and retry; if it persists, \
+                 the upstream file changed and the formula or cask needs an update"
+            }
+            Self::NoBottle => "Try --build-from-source, or check the formula supports this system",
+            Self::Unsupported => "Check the package's OS and architecture requirements",
             Self::NotFound => "Verify the package name or add the required tap",
             Self::Conflict => "Resolve the conflict by removing conflicting packages",
             Self::Permission => "Check directory permissions or run with appropriate access",
@@ -79,6 +98,36 @@ pub enum Error {
         message: String,
     },
 
+    /// Another brew process holds a lock on the package or Homebrew
+    #[error("locked by another brew process: {message}")]
+    Locked {
+        /// Lock message from brew
+        message: String,
+    },
+
+    /// Downloaded file doesn't match the expected checksum
+    #[error("checksum mismatch for {name}: {message}")]
+    Checksum {
+        /// Package whose download failed verification
+        name: String,
+        /// Checksum details from brew
+        message: String,
+    },
+
+    /// No bottle is available for this system
+    #[error("no bottle available for {name}")]
+    NoBottle {
+        /// Package without a bottle
+        name: String,
+    },
+
+    /// Package doesn't support this OS version or architecture
+    #[error("unsupported: {message}")]
+    Unsupported {
+        /// Requirement message from brew
+        message: String,
+    },
+
     /// Package not found in any configured tap
     #[error("package not found: {name}")]
     NotFound {
@@ -151,6 +200,10 @@ impl Error {
     pub fn category(&self) -> ErrorCategory {
         match self {
             Error::Network { .. } => ErrorCategory::Network,
+            Error::Locked { .. } => ErrorCategory::Locked,
+            Error::Checksum { .. } => ErrorCategory::Checksum,
+            Error::NoBottle { .. } => ErrorCategory::NoBottle,
+            Error::Unsupported { .. } => ErrorCategory::Unsupported,
             Error::NotFound { .. } => ErrorCategory::NotFound,
             Error::Conflict { .. } => ErrorCategory::Conflict,
             Error::Permission { .. } => ErrorCategory::Permission,
@@ -172,21 +225,78 @@ impl Error {
 
     /// Create an error from brew command output.
     ///
-    /// Analyzes stderr to categorize the error appropriately.
+    /// Analyzes stderr to categorize the error appropriately. Specific,
+    /// permanent failures are checked before the transient ones, so that
+    /// e.g. a checksum mismatch reported by curl isn't retried as a network
+    /// error.
     pub fn from_brew_output(stderr: &str, package_name: Option<&str>) -> Self {
         let stderr_lower = stderr.to_lowercase();
+        let has = |patterns: &[&str]| patterns.iter().any(|p| stderr_lower.contains(p));
+        let name = || package_name.unwrap_or("unknown").to_string();
+
+        // Lock contention with a concurrent brew process
+        if has(&["has already locked", "another active homebrew"]) {
+            return Error::Locked {
+                message: stderr.trim().to_string(),
+            };
+        }
+
+        // Corrupted or changed downloads
+        if has(&[
+            "sha256 mismatch",
+            "checksum mismatch",
+            "checksum does not match",
+        ]) {
+            return Error::Checksum {
+                name: name(),
+                message: stderr.trim().to_string(),
+            };
+        }
+
+        if has(&["no bottle available", "no bottle for"]) {
+            return Error::NoBottle { name: name() };
+        }
+
+        if has(&[
+            "does not run on macos",
+            "requires macos",
+            "is not supported on",
+            "only supports",
+            "this software does not run on",
+            "an unsatisfied requirement failed",
+        ]) {
+            return Error::Unsupported {
+                message: stderr.trim().to_string(),
+            };
+        }
+
+        // A download URL that no longer exists won't come back on retry
+        if has(&[
+            "returned error: 404",
+            "returned error: 403",
+            "returned error: 410",
+        ]) {
+            return Error::CommandFailed {
+                message: format!("download not available for {}", name()),
+                stderr: stderr.trim().to_string(),
+            };
+        }
 
         // Network errors
-        if stderr_lower.contains("curl")
-            || stderr_lower.contains("could not resolve")
-            || stderr_lower.contains("connection refused")
-            || stderr_lower.contains("timed out")
-            || stderr_lower.contains("network")
-            || stderr_lower.contains("ssl")
-            || stderr_lower.contains("certificate")
-            || stderr_lower.contains("failed to download")
-            || stderr_lower.contains("error: sha256 mismatch")
-        {
+        if has(&[
+            "curl: (",
+            "could not resolve",
+            "connection refused",
+            "connection reset",
+            "timed out",
+            "network is unreachable",
+            "temporary failure in name resolution",
+            "ssl connect error",
+            "certificate verify failed",
+            "empty reply from server",
+            "failed to download",
+            "download failed",
+        ]) {
             return Error::Network {
                 message: stderr.trim().to_string(),
             };
@@ -200,24 +310,20 @@ impl Error {
             || stderr_lower.contains("error: no such keg")
             || stderr_lower.contains("couldn't find")
         {
-            return Error::NotFound {
-                name: package_name.unwrap_or("unknown").to_string(),
-            };
+            return Error::NotFound { name: name() };
         }
 
         // Already installed
         if stderr_lower.contains("already installed")
             || stderr_lower.contains("is already an installed")
         {
-            return Error::AlreadyInstalled {
-                name: package_name.unwrap_or("unknown").to_string(),
-            };
+            return Error::AlreadyInstalled { name: name() };
         }
 
         // Conflicts
         if stderr_lower.contains("conflict")
             || stderr_lower.contains("depends on")
-            || stderr_lower.contains("dependency")
+            || stderr_lower.contains("required by")
             || stderr_lower.contains("is a dependency")
         {
             return Error::Conflict {
@@ -305,4 +411,57 @@ mod tests {
         let err = Error::from_brew_output("Error: foo conflicts with bar", Some("foo"));
         assert_eq!(err.category(), ErrorCategory::Conflict);
     }
+
+    #[test]
+    fn test_from_brew_output_permanent_download_failures() {
+        let err = Error::from_brew_output(
+            "Error: SHA256 mismatch\nExpected: abc\n  Actual: def\n    File: /Caches/foo.dmg\n\
+             To retry an incomplete download, remove the file above.",
+            Some("foo"),
+        );
+        assert_eq!(err.category(), ErrorCategory::Checksum);
+        assert!(!err.is_retryable());
+
+        let err = Error::from_brew_output(
+            "Error: foo: no bottle available!\nYou can try to install from source with:",
+            Some("foo"),
+        );
+        assert_eq!(err.category(), ErrorCategory::NoBottle);
+
+        let err = Error::from_brew_output(
+            "Error: This software does not run on macOS versions older than Sonoma.",
+            Some("foo"),
+        );
+        assert_eq!(err.category(), ErrorCategory::Unsupported);
+
+        let err = Error::from_brew_output(
+            "curl: (22) The requested URL returned error: 404\nError: Download failed",
+            Some("foo"),
+        );
+        assert_eq!(err.category(), ErrorCategory::Other);
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_from_brew_output_transient_failures() {
+        let err = Error::from_brew_output(
+            "Error: A `brew install bar` process has already locked /opt/homebrew/Cellar/bar",
+            Some("foo"),
+        );
+        assert_eq!(err.category(), ErrorCategory::Locked);
+        assert!(err.is_retryable());
+
+        let err = Error::from_brew_output(
+            "curl: (35) LibreSSL SSL_connect: SSL_ERROR_SYSCALL in connection",
+            Some("foo"),
+        );
+        assert_eq!(err.category(), ErrorCategory::Network);
+
+        // Package names mentioning ssl or certificates aren't network errors
+        let err = Error::from_brew_output(
+            "Error: Cannot install openssl@3 because ca-certificates is keg-only",
+            Some("openssl@3"),
+        );
+        assert_ne!(err.category(), ErrorCategory::Network);
+    }
 }
@@ -0,0 +1,215 @@
+//! Installing many packages at once.
+//!
+//! [`Client::install_many`](crate::Client::install_many) installs taps first,
+//! one at a time, since formulas and casks may come from them. Everything
+//! else then runs on up to [`jobs`](InstallManyOptions::jobs) workers, except
+//! casks and mas apps: they may open installers or password prompts, so they
+//! share a single lane and install one after another. Taps implied by
+//! namespaced packages (`owner/tap/name`) are added along with the others.
+
+use crate::Client;
+use crate::error::{ErrorCategory, Result};
+use crate::progress::{self, BrewProgress};
+use crate::retry;
+use crate::types::{Package, PackageType, RetryConfig};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Options for [`Client::install_many`](crate::Client::install_many).
+#[derive(Debug, Clone)]
+pub struct InstallManyOptions {
+    /// Maximum number of installs running at once
+    pub jobs: usize,
+    /// Retry configuration applied to each package
+    pub retry: RetryConfig,
+}
+
+impl Default for InstallManyOptions {
+    fn default() -> Self {
+        Self {
+            jobs: 4,
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+/// Whether a package goes on the serial lane.
+fn is_serial(package: &Package) -> bool {
+    matches!(package.package_type, PackageType::Cask | PackageType::Mas)
+}
+
+/// Install `packages`, returning one result per package in input order.
+pub(crate) fn install_many(
+    client: &Client,
+    packages: &[Package],
+    options: &InstallManyOptions,
+    progress: &dyn BrewProgress,
+) -> Vec<Result<()>> {
+    let install = |package: &Package| {
+        progress::report(progress, &package.name, || {
+            retry::with_retry(&options.retry, None, || {
+                client.install_streaming(package, progress)
+            })
+        })
+    };
+    let results: Vec<Mutex<Option<Result<()>>>> =
+        packages.iter().map(|_| Mutex::new(None)).collect();
+    let store = |index: usize, result| *results[index].lock().unwrap() = Some(result);
+
+    // Add the taps of namespaced packages up front so parallel installs from
+    // one tap don't race to add it. A failure here surfaces again when the
+    // package itself is installed.
+    for tap in client.missing_taps(packages).unwrap_or_default() {
+        let _ = install(&tap);
+    }
+
+    let (taps, rest): (Vec<usize>, Vec<usize>) =
+        (0..packages.len()).partition(|&i| packages[i].package_type == PackageType::Tap);
+    let (serial, parallel): (Vec<usize>, Vec<usize>) =
+        rest.into_iter().partition(|&i| is_serial(&packages[i]));
+
+    if options.jobs <= 1 {
+        for &index in taps.iter().chain(&serial).chain(&parallel) {
+            store(index, install(&packages[index]));
+        }
+        return collect(results);
+    }
+
+    for &index in &taps {
+        store(index, install(&packages[index]));
+    }
+
+    // The serial lane counts as one of the jobs
+    let lanes = usize::from(!serial.is_empty());
+    let workers = (options.jobs - lanes).clamp(1, parallel.len().max(1));
+    let next = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        if !serial.is_empty() {
+            scope.spawn(|| {
+                for &index in &serial {
+                    store(index, install(&packages[index]));
+                }
+            });
+        }
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while let Some(&index) = parallel.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    store(index, install(&packages[index]));
+                }
+            });
+        }
+    });
+
+    let mut results = collect(results);
+
+    // Concurrent brew processes can fail on each other's locks when they
+    // share a dependency, so give lock and unexplained failures one more
+    // serial try
+    if workers + lanes > 1 {
+        for &index in &parallel {
+            if results[index].as_ref().is_err_and(|e| {
+                matches!(e.category(), ErrorCategory::Locked | ErrorCategory::Other)
+            }) {
+                results[index] = install(&packages[index]);
+            }
+        }
+    }
+
+    results
+}
+
+/// Unwrap the per-package result slots.
+fn collect(slots: Vec<Mutex<Option<Result<()>>>>) -> Vec<Result<()>> {
+    slots
+        .into_iter()
+        .map(|slot| slot.into_inner().unwrap().unwrap_or(Ok(())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+    use crate::error::Error;
+    use std::time::Duration;
+
+    /// Backend taking a while per install; `locked` fails on its first
+    /// attempt, `missing` always fails.
+    fn slow_backend() -> MockBackend {
+        MockBackend::default()
+            .with_install_delay(Duration::from_millis(20))
+            .failing("missing", usize::MAX, |name| Error::NotFound {
+                name: name.to_string(),
+            })
+            .failing("locked", 1, |name| Error::CommandFailed {
+                message: format!("brew command failed for {name}"),
+                stderr: "has already locked".to_string(),
+            })
+    }
+
+    #[test]
+    fn test_install_many_bounds_concurrency_and_serializes_casks() {
+        let backend = slow_backend();
+        let stats = backend.stats();
+        let client = Client::with_backend(Box::new(backend));
+
+        let mut packages = vec![Package::tap("homebrew/cask-fonts")];
+        packages.extend((0..6).map(|i| Package::brew(format!("formula{i}"))));
+        packages.extend((0..3).map(|i| Package::cask(format!("cask{i}"))));
+        packages.push(Package::brew("missing"));
+        packages.push(Package::brew("locked"));
+
+        let options = InstallManyOptions {
+            jobs: 3,
+            retry: RetryConfig::no_retry(),
+        };
+        let results = client.install_many(&packages, &options);
+
+        assert_eq!(results.len(), packages.len());
+        // Two formula workers plus the serial cask lane
+        assert!(stats.max_running.load(Ordering::SeqCst) <= 3);
+        assert_eq!(stats.max_cask_running.load(Ordering::SeqCst), 1);
+        assert!(matches!(results[10], Err(Error::NotFound { .. })));
+        // Failed once, succeeded on the serial retry
+        assert!(results[11].is_ok());
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 11);
+    }
+
+    #[test]
+    fn test_install_many_reports_progress() {
+        /// Counts finished packages, failed ones separately.
+        #[derive(Default)]
+        struct Counter {
+            started: AtomicUsize,
+            finished: AtomicUsize,
+            failed: AtomicUsize,
+        }
+
+        impl BrewProgress for Counter {
+            fn started(&self, _name: &str) {
+                self.started.fetch_add(1, Ordering::SeqCst);
+            }
+
+            fn finished(&self, _name: &str, error: Option<&str>) {
+                self.finished.fetch_add(1, Ordering::SeqCst);
+                if error.is_some() {
+                    self.failed.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        }
+
+        let client = Client::with_backend(Box::new(slow_backend()));
+        let packages = vec![
+            Package::brew("git"),
+            Package::cask("firefox"),
+            Package::brew("missing"),
+        ];
+        let counter = Counter::default();
+        client.install_many_with_progress(&packages, &InstallManyOptions::default(), &counter);
+
+        assert_eq!(counter.started.load(Ordering::SeqCst), 3);
+        assert_eq!(counter.finished.load(Ordering::SeqCst), 3);
+        assert_eq!(counter.failed.load(Ordering::SeqCst), 1);
+    }
+}
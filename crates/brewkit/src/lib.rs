@@ -6,6 +6,9 @@
 //! - Parsing and generating Brewfiles
 //! - Installing packages with smart retry logic
 //! - Detecting drift between installed packages and Brewfile
+//! - Inspecting the dependency graph and finding orphaned formulas
+//! - Caching installed package metadata between runs
+//! - Targeting one of several Homebrew installations (Intel and Apple Silicon)
 //! - Managing taps, formulas, casks, mas apps, and VS Code extensions
 //!
 //! ## Example
@@ -55,13 +58,25 @@ pub mod audit;
 pub mod backend;
 pub mod brewfile;
 pub mod bundle;
+pub mod deps;
 pub mod error;
+pub mod install;
+pub mod progress;
+pub mod provider;
 pub mod retry;
 pub mod types;
 
+pub use backend::brew::detect_prefixes;
+pub use backend::cache::InfoCache;
+pub use deps::DependencyGraph;
 pub use error::{Error, ErrorCategory, Result};
+pub use install::InstallManyOptions;
+pub use progress::{BrewProgress, NoProgress};
+pub use provider::PackageProvider;
 pub use types::{
-    AuditResult, Brewfile, BundleResult, InstalledPackage, Package, PackageType, RetryConfig,
+    AuditResult, Brewfile, BundleResult, CaptureOptions, CaskOptions, CleanupOptions,
+    CleanupResult, InstalledPackage, OutdatedPackage, Package, PackageType, RetryConfig,
+    UninstallOptions,
 };
 
 use backend::{Backend, brew::BrewBackend};
@@ -74,6 +89,8 @@ use std::path::Path;
 /// and detecting drift.
 pub struct Client {
     backend: Box<dyn Backend>,
+    providers: Vec<Box<dyn PackageProvider>>,
+    tags: Vec<String>,
 }
 
 impl Client {
@@ -82,14 +99,82 @@ impl Client {
     /// Returns an error if Homebrew is not installed.
     pub fn new() -> Result<Self> {
         let backend = BrewBackend::new()?;
-        Ok(Self {
-            backend: Box::new(backend),
-        })
+        Ok(Self::with_backend(Box::new(backend)))
+    }
+
+    /// Create a client with the default backend, caching installed package
+    /// metadata in `cache`.
+    ///
+    /// Returns an error if Homebrew is not installed.
+    pub fn with_info_cache(cache: InfoCache) -> Result<Self> {
+        let backend = BrewBackend::with_info_cache(cache)?;
+        Ok(Self::with_backend(Box::new(backend)))
+    }
+
+    /// Create a client for the Homebrew installed in `prefix`, for machines
+    /// with more than one (see [`detect_prefixes`]).
+    ///
+    /// Each installation has its own packages, so give each its own cache.
+    /// Returns an error if there is no brew in that prefix.
+    pub fn with_prefix(prefix: &Path, cache: InfoCache) -> Result<Self> {
+        let backend = BrewBackend::with_prefix(prefix, cache)?;
+        Ok(Self::with_backend(Box::new(backend)))
     }
 
     /// Create a client with a custom backend (useful for testing).
     pub fn with_backend(backend: Box<dyn Backend>) -> Self {
-        Self { backend }
+        Self {
+            backend,
+            providers: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    /// Set the tags of this machine (e.g. its hostname and profile).
+    ///
+    /// Audits and bundles only consider Brewfile packages that apply to
+    /// these tags; see [`Brewfile::for_tags`]. Without tags, packages in
+    /// conditional blocks are skipped.
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Tags of this machine.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Register a provider for a custom package kind.
+    ///
+    /// Its directive is then parsed from Brewfiles, captured, audited, and
+    /// installed alongside the built-in package types. A provider registered
+    /// later for the same directive replaces the earlier one.
+    pub fn with_provider(mut self, provider: impl PackageProvider + 'static) -> Self {
+        self.providers
+            .retain(|p| p.directive() != provider.directive());
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Directives of the registered providers.
+    pub fn custom_directives(&self) -> Vec<&str> {
+        self.providers.iter().map(|p| p.directive()).collect()
+    }
+
+    /// Find the provider for a custom package.
+    fn provider_for(&self, package: &Package) -> Result<&dyn PackageProvider> {
+        self.providers
+            .iter()
+            .find(|p| p.directive() == package.directive())
+            .map(AsRef::as_ref)
+            .ok_or_else(|| {
+                Error::Other(format!(
+                    "no provider registered for {} \"{}\"",
+                    package.directive(),
+                    package.name
+                ))
+            })
     }
 
     /// Check if Homebrew is available.
@@ -103,13 +188,69 @@ impl Client {
 
     /// Install a package.
     pub fn install(&self, package: &Package) -> Result<()> {
-        self.backend.install(package)
+        self.install_streaming(package, &NoProgress)
+    }
+
+    /// Install a package, reporting it and its output to `progress`.
+    pub fn install_with_progress(
+        &self,
+        package: &Package,
+        progress: &dyn BrewProgress,
+    ) -> Result<()> {
+        progress::report(progress, &package.name, || {
+            self.install_streaming(package, progress)
+        })
+    }
+
+    /// Install a package, streaming its output without started/finished events.
+    pub(crate) fn install_streaming(
+        &self,
+        package: &Package,
+        progress: &dyn BrewProgress,
+    ) -> Result<()> {
+        if package.package_type == PackageType::Custom {
+            return self.provider_for(package)?.install(package);
+        }
+        if let Some(tap) = package.implied_tap()
+            && !self.has_tap(tap)?
+        {
+            self.backend
+                .install_with_progress(&Package::tap(tap), progress)?;
+        }
+        self.backend.install_with_progress(package, progress)
+    }
+
+    /// Taps that installing `packages` would add first.
+    ///
+    /// Namespaced formulas and casks (`owner/tap/name`) need their tap, which
+    /// [`install`](Self::install) adds automatically when it is missing.
+    /// Taps listed in `packages` themselves are not repeated.
+    pub fn missing_taps(&self, packages: &[Package]) -> Result<Vec<Package>> {
+        let mut taps: Vec<Package> = Vec::new();
+        for tap in packages.iter().filter_map(Package::implied_tap) {
+            let listed = |p: &Package| {
+                p.package_type == PackageType::Tap && p.name.eq_ignore_ascii_case(tap)
+            };
+            if !packages.iter().any(listed) && !taps.iter().any(listed) && !self.has_tap(tap)? {
+                taps.push(Package::tap(tap));
+            }
+        }
+        Ok(taps)
+    }
+
+    /// Check if a tap is installed.
+    fn has_tap(&self, tap: &str) -> Result<bool> {
+        Ok(self
+            .backend
+            .list_taps()?
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(tap)))
     }
 
     /// Install a package with retry logic.
     pub fn install_with_retry(&self, package: &Package, config: &RetryConfig) -> Result<()> {
         retry::with_retry(config, Some(&retry::PrintCallback), || {
-            self.backend.install(package)
+            self.install(package)
         })
     }
 
@@ -120,16 +261,47 @@ impl Client {
         config: &RetryConfig,
         callback: &dyn retry::RetryCallback,
     ) -> Result<()> {
-        retry::with_retry(config, Some(callback), || self.backend.install(package))
+        retry::with_retry(config, Some(callback), || self.install(package))
+    }
+
+    /// Install many packages concurrently.
+    ///
+    /// Returns one result per package, in the order given. See
+    /// [`install`](Self::install) for how packages are scheduled.
+    pub fn install_many(
+        &self,
+        packages: &[Package],
+        options: &InstallManyOptions,
+    ) -> Vec<Result<()>> {
+        self.install_many_with_progress(packages, options, &NoProgress)
+    }
+
+    /// Install many packages concurrently, reporting each to `progress`.
+    pub fn install_many_with_progress(
+        &self,
+        packages: &[Package],
+        options: &InstallManyOptions,
+        progress: &dyn BrewProgress,
+    ) -> Vec<Result<()>> {
+        install::install_many(self, packages, options, progress)
     }
 
     /// Uninstall a package.
-    pub fn uninstall(&self, package: &Package) -> Result<()> {
-        self.backend.uninstall(package)
+    ///
+    /// With [`zap`](UninstallOptions::zap), casks also leave no preferences
+    /// or caches behind. Options don't apply to custom packages.
+    pub fn uninstall(&self, package: &Package, options: &UninstallOptions) -> Result<()> {
+        if package.package_type == PackageType::Custom {
+            return self.provider_for(package)?.uninstall(package);
+        }
+        self.backend.uninstall(package, options)
     }
 
     /// Check if a package is installed.
     pub fn is_installed(&self, package: &Package) -> Result<bool> {
+        if package.package_type == PackageType::Custom {
+            return self.provider_for(package)?.is_installed(package);
+        }
         self.backend.is_installed(package)
     }
 
@@ -148,12 +320,74 @@ impl Client {
         self.backend.upgrade(package)
     }
 
+    /// List formulas and casks with newer versions available.
+    ///
+    /// Run [`update`](Self::update) first for up-to-date results.
+    pub fn outdated(&self) -> Result<Vec<OutdatedPackage>> {
+        self.backend.outdated()
+    }
+
+    /// Fetch installed package metadata again, bypassing the cache.
+    ///
+    /// Needed after packages were changed outside this client, e.g. by
+    /// running `brew` directly.
+    pub fn refresh_info(&self) -> Result<()> {
+        self.backend.refresh_info()
+    }
+
+    /// Remove unneeded dependencies, old versions and cached downloads.
+    ///
+    /// Wraps `brew autoremove` (unless disabled in `options`) and
+    /// `brew cleanup`; with `options.dry_run` nothing is removed and the
+    /// result lists what would be.
+    pub fn cleanup(&self, options: &CleanupOptions) -> Result<CleanupResult> {
+        self.backend.cleanup(options)
+    }
+
+    /// Build the dependency graph of installed formulas.
+    ///
+    /// See [`DependencyGraph`] for leaf, orphan and reverse dependency queries.
+    pub fn dependency_graph(&self) -> Result<DependencyGraph> {
+        self.backend.dependency_graph()
+    }
+
+    /// Upgrade the outdated packages accepted by `filter`, one at a time.
+    ///
+    /// Pinned formulas are reported as skipped; a failed upgrade is recorded
+    /// and the remaining packages are still upgraded.
+    pub fn upgrade_outdated(
+        &self,
+        filter: impl Fn(&OutdatedPackage) -> bool,
+    ) -> Result<BundleResult> {
+        let mut result = BundleResult::default();
+        for pkg in self.outdated()?.into_iter().filter(|p| filter(p)) {
+            if pkg.pinned {
+                result.skipped.push(pkg.name);
+                continue;
+            }
+            match self.upgrade(Some(&pkg.to_package())) {
+                Ok(()) => result.upgraded.push(pkg.name),
+                Err(e) => result.failed.push((pkg.name, e.to_string())),
+            }
+        }
+        Ok(result)
+    }
+
     // =========================================================================
     // List Operations
     // =========================================================================
 
     /// List all installed packages of a given type.
+    ///
+    /// [`PackageType::Custom`] lists the packages of every registered provider.
     pub fn list_installed(&self, package_type: PackageType) -> Result<Vec<InstalledPackage>> {
+        if package_type == PackageType::Custom {
+            let mut installed = Vec::new();
+            for provider in &self.providers {
+                installed.extend(provider::installed_packages(provider.as_ref())?);
+            }
+            return Ok(installed);
+        }
         self.backend.list_installed(package_type)
     }
 
@@ -177,17 +411,24 @@ impl Client {
     // =========================================================================
 
     /// Parse a Brewfile from a path.
+    ///
+    /// Entries for registered providers are kept as custom packages.
     pub fn parse_brewfile(&self, path: &Path) -> Result<Brewfile> {
-        brewfile::parse_file(path)
+        brewfile::parse_file_with(path, &self.custom_directives())
     }
 
     /// Parse a Brewfile from a string.
     pub fn parse_brewfile_string(&self, content: &str) -> Result<Brewfile> {
-        brewfile::parse_string(content)
+        brewfile::parse_string_with(content, &self.custom_directives())
     }
 
     /// Generate a Brewfile from installed packages.
     pub fn capture_brewfile(&self) -> Result<Brewfile> {
+        self.capture_brewfile_with(&CaptureOptions::default())
+    }
+
+    /// Generate a Brewfile from installed packages, with options.
+    pub fn capture_brewfile_with(&self, options: &CaptureOptions) -> Result<Brewfile> {
         let mut brewfile = Brewfile::new();
 
         // Taps
@@ -195,16 +436,48 @@ impl Client {
             brewfile.add(Package::tap(tap));
         }
 
-        // Formulas (only explicitly installed)
-        for pkg in self.backend.list_formulas()? {
-            if pkg.installed_on_request {
-                brewfile.add(Package::brew(&pkg.name).with_version(&pkg.version));
+        // Formulas (only explicitly installed) and casks, with third-party
+        // ones namespaced by their tap so the Brewfile can install them
+        let formulas = self.backend.list_formulas()?;
+        let requested = formulas.into_iter().filter(|pkg| pkg.installed_on_request);
+        for pkg in requested.chain(self.backend.list_casks()?) {
+            let mut package = pkg.to_package();
+            if options.descriptions {
+                package.description = pkg.description;
             }
+            if let Some(tap) = package.implied_tap()
+                && !brewfile
+                    .taps()
+                    .iter()
+                    .any(|t| t.name.eq_ignore_ascii_case(tap))
+            {
+                brewfile.add(Package::tap(tap));
+            }
+            brewfile.add(package);
         }
 
-        // Casks
-        for pkg in self.backend.list_casks()? {
-            brewfile.add(Package::cask(&pkg.name).with_version(&pkg.version));
+        // Mac App Store apps and VS Code extensions (mas and code are
+        // optional, so skip them if the CLI is missing)
+        for pkg in self
+            .backend
+            .list_installed(PackageType::Mas)
+            .unwrap_or_default()
+        {
+            if pkg.id.is_some() {
+                brewfile.add(pkg.to_package());
+            }
+        }
+        for pkg in self
+            .backend
+            .list_installed(PackageType::Vscode)
+            .unwrap_or_default()
+        {
+            brewfile.add(pkg.to_package());
+        }
+
+        // Registered providers
+        for pkg in self.list_installed(PackageType::Custom)? {
+            brewfile.add(pkg.to_package());
         }
 
         Ok(brewfile)
@@ -216,14 +489,72 @@ impl Client {
             include_versions: true,
             group_by_type: true,
             sort_packages: true,
+            ..Default::default()
         };
-        brewfile::write_file(brewfile, path, &options)?;
+        self.write_brewfile_with(brewfile, path, &options)
+    }
+
+    /// Write a Brewfile to a path with custom formatting.
+    pub fn write_brewfile_with(
+        &self,
+        brewfile: &Brewfile,
+        path: &Path,
+        options: &brewfile::WriteOptions,
+    ) -> Result<()> {
+        brewfile::write_file(brewfile, path, options)?;
         Ok(())
     }
 
     /// Run `brew bundle` with a Brewfile.
+    ///
+    /// `brew bundle` knows nothing about conditional blocks, so a Brewfile
+    /// with any is filtered for this machine's tags into a temporary file
+    /// first.
     pub fn bundle(&self, brewfile_path: &Path) -> Result<BundleResult> {
-        self.backend.bundle(brewfile_path, true)
+        self.bundle_with_progress(brewfile_path, &NoProgress)
+    }
+
+    /// Run `brew bundle`, reporting each package and output line to `progress`.
+    pub fn bundle_with_progress(
+        &self,
+        brewfile_path: &Path,
+        progress: &dyn BrewProgress,
+    ) -> Result<BundleResult> {
+        self.with_tagged_brewfile(brewfile_path, |path| {
+            self.backend.bundle_with_progress(path, true, progress)
+        })
+    }
+
+    /// List Brewfile entries that are not installed, without installing
+    /// anything.
+    ///
+    /// Much cheaper than an [`audit`](Self::audit): `brew bundle check`
+    /// only looks at what the Brewfile declares, and reports neither
+    /// untracked packages nor version drift. Like [`bundle`](Self::bundle),
+    /// only entries for this machine's tags are checked.
+    pub fn bundle_check(&self, brewfile_path: &Path) -> Result<Vec<Package>> {
+        self.with_tagged_brewfile(brewfile_path, |path| self.backend.bundle_check(path))
+    }
+
+    /// Run `op` on a Brewfile `brew bundle` can read: the file itself, or a
+    /// temporary copy filtered for this machine's tags if it has conditional
+    /// blocks.
+    fn with_tagged_brewfile<T>(
+        &self,
+        brewfile_path: &Path,
+        op: impl FnOnce(&Path) -> Result<T>,
+    ) -> Result<T> {
+        let brewfile = self.parse_brewfile(brewfile_path)?;
+        if !brewfile.has_conditions() {
+            return op(brewfile_path);
+        }
+
+        let temp_path =
+            std::env::temp_dir().join(format!("brewkit-{}.Brewfile", std::process::id()));
+        self.write_brewfile(&brewfile.for_tags(&self.tags), &temp_path)?;
+        let result = op(&temp_path);
+        let _ = std::fs::remove_file(&temp_path);
+        result
     }
 
     // =========================================================================
@@ -236,23 +567,80 @@ impl Client {
     /// - Packages installed but not in Brewfile (untracked)
     /// - Packages in Brewfile but not installed (missing)
     /// - Packages with version mismatches
+    ///
+    /// Packages of registered providers are audited as well. Packages that
+    /// don't apply to this machine's [`tags`](Self::with_tags) aren't
+    /// reported missing, but still count as tracked when installed.
     pub fn audit(&self, brewfile: &Brewfile) -> Result<AuditResult> {
-        audit::audit(self.backend.as_ref(), brewfile)
+        let mut result = audit::audit(self.backend.as_ref(), brewfile)?;
+        for provider in &self.providers {
+            audit::audit_provider(provider.as_ref(), brewfile, &mut result)?;
+        }
+        audit::restrict_to_tags(&mut result, &self.tags);
+        Ok(result)
+    }
+
+    /// Audit only the packages in the given groups.
+    ///
+    /// See [`audit::audit_groups`] for how untracked packages are handled.
+    pub fn audit_groups(
+        &self,
+        brewfile: &Brewfile,
+        groups: &[impl AsRef<str>],
+    ) -> Result<AuditResult> {
+        let mut result = self.audit(brewfile)?;
+        audit::restrict_to_groups(&mut result, groups);
+        Ok(result)
+    }
+
+    /// Audit and serialize the result as pretty-printed JSON.
+    ///
+    /// With `groups`, only those groups are audited as in
+    /// [`audit_groups`](Self::audit_groups). Meant for CI jobs that fail on
+    /// drift, e.g. when untracked packages show up on a managed machine.
+    pub fn audit_json(&self, brewfile: &Brewfile, groups: &[impl AsRef<str>]) -> Result<String> {
+        let result = if groups.is_empty() {
+            self.audit(brewfile)?
+        } else {
+            self.audit_groups(brewfile, groups)?
+        };
+        Ok(serde_json::to_string_pretty(&result)?)
     }
 
     /// Audit with custom options.
+    ///
+    /// Registered providers are included when `options.package_types` is
+    /// empty or contains [`PackageType::Custom`].
     pub fn audit_with_options(
         &self,
         brewfile: &Brewfile,
         options: &audit::AuditOptions,
     ) -> Result<AuditResult> {
-        audit::audit_with_options(self.backend.as_ref(), brewfile, options)
+        let mut result = audit::audit_with_options(self.backend.as_ref(), brewfile, options)?;
+
+        if options.package_types.is_empty() || options.package_types.contains(&PackageType::Custom)
+        {
+            let mut custom = AuditResult::default();
+            for provider in &self.providers {
+                audit::audit_provider(provider.as_ref(), brewfile, &mut custom)?;
+            }
+            if !options.groups.is_empty() {
+                audit::restrict_to_groups(&mut custom, &options.groups);
+            }
+            result.untracked.extend(custom.untracked);
+            result.missing.extend(custom.missing);
+            result.mismatched.extend(custom.mismatched);
+        }
+        audit::restrict_to_tags(&mut result, &self.tags);
+
+        Ok(result)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::mock::{MockBackend, installed};
 
     // Note: Most tests require Homebrew to be installed.
     // These are integration tests that would run in CI with brew available.
@@ -287,4 +675,234 @@ cask "firefox"
         assert_eq!(brewfile.brews().len(), 1);
         assert_eq!(brewfile.casks().len(), 1);
     }
+
+    #[test]
+    fn test_upgrade_outdated() {
+        let outdated = |name: &str, package_type, pinned| OutdatedPackage {
+            name: name.to_string(),
+            package_type,
+            installed_version: "1.0".to_string(),
+            latest_version: "2.0".to_string(),
+            pinned,
+        };
+        let backend = MockBackend::default()
+            .with_outdated(vec![
+                outdated("git", PackageType::Brew, false),
+                outdated("node", PackageType::Brew, true),
+                outdated("firefox", PackageType::Cask, false),
+                outdated("slack", PackageType::Cask, false),
+            ])
+            .failing("slack", usize::MAX, |_| {
+                Error::Other("download failed".to_string())
+            });
+        let client = Client::with_backend(Box::new(backend));
+        assert_eq!(client.outdated().unwrap().len(), 4);
+
+        let result = client
+            .upgrade_outdated(|p| p.package_type == PackageType::Brew)
+            .unwrap();
+        assert_eq!(result.upgraded, vec!["git"]);
+        assert_eq!(result.skipped, vec!["node"]);
+
+        let result = client
+            .upgrade_outdated(|p| p.package_type == PackageType::Cask)
+            .unwrap();
+        assert_eq!(result.upgraded, vec!["firefox"]);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].0, "slack");
+    }
+
+    /// Backend where `owner/tools/fd` is installed from a third-party tap.
+    fn tap_backend() -> MockBackend {
+        MockBackend::default().with_installed([
+            installed("homebrew/core", PackageType::Tap),
+            InstalledPackage {
+                tap: Some("owner/tools".to_string()),
+                ..installed("fd", PackageType::Brew)
+            },
+        ])
+    }
+
+    #[test]
+    fn test_install_adds_implied_tap() {
+        let backend = tap_backend();
+        let installs = backend.installs();
+        let client = Client::with_backend(Box::new(backend));
+
+        let packages = [
+            Package::brew("owner/tools/ripgrep-all"),
+            Package::cask("owner/tools/viewer"),
+            Package::tap("other/tap"),
+            Package::brew("other/tap/jq"),
+            Package::brew("homebrew/core/git"),
+        ];
+        let missing = client.missing_taps(&packages).unwrap();
+        assert_eq!(missing, vec![Package::tap("owner/tools")]);
+
+        client.install(&packages[0]).unwrap();
+        client.install(&packages[1]).unwrap();
+        assert_eq!(
+            *installs.lock().unwrap(),
+            vec![
+                "owner/tools",
+                "owner/tools/ripgrep-all",
+                "owner/tools/viewer"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capture_records_implied_tap() {
+        let client = Client::with_backend(Box::new(tap_backend()));
+        let brewfile = client.capture_brewfile().unwrap();
+
+        let names: Vec<&str> = brewfile.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["homebrew/core", "owner/tools", "owner/tools/fd"]
+        );
+        // The namespaced entry still matches the installed formula; only the
+        // tap, which was removed after installing, is reported
+        let audit = client.audit(&brewfile).unwrap();
+        assert_eq!(audit.missing, vec![Package::tap("owner/tools")]);
+        assert!(audit.untracked.is_empty());
+    }
+
+    /// pipx provider with `httpie` and `ruff` installed, recording installs.
+    #[derive(Default)]
+    struct Pipx {
+        installs: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl PackageProvider for Pipx {
+        fn directive(&self) -> &'static str {
+            "pipx"
+        }
+
+        fn install(&self, package: &Package) -> Result<()> {
+            self.installs.lock().unwrap().push(package.name.clone());
+            Ok(())
+        }
+
+        fn list_installed(&self) -> Result<Vec<InstalledPackage>> {
+            Ok(["httpie", "ruff"]
+                .into_iter()
+                .map(|name| installed(name, PackageType::Custom))
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_audit_json() {
+        let client = Client::with_backend(Box::<MockBackend>::default());
+        let brewfile = client
+            .parse_brewfile_string("# group: base\nbrew \"git\"\n# endgroup\ncask \"firefox\"\n")
+            .unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&client.audit_json(&brewfile, &[] as &[&str]).unwrap()).unwrap();
+        assert_eq!(json["missing"].as_array().unwrap().len(), 2);
+        assert_eq!(json["missing"][0]["name"], "git");
+        assert_eq!(json["missing"][0]["package_type"], "brew");
+        assert_eq!(json["missing"][0]["group"], "base");
+        assert!(json["untracked"].as_array().unwrap().is_empty());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&client.audit_json(&brewfile, &["base"]).unwrap()).unwrap();
+        assert_eq!(json["missing"].as_array().unwrap().len(), 1);
+
+        let result: AuditResult =
+            serde_json::from_str(&client.audit_json(&brewfile, &["base"]).unwrap()).unwrap();
+        assert_eq!(
+            result.missing,
+            vec![Package::brew("git").with_group("base")]
+        );
+    }
+
+    #[test]
+    fn test_audit_evaluates_tags() {
+        let content = "brew \"git\"\n# only: work\ncask \"slack\"\n# endonly\n";
+        let client = Client::with_backend(Box::<MockBackend>::default());
+        let brewfile = client.parse_brewfile_string(content).unwrap();
+        assert_eq!(client.audit(&brewfile).unwrap().missing.len(), 1);
+
+        let client = client.with_tags(["work"]);
+        assert_eq!(client.audit(&brewfile).unwrap().missing.len(), 2);
+    }
+
+    #[test]
+    fn test_audit_tracks_packages_of_other_tags() {
+        let content = "brew \"git\"\n# only: work\ncask \"slack\"\n# endonly\n";
+        let backend =
+            MockBackend::default().with_installed([installed("slack", PackageType::Cask)]);
+        let client = Client::with_backend(Box::new(backend));
+        let brewfile = client.parse_brewfile_string(content).unwrap();
+
+        let result = client.audit(&brewfile).unwrap();
+        assert!(result.untracked.is_empty());
+        assert_eq!(result.missing, vec![Package::brew("git")]);
+
+        let result = client
+            .audit_with_options(&brewfile, &audit::AuditOptions::default())
+            .unwrap();
+        assert!(result.untracked.is_empty());
+        assert_eq!(result.missing, vec![Package::brew("git")]);
+    }
+
+    #[test]
+    fn test_audit_with_options_restricts_mismatches_to_groups() {
+        let content = "# group: cli\nbrew \"git\" # 2.40.0\n# endgroup\n\
+                       # group: gui\ncask \"firefox\" # 120.0\n# endgroup\n";
+        let backend = MockBackend::default().with_installed([
+            installed("git", PackageType::Brew),
+            installed("firefox", PackageType::Cask),
+        ]);
+        let client = Client::with_backend(Box::new(backend));
+        let brewfile = client.parse_brewfile_string(content).unwrap();
+
+        let options = audit::AuditOptions {
+            groups: vec!["gui".to_string()],
+            ..Default::default()
+        };
+        let names = |result: &AuditResult| -> Vec<String> {
+            result
+                .mismatched
+                .iter()
+                .map(|(p, _)| p.name.clone())
+                .collect()
+        };
+        let result = client.audit_with_options(&brewfile, &options).unwrap();
+        assert_eq!(names(&result), vec!["firefox"]);
+        let result = client.audit_groups(&brewfile, &["gui"]).unwrap();
+        assert_eq!(names(&result), vec!["firefox"]);
+    }
+
+    #[test]
+    fn test_custom_provider() {
+        let client =
+            Client::with_backend(Box::<MockBackend>::default()).with_provider(Pipx::default());
+        let brewfile = client
+            .parse_brewfile_string("pipx \"httpie\"\npipx \"black\"\n")
+            .unwrap();
+        assert_eq!(brewfile.custom_packages("pipx").len(), 2);
+
+        let result = client.audit(&brewfile).unwrap();
+        let untracked: Vec<_> = result.untracked.iter().map(|p| p.name.as_str()).collect();
+        let missing: Vec<_> = result.missing.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(untracked, vec!["ruff"]);
+        assert_eq!(missing, vec!["black"]);
+        assert_eq!(result.untracked[0].provider.as_deref(), Some("pipx"));
+
+        client.install(&Package::custom("pipx", "black")).unwrap();
+        assert!(
+            client
+                .is_installed(&Package::custom("pipx", "HTTPie"))
+                .unwrap()
+        );
+        assert!(client.install(&Package::custom("go", "gopls")).is_err());
+
+        let captured = client.capture_brewfile().unwrap();
+        let output = brewfile::write_string(&captured, &brewfile::WriteOptions::default());
+        assert_eq!(output, "pipx \"httpie\"\npipx \"ruff\"\n");
+    }
 }
@@ -0,0 +1,44 @@
+//! Live feedback from long-running installs.
+//!
+//! `brew install` and `brew bundle` can run for minutes. Passing a
+//! [`BrewProgress`] to [`Client::install_with_progress`](crate::Client::install_with_progress),
+//! [`Client::install_many_with_progress`](crate::Client::install_many_with_progress)
+//! or [`Client::bundle_with_progress`](crate::Client::bundle_with_progress)
+//! reports each package as it starts and finishes, along with every line
+//! `brew` prints while it works.
+
+use crate::error::Result;
+
+/// Receiver of progress events.
+///
+/// All methods default to doing nothing, so implementations only override
+/// the events they care about. Events may arrive from several threads at
+/// once during [`install_many_with_progress`](crate::Client::install_many_with_progress).
+pub trait BrewProgress: Sync {
+    /// A package started installing.
+    fn started(&self, _name: &str) {}
+
+    /// A package finished, with the error message if it failed.
+    fn finished(&self, _name: &str, _error: Option<&str>) {}
+
+    /// A line of `brew` output.
+    fn output(&self, _line: &str) {}
+}
+
+/// Progress receiver that ignores every event.
+pub struct NoProgress;
+
+impl BrewProgress for NoProgress {}
+
+/// Run `op` for a package, reporting it as started and finished.
+pub(crate) fn report<T>(
+    progress: &dyn BrewProgress,
+    name: &str,
+    op: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    progress.started(name);
+    let result = op();
+    let error = result.as_ref().err().map(ToString::to_string);
+    progress.finished(name, error.as_deref());
+    result
+}
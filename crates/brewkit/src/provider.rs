@@ -0,0 +1,61 @@
+//! Extension point for package kinds Homebrew doesn't know about.
+//!
+//! A [`PackageProvider`] handles one Brewfile directive (e.g. `whalebrew`,
+//! `go`, `pipx`). Registered on a [`Client`](crate::Client) with
+//! [`with_provider`](crate::Client::with_provider), its entries are parsed
+//! from and written to Brewfiles, audited, and installed like built-in
+//! packages:
+//!
+//! ```text
+//! whalebrew "whalebrew/wget"
+//! pipx "httpie" # 3.2.2
+//! ```
+//!
+//! Provider packages use [`PackageType::Custom`](crate::PackageType::Custom)
+//! with the directive stored in [`Package::provider`](crate::Package::provider).
+
+use crate::error::{Error, Result};
+use crate::types::{InstalledPackage, Package, PackageType};
+
+/// A custom package kind, identified by its Brewfile directive.
+pub trait PackageProvider: Send + Sync {
+    /// Brewfile directive handled by this provider (e.g. `"pipx"`).
+    fn directive(&self) -> &str;
+
+    /// Install a package.
+    fn install(&self, package: &Package) -> Result<()>;
+
+    /// List installed packages.
+    ///
+    /// The package type and provider of the returned entries are filled in
+    /// by the client, so implementations only need name and version.
+    fn list_installed(&self) -> Result<Vec<InstalledPackage>>;
+
+    /// Uninstall a package.
+    fn uninstall(&self, package: &Package) -> Result<()> {
+        Err(Error::Other(format!(
+            "{} uninstall not supported for {}",
+            self.directive(),
+            package.name
+        )))
+    }
+
+    /// Check if a package is installed.
+    fn is_installed(&self, package: &Package) -> Result<bool> {
+        let name = package.name.to_lowercase();
+        Ok(self
+            .list_installed()?
+            .iter()
+            .any(|p| p.name.to_lowercase() == name))
+    }
+}
+
+/// List a provider's packages, tagged as custom packages of that provider.
+pub(crate) fn installed_packages(provider: &dyn PackageProvider) -> Result<Vec<InstalledPackage>> {
+    let mut installed = provider.list_installed()?;
+    for pkg in &mut installed {
+        pkg.package_type = PackageType::Custom;
+        pkg.provider = Some(provider.directive().to_string());
+    }
+    Ok(installed)
+}
@@ -1,7 +1,7 @@
 //! Core types for Homebrew package management.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -19,6 +19,8 @@ pub enum PackageType {
     Mas,
     /// VS Code extension
     Vscode,
+    /// Package handled by a registered [`PackageProvider`](crate::provider::PackageProvider)
+    Custom,
 }
 
 impl PackageType {
@@ -30,10 +32,14 @@ impl PackageType {
             PackageType::Cask => "cask",
             PackageType::Mas => "mas",
             PackageType::Vscode => "vscode",
+            PackageType::Custom => "custom",
         }
     }
 
     /// Parse a package type from a Brewfile directive.
+    ///
+    /// Custom directives are only known to a [`Client`](crate::Client) with
+    /// the matching provider, so they return `None` here.
     pub fn from_directive(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "tap" => Some(PackageType::Tap),
@@ -63,6 +69,92 @@ pub struct Package {
     pub version: Option<String>,
     /// Additional options (e.g., restart_service: :changed)
     pub options: HashMap<String, String>,
+    /// Group this package belongs to (e.g., "base", "gui", "work")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Directive of the provider handling a [`PackageType::Custom`] package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Tags this package is restricted to (empty means everywhere)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub only: Vec<String>,
+    /// Install and upgrade options of a [`PackageType::Cask`] package
+    #[serde(default, skip_serializing_if = "CaskOptions::is_empty")]
+    pub cask: CaskOptions,
+    /// Description, written as a trailing comment (e.g. from `brew desc`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Cask install options.
+///
+/// In a Brewfile these are `args: { ... }` and `greedy:` on a cask line:
+///
+/// ```text
+/// cask "firefox", args: { appdir: "~/Applications", no_quarantine: true }, greedy: true
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaskOptions {
+    /// Directory to install the app into instead of `/Applications`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub appdir: Option<String>,
+    /// Skip Gatekeeper's quarantine of the downloaded app
+    #[serde(default)]
+    pub no_quarantine: bool,
+    /// Upgrade even casks that update themselves
+    #[serde(default)]
+    pub greedy: bool,
+    /// Other `args:` passed to `brew install` (e.g. `fontdir`), `"true"` for
+    /// plain flags
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub args: BTreeMap<String, String>,
+}
+
+impl CaskOptions {
+    /// Check if no option is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Entries of the Brewfile `args:` hash, in writing order.
+    pub fn brewfile_args(&self) -> Vec<(&str, &str)> {
+        let mut args = Vec::new();
+        if let Some(appdir) = &self.appdir {
+            args.push(("appdir", appdir.as_str()));
+        }
+        if self.no_quarantine {
+            args.push(("no_quarantine", "true"));
+        }
+        args.extend(self.args.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        args
+    }
+
+    /// Set an `args:` entry from a Brewfile.
+    pub fn set_arg(&mut self, key: &str, value: &str) {
+        match key {
+            "appdir" => self.appdir = Some(value.to_string()),
+            "no_quarantine" => self.no_quarantine = value == "true",
+            _ => {
+                self.args.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+
+    /// Command-line flags for `brew install --cask`.
+    pub fn install_flags(&self) -> Vec<String> {
+        self.brewfile_args()
+            .into_iter()
+            .filter(|(_, value)| *value != "false")
+            .map(|(key, value)| {
+                let flag = key.replace('_', "-");
+                if value == "true" {
+                    format!("--{flag}")
+                } else {
+                    format!("--{flag}={value}")
+                }
+            })
+            .collect()
+    }
 }
 
 impl Package {
@@ -73,6 +165,11 @@ impl Package {
             package_type,
             version: None,
             options: HashMap::new(),
+            group: None,
+            provider: None,
+            only: Vec::new(),
+            cask: CaskOptions::default(),
+            description: None,
         }
     }
 
@@ -103,6 +200,21 @@ impl Package {
         Self::new(name, PackageType::Vscode)
     }
 
+    /// Create a package handled by the provider for `directive`.
+    pub fn custom(directive: impl Into<String>, name: impl Into<String>) -> Self {
+        let mut pkg = Self::new(name, PackageType::Custom);
+        pkg.provider = Some(directive.into());
+        pkg
+    }
+
+    /// Get the Brewfile directive for this package.
+    pub fn directive(&self) -> &str {
+        match (&self.package_type, &self.provider) {
+            (PackageType::Custom, Some(provider)) => provider,
+            (package_type, _) => package_type.directive(),
+        }
+    }
+
     /// Set the version.
     pub fn with_version(mut self, version: impl Into<String>) -> Self {
         self.version = Some(version.into());
@@ -115,6 +227,66 @@ impl Package {
         self
     }
 
+    /// Set the description.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the cask install options.
+    pub fn with_cask_options(mut self, options: CaskOptions) -> Self {
+        self.cask = options;
+        self
+    }
+
+    /// Set the group.
+    pub fn with_group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Restrict the package to machines with one of the given tags.
+    pub fn with_only(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.only = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Check if this package applies to a machine with the given tags.
+    ///
+    /// Unconditional packages apply everywhere; conditional ones need at
+    /// least one of their tags to be active.
+    pub fn applies_to(&self, tags: &[impl AsRef<str>]) -> bool {
+        self.only.is_empty()
+            || tags
+                .iter()
+                .any(|t| self.only.iter().any(|o| o == t.as_ref()))
+    }
+
+    /// Check if this package belongs to one of the given groups.
+    pub fn in_groups(&self, groups: &[impl AsRef<str>]) -> bool {
+        self.group
+            .as_deref()
+            .is_some_and(|g| groups.iter().any(|s| s.as_ref() == g))
+    }
+
+    /// Tap a namespaced formula or cask comes from (`owner/tap` for
+    /// `owner/tap/name`).
+    pub fn implied_tap(&self) -> Option<&str> {
+        if !matches!(self.package_type, PackageType::Brew | PackageType::Cask) {
+            return None;
+        }
+        let (tap, _) = self.name.rsplit_once('/')?;
+        (tap.matches('/').count() == 1).then_some(tap)
+    }
+
+    /// Name without the tap prefix of a namespaced formula or cask.
+    pub fn short_name(&self) -> &str {
+        match self.implied_tap() {
+            Some(tap) => &self.name[tap.len() + 1..],
+            None => &self.name,
+        }
+    }
+
     /// Get the mas app ID if this is a mas package.
     pub fn mas_id(&self) -> Option<&str> {
         if self.package_type == PackageType::Mas {
@@ -136,6 +308,61 @@ pub struct InstalledPackage {
     pub version: String,
     /// Whether this package was explicitly installed (not a dependency)
     pub installed_on_request: bool,
+    /// Store identifier, for packages identified by more than their name
+    /// (the App Store ID of mas apps)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Directive of the provider for [`PackageType::Custom`] packages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Third-party tap a formula or cask was installed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tap: Option<String>,
+    /// One-line description (`brew desc`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl InstalledPackage {
+    /// Convert back into a Brewfile entry (with the installed version).
+    pub fn to_package(&self) -> Package {
+        let package = match (self.package_type, &self.id, &self.provider) {
+            (PackageType::Mas, Some(id), _) => Package::mas(&self.name, id),
+            (PackageType::Custom, _, Some(provider)) => Package::custom(provider, &self.name),
+            _ => match &self.tap {
+                Some(tap) => Package::new(format!("{tap}/{}", self.name), self.package_type),
+                None => Package::new(&self.name, self.package_type),
+            },
+        };
+        if self.version.is_empty() {
+            package
+        } else {
+            package.with_version(&self.version)
+        }
+    }
+}
+
+/// An installed formula or cask with a newer version available.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutdatedPackage {
+    /// Package name
+    pub name: String,
+    /// Package type ([`PackageType::Brew`] or [`PackageType::Cask`])
+    pub package_type: PackageType,
+    /// Currently installed version
+    pub installed_version: String,
+    /// Latest available version
+    pub latest_version: String,
+    /// Whether the formula is pinned (`brew upgrade` leaves it alone)
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl OutdatedPackage {
+    /// The package to pass to [`Client::upgrade`](crate::Client::upgrade).
+    pub fn to_package(&self) -> Package {
+        Package::new(&self.name, self.package_type)
+    }
 }
 
 /// Configuration for retry logic.
@@ -231,6 +458,12 @@ pub struct AuditResult {
     pub missing: Vec<Package>,
     /// Packages with version mismatch
     pub mismatched: Vec<(Package, InstalledPackage)>,
+    /// Formulas installed only as dependencies that nothing needs anymore
+    /// (what `brew autoremove` would uninstall), in a safe removal order.
+    ///
+    /// Not counted as drift: they are suggestions, not Brewfile mismatches.
+    #[serde(default)]
+    pub removable: Vec<String>,
 }
 
 impl AuditResult {
@@ -240,6 +473,80 @@ impl AuditResult {
     }
 }
 
+/// Options for [`Client::capture_brewfile_with`](crate::Client::capture_brewfile_with).
+#[derive(Debug, Clone, Default)]
+pub struct CaptureOptions {
+    /// Attach each formula's and cask's `brew desc` description
+    pub descriptions: bool,
+}
+
+/// Options for [`Client::cleanup`](crate::Client::cleanup).
+#[derive(Debug, Clone)]
+pub struct CleanupOptions {
+    /// Report what would be removed without removing anything
+    pub dry_run: bool,
+    /// Uninstall dependencies nothing needs anymore (`brew autoremove`)
+    pub autoremove: bool,
+    /// Remove cached downloads older than this many days (`--prune`);
+    /// `None` keeps Homebrew's default
+    pub prune_days: Option<u32>,
+    /// Also remove downloads for the latest versions (`-s`)
+    pub scrub: bool,
+}
+
+impl Default for CleanupOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            autoremove: true,
+            prune_days: None,
+            scrub: false,
+        }
+    }
+}
+
+/// Options for [`Client::uninstall`](crate::Client::uninstall).
+#[derive(Debug, Clone, Default)]
+pub struct UninstallOptions {
+    /// Also remove a cask's preferences, caches and support files (`--zap`)
+    pub zap: bool,
+    /// Uninstall a formula even if other formulas depend on it
+    pub ignore_dependencies: bool,
+}
+
+impl UninstallOptions {
+    /// Command-line flags for `brew uninstall` of a package type.
+    ///
+    /// `zap` only applies to casks and `ignore_dependencies` to formulas.
+    pub fn flags(&self, package_type: PackageType) -> Vec<&'static str> {
+        match package_type {
+            PackageType::Cask if self.zap => vec!["--zap"],
+            PackageType::Brew if self.ignore_dependencies => vec!["--ignore-dependencies"],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Result of a cleanup.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanupResult {
+    /// Whether this was a dry run (nothing was removed)
+    pub dry_run: bool,
+    /// Formulas uninstalled by autoremove
+    pub autoremoved: Vec<String>,
+    /// Old versions and cache files removed
+    pub removed_files: Vec<PathBuf>,
+    /// Bytes reclaimed (or reclaimable, for a dry run)
+    pub bytes_reclaimed: u64,
+}
+
+impl CleanupResult {
+    /// Check if there was nothing to clean up.
+    pub fn is_empty(&self) -> bool {
+        self.autoremoved.is_empty() && self.removed_files.is_empty()
+    }
+}
+
 /// Parsed Brewfile representation.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Brewfile {
@@ -300,6 +607,70 @@ impl Brewfile {
     pub fn vscode_extensions(&self) -> Vec<&Package> {
         self.packages_of_type(PackageType::Vscode)
     }
+
+    /// Get packages handled by the provider for `directive`.
+    pub fn custom_packages(&self, directive: &str) -> Vec<&Package> {
+        self.packages
+            .iter()
+            .filter(|p| p.package_type == PackageType::Custom && p.directive() == directive)
+            .collect()
+    }
+
+    /// Get the names of all groups, in order of first appearance.
+    pub fn groups(&self) -> Vec<&str> {
+        let mut groups: Vec<&str> = Vec::new();
+        for group in self.packages.iter().filter_map(|p| p.group.as_deref()) {
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+        groups
+    }
+
+    /// Get all tags used by conditional blocks, in order of first appearance.
+    pub fn tags(&self) -> Vec<&str> {
+        let mut tags: Vec<&str> = Vec::new();
+        for tag in self.packages.iter().flat_map(|p| &p.only) {
+            if !tags.contains(&tag.as_str()) {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
+
+    /// Check if any package is restricted to some tags.
+    pub fn has_conditions(&self) -> bool {
+        self.packages.iter().any(|p| !p.only.is_empty())
+    }
+
+    /// Create a Brewfile with only the packages that apply to a machine
+    /// with the given tags (e.g. its hostname and profile).
+    pub fn for_tags(&self, tags: &[impl AsRef<str>]) -> Brewfile {
+        Brewfile {
+            path: self.path.clone(),
+            packages: self
+                .packages
+                .iter()
+                .filter(|p| p.applies_to(tags))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Create a Brewfile containing only the packages in the given groups.
+    ///
+    /// Taps are always kept, since packages in any group may depend on them.
+    pub fn select_groups(&self, groups: &[impl AsRef<str>]) -> Brewfile {
+        Brewfile {
+            path: self.path.clone(),
+            packages: self
+                .packages
+                .iter()
+                .filter(|p| p.package_type == PackageType::Tap || p.in_groups(groups))
+                .cloned()
+                .collect(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -391,6 +762,25 @@ mod tests {
         assert_eq!(brewfile.mas_apps().len(), 0);
     }
 
+    #[test]
+    fn test_brewfile_groups() {
+        let mut brewfile = Brewfile::new();
+        brewfile.add(Package::tap("homebrew/cask"));
+        brewfile.add(Package::brew("git").with_group("base"));
+        brewfile.add(Package::cask("firefox").with_group("gui"));
+        brewfile.add(Package::brew("jq").with_group("base"));
+        brewfile.add(Package::brew("curl"));
+
+        assert_eq!(brewfile.groups(), vec!["base", "gui"]);
+
+        let base = brewfile.select_groups(&["base"]);
+        let names: Vec<_> = base.packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["homebrew/cask", "git", "jq"]);
+
+        let none = brewfile.select_groups(&["work"]);
+        assert_eq!(none.packages.len(), 1);
+    }
+
     #[test]
     fn test_audit_result_has_drift() {
         let mut result = AuditResult::default();
@@ -401,7 +791,30 @@ mod tests {
             package_type: PackageType::Brew,
             version: "1.0".to_string(),
             installed_on_request: true,
+            id: None,
+            provider: None,
+            tap: None,
+            description: None,
         });
         assert!(result.has_drift());
     }
+
+    #[test]
+    fn test_uninstall_flags() {
+        let options = UninstallOptions {
+            zap: true,
+            ignore_dependencies: true,
+        };
+        assert_eq!(options.flags(PackageType::Cask), vec!["--zap"]);
+        assert_eq!(
+            options.flags(PackageType::Brew),
+            vec!["--ignore-dependencies"]
+        );
+        assert!(options.flags(PackageType::Tap).is_empty());
+        assert!(
+            UninstallOptions::default()
+                .flags(PackageType::Cask)
+                .is_empty()
+        );
+    }
 }
@@ -0,0 +1,139 @@
+//! Planned actions - what applying a resource would concretely do
+//!
+//! A dry run can't show its results, so resources describe their work
+//! instead through [`Resource::plan`](crate::Resource::plan): the commands
+//! they'd run, the files they'd write, the values they'd set. Diffs carry
+//! them in [`ResourceDiff::planned`](crate::ResourceDiff::planned).
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+/// A single step of applying a resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PlannedAction {
+    /// Run a command
+    Run {
+        program: String,
+        args: Vec<String>,
+        /// Whether it runs with sudo
+        #[serde(default)]
+        sudo: bool,
+    },
+    /// Write a file
+    WriteFile { path: PathBuf },
+    /// Create a symlink at `path` pointing to `target`
+    Symlink { path: PathBuf, target: PathBuf },
+    /// Set a value, e.g. a preference without a command of its own
+    SetValue { key: String, value: String },
+}
+
+impl PlannedAction {
+    /// Run a command
+    pub fn run(program: &str, args: &[&str]) -> Self {
+        Self::Run {
+            program: program.to_string(),
+            args: args.iter().map(ToString::to_string).collect(),
+            sudo: false,
+        }
+    }
+
+    /// Run a command with sudo
+    pub fn run_sudo(program: &str, args: &[&str]) -> Self {
+        Self::Run {
+            program: program.to_string(),
+            args: args.iter().map(ToString::to_string).collect(),
+            sudo: true,
+        }
+    }
+
+    /// Write a file
+    pub fn write_file(path: impl Into<PathBuf>) -> Self {
+        Self::WriteFile { path: path.into() }
+    }
+
+    /// Create a symlink at `path` pointing to `target`
+    pub fn symlink(path: impl Into<PathBuf>, target: impl Into<PathBuf>) -> Self {
+        Self::Symlink {
+            path: path.into(),
+            target: target.into(),
+        }
+    }
+
+    /// Set a value
+    pub fn set_value(key: &str, value: &str) -> Self {
+        Self::SetValue {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for PlannedAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Run {
+                program,
+                args,
+                sudo,
+            } => {
+                if *sudo {
+                    write!(f, "sudo ")?;
+                }
+                write!(f, "{}", quote(program))?;
+                for arg in args {
+                    write!(f, " {}", quote(arg))?;
+                }
+                Ok(())
+            }
+            Self::WriteFile { path } => write!(f, "write {}", path.display()),
+            Self::Symlink { path, target } => {
+                write!(f, "link {} -> {}", path.display(), target.display())
+            }
+            Self::SetValue { key, value } => write!(f, "set {key} = {value}"),
+        }
+    }
+}
+
+/// Quote a command argument for display, if the shell would need it
+fn quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,~".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            PlannedAction::run("brew", &["install", "--formula", "ripgrep"]).to_string(),
+            "brew install --formula ripgrep"
+        );
+        assert_eq!(
+            PlannedAction::run_sudo(
+                "defaults",
+                &["write", "com.apple.dock", "tilesize", "-string", "it's big"]
+            )
+            .to_string(),
+            r"sudo defaults write com.apple.dock tilesize -string 'it'\''s big'"
+        );
+        assert_eq!(
+            PlannedAction::symlink("~/.zshrc", "~/dotfiles/zsh/.zshrc").to_string(),
+            "link ~/.zshrc -> ~/dotfiles/zsh/.zshrc"
+        );
+        assert_eq!(
+            PlannedAction::set_value("autohide", "true").to_string(),
+            "set autohide = true"
+        );
+    }
+}
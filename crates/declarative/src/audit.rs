@@ -0,0 +1,202 @@
+//! Audit log - what was changed on this machine, and when
+//!
+//! Executing with [`ExecuteOptions::audit_log`](crate::ExecuteOptions::audit_log)
+//! appends an [`AuditEntry`] for every resource applied to a JSON lines
+//! file: its state before and after, the result, hashes of the output of the
+//! commands it ran (see [`ApplyContext::run`](crate::ApplyContext::run)),
+//! and when and by whom it was applied. The file is only ever appended to,
+//! so it covers every run.
+//!
+//! The state before comes from the diff computed for the plan; reading the
+//! state after costs another check per resource, so it's only recorded
+//! with [`ExecuteOptions::audit_new_state`](crate::ExecuteOptions::audit_new_state).
+
+use crate::diff::ResourceDiff;
+use crate::types::{ApplyResult, CommandOutput, ResourceState};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+/// One applied resource
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the resource finished applying
+    pub timestamp: DateTime<Utc>,
+    /// User who applied it, if known
+    pub user: Option<String>,
+    pub resource_type: String,
+    pub resource_id: String,
+    /// State before applying, if it could be read
+    pub old_state: Option<ResourceState>,
+    /// State after applying, if it was asked for and could be read
+    pub new_state: Option<ResourceState>,
+    pub result: ApplyResult,
+    /// Hashes of the commands' output, in the order they ran
+    pub outputs: Vec<String>,
+}
+
+/// An append-only JSON lines file of [`AuditEntry`]s
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    file: Mutex<File>,
+    /// Whether to read each resource's state after applying it
+    new_state: bool,
+    /// States from the plan's diffs, by [`cache_key`](crate::cache_key)
+    before: Mutex<HashMap<String, ResourceState>>,
+}
+
+impl AuditLog {
+    /// Open a log for appending, creating it if needed
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            new_state: false,
+            before: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Also record each resource's state after applying it
+    pub fn with_new_state(mut self, new_state: bool) -> Self {
+        self.new_state = new_state;
+        self
+    }
+
+    /// Whether entries record the state after applying
+    pub fn records_new_state(&self) -> bool {
+        self.new_state
+    }
+
+    /// Remember the current states `diffs` found, to record as the states
+    /// before applying without reading them again
+    pub fn remember(&self, diffs: &[ResourceDiff]) {
+        let mut before = self.before.lock().unwrap_or_else(PoisonError::into_inner);
+        for diff in diffs {
+            let key = format!("{}:{}", diff.resource_type, diff.resource_id);
+            before.insert(key, diff.current.clone());
+        }
+    }
+
+    /// A remembered state before applying, by [`cache_key`](crate::cache_key)
+    pub fn state_before(&self, key: &str) -> Option<ResourceState> {
+        self.before
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(key)
+            .cloned()
+    }
+
+    /// File the log is written to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry
+    ///
+    /// Each entry is written with a single write, so entries recorded from
+    /// parallel batches don't interleave.
+    pub fn record(&self, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        self.file
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write_all(line.as_bytes())
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    /// Read every entry of a log; a missing file has none
+    pub fn read(path: &Path) -> Result<Vec<AuditEntry>> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+        let mut entries = Vec::new();
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line).with_context(|| {
+                format!("Invalid entry on line {} of {}", number + 1, path.display())
+            })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+/// Hash of a command's output, stdout then stderr, as hex
+pub fn output_hash(output: &CommandOutput) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&output.stdout);
+    hasher.update(&output.stderr);
+    hasher.finalize().to_hex().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(resource_id: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: Utc::now(),
+            user: Some("alberto".to_string()),
+            resource_type: "brew_formula".to_string(),
+            resource_id: resource_id.to_string(),
+            old_state: Some(ResourceState::Absent),
+            new_state: Some(ResourceState::Present { details: None }),
+            result: ApplyResult::Created,
+            outputs: vec![output_hash(&CommandOutput {
+                stdout: b"Pouring ripgrep".to_vec(),
+                stderr: Vec::new(),
+                success: true,
+            })],
+        }
+    }
+
+    #[test]
+    fn test_log_appends_across_opens() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("audit.jsonl");
+        assert!(AuditLog::read(&path).unwrap().is_empty());
+
+        let first = entry("ripgrep");
+        AuditLog::open(&path).unwrap().record(&first).unwrap();
+        let second = entry("fd");
+        AuditLog::open(&path).unwrap().record(&second).unwrap();
+
+        assert_eq!(AuditLog::read(&path).unwrap(), [first, second]);
+    }
+
+    #[test]
+    fn test_output_hash() {
+        let output = |stdout: &str| CommandOutput {
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: Vec::new(),
+            success: true,
+        };
+        assert_eq!(output_hash(&output("a")), output_hash(&output("a")));
+        assert_ne!(output_hash(&output("a")), output_hash(&output("b")));
+        assert_eq!(output_hash(&output("a")).len(), 64);
+    }
+}
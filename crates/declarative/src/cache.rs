@@ -0,0 +1,145 @@
+//! State cache - memoizes current state checks
+//!
+//! Detecting current state is the slow part of a diff: `brew list`,
+//! `defaults read` and friends, once per resource. A [`StateCache`] keeps
+//! detected states so repeated diffs can skip them, and the executor
+//! invalidates the entries of every resource it applies.
+
+use crate::resource::Resource;
+use crate::types::ResourceState;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Storage for detected resource states
+///
+/// Implement this trait to keep states somewhere other than memory, e.g. on
+/// disk so they survive between runs.
+pub trait StateCache: Send + Sync + fmt::Debug {
+    /// Get a cached state, if present and still fresh
+    fn get(&self, key: &str) -> Option<ResourceState>;
+
+    /// Cache a state
+    fn insert(&self, key: &str, state: ResourceState);
+
+    /// Drop the cached states of several resources at once
+    fn invalidate(&self, keys: &[String]);
+
+    /// Drop all cached states
+    fn clear(&self);
+}
+
+/// Cache key of a resource: its type and ID, as IDs are only unique per type
+pub fn cache_key(resource: &dyn Resource) -> String {
+    format!("{}:{}", resource.resource_type(), resource.id())
+}
+
+/// Get a resource's current state through a cache
+///
+/// Detects and caches the state on a miss. Failed detections aren't cached.
+pub fn cached_state(resource: &dyn Resource, cache: &dyn StateCache) -> Result<ResourceState> {
+    let key = cache_key(resource);
+    if let Some(state) = cache.get(&key) {
+        return Ok(state);
+    }
+    let state = resource.current_state()?;
+    cache.insert(&key, state.clone());
+    Ok(state)
+}
+
+/// In-memory state cache whose entries expire after a TTL
+#[derive(Debug)]
+pub struct MemoryStateCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, ResourceState)>>,
+}
+
+impl MemoryStateCache {
+    /// Create a cache keeping states for `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Number of cached states, including expired ones not yet evicted
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    /// Check if the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<String, (Instant, ResourceState)>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl StateCache for MemoryStateCache {
+    fn get(&self, key: &str) -> Option<ResourceState> {
+        let mut entries = self.entries();
+        match entries.get(key) {
+            Some((cached_at, state)) if cached_at.elapsed() < self.ttl => Some(state.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: &str, state: ResourceState) {
+        self.entries()
+            .insert(key.to_string(), (Instant::now(), state));
+    }
+
+    fn invalidate(&self, keys: &[String]) {
+        let mut entries = self.entries();
+        for key in keys {
+            entries.remove(key);
+        }
+    }
+
+    fn clear(&self) {
+        self.entries().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockResource;
+
+    #[test]
+    fn test_cached_state_skips_repeat_checks() {
+        let cache = MemoryStateCache::new(Duration::from_secs(60));
+        let resource = MockResource::new("ripgrep").of_type("brew_formula");
+
+        assert_eq!(cache_key(&resource), "brew_formula:ripgrep");
+        for _ in 0..3 {
+            assert_eq!(
+                cached_state(&resource, &cache).unwrap(),
+                ResourceState::Absent
+            );
+        }
+        assert_eq!(resource.checks(), 1);
+
+        cache.invalidate(&[cache_key(&resource)]);
+        assert!(cache.is_empty());
+        cached_state(&resource, &cache).unwrap();
+        assert_eq!(resource.checks(), 2);
+    }
+
+    #[test]
+    fn test_entries_expire() {
+        let cache = MemoryStateCache::new(Duration::ZERO);
+        cache.insert("symlink:~/.zshrc", ResourceState::Absent);
+        assert_eq!(cache.get("symlink:~/.zshrc"), None);
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+//! Checkpoints - resume an interrupted execution where it left off
+//!
+//! A [`Checkpoint`] records every resource the executor applies, as it
+//! finishes, in a JSON file. Executing with
+//! [`ExecuteOptions::resume_from`](crate::ExecuteOptions::resume_from) loads
+//! it first and leaves out resources it shows as already converged, so a
+//! rerun after a crash or Ctrl-C doesn't recheck everything. Once a run
+//! succeeds the file is removed, and the next run starts from scratch.
+
+use crate::types::ApplyResult;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Resources converged so far, persisted to a file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    #[serde(skip)]
+    path: PathBuf,
+    /// Results of converged resources, by [`cache_key`](crate::cache_key)
+    pub completed: BTreeMap<String, ApplyResult>,
+}
+
+impl Checkpoint {
+    /// Load a checkpoint, or start an empty one if the file doesn't exist
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut checkpoint: Self = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .with_context(|| format!("Invalid checkpoint {}", path.display()))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+        checkpoint.path = path;
+        Ok(checkpoint)
+    }
+
+    /// File the checkpoint is saved to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of converged resources
+    pub fn len(&self) -> usize {
+        self.completed.len()
+    }
+
+    /// Check if no resource has converged yet
+    pub fn is_empty(&self) -> bool {
+        self.completed.is_empty()
+    }
+
+    /// Check if a resource, by [`cache_key`](crate::cache_key), has converged
+    pub fn is_converged(&self, key: &str) -> bool {
+        self.completed.contains_key(key)
+    }
+
+    /// Record a resource's result and save
+    ///
+    /// Only results that leave the resource in its desired state count:
+    /// failed and skipped resources are left to the next run.
+    pub fn record(&mut self, key: &str, result: &ApplyResult) -> Result<()> {
+        if !result.is_success() || matches!(result, ApplyResult::Skipped { .. }) {
+            return Ok(());
+        }
+        self.completed.insert(key.to_string(), result.clone());
+        self.save()
+    }
+
+    /// Forget resources by ID, e.g. after they were rolled back, and save
+    pub fn forget(&mut self, ids: &[String]) -> Result<()> {
+        let before = self.completed.len();
+        self.completed.retain(|key, _| {
+            let id = key.split_once(':').map_or(key.as_str(), |(_, id)| id);
+            !ids.iter().any(|forgotten| forgotten == id)
+        });
+        if self.completed.len() == before {
+            return Ok(());
+        }
+        self.save()
+    }
+
+    /// Write the checkpoint to its file
+    ///
+    /// Writes a temporary file and renames it over the old one, so an
+    /// interrupted save never leaves a truncated checkpoint.
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    /// Delete the checkpoint file, once its run has finished
+    pub fn remove(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove {}", self.path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("checkpoint.json");
+
+        let mut checkpoint = Checkpoint::load(&path).unwrap();
+        assert!(checkpoint.is_empty());
+        checkpoint
+            .record("brew_formula:ripgrep", &ApplyResult::Created)
+            .unwrap();
+        checkpoint
+            .record("symlink:~/.zshrc", &ApplyResult::NoChange)
+            .unwrap();
+        checkpoint
+            .record(
+                "brew_cask:docker",
+                &ApplyResult::Failed {
+                    error: "Download failed".to_string(),
+                },
+            )
+            .unwrap();
+
+        let loaded = Checkpoint::load(&path).unwrap();
+        assert_eq!(loaded, checkpoint);
+        assert_eq!(loaded.len(), 2);
+        assert!(loaded.is_converged("brew_formula:ripgrep"));
+        assert!(!loaded.is_converged("brew_cask:docker"));
+
+        checkpoint.forget(&["~/.zshrc".to_string()]).unwrap();
+        assert!(
+            !Checkpoint::load(&path)
+                .unwrap()
+                .is_converged("symlink:~/.zshrc")
+        );
+
+        checkpoint.remove().unwrap();
+        assert!(!path.exists());
+        checkpoint.remove().unwrap();
+    }
+
+    #[test]
+    fn test_invalid_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.json");
+        fs::write(&path, "not json").unwrap();
+        assert!(Checkpoint::load(&path).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! Conditional resources - apply only when the machine calls for it
+//!
+//! A resource can refuse to apply through [`Resource::precondition`], or be
+//! wrapped in a [`Conditional`] with `only_if` and `unless` [`Condition`]s.
+//! Preconditions are checked when diffing; a resource whose precondition
+//! isn't met shows up as skipped, with the reason, and isn't applied.
+
+use crate::action::PlannedAction;
+use crate::context::ApplyContext;
+use crate::import::ConfigSuggestion;
+use crate::resource::{BoxedResource, Resource};
+use crate::types::{ApplyResult, ResourceState, SudoRequirement};
+use anyhow::Result;
+use std::fmt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Something about the machine that is either true or not
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    /// A command exits successfully
+    Command { program: String, args: Vec<String> },
+    /// A file or directory exists
+    PathExists(PathBuf),
+    /// An environment variable is set and not empty
+    EnvSet(String),
+}
+
+impl Condition {
+    /// A command exits successfully
+    pub fn command(program: &str, args: &[&str]) -> Self {
+        Self::Command {
+            program: program.to_string(),
+            args: args.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    /// A file or directory exists
+    pub fn path_exists(path: impl Into<PathBuf>) -> Self {
+        Self::PathExists(path.into())
+    }
+
+    /// An environment variable is set and not empty
+    pub fn env_set(name: &str) -> Self {
+        Self::EnvSet(name.to_string())
+    }
+
+    /// Check whether the condition holds
+    pub fn is_met(&self) -> bool {
+        match self {
+            Self::Command { program, args } => Command::new(program)
+                .args(args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success()),
+            Self::PathExists(path) => path.exists(),
+            Self::EnvSet(name) => std::env::var_os(name).is_some_and(|value| !value.is_empty()),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Command { program, args } if args.is_empty() => {
+                write!(f, "`{program}` succeeds")
+            }
+            Self::Command { program, args } => write!(f, "`{program} {}` succeeds", args.join(" ")),
+            Self::PathExists(path) => write!(f, "{} exists", path.display()),
+            Self::EnvSet(name) => write!(f, "${name} is set"),
+        }
+    }
+}
+
+/// A resource that only applies under some conditions
+///
+/// Applies when every `only_if` condition holds and no `unless` condition
+/// does; everything else is the wrapped resource's.
+#[derive(Debug)]
+pub struct Conditional {
+    resource: BoxedResource,
+    only_if: Vec<Condition>,
+    unless: Vec<Condition>,
+}
+
+impl Conditional {
+    /// Wrap a resource, initially without conditions
+    pub fn new(resource: BoxedResource) -> Self {
+        Self {
+            resource,
+            only_if: Vec::new(),
+            unless: Vec::new(),
+        }
+    }
+
+    /// Only apply when `condition` holds
+    pub fn only_if(mut self, condition: Condition) -> Self {
+        self.only_if.push(condition);
+        self
+    }
+
+    /// Don't apply when `condition` holds
+    pub fn unless(mut self, condition: Condition) -> Self {
+        self.unless.push(condition);
+        self
+    }
+}
+
+impl Resource for Conditional {
+    fn id(&self) -> String {
+        self.resource.id()
+    }
+
+    fn description(&self) -> String {
+        self.resource.description()
+    }
+
+    fn resource_type(&self) -> &'static str {
+        self.resource.resource_type()
+    }
+
+    fn sudo_requirement(&self) -> SudoRequirement {
+        self.resource.sudo_requirement()
+    }
+
+    fn current_state(&self) -> Result<ResourceState> {
+        self.resource.current_state()
+    }
+
+    fn desired_state(&self) -> ResourceState {
+        self.resource.desired_state()
+    }
+
+    fn needs_apply(&self) -> Result<bool> {
+        self.resource.needs_apply()
+    }
+
+    fn apply(&self, ctx: &mut ApplyContext) -> Result<ApplyResult> {
+        self.resource.apply(ctx)
+    }
+
+    fn plan(&self) -> Vec<PlannedAction> {
+        self.resource.plan()
+    }
+
+    fn adopt(&self) -> Option<ConfigSuggestion> {
+        self.resource.adopt()
+    }
+
+    fn can_parallelize(&self) -> bool {
+        self.resource.can_parallelize()
+    }
+
+    fn revert(&self, ctx: &mut ApplyContext) -> Result<ApplyResult> {
+        self.resource.revert(ctx)
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.resource.dependencies()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.resource.timeout()
+    }
+
+    fn tags(&self) -> Vec<String> {
+        self.resource.tags()
+    }
+
+    fn precondition(&self) -> Option<String> {
+        if let Some(condition) = self.only_if.iter().find(|c| !c.is_met()) {
+            return Some(format!("Condition not met: {condition}"));
+        }
+        if let Some(condition) = self.unless.iter().find(|c| c.is_met()) {
+            return Some(format!("Excluded because {condition}"));
+        }
+        self.resource.precondition()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::compute_diffs;
+    use crate::testing::MockResource;
+
+    fn extension() -> BoxedResource {
+        Box::new(MockResource::new("gh-dash").of_type("gh_extension"))
+    }
+
+    #[test]
+    fn test_conditions() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(Condition::path_exists(dir.path()).is_met());
+        assert!(!Condition::path_exists(dir.path().join("missing")).is_met());
+        assert!(Condition::env_set("PATH").is_met());
+        assert!(!Condition::env_set("BOSSA_TEST_UNSET_VARIABLE").is_met());
+        assert!(!Condition::command("bossa-test-missing-command", &[]).is_met());
+
+        assert_eq!(
+            Condition::command("gh", &["auth", "status"]).to_string(),
+            "`gh auth status` succeeds"
+        );
+        assert_eq!(Condition::env_set("WORK").to_string(), "$WORK is set");
+    }
+
+    #[test]
+    fn test_unmet_precondition_shows_as_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let resources: Vec<BoxedResource> = vec![
+            Box::new(
+                Conditional::new(extension())
+                    .only_if(Condition::path_exists(dir.path().join("missing"))),
+            ),
+            Box::new(Conditional::new(extension()).unless(Condition::env_set("PATH"))),
+            Box::new(Conditional::new(extension()).only_if(Condition::path_exists(dir.path()))),
+        ];
+
+        let diffs = compute_diffs(&resources);
+        assert_eq!(diffs.len(), 3);
+        assert!(
+            diffs[0]
+                .skipped
+                .as_ref()
+                .unwrap()
+                .starts_with("Condition not met")
+        );
+        assert_eq!(
+            diffs[1].skipped.as_deref(),
+            Some("Excluded because $PATH is set")
+        );
+        assert_eq!(diffs[2].skipped, None);
+        assert!(diffs[2].is_addition());
+    }
+}
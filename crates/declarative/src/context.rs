@@ -3,8 +3,14 @@
 //! These traits allow the declarative crate to be used without
 //! depending on specific implementations of sudo, progress, etc.
 
+use crate::audit::output_hash;
+use crate::events::ExecutorEvent;
 use crate::types::{ApplyResult, CommandOutput};
-use anyhow::Result;
+use crate::variables::Variables;
+use anyhow::{Context, Result};
+use std::cell::RefCell;
+use std::process::Command;
+use std::sync::mpsc::Sender;
 
 /// Provider for elevated privilege operations
 ///
@@ -65,6 +71,9 @@ pub trait ProgressCallback: Send {
 
     /// Called when a batch completes
     fn on_batch_complete(&mut self);
+
+    /// Called for every executor event, as it happens
+    fn on_event(&mut self, _event: &ExecutorEvent) {}
 }
 
 /// Confirmation callback for user interaction
@@ -91,6 +100,19 @@ impl ProgressCallback for NoProgress {
     fn on_batch_complete(&mut self) {}
 }
 
+/// Forwards executor events to a channel, e.g. for a UI thread
+impl ProgressCallback for Sender<ExecutorEvent> {
+    fn on_batch_start(&mut self, _count: usize, _privileged: bool) {}
+    fn on_resource_start(&mut self, _id: &str, _description: &str) {}
+    fn on_resource_complete(&mut self, _id: &str, _result: &ApplyResult) {}
+    fn on_batch_complete(&mut self) {}
+
+    fn on_event(&mut self, event: &ExecutorEvent) {
+        // A receiver that hung up just stops listening
+        let _ = self.send(event.clone());
+    }
+}
+
 /// Auto-confirm callback (always returns true)
 pub struct AutoConfirm;
 
@@ -117,6 +139,10 @@ pub struct ApplyContext<'a> {
     pub verbose: bool,
     /// Optional sudo provider for privileged operations
     pub sudo: Option<&'a dyn SudoProvider>,
+    /// The plan's variables, if any
+    pub variables: Option<&'a Variables>,
+    /// Hashes of the output of commands run through the context
+    outputs: RefCell<Vec<String>>,
 }
 
 impl<'a> ApplyContext<'a> {
@@ -126,6 +152,8 @@ impl<'a> ApplyContext<'a> {
             dry_run,
             verbose,
             sudo: None,
+            variables: None,
+            outputs: RefCell::default(),
         }
     }
 
@@ -135,6 +163,25 @@ impl<'a> ApplyContext<'a> {
             dry_run,
             verbose,
             sudo: Some(sudo),
+            variables: None,
+            outputs: RefCell::default(),
+        }
+    }
+
+    /// Give resources the plan's variables
+    pub fn with_variables(mut self, variables: &'a Variables) -> Self {
+        self.variables = Some(variables);
+        self
+    }
+
+    /// Expand `${name}` references with the plan's variables
+    ///
+    /// See [`Variables::expand`]; without variables, only text without
+    /// references expands.
+    pub fn expand(&self, input: &str) -> Result<String> {
+        match self.variables {
+            Some(variables) => variables.expand(input),
+            None => Variables::new().expand(input),
         }
     }
 
@@ -143,4 +190,28 @@ impl<'a> ApplyContext<'a> {
         self.sudo
             .ok_or_else(|| anyhow::anyhow!("Sudo required but not available"))
     }
+
+    /// Run a command, recording a hash of its output for the audit log
+    pub fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
+        let output: CommandOutput = Command::new(program)
+            .args(args)
+            .output()
+            .with_context(|| format!("Failed to run {program}"))?
+            .into();
+        self.outputs.borrow_mut().push(output_hash(&output));
+        Ok(output)
+    }
+
+    /// Run a command with the sudo provider, recording a hash of its output
+    pub fn run_sudo(&self, program: &str, args: &[&str]) -> Result<CommandOutput> {
+        let output = self.require_sudo()?.run(program, args)?;
+        self.outputs.borrow_mut().push(output_hash(&output));
+        Ok(output)
+    }
+
+    /// Hashes of the output of commands run with [`run`](Self::run) and
+    /// [`run_sudo`](Self::run_sudo), in order
+    pub fn output_hashes(&self) -> Vec<String> {
+        self.outputs.borrow().clone()
+    }
 }
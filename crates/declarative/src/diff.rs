@@ -1,12 +1,14 @@
 //! Diff computation for resources
 
+use crate::action::PlannedAction;
+use crate::cache::{StateCache, cached_state};
 use crate::resource::Resource;
 use crate::types::{ResourceState, SudoRequirement};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
 /// A diff between current and desired state of a resource
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ResourceDiff {
     /// Unique identifier of the resource
     pub resource_id: String,
@@ -20,12 +22,54 @@ pub struct ResourceDiff {
     pub desired: ResourceState,
     /// Whether this resource requires sudo
     pub requires_sudo: bool,
+    /// Why the resource won't be applied, if its precondition isn't met
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+    /// What applying the resource would do (see [`Resource::plan`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub planned: Vec<PlannedAction>,
 }
 
 impl ResourceDiff {
     /// Create a diff from a resource, returning None if no changes needed
+    ///
+    /// A resource whose precondition isn't met always has a diff, marked
+    /// as skipped.
     pub fn from_resource(resource: &dyn Resource) -> Result<Option<Self>> {
-        let current = resource.current_state()?;
+        if let Some(reason) = resource.precondition() {
+            return Ok(Some(Self::skipped(resource, reason)));
+        }
+        Self::with_state(resource, resource.current_state()?)
+    }
+
+    /// Like [`ResourceDiff::from_resource`], with the current state from a cache
+    pub fn from_resource_cached(
+        resource: &dyn Resource,
+        cache: &dyn StateCache,
+    ) -> Result<Option<Self>> {
+        if let Some(reason) = resource.precondition() {
+            return Ok(Some(Self::skipped(resource, reason)));
+        }
+        Self::with_state(resource, cached_state(resource, cache)?)
+    }
+
+    fn skipped(resource: &dyn Resource, reason: String) -> Self {
+        Self {
+            resource_id: resource.id(),
+            resource_type: resource.resource_type().to_string(),
+            description: resource.description(),
+            current: ResourceState::Unknown,
+            desired: resource.desired_state(),
+            requires_sudo: matches!(
+                resource.sudo_requirement(),
+                SudoRequirement::Required { .. }
+            ),
+            skipped: Some(reason),
+            planned: Vec::new(),
+        }
+    }
+
+    fn with_state(resource: &dyn Resource, current: ResourceState) -> Result<Option<Self>> {
         let desired = resource.desired_state();
 
         if current == desired {
@@ -42,9 +86,16 @@ impl ResourceDiff {
                 resource.sudo_requirement(),
                 SudoRequirement::Required { .. }
             ),
+            skipped: None,
+            planned: resource.plan(),
         }))
     }
 
+    /// Check if the resource will be skipped rather than changed
+    pub fn is_skipped(&self) -> bool {
+        self.skipped.is_some()
+    }
+
     /// Check if this diff represents an addition
     pub fn is_addition(&self) -> bool {
         matches!(
@@ -86,8 +137,23 @@ pub fn compute_diffs(resources: &[Box<dyn Resource>]) -> Vec<ResourceDiff> {
         .collect()
 }
 
+/// Compute diffs, checking current state through a cache
+pub fn compute_diffs_cached(
+    resources: &[Box<dyn Resource>],
+    cache: &dyn StateCache,
+) -> Vec<ResourceDiff> {
+    resources
+        .iter()
+        .filter_map(|r| {
+            ResourceDiff::from_resource_cached(r.as_ref(), cache)
+                .ok()
+                .flatten()
+        })
+        .collect()
+}
+
 /// Diff summary statistics
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DiffSummary {
     /// Number of resources to add
     pub additions: usize,
@@ -97,6 +163,8 @@ pub struct DiffSummary {
     pub modifications: usize,
     /// Number of resources requiring sudo
     pub sudo_required: usize,
+    /// Number of resources skipped because their precondition isn't met
+    pub skipped: usize,
 }
 
 impl DiffSummary {
@@ -104,6 +172,10 @@ impl DiffSummary {
     pub fn from_diffs(diffs: &[ResourceDiff]) -> Self {
         let mut summary = Self::default();
         for diff in diffs {
+            if diff.is_skipped() {
+                summary.skipped += 1;
+                continue;
+            }
             if diff.is_addition() {
                 summary.additions += 1;
             } else if diff.is_removal() {
@@ -0,0 +1,56 @@
+//! Executor events - a structured record of what execution is doing
+//!
+//! The executor reports every step through
+//! [`ProgressCallback::on_event`](crate::ProgressCallback::on_event), as it
+//! happens, including from parallel batches. Events serialize to tagged
+//! JSON objects, one per line for an NDJSON log:
+//!
+//! ```json
+//! {"event":"resource_finished","id":"ripgrep","resource_type":"brew_formula","result":"Created","duration_ms":1520}
+//! ```
+
+use crate::types::{ApplyResult, ExecuteSummary};
+use serde::{Serialize, Serializer};
+use std::time::Duration;
+
+/// Something the executor did
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ExecutorEvent {
+    /// Execution started
+    PlanStarted {
+        /// Resources in the plan
+        resources: usize,
+        /// Resources that differ from their desired state
+        changes: usize,
+    },
+    /// A resource started applying
+    ResourceStarted {
+        id: String,
+        resource_type: String,
+        description: String,
+    },
+    /// A resource finished applying, or was skipped
+    ResourceFinished {
+        id: String,
+        resource_type: String,
+        result: ApplyResult,
+        #[serde(rename = "duration_ms", serialize_with = "as_millis")]
+        duration: Duration,
+    },
+    /// Privileges are about to be acquired for privileged resources
+    SudoRequested {
+        /// Privileged resources in the plan
+        resources: usize,
+    },
+    /// Execution finished
+    PlanFinished {
+        summary: ExecuteSummary,
+        #[serde(rename = "duration_ms", serialize_with = "as_millis")]
+        duration: Duration,
+    },
+}
+
+fn as_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+}
@@ -1,16 +1,70 @@
 //! Execution engine - applies resources with parallelism and privilege batching
 
+use crate::audit::{AuditEntry, AuditLog};
+use crate::cache::cache_key;
+use crate::checkpoint::Checkpoint;
 use crate::context::{ApplyContext, ConfirmCallback, ProgressCallback, SudoProvider};
-use crate::diff::compute_diffs;
-use crate::planner::ExecutionPlan;
-use crate::resource::Resource;
-use crate::types::{ApplyResult, ExecuteOptions, ExecuteSummary};
+use crate::diff::{ResourceDiff, compute_diffs, compute_diffs_cached};
+use crate::events::ExecutorEvent;
+use crate::planner::{ExecutionPlan, Stage};
+use crate::resource::{Resource, ResourceExt};
+use crate::types::{
+    ApplyResult, ExecuteOptions, ExecuteSummary, FailurePolicy, ResourceOutcome, ResourceState,
+    RetryPolicy,
+};
+use crate::variables::{Variables, current_user};
 use anyhow::Result;
+use chrono::Utc;
 use rayon::prelude::*;
-use std::sync::{Arc, Mutex};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// A resource shared with the thread applying it
+type SharedResource = Arc<dyn Resource>;
+
+/// What a batch's resources apply with, besides options
+#[derive(Clone, Copy)]
+struct Env<'a> {
+    sudo: Option<&'a Arc<dyn SudoProvider>>,
+    variables: &'a Arc<Variables>,
+    audit: Option<&'a Arc<AuditLog>>,
+}
+
+/// A resource and the result of applying it
+struct Applied<'a> {
+    resource: &'a SharedResource,
+    result: ApplyResult,
+    /// Attempts made, per the retry policy
+    attempts: u32,
+}
 
 /// Execute a plan with the given options and callbacks
 ///
+/// Resources run in dependency order (see [`ExecutionPlan::stages`]). What
+/// happens after a failure depends on [`ExecuteOptions::failure_policy`]:
+/// by default, only resources whose dependency failed are skipped. With
+/// [`ExecuteOptions::rollback`], the first failure stops execution and
+/// reverts what was already applied. Each resource's result is recorded in
+/// [`ExecuteSummary::outcomes`].
+///
+/// With [`ExecuteOptions::tags`], only resources whose tags pass the filter
+/// are considered at all.
+///
+/// A resource still applying after its timeout (see [`Resource::timeout`]
+/// and [`ExecuteOptions::timeout`]) fails without being retried, and
+/// execution moves on.
+///
+/// With [`ExecuteOptions::checkpoint`], resources a previous run already
+/// converged are left out, and each resource that converges is recorded as
+/// it finishes. The checkpoint is removed once everything has converged.
+///
+/// Every step is reported to `progress` as an [`ExecutorEvent`], from
+/// `PlanStarted` to `PlanFinished`. Nothing is reported when there is
+/// nothing to apply, or on a dry run.
+///
 /// # Type Parameters
 /// * `S` - Sudo provider type
 /// * `P` - Progress callback type
@@ -33,17 +87,65 @@ pub fn execute<S, P, C>(
     confirm: &mut C,
 ) -> Result<ExecuteSummary>
 where
-    S: SudoProvider,
+    S: SudoProvider + 'static,
     P: ProgressCallback,
     C: ConfirmCallback,
 {
+    // Only the tagged subset, if asked
+    let plan = plan.filter_by_tags(&opts.tags);
+
+    // Pick up where an interrupted run left off
+    let mut checkpoint = opts
+        .checkpoint
+        .as_ref()
+        .map(|path| Checkpoint::load(path.as_path()))
+        .transpose()?;
+    let mut resumed = 0;
+    let plan = match &checkpoint {
+        Some(checkpoint) if !checkpoint.is_empty() => {
+            let total = plan.total_resources();
+            let plan = plan.filter(|r| !checkpoint.is_converged(&cache_key(r)));
+            resumed = total - plan.total_resources();
+            plan
+        }
+        _ => plan,
+    };
+
+    // Order by dependencies first, so a cycle fails before anything runs
+    let stages = plan.stages()?;
+
     // Compute diffs for reporting
-    let unprivileged_diffs = compute_diffs(&plan.unprivileged);
-    let privileged_diffs = compute_diffs(&plan.privileged);
-    let total_changes = unprivileged_diffs.len() + privileged_diffs.len();
+    let (unprivileged_diffs, privileged_diffs) = match &opts.state_cache {
+        Some(cache) => (
+            compute_diffs_cached(&plan.unprivileged, cache.as_ref()),
+            compute_diffs_cached(&plan.privileged, cache.as_ref()),
+        ),
+        None => (
+            compute_diffs(&plan.unprivileged),
+            compute_diffs(&plan.privileged),
+        ),
+    };
+    // Resources whose precondition isn't met are skipped, not applied
+    let unmet: HashMap<String, String> = unprivileged_diffs
+        .iter()
+        .chain(&privileged_diffs)
+        .filter_map(|diff| {
+            let key = format!("{}:{}", diff.resource_type, diff.resource_id);
+            diff.skipped.clone().map(|reason| (key, reason))
+        })
+        .collect();
+    let total_changes = unprivileged_diffs.len() + privileged_diffs.len() - unmet.len();
 
     if total_changes == 0 {
-        return Ok(ExecuteSummary::default());
+        if let Some(checkpoint) = &checkpoint
+            && !opts.dry_run
+        {
+            checkpoint.remove()?;
+        }
+        return Ok(ExecuteSummary {
+            resumed,
+            ..Default::default()
+        });
     }
 
     // Confirm before proceeding (unless dry_run)
@@ -55,127 +157,725 @@ where
     }
 
     if opts.dry_run {
-        return Ok(ExecuteSummary::default());
+        let planned = unprivileged_diffs
+            .into_iter()
+            .chain(privileged_diffs)
+            .filter(|diff| !diff.is_skipped())
+            .collect();
+        return Ok(ExecuteSummary {
+            resumed,
+            planned,
+            ..Default::default()
+        });
     }
 
-    let mut summary = ExecuteSummary::default();
+    let diffs: Vec<ResourceDiff> = unprivileged_diffs
+        .into_iter()
+        .chain(privileged_diffs)
+        .collect();
+    let started = Instant::now();
+    progress.on_event(&ExecutorEvent::PlanStarted {
+        resources: plan.total_resources(),
+        changes: total_changes,
+    });
+    let mut summary = match &mut checkpoint {
+        Some(checkpoint) => {
+            let mut progress = Checkpointing {
+                progress,
+                checkpoint,
+            };
+            apply_stages(
+                plan,
+                &stages,
+                &opts,
+                &diffs,
+                &unmet,
+                sudo_provider,
+                &mut progress,
+            )?
+        }
+        None => apply_stages(
+            plan,
+            &stages,
+            &opts,
+            &diffs,
+            &unmet,
+            sudo_provider,
+            progress,
+        )?,
+    };
+    summary.resumed = resumed;
+    if let Some(checkpoint) = &mut checkpoint {
+        if summary.is_success() {
+            checkpoint.remove()?;
+        } else {
+            // Reverted resources have to be applied again
+            checkpoint.forget(&summary.rolled_back)?;
+        }
+    }
+    progress.on_event(&ExecutorEvent::PlanFinished {
+        summary: summary.clone(),
+        duration: started.elapsed(),
+    });
+
+    Ok(summary)
+}
+
+/// Progress callback recording converged resources in a checkpoint
+struct Checkpointing<'a, P> {
+    progress: &'a mut P,
+    checkpoint: &'a mut Checkpoint,
+}
+
+impl<P: ProgressCallback> ProgressCallback for Checkpointing<'_, P> {
+    fn on_batch_start(&mut self, count: usize, privileged: bool) {
+        self.progress.on_batch_start(count, privileged);
+    }
+
+    fn on_resource_start(&mut self, id: &str, description: &str) {
+        self.progress.on_resource_start(id, description);
+    }
+
+    fn on_resource_complete(&mut self, id: &str, result: &ApplyResult) {
+        self.progress.on_resource_complete(id, result);
+    }
+
+    fn on_batch_complete(&mut self) {
+        self.progress.on_batch_complete();
+    }
 
-    // Execute unprivileged resources in parallel
-    if !plan.unprivileged.is_empty() {
-        progress.on_batch_start(plan.unprivileged.len(), false);
-        let results = execute_batch(&plan.unprivileged, opts.jobs, opts.verbose, None, progress)?;
-        for result in &results {
-            summary.add_result(result);
+    fn on_event(&mut self, event: &ExecutorEvent) {
+        if let ExecutorEvent::ResourceFinished {
+            id,
+            resource_type,
+            result,
+            ..
+        } = event
+        {
+            // A checkpoint that can't be saved only costs rechecking on resume
+            let _ = self
+                .checkpoint
+                .record(&format!("{resource_type}:{id}"), result);
         }
-        progress.on_batch_complete();
+        self.progress.on_event(event);
     }
+}
 
-    // Execute privileged resources (sequentially, with sudo)
-    if !plan.privileged.is_empty() {
-        // Acquire sudo only when needed
-        let sudo = sudo_provider()?;
+/// Apply a plan's stages in order
+///
+/// `diffs` are the plan's, for the audit log's states before applying.
+fn apply_stages<S, P>(
+    plan: ExecutionPlan,
+    stages: &[Stage],
+    opts: &ExecuteOptions,
+    diffs: &[ResourceDiff],
+    unmet: &HashMap<String, String>,
+    sudo_provider: impl FnOnce() -> Result<S>,
+    progress: &mut P,
+) -> Result<ExecuteSummary>
+where
+    S: SudoProvider + 'static,
+    P: ProgressCallback,
+{
+    // Shared, so a resource that times out can be left applying
+    let all_unprivileged: Vec<SharedResource> =
+        plan.unprivileged.into_iter().map(Arc::from).collect();
+    let all_privileged: Vec<SharedResource> = plan.privileged.into_iter().map(Arc::from).collect();
+    let variables = Arc::new(plan.variables);
+    let audit = opts
+        .audit_log
+        .as_ref()
+        .map(|path| AuditLog::open(path).map(|log| log.with_new_state(opts.audit_new_state)))
+        .transpose()?
+        .map(Arc::new);
+    if let Some(audit) = &audit {
+        audit.remember(diffs);
+    }
 
-        progress.on_batch_start(plan.privileged.len(), true);
-        let results = execute_batch(
-            &plan.privileged,
-            1, // Sequential for sudo
-            opts.verbose,
-            Some(&sudo),
-            progress,
-        )?;
-        for result in &results {
-            summary.add_result(result);
+    let mut summary = ExecuteSummary::default();
+    let mut sudo_provider = Some(sudo_provider);
+    let mut sudo: Option<Arc<dyn SudoProvider>> = None;
+    // Resources that failed, or were skipped because a dependency did
+    let mut failed = HashSet::new();
+    // Resources that changed, in the order they were applied
+    let mut applied: Vec<(&dyn Resource, bool)> = Vec::new();
+
+    for stage in stages {
+        // Unprivileged resources in parallel
+        let unprivileged: Vec<&SharedResource> = stage
+            .unprivileged
+            .iter()
+            .map(|&i| &all_unprivileged[i])
+            .collect();
+        if !unprivileged.is_empty() {
+            progress.on_batch_start(unprivileged.len(), false);
+            let env = Env {
+                sudo: None,
+                variables: &variables,
+                audit: audit.as_ref(),
+            };
+            let results = execute_batch(
+                &unprivileged,
+                opts.jobs,
+                opts,
+                env,
+                unmet,
+                &mut failed,
+                progress,
+            )?;
+            invalidate(opts, &results);
+            let any_failed = record(&results, false, &mut summary, &mut applied);
+            progress.on_batch_complete();
+
+            if opts.rollback && any_failed {
+                rollback(
+                    &applied,
+                    opts.verbose,
+                    sudo.as_deref(),
+                    &variables,
+                    audit.as_deref(),
+                    &mut summary,
+                );
+            }
+        }
+
+        // Privileged resources sequentially, with sudo
+        let privileged: Vec<&SharedResource> = stage
+            .privileged
+            .iter()
+            .map(|&i| &all_privileged[i])
+            .collect();
+        if !privileged.is_empty() {
+            // Acquire sudo only when needed, and only once. After a failure
+            // that stops execution, including one in this stage's
+            // unprivileged batch, everything left is skipped
+            let stopped = opts.stops_on_failure() && !failed.is_empty();
+            if !stopped && let Some(acquire) = sudo_provider.take() {
+                progress.on_event(&ExecutorEvent::SudoRequested {
+                    resources: all_privileged.len(),
+                });
+                sudo = Some(Arc::new(acquire()?));
+            }
+
+            progress.on_batch_start(privileged.len(), true);
+            let env = Env {
+                sudo: sudo.as_ref(),
+                variables: &variables,
+                audit: audit.as_ref(),
+            };
+            let results = execute_batch(
+                &privileged,
+                1, // Sequential for sudo
+                opts,
+                env,
+                unmet,
+                &mut failed,
+                progress,
+            )?;
+            invalidate(opts, &results);
+            let any_failed = record(&results, true, &mut summary, &mut applied);
+            progress.on_batch_complete();
+
+            if opts.rollback && any_failed {
+                rollback(
+                    &applied,
+                    opts.verbose,
+                    sudo.as_deref(),
+                    &variables,
+                    audit.as_deref(),
+                    &mut summary,
+                );
+            }
         }
-        progress.on_batch_complete();
     }
 
     Ok(summary)
 }
 
-/// Execute a batch of resources
-fn execute_batch<P: ProgressCallback>(
-    resources: &[Box<dyn Resource>],
-    jobs: usize,
+/// Drop a batch's cached states, which applying may have changed
+fn invalidate(opts: &ExecuteOptions, results: &[Applied]) {
+    if let Some(cache) = &opts.state_cache {
+        let keys: Vec<String> = results
+            .iter()
+            .map(|applied| cache_key(applied.resource.as_ref()))
+            .collect();
+        cache.invalidate(&keys);
+    }
+}
+
+/// Add a batch's results to the summary, and its changed resources to
+/// `applied`
+///
+/// Returns whether any resource in the batch failed.
+fn record<'a>(
+    results: &[Applied<'a>],
+    privileged: bool,
+    summary: &mut ExecuteSummary,
+    applied: &mut Vec<(&'a dyn Resource, bool)>,
+) -> bool {
+    let mut any_failed = false;
+    for result in results {
+        summary.add_result(&result.result);
+        summary.add_attempts(&result.resource.id(), result.attempts);
+        summary.outcomes.push(ResourceOutcome {
+            resource_type: result.resource.resource_type().to_string(),
+            resource_id: result.resource.id(),
+            result: result.result.clone(),
+        });
+        if result.result.is_change() {
+            applied.push((result.resource.as_ref(), privileged));
+        }
+        any_failed |= !result.result.is_success();
+    }
+    any_failed
+}
+
+/// Revert applied resources, most recent first
+///
+/// Privileged resources are reverted with `sudo`, which is available
+/// whenever one was applied.
+fn rollback(
+    applied: &[(&dyn Resource, bool)],
     verbose: bool,
     sudo: Option<&dyn SudoProvider>,
+    variables: &Variables,
+    audit: Option<&AuditLog>,
+    summary: &mut ExecuteSummary,
+) {
+    for &(resource, privileged) in applied.iter().rev() {
+        let ctx = match sudo {
+            Some(s) if privileged => ApplyContext::with_sudo(false, verbose, s),
+            _ => ApplyContext::new(false, verbose),
+        };
+        let mut ctx = ctx.with_variables(variables);
+        // Only resources that changed are reverted, so they were converged
+        let old_state = Some(resource.desired_state());
+        let result = audited(resource, &mut ctx, audit, old_state, |ctx| {
+            resource
+                .revert(ctx)
+                .unwrap_or_else(|e| ApplyResult::Failed {
+                    error: e.to_string(),
+                })
+        });
+        if result.is_change() || result == ApplyResult::NoChange {
+            summary.rolled_back.push(resource.id());
+        } else {
+            summary.rollback_failed.push(resource.id());
+        }
+    }
+}
+
+/// Execute a batch of resources, skipping those with an unmet precondition
+/// (from `unmet`, by [`cache_key`]) and those the
+/// [`FailurePolicy`] leaves alone after a failure
+///
+/// Adds the IDs of failed and dependency-skipped resources to `failed`.
+fn execute_batch<'a, P: ProgressCallback>(
+    resources: &[&'a SharedResource],
+    jobs: usize,
+    opts: &ExecuteOptions,
+    env: Env<'_>,
+    unmet: &HashMap<String, String>,
+    failed: &mut HashSet<String>,
     progress: &mut P,
-) -> Result<Vec<ApplyResult>> {
-    if jobs == 1 || resources.len() == 1 {
-        // Sequential execution
-        let mut results = Vec::with_capacity(resources.len());
-        for resource in resources {
-            progress.on_resource_start(&resource.id(), &resource.description());
-            let result = apply_resource(resource.as_ref(), verbose, sudo);
-            progress.on_resource_complete(&resource.id(), &result);
-            results.push(result);
+) -> Result<Vec<Applied<'a>>> {
+    let stops = opts.stops_on_failure();
+    let mut results = Vec::with_capacity(resources.len());
+    let mut runnable = Vec::with_capacity(resources.len());
+    for &resource in resources {
+        if stops && !failed.is_empty() {
+            results.push(skip(resource, STOPPED.to_string(), progress));
+            continue;
+        }
+        if let Some(reason) = unmet.get(&cache_key(resource.as_ref())) {
+            results.push(skip(resource, reason.clone(), progress));
+            continue;
+        }
+        if opts.failure_policy == FailurePolicy::BestEffort {
+            runnable.push(resource);
+            continue;
+        }
+        match resource.blocked_by(failed) {
+            Some(dependency) => {
+                let reason = format!("Dependency {dependency} failed");
+                results.push(skip(resource, reason, progress));
+                failed.insert(resource.id());
+            }
+            None => runnable.push(resource),
+        }
+    }
+
+    if jobs == 1 || runnable.len() == 1 {
+        // Sequential execution, which can stop right after a failure
+        for resource in runnable {
+            if stops && !failed.is_empty() {
+                results.push(skip(resource, STOPPED.to_string(), progress));
+                continue;
+            }
+            report(progress, started(resource.as_ref()));
+            let start = Instant::now();
+            let (result, attempts) = apply_resource(resource, opts, env);
+            report(
+                progress,
+                finished(resource.as_ref(), &result, start.elapsed()),
+            );
+            if !result.is_success() {
+                failed.insert(resource.id());
+            }
+            results.push(Applied {
+                resource,
+                result,
+                attempts,
+            });
         }
-        Ok(results)
     } else {
         // Parallel execution
-        execute_parallel(resources, jobs, verbose, sudo, progress)
+        for applied in execute_parallel(&runnable, jobs, opts, env, progress)? {
+            if !applied.result.is_success() {
+                failed.insert(applied.resource.id());
+            }
+            results.push(applied);
+        }
+    }
+
+    Ok(results)
+}
+
+/// Why resources are skipped once a failure stops execution
+const STOPPED: &str = "Execution stopped after a failure";
+
+/// Skip a resource without applying it
+fn skip<'a, P: ProgressCallback>(
+    resource: &'a SharedResource,
+    reason: String,
+    progress: &mut P,
+) -> Applied<'a> {
+    let result = ApplyResult::Skipped { reason };
+    report(
+        progress,
+        finished(resource.as_ref(), &result, Duration::ZERO),
+    );
+    Applied {
+        resource,
+        result,
+        attempts: 0,
     }
 }
 
 /// Execute resources in parallel using rayon
-fn execute_parallel<P: ProgressCallback>(
-    resources: &[Box<dyn Resource>],
+///
+/// Resource types with a limit in [`ExecuteOptions::concurrency`] run in a
+/// pool of their own (or one shared with their group), at the same time as
+/// everything else.
+fn execute_parallel<'a, P: ProgressCallback>(
+    resources: &[&'a SharedResource],
     jobs: usize,
-    verbose: bool,
-    sudo: Option<&dyn SudoProvider>,
+    opts: &ExecuteOptions,
+    env: Env<'_>,
     progress: &mut P,
-) -> Result<Vec<ApplyResult>> {
-    // For parallel execution, we can't use the progress callback during iteration
-    // because it's not thread-safe. We collect results and report after.
-    let results: Arc<Mutex<Vec<(String, ApplyResult)>>> = Arc::new(Mutex::new(Vec::new()));
-
-    let pool = rayon::ThreadPoolBuilder::new()
-        .num_threads(jobs)
-        .build()
-        .map_err(|e| anyhow::anyhow!("Failed to create thread pool: {e}"))?;
-
-    pool.install(|| {
-        resources.par_iter().for_each(|resource| {
-            let result = apply_resource(resource.as_ref(), verbose, sudo);
-            results.lock().unwrap().push((resource.id(), result));
-        });
+) -> Result<Vec<Applied<'a>>> {
+    let pools = concurrency_groups(resources, jobs, opts)
+        .into_iter()
+        .map(|(jobs, group)| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(jobs)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to create thread pool: {e}"))?;
+            Ok((pool, group))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // The progress callback isn't thread-safe, so workers send their events
+    // here and this thread reports them as they arrive
+    let (tx, rx) = mpsc::channel();
+    let results = std::thread::scope(|scope| {
+        let mut workers = Vec::with_capacity(pools.len());
+        for (pool, group) in &pools {
+            let tx = tx.clone();
+            workers.push(scope.spawn(move || {
+                pool.install(|| {
+                    group
+                        .par_iter()
+                        .map_with(tx, |tx, &resource| {
+                            let _ = tx.send(started(resource.as_ref()));
+                            let start = Instant::now();
+                            let (result, attempts) = apply_resource(resource, opts, env);
+                            let _ = tx.send(finished(resource.as_ref(), &result, start.elapsed()));
+                            Applied {
+                                resource,
+                                result,
+                                attempts,
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+            }));
+        }
+        drop(tx);
+
+        // Ends once every worker has dropped its sender
+        for event in rx {
+            report(progress, event);
+        }
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
     });
 
-    let results = Arc::try_unwrap(results)
-        .map_err(|_| anyhow::anyhow!("Failed to unwrap results"))?
-        .into_inner()
-        .unwrap();
+    Ok(results)
+}
 
-    // Report results to progress callback
-    for (id, result) in &results {
-        progress.on_resource_complete(id, result);
+/// Split resources into groups that each run in a pool of their own: one
+/// per resource type or group with a concurrency limit, and one of `jobs`
+/// threads for the rest
+fn concurrency_groups<'a>(
+    resources: &[&'a SharedResource],
+    jobs: usize,
+    opts: &ExecuteOptions,
+) -> Vec<(usize, Vec<&'a SharedResource>)> {
+    let mut groups: Vec<(Option<&str>, usize, Vec<&'a SharedResource>)> = Vec::new();
+    for &resource in resources {
+        let (key, limit) = match opts.pool_for(resource.resource_type()) {
+            Some((pool, limit)) => (Some(pool), limit),
+            None => (None, jobs),
+        };
+        match groups.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((_, _, group)) => group.push(resource),
+            None => groups.push((key, limit, vec![resource])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(_, limit, group)| (limit, group))
+        .collect()
+}
+
+/// Event for a resource starting to apply
+fn started(resource: &dyn Resource) -> ExecutorEvent {
+    ExecutorEvent::ResourceStarted {
+        id: resource.id(),
+        resource_type: resource.resource_type().to_string(),
+        description: resource.description(),
     }
+}
 
-    Ok(results.into_iter().map(|(_, r)| r).collect())
+/// Event for a resource finishing
+fn finished(resource: &dyn Resource, result: &ApplyResult, duration: Duration) -> ExecutorEvent {
+    ExecutorEvent::ResourceFinished {
+        id: resource.id(),
+        resource_type: resource.resource_type().to_string(),
+        result: result.clone(),
+        duration,
+    }
+}
+
+/// Report a resource event through both the per-resource callbacks and
+/// [`ProgressCallback::on_event`]
+fn report<P: ProgressCallback>(progress: &mut P, event: ExecutorEvent) {
+    match &event {
+        ExecutorEvent::ResourceStarted {
+            id, description, ..
+        } => progress.on_resource_start(id, description),
+        ExecutorEvent::ResourceFinished { id, result, .. } => {
+            progress.on_resource_complete(id, result);
+        }
+        _ => {}
+    }
+    progress.on_event(&event);
 }
 
 /// Apply a single resource
+///
+/// Returns the final result and the number of attempts.
 fn apply_resource(
-    resource: &dyn Resource,
+    resource: &SharedResource,
+    opts: &ExecuteOptions,
+    env: Env<'_>,
+) -> (ApplyResult, u32) {
+    apply_with_retry(
+        resource,
+        env.sudo,
+        env.variables,
+        env.audit,
+        opts.verbose,
+        resource.timeout().or(opts.timeout),
+        &opts.retry,
+    )
+}
+
+/// Apply a resource with [`apply_with_timeout`], retrying failures per
+/// `retry`
+///
+/// A timeout isn't retried: the abandoned attempt may still be running, and
+/// a second one would race it. Returns the final result and the number of
+/// attempts.
+pub fn apply_with_retry(
+    resource: &Arc<dyn Resource>,
+    sudo: Option<&Arc<dyn SudoProvider>>,
+    variables: &Arc<Variables>,
+    audit: Option<&Arc<AuditLog>>,
+    verbose: bool,
+    timeout: Option<Duration>,
+    retry: &RetryPolicy,
+) -> (ApplyResult, u32) {
+    let timed_out = Cell::new(false);
+    retry.run_while(
+        resource.resource_type(),
+        || {
+            let (result, expired) = apply_timed(resource, sudo, variables, audit, verbose, timeout);
+            timed_out.set(expired);
+            result
+        },
+        |_| !timed_out.get(),
+    )
+}
+
+/// Apply a resource, giving up on it after `timeout`
+///
+/// With a timeout, the resource applies on a thread of its own. One that
+/// times out is abandoned rather than stopped: it fails, but its thread and
+/// any command it started keep running in the background.
+pub fn apply_with_timeout(
+    resource: &Arc<dyn Resource>,
+    sudo: Option<&Arc<dyn SudoProvider>>,
+    variables: &Arc<Variables>,
+    audit: Option<&Arc<AuditLog>>,
+    verbose: bool,
+    timeout: Option<Duration>,
+) -> ApplyResult {
+    apply_timed(resource, sudo, variables, audit, verbose, timeout).0
+}
+
+/// [`apply_with_timeout`], also telling whether the resource timed out
+fn apply_timed(
+    resource: &Arc<dyn Resource>,
+    sudo: Option<&Arc<dyn SudoProvider>>,
+    variables: &Arc<Variables>,
+    audit: Option<&Arc<AuditLog>>,
     verbose: bool,
+    timeout: Option<Duration>,
+) -> (ApplyResult, bool) {
+    let Some(timeout) = timeout else {
+        let result = apply_now(
+            resource.as_ref(),
+            sudo.map(AsRef::as_ref),
+            variables,
+            audit.map(AsRef::as_ref),
+            verbose,
+        );
+        return (result, false);
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let resource = Arc::clone(resource);
+    let sudo = sudo.cloned();
+    let variables = Arc::clone(variables);
+    let audit = audit.cloned();
+    let spawned = std::thread::Builder::new()
+        .name(format!("apply {}", resource.id()))
+        .spawn(move || {
+            let _ = tx.send(apply_now(
+                resource.as_ref(),
+                sudo.as_deref(),
+                &variables,
+                audit.as_deref(),
+                verbose,
+            ));
+        });
+    if let Err(e) = spawned {
+        let result = ApplyResult::Failed {
+            error: format!("Failed to start applying: {e}"),
+        };
+        return (result, false);
+    }
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => (result, false),
+        Err(RecvTimeoutError::Timeout) => {
+            let result = ApplyResult::Failed {
+                error: format!("Timed out after {}s", timeout.as_secs_f64()),
+            };
+            (result, true)
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            let result = ApplyResult::Failed {
+                error: "Apply panicked".to_string(),
+            };
+            (result, false)
+        }
+    }
+}
+
+/// Apply a resource on the current thread
+fn apply_now(
+    resource: &dyn Resource,
     sudo: Option<&dyn SudoProvider>,
+    variables: &Variables,
+    audit: Option<&AuditLog>,
+    verbose: bool,
 ) -> ApplyResult {
-    let mut ctx = match sudo {
+    let ctx = match sudo {
         Some(s) => ApplyContext::with_sudo(false, verbose, s),
         None => ApplyContext::new(false, verbose),
     };
+    let mut ctx = ctx.with_variables(variables);
 
-    match resource.apply(&mut ctx) {
-        Ok(result) => result,
-        Err(e) => ApplyResult::Failed {
+    // The state the plan's diff found, if the executor remembered it
+    let old_state = audit.and_then(|audit| audit.state_before(&cache_key(resource)));
+    audited(resource, &mut ctx, audit, old_state, |ctx| {
+        resource.apply(ctx).unwrap_or_else(|e| ApplyResult::Failed {
             error: e.to_string(),
-        },
-    }
+        })
+    })
+}
+
+/// Make a change to a resource, recording it in the audit log, if any
+///
+/// The state before is `old_state` when known, and is otherwise read
+/// (only when there's a log to record it in). The state after is only read
+/// when the log asks for it (see [`AuditLog::records_new_state`]).
+fn audited(
+    resource: &dyn Resource,
+    ctx: &mut ApplyContext,
+    audit: Option<&AuditLog>,
+    old_state: Option<ResourceState>,
+    change: impl FnOnce(&mut ApplyContext) -> ApplyResult,
+) -> ApplyResult {
+    let Some(audit) = audit else {
+        return change(ctx);
+    };
+
+    let old_state = old_state.or_else(|| resource.current_state().ok());
+    let result = change(ctx);
+    let new_state = if audit.records_new_state() {
+        resource.current_state().ok()
+    } else {
+        None
+    };
+    let entry = AuditEntry {
+        timestamp: Utc::now(),
+        user: current_user(),
+        resource_type: resource.resource_type().to_string(),
+        resource_id: resource.id(),
+        old_state,
+        new_state,
+        result: result.clone(),
+        outputs: ctx.output_hashes(),
+    };
+    // A change that can't be logged has still been made
+    let _ = audit.record(&entry);
+    result
 }
 
 /// Simple execution without callbacks
 ///
 /// For basic use cases where you don't need progress or confirmation.
-pub fn execute_simple<S: SudoProvider>(
+pub fn execute_simple<S: SudoProvider + 'static>(
     plan: ExecutionPlan,
     opts: ExecuteOptions,
     sudo_provider: impl FnOnce() -> Result<S>,
@@ -188,8 +888,13 @@ pub fn execute_simple<S: SudoProvider>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::action::PlannedAction;
     use crate::context::{AutoConfirm, NoProgress};
+    use crate::testing::{Concurrency, MockResource};
     use crate::types::{CommandOutput, ResourceState};
+    use std::collections::BTreeMap;
+    use std::sync::atomic::Ordering;
+    use std::sync::{Arc, Mutex};
 
     /// Mock sudo provider for tests
     struct MockSudo;
@@ -204,51 +909,6 @@ mod tests {
         }
     }
 
-    #[derive(Debug)]
-    struct TestResource {
-        id: String,
-        should_change: bool,
-    }
-
-    impl Resource for TestResource {
-        fn id(&self) -> String {
-            self.id.clone()
-        }
-
-        fn description(&self) -> String {
-            format!("Test resource {}", self.id)
-        }
-
-        fn resource_type(&self) -> &'static str {
-            "test"
-        }
-
-        fn current_state(&self) -> Result<ResourceState> {
-            if self.should_change {
-                Ok(ResourceState::Absent)
-            } else {
-                Ok(ResourceState::Present { details: None })
-            }
-        }
-
-        fn desired_state(&self) -> ResourceState {
-            ResourceState::Present { details: None }
-        }
-
-        fn apply(&self, ctx: &mut ApplyContext) -> Result<ApplyResult> {
-            if ctx.dry_run {
-                return Ok(ApplyResult::Skipped {
-                    reason: "Dry run".into(),
-                });
-            }
-            if self.should_change {
-                Ok(ApplyResult::Created)
-            } else {
-                Ok(ApplyResult::NoChange)
-            }
-        }
-    }
-
     #[test]
     fn test_execute_empty_plan() {
         let plan = ExecutionPlan::new();
@@ -268,10 +928,8 @@ mod tests {
     #[test]
     fn test_execute_no_changes() {
         let mut plan = ExecutionPlan::new();
-        plan.unprivileged.push(Box::new(TestResource {
-            id: "test1".into(),
-            should_change: false,
-        }));
+        plan.unprivileged
+            .push(Box::new(MockResource::new("test1").converged()));
 
         let opts = ExecuteOptions::default();
         let result = execute(
@@ -290,10 +948,7 @@ mod tests {
     #[test]
     fn test_execute_with_changes() {
         let mut plan = ExecutionPlan::new();
-        plan.unprivileged.push(Box::new(TestResource {
-            id: "test1".into(),
-            should_change: true,
-        }));
+        plan.unprivileged.push(Box::new(MockResource::new("test1")));
 
         let opts = ExecuteOptions::default();
         let result = execute(
@@ -307,4 +962,630 @@ mod tests {
 
         assert_eq!(result.created, 1);
     }
+
+    #[test]
+    fn test_execute_dry_run_collects_planned_actions() {
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged.push(Box::new(
+            MockResource::new("test1").with_plan(vec![PlannedAction::write_file("/tmp/test1")]),
+        ));
+        plan.unprivileged
+            .push(Box::new(MockResource::new("test2").converged()));
+
+        let opts = ExecuteOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        let summary = execute(
+            plan,
+            opts,
+            || -> Result<MockSudo> { Ok(MockSudo) },
+            &mut NoProgress,
+            &mut AutoConfirm,
+        )
+        .unwrap();
+
+        assert_eq!(summary.total(), 0);
+        assert_eq!(summary.planned.len(), 1);
+        assert_eq!(summary.planned[0].resource_id, "test1");
+        assert_eq!(
+            summary.planned[0].planned,
+            [PlannedAction::write_file("/tmp/test1")]
+        );
+    }
+
+    #[test]
+    fn test_execute_in_dependency_order() {
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        let resource = |id, deps: &[&str], fail| -> Box<dyn Resource> {
+            let resource = MockResource::new(id).depends_on(deps).recording(&applied);
+            Box::new(if fail { resource.failing() } else { resource })
+        };
+
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged
+            .push(resource("symlink", &["formula"], false));
+        plan.unprivileged.push(resource("broken", &[], true));
+        plan.unprivileged
+            .push(resource("dependent", &["broken"], false));
+        plan.unprivileged
+            .push(resource("transitive", &["dependent"], false));
+        plan.privileged.push(resource("formula", &["tap"], false));
+        plan.unprivileged.push(resource("tap", &[], false));
+
+        let summary = execute(
+            plan,
+            ExecuteOptions::default(),
+            || -> Result<MockSudo> { Ok(MockSudo) },
+            &mut NoProgress,
+            &mut AutoConfirm,
+        )
+        .unwrap();
+
+        let applied = applied.lock().unwrap();
+        let position = |id: &str| applied.iter().position(|a| a == id);
+        assert!(position("tap") < position("formula"));
+        assert!(position("formula") < position("symlink"));
+        // Everything downstream of a failure is skipped, not applied
+        assert_eq!(position("dependent"), None);
+        assert_eq!(position("transitive"), None);
+        assert_eq!(summary.created, 3);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.skipped, 2);
+    }
+
+    #[test]
+    fn test_execute_failure_policies() {
+        let run = |policy| {
+            let applied = Arc::new(Mutex::new(Vec::new()));
+            let mut plan = ExecutionPlan::new();
+            for (id, deps, fail) in [
+                ("broken", &[][..], true),
+                ("independent", &[], false),
+                ("dependent", &["broken"], false),
+            ] {
+                let resource = MockResource::new(id).depends_on(deps).recording(&applied);
+                plan.unprivileged
+                    .push(Box::new(if fail { resource.failing() } else { resource }));
+            }
+            let opts = ExecuteOptions {
+                jobs: 1,
+                ..Default::default()
+            }
+            .on_failure(policy);
+            let summary =
+                execute_simple(plan, opts, || -> Result<MockSudo> { Ok(MockSudo) }).unwrap();
+            let applied = applied.lock().unwrap().clone();
+            (applied, summary.outcomes)
+        };
+        let skipped = |outcomes: &[ResourceOutcome], id: &str| {
+            outcomes
+                .iter()
+                .find(|o| o.resource_id == id)
+                .and_then(|o| match &o.result {
+                    ApplyResult::Skipped { reason } => Some(reason.clone()),
+                    _ => None,
+                })
+        };
+
+        let (applied, outcomes) = run(FailurePolicy::Stop);
+        assert_eq!(applied, ["broken"]);
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(skipped(&outcomes, "independent").as_deref(), Some(STOPPED));
+        assert_eq!(skipped(&outcomes, "dependent").as_deref(), Some(STOPPED));
+
+        let (applied, outcomes) = run(FailurePolicy::ContinueIndependent);
+        assert_eq!(applied, ["broken", "independent"]);
+        assert_eq!(skipped(&outcomes, "independent"), None);
+        assert_eq!(
+            skipped(&outcomes, "dependent").as_deref(),
+            Some("Dependency broken failed")
+        );
+
+        let (applied, outcomes) = run(FailurePolicy::BestEffort);
+        assert_eq!(applied, ["broken", "independent", "dependent"]);
+        assert!(matches!(outcomes[0].result, ApplyResult::Failed { .. }));
+        assert_eq!(outcomes[2].result, ApplyResult::Created);
+    }
+
+    #[test]
+    fn test_execute_rejects_cycles() {
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        let mut plan = ExecutionPlan::new();
+        for (id, deps) in [("a", ["b"]), ("b", ["a"])] {
+            plan.unprivileged.push(Box::new(
+                MockResource::new(id).depends_on(&deps).recording(&applied),
+            ));
+        }
+
+        let result = execute_simple(plan, ExecuteOptions::default(), || -> Result<MockSudo> {
+            Ok(MockSudo)
+        });
+        assert!(result.is_err());
+        assert!(applied.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_execute_rolls_back_on_failure() {
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        let resource = |id, deps: &[&str], fail, revertible| -> Box<dyn Resource> {
+            let mut resource = MockResource::new(id).depends_on(deps).recording(&applied);
+            if fail {
+                resource = resource.failing();
+            }
+            if revertible {
+                resource = resource.revertible();
+            }
+            Box::new(resource)
+        };
+
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged.push(resource("a", &[], false, true));
+        plan.unprivileged.push(resource("b", &["a"], false, false));
+        plan.unprivileged.push(resource("c", &["b"], true, true));
+        plan.unprivileged.push(resource("d", &["c"], false, true));
+
+        let opts = ExecuteOptions {
+            rollback: true,
+            ..Default::default()
+        };
+        let summary = execute_simple(plan, opts, || -> Result<MockSudo> { Ok(MockSudo) }).unwrap();
+
+        assert_eq!(*applied.lock().unwrap(), ["a", "b", "c", "revert a"]);
+        assert_eq!(summary.rolled_back, ["a"]);
+        assert_eq!(summary.rollback_failed, ["b"]);
+        assert!(summary.was_rolled_back());
+        assert_eq!(summary.created, 2);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.skipped, 1);
+    }
+
+    #[test]
+    fn test_execute_skips_sudo_after_an_unprivileged_failure() {
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged.push(Box::new(
+            MockResource::new("broken").failing().recording(&applied),
+        ));
+        plan.privileged
+            .push(Box::new(MockResource::new("system").recording(&applied)));
+
+        let opts = ExecuteOptions {
+            rollback: true,
+            ..Default::default()
+        };
+        let summary = execute_simple(plan, opts, || -> Result<MockSudo> {
+            panic!("sudo acquired after a failure that stops execution")
+        })
+        .unwrap();
+
+        assert_eq!(*applied.lock().unwrap(), ["broken"]);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.skipped, 1);
+    }
+
+    #[test]
+    fn test_execute_emits_events() {
+        let resource = |id, deps: &[&str], fail| -> Box<dyn Resource> {
+            let resource = MockResource::new(id).depends_on(deps);
+            Box::new(if fail { resource.failing() } else { resource })
+        };
+
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged.push(resource("a", &[], false));
+        plan.unprivileged.push(resource("b", &[], true));
+        plan.unprivileged.push(resource("c", &["b"], false));
+        plan.privileged.push(resource("d", &["a"], false));
+
+        let (tx, rx) = mpsc::channel();
+        let mut progress = tx;
+        let summary = execute(
+            plan,
+            ExecuteOptions {
+                jobs: 4,
+                ..Default::default()
+            },
+            || -> Result<MockSudo> { Ok(MockSudo) },
+            &mut progress,
+            &mut AutoConfirm,
+        )
+        .unwrap();
+        drop(progress);
+        let events: Vec<ExecutorEvent> = rx.into_iter().collect();
+
+        assert_eq!(
+            events.first(),
+            Some(&ExecutorEvent::PlanStarted {
+                resources: 4,
+                changes: 4
+            })
+        );
+        assert!(matches!(
+            events.last(),
+            Some(ExecutorEvent::PlanFinished { summary: s, .. }) if *s == summary
+        ));
+        let started = |id: &str| {
+            events
+                .iter()
+                .position(|e| matches!(e, ExecutorEvent::ResourceStarted { id: i, .. } if i == id))
+        };
+        let finished = |id: &str| {
+            events.iter().find_map(|e| match e {
+                ExecutorEvent::ResourceFinished { id: i, result, .. } if i == id => {
+                    Some(result.clone())
+                }
+                _ => None,
+            })
+        };
+        // Parallel resources report as they run, not after the batch
+        assert!(started("a").is_some() && started("b").is_some());
+        assert!(!finished("b").unwrap().is_success());
+        // A skipped dependent finishes without starting
+        assert_eq!(started("c"), None);
+        assert!(matches!(finished("c"), Some(ApplyResult::Skipped { .. })));
+        let sudo = events
+            .iter()
+            .position(|e| matches!(e, ExecutorEvent::SudoRequested { resources: 1 }));
+        assert!(sudo.is_some() && sudo < started("d"));
+        assert_eq!(finished("d"), Some(ApplyResult::Created));
+    }
+
+    #[test]
+    fn test_execute_retries_per_resource_type() {
+        let flaky = |id, resource_type, failures| -> Box<dyn Resource> {
+            Box::new(
+                MockResource::new(id)
+                    .of_type(resource_type)
+                    .failing_first(failures),
+            )
+        };
+
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged.push(flaky("ripgrep", "brew_formula", 2));
+        plan.unprivileged.push(flaky("fd", "brew_formula", 5));
+        plan.unprivileged.push(flaky("~/.zshrc", "symlink", 1));
+        plan.unprivileged.push(flaky("~/.gitconfig", "symlink", 0));
+
+        let opts = ExecuteOptions {
+            retry: RetryPolicy::attempts(1)
+                .with_backoff(Duration::ZERO)
+                .with_override("brew_formula", 3),
+            ..Default::default()
+        };
+        let summary = execute_simple(plan, opts, || -> Result<MockSudo> { Ok(MockSudo) }).unwrap();
+
+        assert_eq!(summary.created, 2);
+        assert_eq!(summary.failed, 2);
+        // Symlinks aren't retried; brew formulae get three attempts
+        assert_eq!(
+            summary.attempts,
+            BTreeMap::from([("fd".to_string(), 3), ("ripgrep".to_string(), 3)])
+        );
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy::attempts(2)
+            .with_backoff(Duration::from_millis(100))
+            .with_override("symlink", 0);
+
+        assert_eq!(policy.max_attempts_for("brew_cask"), 2);
+        assert_eq!(policy.max_attempts_for("symlink"), 1);
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_execute_limits_concurrency_per_type() {
+        let brew = Arc::new(Concurrency::default());
+        let links = Arc::new(Concurrency::default());
+        let counted = |id: String, resource_type, concurrency| -> Box<dyn Resource> {
+            Box::new(
+                MockResource::new(id)
+                    .of_type(resource_type)
+                    .with_delay(Duration::from_millis(50))
+                    .counted(concurrency),
+            )
+        };
+
+        let mut plan = ExecutionPlan::new();
+        for i in 0..4 {
+            plan.unprivileged
+                .push(counted(format!("formula{i}"), "brew_formula", &brew));
+            plan.unprivileged
+                .push(counted(format!("link{i}"), "symlink", &links));
+        }
+
+        let opts = ExecuteOptions::default().with_concurrency("brew_formula", 1);
+        let summary = execute_simple(plan, opts, || -> Result<MockSudo> { Ok(MockSudo) }).unwrap();
+
+        assert_eq!(summary.created, 8);
+        assert_eq!(brew.peak.load(Ordering::SeqCst), 1);
+        // Symlinks didn't queue behind the formulas
+        assert!(links.peak.load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn test_execute_shares_concurrency_within_a_group() {
+        let concurrency = Arc::new(Concurrency::default());
+
+        let mut plan = ExecutionPlan::new();
+        for (i, resource_type) in ["brew_formula", "brew_cask", "brew_tap"]
+            .iter()
+            .cycle()
+            .take(6)
+            .enumerate()
+        {
+            plan.unprivileged.push(Box::new(
+                MockResource::new(format!("package{i}"))
+                    .of_type(resource_type)
+                    .with_delay(Duration::from_millis(50))
+                    .counted(&concurrency),
+            ));
+        }
+
+        let opts = ExecuteOptions::default().with_shared_concurrency(
+            "brew",
+            &["brew_formula", "brew_cask", "brew_tap"],
+            1,
+        );
+        let summary = execute_simple(plan, opts, || -> Result<MockSudo> { Ok(MockSudo) }).unwrap();
+
+        assert_eq!(summary.created, 6);
+        // One at a time across all three types
+        assert_eq!(concurrency.peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_execute_times_out_hung_resources() {
+        let slow = |id, delay, timeout: Option<u64>| -> Box<dyn Resource> {
+            let resource = MockResource::new(id).with_delay(Duration::from_millis(delay));
+            Box::new(match timeout {
+                Some(timeout) => resource.with_timeout(Duration::from_millis(timeout)),
+                None => resource,
+            })
+        };
+
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged.push(slow("hung", 10_000, None));
+        plan.unprivileged.push(slow("patient", 300, Some(5_000)));
+        plan.unprivileged.push(slow("quick", 0, None));
+
+        let opts = ExecuteOptions {
+            jobs: 1,
+            timeout: Some(Duration::from_millis(100)),
+            retry: RetryPolicy::attempts(3).with_backoff(Duration::ZERO),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let (tx, rx) = mpsc::channel();
+        let mut progress = tx;
+        let summary = execute(
+            plan,
+            opts,
+            || -> Result<MockSudo> { Ok(MockSudo) },
+            &mut progress,
+            &mut AutoConfirm,
+        )
+        .unwrap();
+        drop(progress);
+
+        // The hung resource is abandoned rather than awaited, and not retried
+        // while the abandoned attempt may still be running
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.created, 2);
+        assert!(summary.attempts.is_empty());
+        let hung = rx.into_iter().find_map(|e| match e {
+            ExecutorEvent::ResourceFinished { id, result, .. } if id == "hung" => Some(result),
+            _ => None,
+        });
+        assert_eq!(
+            hung,
+            Some(ApplyResult::Failed {
+                error: "Timed out after 0.1s".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_execute_skips_unmet_preconditions() {
+        use crate::condition::{Condition, Conditional};
+
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        let resource =
+            |id| -> Box<dyn Resource> { Box::new(MockResource::new(id).recording(&applied)) };
+
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged.push(Box::new(
+            Conditional::new(resource("work-vpn"))
+                .only_if(Condition::env_set("BOSSA_TEST_UNSET_VARIABLE")),
+        ));
+        plan.unprivileged.push(resource("ripgrep"));
+
+        let summary = execute_simple(plan, ExecuteOptions::default(), || -> Result<MockSudo> {
+            Ok(MockSudo)
+        })
+        .unwrap();
+
+        assert_eq!(*applied.lock().unwrap(), ["ripgrep"]);
+        assert_eq!(summary.created, 1);
+        assert_eq!(summary.skipped, 1);
+    }
+
+    #[test]
+    fn test_execute_resumes_from_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.json");
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        let plan = |fail_docker| {
+            let mut plan = ExecutionPlan::new();
+            for (id, fail) in [("ripgrep", false), ("docker", fail_docker)] {
+                let resource = MockResource::new(id).recording(&applied);
+                plan.unprivileged
+                    .push(Box::new(if fail { resource.failing() } else { resource }));
+            }
+            plan
+        };
+        let opts = ExecuteOptions {
+            jobs: 1,
+            ..Default::default()
+        }
+        .resume_from(&path);
+
+        // Interrupted: ripgrep converged, docker didn't
+        let summary = execute_simple(plan(true), opts.clone(), || -> Result<MockSudo> {
+            Ok(MockSudo)
+        })
+        .unwrap();
+        assert_eq!(summary.failed, 1);
+        assert!(
+            Checkpoint::load(&path)
+                .unwrap()
+                .is_converged("test:ripgrep")
+        );
+
+        applied.lock().unwrap().clear();
+        let summary =
+            execute_simple(plan(false), opts, || -> Result<MockSudo> { Ok(MockSudo) }).unwrap();
+        assert_eq!(*applied.lock().unwrap(), ["docker"]);
+        assert_eq!(summary.created, 1);
+        assert_eq!(summary.resumed, 1);
+        // Everything converged, so the next run starts over
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_execute_expands_variables() {
+        let expanded = Arc::new(Mutex::new(Vec::new()));
+        let mut plan = ExecutionPlan::new();
+        plan.variables = Variables::new().with("hostname", "studio");
+        for path in ["~/.config/${hostname}.toml", "~/.config/${profile}.toml"] {
+            plan.unprivileged.push(Box::new(
+                MockResource::new(path).expanding().recording(&expanded),
+            ));
+        }
+
+        let summary = execute(
+            plan,
+            // Applied on a thread of its own when there's a timeout
+            ExecuteOptions {
+                timeout: Some(Duration::from_secs(5)),
+                ..Default::default()
+            },
+            || -> Result<MockSudo> { Ok(MockSudo) },
+            &mut NoProgress,
+            &mut AutoConfirm,
+        )
+        .unwrap();
+
+        assert_eq!(*expanded.lock().unwrap(), ["~/.config/studio.toml"]);
+        assert_eq!(summary.created, 1);
+        assert_eq!(summary.failed, 1);
+    }
+
+    #[test]
+    fn test_execute_writes_audit_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged
+            .push(Box::new(MockResource::new("tap").revertible()));
+        plan.unprivileged.push(Box::new(
+            MockResource::new("formula")
+                .depends_on(&["tap"])
+                .failing()
+                .revertible(),
+        ));
+
+        let opts = ExecuteOptions {
+            rollback: true,
+            ..Default::default()
+        }
+        .audit_log(&path);
+        execute(
+            plan,
+            opts,
+            || -> Result<MockSudo> { Ok(MockSudo) },
+            &mut NoProgress,
+            &mut AutoConfirm,
+        )
+        .unwrap();
+
+        let entries = AuditLog::read(&path).unwrap();
+        let logged: Vec<(&str, &ApplyResult)> = entries
+            .iter()
+            .map(|entry| (entry.resource_id.as_str(), &entry.result))
+            .collect();
+        assert_eq!(
+            logged,
+            [
+                ("tap", &ApplyResult::Created),
+                (
+                    "formula",
+                    &ApplyResult::Failed {
+                        error: "formula failed".to_string()
+                    }
+                ),
+                ("tap", &ApplyResult::Removed),
+            ]
+        );
+        assert_eq!(entries[0].old_state, Some(ResourceState::Absent));
+        assert_eq!(entries[0].new_state, None);
+        assert_eq!(
+            entries[2].old_state,
+            Some(ResourceState::Present { details: None })
+        );
+    }
+
+    #[test]
+    fn test_audit_log_reuses_the_diff_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let run = |opts: ExecuteOptions| {
+            let resource = MockResource::new("tap");
+            let checks = resource.check_counter();
+            let mut plan = ExecutionPlan::new();
+            plan.unprivileged.push(Box::new(resource));
+            execute(
+                plan,
+                opts.audit_log(&path),
+                || -> Result<MockSudo> { Ok(MockSudo) },
+                &mut NoProgress,
+                &mut AutoConfirm,
+            )
+            .unwrap();
+            checks.load(Ordering::SeqCst)
+        };
+
+        // Only the diff reads the state, unless the state after is asked for
+        assert_eq!(run(ExecuteOptions::default()), 1);
+        assert_eq!(run(ExecuteOptions::default().audit_new_state()), 2);
+
+        let entries = AuditLog::read(&path).unwrap();
+        assert_eq!(entries[0].new_state, None);
+        assert_eq!(entries[1].new_state, Some(ResourceState::Absent));
+    }
+
+    #[test]
+    fn test_execute_invalidates_state_cache() {
+        use crate::cache::{MemoryStateCache, StateCache};
+
+        let cache = Arc::new(MemoryStateCache::new(std::time::Duration::from_secs(60)));
+        cache.insert("test:untouched", ResourceState::Absent);
+
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged.push(Box::new(MockResource::new("test1")));
+
+        let opts = ExecuteOptions {
+            state_cache: Some(Arc::clone(&cache) as Arc<dyn StateCache>),
+            ..Default::default()
+        };
+        let summary = execute_simple(plan, opts, || -> Result<MockSudo> { Ok(MockSudo) }).unwrap();
+
+        assert_eq!(summary.created, 1);
+        // The diff cached test1's state; applying it dropped that entry
+        assert_eq!(cache.get("test:test1"), None);
+        assert_eq!(cache.get("test:untouched"), Some(ResourceState::Absent));
+    }
 }
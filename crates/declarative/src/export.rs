@@ -0,0 +1,176 @@
+//! Summary export - execution results for CI
+//!
+//! [`ExecuteSummary::to_json`] and [`ExecuteSummary::to_junit_xml`] turn a
+//! summary into files CI systems understand. In JUnit terms every resource
+//! is a test case: failed resources fail, skipped ones are skipped. On a dry
+//! run, every change that would be made fails too, so publishing a dry run
+//! of a machine that has drifted from its configuration fails the build.
+
+use crate::types::{ApplyResult, ExecuteSummary};
+use anyhow::Result;
+use std::fmt::Write;
+
+impl ExecuteSummary {
+    /// The summary as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// The summary as a JUnit XML report
+    pub fn to_junit_xml(&self) -> String {
+        let mut cases = String::new();
+        let (mut failures, mut skipped) = (0, 0);
+        for outcome in &self.outcomes {
+            let body = match &outcome.result {
+                ApplyResult::Failed { error } => {
+                    failures += 1;
+                    format!("<failure message=\"{}\"/>", escape(error))
+                }
+                ApplyResult::Skipped { reason } => {
+                    skipped += 1;
+                    format!("<skipped message=\"{}\"/>", escape(reason))
+                }
+                _ => String::new(),
+            };
+            write_case(
+                &mut cases,
+                &outcome.resource_type,
+                &outcome.resource_id,
+                &body,
+            );
+        }
+        for diff in &self.planned {
+            failures += 1;
+            let actions: Vec<String> = diff.planned.iter().map(ToString::to_string).collect();
+            let body = format!(
+                "<failure message=\"Would apply: {}\">{}</failure>",
+                escape(&diff.description),
+                escape(&actions.join("\n"))
+            );
+            write_case(&mut cases, &diff.resource_type, &diff.resource_id, &body);
+        }
+
+        let tests = self.outcomes.len() + self.planned.len();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuites tests=\"{tests}\" failures=\"{failures}\" skipped=\"{skipped}\">"
+        );
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"execute\" tests=\"{tests}\" failures=\"{failures}\" skipped=\"{skipped}\">"
+        );
+        xml.push_str(&cases);
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+/// Append a test case for a resource, with an optional failure or skip
+fn write_case(xml: &mut String, resource_type: &str, resource_id: &str, body: &str) {
+    let _ = write!(
+        xml,
+        "    <testcase classname=\"{}\" name=\"{}\"",
+        escape(resource_type),
+        escape(resource_id)
+    );
+    if body.is_empty() {
+        xml.push_str("/>\n");
+    } else {
+        let _ = writeln!(xml, ">{body}</testcase>");
+    }
+}
+
+/// Escape text for an XML attribute or element
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::PlannedAction;
+    use crate::diff::ResourceDiff;
+    use crate::types::{ResourceOutcome, ResourceState};
+
+    fn outcome(resource_id: &str, result: ApplyResult) -> ResourceOutcome {
+        ResourceOutcome {
+            resource_type: "brew_formula".to_string(),
+            resource_id: resource_id.to_string(),
+            result,
+        }
+    }
+
+    #[test]
+    fn test_to_junit_xml() {
+        let summary = ExecuteSummary {
+            created: 1,
+            failed: 1,
+            skipped: 1,
+            outcomes: vec![
+                outcome("ripgrep", ApplyResult::Created),
+                outcome(
+                    "fd",
+                    ApplyResult::Failed {
+                        error: "Error: <fd> not found".to_string(),
+                    },
+                ),
+                outcome(
+                    "bat",
+                    ApplyResult::Skipped {
+                        reason: "Dependency fd failed".to_string(),
+                    },
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let xml = summary.to_junit_xml();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<testsuites tests=\"3\" failures=\"1\" skipped=\"1\">"));
+        assert!(xml.contains("<testcase classname=\"brew_formula\" name=\"ripgrep\"/>"));
+        assert!(xml.contains("<failure message=\"Error: &lt;fd&gt; not found\"/>"));
+        assert!(xml.contains("<skipped message=\"Dependency fd failed\"/>"));
+        assert!(xml.ends_with("</testsuites>\n"));
+    }
+
+    #[test]
+    fn test_dry_run_changes_fail() {
+        let summary = ExecuteSummary {
+            planned: vec![ResourceDiff {
+                resource_id: "com.apple.dock.autohide".to_string(),
+                resource_type: "macos_default".to_string(),
+                description: "Set autohide to true".to_string(),
+                current: ResourceState::Absent,
+                desired: ResourceState::Present { details: None },
+                requires_sudo: false,
+                skipped: None,
+                planned: vec![PlannedAction::run(
+                    "defaults",
+                    &["write", "com.apple.dock", "autohide", "-bool", "true"],
+                )],
+            }],
+            ..Default::default()
+        };
+
+        let xml = summary.to_junit_xml();
+        assert!(xml.contains("failures=\"1\""));
+        assert!(xml.contains(
+            "<failure message=\"Would apply: Set autohide to true\">defaults write com.apple.dock autohide -bool true</failure>"
+        ));
+
+        let json: serde_json::Value = serde_json::from_str(&summary.to_json().unwrap()).unwrap();
+        assert_eq!(json["planned"][0]["resource_id"], "com.apple.dock.autohide");
+    }
+}
@@ -0,0 +1,122 @@
+//! Import - adopt what's already on a machine instead of overwriting it
+//!
+//! [`import`] compares a plan with the system without changing anything.
+//! Resources already in their desired state are adopted as they are. For
+//! the others, [`Resource::adopt`] can suggest a configuration entry that
+//! would make what's there the desired state, e.g. the value a default is
+//! already set to; the rest are left for a normal run to change.
+
+use crate::cache::cache_key;
+use crate::diff::ResourceDiff;
+use crate::planner::ExecutionPlan;
+use crate::resource::Resource;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A configuration entry that would adopt a resource's current state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigSuggestion {
+    pub resource_type: String,
+    pub resource_id: String,
+    /// Section of the configuration the entry belongs in, e.g. "defaults"
+    pub section: String,
+    /// The entry itself, in the configuration's syntax
+    pub entry: String,
+}
+
+impl fmt::Display for ConfigSuggestion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]\n{}", self.section, self.entry)
+    }
+}
+
+/// What [`import`] found
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Resources already in their desired state, by
+    /// [`cache_key`](crate::cache_key)
+    pub adopted: Vec<String>,
+    /// Entries that would adopt the current state of resources that differ
+    pub suggestions: Vec<ConfigSuggestion>,
+    /// Resources that differ and can't be adopted, which applying would change
+    pub pending: Vec<ResourceDiff>,
+}
+
+impl ImportReport {
+    /// Suggestions grouped by section, in the order sections first appear
+    pub fn by_section(&self) -> Vec<(&str, Vec<&ConfigSuggestion>)> {
+        let mut sections: Vec<(&str, Vec<&ConfigSuggestion>)> = Vec::new();
+        for suggestion in &self.suggestions {
+            match sections
+                .iter_mut()
+                .find(|(section, _)| *section == suggestion.section)
+            {
+                Some((_, suggestions)) => suggestions.push(suggestion),
+                None => sections.push((&suggestion.section, vec![suggestion])),
+            }
+        }
+        sections
+    }
+}
+
+/// Compare a plan with the system, adopting existing state where possible
+///
+/// Nothing is applied. Resources skipped by their precondition, and those
+/// whose state can't be read, are left out.
+pub fn import(plan: &ExecutionPlan) -> ImportReport {
+    let mut report = ImportReport::default();
+    for resource in plan.unprivileged.iter().chain(&plan.privileged) {
+        let resource: &dyn Resource = resource.as_ref();
+        match ResourceDiff::from_resource(resource) {
+            Ok(None) => report.adopted.push(cache_key(resource)),
+            Ok(Some(diff)) if diff.is_skipped() => {}
+            Ok(Some(diff)) => match resource.adopt() {
+                Some(suggestion) => report.suggestions.push(suggestion),
+                None => report.pending.push(diff),
+            },
+            Err(_) => {}
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockResource, present};
+    use crate::types::ResourceState;
+
+    #[test]
+    fn test_import() {
+        let mut plan = ExecutionPlan::new();
+        for (key, current) in [
+            ("autohide", Some("true")),
+            ("show-recents", Some("false")),
+            ("magnification", None),
+        ] {
+            let id = format!("com.apple.dock.{key}");
+            let mut resource = MockResource::new(&id)
+                .of_type("macos_default")
+                .with_desired(present("true"))
+                .with_current(current.map_or(ResourceState::Absent, present));
+            if let Some(value) = current {
+                resource = resource.adoptable("defaults", format!("\"{id}\" = {value}"));
+            }
+            plan.unprivileged.push(Box::new(resource));
+        }
+
+        let report = import(&plan);
+        assert_eq!(report.adopted, ["macos_default:com.apple.dock.autohide"]);
+        assert_eq!(report.suggestions.len(), 1);
+        assert_eq!(
+            report.suggestions[0].to_string(),
+            "[defaults]\n\"com.apple.dock.show-recents\" = false"
+        );
+        assert_eq!(report.pending.len(), 1);
+        assert_eq!(
+            report.pending[0].resource_id,
+            "com.apple.dock.magnification"
+        );
+        assert_eq!(report.by_section()[0].0, "defaults");
+    }
+}
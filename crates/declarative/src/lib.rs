@@ -9,7 +9,8 @@
 //!
 //! - **Resource**: Something with state that can be managed (files, packages, settings)
 //! - **ResourceState**: The current or desired state of a resource
-//! - **ExecutionPlan**: A plan grouping resources by privilege level
+//! - **ExecutionPlan**: A plan grouping resources by privilege level, run in
+//!   dependency order (see [`Resource::dependencies`])
 //! - **Executor**: Applies resources with parallelism and privilege batching
 //!
 //! ## Example
@@ -74,22 +75,50 @@
 //! This allows the crate to be used without hard dependencies on
 //! specific UI frameworks, sudo implementations, etc.
 
+pub mod action;
+pub mod audit;
+pub mod cache;
+pub mod checkpoint;
+pub mod condition;
 pub mod context;
 pub mod diff;
+pub mod events;
 pub mod executor;
+pub mod export;
+pub mod import;
 pub mod planner;
+pub mod render;
 pub mod resource;
+pub mod sudo;
+pub mod tags;
+#[cfg(test)]
+pub(crate) mod testing;
 pub mod types;
+pub mod variables;
+pub mod watch;
 
 // Re-export main types at crate root
+pub use action::PlannedAction;
+pub use audit::{AuditEntry, AuditLog};
+pub use cache::{MemoryStateCache, StateCache, cache_key, cached_state};
+pub use checkpoint::Checkpoint;
+pub use condition::{Condition, Conditional};
 pub use context::{
     ApplyContext, AutoConfirm, AutoDecline, ConfirmCallback, NoProgress, NoSudo, ProgressCallback,
     SudoClassifier, SudoProvider,
 };
-pub use diff::{DiffSummary, ResourceDiff, compute_diffs, group_by_type};
-pub use executor::{execute, execute_simple};
-pub use planner::ExecutionPlan;
+pub use diff::{DiffSummary, ResourceDiff, compute_diffs, compute_diffs_cached, group_by_type};
+pub use events::ExecutorEvent;
+pub use executor::{apply_with_retry, apply_with_timeout, execute, execute_simple};
+pub use import::{ConfigSuggestion, ImportReport, import};
+pub use planner::{ExecutionPlan, Stage};
+pub use render::{DiffRenderer, DiffStyle, render_planned};
 pub use resource::{BoxedResource, Resource, ResourceExt};
+pub use sudo::SudoSession;
+pub use tags::{TagFilter, Tagged};
 pub use types::{
-    ApplyResult, CommandOutput, ExecuteOptions, ExecuteSummary, ResourceState, SudoRequirement,
+    ApplyResult, CommandOutput, ExecuteOptions, ExecuteSummary, FailurePolicy, ResourceOutcome,
+    ResourceState, RetryPolicy, SudoRequirement,
 };
+pub use variables::Variables;
+pub use watch::{DriftEvent, DriftWatcher, watch};
@@ -2,6 +2,10 @@
 
 use crate::context::SudoClassifier;
 use crate::resource::{BoxedResource, Resource};
+use crate::tags::{TagFilter, Tagged};
+use crate::variables::Variables;
+use anyhow::Result;
+use std::collections::HashMap;
 
 /// An execution plan with resources grouped by privilege level
 pub struct ExecutionPlan {
@@ -11,6 +15,20 @@ pub struct ExecutionPlan {
     pub privileged: Vec<BoxedResource>,
     /// Post-apply actions (e.g., services to restart)
     pub post_actions: Vec<String>,
+    /// Variables for expanding resource fields, passed on to resources as
+    /// they apply
+    pub variables: Variables,
+}
+
+/// Resources whose dependencies are all applied by earlier stages
+///
+/// Holds indices into the plan's `unprivileged` and `privileged` lists.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stage {
+    /// Indices into `ExecutionPlan::unprivileged`
+    pub unprivileged: Vec<usize>,
+    /// Indices into `ExecutionPlan::privileged`
+    pub privileged: Vec<usize>,
 }
 
 impl ExecutionPlan {
@@ -20,6 +38,7 @@ impl ExecutionPlan {
             unprivileged: Vec::new(),
             privileged: Vec::new(),
             post_actions: Vec::new(),
+            variables: Variables::new(),
         }
     }
 
@@ -74,6 +93,7 @@ impl ExecutionPlan {
                 .filter(|r| predicate(r.as_ref()))
                 .collect(),
             post_actions: self.post_actions,
+            variables: self.variables,
         }
     }
 
@@ -90,6 +110,38 @@ impl ExecutionPlan {
         }
     }
 
+    /// Filter plan to only include resources whose tags pass `filter`
+    pub fn filter_by_tags(self, filter: &TagFilter) -> Self {
+        if filter.is_empty() {
+            return self;
+        }
+        self.filter(|r| filter.matches(&r.tags()))
+    }
+
+    /// Add tags to every resource in the plan
+    pub fn with_tags(self, tags: &[&str]) -> Self {
+        let tag = |resource: BoxedResource| -> BoxedResource {
+            Box::new(Tagged::new(resource, tags.iter().copied()))
+        };
+        Self {
+            unprivileged: self.unprivileged.into_iter().map(tag).collect(),
+            privileged: self.privileged.into_iter().map(tag).collect(),
+            post_actions: self.post_actions,
+            variables: self.variables,
+        }
+    }
+
+    /// Move another plan's resources, post-apply actions and variables into
+    /// this one
+    pub fn append(&mut self, other: ExecutionPlan) {
+        self.unprivileged.extend(other.unprivileged);
+        self.variables.extend(other.variables);
+        self.privileged.extend(other.privileged);
+        for action in other.post_actions {
+            self.add_post_action(action);
+        }
+    }
+
     /// Total number of resources in the plan
     pub fn total_resources(&self) -> usize {
         self.unprivileged.len() + self.privileged.len()
@@ -104,6 +156,92 @@ impl ExecutionPlan {
     pub fn has_privileged(&self) -> bool {
         !self.privileged.is_empty()
     }
+
+    /// Order the plan into stages by resource dependencies
+    ///
+    /// Every resource lands in the stage after the last of its dependencies,
+    /// so resources within a stage are independent of each other. A plan
+    /// without dependencies is a single stage.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the dependencies form a cycle.
+    pub fn stages(&self) -> Result<Vec<Stage>> {
+        let nodes: Vec<(bool, usize, &BoxedResource)> = self
+            .unprivileged
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (false, i, r))
+            .chain(
+                self.privileged
+                    .iter()
+                    .enumerate()
+                    .map(|(i, r)| (true, i, r)),
+            )
+            .collect();
+        let ids: Vec<String> = nodes.iter().map(|(_, _, r)| r.id()).collect();
+
+        // IDs are only unique within a type, so a dependency may match several
+        let mut by_id: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (node, id) in ids.iter().enumerate() {
+            by_id.entry(id.as_str()).or_default().push(node);
+        }
+        let dependencies: Vec<Vec<usize>> = nodes
+            .iter()
+            .enumerate()
+            .map(|(node, (_, _, resource))| {
+                let mut deps: Vec<usize> = resource
+                    .dependencies()
+                    .iter()
+                    .filter_map(|dep| by_id.get(dep.as_str()))
+                    .flatten()
+                    .copied()
+                    .filter(|&dep| dep != node)
+                    .collect();
+                deps.sort_unstable();
+                deps.dedup();
+                deps
+            })
+            .collect();
+
+        // Each resource's stage is one past its deepest dependency
+        let mut stage_of: Vec<Option<usize>> = vec![None; nodes.len()];
+        let mut placed = 0;
+        while placed < nodes.len() {
+            let ready: Vec<(usize, usize)> = (0..nodes.len())
+                .filter(|&node| stage_of[node].is_none())
+                .filter_map(|node| {
+                    dependencies[node]
+                        .iter()
+                        .map(|&dep| stage_of[dep].map(|stage| stage + 1))
+                        .try_fold(0, |deepest, stage| stage.map(|s| s.max(deepest)))
+                        .map(|stage| (node, stage))
+                })
+                .collect();
+            if ready.is_empty() {
+                anyhow::bail!(
+                    "Dependency cycle: {}",
+                    describe_cycle(&dependencies, &stage_of, &ids)
+                );
+            }
+            placed += ready.len();
+            for (node, stage) in ready {
+                stage_of[node] = Some(stage);
+            }
+        }
+
+        let mut stages =
+            vec![Stage::default(); stage_of.iter().flatten().max().map_or(0, |s| s + 1)];
+        for ((privileged, index, _), stage) in nodes.iter().zip(stage_of) {
+            let stage = &mut stages[stage.unwrap_or_default()];
+            if *privileged {
+                stage.privileged.push(*index);
+            } else {
+                stage.unprivileged.push(*index);
+            }
+        }
+        Ok(stages)
+    }
 }
 
 impl Default for ExecutionPlan {
@@ -112,6 +250,40 @@ impl Default for ExecutionPlan {
     }
 }
 
+/// Describe a cycle among the unplaced resources, e.g. "a -> b -> a"
+fn describe_cycle(
+    dependencies: &[Vec<usize>],
+    stage_of: &[Option<usize>],
+    ids: &[String],
+) -> String {
+    // Every unplaced resource waits on another unplaced one, so following
+    // those edges from any of them must come back around
+    let Some(start) = stage_of.iter().position(Option::is_none) else {
+        return String::new();
+    };
+    let mut path = vec![start];
+    loop {
+        let current = path[path.len() - 1];
+        let Some(next) = dependencies[current]
+            .iter()
+            .copied()
+            .find(|&dep| stage_of[dep].is_none())
+        else {
+            break;
+        };
+        if let Some(pos) = path.iter().position(|&node| node == next) {
+            path.drain(..pos);
+            path.push(next);
+            break;
+        }
+        path.push(next);
+    }
+    path.iter()
+        .map(|&node| ids[node].as_str())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
 /// Parse a target string like "type.name" into (type, name)
 fn parse_target(target: &str) -> (Option<String>, Option<String>) {
     let parts: Vec<&str> = target.split('.').collect();
@@ -154,6 +326,61 @@ fn matches_filter(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::MockResource;
+
+    fn node(id: &str, deps: &[&str]) -> BoxedResource {
+        Box::new(MockResource::new(id).depends_on(deps))
+    }
+
+    #[test]
+    fn test_stages_without_dependencies() {
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged.push(node("a", &[]));
+        plan.unprivileged.push(node("b", &[]));
+        plan.privileged.push(node("c", &[]));
+
+        let stages = plan.stages().unwrap();
+        assert_eq!(
+            stages,
+            [Stage {
+                unprivileged: vec![0, 1],
+                privileged: vec![0],
+            }]
+        );
+        assert!(ExecutionPlan::new().stages().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_stages_follow_dependencies() {
+        let mut plan = ExecutionPlan::new();
+        // symlink -> formula (privileged) -> tap; "missing" isn't in the plan
+        plan.unprivileged
+            .push(node("symlink", &["formula", "missing"]));
+        plan.unprivileged.push(node("tap", &[]));
+        plan.privileged.push(node("formula", &["tap"]));
+
+        let stages = plan.stages().unwrap();
+        assert_eq!(stages.len(), 3);
+        assert_eq!(stages[0].unprivileged, [1]);
+        assert_eq!(stages[1].privileged, [0]);
+        assert_eq!(stages[2].unprivileged, [0]);
+    }
+
+    #[test]
+    fn test_stages_reject_cycles() {
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged.push(node("root", &[]));
+        plan.unprivileged.push(node("a", &["b", "root"]));
+        plan.unprivileged.push(node("b", &["c"]));
+        plan.privileged.push(node("c", &["a"]));
+
+        let err = plan.stages().unwrap_err().to_string();
+        assert!(err.starts_with("Dependency cycle: "), "{err}");
+        for id in ["a", "b", "c"] {
+            assert!(err.contains(id), "{err}");
+        }
+        assert!(!err.contains("root"), "{err}");
+    }
 
     #[test]
     fn test_parse_target() {
@@ -0,0 +1,323 @@
+//! Diff rendering - show how a resource's state will change
+//!
+//! A [`DiffRenderer`] turns a [`ResourceDiff`] into text. Each state is
+//! rendered one line per line of its details, the two are compared line by
+//! line, and only changed lines are shown, with some unchanged lines of
+//! context around them. Renderers produce plain text; colouring is up to
+//! the caller.
+//!
+//! ```text
+//! --- macos_default:com.apple.dock/autohide (current)
+//! +++ macos_default:com.apple.dock/autohide (desired)
+//! @@ -1 +1 @@
+//! -false
+//! +true
+//! ```
+
+use crate::diff::ResourceDiff;
+use crate::types::ResourceState;
+use similar::{ChangeTag, TextDiff};
+use std::fmt::Write;
+
+/// How to lay out a diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStyle {
+    /// `diff -u` style hunks, removed lines before added ones
+    Unified,
+    /// Current state on the left, desired state on the right
+    SideBySide,
+}
+
+/// Renders resource diffs as text
+#[derive(Debug, Clone)]
+pub struct DiffRenderer {
+    /// Layout of rendered diffs
+    pub style: DiffStyle,
+    /// Unchanged lines to show around each change
+    pub context: usize,
+    /// Total width of a side-by-side diff, in characters
+    pub width: usize,
+}
+
+impl Default for DiffRenderer {
+    fn default() -> Self {
+        Self::unified()
+    }
+}
+
+impl DiffRenderer {
+    /// Render unified diffs with 3 lines of context
+    pub fn unified() -> Self {
+        Self {
+            style: DiffStyle::Unified,
+            context: 3,
+            width: 80,
+        }
+    }
+
+    /// Render side-by-side diffs `width` characters wide
+    pub fn side_by_side(width: usize) -> Self {
+        Self {
+            style: DiffStyle::SideBySide,
+            width,
+            ..Self::unified()
+        }
+    }
+
+    /// Show `context` unchanged lines around each change
+    pub fn with_context(mut self, context: usize) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Render a single diff
+    pub fn render(&self, diff: &ResourceDiff) -> String {
+        let name = format!("{}:{}", diff.resource_type, diff.resource_id);
+        if let Some(reason) = &diff.skipped {
+            return format!("{name} (skipped: {reason})\n");
+        }
+
+        let current = state_text(&diff.current, Side::Current);
+        let desired = state_text(&diff.desired, Side::Desired);
+        let text_diff = TextDiff::from_lines(&current, &desired);
+
+        match self.style {
+            DiffStyle::Unified => text_diff
+                .unified_diff()
+                .context_radius(self.context)
+                .header(&format!("{name} (current)"), &format!("{name} (desired)"))
+                .to_string(),
+            DiffStyle::SideBySide => self.render_side_by_side(&name, &text_diff),
+        }
+    }
+
+    /// Render several diffs, one after another
+    pub fn render_all(&self, diffs: &[ResourceDiff]) -> String {
+        diffs.iter().map(|diff| self.render(diff)).collect()
+    }
+
+    fn render_side_by_side(&self, name: &str, text_diff: &TextDiff<'_, '_, '_, str>) -> String {
+        let column = self.width.saturating_sub(3) / 2;
+        let mut out = String::new();
+        let _ = writeln!(out, "{name}");
+        push_row(&mut out, "current", '|', "desired", column);
+
+        for (index, group) in text_diff.grouped_ops(self.context).iter().enumerate() {
+            if index > 0 {
+                let _ = writeln!(out, "...");
+            }
+
+            // Pair up each run of removed lines with the added lines after it
+            let mut removed = Vec::new();
+            let mut added = Vec::new();
+            for op in group {
+                for change in text_diff.iter_changes(op) {
+                    let line = change.value().trim_end_matches('\n');
+                    match change.tag() {
+                        ChangeTag::Delete => removed.push(line),
+                        ChangeTag::Insert => added.push(line),
+                        ChangeTag::Equal => {
+                            push_changed(&mut out, &mut removed, &mut added, column);
+                            push_row(&mut out, line, ' ', line, column);
+                        }
+                    }
+                }
+            }
+            push_changed(&mut out, &mut removed, &mut added, column);
+        }
+        out
+    }
+}
+
+/// Render what applying each diff's resource would do
+///
+/// Lists a resource's [`planned`](ResourceDiff::planned) actions under its
+/// name, or its description if it didn't plan any. Skipped diffs are left
+/// out.
+///
+/// ```text
+/// brew_formula:ripgrep
+///   brew install --formula ripgrep
+/// ```
+pub fn render_planned(diffs: &[ResourceDiff]) -> String {
+    let mut out = String::new();
+    for diff in diffs.iter().filter(|diff| !diff.is_skipped()) {
+        let _ = writeln!(out, "{}:{}", diff.resource_type, diff.resource_id);
+        if diff.planned.is_empty() {
+            let _ = writeln!(out, "  {}", diff.description);
+        }
+        for action in &diff.planned {
+            let _ = writeln!(out, "  {action}");
+        }
+    }
+    out
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Current,
+    Desired,
+}
+
+/// Text representing a state, one item per line; an absent resource has none
+fn state_text(state: &ResourceState, side: Side) -> String {
+    let text = match (state, side) {
+        (ResourceState::Absent, _) => return String::new(),
+        (
+            ResourceState::Present {
+                details: Some(details),
+            },
+            _,
+        ) => details.as_str(),
+        (ResourceState::Present { details: None }, _) => "present",
+        (ResourceState::Modified { from, .. }, Side::Current) => from.as_str(),
+        (ResourceState::Modified { to, .. }, Side::Desired) => to.as_str(),
+        (ResourceState::Unknown, _) => "unknown",
+    };
+    // A trailing newline keeps the last line comparable with the others
+    format!("{}\n", text.trim_end_matches('\n'))
+}
+
+fn push_changed(out: &mut String, removed: &mut Vec<&str>, added: &mut Vec<&str>, column: usize) {
+    for row in 0..removed.len().max(added.len()) {
+        let (left, right) = (removed.get(row), added.get(row));
+        let marker = match (left, right) {
+            (Some(_), Some(_)) => '|',
+            (Some(_), None) => '<',
+            _ => '>',
+        };
+        push_row(
+            out,
+            left.copied().unwrap_or_default(),
+            marker,
+            right.copied().unwrap_or_default(),
+            column,
+        );
+    }
+    removed.clear();
+    added.clear();
+}
+
+fn push_row(out: &mut String, left: &str, marker: char, right: &str, column: usize) {
+    let left = truncate(left, column);
+    let row = format!("{left:<column$} {marker} {}", truncate(right, column));
+    let _ = writeln!(out, "{}", row.trim_end());
+}
+
+/// Cut `text` down to `width` characters, marking the cut with an ellipsis
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::PlannedAction;
+
+    fn diff(current: ResourceState, desired: ResourceState) -> ResourceDiff {
+        ResourceDiff {
+            resource_id: "~/.gitconfig".to_string(),
+            resource_type: "file".to_string(),
+            description: "Write ~/.gitconfig".to_string(),
+            current,
+            desired,
+            requires_sudo: false,
+            skipped: None,
+            planned: Vec::new(),
+        }
+    }
+
+    fn modified(from: &str, to: &str) -> ResourceDiff {
+        diff(
+            ResourceState::Modified {
+                from: from.to_string(),
+                to: to.to_string(),
+            },
+            ResourceState::Present {
+                details: Some(to.to_string()),
+            },
+        )
+    }
+
+    #[test]
+    fn test_unified_shows_changes_with_context() {
+        let from = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj";
+        let to = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk";
+        let rendered = DiffRenderer::unified()
+            .with_context(1)
+            .render(&modified(from, to));
+
+        assert_eq!(
+            rendered,
+            "--- file:~/.gitconfig (current)\n\
+             +++ file:~/.gitconfig (desired)\n\
+             @@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n\
+             @@ -10 +10,2 @@\n j\n+k\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_addition() {
+        let rendered = DiffRenderer::unified().render(&diff(
+            ResourceState::Absent,
+            ResourceState::Present {
+                details: Some("ripgrep 14.1".to_string()),
+            },
+        ));
+        assert!(rendered.ends_with("@@ -0,0 +1 @@\n+ripgrep 14.1\n"));
+    }
+
+    #[test]
+    fn test_side_by_side_pairs_changed_lines() {
+        let rendered = DiffRenderer::side_by_side(23)
+            .with_context(0)
+            .render(&modified("same\nold\ngone", "same\nnew"));
+
+        assert_eq!(
+            rendered,
+            "file:~/.gitconfig\n\
+             current    | desired\n\
+             old        | new\n\
+             gone       <\n"
+        );
+    }
+
+    #[test]
+    fn test_side_by_side_truncates_long_lines() {
+        let rendered =
+            DiffRenderer::side_by_side(13).render(&modified("a very long line", "short"));
+        assert!(rendered.contains("a ve… | short\n"));
+    }
+
+    #[test]
+    fn test_render_planned() {
+        let mut planned = modified("a", "b");
+        planned.planned = vec![
+            PlannedAction::write_file("~/.gitconfig"),
+            PlannedAction::run("git", &["config", "--global", "user.name", "Alberto"]),
+        ];
+        let mut skipped = modified("a", "b");
+        skipped.skipped = Some("Condition not met".to_string());
+
+        assert_eq!(
+            render_planned(&[planned, modified("a", "b"), skipped]),
+            "file:~/.gitconfig\n  write ~/.gitconfig\n  git config --global user.name Alberto\n\
+             file:~/.gitconfig\n  Write ~/.gitconfig\n"
+        );
+    }
+
+    #[test]
+    fn test_skipped_diff() {
+        let mut skipped = modified("a", "b");
+        skipped.skipped = Some("Condition not met".to_string());
+        assert_eq!(
+            DiffRenderer::unified().render(&skipped),
+            "file:~/.gitconfig (skipped: Condition not met)\n"
+        );
+    }
+}
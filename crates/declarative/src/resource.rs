@@ -3,10 +3,14 @@
 //! A Resource represents something that can be in a certain state,
 //! and can be changed to reach a desired state.
 
+use crate::action::PlannedAction;
 use crate::context::ApplyContext;
+use crate::import::ConfigSuggestion;
 use crate::types::{ApplyResult, ResourceState, SudoRequirement};
 use anyhow::Result;
+use std::collections::HashSet;
 use std::fmt;
+use std::time::Duration;
 
 /// Core trait for declarative resources
 ///
@@ -119,6 +123,23 @@ pub trait Resource: Send + Sync + fmt::Debug {
     /// 4. Return the appropriate ApplyResult
     fn apply(&self, ctx: &mut ApplyContext) -> Result<ApplyResult>;
 
+    /// What `apply` would do, for dry runs to show
+    ///
+    /// Called only when the resource needs changes. The default plans
+    /// nothing, and dry runs fall back to the description.
+    fn plan(&self) -> Vec<PlannedAction> {
+        Vec::new()
+    }
+
+    /// A configuration entry that would make the current state desired
+    ///
+    /// Called by [`import`](crate::import()) when the resource isn't in its
+    /// desired state, to adopt what's there rather than overwrite it. The
+    /// default can't adopt anything.
+    fn adopt(&self) -> Option<ConfigSuggestion> {
+        None
+    }
+
     /// Whether this resource can be applied in parallel with others
     ///
     /// Override to return false for resources that have ordering
@@ -126,6 +147,47 @@ pub trait Resource: Send + Sync + fmt::Debug {
     fn can_parallelize(&self) -> bool {
         true
     }
+
+    /// Undo what `apply` changed
+    ///
+    /// Called when a later resource fails and the executor runs with
+    /// rollback enabled. Resources that need the state from before `apply`
+    /// should record it there. The default can't revert anything.
+    fn revert(&self, _ctx: &mut ApplyContext) -> Result<ApplyResult> {
+        Ok(ApplyResult::Skipped {
+            reason: "Revert not supported".into(),
+        })
+    }
+
+    /// IDs of resources that must be applied before this one
+    ///
+    /// Dependencies that aren't in the plan (e.g. filtered out by a target)
+    /// are ignored. If a dependency fails, this resource is skipped.
+    fn dependencies(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// How long `apply` may run before the executor gives up on it
+    ///
+    /// `None` uses [`ExecuteOptions::timeout`](crate::ExecuteOptions::timeout).
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Labels for converging a subset of a plan, e.g. "shell" or "editor"
+    ///
+    /// See [`TagFilter`](crate::TagFilter).
+    fn tags(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Why this resource shouldn't be applied on this machine, if so
+    ///
+    /// Checked when diffing; a resource with a reason is skipped. See
+    /// [`Conditional`](crate::Conditional) for common conditions.
+    fn precondition(&self) -> Option<String> {
+        None
+    }
 }
 
 /// A boxed resource for type-erased storage
@@ -135,10 +197,19 @@ pub type BoxedResource = Box<dyn Resource>;
 pub trait ResourceExt {
     /// Check if the resource requires sudo based on its requirement
     fn requires_sudo(&self) -> bool;
+
+    /// The first dependency among `failed`, if any
+    fn blocked_by(&self, failed: &HashSet<String>) -> Option<String>;
 }
 
 impl<R: Resource + ?Sized> ResourceExt for R {
     fn requires_sudo(&self) -> bool {
         matches!(self.sudo_requirement(), SudoRequirement::Required { .. })
     }
+
+    fn blocked_by(&self, failed: &HashSet<String>) -> Option<String> {
+        self.dependencies()
+            .into_iter()
+            .find(|dependency| failed.contains(dependency))
+    }
 }
@@ -0,0 +1,133 @@
+//! Sudo sessions - one password prompt per plan
+//!
+//! `sudo` forgets a password after a few minutes, so a plan whose privileged
+//! batches are separated by long unprivileged work would prompt again for
+//! each one. A [`SudoSession`] validates once, then refreshes the timestamp
+//! on a background ticker without prompting, and invalidates it when
+//! dropped, so privileges don't outlive the plan.
+
+use crate::context::SudoProvider;
+use crate::types::CommandOutput;
+use anyhow::{Context, Result, bail};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// How often the timestamp is refreshed, well within sudo's default 5 minutes
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A validated sudo session, kept alive until dropped
+#[derive(Debug)]
+pub struct SudoSession {
+    program: String,
+    /// Stops the ticker when dropped
+    stop: Option<Sender<()>>,
+    ticker: Option<JoinHandle<()>>,
+}
+
+impl SudoSession {
+    /// Validate with `sudo -v`, prompting for a password if needed
+    pub fn start() -> Result<Self> {
+        Self::start_with("sudo", KEEPALIVE_INTERVAL)
+    }
+
+    /// Validate with a sudo-compatible `program`, refreshing every `interval`
+    pub fn start_with(program: &str, interval: Duration) -> Result<Self> {
+        let status = Command::new(program)
+            .arg("-v")
+            .status()
+            .with_context(|| format!("Failed to execute {program}"))?;
+        if !status.success() {
+            bail!("Failed to acquire sudo privileges");
+        }
+
+        let (stop, stopped) = mpsc::channel::<()>();
+        let keepalive = program.to_string();
+        let ticker = thread::spawn(move || {
+            while stopped.recv_timeout(interval) == Err(RecvTimeoutError::Timeout) {
+                // `-n` never prompts: an expired timestamp is left to `run`
+                let _ = Command::new(&keepalive)
+                    .args(["-n", "-v"])
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status();
+            }
+        });
+
+        Ok(Self {
+            program: program.to_string(),
+            stop: Some(stop),
+            ticker: Some(ticker),
+        })
+    }
+}
+
+impl SudoProvider for SudoSession {
+    fn run(&self, cmd: &str, args: &[&str]) -> Result<CommandOutput> {
+        let output = Command::new(&self.program)
+            .arg(cmd)
+            .args(args)
+            .output()
+            .with_context(|| format!("Failed to execute: {} {cmd} {args:?}", self.program))?;
+        Ok(output.into())
+    }
+}
+
+impl Drop for SudoSession {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(ticker) = self.ticker.take() {
+            let _ = ticker.join();
+        }
+        // Invalidate the timestamp to release privileges
+        let _ = Command::new(&self.program)
+            .arg("-k")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_session_keeps_alive_until_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("sudo.log");
+        let program = dir.path().join("sudo");
+        fs::write(
+            &program,
+            format!("#!/bin/sh\nprintf '%s\\n' \"$*\" >> '{}'\n", log.display()),
+        )
+        .unwrap();
+        fs::set_permissions(&program, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let session =
+            SudoSession::start_with(program.to_str().unwrap(), Duration::from_millis(10)).unwrap();
+        assert!(session.run("true", &[]).unwrap().success);
+        thread::sleep(Duration::from_millis(100));
+        drop(session);
+
+        let log = fs::read_to_string(&log).unwrap();
+        let calls: Vec<&str> = log.lines().collect();
+        assert_eq!(calls.first(), Some(&"-v"));
+        assert!(calls.contains(&"true"));
+        assert!(calls.contains(&"-n -v"));
+        assert_eq!(calls.last(), Some(&"-k"));
+    }
+
+    #[test]
+    fn test_session_fails_when_not_validated() {
+        assert!(SudoSession::start_with("false", Duration::from_secs(60)).is_err());
+        assert!(
+            SudoSession::start_with("bossa-test-missing-sudo", Duration::from_secs(60)).is_err()
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Resource tags - converge a tagged subset of a plan
+//!
+//! Resources report tags through [`Resource::tags`], or get them from a
+//! [`Tagged`] wrapper. A [`TagFilter`] then narrows a plan down with
+//! [`ExecutionPlan::filter_by_tags`](crate::ExecutionPlan::filter_by_tags)
+//! or [`ExecuteOptions::tags`](crate::ExecuteOptions::tags).
+
+use crate::action::PlannedAction;
+use crate::context::ApplyContext;
+use crate::import::ConfigSuggestion;
+use crate::resource::{BoxedResource, Resource};
+use crate::types::{ApplyResult, ResourceState, SudoRequirement};
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+/// Which tags a resource must, and must not, have
+///
+/// An empty filter matches everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    /// Match resources with at least one of these tags, if any are set
+    pub include: BTreeSet<String>,
+    /// Never match resources with any of these tags
+    pub exclude: BTreeSet<String>,
+}
+
+impl TagFilter {
+    /// Create a filter matching everything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match resources with one of these tags
+    pub fn include<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.include.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Don't match resources with any of these tags
+    pub fn exclude<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.exclude.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Check if the filter matches everything
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Check if a resource with these tags passes the filter
+    pub fn matches(&self, tags: &[String]) -> bool {
+        if tags.iter().any(|tag| self.exclude.contains(tag)) {
+            return false;
+        }
+        self.include.is_empty() || tags.iter().any(|tag| self.include.contains(tag))
+    }
+}
+
+/// A resource with extra tags
+///
+/// Its tags are the wrapped resource's own plus the added ones; everything
+/// else is the wrapped resource's.
+#[derive(Debug)]
+pub struct Tagged {
+    resource: BoxedResource,
+    tags: Vec<String>,
+}
+
+impl Tagged {
+    /// Add tags to a resource
+    pub fn new<I, S>(resource: BoxedResource, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            resource,
+            tags: tags.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl Resource for Tagged {
+    fn id(&self) -> String {
+        self.resource.id()
+    }
+
+    fn description(&self) -> String {
+        self.resource.description()
+    }
+
+    fn resource_type(&self) -> &'static str {
+        self.resource.resource_type()
+    }
+
+    fn sudo_requirement(&self) -> SudoRequirement {
+        self.resource.sudo_requirement()
+    }
+
+    fn current_state(&self) -> Result<ResourceState> {
+        self.resource.current_state()
+    }
+
+    fn desired_state(&self) -> ResourceState {
+        self.resource.desired_state()
+    }
+
+    fn needs_apply(&self) -> Result<bool> {
+        self.resource.needs_apply()
+    }
+
+    fn apply(&self, ctx: &mut ApplyContext) -> Result<ApplyResult> {
+        self.resource.apply(ctx)
+    }
+
+    fn plan(&self) -> Vec<PlannedAction> {
+        self.resource.plan()
+    }
+
+    fn adopt(&self) -> Option<ConfigSuggestion> {
+        self.resource.adopt()
+    }
+
+    fn can_parallelize(&self) -> bool {
+        self.resource.can_parallelize()
+    }
+
+    fn revert(&self, ctx: &mut ApplyContext) -> Result<ApplyResult> {
+        self.resource.revert(ctx)
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.resource.dependencies()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.resource.timeout()
+    }
+
+    fn precondition(&self) -> Option<String> {
+        self.resource.precondition()
+    }
+
+    fn tags(&self) -> Vec<String> {
+        let mut tags = self.resource.tags();
+        for tag in &self.tags {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::ExecutionPlan;
+    use crate::testing::MockResource;
+
+    fn package(name: &str) -> BoxedResource {
+        Box::new(
+            MockResource::new(name)
+                .of_type("brew_formula")
+                .tagged(&["packages"]),
+        )
+    }
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_tag_filter_matches() {
+        let filter = TagFilter::new()
+            .include(["shell", "editor"])
+            .exclude(["work"]);
+
+        assert!(filter.matches(&tags(&["shell"])));
+        assert!(filter.matches(&tags(&["packages", "editor"])));
+        assert!(!filter.matches(&tags(&["packages"])));
+        assert!(!filter.matches(&tags(&["shell", "work"])));
+        assert!(!filter.matches(&[]));
+
+        assert!(TagFilter::new().matches(&[]));
+        assert!(!TagFilter::new().exclude(["work"]).matches(&tags(&["work"])));
+    }
+
+    #[test]
+    fn test_filter_plan_by_tags() {
+        let mut plan = ExecutionPlan::new();
+        plan.unprivileged
+            .push(Box::new(Tagged::new(package("zsh"), ["shell"])));
+        plan.unprivileged.push(Box::new(Tagged::new(
+            package("neovim"),
+            ["editor", "packages"],
+        )));
+        plan.privileged.push(package("docker"));
+
+        assert_eq!(plan.unprivileged[0].tags(), tags(&["packages", "shell"]));
+        assert_eq!(plan.unprivileged[1].tags(), tags(&["packages", "editor"]));
+
+        let plan = plan.filter_by_tags(&TagFilter::new().include(["shell", "editor"]));
+        let ids: Vec<String> = plan.unprivileged.iter().map(|r| r.id()).collect();
+        assert_eq!(ids, ["zsh", "neovim"]);
+        assert!(plan.privileged.is_empty());
+    }
+}
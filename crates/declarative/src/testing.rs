@@ -0,0 +1,256 @@
+//! Configurable resource for tests.
+//!
+//! A [`MockResource`] is absent and applies by creating itself. Builder
+//! methods give it another type or state, dependencies and tags, make it
+//! fail, revert, take its time, or record what it did.
+
+use crate::action::PlannedAction;
+use crate::context::ApplyContext;
+use crate::import::ConfigSuggestion;
+use crate::resource::Resource;
+use crate::types::{ApplyResult, ResourceState};
+use anyhow::Result;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A present state with `details`.
+pub(crate) fn present(details: &str) -> ResourceState {
+    ResourceState::Present {
+        details: Some(details.to_string()),
+    }
+}
+
+/// How many resources apply at once, shared by the ones counted together.
+#[derive(Debug, Default)]
+pub(crate) struct Concurrency {
+    running: AtomicU32,
+    /// Most resources applying at the same time
+    pub peak: AtomicU32,
+}
+
+#[derive(Debug)]
+pub(crate) struct MockResource {
+    id: String,
+    resource_type: &'static str,
+    current: ResourceState,
+    desired: ResourceState,
+    dependencies: Vec<String>,
+    tags: Vec<String>,
+    planned: Vec<PlannedAction>,
+    suggestion: Option<ConfigSuggestion>,
+    failures: u32,
+    revertible: bool,
+    expand: bool,
+    delay: Duration,
+    timeout: Option<Duration>,
+    log: Option<Arc<Mutex<Vec<String>>>>,
+    concurrency: Option<Arc<Concurrency>>,
+    attempts: AtomicU32,
+    checks: Arc<AtomicUsize>,
+}
+
+impl MockResource {
+    /// An absent resource of type `test`
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            resource_type: "test",
+            current: ResourceState::Absent,
+            desired: ResourceState::Present { details: None },
+            dependencies: Vec::new(),
+            tags: Vec::new(),
+            planned: Vec::new(),
+            suggestion: None,
+            failures: 0,
+            revertible: false,
+            expand: false,
+            delay: Duration::ZERO,
+            timeout: None,
+            log: None,
+            concurrency: None,
+            attempts: AtomicU32::new(0),
+            checks: Arc::default(),
+        }
+    }
+
+    pub fn of_type(mut self, resource_type: &'static str) -> Self {
+        self.resource_type = resource_type;
+        self
+    }
+
+    /// Already in its desired state
+    pub fn converged(mut self) -> Self {
+        self.current = self.desired.clone();
+        self
+    }
+
+    pub fn with_current(mut self, state: ResourceState) -> Self {
+        self.current = state;
+        self
+    }
+
+    pub fn with_desired(mut self, state: ResourceState) -> Self {
+        self.desired = state;
+        self
+    }
+
+    pub fn depends_on(mut self, ids: &[&str]) -> Self {
+        self.dependencies = ids.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    pub fn tagged(mut self, tags: &[&str]) -> Self {
+        self.tags = tags.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    pub fn with_plan(mut self, planned: Vec<PlannedAction>) -> Self {
+        self.planned = planned;
+        self
+    }
+
+    /// Suggest `entry` in `section` to adopt the current state
+    pub fn adoptable(mut self, section: &str, entry: impl Into<String>) -> Self {
+        self.suggestion = Some(ConfigSuggestion {
+            resource_type: self.resource_type.to_string(),
+            resource_id: self.id.clone(),
+            section: section.to_string(),
+            entry: entry.into(),
+        });
+        self
+    }
+
+    /// Fail every attempt to apply
+    pub fn failing(self) -> Self {
+        self.failing_first(u32::MAX)
+    }
+
+    /// Fail the first `attempts` attempts to apply
+    pub fn failing_first(mut self, attempts: u32) -> Self {
+        self.failures = attempts;
+        self
+    }
+
+    pub fn revertible(mut self) -> Self {
+        self.revertible = true;
+        self
+    }
+
+    /// Record applies (and reverts) in `log`
+    pub fn recording(mut self, log: &Arc<Mutex<Vec<String>>>) -> Self {
+        self.log = Some(Arc::clone(log));
+        self
+    }
+
+    /// Record the id expanded with the plan's variables, rather than as is
+    pub fn expanding(mut self) -> Self {
+        self.expand = true;
+        self
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Count applies running at once in `concurrency`
+    pub fn counted(mut self, concurrency: &Arc<Concurrency>) -> Self {
+        self.concurrency = Some(Arc::clone(concurrency));
+        self
+    }
+
+    /// How often the current state was read
+    pub fn checks(&self) -> usize {
+        self.checks.load(Ordering::SeqCst)
+    }
+
+    /// [`checks`](Self::checks), still counting once the resource is in a plan
+    pub fn check_counter(&self) -> Arc<AtomicUsize> {
+        Arc::clone(&self.checks)
+    }
+
+    fn record(&self, entry: String) {
+        if let Some(log) = &self.log {
+            log.lock().unwrap().push(entry);
+        }
+    }
+}
+
+impl Resource for MockResource {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn description(&self) -> String {
+        format!("Test resource {}", self.id)
+    }
+
+    fn resource_type(&self) -> &'static str {
+        self.resource_type
+    }
+
+    fn current_state(&self) -> Result<ResourceState> {
+        self.checks.fetch_add(1, Ordering::SeqCst);
+        Ok(self.current.clone())
+    }
+
+    fn desired_state(&self) -> ResourceState {
+        self.desired.clone()
+    }
+
+    fn apply(&self, ctx: &mut ApplyContext) -> Result<ApplyResult> {
+        if let Some(concurrency) = &self.concurrency {
+            let running = concurrency.running.fetch_add(1, Ordering::SeqCst) + 1;
+            concurrency.peak.fetch_max(running, Ordering::SeqCst);
+        }
+        std::thread::sleep(self.delay);
+        if let Some(concurrency) = &self.concurrency {
+            concurrency.running.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        let entry = if self.expand {
+            ctx.expand(&self.id)?
+        } else {
+            self.id.clone()
+        };
+        self.record(entry);
+        if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+            anyhow::bail!("{} failed", self.id);
+        }
+        Ok(ApplyResult::Created)
+    }
+
+    fn plan(&self) -> Vec<PlannedAction> {
+        self.planned.clone()
+    }
+
+    fn adopt(&self) -> Option<ConfigSuggestion> {
+        self.suggestion.clone()
+    }
+
+    fn revert(&self, _ctx: &mut ApplyContext) -> Result<ApplyResult> {
+        if !self.revertible {
+            anyhow::bail!("{} can't be reverted", self.id);
+        }
+        self.record(format!("revert {}", self.id));
+        Ok(ApplyResult::Removed)
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.dependencies.clone()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    fn tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+}
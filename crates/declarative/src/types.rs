@@ -1,7 +1,14 @@
 //! Core types for declarative resource management
 
+use crate::cache::StateCache;
+use crate::diff::ResourceDiff;
+use crate::tags::TagFilter;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 use std::process::Output;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Requirement level for sudo/elevated privileges
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -68,7 +75,7 @@ impl ApplyResult {
 }
 
 /// Summary of execution results
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ExecuteSummary {
     pub created: usize,
     pub modified: usize,
@@ -76,6 +83,35 @@ pub struct ExecuteSummary {
     pub skipped: usize,
     pub failed: usize,
     pub no_change: usize,
+    /// Resources reverted after a failure, most recent first
+    #[serde(default)]
+    pub rolled_back: Vec<String>,
+    /// Resources that couldn't be reverted
+    #[serde(default)]
+    pub rollback_failed: Vec<String>,
+    /// Attempts taken by resources that were retried, by ID
+    #[serde(default)]
+    pub attempts: BTreeMap<String, u32>,
+    /// Resources left out because a checkpoint showed them already converged
+    #[serde(default)]
+    pub resumed: usize,
+    /// On a dry run, the changes that would have been made
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub planned: Vec<ResourceDiff>,
+    /// What happened to each resource, in the order they finished
+    #[serde(default)]
+    pub outcomes: Vec<ResourceOutcome>,
+}
+
+/// What happened to a single resource during execution
+///
+/// Resources left alone because of the [`FailurePolicy`] are skipped, with
+/// the reason saying why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceOutcome {
+    pub resource_type: String,
+    pub resource_id: String,
+    pub result: ApplyResult,
 }
 
 impl ExecuteSummary {
@@ -102,6 +138,26 @@ impl ExecuteSummary {
         self.skipped += other.skipped;
         self.failed += other.failed;
         self.no_change += other.no_change;
+        self.rolled_back.extend(other.rolled_back.iter().cloned());
+        self.rollback_failed
+            .extend(other.rollback_failed.iter().cloned());
+        self.attempts
+            .extend(other.attempts.iter().map(|(id, n)| (id.clone(), *n)));
+        self.resumed += other.resumed;
+        self.planned.extend(other.planned.iter().cloned());
+        self.outcomes.extend(other.outcomes.iter().cloned());
+    }
+
+    /// Record how many attempts a resource took, if it was retried
+    pub fn add_attempts(&mut self, id: &str, attempts: u32) {
+        if attempts > 1 {
+            self.attempts.insert(id.to_string(), attempts);
+        }
+    }
+
+    /// Check if execution failed and was rolled back
+    pub fn was_rolled_back(&self) -> bool {
+        !self.rolled_back.is_empty() || !self.rollback_failed.is_empty()
     }
 
     /// Add a result to the summary
@@ -117,6 +173,19 @@ impl ExecuteSummary {
     }
 }
 
+/// What execution does after a resource fails
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FailurePolicy {
+    /// Stop at the first failure, skipping everything not yet started
+    Stop,
+    /// Keep going, skipping only resources that depend on a failed one
+    #[default]
+    ContinueIndependent,
+    /// Keep going and apply everything, dependents of failures included
+    BestEffort,
+}
+
 /// Options for execution
 #[derive(Debug, Clone)]
 pub struct ExecuteOptions {
@@ -124,8 +193,39 @@ pub struct ExecuteOptions {
     pub dry_run: bool,
     /// Number of parallel jobs for unprivileged operations
     pub jobs: usize,
+    /// Parallel jobs by resource type (see `Resource::resource_type`)
+    ///
+    /// Each listed type runs in a pool of its own, alongside the `jobs`
+    /// shared by all other types, so a package manager that can only run
+    /// one install at a time doesn't hold up cheap file operations.
+    pub concurrency: HashMap<String, usize>,
+    /// Resource types that share a pool, by the name of its limit in
+    /// `concurrency` (see [`with_shared_concurrency`](Self::with_shared_concurrency))
+    pub concurrency_groups: HashMap<String, String>,
     /// Verbose output
     pub verbose: bool,
+    /// On the first failure, stop and revert the resources already applied
+    pub rollback: bool,
+    /// What to do after a resource fails; rollback always stops
+    pub failure_policy: FailurePolicy,
+    /// Cache for current state checks, invalidated for applied resources
+    pub state_cache: Option<Arc<dyn StateCache>>,
+    /// How often to attempt failing resources
+    pub retry: RetryPolicy,
+    /// How long a resource may apply before it fails, unless it sets its
+    /// own timeout; `None` waits forever
+    pub timeout: Option<Duration>,
+    /// Only apply resources whose tags pass this filter
+    pub tags: TagFilter,
+    /// Checkpoint file to record progress in, and resume from (see
+    /// [`Checkpoint`](crate::Checkpoint))
+    pub checkpoint: Option<PathBuf>,
+    /// JSON lines file to append every applied resource to (see
+    /// [`AuditLog`](crate::AuditLog))
+    pub audit_log: Option<PathBuf>,
+    /// Also read and record each resource's state after applying it in
+    /// the audit log, at the cost of another state check
+    pub audit_new_state: bool,
 }
 
 impl Default for ExecuteOptions {
@@ -133,7 +233,176 @@ impl Default for ExecuteOptions {
         Self {
             dry_run: false,
             jobs: 4,
+            concurrency: HashMap::new(),
+            concurrency_groups: HashMap::new(),
             verbose: false,
+            rollback: false,
+            failure_policy: FailurePolicy::default(),
+            state_cache: None,
+            retry: RetryPolicy::default(),
+            timeout: None,
+            tags: TagFilter::default(),
+            checkpoint: None,
+            audit_log: None,
+            audit_new_state: false,
+        }
+    }
+}
+
+impl ExecuteOptions {
+    /// Run resources of one type at most `jobs` at a time, in their own pool
+    pub fn with_concurrency(mut self, resource_type: &str, jobs: usize) -> Self {
+        self.concurrency.insert(resource_type.to_string(), jobs);
+        self
+    }
+
+    /// Run resources of several types at most `jobs` at a time between
+    /// them, in one pool called `group`
+    pub fn with_shared_concurrency(
+        mut self,
+        group: &str,
+        resource_types: &[&str],
+        jobs: usize,
+    ) -> Self {
+        for resource_type in resource_types {
+            self.concurrency_groups
+                .insert(resource_type.to_string(), group.to_string());
+        }
+        self.with_concurrency(group, jobs)
+    }
+
+    /// The pool a resource type runs in and its limit, if it isn't the
+    /// shared pool of `jobs` threads
+    pub fn pool_for<'a>(&'a self, resource_type: &'a str) -> Option<(&'a str, usize)> {
+        let pool = self
+            .concurrency_groups
+            .get(resource_type)
+            .map_or(resource_type, String::as_str);
+        self.concurrency
+            .get(pool)
+            .map(|&limit| (pool, limit.max(1)))
+    }
+
+    /// Record progress in a checkpoint file, and skip the resources it
+    /// shows already converged by an earlier, interrupted run
+    pub fn resume_from(mut self, path: impl Into<PathBuf>) -> Self {
+        self.checkpoint = Some(path.into());
+        self
+    }
+
+    /// Append every applied resource to an audit log
+    pub fn audit_log(mut self, path: impl Into<PathBuf>) -> Self {
+        self.audit_log = Some(path.into());
+        self
+    }
+
+    /// Record each resource's state after applying it in the audit log
+    pub fn audit_new_state(mut self) -> Self {
+        self.audit_new_state = true;
+        self
+    }
+
+    /// Set what to do after a resource fails
+    pub fn on_failure(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Check if the first failure stops execution
+    pub fn stops_on_failure(&self) -> bool {
+        self.rollback || self.failure_policy == FailurePolicy::Stop
+    }
+}
+
+/// How many times to attempt a failing resource
+///
+/// Network-bound resources (brew, gh extensions) often fail transiently,
+/// while a failed file operation would just fail again, so attempts can be
+/// set per resource type. By default nothing is retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts for resource types without an override; 1 means no retries
+    pub max_attempts: u32,
+    /// Pause before the first retry, doubled before each one after it
+    pub backoff: Duration,
+    /// Attempts by resource type (see `Resource::resource_type`)
+    pub overrides: HashMap<String, u32>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            backoff: Duration::from_secs(1),
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Attempt every resource up to `max_attempts` times
+    pub fn attempts(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    /// Set the pause before the first retry
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Attempt resources of one type up to `max_attempts` times
+    pub fn with_override(mut self, resource_type: &str, max_attempts: u32) -> Self {
+        self.overrides
+            .insert(resource_type.to_string(), max_attempts);
+        self
+    }
+
+    /// Maximum attempts for a resource type, at least 1
+    pub fn max_attempts_for(&self, resource_type: &str) -> u32 {
+        self.overrides
+            .get(resource_type)
+            .copied()
+            .unwrap_or(self.max_attempts)
+            .max(1)
+    }
+
+    /// Pause before the given retry, counting from 1
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.backoff.saturating_mul(factor)
+    }
+
+    /// Run `apply` until it succeeds or a resource type's attempts run out
+    ///
+    /// Returns the last result and the number of attempts made.
+    pub fn run(
+        &self,
+        resource_type: &str,
+        apply: impl FnMut() -> ApplyResult,
+    ) -> (ApplyResult, u32) {
+        self.run_while(resource_type, apply, |_| true)
+    }
+
+    /// Like [`run`](Self::run), but stops at a failure `retryable` rejects
+    pub fn run_while(
+        &self,
+        resource_type: &str,
+        mut apply: impl FnMut() -> ApplyResult,
+        retryable: impl Fn(&ApplyResult) -> bool,
+    ) -> (ApplyResult, u32) {
+        let max_attempts = self.max_attempts_for(resource_type);
+        let mut attempt = 1;
+        loop {
+            let result = apply();
+            if result.is_success() || attempt >= max_attempts || !retryable(&result) {
+                return (result, attempt);
+            }
+            std::thread::sleep(self.delay(attempt));
+            attempt += 1;
         }
     }
 }
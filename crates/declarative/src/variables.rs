@@ -0,0 +1,212 @@
+//! Variables - one configuration for several machines
+//!
+//! [`Variables`] hold facts about the machine (`hostname`, `user`, `os`,
+//! `arch`, `home`) plus custom values, and expand `${name}` references in
+//! resource fields. A plan carries its variables in
+//! [`ExecutionPlan::variables`](crate::ExecutionPlan::variables), and the
+//! executor hands them to resources through
+//! [`ApplyContext::variables`](crate::ApplyContext::variables).
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::process::{Command, Stdio};
+
+/// Named values to expand in resource fields
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Variables {
+    values: BTreeMap<String, String>,
+}
+
+impl Variables {
+    /// Create an empty set of variables
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Variables describing this machine
+    ///
+    /// `hostname` (short form), `user` and `home` are left out when they
+    /// can't be determined; `os` and `arch` are Rust's names, e.g. `macos`
+    /// and `aarch64`.
+    pub fn from_system() -> Self {
+        let mut variables = Self::new()
+            .with("os", std::env::consts::OS)
+            .with("arch", std::env::consts::ARCH);
+        if let Some(hostname) = hostname() {
+            variables.set("hostname", hostname);
+        }
+        if let Some(user) = current_user() {
+            variables.set("user", user);
+        }
+        if let Some(home) = std::env::var_os("HOME").filter(|v| !v.is_empty()) {
+            variables.set("home", home.to_string_lossy());
+        }
+        variables
+    }
+
+    /// Set a variable, replacing any previous value
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(name.into(), value.into());
+    }
+
+    /// Set a variable, builder style
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Set several variables, replacing previous values
+    pub fn extend<I, K, V>(&mut self, values: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        for (name, value) in values {
+            self.set(name, value);
+        }
+    }
+
+    /// Get a variable's value
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// Check if no variables are set
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Expand `${name}` references in `input`
+    ///
+    /// `$$` stands for a literal `$`; a `$` followed by anything else is left
+    /// alone, so shell variables like `$HOME` pass through. Fails on an
+    /// unknown variable or an unclosed `${`.
+    pub fn expand(&self, input: &str) -> Result<String> {
+        self.expand_refs(input, false)
+    }
+
+    /// Expand `${name}` references in `input`, leaving unknown ones as they
+    /// are
+    ///
+    /// For fields that go through shell-style expansion afterwards, where
+    /// `${HOME}` and friends are resolved from the environment.
+    pub fn expand_known(&self, input: &str) -> Result<String> {
+        self.expand_refs(input, true)
+    }
+
+    fn expand_refs(&self, input: &str, keep_unknown: bool) -> Result<String> {
+        let mut output = String::with_capacity(input.len());
+        let mut rest = input;
+        while let Some(dollar) = rest.find('$') {
+            output.push_str(&rest[..dollar]);
+            rest = &rest[dollar..];
+
+            if let Some(after) = rest.strip_prefix("$$") {
+                output.push('$');
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("${") {
+                let Some(end) = after.find('}') else {
+                    bail!("Unclosed variable reference in {input:?}");
+                };
+                let name = after[..end].trim();
+                match self.get(name) {
+                    Some(value) => output.push_str(value),
+                    None if keep_unknown => output.push_str(&rest[..end + 3]),
+                    None => bail!("Unknown variable ${{{name}}} in {input:?}"),
+                }
+                rest = &after[end + 1..];
+            } else {
+                output.push('$');
+                rest = &rest[1..];
+            }
+        }
+        output.push_str(rest);
+        Ok(output)
+    }
+}
+
+impl IntoIterator for Variables {
+    type Item = (String, String);
+    type IntoIter = std::collections::btree_map::IntoIter<String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+/// Name of the user running this process
+pub(crate) fn current_user() -> Option<String> {
+    ["USER", "LOGNAME"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+}
+
+/// Short hostname of this machine
+fn hostname() -> Option<String> {
+    let output = Command::new("hostname")
+        .arg("-s")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success());
+    output
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .filter(|hostname| !hostname.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand() {
+        let variables = Variables::new()
+            .with("hostname", "studio")
+            .with("user", "alberto");
+
+        assert_eq!(
+            variables
+                .expand("/Users/${user}/.config/${hostname}.toml")
+                .unwrap(),
+            "/Users/alberto/.config/studio.toml"
+        );
+        assert_eq!(variables.expand("${ user }").unwrap(), "alberto");
+        assert_eq!(
+            variables.expand("$HOME costs $$5").unwrap(),
+            "$HOME costs $5"
+        );
+        assert_eq!(variables.expand("no variables").unwrap(), "no variables");
+
+        assert!(
+            variables
+                .expand("${missing}")
+                .unwrap_err()
+                .to_string()
+                .contains("Unknown variable ${missing}")
+        );
+        assert!(variables.expand("${user").is_err());
+
+        assert_eq!(
+            variables.expand_known("${HOME}/${user}").unwrap(),
+            "${HOME}/alberto"
+        );
+        assert!(variables.expand_known("${user").is_err());
+    }
+
+    #[test]
+    fn test_system_variables() {
+        let variables = Variables::from_system();
+        assert_eq!(variables.get("os"), Some(std::env::consts::OS));
+        assert_eq!(variables.get("arch"), Some(std::env::consts::ARCH));
+
+        let mut custom = variables.with("os", "plan9");
+        custom.extend([("profile", "work")]);
+        assert_eq!(custom.get("os"), Some("plan9"));
+        assert_eq!(custom.get("profile"), Some("work"));
+    }
+}
@@ -0,0 +1,212 @@
+//! Drift detection - notice when a system wanders from its desired state
+//!
+//! [`watch`] rebuilds a plan every interval, diffs it, and reports what
+//! changed since the last check as [`DriftEvent`]s: resources that drifted,
+//! and drifted resources that are back in their desired state. A
+//! [`DriftWatcher`] does a single check, for callers with a loop of their own.
+//!
+//! ```ignore
+//! declarative::watch(|| build_plan(&config), Duration::from_secs(300), |event| {
+//!     notify(&event);
+//!     ControlFlow::Continue(())
+//! });
+//! ```
+
+use crate::diff::{ResourceDiff, compute_diffs};
+use crate::planner::ExecutionPlan;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+/// A change in how a plan's resources compare to their desired state
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum DriftEvent {
+    /// A resource drifted, or drifted differently than before
+    Drifted { diff: ResourceDiff },
+    /// A drifted resource is back in its desired state
+    Resolved {
+        resource_type: String,
+        resource_id: String,
+    },
+    /// A check finished
+    Checked {
+        /// Resources currently drifted
+        drifted: usize,
+    },
+    /// The plan couldn't be built; the next check tries again
+    CheckFailed { error: String },
+}
+
+/// Remembers drift between checks, to report only what changed
+#[derive(Debug, Default)]
+pub struct DriftWatcher {
+    /// Drifted resources, by [`cache_key`](crate::cache_key)
+    drifted: BTreeMap<String, ResourceDiff>,
+}
+
+impl DriftWatcher {
+    /// Create a watcher that has seen no drift yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resources drifted as of the last check
+    pub fn drifted(&self) -> impl Iterator<Item = &ResourceDiff> {
+        self.drifted.values()
+    }
+
+    /// Diff a plan and report what changed since the last check
+    ///
+    /// Resources skipped by their precondition aren't drift. Always ends
+    /// with a [`DriftEvent::Checked`].
+    pub fn check(&mut self, plan: &ExecutionPlan) -> Vec<DriftEvent> {
+        let current: BTreeMap<String, ResourceDiff> = compute_diffs(&plan.unprivileged)
+            .into_iter()
+            .chain(compute_diffs(&plan.privileged))
+            .filter(|diff| !diff.is_skipped())
+            .map(|diff| (format!("{}:{}", diff.resource_type, diff.resource_id), diff))
+            .collect();
+
+        let mut events = Vec::new();
+        for (key, previous) in &self.drifted {
+            if !current.contains_key(key) {
+                events.push(DriftEvent::Resolved {
+                    resource_type: previous.resource_type.clone(),
+                    resource_id: previous.resource_id.clone(),
+                });
+            }
+        }
+        for (key, diff) in &current {
+            let unchanged = self.drifted.get(key).is_some_and(|previous| {
+                previous.current == diff.current && previous.desired == diff.desired
+            });
+            if !unchanged {
+                events.push(DriftEvent::Drifted { diff: diff.clone() });
+            }
+        }
+
+        events.push(DriftEvent::Checked {
+            drifted: current.len(),
+        });
+        self.drifted = current;
+        events
+    }
+}
+
+/// Check a plan for drift every `interval`, until `callback` breaks
+///
+/// The plan is rebuilt by `plan_factory` for every check, so configuration
+/// changes are picked up. The first check runs right away and reports all
+/// existing drift; later ones report only changes. A plan that fails to
+/// build is reported as [`DriftEvent::CheckFailed`] and doesn't stop
+/// watching.
+pub fn watch<F, C>(mut plan_factory: F, interval: Duration, mut callback: C)
+where
+    F: FnMut() -> Result<ExecutionPlan>,
+    C: FnMut(DriftEvent) -> ControlFlow<()>,
+{
+    let mut watcher = DriftWatcher::new();
+    loop {
+        let events = match plan_factory() {
+            Ok(plan) => watcher.check(&plan),
+            Err(e) => vec![DriftEvent::CheckFailed {
+                error: e.to_string(),
+            }],
+        };
+        for event in events {
+            if callback(event).is_break() {
+                return;
+            }
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockResource, present};
+
+    fn plan(values: &[(&'static str, &'static str)]) -> ExecutionPlan {
+        let mut plan = ExecutionPlan::new();
+        for &(key, value) in values {
+            plan.unprivileged.push(Box::new(
+                MockResource::new(key)
+                    .of_type("macos_default")
+                    .with_desired(present("true"))
+                    .with_current(present(value)),
+            ));
+        }
+        plan
+    }
+
+    fn describe(events: &[DriftEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| match event {
+                DriftEvent::Drifted { diff } => format!("drifted {}", diff.resource_id),
+                DriftEvent::Resolved { resource_id, .. } => format!("resolved {resource_id}"),
+                DriftEvent::Checked { drifted } => format!("checked {drifted}"),
+                DriftEvent::CheckFailed { error } => format!("failed {error}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_watcher_reports_only_changes() {
+        let mut watcher = DriftWatcher::new();
+
+        let events = watcher.check(&plan(&[("autohide", "false"), ("show-recents", "true")]));
+        assert_eq!(describe(&events), ["drifted autohide", "checked 1"]);
+
+        // Nothing changed
+        let events = watcher.check(&plan(&[("autohide", "false"), ("show-recents", "true")]));
+        assert_eq!(describe(&events), ["checked 1"]);
+
+        let events = watcher.check(&plan(&[("autohide", "true"), ("show-recents", "false")]));
+        assert_eq!(
+            describe(&events),
+            ["resolved autohide", "drifted show-recents", "checked 1"]
+        );
+        assert_eq!(watcher.drifted().count(), 1);
+    }
+
+    #[test]
+    fn test_watch_until_callback_breaks() {
+        let mut builds = 0;
+        let mut seen = Vec::new();
+        watch(
+            || {
+                builds += 1;
+                match builds {
+                    1 => anyhow::bail!("config unreadable"),
+                    2 => Ok(plan(&[("autohide", "false")])),
+                    _ => Ok(plan(&[("autohide", "true")])),
+                }
+            },
+            Duration::ZERO,
+            |event| {
+                seen.push(event);
+                if seen.len() == 5 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            },
+        );
+
+        assert_eq!(
+            describe(&seen),
+            [
+                "failed config unreadable",
+                "drifted autohide",
+                "checked 1",
+                "resolved autohide",
+                "checked 0"
+            ]
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! Async wrapper around [`Client`] for use inside a tokio runtime.
+//!
+//! Every operation runs the blocking backend call on tokio's blocking thread
+//! pool, so callers never stall a runtime worker. Bulk operations return a
+//! [`BulkStream`] that yields one [`BulkItem`] per file as soon as it is done.
+
+use crate::error::{Error, Result};
+use crate::types::{BulkResult, DownloadOptions, EvictOptions, FileStatus, ItemOutcome};
+use crate::{Client, EvictionPolicy};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::mpsc;
+
+/// Per-file result yielded by a [`BulkStream`]
+#[derive(Debug, Clone)]
+pub struct BulkItem {
+    /// File the outcome applies to
+    pub path: PathBuf,
+    /// What happened to it
+    pub outcome: ItemOutcome,
+}
+
+impl BulkItem {
+    fn from_result(path: PathBuf, result: Result<BulkResult>) -> Self {
+        let outcome = match result {
+            Ok(r) => r.files.into_iter().next().map_or_else(
+                || ItemOutcome::Failed {
+                    error: "no outcome reported".to_string(),
+                },
+                |f| f.outcome,
+            ),
+            Err(e) => ItemOutcome::Failed {
+                error: e.to_string(),
+            },
+        };
+        Self { path, outcome }
+    }
+}
+
+/// Stream of per-file results from a bulk operation.
+///
+/// Items arrive in completion order, which differs from input order when
+/// more than one worker is used.
+pub struct BulkStream {
+    rx: mpsc::Receiver<BulkItem>,
+}
+
+impl BulkStream {
+    /// Wait for the next finished file, or `None` once all files are done
+    pub async fn next(&mut self) -> Option<BulkItem> {
+        self.rx.recv().await
+    }
+
+    /// Drain the stream into a summary, with files in completion order
+    pub async fn collect(mut self) -> BulkResult {
+        let mut result = BulkResult::default();
+        while let Some(item) = self.next().await {
+            result.record(&item.path, item.outcome);
+        }
+        result
+    }
+}
+
+/// Async iCloud Drive client.
+///
+/// Cheap to clone; clones share the underlying [`Client`] and its pin list.
+#[derive(Clone)]
+pub struct AsyncClient {
+    inner: Arc<Client>,
+}
+
+impl From<Client> for AsyncClient {
+    fn from(client: Client) -> Self {
+        Self {
+            inner: Arc::new(client),
+        }
+    }
+}
+
+impl AsyncClient {
+    /// Create a new async client with the default backend
+    pub fn new() -> Result<Self> {
+        Client::new().map(Self::from)
+    }
+
+    /// Borrow the underlying blocking client
+    pub fn blocking(&self) -> &Client {
+        &self.inner
+    }
+
+    /// Get the download/sync status of a file
+    pub async fn status(&self, path: impl AsRef<Path>) -> Result<FileStatus> {
+        let path = path.as_ref().to_path_buf();
+        self.run(move |client| client.status(path)).await
+    }
+
+    /// Evict a file; see [`Client::evict`]
+    pub async fn evict(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref().to_path_buf();
+        self.run(move |client| client.evict(path)).await
+    }
+
+    /// Start downloading a file; see [`Client::download`]
+    pub async fn download(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref().to_path_buf();
+        self.run(move |client| client.download(path)).await
+    }
+
+    /// Download a file and wait until it is local; see [`Client::download_blocking`]
+    pub async fn download_and_wait(
+        &self,
+        path: impl AsRef<Path>,
+        options: DownloadOptions,
+    ) -> Result<FileStatus> {
+        let path = path.as_ref().to_path_buf();
+        self.run(move |client| client.download_blocking(path, &options))
+            .await
+    }
+
+    /// List files in a directory with their status
+    pub async fn list(&self, path: impl AsRef<Path>) -> Result<Vec<FileStatus>> {
+        let path = path.as_ref().to_path_buf();
+        self.run(move |client| client.list(path)).await
+    }
+
+    /// Evict many files, streaming each result as it completes.
+    ///
+    /// Pinned paths are reported as skipped. Up to `options.concurrency`
+    /// files are processed at once.
+    pub fn evict_bulk(&self, paths: Vec<PathBuf>, options: EvictOptions) -> BulkStream {
+        let concurrency = options.concurrency;
+        self.stream(paths, concurrency, move |client, path| {
+            client.evict_bulk(&[path], &options)
+        })
+    }
+
+    /// Download many files, streaming each result as it completes
+    pub fn download_bulk(&self, paths: Vec<PathBuf>, options: DownloadOptions) -> BulkStream {
+        let concurrency = options.concurrency;
+        self.stream(paths, concurrency, move |client, path| {
+            client.download_bulk(&[path], &options)
+        })
+    }
+
+    /// Evict every file under `root` matching `policy`, streaming results
+    pub async fn evict_by_policy(
+        &self,
+        root: impl AsRef<Path>,
+        policy: EvictionPolicy,
+    ) -> Result<BulkStream> {
+        let root = root.as_ref().to_path_buf();
+        let files = self
+            .run(move |client| client.find_by_policy(root, &policy))
+            .await?;
+        let paths = files.into_iter().map(|f| f.path).collect();
+        Ok(self.evict_bulk(paths, EvictOptions::default()))
+    }
+
+    /// Run a blocking client call on the blocking thread pool
+    async fn run<T, F>(&self, op: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Client) -> Result<T> + Send + 'static,
+    {
+        let client = Arc::clone(&self.inner);
+        tokio::task::spawn_blocking(move || op(&client))
+            .await
+            .map_err(|e| Error::Other(format!("blocking task failed: {e}")))?
+    }
+
+    /// Fan `paths` out to a bounded set of blocking workers
+    fn stream<F>(&self, paths: Vec<PathBuf>, concurrency: Option<usize>, op: F) -> BulkStream
+    where
+        F: Fn(&Client, &Path) -> Result<BulkResult> + Send + Sync + 'static,
+    {
+        let workers = concurrency.unwrap_or(1).clamp(1, paths.len().max(1));
+        let (tx, rx) = mpsc::channel(workers * 2);
+        let paths = Arc::new(paths);
+        let next = Arc::new(AtomicUsize::new(0));
+        let op = Arc::new(op);
+
+        for _ in 0..workers {
+            let (client, paths, next, op, tx) = (
+                Arc::clone(&self.inner),
+                Arc::clone(&paths),
+                Arc::clone(&next),
+                Arc::clone(&op),
+                tx.clone(),
+            );
+            tokio::task::spawn_blocking(move || {
+                while let Some(path) = paths.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let item = BulkItem::from_result(path.clone(), op(&client, path));
+                    // Receiver dropped: the caller lost interest, stop early
+                    if tx.blocking_send(item).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        BulkStream { rx }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::NamedBackend;
+    use crate::types::{DownloadState, SkipReason};
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_status_and_evict() {
+        let client = AsyncClient::from(Client::with_backend(Box::new(NamedBackend)));
+        runtime().block_on(async {
+            let status = client.status("/icloud/a.txt").await.unwrap();
+            assert_eq!(status.state, DownloadState::Local);
+            client.evict("/icloud/a.txt").await.unwrap();
+            assert!(client.status("/icloud/bad.txt").await.is_err());
+        });
+    }
+
+    #[test]
+    fn test_evict_bulk_streams_every_file() {
+        let client = AsyncClient::from(Client::with_backend(Box::new(NamedBackend)));
+        let paths: Vec<PathBuf> = ["a", "b", "cloud1", "bad1", "c"]
+            .iter()
+            .map(|n| PathBuf::from("/icloud").join(n))
+            .collect();
+        let options = EvictOptions {
+            concurrency: Some(3),
+            ..Default::default()
+        };
+
+        runtime().block_on(async {
+            let mut stream = client.evict_bulk(paths.clone(), options.clone());
+            let mut seen = Vec::new();
+            while let Some(item) = stream.next().await {
+                if item.path.ends_with("cloud1") {
+                    assert_eq!(
+                        item.outcome,
+                        ItemOutcome::Skipped {
+                            reason: SkipReason::AlreadyDone
+                        }
+                    );
+                }
+                seen.push(item.path);
+            }
+            seen.sort();
+            let mut expected = paths.clone();
+            expected.sort();
+            assert_eq!(seen, expected);
+
+            let summary = client.evict_bulk(paths, options).collect().await;
+            assert_eq!(summary.succeeded, 3);
+            assert_eq!(summary.skipped, 1);
+            assert_eq!(summary.failed, 1);
+            assert_eq!(summary.bytes, 30);
+        });
+    }
+}
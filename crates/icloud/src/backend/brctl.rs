@@ -13,8 +13,9 @@
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use crate::containers::{self, Container};
 use crate::error::{Error, Result};
-use crate::types::{DownloadState, FileStatus};
+use crate::types::{AccountUsage, DownloadState, FileStatus, RetryConfig};
 
 use super::Backend;
 
@@ -35,8 +36,20 @@ use super::Backend;
 /// - Download (fetch cloud to local)
 ///
 /// There is no way to delete files from iCloud using brctl.
+///
+/// ## Retries
+///
+/// brctl sometimes fails with "operation couldn't be completed" while the
+/// sync daemon (`bird`) is busy. Those failures are retried with backoff,
+/// configured per operation via [`with_evict_retry`](Self::with_evict_retry),
+/// [`with_download_retry`](Self::with_download_retry) and
+/// [`with_quota_retry`](Self::with_quota_retry). Permanent errors (missing
+/// file, permission denied, upload in progress) fail immediately.
 pub struct BrctlBackend {
     icloud_root: PathBuf,
+    evict_retry: RetryConfig,
+    download_retry: RetryConfig,
+    quota_retry: RetryConfig,
 }
 
 impl BrctlBackend {
@@ -66,7 +79,50 @@ impl BrctlBackend {
             )));
         }
 
-        Ok(Self { icloud_root })
+        Ok(Self {
+            icloud_root,
+            evict_retry: RetryConfig::default(),
+            download_retry: RetryConfig::default(),
+            quota_retry: RetryConfig::default(),
+        })
+    }
+
+    /// Use `retry` for every brctl operation
+    #[must_use]
+    pub fn with_retry(self, retry: RetryConfig) -> Self {
+        Self {
+            evict_retry: retry,
+            download_retry: retry,
+            quota_retry: retry,
+            ..self
+        }
+    }
+
+    /// Retry policy for `brctl evict`
+    #[must_use]
+    pub fn with_evict_retry(self, retry: RetryConfig) -> Self {
+        Self {
+            evict_retry: retry,
+            ..self
+        }
+    }
+
+    /// Retry policy for `brctl download`
+    #[must_use]
+    pub fn with_download_retry(self, retry: RetryConfig) -> Self {
+        Self {
+            download_retry: retry,
+            ..self
+        }
+    }
+
+    /// Retry policy for `brctl quota`
+    #[must_use]
+    pub fn with_quota_retry(self, retry: RetryConfig) -> Self {
+        Self {
+            quota_retry: retry,
+            ..self
+        }
     }
 
     /// Check if brctl is available on this system.
@@ -97,7 +153,16 @@ impl BrctlBackend {
         }
     }
 
+    /// Run a brctl command, retrying transient failures per `retry`.
+    fn run_brctl_with_retry(&self, args: &[&str], retry: &RetryConfig) -> Result<String> {
+        with_retry(retry, std::thread::sleep, || self.run_brctl(args))
+    }
+
     /// Parse brctl error messages into specific error types.
+    ///
+    /// brctl prefixes nearly every failure with "operation couldn't be
+    /// completed", so the permanent errors are matched before the transient
+    /// ones.
     fn parse_brctl_error(&self, stderr: &str) -> Error {
         if stderr.contains("cannot be evicted") {
             // File is still uploading or not ready
@@ -106,6 +171,8 @@ impl BrctlBackend {
             Error::NotFound(PathBuf::from("file not found"))
         } else if stderr.contains("Permission denied") {
             Error::PermissionDenied(PathBuf::from("permission denied"))
+        } else if TRANSIENT_ERRORS.iter().any(|msg| stderr.contains(msg)) {
+            Error::BrctlBusy(stderr.trim().to_string())
         } else {
             Error::BrctlFailed(stderr.to_string())
         }
@@ -180,6 +247,11 @@ impl BrctlBackend {
             .unwrap_or(false)
     }
 
+    /// `~/Library/Mobile Documents`, which holds every ubiquity container.
+    fn mobile_documents(&self) -> &Path {
+        self.icloud_root.parent().unwrap_or(&self.icloud_root)
+    }
+
     /// Canonicalize path for consistent handling.
     fn normalize_path(&self, path: &Path) -> PathBuf {
         if path.is_absolute() {
@@ -190,6 +262,12 @@ impl BrctlBackend {
     }
 }
 
+/// Bytes allocated on disk (`st_blocks` is always in 512-byte units)
+fn allocated_size(metadata: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
 impl Backend for BrctlBackend {
     fn status(&self, path: &Path) -> Result<FileStatus> {
         let path = self.normalize_path(path);
@@ -202,7 +280,7 @@ impl Backend for BrctlBackend {
             if m.is_dir() {
                 status = status.as_dir();
             } else {
-                status = status.with_size(m.len());
+                status = status.with_size(m.len()).with_allocated(allocated_size(&m));
             }
         }
 
@@ -238,7 +316,7 @@ impl Backend for BrctlBackend {
         // Execute eviction
         // Note: brctl evict ONLY removes the local copy
         // The file remains safely in iCloud
-        self.run_brctl(&["evict", path_str])?;
+        self.run_brctl_with_retry(&["evict", path_str], &self.evict_retry)?;
 
         Ok(())
     }
@@ -255,7 +333,7 @@ impl Backend for BrctlBackend {
             Error::InvalidPath(format!("path contains invalid UTF-8: {}", path.display()))
         })?;
 
-        self.run_brctl(&["download", path_str])?;
+        self.run_brctl_with_retry(&["download", path_str], &self.download_retry)?;
 
         Ok(())
     }
@@ -263,20 +341,67 @@ impl Backend for BrctlBackend {
     fn is_in_icloud(&self, path: &Path) -> bool {
         let path = self.normalize_path(path);
 
-        // Check if path is under iCloud Drive root
-        // Also check Mobile Documents for app-specific containers
-        let mobile_docs = self
-            .icloud_root
-            .parent()
-            .unwrap_or(&self.icloud_root)
-            .to_path_buf();
-
-        path.starts_with(&self.icloud_root) || path.starts_with(&mobile_docs)
+        // Anything inside a container: iCloud Drive or an app container
+        // (the Mobile Documents folder itself is not a container)
+        let mobile_docs = self.mobile_documents();
+        path.starts_with(&self.icloud_root)
+            || (path.starts_with(mobile_docs) && path != mobile_docs)
     }
 
     fn icloud_root(&self) -> Result<PathBuf> {
         Ok(self.icloud_root.clone())
     }
+
+    fn containers(&self) -> Result<Vec<Container>> {
+        containers::discover(self.mobile_documents())
+    }
+
+    fn account_usage(&self) -> Result<AccountUsage> {
+        // brctl only reports the remaining quota; total and used stay unknown
+        let output = self.run_brctl_with_retry(&["quota"], &self.quota_retry)?;
+        parse_quota(&output)
+            .map(AccountUsage::from_available)
+            .ok_or_else(|| {
+                Error::BrctlFailed(format!("unexpected quota output: {}", output.trim()))
+            })
+    }
+}
+
+/// brctl messages that mean "try again later" rather than a real failure
+const TRANSIENT_ERRORS: &[&str] = &[
+    "operation couldn’t be completed",
+    "operation couldn't be completed",
+    "Resource temporarily unavailable",
+    "Resource busy",
+    "timed out",
+];
+
+/// Run `op`, sleeping and retrying while it fails with [`Error::BrctlBusy`].
+///
+/// Only busy errors are retried: an upload in progress (`NotSynced`) takes
+/// far longer than any reasonable backoff.
+fn with_retry<T>(
+    retry: &RetryConfig,
+    sleep: impl Fn(std::time::Duration),
+    mut op: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(Error::BrctlBusy(_)) if attempt < retry.max_retries => {
+                sleep(retry.delay(attempt));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Parse `brctl quota` output, e.g. `123456789 bytes of quota remaining`.
+fn parse_quota(output: &str) -> Option<u64> {
+    output
+        .split_whitespace()
+        .find_map(|word| word.parse::<u64>().ok())
 }
 
 #[cfg(test)]
@@ -304,6 +429,106 @@ mod tests {
         }
     }
 
+    fn backend() -> BrctlBackend {
+        BrctlBackend {
+            icloud_root: PathBuf::from("/icloud"),
+            evict_retry: RetryConfig::default(),
+            download_retry: RetryConfig::default(),
+            quota_retry: RetryConfig::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_brctl_error_classifies_transient_failures() {
+        let backend = backend();
+        let busy = backend.parse_brctl_error(
+            "evict failed: The operation couldn’t be completed. (BRCloudDocsErrorDomain error 12.)",
+        );
+        assert!(matches!(busy, Error::BrctlBusy(_)));
+        assert!(busy.is_transient());
+
+        assert!(matches!(
+            backend.parse_brctl_error("No such file or directory"),
+            Error::NotFound(_)
+        ));
+        assert!(matches!(
+            backend.parse_brctl_error("item cannot be evicted"),
+            Error::NotSynced(_)
+        ));
+
+        // The generic prefix doesn't hide a permanent error behind it
+        let not_found = backend.parse_brctl_error(
+            "download failed: The operation couldn’t be completed. No such file or directory",
+        );
+        assert!(matches!(not_found, Error::NotFound(_)));
+        assert!(!not_found.is_transient());
+        assert!(matches!(
+            backend.parse_brctl_error(
+                "evict failed: The operation couldn't be completed. Permission denied"
+            ),
+            Error::PermissionDenied(_)
+        ));
+    }
+
+    #[test]
+    fn test_with_retry_backs_off_on_busy_errors() {
+        use std::cell::RefCell;
+        use std::time::Duration;
+
+        let retry = RetryConfig {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(250),
+        };
+        let delays = RefCell::new(Vec::new());
+        let sleep = |d| delays.borrow_mut().push(d);
+
+        // Succeeds on the third attempt
+        let mut calls = 0;
+        let result = with_retry(&retry, sleep, || {
+            calls += 1;
+            if calls < 3 {
+                Err(Error::BrctlBusy("busy".to_string()))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(
+            *delays.borrow(),
+            vec![Duration::from_millis(100), Duration::from_millis(200)]
+        );
+
+        // Gives up after max_retries
+        delays.borrow_mut().clear();
+        let mut calls = 0;
+        let result: Result<()> = with_retry(&retry, sleep, || {
+            calls += 1;
+            Err(Error::BrctlBusy("busy".to_string()))
+        });
+        assert!(matches!(result, Err(Error::BrctlBusy(_))));
+        assert_eq!(calls, 4);
+        assert_eq!(delays.borrow().last(), Some(&Duration::from_millis(250)));
+
+        // Permanent errors fail immediately
+        let mut calls = 0;
+        let result: Result<()> = with_retry(&retry, sleep, || {
+            calls += 1;
+            Err(Error::PermissionDenied(PathBuf::from("/icloud/a")))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_parse_quota() {
+        assert_eq!(
+            parse_quota("52613349376 bytes of quota remaining\n"),
+            Some(52_613_349_376)
+        );
+        assert_eq!(parse_quota("quota: unavailable"), None);
+    }
+
     #[test]
     fn test_brctl_available() {
         // This test will pass on macOS, fail on other platforms
@@ -0,0 +1,43 @@
+//! Backend for tests.
+//!
+//! [`NamedBackend`] decides a file's state from its name, so tests can mix
+//! local, evicted and failing files by naming them.
+
+use super::Backend;
+use crate::error::{Error, Result};
+use crate::types::{DownloadState, FileStatus};
+use std::path::{Path, PathBuf};
+
+/// Backend where files named `cloud*` are evicted and `bad*` fail.
+pub(crate) struct NamedBackend;
+
+impl Backend for NamedBackend {
+    fn status(&self, path: &Path) -> Result<FileStatus> {
+        let name = path.file_name().unwrap().to_string_lossy();
+        if name.starts_with("bad") {
+            return Err(Error::NotFound(path.to_path_buf()));
+        }
+        let state = if name.starts_with("cloud") {
+            DownloadState::Cloud
+        } else {
+            DownloadState::Local
+        };
+        Ok(FileStatus::new(path.to_path_buf(), state).with_size(10))
+    }
+
+    fn evict(&self, _path: &Path) -> Result<()> {
+        Ok(())
+    }
+
+    fn download(&self, _path: &Path) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_in_icloud(&self, _path: &Path) -> bool {
+        true
+    }
+
+    fn icloud_root(&self) -> Result<PathBuf> {
+        Ok(PathBuf::from("/icloud"))
+    }
+}
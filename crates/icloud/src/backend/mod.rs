@@ -1,10 +1,19 @@
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
-use crate::error::Result;
-use crate::types::{BulkResult, DownloadOptions, DownloadState, EvictOptions, FileStatus};
+use crate::containers::Container;
+use crate::error::{Error, Result};
+use crate::types::{
+    AccountUsage, BulkResult, DownloadOptions, DownloadState, EvictOptions, FileStatus,
+    ItemOutcome, RateLimit, SkipReason,
+};
 
 #[cfg(feature = "brctl")]
 pub mod brctl;
+#[cfg(test)]
+pub(crate) mod mock;
 
 /// Backend trait for iCloud operations
 ///
@@ -28,71 +37,229 @@ pub trait Backend: Send + Sync {
     /// Get the iCloud Drive root path
     fn icloud_root(&self) -> Result<std::path::PathBuf>;
 
+    /// List ubiquity containers (iCloud Drive plus app containers).
+    ///
+    /// Defaults to just the iCloud Drive root.
+    fn containers(&self) -> Result<Vec<Container>> {
+        Ok(Container::from_path(self.icloud_root()?)
+            .into_iter()
+            .collect())
+    }
+
+    /// Get the account's storage quota
+    fn account_usage(&self) -> Result<AccountUsage> {
+        Err(Error::Unsupported("account usage"))
+    }
+
     /// Evict multiple files with options
-    fn evict_bulk(&self, paths: &[&Path], options: &EvictOptions) -> Result<BulkResult> {
-        let mut result = BulkResult::default();
+    ///
+    /// Files are processed on up to `options.concurrency` threads; errors are
+    /// reported in the same order as `paths`. `on_item` is called as each
+    /// file finishes, in completion order.
+    fn evict_bulk(
+        &self,
+        paths: &[&Path],
+        options: &EvictOptions,
+        on_item: &OnItem<'_>,
+    ) -> Result<BulkResult> {
+        let limiter = Limiter::new(options.rate_limit);
+        Ok(run_bulk(paths, options.concurrency, on_item, |path| {
+            let status = match self.status(path) {
+                Ok(status) => status,
+                Err(e) => {
+                    return ItemOutcome::Failed {
+                        error: e.to_string(),
+                    };
+                }
+            };
+
+            if status.state == DownloadState::Cloud {
+                return ItemOutcome::Skipped {
+                    reason: SkipReason::AlreadyDone,
+                };
+            }
+
+            if let Some(min_size) = options.min_size
+                && let Some(size) = status.size
+                && size < min_size
+            {
+                return ItemOutcome::Skipped {
+                    reason: SkipReason::TooSmall,
+                };
+            }
 
-        for path in paths {
+            // A dry run reports what evicting would free, without evicting
             if options.dry_run {
-                result.add_success(0);
-                continue;
+                return ItemOutcome::Succeeded {
+                    bytes: status.reclaimable().unwrap_or(0),
+                };
             }
 
-            match self.status(path) {
-                Ok(status) => {
-                    if status.state == DownloadState::Cloud {
-                        result.add_skip();
-                        continue;
-                    }
+            limiter.wait();
+            match self.evict(path) {
+                Ok(()) => ItemOutcome::Succeeded {
+                    bytes: status.reclaimable().unwrap_or(0),
+                },
+                Err(e) => ItemOutcome::Failed {
+                    error: e.to_string(),
+                },
+            }
+        }))
+    }
 
-                    if let Some(min_size) = options.min_size
-                        && let Some(size) = status.size
-                        && size < min_size
-                    {
-                        result.add_skip();
-                        continue;
-                    }
+    /// Download multiple files with options
+    ///
+    /// Files are processed on up to `options.concurrency` threads; errors are
+    /// reported in the same order as `paths`. `on_item` is called as each
+    /// file finishes, in completion order.
+    fn download_bulk(
+        &self,
+        paths: &[&Path],
+        options: &DownloadOptions,
+        on_item: &OnItem<'_>,
+    ) -> Result<BulkResult> {
+        let limiter = Limiter::new(options.rate_limit);
+        Ok(run_bulk(paths, options.concurrency, on_item, |path| {
+            if options.dry_run {
+                return ItemOutcome::Succeeded { bytes: 0 };
+            }
 
-                    match self.evict(path) {
-                        Ok(()) => result.add_success(status.size.unwrap_or(0)),
-                        Err(e) => result.add_failure(path.to_path_buf(), e.to_string()),
-                    }
+            let status = match self.status(path) {
+                Ok(status) => status,
+                Err(e) => {
+                    return ItemOutcome::Failed {
+                        error: e.to_string(),
+                    };
                 }
-                Err(e) => result.add_failure(path.to_path_buf(), e.to_string()),
+            };
+
+            if status.state == DownloadState::Local {
+                return ItemOutcome::Skipped {
+                    reason: SkipReason::AlreadyDone,
+                };
             }
-        }
 
-        Ok(result)
+            limiter.wait();
+            match self.download(path) {
+                Ok(()) => ItemOutcome::Succeeded {
+                    bytes: status.size.unwrap_or(0),
+                },
+                Err(e) => ItemOutcome::Failed {
+                    error: e.to_string(),
+                },
+            }
+        }))
     }
+}
 
-    /// Download multiple files with options
-    fn download_bulk(&self, paths: &[&Path], options: &DownloadOptions) -> Result<BulkResult> {
-        let mut result = BulkResult::default();
+/// Shared throttle for the workers of one bulk operation
+struct Limiter {
+    limit: Option<RateLimit>,
+    started: Instant,
+    /// Files let through so far
+    count: Mutex<usize>,
+}
 
-        for path in paths {
-            if options.dry_run {
-                result.add_success(0);
-                continue;
+impl Limiter {
+    fn new(limit: Option<RateLimit>) -> Self {
+        Self {
+            limit,
+            started: Instant::now(),
+            count: Mutex::new(0),
+        }
+    }
+
+    /// Block until the next file may go to the backend
+    fn wait(&self) {
+        let Some(limit) = self.limit else { return };
+        let mut count = self.count.lock().unwrap_or_else(PoisonError::into_inner);
+        let index = *count;
+        *count += 1;
+
+        match limit {
+            RateLimit::PerSecond(rate) if rate > 0.0 => {
+                drop(count);
+                // A rate too slow for a Duration waits as long as one can
+                let delay =
+                    Duration::try_from_secs_f64(index as f64 / rate).unwrap_or(Duration::MAX);
+                let wait = match self.started.checked_add(delay) {
+                    Some(due) => due.saturating_duration_since(Instant::now()),
+                    None => delay,
+                };
+                std::thread::sleep(wait);
             }
+            // Sleep while holding the lock so every worker pauses
+            RateLimit::PauseEvery { items, pause }
+                if items > 0 && index > 0 && index.is_multiple_of(items) =>
+            {
+                std::thread::sleep(pause);
+            }
+            _ => {}
+        }
+    }
+}
 
-            match self.status(path) {
-                Ok(status) => {
-                    if status.state == DownloadState::Local {
-                        result.add_skip();
-                        continue;
-                    }
+/// Per-file callback for bulk operations.
+pub type OnItem<'a> = dyn Fn(&Path, &ItemOutcome) + Sync + 'a;
+
+/// Run `op` over `paths` on a bounded number of worker threads.
+///
+/// Outcomes are collected per index and folded in input order, so the
+/// result is the same regardless of scheduling.
+fn run_bulk<F>(
+    paths: &[&Path],
+    concurrency: Option<usize>,
+    on_item: &OnItem<'_>,
+    op: F,
+) -> BulkResult
+where
+    F: Fn(&Path) -> ItemOutcome + Sync,
+{
+    let op = |path: &Path| {
+        let outcome = op(path);
+        on_item(path, &outcome);
+        outcome
+    };
+
+    let workers = concurrency.unwrap_or(1).clamp(1, paths.len().max(1));
 
-                    match self.download(path) {
-                        Ok(()) => result.add_success(status.size.unwrap_or(0)),
-                        Err(e) => result.add_failure(path.to_path_buf(), e.to_string()),
+    let outcomes: Vec<ItemOutcome> = if workers == 1 {
+        paths.iter().map(|path| op(path)).collect()
+    } else {
+        let next = AtomicUsize::new(0);
+        let slots: Vec<Mutex<Option<ItemOutcome>>> =
+            paths.iter().map(|_| Mutex::new(None)).collect();
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = paths.get(i) else { break };
+                        let outcome = op(path);
+                        *slots[i].lock().unwrap_or_else(PoisonError::into_inner) = Some(outcome);
                     }
-                }
-                Err(e) => result.add_failure(path.to_path_buf(), e.to_string()),
+                });
             }
-        }
+        });
+
+        slots
+            .into_iter()
+            .map(|slot| {
+                slot.into_inner()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .unwrap_or_else(|| ItemOutcome::Failed {
+                        error: "worker thread panicked".to_string(),
+                    })
+            })
+            .collect()
+    };
 
-        Ok(result)
+    let mut result = BulkResult::default();
+    for (path, outcome) in paths.iter().zip(outcomes) {
+        result.record(path, outcome);
     }
+    result
 }
 
 /// Get the default backend based on enabled features.
@@ -102,3 +269,157 @@ pub trait Backend: Send + Sync {
 pub fn default_backend() -> Result<brctl::BrctlBackend> {
     brctl::BrctlBackend::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::mock::NamedBackend;
+    use super::*;
+    use std::path::PathBuf;
+
+    fn paths() -> Vec<PathBuf> {
+        (0..50)
+            .map(|i| match i % 5 {
+                0 => PathBuf::from(format!("/icloud/bad{i}")),
+                1 => PathBuf::from(format!("/icloud/cloud{i}")),
+                _ => PathBuf::from(format!("/icloud/local{i}")),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_evict_bulk_concurrent_matches_serial() {
+        let paths = paths();
+        let refs: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+
+        let serial = NamedBackend
+            .evict_bulk(&refs, &EvictOptions::default(), &|_, _| {})
+            .unwrap();
+        let options = EvictOptions {
+            concurrency: Some(8),
+            ..Default::default()
+        };
+        let parallel = NamedBackend
+            .evict_bulk(&refs, &options, &|_, _| {})
+            .unwrap();
+
+        assert_eq!(serial.succeeded, 30);
+        assert_eq!(serial.skipped, 10);
+        assert_eq!(serial.failed, 10);
+        assert_eq!(serial.bytes, 300);
+        assert_eq!(parallel.succeeded, serial.succeeded);
+        assert_eq!(parallel.skipped, serial.skipped);
+        assert_eq!(parallel.errors, serial.errors);
+    }
+
+    #[test]
+    fn test_evict_bulk_dry_run_reports_reclaimable_bytes() {
+        let paths = paths();
+        let refs: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+
+        let options = EvictOptions {
+            dry_run: true,
+            min_size: Some(5),
+            ..Default::default()
+        };
+        let result = NamedBackend
+            .evict_bulk(&refs, &options, &|_, _| {})
+            .unwrap();
+        assert_eq!(result.succeeded, 30);
+        assert_eq!(result.skipped, 10);
+        assert_eq!(result.failed, 10);
+        assert_eq!(result.bytes, 300);
+
+        let options = EvictOptions {
+            min_size: Some(20),
+            ..options
+        };
+        let result = NamedBackend
+            .evict_bulk(&refs, &options, &|_, _| {})
+            .unwrap();
+        assert_eq!(result.succeeded, 0);
+        assert_eq!(result.bytes, 0);
+    }
+
+    #[test]
+    fn test_rate_limit_throttles_backend_calls() {
+        let paths: Vec<PathBuf> = (0..6)
+            .map(|i| PathBuf::from(format!("/icloud/local{i}")))
+            .collect();
+        let refs: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+
+        // 6 files at 100/s: the last one is due 50ms after the first
+        let options = EvictOptions {
+            concurrency: Some(3),
+            rate_limit: Some(RateLimit::PerSecond(100.0)),
+            ..Default::default()
+        };
+        let started = Instant::now();
+        let result = NamedBackend
+            .evict_bulk(&refs, &options, &|_, _| {})
+            .unwrap();
+        assert_eq!(result.succeeded, 6);
+        assert!(started.elapsed() >= Duration::from_millis(50));
+
+        // Pauses after files 2 and 4
+        let options = DownloadOptions {
+            rate_limit: Some(RateLimit::PauseEvery {
+                items: 2,
+                pause: Duration::from_millis(20),
+            }),
+            ..Default::default()
+        };
+        let cloud: Vec<PathBuf> = (0..6)
+            .map(|i| PathBuf::from(format!("/icloud/cloud{i}")))
+            .collect();
+        let refs: Vec<&Path> = cloud.iter().map(PathBuf::as_path).collect();
+        let started = Instant::now();
+        let result = NamedBackend
+            .download_bulk(&refs, &options, &|_, _| {})
+            .unwrap();
+        assert_eq!(result.succeeded, 6);
+        assert!(started.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn test_rate_limit_ignores_rates_that_are_not_positive() {
+        for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let limiter = Limiter::new(Some(RateLimit::PerSecond(rate)));
+            let started = Instant::now();
+            for _ in 0..3 {
+                limiter.wait();
+            }
+            assert!(started.elapsed() < Duration::from_secs(1), "{rate}");
+        }
+    }
+
+    #[test]
+    fn test_download_bulk_concurrent() {
+        let paths = paths();
+        let refs: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+
+        let options = DownloadOptions {
+            concurrency: Some(4),
+            ..Default::default()
+        };
+        let seen = Mutex::new(Vec::new());
+        let result = NamedBackend
+            .download_bulk(&refs, &options, &|path, outcome| {
+                seen.lock()
+                    .unwrap()
+                    .push((path.to_path_buf(), outcome.clone()));
+            })
+            .unwrap();
+
+        let seen = seen.into_inner().unwrap();
+        assert_eq!(seen.len(), 50);
+        assert!(seen.contains(&(
+            PathBuf::from("/icloud/cloud1"),
+            ItemOutcome::Succeeded { bytes: 10 }
+        )));
+        assert_eq!(result.succeeded, 10);
+        assert_eq!(result.skipped, 30);
+        assert_eq!(result.failed, 10);
+        assert_eq!(result.errors[0].0, PathBuf::from("/icloud/bad0"));
+        assert_eq!(result.errors[9].0, PathBuf::from("/icloud/bad45"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Sync conflict detection.
+//!
+//! When two devices edit the same file, iCloud keeps both and renames one,
+//! e.g. `report (Conflicted copy from MacBook).pages` or `report 2.pages`.
+//! Detection here is name-based only; reading `NSFileVersion` conflict
+//! versions needs the (future) native backend.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// How a conflict copy was recognized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConflictKind {
+    /// Name contains "conflicted copy" or "(conflict ...)"
+    ConflictedCopy,
+    /// `name 2.ext` next to an existing `name.ext`
+    NumberedDuplicate,
+}
+
+/// A file that looks like a sync conflict copy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conflict {
+    /// The conflict copy
+    pub path: PathBuf,
+    /// The file it conflicts with, if it still exists
+    pub original: Option<PathBuf>,
+    /// How it was recognized
+    pub kind: ConflictKind,
+    /// Size in bytes
+    pub size: Option<u64>,
+}
+
+/// Check whether `path` is a conflict copy.
+///
+/// `exists` reports whether a sibling path exists; numbered duplicates are
+/// only flagged when their original is present, since `Photo 2.jpg` is
+/// often just a second photo.
+pub fn detect(
+    path: &Path,
+    exists: impl Fn(&Path) -> bool,
+) -> Option<(ConflictKind, Option<PathBuf>)> {
+    let stem = path.file_stem()?.to_string_lossy();
+    let sibling = |base: &str| {
+        let name = match path.extension() {
+            Some(ext) => format!("{base}.{}", ext.to_string_lossy()),
+            None => base.to_string(),
+        };
+        path.with_file_name(name)
+    };
+
+    if let Some(base) = strip_conflict_marker(&stem) {
+        let original = sibling(base);
+        return Some((
+            ConflictKind::ConflictedCopy,
+            exists(&original).then_some(original),
+        ));
+    }
+
+    let (base, number) = stem.rsplit_once(' ')?;
+    let is_copy_number =
+        number.parse::<u32>().is_ok_and(|n| (2..100).contains(&n)) && !number.starts_with('0');
+    if !is_copy_number || base.is_empty() {
+        return None;
+    }
+    let original = sibling(base);
+    exists(&original).then_some((ConflictKind::NumberedDuplicate, Some(original)))
+}
+
+/// `report (Conflicted copy ...)` -> `report`
+fn strip_conflict_marker(stem: &str) -> Option<&str> {
+    let lower = stem.to_lowercase();
+    let marker = lower
+        .find("(conflicted copy")
+        .or_else(|| lower.find("(conflict"))
+        .or_else(|| lower.find("conflicted copy"))?;
+    // Lowercasing can change byte offsets for non-ASCII names
+    let base = stem.get(..marker)?.trim_end();
+    (!base.is_empty()).then_some(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_conflict_copies() {
+        let existing = [
+            PathBuf::from("/d/report.pages"),
+            PathBuf::from("/d/notes.txt"),
+        ];
+        let exists = |p: &Path| existing.iter().any(|e| e == p);
+
+        assert_eq!(
+            detect(
+                Path::new("/d/report (Conflicted copy from MacBook 2026-01-02).pages"),
+                exists
+            ),
+            Some((
+                ConflictKind::ConflictedCopy,
+                Some(PathBuf::from("/d/report.pages"))
+            ))
+        );
+        // Original gone: still a conflict, just without a partner
+        assert_eq!(
+            detect(Path::new("/d/todo (conflict 1).md"), exists),
+            Some((ConflictKind::ConflictedCopy, None))
+        );
+        assert_eq!(
+            detect(Path::new("/d/notes 2.txt"), exists),
+            Some((
+                ConflictKind::NumberedDuplicate,
+                Some(PathBuf::from("/d/notes.txt"))
+            ))
+        );
+
+        // Not conflicts
+        assert_eq!(detect(Path::new("/d/Photo 2.jpg"), exists), None);
+        assert_eq!(detect(Path::new("/d/report.pages"), exists), None);
+        assert_eq!(detect(Path::new("/d/notes 2026.txt"), exists), None);
+    }
+}
@@ -0,0 +1,121 @@
+//! Ubiquity containers under `~/Library/Mobile Documents/`.
+//!
+//! iCloud Drive itself is the `com~apple~CloudDocs` container; apps that sync
+//! through iCloud (Obsidian, iA Writer, Pages, ...) get a container of their
+//! own next to it, e.g. `iCloud~md~obsidian`.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Directory name of the iCloud Drive container
+pub const ICLOUD_DRIVE_ID: &str = "com~apple~CloudDocs";
+
+/// One ubiquity container
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Container {
+    /// Container directory name (e.g. `iCloud~md~obsidian`)
+    pub id: String,
+    /// Human-readable name (e.g. `obsidian`, or `iCloud Drive`)
+    pub name: String,
+    /// Absolute path of the container
+    pub path: PathBuf,
+}
+
+impl Container {
+    /// Describe the container stored at `path`
+    pub fn from_path(path: impl Into<PathBuf>) -> Option<Self> {
+        let path = path.into();
+        let id = path.file_name()?.to_string_lossy().to_string();
+        let name = if id == ICLOUD_DRIVE_ID {
+            "iCloud Drive".to_string()
+        } else {
+            id.rsplit('~').next().unwrap_or(&id).to_string()
+        };
+        Some(Self { id, name, path })
+    }
+
+    /// Whether this is the main iCloud Drive container
+    pub fn is_icloud_drive(&self) -> bool {
+        self.id == ICLOUD_DRIVE_ID
+    }
+
+    /// Folder holding the user-visible files.
+    ///
+    /// App containers keep them in `Documents/`; iCloud Drive uses its root.
+    pub fn documents(&self) -> PathBuf {
+        let documents = self.path.join("Documents");
+        if !self.is_icloud_drive() && documents.is_dir() {
+            documents
+        } else {
+            self.path.clone()
+        }
+    }
+
+    /// Whether `path` lies inside this container
+    pub fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.path)
+    }
+}
+
+/// List the containers in `mobile_documents`, iCloud Drive first.
+///
+/// Hidden entries and plain files are skipped.
+pub fn discover(mobile_documents: &Path) -> Result<Vec<Container>> {
+    let mut containers: Vec<Container> = std::fs::read_dir(mobile_documents)?
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| Container::from_path(entry.path()))
+        .collect();
+
+    containers.sort_by(|a, b| {
+        b.is_icloud_drive()
+            .cmp(&a.is_icloud_drive())
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    Ok(containers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_lists_app_containers() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "iCloud~md~obsidian",
+            ICLOUD_DRIVE_ID,
+            "27N4MQEA55~pro~writer",
+            ".Trash",
+        ] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        std::fs::create_dir(dir.path().join("iCloud~md~obsidian/Documents")).unwrap();
+        std::fs::write(dir.path().join(".DS_Store"), "").unwrap();
+
+        let containers = discover(dir.path()).unwrap();
+        let names: Vec<&str> = containers.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["iCloud Drive", "obsidian", "writer"]);
+
+        let drive = &containers[0];
+        assert!(drive.is_icloud_drive());
+        assert_eq!(drive.documents(), dir.path().join(ICLOUD_DRIVE_ID));
+
+        let obsidian = &containers[1];
+        assert_eq!(obsidian.id, "iCloud~md~obsidian");
+        assert_eq!(
+            obsidian.documents(),
+            dir.path().join("iCloud~md~obsidian/Documents")
+        );
+        assert!(obsidian.contains(&dir.path().join("iCloud~md~obsidian/Documents/a.md")));
+        assert!(!obsidian.contains(&dir.path().join(ICLOUD_DRIVE_ID)));
+
+        // Without a Documents folder the container root is used
+        assert_eq!(
+            containers[2].documents(),
+            dir.path().join("27N4MQEA55~pro~writer")
+        );
+    }
+}
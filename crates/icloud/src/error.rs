@@ -35,6 +35,14 @@ pub enum Error {
     #[error("file is currently syncing: {0}")]
     Syncing(PathBuf),
 
+    /// Path is pinned and must not be evicted
+    #[error("path is pinned and cannot be evicted: {0}")]
+    Pinned(PathBuf),
+
+    /// Timed out waiting for a download to complete
+    #[error("timed out waiting for download: {0}")]
+    Timeout(PathBuf),
+
     /// iCloud Drive is not available or not configured
     #[error("iCloud Drive is not available: {0}")]
     ICloudNotAvailable(String),
@@ -43,6 +51,10 @@ pub enum Error {
     #[error("brctl command failed: {0}")]
     BrctlFailed(String),
 
+    /// brctl could not complete the operation right now (e.g., bird is busy)
+    #[error("brctl is busy: {0}")]
+    BrctlBusy(String),
+
     /// brctl not found (not on macOS?)
     #[error("brctl not found - this crate requires macOS")]
     BrctlNotFound,
@@ -51,6 +63,10 @@ pub enum Error {
     #[error("permission denied: {0}")]
     PermissionDenied(PathBuf),
 
+    /// The backend does not implement this operation
+    #[error("not supported by this backend: {0}")]
+    Unsupported(&'static str),
+
     /// IO error
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -72,7 +88,10 @@ impl Error {
 
     /// Returns true if this error is recoverable by waiting/retrying
     pub fn is_transient(&self) -> bool {
-        matches!(self, Error::Syncing(_) | Error::NotSynced(_))
+        matches!(
+            self,
+            Error::Syncing(_) | Error::NotSynced(_) | Error::Timeout(_) | Error::BrctlBusy(_)
+        )
     }
 
     /// Returns true if the operation was a no-op (file already in desired state)
@@ -98,6 +117,14 @@ mod tests {
         assert!(evicted.is_already_done());
         assert!(!evicted.is_transient());
 
+        let timeout = Error::Timeout(PathBuf::from("/test"));
+        assert!(timeout.is_transient());
+        assert!(!timeout.is_not_ready());
+
+        let busy = Error::BrctlBusy("operation couldn't be completed".to_string());
+        assert!(busy.is_transient());
+        assert!(!busy.is_not_ready());
+
         let not_found = Error::NotFound(PathBuf::from("/test"));
         assert!(!not_found.is_transient());
         assert!(!not_found.is_already_done());
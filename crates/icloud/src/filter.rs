@@ -0,0 +1,182 @@
+//! Path filters for listing and walking iCloud Drive.
+//!
+//! A [`FileFilter`] only looks at paths, so it is applied before any status
+//! lookup: filtered-out files never cost a `brctl` call.
+//!
+//! Eviction planning never descends into app packages such as
+//! `.photoslibrary` or `.sparsebundle` (see [`DEFAULT_EXCLUDED_BUNDLES`]):
+//! evicting files inside a library the app is using can corrupt it or
+//! trigger huge re-downloads. Other walks look inside them.
+
+use crate::policy::matches_glob;
+use std::path::Path;
+
+/// Package extensions whose contents are skipped by default
+pub const DEFAULT_EXCLUDED_BUNDLES: &[&str] = &[
+    "photoslibrary",
+    "sparsebundle",
+    "sparseimage",
+    "musiclibrary",
+    "tvlibrary",
+    "fcpbundle",
+    "lrlibrary",
+    "aplibrary",
+    "photolibrary",
+    "imovielibrary",
+    "logicx",
+    "band",
+];
+
+/// Path-based filter for [`Client::list_filtered`](crate::Client::list_filtered)
+/// and [`Client::find_evictable_filtered`](crate::Client::find_evictable_filtered).
+///
+/// Include globs and extension lists apply to files only; directories are
+/// kept (and descended into) unless they are hidden or excluded.
+#[derive(Debug, Clone, Default)]
+pub struct FileFilter {
+    /// Glob patterns a file must match (empty = every file)
+    pub include: Vec<String>,
+    /// Glob patterns that exclude a file or directory
+    pub exclude: Vec<String>,
+    /// Extensions a file must have, without the dot (empty = any)
+    pub extensions: Vec<String>,
+    /// Extensions that exclude a file, without the dot
+    pub exclude_extensions: Vec<String>,
+    /// Include hidden (dot) files and directories
+    pub include_hidden: bool,
+    /// Extra package extensions whose contents are skipped, on top of
+    /// [`DEFAULT_EXCLUDED_BUNDLES`]
+    pub exclude_bundles: Vec<String>,
+    /// Package extensions to descend into anyway (overrides both lists)
+    pub allow_bundles: Vec<String>,
+}
+
+impl FileFilter {
+    /// Only files with one of `extensions` (e.g. `["mov", "zip"]`)
+    pub fn with_extensions<S: Into<String>>(extensions: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            extensions: extensions.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Check a file's path (relative to the listing root)
+    pub fn matches_file(&self, relative_path: &Path) -> bool {
+        if !self.allows_dir(relative_path) {
+            return false;
+        }
+
+        if !self.include.is_empty() && !self.include.iter().any(|p| matches_glob(p, relative_path))
+        {
+            return false;
+        }
+
+        let extension = relative_path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase());
+        let listed = |list: &[String]| {
+            extension.as_deref().is_some_and(|ext| {
+                list.iter()
+                    .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(ext))
+            })
+        };
+
+        (self.extensions.is_empty() || listed(&self.extensions))
+            && !listed(&self.exclude_extensions)
+    }
+
+    /// Check whether a directory (relative to the listing root) is kept
+    pub fn allows_dir(&self, relative_path: &Path) -> bool {
+        if !self.include_hidden && is_hidden(relative_path) {
+            return false;
+        }
+        !self.exclude.iter().any(|p| matches_glob(p, relative_path))
+    }
+
+    /// Check whether an eviction walk may descend into a kept directory.
+    ///
+    /// Excluded packages are still listed, but their contents are not.
+    pub fn descends_into(&self, relative_path: &Path) -> bool {
+        self.allows_dir(relative_path) && !self.is_excluded_bundle(relative_path)
+    }
+
+    /// Whether `path` is a package whose contents are skipped
+    pub fn is_excluded_bundle(&self, path: &Path) -> bool {
+        let Some(extension) = path.extension().map(|e| e.to_string_lossy()) else {
+            return false;
+        };
+        let is = |e: &str| e.trim_start_matches('.').eq_ignore_ascii_case(&extension);
+
+        let excluded = DEFAULT_EXCLUDED_BUNDLES.iter().any(|e| is(e))
+            || self.exclude_bundles.iter().any(|e| is(e));
+        excluded && !self.allow_bundles.iter().any(|e| is(e))
+    }
+}
+
+/// Whether the last component of `path` starts with a dot
+pub(crate) fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_lists() {
+        let filter = FileFilter::with_extensions(["mov", ".ZIP"]);
+        assert!(filter.matches_file(Path::new("a/clip.MOV")));
+        assert!(filter.matches_file(Path::new("backup.zip")));
+        assert!(!filter.matches_file(Path::new("notes.txt")));
+        assert!(!filter.matches_file(Path::new("Makefile")));
+
+        let filter = FileFilter {
+            exclude_extensions: vec!["key".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.matches_file(Path::new("notes.txt")));
+        assert!(!filter.matches_file(Path::new("deck.key")));
+    }
+
+    #[test]
+    fn test_globs_and_hidden() {
+        let filter = FileFilter {
+            include: vec!["Projects/**".to_string()],
+            exclude: vec!["Archive".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.matches_file(Path::new("Projects/a.mov")));
+        assert!(!filter.matches_file(Path::new("Other/a.mov")));
+        assert!(!filter.matches_file(Path::new("Projects/Archive/a.mov")));
+        assert!(!filter.allows_dir(Path::new("Projects/Archive")));
+        // Include globs never prune directories
+        assert!(filter.allows_dir(Path::new("Other")));
+
+        assert!(!filter.matches_file(Path::new("Projects/.DS_Store")));
+        let hidden = FileFilter {
+            include_hidden: true,
+            ..Default::default()
+        };
+        assert!(hidden.matches_file(Path::new(".DS_Store")));
+    }
+
+    #[test]
+    fn test_bundles_are_not_descended_into() {
+        let filter = FileFilter::default();
+        let library = Path::new("Pictures/Photos Library.photoslibrary");
+        // Listed, but never walked
+        assert!(filter.allows_dir(library));
+        assert!(!filter.descends_into(library));
+        assert!(filter.descends_into(Path::new("Pictures")));
+
+        let filter = FileFilter {
+            exclude_bundles: vec!["vmwarevm".to_string()],
+            allow_bundles: vec!["SparseBundle".to_string()],
+            ..Default::default()
+        };
+        assert!(filter.is_excluded_bundle(Path::new("Windows.vmwarevm")));
+        assert!(!filter.is_excluded_bundle(Path::new("Backup.sparsebundle")));
+        assert!(filter.is_excluded_bundle(library));
+    }
+}
@@ -51,6 +51,9 @@
 //! - `brctl` (default): Uses Apple's brctl CLI tool (safe, well-tested)
 //! - `native` (future): Direct FFI to NSFileManager
 //!
+//! Enable the `tokio` feature for `AsyncClient`, whose operations return futures and whose bulk operations stream per-file
+//! results.
+//!
 //! ## Platform Support
 //!
 //! This crate only works on macOS, as iCloud Drive is a macOS/iOS feature.
@@ -64,18 +67,58 @@
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
+/// Async client built on tokio (feature `tokio`).
+#[cfg(feature = "tokio")]
+pub mod async_client;
 /// Backend implementations for iCloud operations.
 pub mod backend;
+/// Sync conflict detection.
+pub mod conflicts;
+/// Ubiquity containers (iCloud Drive and per-app folders).
+pub mod containers;
 /// Error types for iCloud operations.
 pub mod error;
+/// Path filters applied before status lookups.
+pub mod filter;
+/// Pin list of paths that must never be evicted.
+pub mod pins;
+/// Target-based eviction planning.
+pub mod planner;
+/// Rule-based eviction policies.
+pub mod policy;
 /// Common types for iCloud file status and operations.
 pub mod types;
+/// Polling watcher for sync state changes.
+pub mod watch;
 
+#[cfg(feature = "tokio")]
+pub use async_client::{AsyncClient, BulkItem, BulkStream};
+pub use conflicts::{Conflict, ConflictKind};
+pub use containers::Container;
 pub use error::{Error, Result};
-pub use types::{BulkResult, DownloadOptions, DownloadState, EvictOptions, FileStatus};
+pub use filter::{DEFAULT_EXCLUDED_BUNDLES, FileFilter};
+pub use pins::PinList;
+pub use planner::{EvictionPlan, EvictionStrategy};
+pub use policy::{EvictionPolicy, PolicyMismatch};
+pub use types::{
+    AccountUsage, BulkProgress, BulkResult, DownloadOptions, DownloadState, EvictOptions,
+    EvictionGuard, FileOutcome, FileStatus, ItemOutcome, NoProgress, RateLimit, RetryConfig,
+    SkipReason, WalkOptions,
+};
+pub use watch::{WatchEvent, WatchOptions};
 
 use backend::Backend;
+use std::collections::HashSet;
+use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Default status polling interval for [`Client::download_blocking`].
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default time [`Client::download_blocking`] waits for a file.
+const DEFAULT_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10 * 60);
 
 /// High-level client for iCloud Drive operations.
 ///
@@ -87,8 +130,11 @@ use std::path::{Path, PathBuf};
 /// - `status()` reads file state (read-only)
 ///
 /// There are NO delete operations. Files are never removed from iCloud.
+///
+/// Pinned paths (see [`pin`](Self::pin)) are never evicted.
 pub struct Client {
     backend: Box<dyn Backend>,
+    pins: Mutex<PinList>,
 }
 
 impl Client {
@@ -98,14 +144,54 @@ impl Client {
     #[cfg(feature = "brctl")]
     pub fn new() -> Result<Self> {
         let backend = backend::brctl::BrctlBackend::new()?;
-        Ok(Self {
-            backend: Box::new(backend),
-        })
+        Ok(Self::with_backend(Box::new(backend)))
     }
 
     /// Create a client with a custom backend (useful for testing).
     pub fn with_backend(backend: Box<dyn Backend>) -> Self {
-        Self { backend }
+        Self {
+            backend,
+            pins: Mutex::new(PinList::new()),
+        }
+    }
+
+    /// Persist pins in the given file (loading any existing pins).
+    ///
+    /// Without a pin file, pins only last for the lifetime of the client.
+    pub fn with_pin_file(self, file: impl Into<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            pins: Mutex::new(PinList::load(file)?),
+            ..self
+        })
+    }
+
+    /// Pin a file or directory so it is never evicted.
+    ///
+    /// Pinning a directory protects everything under it. Returns `true` if
+    /// the path wasn't already pinned.
+    pub fn pin(&self, path: impl AsRef<Path>) -> Result<bool> {
+        let path = expand_and_validate_path(path.as_ref())?;
+        self.lock_pins().pin(&path)
+    }
+
+    /// Remove a pin. Returns `true` if the path was pinned.
+    pub fn unpin(&self, path: impl AsRef<Path>) -> Result<bool> {
+        let path = expand_and_validate_path(path.as_ref())?;
+        self.lock_pins().unpin(&path)
+    }
+
+    /// Check whether a path is pinned, directly or through a parent directory.
+    pub fn is_pinned(&self, path: impl AsRef<Path>) -> bool {
+        expand_and_validate_path(path.as_ref()).is_ok_and(|p| self.lock_pins().is_pinned(&p))
+    }
+
+    /// All pinned paths.
+    pub fn pins(&self) -> Vec<PathBuf> {
+        self.lock_pins().paths()
+    }
+
+    fn lock_pins(&self) -> MutexGuard<'_, PinList> {
+        self.pins.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
     /// Get the iCloud Drive root path.
@@ -115,9 +201,30 @@ impl Client {
         self.backend.icloud_root()
     }
 
+    /// Get the iCloud account's storage quota.
+    ///
+    /// The `brctl` backend only knows the remaining quota, so `total` and
+    /// `used` are `None` there.
+    pub fn account_usage(&self) -> Result<AccountUsage> {
+        self.backend.account_usage()
+    }
+
+    /// List ubiquity containers: iCloud Drive first, then app containers
+    /// such as `iCloud~md~obsidian`.
+    pub fn containers(&self) -> Result<Vec<Container>> {
+        self.backend.containers()
+    }
+
+    /// Find the container holding `path`, if any.
+    pub fn container_for(&self, path: impl AsRef<Path>) -> Result<Option<Container>> {
+        let path = expand_and_validate_path(path.as_ref())?;
+        Ok(self.containers()?.into_iter().find(|c| c.contains(&path)))
+    }
+
     /// Check if a path is within iCloud Drive.
     ///
-    /// Returns `true` if the path is under the iCloud Drive root directory.
+    /// Returns `true` if the path is under the iCloud Drive root directory
+    /// or inside an app container.
     pub fn is_in_icloud(&self, path: impl AsRef<Path>) -> bool {
         let path = expand_path(path.as_ref());
         self.backend.is_in_icloud(&path)
@@ -160,6 +267,7 @@ impl Client {
     /// - `NotInICloud`: Path is not in iCloud Drive
     /// - `NotSynced`: File hasn't finished uploading to iCloud yet
     /// - `AlreadyEvicted`: File is already cloud-only
+    /// - `Pinned`: Path is pinned
     pub fn evict(&self, path: impl AsRef<Path>) -> Result<()> {
         let path = expand_and_validate_path(path.as_ref())?;
 
@@ -168,6 +276,10 @@ impl Client {
             return Err(Error::NotInICloud(path));
         }
 
+        if self.lock_pins().is_pinned(&path) {
+            return Err(Error::Pinned(path));
+        }
+
         self.backend.evict(&path)
     }
 
@@ -194,6 +306,82 @@ impl Client {
         self.backend.download(&path)
     }
 
+    /// Download a file and wait until it is available locally.
+    ///
+    /// Triggers the download, then polls the file status every
+    /// `options.poll_interval` until it reaches [`DownloadState::Local`].
+    /// Returns the final status.
+    ///
+    /// # Errors
+    ///
+    /// - `Timeout`: The file wasn't local before `options.timeout` (10
+    ///   minutes by default) elapsed
+    /// - Any error from [`download`](Self::download) or [`status`](Self::status)
+    pub fn download_blocking(
+        &self,
+        path: impl AsRef<Path>,
+        options: &DownloadOptions,
+    ) -> Result<FileStatus> {
+        self.download_blocking_with_progress(path, options, |_, _| {})
+    }
+
+    /// Like [`download_blocking`](Self::download_blocking), reporting progress.
+    ///
+    /// `on_progress` is called after every poll with the bytes downloaded so far
+    /// and the total size (if known).
+    pub fn download_blocking_with_progress(
+        &self,
+        path: impl AsRef<Path>,
+        options: &DownloadOptions,
+        mut on_progress: impl FnMut(u64, Option<u64>),
+    ) -> Result<FileStatus> {
+        let path = expand_and_validate_path(path.as_ref())?;
+
+        // Safety check: must be in iCloud
+        if !self.backend.is_in_icloud(&path) {
+            return Err(Error::NotInICloud(path));
+        }
+
+        let status = self.backend.status(&path)?;
+        if status.state.is_local() || options.dry_run {
+            return Ok(status);
+        }
+
+        self.backend.download(&path)?;
+
+        // A timeout too long to add to the clock never runs out
+        let deadline =
+            Instant::now().checked_add(options.timeout.unwrap_or(DEFAULT_DOWNLOAD_TIMEOUT));
+        let poll_interval = options.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL);
+
+        loop {
+            let status = self.backend.status(&path)?;
+            let total = status.size;
+
+            match status.state {
+                DownloadState::Local => {
+                    on_progress(total.unwrap_or(0), total);
+                    return Ok(status);
+                }
+                DownloadState::Downloading { percent } => {
+                    let bytes = total.map_or(0, |t| t * u64::from(percent.min(100)) / 100);
+                    on_progress(bytes, total);
+                }
+                _ => on_progress(0, total),
+            }
+
+            let remaining = deadline.map_or(Duration::MAX, |deadline| {
+                deadline.saturating_duration_since(Instant::now())
+            });
+            if remaining.is_zero() {
+                return Err(Error::Timeout(path));
+            }
+
+            // The last poll is at the deadline, not a whole interval past it
+            std::thread::sleep(poll_interval.min(remaining));
+        }
+    }
+
     /// Evict multiple files with options.
     ///
     /// See [`evict`](Self::evict) for details on what eviction does.
@@ -201,13 +389,114 @@ impl Client {
         &self,
         paths: &[impl AsRef<Path>],
         options: &EvictOptions,
+    ) -> Result<BulkResult> {
+        self.evict_bulk_with_progress(paths, options, &mut NoProgress)
+    }
+
+    /// Like [`evict_bulk`](Self::evict_bulk), reporting each file to `progress`.
+    ///
+    /// Pinned paths are reported as skipped.
+    pub fn evict_bulk_with_progress(
+        &self,
+        paths: &[impl AsRef<Path>],
+        options: &EvictOptions,
+        progress: &mut dyn BulkProgress,
+    ) -> Result<BulkResult> {
+        self.evict_checked(paths, options, None, progress)
+    }
+
+    /// Evict only the files that `guard` allows.
+    ///
+    /// Meant for belt-and-braces eviction, e.g. only files whose content is
+    /// known to exist on a backup drive. Rejected files are reported as
+    /// [`SkipReason::Unverified`], and guard errors as failures. The guard is
+    /// not consulted for cloud-only files, so checking never triggers a
+    /// download.
+    pub fn evict_verified(
+        &self,
+        paths: &[impl AsRef<Path>],
+        options: &EvictOptions,
+        guard: &dyn EvictionGuard,
+    ) -> Result<BulkResult> {
+        self.evict_verified_with_progress(paths, options, guard, &mut NoProgress)
+    }
+
+    /// Like [`evict_verified`](Self::evict_verified), reporting each file to `progress`.
+    pub fn evict_verified_with_progress(
+        &self,
+        paths: &[impl AsRef<Path>],
+        options: &EvictOptions,
+        guard: &dyn EvictionGuard,
+        progress: &mut dyn BulkProgress,
+    ) -> Result<BulkResult> {
+        self.evict_checked(paths, options, Some(guard), progress)
+    }
+
+    /// Evict `paths`, holding back pinned files and those `guard` rejects.
+    fn evict_checked(
+        &self,
+        paths: &[impl AsRef<Path>],
+        options: &EvictOptions,
+        guard: Option<&dyn EvictionGuard>,
+        progress: &mut dyn BulkProgress,
     ) -> Result<BulkResult> {
         let paths: Vec<PathBuf> = paths
             .iter()
             .filter_map(|p| expand_and_validate_path(p.as_ref()).ok())
             .collect();
-        let path_refs: Vec<&Path> = paths.iter().map(std::path::PathBuf::as_path).collect();
-        self.backend.evict_bulk(&path_refs, options)
+        progress.on_start(paths.len());
+
+        // Held-back paths are never handed to the backend
+        let mut held = Vec::new();
+        let mut to_evict = Vec::new();
+        for path in paths {
+            let verdict = if self.lock_pins().is_pinned(&path) {
+                Some(ItemOutcome::Skipped {
+                    reason: SkipReason::Pinned,
+                })
+            } else {
+                guard
+                    .filter(|_| {
+                        // Cloud-only files are left to the backend, which skips them
+                        self.backend
+                            .status(&path)
+                            .is_ok_and(|s| !s.state.is_cloud_only())
+                    })
+                    .and_then(|guard| match guard.allows(&path) {
+                        Ok(true) => None,
+                        Ok(false) => Some(ItemOutcome::Skipped {
+                            reason: SkipReason::Unverified,
+                        }),
+                        Err(e) => Some(ItemOutcome::Failed {
+                            error: e.to_string(),
+                        }),
+                    })
+            };
+            match verdict {
+                Some(outcome) => {
+                    progress.on_item(&path, &outcome);
+                    held.push((path, outcome));
+                }
+                None => to_evict.push(path),
+            }
+        }
+        let path_refs: Vec<&Path> = to_evict.iter().map(PathBuf::as_path).collect();
+
+        let progress = Mutex::new(progress);
+        let mut result = self
+            .backend
+            .evict_bulk(&path_refs, options, &|path, outcome| {
+                lock_progress(&progress).on_item(path, outcome);
+            })?;
+        for (path, outcome) in held {
+            result.record(&path, outcome);
+        }
+
+        progress
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .on_complete(&result);
+        Ok(result)
     }
 
     /// Download multiple files with options.
@@ -217,13 +506,36 @@ impl Client {
         &self,
         paths: &[impl AsRef<Path>],
         options: &DownloadOptions,
+    ) -> Result<BulkResult> {
+        self.download_bulk_with_progress(paths, options, &mut NoProgress)
+    }
+
+    /// Like [`download_bulk`](Self::download_bulk), reporting each file to `progress`.
+    pub fn download_bulk_with_progress(
+        &self,
+        paths: &[impl AsRef<Path>],
+        options: &DownloadOptions,
+        progress: &mut dyn BulkProgress,
     ) -> Result<BulkResult> {
         let paths: Vec<PathBuf> = paths
             .iter()
             .filter_map(|p| expand_and_validate_path(p.as_ref()).ok())
             .collect();
         let path_refs: Vec<&Path> = paths.iter().map(std::path::PathBuf::as_path).collect();
-        self.backend.download_bulk(&path_refs, options)
+
+        progress.on_start(path_refs.len());
+        let progress = Mutex::new(progress);
+        let result = self
+            .backend
+            .download_bulk(&path_refs, options, &|path, outcome| {
+                lock_progress(&progress).on_item(path, outcome);
+            })?;
+
+        progress
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .on_complete(&result);
+        Ok(result)
     }
 
     /// List files in an iCloud Drive directory with their status.
@@ -231,62 +543,354 @@ impl Client {
     /// Returns a list of files and their download states.
     /// Skips hidden AppleDouble files (._*).
     pub fn list(&self, path: impl AsRef<Path>) -> Result<Vec<FileStatus>> {
+        self.list_filtered(path, &FileFilter::default())
+    }
+
+    /// List a directory's entries that pass `filter`.
+    ///
+    /// The filter is checked against each entry's name before its status is
+    /// fetched, so filtered-out files cost no backend calls.
+    pub fn list_filtered(
+        &self,
+        path: impl AsRef<Path>,
+        filter: &FileFilter,
+    ) -> Result<Vec<FileStatus>> {
         let path = expand_and_validate_path(path.as_ref())?;
 
         if !self.backend.is_in_icloud(&path) {
             return Err(Error::NotInICloud(path));
         }
 
+        let results = read_entries(&path, filter.include_hidden)?
+            .into_iter()
+            .filter(|entry_path| passes_filter(filter, &path, entry_path))
+            .filter_map(|entry_path| self.status(&entry_path).ok())
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Recursively list an iCloud Drive directory with the status of every entry.
+    ///
+    /// Returns statuses for all files and directories in the subtree below `path`
+    /// (the root itself is not included). Hidden entries are skipped, as in
+    /// [`list`](Self::list), and so are their contents.
+    ///
+    /// Unreadable subdirectories are skipped rather than failing the whole walk.
+    /// When `follow_symlinks` is set, each directory is visited at most once so
+    /// symlink cycles cannot cause infinite recursion.
+    pub fn walk(&self, path: impl AsRef<Path>, options: &WalkOptions) -> Result<Vec<FileStatus>> {
+        let path = expand_and_validate_path(path.as_ref())?;
+
+        if !self.backend.is_in_icloud(&path) {
+            return Err(Error::NotInICloud(path));
+        }
+
+        let mut visited = HashSet::new();
+        if let Ok(canonical) = path.canonicalize() {
+            visited.insert(canonical);
+        }
+
         let mut results = Vec::new();
+        self.walk_dir(&path, &path, 1, options, &mut visited, &mut results)?;
+        Ok(results)
+    }
 
-        let entries = std::fs::read_dir(&path).map_err(|e| {
-            if e.kind() == std::io::ErrorKind::NotFound {
-                Error::NotFound(path.clone())
-            } else if e.kind() == std::io::ErrorKind::PermissionDenied {
-                Error::PermissionDenied(path.clone())
-            } else {
-                Error::Io(e)
+    /// Walk a single directory level, descending into subdirectories as allowed.
+    fn walk_dir(
+        &self,
+        root: &Path,
+        dir: &Path,
+        depth: usize,
+        options: &WalkOptions,
+        visited: &mut HashSet<PathBuf>,
+        results: &mut Vec<FileStatus>,
+    ) -> Result<()> {
+        for entry_path in read_entries(dir, options.filter.include_hidden)? {
+            if !passes_filter(&options.filter, root, &entry_path) {
+                continue;
             }
-        })?;
 
-        for entry in entries.flatten() {
-            let entry_path = entry.path();
+            let Ok(status) = self.status(&entry_path) else {
+                continue;
+            };
 
-            // Skip hidden AppleDouble metadata files
-            if let Some(name) = entry_path.file_name() {
-                let name_str = name.to_string_lossy();
-                if name_str.starts_with("._") || name_str.starts_with('.') {
-                    continue;
+            let relative = entry_path.strip_prefix(root).unwrap_or(&entry_path);
+            let descend = status.is_dir
+                && options.max_depth.is_none_or(|max| depth < max)
+                && if options.skip_bundles {
+                    options.filter.descends_into(relative)
+                } else {
+                    options.filter.allows_dir(relative)
                 }
-            }
+                && should_descend(&entry_path, options.follow_symlinks, visited);
+
+            results.push(status);
 
-            if let Ok(status) = self.status(&entry_path) {
-                results.push(status);
+            if descend {
+                // Errors in nested directories (e.g. permission denied) are not fatal
+                let _ = self.walk_dir(root, &entry_path, depth + 1, options, visited, results);
             }
         }
 
-        Ok(results)
+        Ok(())
     }
 
     /// Find large local files that could be evicted to free space.
     ///
-    /// Returns files larger than `min_size` bytes that are currently downloaded locally.
+    /// Searches the entire subtree below `path` (see [`walk`](Self::walk)) and returns
+    /// files larger than `min_size` bytes that are currently downloaded locally.
     /// These files can be safely evicted to free disk space while keeping them in iCloud.
     pub fn find_evictable(&self, path: impl AsRef<Path>, min_size: u64) -> Result<Vec<FileStatus>> {
-        let files = self.list(path)?;
+        self.find_evictable_filtered(path, min_size, &FileFilter::default())
+    }
+
+    /// Like [`find_evictable`](Self::find_evictable), but only considers files
+    /// passing `filter` (e.g. "only `*.mov` and `*.zip`").
+    ///
+    /// Filtered-out files and excluded directories are skipped during the walk,
+    /// before their status is fetched, and so are the contents of excluded
+    /// app packages.
+    pub fn find_evictable_filtered(
+        &self,
+        path: impl AsRef<Path>,
+        min_size: u64,
+        filter: &FileFilter,
+    ) -> Result<Vec<FileStatus>> {
+        let options = WalkOptions {
+            filter: filter.clone(),
+            skip_bundles: true,
+            ..Default::default()
+        };
+        let files = self.walk(path, &options)?;
         Ok(files
             .into_iter()
             .filter(|f| f.state.is_local() && !f.is_dir && f.size.is_some_and(|s| s >= min_size))
             .collect())
     }
 
+    /// Find files under `root` matching an eviction policy.
+    ///
+    /// Walks the whole subtree except the contents of app packages in
+    /// [`DEFAULT_EXCLUDED_BUNDLES`]; see [`EvictionPolicy`] for the rules.
+    /// Pinned files are never returned.
+    pub fn find_by_policy(
+        &self,
+        root: impl AsRef<Path>,
+        policy: &EvictionPolicy,
+    ) -> Result<Vec<FileStatus>> {
+        let root = expand_and_validate_path(root.as_ref())?;
+
+        if !self.backend.is_in_icloud(&root) {
+            return Err(Error::NotInICloud(root));
+        }
+
+        let options = WalkOptions {
+            skip_bundles: true,
+            ..Default::default()
+        };
+        let files = self.walk(&root, &options)?;
+        let pins = self.lock_pins();
+        Ok(files
+            .into_iter()
+            .filter(|f| {
+                let relative = f.path.strip_prefix(&root).unwrap_or(&f.path);
+                !pins.is_pinned(&f.path) && policy.matches(relative, f)
+            })
+            .collect())
+    }
+
+    /// Evict every file under `root` matching an eviction policy.
+    ///
+    /// See [`evict`](Self::evict) for details on what eviction does.
+    pub fn evict_by_policy(
+        &self,
+        root: impl AsRef<Path>,
+        policy: &EvictionPolicy,
+    ) -> Result<BulkResult> {
+        let files = self.find_by_policy(root, policy)?;
+        let paths: Vec<&Path> = files.iter().map(|f| f.path.as_path()).collect();
+        self.evict_bulk(&paths, &EvictOptions::default())
+    }
+
+    /// Plan which local files under `root` to evict to free about `target_bytes`.
+    ///
+    /// Candidates are local, unpinned files; `strategy` decides which go
+    /// first. Nothing is evicted: inspect the plan, then pass it to
+    /// [`execute_plan`](Self::execute_plan). When the candidates cannot reach
+    /// the target, the plan holds all of them (see [`EvictionPlan::shortfall`]).
+    pub fn plan_eviction(
+        &self,
+        root: impl AsRef<Path>,
+        target_bytes: u64,
+        strategy: EvictionStrategy,
+    ) -> Result<EvictionPlan> {
+        let root = expand_and_validate_path(root.as_ref())?;
+        let candidates = self
+            .find_by_policy(&root, &EvictionPolicy::default())?
+            .into_iter()
+            .map(|f| {
+                let modified = std::fs::metadata(&f.path).and_then(|m| m.modified()).ok();
+                (f, modified)
+            })
+            .collect();
+
+        let files = planner::select(candidates, target_bytes, strategy);
+        let total_bytes = files.iter().filter_map(FileStatus::reclaimable).sum();
+        Ok(EvictionPlan {
+            root,
+            target_bytes,
+            strategy,
+            files,
+            total_bytes,
+        })
+    }
+
+    /// Evict every file in `plan`.
+    ///
+    /// Files pinned since the plan was made are skipped.
+    pub fn execute_plan(&self, plan: &EvictionPlan) -> Result<BulkResult> {
+        self.execute_plan_with_progress(plan, &mut NoProgress)
+    }
+
+    /// Like [`execute_plan`](Self::execute_plan), reporting each file to `progress`.
+    pub fn execute_plan_with_progress(
+        &self,
+        plan: &EvictionPlan,
+        progress: &mut dyn BulkProgress,
+    ) -> Result<BulkResult> {
+        let paths: Vec<&Path> = plan.files.iter().map(|f| f.path.as_path()).collect();
+        self.evict_bulk_with_progress(&paths, &EvictOptions::default(), progress)
+    }
+
+    /// Watch files below `path` and report sync state changes.
+    ///
+    /// Polls every `options.interval` and calls `on_event` for each file that
+    /// appeared, disappeared, or moved between local, cloud-only, downloading
+    /// and uploading. Runs until `on_event` returns [`ControlFlow::Break`] or
+    /// a poll fails.
+    pub fn watch(
+        &self,
+        path: impl AsRef<Path>,
+        options: &WatchOptions,
+        mut on_event: impl FnMut(&WatchEvent) -> ControlFlow<()>,
+    ) -> Result<()> {
+        let path = expand_and_validate_path(path.as_ref())?;
+
+        if !self.backend.is_in_icloud(&path) {
+            return Err(Error::NotInICloud(path));
+        }
+
+        let snapshot = || -> Result<watch::Snapshot> {
+            let files = if options.recursive {
+                self.walk(&path, &WalkOptions::default())?
+            } else {
+                self.list(&path)?
+            };
+            Ok(files
+                .into_iter()
+                .filter(|f| !f.is_dir)
+                .map(|f| (f.path, f.state))
+                .collect())
+        };
+
+        let mut previous = snapshot()?;
+        loop {
+            std::thread::sleep(options.interval);
+            let current = snapshot()?;
+            for event in watch::diff(&previous, &current) {
+                if on_event(&event).is_break() {
+                    return Ok(());
+                }
+            }
+            previous = current;
+        }
+    }
+
     /// Calculate total size of local files that could be evicted.
     ///
     /// Returns the total bytes that could be freed by evicting all local files
-    /// larger than `min_size`.
+    /// larger than `min_size`. Sizes are on-disk allocations where the backend
+    /// reports them, so sparse and compressed files are not overcounted.
     pub fn evictable_size(&self, path: impl AsRef<Path>, min_size: u64) -> Result<u64> {
         let evictable = self.find_evictable(path, min_size)?;
-        Ok(evictable.iter().filter_map(|f| f.size).sum())
+        Ok(evictable.iter().filter_map(FileStatus::reclaimable).sum())
+    }
+
+    /// Find sync conflict copies below `path`.
+    ///
+    /// Conflicts are recognized by name (see [`conflicts::detect`]), so
+    /// cloud-only copies are found without downloading them.
+    pub fn find_conflicts(&self, path: impl AsRef<Path>) -> Result<Vec<Conflict>> {
+        let files = self.walk(path, &WalkOptions::default())?;
+        Ok(files
+            .into_iter()
+            .filter(|f| !f.is_dir)
+            .filter_map(|f| {
+                let (kind, original) = conflicts::detect(&f.path, Path::exists)?;
+                Some(Conflict {
+                    path: f.path,
+                    original,
+                    kind,
+                    size: f.size,
+                })
+            })
+            .collect())
+    }
+}
+
+/// Read a directory, returning the paths of its entries.
+///
+/// Hidden files (including AppleDouble `._*` metadata files) are skipped
+/// unless `include_hidden` is set.
+fn read_entries(path: &Path, include_hidden: bool) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            Error::NotFound(path.to_path_buf())
+        } else if e.kind() == std::io::ErrorKind::PermissionDenied {
+            Error::PermissionDenied(path.to_path_buf())
+        } else {
+            Error::Io(e)
+        }
+    })?;
+
+    Ok(entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| include_hidden || !filter::is_hidden(p))
+        .collect())
+}
+
+/// Lock a shared progress callback, recovering from a panicked worker.
+fn lock_progress<'a, 'b>(
+    progress: &'a Mutex<&'b mut dyn BulkProgress>,
+) -> MutexGuard<'a, &'b mut dyn BulkProgress> {
+    progress.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Check a directory entry against `filter`, relative to the listing root.
+fn passes_filter(filter: &FileFilter, root: &Path, entry_path: &Path) -> bool {
+    let relative = entry_path.strip_prefix(root).unwrap_or(entry_path);
+    if entry_path.is_dir() {
+        filter.allows_dir(relative)
+    } else {
+        filter.matches_file(relative)
+    }
+}
+
+/// Decide whether a walk should descend into a directory entry.
+///
+/// Symlinked directories are only followed when requested, and each
+/// canonical directory is visited at most once.
+fn should_descend(path: &Path, follow_symlinks: bool, visited: &mut HashSet<PathBuf>) -> bool {
+    let is_symlink = std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink());
+    if is_symlink && !follow_symlinks {
+        return false;
+    }
+
+    match path.canonicalize() {
+        Ok(canonical) => visited.insert(canonical),
+        Err(_) => false,
     }
 }
 
@@ -327,6 +931,649 @@ fn expand_and_validate_path(path: &Path) -> Result<PathBuf> {
 mod tests {
     use super::*;
 
+    /// Backend that treats a temporary directory as iCloud Drive, reporting
+    /// every file as downloaded locally.
+    struct MockBackend {
+        root: PathBuf,
+    }
+
+    impl Backend for MockBackend {
+        fn status(&self, path: &Path) -> Result<FileStatus> {
+            let metadata = std::fs::metadata(path).map_err(|_| Error::NotFound(path.into()))?;
+            let status = FileStatus::new(path.to_path_buf(), DownloadState::Local);
+            Ok(if metadata.is_dir() {
+                status.as_dir()
+            } else {
+                status.with_size(metadata.len())
+            })
+        }
+
+        fn evict(&self, _path: &Path) -> Result<()> {
+            Ok(())
+        }
+
+        fn download(&self, _path: &Path) -> Result<()> {
+            Ok(())
+        }
+
+        fn is_in_icloud(&self, path: &Path) -> bool {
+            path.starts_with(&self.root)
+        }
+
+        fn icloud_root(&self) -> Result<PathBuf> {
+            Ok(self.root.clone())
+        }
+    }
+
+    fn mock_client(root: &Path) -> Client {
+        Client::with_backend(Box::new(MockBackend {
+            root: root.to_path_buf(),
+        }))
+    }
+
+    /// Create `a.txt`, `.hidden`, `sub/b.bin` and `sub/deeper/c.bin` under `root`.
+    fn create_tree(root: &Path) {
+        std::fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        std::fs::write(root.join("a.txt"), "a").unwrap();
+        std::fs::write(root.join(".hidden"), "h").unwrap();
+        std::fs::write(root.join("sub/b.bin"), vec![0u8; 2048]).unwrap();
+        std::fs::write(root.join("sub/deeper/c.bin"), vec![0u8; 4096]).unwrap();
+    }
+
+    fn names(files: &[FileStatus], root: &Path) -> Vec<String> {
+        let mut names: Vec<String> = files
+            .iter()
+            .map(|f| f.path.strip_prefix(root).unwrap().display().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Backend that reports a scripted sequence of states for a single file,
+    /// repeating the last one once the script runs out.
+    struct SequenceBackend {
+        states: std::sync::Mutex<Vec<DownloadState>>,
+    }
+
+    impl SequenceBackend {
+        fn new(mut states: Vec<DownloadState>) -> Self {
+            states.reverse();
+            Self {
+                states: std::sync::Mutex::new(states),
+            }
+        }
+    }
+
+    impl Backend for SequenceBackend {
+        fn status(&self, path: &Path) -> Result<FileStatus> {
+            let mut states = self.states.lock().unwrap();
+            let state = if states.len() > 1 {
+                states.pop().unwrap()
+            } else {
+                states[0]
+            };
+            Ok(FileStatus::new(path.to_path_buf(), state).with_size(1000))
+        }
+
+        fn evict(&self, _path: &Path) -> Result<()> {
+            Ok(())
+        }
+
+        fn download(&self, _path: &Path) -> Result<()> {
+            Ok(())
+        }
+
+        fn is_in_icloud(&self, _path: &Path) -> bool {
+            true
+        }
+
+        fn icloud_root(&self) -> Result<PathBuf> {
+            Ok(PathBuf::from("/icloud"))
+        }
+    }
+
+    fn fast_poll() -> DownloadOptions {
+        DownloadOptions {
+            poll_interval: Some(Duration::from_millis(1)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_download_blocking_reports_progress() {
+        let client = Client::with_backend(Box::new(SequenceBackend::new(vec![
+            DownloadState::Cloud,
+            DownloadState::Downloading { percent: 25 },
+            DownloadState::Downloading { percent: 80 },
+            DownloadState::Local,
+        ])));
+
+        let mut progress = Vec::new();
+        let status = client
+            .download_blocking_with_progress("/icloud/file.bin", &fast_poll(), |bytes, total| {
+                progress.push((bytes, total));
+            })
+            .unwrap();
+
+        assert!(status.state.is_local());
+        assert_eq!(
+            progress,
+            vec![(250, Some(1000)), (800, Some(1000)), (1000, Some(1000))]
+        );
+    }
+
+    #[test]
+    fn test_download_blocking_already_local() {
+        let client =
+            Client::with_backend(Box::new(SequenceBackend::new(vec![DownloadState::Local])));
+
+        let status = client
+            .download_blocking("/icloud/file.bin", &fast_poll())
+            .unwrap();
+        assert!(status.state.is_local());
+    }
+
+    #[test]
+    fn test_download_blocking_times_out() {
+        let client =
+            Client::with_backend(Box::new(SequenceBackend::new(vec![DownloadState::Cloud])));
+
+        let options = DownloadOptions {
+            timeout: Some(Duration::from_millis(20)),
+            ..fast_poll()
+        };
+        let err = client
+            .download_blocking("/icloud/file.bin", &options)
+            .unwrap_err();
+        assert!(matches!(err, Error::Timeout(_)));
+    }
+
+    #[test]
+    fn test_find_by_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        let old = std::time::SystemTime::now() - Duration::from_secs(100 * 24 * 60 * 60);
+        std::fs::File::options()
+            .write(true)
+            .open(dir.path().join("sub/deeper/c.bin"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        let client = mock_client(dir.path());
+
+        let policy = EvictionPolicy {
+            min_size: Some(1024),
+            ..Default::default()
+        };
+        let files = client.find_by_policy(dir.path(), &policy).unwrap();
+        assert_eq!(
+            names(&files, dir.path()),
+            vec!["sub/b.bin", "sub/deeper/c.bin"]
+        );
+
+        let policy = EvictionPolicy {
+            older_than: Some(Duration::from_secs(90 * 24 * 60 * 60)),
+            ..Default::default()
+        };
+        let files = client.find_by_policy(dir.path(), &policy).unwrap();
+        assert_eq!(names(&files, dir.path()), vec!["sub/deeper/c.bin"]);
+
+        let policy = EvictionPolicy {
+            exclude: vec!["deeper".to_string()],
+            ..Default::default()
+        };
+        let files = client.find_by_policy(dir.path(), &policy).unwrap();
+        assert_eq!(names(&files, dir.path()), vec!["a.txt", "sub/b.bin"]);
+
+        let result = client.evict_by_policy(dir.path(), &policy).unwrap();
+        assert_eq!(result.succeeded, 2);
+    }
+
+    #[test]
+    fn test_pinned_paths_are_never_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        let client = mock_client(dir.path())
+            .with_pin_file(dir.path().join(".pins"))
+            .unwrap();
+
+        assert!(client.pin(dir.path().join("sub")).unwrap());
+        assert!(client.is_pinned(dir.path().join("sub/deeper/c.bin")));
+        assert!(!client.is_pinned(dir.path().join("a.txt")));
+
+        let err = client.evict(dir.path().join("sub/b.bin")).unwrap_err();
+        assert!(matches!(err, Error::Pinned(_)));
+
+        let paths = [dir.path().join("a.txt"), dir.path().join("sub/b.bin")];
+        let result = client.evict_bulk(&paths, &EvictOptions::default()).unwrap();
+        assert_eq!(result.succeeded, 1);
+        assert_eq!(result.skipped, 1);
+
+        let files = client
+            .find_by_policy(dir.path(), &EvictionPolicy::default())
+            .unwrap();
+        assert_eq!(names(&files, dir.path()), vec!["a.txt"]);
+        assert_eq!(
+            client
+                .evict_by_policy(dir.path(), &EvictionPolicy::default())
+                .unwrap()
+                .succeeded,
+            1
+        );
+
+        // Pins survive a new client
+        let reopened = mock_client(dir.path())
+            .with_pin_file(dir.path().join(".pins"))
+            .unwrap();
+        assert_eq!(reopened.pins(), vec![dir.path().join("sub")]);
+        assert!(reopened.unpin(dir.path().join("sub")).unwrap());
+        assert!(!reopened.is_pinned(dir.path().join("sub/b.bin")));
+    }
+
+    #[test]
+    fn test_bulk_progress_reports_every_file() {
+        #[derive(Default)]
+        struct Recorder {
+            total: usize,
+            items: Vec<(PathBuf, ItemOutcome)>,
+            completed: Option<usize>,
+        }
+
+        impl BulkProgress for Recorder {
+            fn on_start(&mut self, total_files: usize) {
+                self.total = total_files;
+            }
+
+            fn on_item(&mut self, path: &Path, outcome: &ItemOutcome) {
+                self.items.push((path.to_path_buf(), outcome.clone()));
+            }
+
+            fn on_complete(&mut self, result: &BulkResult) {
+                self.completed = Some(result.succeeded + result.skipped + result.failed);
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        let client = mock_client(dir.path());
+        client.pin(dir.path().join("sub/deeper")).unwrap();
+
+        let paths = [
+            dir.path().join("a.txt"),
+            dir.path().join("sub/b.bin"),
+            dir.path().join("sub/deeper/c.bin"),
+        ];
+        let mut recorder = Recorder::default();
+        let result = client
+            .evict_bulk_with_progress(&paths, &EvictOptions::default(), &mut recorder)
+            .unwrap();
+
+        assert_eq!(recorder.total, 3);
+        assert_eq!(recorder.completed, Some(3));
+        assert_eq!(result.succeeded, 2);
+        assert_eq!(result.skipped, 1);
+        assert!(recorder.items.contains(&(
+            dir.path().join("sub/deeper/c.bin"),
+            ItemOutcome::Skipped {
+                reason: SkipReason::Pinned
+            }
+        )));
+        assert!(recorder.items.contains(&(
+            dir.path().join("sub/b.bin"),
+            ItemOutcome::Succeeded { bytes: 2048 }
+        )));
+    }
+
+    #[test]
+    fn test_evict_verified_only_evicts_allowed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        let client = mock_client(dir.path());
+
+        // Pretend only b.bin is backed up and the backup can't read c.bin
+        let guard = |path: &Path| match path.file_name().and_then(|n| n.to_str()) {
+            Some("b.bin") => Ok(true),
+            Some("c.bin") => Err(Error::Other("backup unreadable".to_string())),
+            _ => Ok(false),
+        };
+        let result = client
+            .evict_verified(
+                &[
+                    dir.path().join("a.txt"),
+                    dir.path().join("sub/b.bin"),
+                    dir.path().join("sub/deeper/c.bin"),
+                ],
+                &EvictOptions::default(),
+                &guard,
+            )
+            .unwrap();
+
+        let evicted: Vec<&Path> = result.succeeded_paths().collect();
+        assert_eq!(evicted, vec![dir.path().join("sub/b.bin")]);
+        assert_eq!((result.skipped, result.failed), (1, 1));
+        assert!(
+            result
+                .files
+                .iter()
+                .any(|f| f.path == dir.path().join("a.txt")
+                    && f.outcome
+                        == ItemOutcome::Skipped {
+                            reason: SkipReason::Unverified
+                        })
+        );
+    }
+
+    #[test]
+    fn test_status_and_bulk_result_serialize_to_json() {
+        let status = FileStatus::new(
+            PathBuf::from("/icloud/a.txt"),
+            DownloadState::Downloading { percent: 40 },
+        )
+        .with_size(10);
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["path"], "/icloud/a.txt");
+        assert_eq!(json["state"]["Downloading"]["percent"], 40);
+        assert_eq!(json["size"], 10);
+        assert_eq!(serde_json::to_value(DownloadState::Cloud).unwrap(), "Cloud");
+
+        let mut result = BulkResult::default();
+        result.record(
+            Path::new("/icloud/a.txt"),
+            ItemOutcome::Succeeded { bytes: 10 },
+        );
+        result.record(
+            Path::new("/icloud/b.txt"),
+            ItemOutcome::Failed {
+                error: "boom".to_string(),
+            },
+        );
+        result.record(
+            Path::new("/icloud/c.txt"),
+            ItemOutcome::Skipped {
+                reason: SkipReason::Pinned,
+            },
+        );
+        let json: serde_json::Value = serde_json::from_str(&result.to_json().unwrap()).unwrap();
+        assert_eq!(json["succeeded"], 1);
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["errors"][0][0], "/icloud/b.txt");
+        assert_eq!(
+            json["files"],
+            serde_json::json!([
+                { "path": "/icloud/a.txt", "action": "succeeded", "bytes": 10 },
+                { "path": "/icloud/b.txt", "action": "failed", "error": "boom" },
+                { "path": "/icloud/c.txt", "action": "skipped", "reason": "pinned" },
+            ])
+        );
+        assert_eq!(
+            result.succeeded_paths().collect::<Vec<_>>(),
+            vec![Path::new("/icloud/a.txt")]
+        );
+
+        // Round-trips, and older reports without per-file detail still load
+        let back: BulkResult = serde_json::from_value(json).unwrap();
+        assert_eq!(back.files, result.files);
+        let old: BulkResult = serde_json::from_str(
+            r#"{"succeeded":1,"failed":0,"skipped":0,"bytes":10,"errors":[]}"#,
+        )
+        .unwrap();
+        assert!(old.files.is_empty());
+    }
+
+    #[test]
+    fn test_account_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = mock_client(dir.path()).account_usage().unwrap_err();
+        assert!(matches!(err, Error::Unsupported(_)));
+
+        let usage = AccountUsage {
+            total: Some(200),
+            used: None,
+            available: 50,
+        };
+        assert_eq!(usage.used_fraction(), Some(0.75));
+        assert_eq!(AccountUsage::from_available(50).used_fraction(), None);
+    }
+
+    #[test]
+    fn test_default_containers_is_icloud_drive() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = mock_client(dir.path());
+
+        let containers = client.containers().unwrap();
+        assert_eq!(containers.len(), 1);
+        assert_eq!(containers[0].path, dir.path());
+
+        let found = client.container_for(dir.path().join("a.txt")).unwrap();
+        assert_eq!(found, Some(containers[0].clone()));
+        assert_eq!(client.container_for("/elsewhere/a.txt").unwrap(), None);
+    }
+
+    #[test]
+    fn test_plan_and_execute_eviction() {
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        let client = mock_client(dir.path());
+
+        let plan = client
+            .plan_eviction(dir.path(), 3000, EvictionStrategy::LargestFirst)
+            .unwrap();
+        assert_eq!(names(&plan.files, dir.path()), vec!["sub/deeper/c.bin"]);
+        assert_eq!(plan.total_bytes, 4096);
+        assert!(plan.meets_target());
+
+        let plan = client
+            .plan_eviction(dir.path(), 100_000, EvictionStrategy::LargestFirst)
+            .unwrap();
+        assert_eq!(plan.files.len(), 3);
+        assert_eq!(plan.shortfall(), 100_000 - 4096 - 2048 - 1);
+
+        client.pin(dir.path().join("sub/deeper")).unwrap();
+        let result = client.execute_plan(&plan).unwrap();
+        assert_eq!(result.succeeded, 2);
+        assert_eq!(result.skipped, 1);
+    }
+
+    #[test]
+    fn test_watch_reports_new_files() {
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        let client = mock_client(dir.path());
+        let options = WatchOptions {
+            interval: Duration::from_millis(5),
+            recursive: true,
+        };
+
+        let new_file = dir.path().join("sub/new.txt");
+        let writer = {
+            let new_file = new_file.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(30));
+                std::fs::write(new_file, "n").unwrap();
+            })
+        };
+
+        let mut events = Vec::new();
+        client
+            .watch(dir.path(), &options, |event| {
+                events.push(event.clone());
+                ControlFlow::Break(())
+            })
+            .unwrap();
+        writer.join().unwrap();
+
+        assert_eq!(
+            events,
+            vec![WatchEvent {
+                path: new_file,
+                previous: None,
+                current: Some(DownloadState::Local),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        std::fs::write(dir.path().join("a (Conflicted copy 2026-03-01).txt"), "x").unwrap();
+        std::fs::write(dir.path().join("sub/b 2.bin"), "xy").unwrap();
+        std::fs::write(dir.path().join("sub/other 2.bin"), "xy").unwrap();
+
+        let client = mock_client(dir.path());
+        let mut conflicts = client.find_conflicts(dir.path()).unwrap();
+        conflicts.sort_by(|a, b| a.path.cmp(&b.path));
+
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].kind, ConflictKind::ConflictedCopy);
+        assert_eq!(conflicts[0].original, Some(dir.path().join("a.txt")));
+        assert_eq!(conflicts[1].kind, ConflictKind::NumberedDuplicate);
+        assert_eq!(conflicts[1].original, Some(dir.path().join("sub/b.bin")));
+        assert_eq!(conflicts[1].size, Some(2));
+    }
+
+    #[test]
+    fn test_filtered_list_and_find_evictable() {
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        let client = mock_client(dir.path());
+
+        let bins = FileFilter::with_extensions(["bin"]);
+        let files = client
+            .find_evictable_filtered(dir.path(), 0, &bins)
+            .unwrap();
+        assert_eq!(
+            names(&files, dir.path()),
+            vec!["sub/b.bin", "sub/deeper/c.bin"]
+        );
+
+        let no_deeper = FileFilter {
+            exclude: vec!["deeper".to_string()],
+            ..bins
+        };
+        let files = client
+            .find_evictable_filtered(dir.path(), 0, &no_deeper)
+            .unwrap();
+        assert_eq!(names(&files, dir.path()), vec!["sub/b.bin"]);
+
+        let hidden = FileFilter {
+            include_hidden: true,
+            exclude_extensions: vec!["txt".to_string()],
+            ..Default::default()
+        };
+        let files = client.list_filtered(dir.path(), &hidden).unwrap();
+        assert_eq!(names(&files, dir.path()), vec![".hidden", "sub"]);
+    }
+
+    #[test]
+    fn test_walk_recurses_and_skips_hidden() {
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        let client = mock_client(dir.path());
+
+        let files = client.walk(dir.path(), &WalkOptions::default()).unwrap();
+        assert_eq!(
+            names(&files, dir.path()),
+            vec![
+                "a.txt",
+                "sub",
+                "sub/b.bin",
+                "sub/deeper",
+                "sub/deeper/c.bin"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_evictable_skips_bundle_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path().join("Photos Library.photoslibrary/originals");
+        std::fs::create_dir_all(&library).unwrap();
+        std::fs::write(library.join("IMG_0001.heic"), vec![0u8; 4096]).unwrap();
+        std::fs::write(dir.path().join("clip.mov"), vec![0u8; 4096]).unwrap();
+        let client = mock_client(dir.path());
+
+        let files = client.find_evictable(dir.path(), 1024).unwrap();
+        assert_eq!(names(&files, dir.path()), vec!["clip.mov"]);
+
+        let filter = FileFilter {
+            allow_bundles: vec!["photoslibrary".to_string()],
+            ..Default::default()
+        };
+        let files = client
+            .find_evictable_filtered(dir.path(), 1024, &filter)
+            .unwrap();
+        assert_eq!(
+            names(&files, dir.path()),
+            vec![
+                "Photos Library.photoslibrary/originals/IMG_0001.heic",
+                "clip.mov"
+            ]
+        );
+
+        // Other walks look inside packages
+        let files = client.walk(dir.path(), &WalkOptions::default()).unwrap();
+        assert!(
+            names(&files, dir.path())
+                .contains(&"Photos Library.photoslibrary/originals/IMG_0001.heic".to_string())
+        );
+    }
+
+    #[test]
+    fn test_walk_respects_max_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        let client = mock_client(dir.path());
+
+        let options = WalkOptions {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        let files = client.walk(dir.path(), &options).unwrap();
+        assert_eq!(
+            names(&files, dir.path()),
+            vec!["a.txt", "sub", "sub/b.bin", "sub/deeper"]
+        );
+    }
+
+    #[test]
+    fn test_walk_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        std::os::unix::fs::symlink(dir.path().join("sub"), dir.path().join("link")).unwrap();
+        // Cycle back to the root - must not recurse forever
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("sub/loop")).unwrap();
+        let client = mock_client(dir.path());
+
+        let files = client.walk(dir.path(), &WalkOptions::default()).unwrap();
+        assert!(!names(&files, dir.path()).contains(&"link/b.bin".to_string()));
+
+        let options = WalkOptions {
+            follow_symlinks: true,
+            ..Default::default()
+        };
+        let files = client.walk(dir.path(), &options).unwrap();
+        let found = names(&files, dir.path());
+        assert!(
+            found.contains(&"sub/b.bin".to_string()) || found.contains(&"link/b.bin".to_string())
+        );
+        assert!(!found.iter().any(|n| n.contains("loop/")));
+    }
+
+    #[test]
+    fn test_find_evictable_includes_nested_files() {
+        let dir = tempfile::tempdir().unwrap();
+        create_tree(dir.path());
+        let client = mock_client(dir.path());
+
+        let evictable = client.find_evictable(dir.path(), 1024).unwrap();
+        assert_eq!(
+            names(&evictable, dir.path()),
+            vec!["sub/b.bin", "sub/deeper/c.bin"]
+        );
+        assert_eq!(client.evictable_size(dir.path(), 1024).unwrap(), 6144);
+    }
+
     #[test]
     fn test_expand_path() {
         let home = std::env::var("HOME").unwrap();
@@ -0,0 +1,121 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+
+/// Paths that must never be evicted.
+///
+/// Pinning a directory protects everything under it. When backed by a file,
+/// pins are stored one absolute path per line and saved on every change.
+#[derive(Debug, Clone, Default)]
+pub struct PinList {
+    file: Option<PathBuf>,
+    pins: BTreeSet<PathBuf>,
+}
+
+impl PinList {
+    /// Create an in-memory pin list (not persisted).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load pins from a file, which is created on first save if missing.
+    pub fn load(file: impl Into<PathBuf>) -> Result<Self> {
+        let file = file.into();
+        let pins = match std::fs::read_to_string(&file) {
+            Ok(content) => content
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(PathBuf::from)
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeSet::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            file: Some(file),
+            pins,
+        })
+    }
+
+    /// Pin a path. Returns true if it wasn't already pinned.
+    pub fn pin(&mut self, path: &Path) -> Result<bool> {
+        let added = self.pins.insert(path.to_path_buf());
+        if added {
+            self.save()?;
+        }
+        Ok(added)
+    }
+
+    /// Unpin a path. Returns true if it was pinned.
+    pub fn unpin(&mut self, path: &Path) -> Result<bool> {
+        let removed = self.pins.remove(path);
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    /// Check whether a path, or any of its ancestors, is pinned.
+    pub fn is_pinned(&self, path: &Path) -> bool {
+        path.ancestors().any(|p| self.pins.contains(p))
+    }
+
+    /// All pinned paths, sorted.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.pins.iter().cloned().collect()
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut content = String::from("# Paths that must never be evicted from local storage\n");
+        for pin in &self.pins {
+            content.push_str(&pin.to_string_lossy());
+            content.push('\n');
+        }
+        std::fs::write(file, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_covers_descendants() {
+        let mut pins = PinList::new();
+        assert!(pins.pin(Path::new("/icloud/Taxes")).unwrap());
+        assert!(!pins.pin(Path::new("/icloud/Taxes")).unwrap());
+
+        assert!(pins.is_pinned(Path::new("/icloud/Taxes")));
+        assert!(pins.is_pinned(Path::new("/icloud/Taxes/2024/return.pdf")));
+        assert!(!pins.is_pinned(Path::new("/icloud/Taxes2")));
+        assert!(!pins.is_pinned(Path::new("/icloud")));
+
+        assert!(pins.unpin(Path::new("/icloud/Taxes")).unwrap());
+        assert!(!pins.is_pinned(Path::new("/icloud/Taxes/2024/return.pdf")));
+    }
+
+    #[test]
+    fn test_pins_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("state/pins");
+
+        let mut pins = PinList::load(&file).unwrap();
+        pins.pin(Path::new("/icloud/a")).unwrap();
+        pins.pin(Path::new("/icloud/b")).unwrap();
+        pins.unpin(Path::new("/icloud/a")).unwrap();
+
+        let reloaded = PinList::load(&file).unwrap();
+        assert_eq!(reloaded.paths(), vec![PathBuf::from("/icloud/b")]);
+    }
+}
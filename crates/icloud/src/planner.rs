@@ -0,0 +1,158 @@
+//! Target-based eviction planning ("free 50 GB").
+//!
+//! [`Client::plan_eviction`](crate::Client::plan_eviction) picks local files
+//! whose eviction frees roughly the requested space; the resulting
+//! [`EvictionPlan`] is plain data that can be shown, serialized, and later
+//! passed to [`Client::execute_plan`](crate::Client::execute_plan).
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::types::FileStatus;
+
+/// Order in which candidate files are picked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvictionStrategy {
+    /// Biggest files first: the fewest evictions for the target
+    #[default]
+    LargestFirst,
+    /// Least recently modified files first: the least likely to be missed
+    ColdestFirst,
+}
+
+/// Files selected for eviction to free a target amount of space
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvictionPlan {
+    /// Directory the candidates were taken from
+    pub root: PathBuf,
+    /// Bytes the caller asked to free
+    pub target_bytes: u64,
+    /// How candidates were ordered
+    pub strategy: EvictionStrategy,
+    /// Files to evict, in selection order
+    pub files: Vec<FileStatus>,
+    /// Bytes freed by evicting every file in the plan (on-disk sizes)
+    pub total_bytes: u64,
+}
+
+impl EvictionPlan {
+    /// Whether evicting the plan reaches the target
+    pub const fn meets_target(&self) -> bool {
+        self.total_bytes >= self.target_bytes
+    }
+
+    /// Bytes still missing when there were not enough candidates
+    pub const fn shortfall(&self) -> u64 {
+        self.target_bytes.saturating_sub(self.total_bytes)
+    }
+}
+
+/// Pick files from `candidates` until `target_bytes` is reached.
+///
+/// Each candidate comes with its modification time and counts for its
+/// [`reclaimable`](FileStatus::reclaimable) size. With
+/// [`LargestFirst`](EvictionStrategy::LargestFirst), once the remaining need
+/// is smaller than the next file, the smallest file that still covers it is
+/// taken instead, so a 1 GB remainder does not evict a 40 GB file.
+pub(crate) fn select(
+    mut candidates: Vec<(FileStatus, Option<SystemTime>)>,
+    target_bytes: u64,
+    strategy: EvictionStrategy,
+) -> Vec<FileStatus> {
+    let size = |f: &FileStatus| f.reclaimable().unwrap_or(0);
+    candidates.retain(|(f, _)| size(f) > 0);
+
+    match strategy {
+        EvictionStrategy::LargestFirst => {
+            candidates.sort_by(|(a, _), (b, _)| size(b).cmp(&size(a)).then(a.path.cmp(&b.path)));
+        }
+        EvictionStrategy::ColdestFirst => {
+            // Files without a modification time go last
+            candidates.sort_by(|(a, ma), (b, mb)| {
+                (ma.is_none(), ma)
+                    .cmp(&(mb.is_none(), mb))
+                    .then(a.path.cmp(&b.path))
+            });
+        }
+    }
+
+    let mut selected = Vec::new();
+    let mut freed = 0u64;
+    let mut remaining = candidates.into_iter().map(|(f, _)| f).collect::<Vec<_>>();
+
+    while freed < target_bytes && !remaining.is_empty() {
+        let need = target_bytes - freed;
+        let index = if strategy == EvictionStrategy::LargestFirst && size(&remaining[0]) > need {
+            // Sorted descending: the last file still >= need is the tightest fit
+            remaining.iter().rposition(|f| size(f) >= need).unwrap_or(0)
+        } else {
+            0
+        };
+        let file = remaining.remove(index);
+        freed += size(&file);
+        selected.push(file);
+    }
+
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DownloadState;
+    use std::time::Duration;
+
+    fn file(name: &str, size: u64, age_days: u64) -> (FileStatus, Option<SystemTime>) {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000)
+            - Duration::from_secs(age_days * 86_400);
+        (
+            FileStatus::new(PathBuf::from(name), DownloadState::Local).with_size(size),
+            Some(modified),
+        )
+    }
+
+    fn names(files: &[FileStatus]) -> Vec<String> {
+        files.iter().map(|f| f.path.display().to_string()).collect()
+    }
+
+    fn candidates() -> Vec<(FileStatus, Option<SystemTime>)> {
+        vec![
+            file("huge", 40_000, 1),
+            file("big", 10_000, 30),
+            file("mid", 3_000, 400),
+            file("small", 1_000, 90),
+            file("empty", 0, 1000),
+        ]
+    }
+
+    #[test]
+    fn test_largest_first_takes_tightest_fit_for_remainder() {
+        let plan = select(candidates(), 43_000, EvictionStrategy::LargestFirst);
+        assert_eq!(names(&plan), vec!["huge", "mid"]);
+
+        let plan = select(candidates(), 2_000, EvictionStrategy::LargestFirst);
+        assert_eq!(names(&plan), vec!["mid"]);
+    }
+
+    #[test]
+    fn test_coldest_first_and_shortfall() {
+        let plan = select(candidates(), 3_500, EvictionStrategy::ColdestFirst);
+        assert_eq!(names(&plan), vec!["mid", "small"]);
+
+        // Not enough candidates: everything with a size is selected
+        let plan = select(candidates(), 1_000_000, EvictionStrategy::ColdestFirst);
+        assert_eq!(plan.len(), 4);
+    }
+
+    #[test]
+    fn test_selection_uses_allocated_size() {
+        // A sparse 40 KB file that only occupies 4 KB on disk
+        let (sparse, modified) = file("sparse", 40_000, 1);
+        let mut candidates = candidates();
+        candidates[0] = (sparse.with_allocated(4_000), modified);
+
+        let plan = select(candidates, 10_000, EvictionStrategy::LargestFirst);
+        assert_eq!(names(&plan), vec!["big"]);
+    }
+}
@@ -0,0 +1,281 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::types::FileStatus;
+
+/// Rule-based selection of files to evict.
+///
+/// A file matches when it is downloaded locally and satisfies every rule
+/// that is set. For example, "not modified in 90 days and larger than 100MB,
+/// except Keynote files":
+///
+/// ```
+/// use std::time::Duration;
+/// use icloud::EvictionPolicy;
+///
+/// let policy = EvictionPolicy {
+///     older_than: Some(Duration::from_secs(90 * 24 * 60 * 60)),
+///     min_size: Some(100 * 1024 * 1024),
+///     exclude: vec!["*.key".to_string()],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EvictionPolicy {
+    /// Only files not modified for at least this long
+    pub older_than: Option<Duration>,
+    /// Only files at least this large (bytes)
+    pub min_size: Option<u64>,
+    /// Glob patterns a file must match (empty = every file)
+    pub include: Vec<String>,
+    /// Glob patterns that exclude a file
+    pub exclude: Vec<String>,
+}
+
+/// The rule of an [`EvictionPolicy`] a file fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyMismatch {
+    /// A directory, or a file that isn't downloaded locally
+    NotLocal,
+    /// Smaller than the minimum size
+    TooSmall,
+    /// Modified more recently than the minimum age
+    TooRecent,
+    /// Matches none of the include patterns
+    NotIncluded,
+    /// Matches an exclude pattern
+    Excluded,
+}
+
+impl EvictionPolicy {
+    /// Check whether a file matches this policy.
+    ///
+    /// `relative_path` is the file's path relative to the policy root and is
+    /// what the include/exclude globs are matched against.
+    pub fn matches(&self, relative_path: &Path, status: &FileStatus) -> bool {
+        self.mismatch(relative_path, status).is_none()
+    }
+
+    /// The first rule a file fails, or `None` if it matches this policy.
+    ///
+    /// See [`matches`](Self::matches) for what `relative_path` is.
+    pub fn mismatch(&self, relative_path: &Path, status: &FileStatus) -> Option<PolicyMismatch> {
+        let modified = std::fs::metadata(&status.path)
+            .and_then(|m| m.modified())
+            .ok();
+        self.mismatch_at(relative_path, status, modified, SystemTime::now())
+    }
+
+    #[cfg(test)]
+    fn matches_at(
+        &self,
+        relative_path: &Path,
+        status: &FileStatus,
+        modified: Option<SystemTime>,
+        now: SystemTime,
+    ) -> bool {
+        self.mismatch_at(relative_path, status, modified, now)
+            .is_none()
+    }
+
+    fn mismatch_at(
+        &self,
+        relative_path: &Path,
+        status: &FileStatus,
+        modified: Option<SystemTime>,
+        now: SystemTime,
+    ) -> Option<PolicyMismatch> {
+        if status.is_dir || !status.state.is_local() {
+            return Some(PolicyMismatch::NotLocal);
+        }
+
+        if let Some(min_size) = self.min_size
+            && status.size.is_none_or(|size| size < min_size)
+        {
+            return Some(PolicyMismatch::TooSmall);
+        }
+
+        if let Some(older_than) = self.older_than {
+            let age = modified.and_then(|m| now.duration_since(m).ok());
+            if age.is_none_or(|age| age < older_than) {
+                return Some(PolicyMismatch::TooRecent);
+            }
+        }
+
+        if !self.include.is_empty() && !self.include.iter().any(|p| matches_glob(p, relative_path))
+        {
+            return Some(PolicyMismatch::NotIncluded);
+        }
+
+        self.exclude
+            .iter()
+            .any(|p| matches_glob(p, relative_path))
+            .then_some(PolicyMismatch::Excluded)
+    }
+}
+
+/// Match a glob pattern against a relative path.
+///
+/// Supports `*` (any characters except `/`), `**` (any characters including
+/// `/`) and `?` (one character except `/`). Patterns without a `/` are matched
+/// against each path component, so `Archive` or `*.key` match at any depth.
+pub fn matches_glob(pattern: &str, relative_path: &Path) -> bool {
+    let pattern: Vec<char> = pattern.trim_start_matches('/').chars().collect();
+
+    if pattern.contains(&'/') {
+        let path: Vec<char> = relative_path.to_string_lossy().chars().collect();
+        return glob(&pattern, &path);
+    }
+
+    relative_path.components().any(|component| {
+        let name: Vec<char> = component.as_os_str().to_string_lossy().chars().collect();
+        glob(&pattern, &name)
+    })
+}
+
+fn glob(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // `**/` may also match zero directories
+            if let ['/', after @ ..] = rest
+                && glob(after, text)
+            {
+                return true;
+            }
+            (0..=text.len()).any(|i| glob(rest, &text[i..]))
+        }
+        ['*', rest @ ..] => {
+            for i in 0..=text.len() {
+                if glob(rest, &text[i..]) {
+                    return true;
+                }
+                if text.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        ['?', rest @ ..] => matches!(text, [c, ..] if *c != '/') && glob(rest, &text[1..]),
+        [c, rest @ ..] => text.first() == Some(c) && glob(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DownloadState;
+    use std::path::PathBuf;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn local(size: u64) -> FileStatus {
+        FileStatus::new(PathBuf::from("/icloud/file"), DownloadState::Local).with_size(size)
+    }
+
+    #[test]
+    fn test_matches_glob() {
+        assert!(matches_glob("*.key", Path::new("talks/demo.key")));
+        assert!(!matches_glob("*.key", Path::new("talks/demo.pdf")));
+        assert!(matches_glob("Archive", Path::new("Projects/Archive/a.txt")));
+        assert!(matches_glob(
+            "Projects/*/a.txt",
+            Path::new("Projects/x/a.txt")
+        ));
+        assert!(!matches_glob(
+            "Projects/*/a.txt",
+            Path::new("Projects/x/y/a.txt")
+        ));
+        assert!(matches_glob(
+            "Projects/**/a.txt",
+            Path::new("Projects/x/y/a.txt")
+        ));
+        assert!(matches_glob(
+            "Projects/**/a.txt",
+            Path::new("Projects/a.txt")
+        ));
+        assert!(matches_glob("/Projects/**", Path::new("Projects/x/y")));
+        assert!(matches_glob("file?.txt", Path::new("file1.txt")));
+        assert!(!matches_glob("file?.txt", Path::new("file10.txt")));
+    }
+
+    #[test]
+    fn test_policy_size_and_age() {
+        let now = SystemTime::now();
+        let policy = EvictionPolicy {
+            older_than: Some(90 * DAY),
+            min_size: Some(100),
+            ..Default::default()
+        };
+        let path = Path::new("file");
+
+        assert!(policy.matches_at(path, &local(200), Some(now - 100 * DAY), now));
+        assert!(!policy.matches_at(path, &local(50), Some(now - 100 * DAY), now));
+        assert!(!policy.matches_at(path, &local(200), Some(now - 10 * DAY), now));
+        // Unknown modification time never counts as old
+        assert!(!policy.matches_at(path, &local(200), None, now));
+    }
+
+    #[test]
+    fn test_policy_include_exclude() {
+        let now = SystemTime::now();
+        let policy = EvictionPolicy {
+            include: vec!["Projects/**".to_string()],
+            exclude: vec!["*.key".to_string()],
+            ..Default::default()
+        };
+
+        assert!(policy.matches_at(Path::new("Projects/a.mov"), &local(1), None, now));
+        assert!(!policy.matches_at(Path::new("Projects/a.key"), &local(1), None, now));
+        assert!(!policy.matches_at(Path::new("Other/a.mov"), &local(1), None, now));
+    }
+
+    #[test]
+    fn test_policy_mismatch_names_the_failed_rule() {
+        let now = SystemTime::now();
+        let policy = EvictionPolicy {
+            older_than: Some(90 * DAY),
+            min_size: Some(100),
+            include: vec!["Projects/**".to_string()],
+            exclude: vec!["*.key".to_string()],
+        };
+        let old = Some(now - 100 * DAY);
+        let mismatch = |path: &str, status: &FileStatus, modified| {
+            policy.mismatch_at(Path::new(path), status, modified, now)
+        };
+
+        assert_eq!(mismatch("Projects/a.mov", &local(200), old), None);
+        assert_eq!(
+            mismatch("Projects/a.mov", &local(50), old),
+            Some(PolicyMismatch::TooSmall)
+        );
+        assert_eq!(
+            mismatch("Projects/a.mov", &local(200), Some(now)),
+            Some(PolicyMismatch::TooRecent)
+        );
+        assert_eq!(
+            mismatch("Other/a.mov", &local(200), old),
+            Some(PolicyMismatch::NotIncluded)
+        );
+        assert_eq!(
+            mismatch("Projects/a.key", &local(200), old),
+            Some(PolicyMismatch::Excluded)
+        );
+        assert_eq!(
+            mismatch("Projects/a.mov", &local(200).as_dir(), old),
+            Some(PolicyMismatch::NotLocal)
+        );
+    }
+
+    #[test]
+    fn test_policy_skips_cloud_and_dirs() {
+        let now = SystemTime::now();
+        let policy = EvictionPolicy::default();
+        let path = Path::new("file");
+
+        let cloud = FileStatus::new(PathBuf::from("/icloud/file"), DownloadState::Cloud);
+        assert!(!policy.matches_at(path, &cloud, None, now));
+        assert!(!policy.matches_at(path, &local(1).as_dir(), None, now));
+        assert!(policy.matches_at(path, &local(1), None, now));
+    }
+}
@@ -1,5 +1,7 @@
+use crate::filter::FileFilter;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// The download/sync state of an iCloud file.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -48,6 +50,12 @@ pub struct FileStatus {
     pub state: DownloadState,
     /// File size in bytes (if known)
     pub size: Option<u64>,
+    /// Bytes allocated on disk (if known).
+    ///
+    /// Smaller than `size` for sparse or compressed files; this is what
+    /// eviction actually frees.
+    #[serde(default)]
+    pub allocated: Option<u64>,
     /// Whether this is a directory
     pub is_dir: bool,
 }
@@ -59,6 +67,7 @@ impl FileStatus {
             path,
             state,
             size: None,
+            allocated: None,
             is_dir: false,
         }
     }
@@ -69,6 +78,18 @@ impl FileStatus {
         self
     }
 
+    /// Set the on-disk allocated size
+    pub fn with_allocated(mut self, bytes: u64) -> Self {
+        self.allocated = Some(bytes);
+        self
+    }
+
+    /// Bytes freed by evicting this file: the allocated size when known,
+    /// otherwise the logical size
+    pub fn reclaimable(&self) -> Option<u64> {
+        self.allocated.or(self.size)
+    }
+
     /// Mark as directory
     pub fn as_dir(mut self) -> Self {
         self.is_dir = true;
@@ -76,6 +97,37 @@ impl FileStatus {
     }
 }
 
+/// iCloud account storage quota
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountUsage {
+    /// Total account capacity in bytes (if the backend can report it)
+    pub total: Option<u64>,
+    /// Bytes used across the account (if the backend can report it)
+    pub used: Option<u64>,
+    /// Bytes still available in the account
+    pub available: u64,
+}
+
+impl AccountUsage {
+    /// Account usage from a remaining-quota figure only
+    pub fn from_available(available: u64) -> Self {
+        Self {
+            total: None,
+            used: None,
+            available,
+        }
+    }
+
+    /// Fraction of the account in use (0.0-1.0), when the total is known
+    pub fn used_fraction(&self) -> Option<f64> {
+        let total = self.total.filter(|t| *t > 0)?;
+        let used = self
+            .used
+            .unwrap_or_else(|| total.saturating_sub(self.available));
+        Some(used as f64 / total as f64)
+    }
+}
+
 /// Options for eviction operations
 #[derive(Debug, Clone, Default)]
 pub struct EvictOptions {
@@ -85,6 +137,29 @@ pub struct EvictOptions {
     pub min_size: Option<u64>,
     /// Dry run - don't actually evict
     pub dry_run: bool,
+    /// Maximum number of files processed in parallel (`None` or `1` = serial)
+    pub concurrency: Option<usize>,
+    /// Throttle evictions so the sync daemon is not overwhelmed
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// Throttle for bulk operations.
+///
+/// Only files that reach the backend count; files skipped because they are
+/// already in the desired state go through at full speed. The limit is
+/// shared by all workers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateLimit {
+    /// At most this many files per second; a rate that isn't positive
+    /// (or is NaN) doesn't limit anything
+    PerSecond(f64),
+    /// Pause for `pause` after every `items` files
+    PauseEvery {
+        /// Files between pauses
+        items: usize,
+        /// Length of each pause
+        pause: Duration,
+    },
 }
 
 /// Options for download operations
@@ -94,6 +169,158 @@ pub struct DownloadOptions {
     pub recursive: bool,
     /// Dry run - don't actually download
     pub dry_run: bool,
+    /// Maximum number of files processed in parallel (`None` or `1` = serial)
+    pub concurrency: Option<usize>,
+    /// Maximum time to wait for a blocking download (default: 10 minutes)
+    pub timeout: Option<Duration>,
+    /// How often to poll status during a blocking download (default: 1s)
+    pub poll_interval: Option<Duration>,
+    /// Throttle downloads so the sync daemon is not overwhelmed
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// Retry policy for backend commands that can fail transiently.
+///
+/// The delay starts at `initial_delay` and doubles after each failed attempt,
+/// capped at `max_delay`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Retries after the first attempt (0 = never retry)
+    pub max_retries: u32,
+    /// Delay before the first retry
+    pub initial_delay: Duration,
+    /// Upper bound for the delay between retries
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Never retry
+    pub const fn none() -> Self {
+        Self {
+            max_retries: 0,
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+
+    /// Delay before retry number `retry` (0-based)
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay)
+    }
+}
+
+/// Options for recursive directory walks
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    /// Maximum depth to descend (`None` = unlimited, `Some(1)` = direct children only)
+    pub max_depth: Option<usize>,
+    /// Follow symbolic links to directories
+    pub follow_symlinks: bool,
+    /// Path filter applied before any status lookup
+    pub filter: FileFilter,
+    /// Skip the contents of app packages the filter excludes (see
+    /// [`FileFilter::is_excluded_bundle`]); set when planning evictions
+    pub skip_bundles: bool,
+}
+
+/// Outcome of one file in a bulk operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ItemOutcome {
+    /// The operation succeeded, affecting `bytes` bytes
+    Succeeded {
+        /// Bytes affected (0 when unknown)
+        bytes: u64,
+    },
+    /// The file was left alone
+    Skipped {
+        /// Why it was left alone
+        reason: SkipReason,
+    },
+    /// The operation failed
+    Failed {
+        /// Error message
+        error: String,
+    },
+}
+
+/// Why a bulk operation skipped a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Already evicted (or already downloaded)
+    AlreadyDone,
+    /// Smaller than the requested minimum size
+    TooSmall,
+    /// Protected by the pin list
+    Pinned,
+    /// Rejected by an [`EvictionGuard`] (e.g. no verified backup)
+    Unverified,
+}
+
+/// Per-file entry of a [`BulkResult`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileOutcome {
+    /// File the outcome applies to
+    pub path: PathBuf,
+    /// What happened to it
+    #[serde(flatten)]
+    pub outcome: ItemOutcome,
+}
+
+/// Callback trait for bulk operation progress
+///
+/// Items are reported in completion order, which differs from input order
+/// when more than one worker is used.
+pub trait BulkProgress: Send {
+    /// Called before any file is processed
+    fn on_start(&mut self, total_files: usize);
+
+    /// Called when a file is done (succeeded, skipped or failed)
+    fn on_item(&mut self, path: &Path, outcome: &ItemOutcome);
+
+    /// Called when the whole operation is done
+    fn on_complete(&mut self, result: &BulkResult);
+}
+
+/// Decides whether a local file may be evicted.
+///
+/// Used by [`Client::evict_verified`](crate::Client::evict_verified) to only
+/// free space for files that pass an extra check, such as having a copy on a
+/// backup drive. Closures `Fn(&Path) -> Result<bool>` implement it.
+pub trait EvictionGuard: Sync {
+    /// `Ok(true)` if `path` may be evicted
+    fn allows(&self, path: &Path) -> crate::Result<bool>;
+}
+
+impl<F> EvictionGuard for F
+where
+    F: Fn(&Path) -> crate::Result<bool> + Sync,
+{
+    fn allows(&self, path: &Path) -> crate::Result<bool> {
+        self(path)
+    }
+}
+
+/// A no-op progress callback for when progress isn't needed
+pub struct NoProgress;
+
+impl BulkProgress for NoProgress {
+    fn on_start(&mut self, _total_files: usize) {}
+    fn on_item(&mut self, _path: &Path, _outcome: &ItemOutcome) {}
+    fn on_complete(&mut self, _result: &BulkResult) {}
 }
 
 /// Result of a bulk operation
@@ -109,9 +336,40 @@ pub struct BulkResult {
     pub bytes: u64,
     /// Paths that failed with error messages
     pub errors: Vec<(PathBuf, String)>,
+    /// Every file recorded with [`record`](Self::record), in the order
+    /// recorded; that isn't input order for files that finish out of order,
+    /// e.g. when collected from an async `BulkStream`
+    #[serde(default)]
+    pub files: Vec<FileOutcome>,
 }
 
 impl BulkResult {
+    /// Fold one file's outcome into the totals and the per-file list
+    pub fn record(&mut self, path: &Path, outcome: ItemOutcome) {
+        match &outcome {
+            ItemOutcome::Succeeded { bytes } => self.add_success(*bytes),
+            ItemOutcome::Skipped { .. } => self.add_skip(),
+            ItemOutcome::Failed { error } => self.add_failure(path.to_path_buf(), error.clone()),
+        }
+        self.files.push(FileOutcome {
+            path: path.to_path_buf(),
+            outcome,
+        });
+    }
+
+    /// Paths that were processed successfully (e.g. evicted)
+    pub fn succeeded_paths(&self) -> impl Iterator<Item = &Path> {
+        self.files
+            .iter()
+            .filter(|f| matches!(f.outcome, ItemOutcome::Succeeded { .. }))
+            .map(|f| f.path.as_path())
+    }
+
+    /// Serialize the totals and per-file outcomes as pretty-printed JSON
+    pub fn to_json(&self) -> crate::Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| crate::Error::Other(e.to_string()))
+    }
+
     /// Add a success
     pub fn add_success(&mut self, bytes: u64) {
         self.succeeded += 1;
@@ -0,0 +1,124 @@
+//! Watching files for sync state changes.
+//!
+//! [`Client::watch`](crate::Client::watch) polls the status of every file
+//! below a directory and reports items that move between local, cloud-only,
+//! downloading and uploading. A native backend could use `NSMetadataQuery`
+//! instead; the `brctl` backend has no change notifications, so polling is
+//! the only option there.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::mem::discriminant;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::types::DownloadState;
+
+/// Options for [`Client::watch`](crate::Client::watch)
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Time between polls
+    pub interval: Duration,
+    /// Watch the whole subtree rather than the direct children only
+    pub recursive: bool,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(2),
+            recursive: true,
+        }
+    }
+}
+
+/// A file whose sync state changed between two polls
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchEvent {
+    /// File that changed
+    pub path: PathBuf,
+    /// State at the previous poll (`None` = the file just appeared)
+    pub previous: Option<DownloadState>,
+    /// State now (`None` = the file was removed or renamed)
+    pub current: Option<DownloadState>,
+}
+
+/// Snapshot of file states, keyed by path
+pub(crate) type Snapshot = HashMap<PathBuf, DownloadState>;
+
+/// Compare two snapshots, sorted by path.
+///
+/// Progress updates within the same state (e.g. 10% -> 20% downloading)
+/// are not reported.
+pub(crate) fn diff(previous: &Snapshot, current: &Snapshot) -> Vec<WatchEvent> {
+    let mut events: Vec<WatchEvent> = current
+        .iter()
+        .filter_map(|(path, state)| match previous.get(path) {
+            Some(old) if discriminant(old) == discriminant(state) => None,
+            old => Some(WatchEvent {
+                path: path.clone(),
+                previous: old.copied(),
+                current: Some(*state),
+            }),
+        })
+        .chain(
+            previous
+                .iter()
+                .filter(|(path, _)| !current.contains_key(*path))
+                .map(|(path, state)| WatchEvent {
+                    path: path.clone(),
+                    previous: Some(*state),
+                    current: None,
+                }),
+        )
+        .collect();
+    events.sort_by(|a, b| a.path.cmp(&b.path));
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_reports_state_transitions() {
+        let path = |n: &str| PathBuf::from(format!("/icloud/{n}"));
+        let previous: Snapshot = [
+            (path("evicted"), DownloadState::Local),
+            (path("progress"), DownloadState::Downloading { percent: 10 }),
+            (path("removed"), DownloadState::Cloud),
+            (path("same"), DownloadState::Local),
+        ]
+        .into_iter()
+        .collect();
+        let current: Snapshot = [
+            (path("added"), DownloadState::Uploading { percent: 0 }),
+            (path("evicted"), DownloadState::Cloud),
+            (path("progress"), DownloadState::Downloading { percent: 60 }),
+            (path("same"), DownloadState::Local),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            diff(&previous, &current),
+            vec![
+                WatchEvent {
+                    path: path("added"),
+                    previous: None,
+                    current: Some(DownloadState::Uploading { percent: 0 }),
+                },
+                WatchEvent {
+                    path: path("evicted"),
+                    previous: Some(DownloadState::Local),
+                    current: Some(DownloadState::Cloud),
+                },
+                WatchEvent {
+                    path: path("removed"),
+                    previous: Some(DownloadState::Cloud),
+                    current: None,
+                },
+            ]
+        );
+    }
+}
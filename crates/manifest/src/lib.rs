@@ -7,6 +7,7 @@
 //! - Store file metadata in a SQLite database
 //! - Find duplicate files by content hash
 //! - Track storage statistics and wasted space
+//! - Check whether a file's content is already stored on a scanned volume
 //!
 //! ## Example
 //!
@@ -136,6 +137,7 @@ impl Manifest {
 
         // Second pass: hash files
         let mut hashed = 0u64;
+        let mut hashed_bytes = 0u64;
         let mut errors = 0u64;
 
         for entry in WalkDir::new(base_path)
@@ -180,6 +182,7 @@ impl Manifest {
                 Ok(hash) => {
                     self.upsert(&rel_path_str, &hash, size, mtime)?;
                     hashed += 1;
+                    hashed_bytes += size;
                     progress.on_file_complete(true);
                 }
                 Err(_) => {
@@ -197,6 +200,7 @@ impl Manifest {
 
         let result = ScanResult {
             hashed,
+            hashed_bytes,
             errors,
             pruned,
             duplicates,
@@ -219,6 +223,56 @@ impl Manifest {
         })
     }
 
+    /// Get statistics for the files under a subtree
+    ///
+    /// `path_prefix` is relative to the scanned root (e.g. `photos/2021`) and
+    /// matches whole path components, so `photos/20` does not match
+    /// `photos/2021/a.jpg`. Duplicates are counted only among files inside the
+    /// subtree. An empty prefix is equivalent to [`stats`](Self::stats).
+    pub fn stats_for_prefix(&self, path_prefix: &str) -> Result<ManifestStats> {
+        let prefix = path_prefix.trim_matches('/');
+        if prefix.is_empty() {
+            return self.stats();
+        }
+
+        let dir_prefix = format!("{prefix}/");
+        let subtree = "WITH sub AS (
+                SELECT hash, size FROM files
+                WHERE path = ?1 OR substr(path, 1, ?2) = ?3
+            )";
+        let params = params![prefix, dir_prefix.chars().count() as i64, dir_prefix];
+
+        let (file_count, total_size): (i64, i64) = self.conn.query_row(
+            &format!("{subtree} SELECT COUNT(*), COALESCE(SUM(size), 0) FROM sub"),
+            params,
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let (dup_file_count, dup_hash_count, wasted): (i64, i64, i64) = self.conn.query_row(
+            &format!(
+                "{subtree} SELECT COALESCE(SUM(count), 0), COUNT(*), COALESCE(SUM((count - 1) * size), 0)
+                 FROM (
+                    SELECT COUNT(*) as count, MAX(size) as size
+                    FROM sub
+                    GROUP BY hash
+                    HAVING COUNT(*) > 1
+                 )"
+            ),
+            params,
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        Ok(ManifestStats {
+            file_count: file_count as u64,
+            total_size: total_size as u64,
+            duplicates: DuplicateStats {
+                duplicate_files: dup_file_count as u64,
+                duplicate_groups: dup_hash_count as u64,
+                wasted_space: wasted as u64,
+            },
+        })
+    }
+
     /// Find duplicate file groups
     ///
     /// # Arguments
@@ -262,6 +316,25 @@ impl Manifest {
         Ok(result)
     }
 
+    /// Check whether any file in the manifest has the given content hash
+    pub fn contains_hash(&self, hash: &str) -> Result<bool> {
+        let found: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM files WHERE hash = ?1)",
+            [hash],
+            |row| row.get(0),
+        )?;
+        Ok(found)
+    }
+
+    /// Check whether the content of `path` exists anywhere in the manifest
+    ///
+    /// Matches by hash only, so the file may live under a different name or
+    /// directory on the scanned volume. Useful as a backup check before
+    /// removing a local copy.
+    pub fn contains_content(&self, path: &Path) -> Result<bool> {
+        self.contains_hash(&content_hash(path)?)
+    }
+
     /// Delete a file entry from the manifest
     ///
     /// This only removes the entry from the database, not the actual file.
@@ -421,6 +494,14 @@ impl Manifest {
     }
 }
 
+/// Compute the BLAKE3 content hash of a file, as stored in manifests
+pub fn content_hash(path: &Path) -> Result<String> {
+    hash_file(path).map_err(|source| Error::HashFailed {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
 /// Hash a file using BLAKE3
 fn hash_file(path: &Path) -> std::io::Result<String> {
     let file = File::open(path)?;
@@ -529,6 +610,7 @@ mod tests {
         let result = manifest.scan(&scan_dir, false, &mut NoProgress).unwrap();
 
         assert_eq!(result.hashed, 3);
+        assert_eq!(result.hashed_bytes, 15);
         assert_eq!(result.errors, 0);
         assert_eq!(result.duplicates.duplicate_groups, 1);
         assert_eq!(result.duplicates.duplicate_files, 2);
@@ -555,6 +637,70 @@ mod tests {
         assert_eq!(dups[0].paths.len(), 2);
     }
 
+    #[test]
+    fn test_stats_for_prefix() {
+        let tmp = TempDir::new().unwrap();
+        let db_path = tmp.path().join("manifest.db");
+        let scan_dir = tmp.path().join("data");
+        std::fs::create_dir_all(scan_dir.join("photos/2021")).unwrap();
+        std::fs::create_dir_all(scan_dir.join("photos/2020")).unwrap();
+
+        std::fs::write(scan_dir.join("photos/2021/a.jpg"), "same").unwrap();
+        std::fs::write(scan_dir.join("photos/2021/b.jpg"), "same").unwrap();
+        std::fs::write(scan_dir.join("photos/2021/c.jpg"), "other").unwrap();
+        std::fs::write(scan_dir.join("photos/2020/d.jpg"), "other").unwrap();
+        std::fs::write(scan_dir.join("top.txt"), "x").unwrap();
+
+        let manifest = Manifest::open(&db_path).unwrap();
+        manifest.scan(&scan_dir, false, &mut NoProgress).unwrap();
+
+        let stats = manifest.stats_for_prefix("photos/2021").unwrap();
+        assert_eq!(stats.file_count, 3);
+        assert_eq!(stats.total_size, 13);
+        // c.jpg duplicates d.jpg, but d.jpg is outside the subtree
+        assert_eq!(stats.duplicates.duplicate_groups, 1);
+        assert_eq!(stats.duplicates.duplicate_files, 2);
+        assert_eq!(stats.duplicates.wasted_space, 4);
+
+        let photos = manifest.stats_for_prefix("/photos/").unwrap();
+        assert_eq!(photos.file_count, 4);
+        assert_eq!(photos.duplicates.duplicate_groups, 2);
+
+        // Prefixes match whole components only
+        assert_eq!(
+            manifest.stats_for_prefix("photos/20").unwrap().file_count,
+            0
+        );
+        // Exact file path
+        assert_eq!(manifest.stats_for_prefix("top.txt").unwrap().file_count, 1);
+        // Empty prefix is the whole manifest
+        assert_eq!(manifest.stats_for_prefix("").unwrap().file_count, 5);
+    }
+
+    #[test]
+    fn test_contains_content() {
+        let tmp = TempDir::new().unwrap();
+        let db_path = tmp.path().join("manifest.db");
+        let scan_dir = tmp.path().join("backup");
+        std::fs::create_dir_all(scan_dir.join("2021")).unwrap();
+        std::fs::write(scan_dir.join("2021/photo.jpg"), "pixels").unwrap();
+
+        let manifest = Manifest::open(&db_path).unwrap();
+        manifest.scan(&scan_dir, false, &mut NoProgress).unwrap();
+
+        // Same content under another name counts, different content doesn't
+        let local = tmp.path().join("renamed.jpg");
+        std::fs::write(&local, "pixels").unwrap();
+        assert!(manifest.contains_content(&local).unwrap());
+        std::fs::write(&local, "edited pixels").unwrap();
+        assert!(!manifest.contains_content(&local).unwrap());
+
+        assert!(matches!(
+            manifest.contains_content(&tmp.path().join("missing.jpg")),
+            Err(Error::HashFailed { .. })
+        ));
+    }
+
     #[test]
     fn test_path_to_name() {
         assert_eq!(path_to_name(Path::new("/Volumes/T9")), "T9");
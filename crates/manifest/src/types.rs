@@ -78,6 +78,8 @@ impl ManifestStats {
 pub struct ScanResult {
     /// Number of files successfully hashed
     pub hashed: u64,
+    /// Total size of the files hashed, in bytes
+    pub hashed_bytes: u64,
     /// Number of files that failed to hash
     pub errors: u64,
     /// Number of stale entries pruned from the manifest
@@ -0,0 +1,244 @@
+//! Release asset formats and extraction.
+//!
+//! Tools ship their binaries in different ways: Buck2 as zstd-compressed
+//! binaries, Bazel and Buildifier as plain binaries, and many others as
+//! `.tar.gz` or `.zip` archives with the binary somewhere inside. This
+//! module recognizes those formats and gets the binary out, so installers
+//! don't each handle decompression.
+
+use crate::error::{Error, Result};
+use flate2::read::GzDecoder;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// Format of a release asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// The binary itself.
+    Raw,
+    /// A zstd-compressed binary (`.zst`).
+    Zstd,
+    /// A gzip-compressed tarball (`.tar.gz`, `.tgz`).
+    TarGz,
+    /// A zip archive (`.zip`).
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Format of an asset, from its file name.
+    #[must_use]
+    pub fn from_name(name: &str) -> Self {
+        if name.ends_with(".zst") || name.ends_with(".zstd") {
+            Self::Zstd
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Self::TarGz
+        } else if name.ends_with(".zip") {
+            Self::Zip
+        } else {
+            Self::Raw
+        }
+    }
+
+    /// Format of downloaded bytes, from their magic number.
+    ///
+    /// Anything unrecognized (e.g. an ELF or Mach-O binary) is `Raw`.
+    #[must_use]
+    pub fn detect(bytes: &[u8]) -> Self {
+        match bytes {
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Self::Zstd,
+            // A gzip stream; in a release, that's a tarball
+            [0x1f, 0x8b, ..] => Self::TarGz,
+            [b'P', b'K', 0x03, 0x04, ..] => Self::Zip,
+            _ => Self::Raw,
+        }
+    }
+
+    /// Whether the binary has to be found among other files.
+    #[must_use]
+    pub fn is_archive(self) -> bool {
+        matches!(self, Self::TarGz | Self::Zip)
+    }
+}
+
+/// Get the binary named `binary_name` out of `bytes` in `format`.
+///
+/// Inside archives, the binary is the first file with that name, whatever
+/// directory it's in (e.g. `ripgrep-14.1.0-x86_64-unknown-linux-musl/rg`).
+///
+/// # Errors
+///
+/// Returns `Error::DecompressionFailed` if the data is corrupt, or if an
+/// archive doesn't contain the binary.
+pub fn extract(format: ArchiveFormat, bytes: &[u8], binary_name: &str) -> Result<Vec<u8>> {
+    let binary = match format {
+        ArchiveFormat::Raw => Some(bytes.to_vec()),
+        ArchiveFormat::Zstd => Some(decompress_zstd(bytes)?),
+        ArchiveFormat::TarGz => find_in_tar_gz(bytes, binary_name)?,
+        ArchiveFormat::Zip => find_in_zip(bytes, binary_name)?,
+    };
+    binary.ok_or_else(|| Error::DecompressionFailed(format!("{binary_name} not found in archive")))
+}
+
+/// [`extract`] with the format detected from the bytes.
+///
+/// # Errors
+///
+/// See [`extract`].
+pub fn unpack(bytes: &[u8], binary_name: &str) -> Result<Vec<u8>> {
+    extract(ArchiveFormat::detect(bytes), bytes, binary_name)
+}
+
+fn decompress_zstd(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut decoder = zstd::Decoder::new(Cursor::new(bytes)).map_err(decompression_failed)?;
+    let mut decompressed = Vec::new();
+    std::io::copy(&mut decoder, &mut decompressed).map_err(decompression_failed)?;
+    Ok(decompressed)
+}
+
+fn find_in_tar_gz(bytes: &[u8], binary_name: &str) -> Result<Option<Vec<u8>>> {
+    let mut archive = tar::Archive::new(GzDecoder::new(bytes));
+    for entry in archive.entries().map_err(decompression_failed)? {
+        let mut entry = entry.map_err(decompression_failed)?;
+        let is_binary = entry.header().entry_type().is_file()
+            && entry
+                .path()
+                .map_err(decompression_failed)?
+                .file_name()
+                .is_some_and(|name| name == binary_name);
+        if is_binary {
+            let mut contents = Vec::new();
+            entry
+                .read_to_end(&mut contents)
+                .map_err(decompression_failed)?;
+            return Ok(Some(contents));
+        }
+    }
+    Ok(None)
+}
+
+fn find_in_zip(bytes: &[u8], binary_name: &str) -> Result<Option<Vec<u8>>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(decompression_failed)?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(decompression_failed)?;
+        let is_binary = file.is_file()
+            && Path::new(file.name())
+                .file_name()
+                .is_some_and(|name| name == binary_name);
+        if is_binary {
+            let mut contents = Vec::new();
+            file.read_to_end(&mut contents)
+                .map_err(decompression_failed)?;
+            return Ok(Some(contents));
+        }
+    }
+    Ok(None)
+}
+
+fn decompression_failed(e: impl std::fmt::Display) -> Error {
+    Error::DecompressionFailed(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn tar_gz(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (path, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append_data(&mut header, path, *contents).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (path, contents) in files {
+            writer
+                .start_file(*path, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_format_from_name() {
+        assert_eq!(
+            ArchiveFormat::from_name("buck2-x86_64-unknown-linux-gnu.zst"),
+            ArchiveFormat::Zstd
+        );
+        assert_eq!(
+            ArchiveFormat::from_name("ripgrep-14.1.0-x86_64-unknown-linux-musl.tar.gz"),
+            ArchiveFormat::TarGz
+        );
+        assert_eq!(
+            ArchiveFormat::from_name("bazelisk.tgz"),
+            ArchiveFormat::TarGz
+        );
+        assert_eq!(
+            ArchiveFormat::from_name("ripgrep-14.1.0-x86_64-pc-windows-msvc.zip"),
+            ArchiveFormat::Zip
+        );
+        assert_eq!(
+            ArchiveFormat::from_name("bazel-7.4.1-linux-x86_64"),
+            ArchiveFormat::Raw
+        );
+        assert!(ArchiveFormat::Zip.is_archive());
+        assert!(!ArchiveFormat::Zstd.is_archive());
+    }
+
+    #[test]
+    fn test_detect_and_unpack() {
+        let targz = tar_gz(&[("rg-14.1.0/doc/rg.1", b"man"), ("rg-14.1.0/rg", b"rg")]);
+        assert_eq!(ArchiveFormat::detect(&targz), ArchiveFormat::TarGz);
+        assert_eq!(unpack(&targz, "rg").unwrap(), b"rg");
+
+        let zip = zip(&[
+            ("rg-14.1.0/README.md", b"readme"),
+            ("rg-14.1.0/rg.exe", b"rg"),
+        ]);
+        assert_eq!(ArchiveFormat::detect(&zip), ArchiveFormat::Zip);
+        assert_eq!(unpack(&zip, "rg.exe").unwrap(), b"rg");
+
+        let zst = zstd::encode_all(&b"buck2"[..], 0).unwrap();
+        assert_eq!(ArchiveFormat::detect(&zst), ArchiveFormat::Zstd);
+        assert_eq!(unpack(&zst, "buck2").unwrap(), b"buck2");
+
+        let elf = b"\x7fELF binary";
+        assert_eq!(ArchiveFormat::detect(elf), ArchiveFormat::Raw);
+        assert_eq!(unpack(elf, "bazel").unwrap(), elf);
+    }
+
+    #[test]
+    fn test_binary_missing_from_archive() {
+        let targz = tar_gz(&[("rg-14.1.0/rg", b"rg")]);
+        assert!(matches!(
+            extract(ArchiveFormat::TarGz, &targz, "fd"),
+            Err(Error::DecompressionFailed(msg)) if msg.contains("fd")
+        ));
+        let zip = zip(&[("rg", b"rg")]);
+        assert!(extract(ArchiveFormat::Zip, &zip, "fd").is_err());
+    }
+
+    #[test]
+    fn test_extract_invalid_data() {
+        let invalid = [0, 1, 2, 3, 4, 5];
+        for format in [
+            ArchiveFormat::Zstd,
+            ArchiveFormat::TarGz,
+            ArchiveFormat::Zip,
+        ] {
+            assert!(matches!(
+                extract(format, &invalid, "buck2"),
+                Err(Error::DecompressionFailed(_))
+            ));
+        }
+        assert!(extract(ArchiveFormat::Zstd, &[], "buck2").is_err());
+    }
+}
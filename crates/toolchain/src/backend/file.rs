@@ -0,0 +1,383 @@
+//! Local directory and mirror backend.
+//!
+//! This module provides the [`FileBackend`] for installing tools on machines
+//! without GitHub access. It reads releases from a local directory (e.g. a
+//! mounted share) or from an internal HTTP mirror with the same layout:
+//!
+//! ```text
+//! <root>/
+//!   bazel/
+//!     releases.json
+//!     7.4.1/
+//!       bazel-7.4.1-linux-x86_64
+//!       bazel-7.4.1-linux-x86_64.sha256
+//!   buck2/
+//!     releases.json
+//!     2024-01-15/
+//!       buck2-x86_64-unknown-linux-gnu.zst
+//! ```
+//!
+//! Each tool directory is named after [`Tool::name`]. Its `releases.json`
+//! lists the mirrored releases, newest first, with the assets found in the
+//! release's directory:
+//!
+//! ```json
+//! [
+//!   {
+//!     "tag": "7.4.1",
+//!     "published_at": "2024-11-05T00:00:00Z",
+//!     "assets": ["bazel-7.4.1-linux-x86_64", "bazel-7.4.1-linux-x86_64.sha256"]
+//!   }
+//! ]
+//! ```
+//!
+//! Only `tag` is required; `assets`, `name`, `published_at`, `notes`
+//! and `prerelease` are optional. The `latest` tag resolves to the first
+//! release that isn't a prerelease.
+
+use crate::backend::{Backend, find_platform_asset};
+use crate::error::{Error, Result};
+use crate::progress::{self, ProgressCallback};
+use crate::proxy::ProxyConfig;
+use crate::types::{Platform, Release, ReleaseAsset, Tool};
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+/// Maximum download size (100 MB should cover most build tools).
+const MAX_BODY_SIZE: u64 = 100 * 1024 * 1024;
+
+/// Name of the per-tool release manifest.
+const MANIFEST: &str = "releases.json";
+
+/// Backend reading releases from a local directory or HTTP mirror.
+///
+/// # Example
+///
+/// ```no_run
+/// use toolchain::backend::file::FileBackend;
+/// use toolchain::{Client, Tool, InstallOptions};
+///
+/// let mirror = FileBackend::new("https://mirror.example.com/toolchain");
+/// let client = Client::with_backend(Box::new(mirror));
+/// client.install(Tool::Bazel, InstallOptions::new()).unwrap();
+/// ```
+pub struct FileBackend {
+    source: Source,
+}
+
+/// Where the mirror lives.
+enum Source {
+    /// Local directory.
+    Dir(PathBuf),
+    /// HTTP(S) mirror.
+    Http {
+        agent: ureq::Agent,
+        base_url: String,
+    },
+}
+
+impl FileBackend {
+    /// Create a backend for `location`: an `http://` or `https://` URL, or
+    /// a local directory.
+    #[must_use]
+    pub fn new(location: impl Into<String>) -> Self {
+        let location = location.into();
+        if location.starts_with("http://") || location.starts_with("https://") {
+            Self {
+                source: Source::Http {
+                    agent: ureq::Agent::new_with_defaults(),
+                    base_url: location.trim_end_matches('/').to_string(),
+                },
+            }
+        } else {
+            Self::with_dir(location)
+        }
+    }
+
+    /// Create a backend for a local directory.
+    #[must_use]
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            source: Source::Dir(dir.into()),
+        }
+    }
+
+    /// Send requests to an HTTP mirror through `proxy` instead of the proxy
+    /// from the environment. Local directories are unaffected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proxy URL is invalid.
+    pub fn with_proxy(mut self, proxy: &ProxyConfig) -> Result<Self> {
+        if let Source::Http { agent, .. } = &mut self.source {
+            *agent = proxy.agent()?;
+        }
+        Ok(self)
+    }
+
+    /// Location of a file in the mirror, as a path or URL.
+    fn location(&self, parts: &[&str]) -> String {
+        match &self.source {
+            Source::Dir(dir) => parts
+                .iter()
+                .fold(dir.clone(), |path, part| path.join(part))
+                .to_string_lossy()
+                .into_owned(),
+            Source::Http { base_url, .. } => {
+                let mut url = base_url.clone();
+                for part in parts {
+                    url.push('/');
+                    url.push_str(part);
+                }
+                url
+            }
+        }
+    }
+
+    /// Read a file from the mirror; `None` if it doesn't exist.
+    fn read(&self, location: &str) -> Result<Option<Vec<u8>>> {
+        self.read_with_progress(location, None)
+    }
+
+    /// [`Self::read`], reporting the progress of HTTP downloads.
+    fn read_with_progress(
+        &self,
+        location: &str,
+        progress: Option<(Tool, &ProgressCallback)>,
+    ) -> Result<Option<Vec<u8>>> {
+        match &self.source {
+            Source::Dir(_) => match fs::read(location) {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(Error::io(location, e)),
+            },
+            Source::Http { agent, .. } => {
+                let mut response = match agent
+                    .get(location)
+                    .header("User-Agent", "toolchain-rs")
+                    .call()
+                {
+                    Ok(response) => response,
+                    Err(ureq::Error::StatusCode(404)) => return Ok(None),
+                    Err(e) => return Err(e.into()),
+                };
+                let body = response.body_mut();
+                if let Some((tool, progress)) = progress {
+                    let total = body.content_length();
+                    let reader = body.with_config().limit(MAX_BODY_SIZE).reader();
+                    return progress::read_to_vec(tool, reader, total, progress).map(Some);
+                }
+                let bytes = body.with_config().limit(MAX_BODY_SIZE).read_to_vec()?;
+                Ok(Some(bytes))
+            }
+        }
+    }
+}
+
+impl Backend for FileBackend {
+    fn fetch_releases(&self, tool: Tool) -> Result<Vec<Release>> {
+        // A tool that isn't mirrored has no releases
+        let Some(manifest) = self.read(&self.location(&[tool.name(), MANIFEST]))? else {
+            return Ok(Vec::new());
+        };
+        let releases: Vec<ManifestRelease> = serde_json::from_slice(&manifest)?;
+
+        Ok(releases
+            .into_iter()
+            .map(|release| {
+                let assets = release
+                    .assets
+                    .iter()
+                    .map(|name| ReleaseAsset {
+                        name: name.clone(),
+                        download_url: self.location(&[tool.name(), &release.tag, name]),
+                        size: 0,
+                    })
+                    .collect();
+                Release {
+                    name: release.name.unwrap_or_else(|| release.tag.clone()),
+                    tag: release.tag,
+                    prerelease: release.prerelease,
+                    published_at: release.published_at,
+                    notes: release.notes,
+                    assets,
+                }
+            })
+            .collect())
+    }
+
+    fn fetch_release(&self, tool: Tool, tag: &str) -> Result<Release> {
+        self.fetch_releases(tool)?
+            .into_iter()
+            .find(|release| {
+                if tag == "latest" {
+                    !release.prerelease
+                } else {
+                    release.tag == tag
+                }
+            })
+            .ok_or_else(|| Error::VersionNotFound {
+                tool: tool.to_string(),
+                version: tag.to_string(),
+            })
+    }
+
+    fn download_asset(
+        &self,
+        tool: Tool,
+        release: &Release,
+        platform: &Platform,
+    ) -> Result<Vec<u8>> {
+        let asset = find_platform_asset(tool, release, platform)?;
+        self.download_release_asset(tool, asset)
+    }
+
+    fn download_release_asset(&self, tool: Tool, asset: &ReleaseAsset) -> Result<Vec<u8>> {
+        self.read(&asset.download_url)?
+            .ok_or_else(|| missing(tool, asset))
+    }
+
+    fn download_release_asset_with_progress(
+        &self,
+        tool: Tool,
+        asset: &ReleaseAsset,
+        progress: &ProgressCallback,
+    ) -> Result<Vec<u8>> {
+        let bytes = self
+            .read_with_progress(&asset.download_url, Some((tool, progress)))?
+            .ok_or_else(|| missing(tool, asset))?;
+        // Local files arrive all at once
+        if matches!(self.source, Source::Dir(_)) {
+            progress.report_done(tool, bytes.len());
+        }
+        Ok(bytes)
+    }
+}
+
+/// Error for an asset listed in the manifest but missing from the mirror.
+fn missing(tool: Tool, asset: &ReleaseAsset) -> Error {
+    Error::DownloadFailed {
+        tool: tool.to_string(),
+        message: format!("{} is missing from the mirror", asset.download_url),
+    }
+}
+
+// =============================================================================
+// Manifest types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ManifestRelease {
+    tag: String,
+    name: Option<String>,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    published_at: String,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    assets: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mirror() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let bazel = dir.path().join("bazel");
+        fs::create_dir_all(bazel.join("7.4.1")).unwrap();
+        fs::write(
+            bazel.join(MANIFEST),
+            r#"[
+                { "tag": "8.0.0rc1", "prerelease": true, "assets": [] },
+                {
+                    "tag": "7.4.1",
+                    "published_at": "2024-11-05T00:00:00Z",
+                    "assets": ["bazel-7.4.1-linux-x86_64", "bazel-7.4.1-darwin-arm64"]
+                }
+            ]"#,
+        )
+        .unwrap();
+        fs::write(
+            bazel.join("7.4.1").join("bazel-7.4.1-linux-x86_64"),
+            b"bazel",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_fetch_releases_from_dir() {
+        let dir = mirror();
+        let backend = FileBackend::new(dir.path().to_string_lossy());
+
+        let releases = backend.fetch_releases(Tool::Bazel).unwrap();
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[1].name, "7.4.1");
+        assert_eq!(releases[1].assets.len(), 2);
+
+        // Latest skips the prerelease
+        let latest = backend.fetch_release(Tool::Bazel, "latest").unwrap();
+        assert_eq!(latest.tag, "7.4.1");
+
+        assert!(backend.fetch_releases(Tool::Buck2).unwrap().is_empty());
+        assert!(matches!(
+            backend.fetch_release(Tool::Bazel, "6.0.0"),
+            Err(Error::VersionNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_download_from_dir() {
+        let dir = mirror();
+        let backend = FileBackend::with_dir(dir.path());
+        let release = backend.fetch_release(Tool::Bazel, "7.4.1").unwrap();
+
+        let linux = Platform::new("linux", "x86_64", "x86_64-unknown-linux-gnu");
+        assert_eq!(
+            backend
+                .download_asset(Tool::Bazel, &release, &linux)
+                .unwrap(),
+            b"bazel"
+        );
+
+        // Listed in the manifest, but not copied into the mirror
+        let mac = Platform::new("macos", "aarch64", "aarch64-apple-darwin");
+        assert!(matches!(
+            backend.download_asset(Tool::Bazel, &release, &mac),
+            Err(Error::DownloadFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_download_reports_progress() {
+        let dir = mirror();
+        let backend = FileBackend::with_dir(dir.path());
+        let release = backend.fetch_release(Tool::Bazel, "7.4.1").unwrap();
+
+        let reported = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let progress = {
+            let reported = std::sync::Arc::clone(&reported);
+            ProgressCallback::new(move |p| *reported.lock().unwrap() = Some(p.clone()))
+        };
+        backend
+            .download_release_asset_with_progress(Tool::Bazel, &release.assets[0], &progress)
+            .unwrap();
+
+        let reported = reported.lock().unwrap().clone().unwrap();
+        assert_eq!(reported.downloaded, 5);
+        assert!(reported.is_done());
+    }
+
+    #[test]
+    fn test_http_locations() {
+        let backend = FileBackend::new("https://mirror.example.com/toolchain/");
+        assert_eq!(
+            backend.location(&["bazel", MANIFEST]),
+            "https://mirror.example.com/toolchain/bazel/releases.json"
+        );
+    }
+}
@@ -6,12 +6,26 @@
 //! # Rate Limiting
 //!
 //! The GitHub API has rate limits. For unauthenticated requests, the limit
-//! is 60 requests per hour. If you need more, consider using a GitHub token.
+//! is 60 requests per hour, which CI runs through quickly. Authenticated
+//! requests get 5,000: [`GitHubBackend::new`] picks up a token from
+//! `GITHUB_TOKEN` (or `GH_TOKEN`), and [`GitHubBackend::token`] sets one
+//! explicitly.
+//!
+//! An exhausted limit fails with `Error::RateLimited`, carrying when it
+//! resets. With [`GitHubBackend::wait_on_rate_limit`], the backend instead
+//! waits for the reset and retries, if that's soon enough.
 
-use crate::backend::Backend;
+use crate::backend::{Backend, find_platform_asset};
 use crate::error::{Error, Result};
+use crate::progress::{self, ProgressCallback};
+use crate::proxy::ProxyConfig;
 use crate::types::{Platform, Release, ReleaseAsset, Tool};
 use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Environment variables holding a GitHub token, in order of precedence.
+const TOKEN_VARS: [&str; 2] = ["GITHUB_TOKEN", "GH_TOKEN"];
 
 /// Maximum download size (100 MB should cover most build tools).
 const MAX_BODY_SIZE: u64 = 100 * 1024 * 1024;
@@ -36,16 +50,25 @@ pub struct GitHubBackend {
     agent: ureq::Agent,
     /// GitHub API base URL.
     api_base: String,
+    /// Token for authenticated API requests.
+    token: Option<String>,
+    /// Longest wait for a rate limit reset before retrying, if enabled.
+    max_rate_limit_wait: Option<Duration>,
 }
 
 impl GitHubBackend {
     /// Create a new GitHub backend.
+    ///
+    /// Authenticates with the token in `GITHUB_TOKEN` or `GH_TOKEN`, if set.
     #[must_use]
     pub fn new() -> Self {
-        let agent = ureq::Agent::new_with_defaults();
+        let token = TOKEN_VARS
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|token| !token.trim().is_empty());
         Self {
-            agent,
-            api_base: "https://api.github.com".to_string(),
+            token,
+            ..Self::with_api_base("https://api.github.com")
         }
     }
 
@@ -56,22 +79,118 @@ impl GitHubBackend {
         Self {
             agent,
             api_base: api_base.into(),
+            token: None,
+            max_rate_limit_wait: None,
         }
     }
 
+    /// Authenticate API requests with `token`.
+    #[must_use]
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// When the rate limit is exhausted, wait for it to reset and retry
+    /// once, as long as that takes at most `max_wait`.
+    #[must_use]
+    pub fn wait_on_rate_limit(mut self, max_wait: Duration) -> Self {
+        self.max_rate_limit_wait = Some(max_wait);
+        self
+    }
+
+    /// Send requests through `proxy` instead of the proxy from the
+    /// environment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proxy URL is invalid.
+    pub fn with_proxy(mut self, proxy: &ProxyConfig) -> Result<Self> {
+        self.agent = proxy.agent()?;
+        Ok(self)
+    }
+
     /// Get the current API base URL.
     #[must_use]
     pub fn api_base(&self) -> &str {
         &self.api_base
     }
 
+    /// Whether API requests are authenticated.
+    #[must_use]
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// GET a JSON document from the API.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RateLimited` if the rate limit is exhausted (and
+    /// waiting is disabled or would take too long), and `Error::HttpError`
+    /// for other failed requests.
+    fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let mut waited = false;
+        loop {
+            let mut request = self
+                .agent
+                .get(url)
+                .header("Accept", "application/vnd.github+json")
+                .header("User-Agent", "toolchain-rs");
+            if let Some(token) = &self.token {
+                request = request.header("Authorization", format!("Bearer {token}"));
+            }
+            let mut response = request
+                .config()
+                .http_status_as_error(false)
+                .build()
+                .call()?;
+
+            let status = response.status().as_u16();
+            if status < 400 {
+                return Ok(response.body_mut().read_json()?);
+            }
+
+            let header = |name: &str| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string)
+            };
+            let Some(RateLimit { reset_at }) = rate_limit(status, header, now()) else {
+                return Err(Error::http(format!("HTTP {status}"), Some(status)));
+            };
+
+            let wait =
+                reset_at.map(|reset_at| Duration::from_secs(reset_at.saturating_sub(now()) + 1));
+            match (wait, self.max_rate_limit_wait) {
+                (Some(wait), Some(max_wait)) if !waited && wait <= max_wait => {
+                    std::thread::sleep(wait);
+                    waited = true;
+                }
+                _ => return Err(Error::RateLimited { reset_at }),
+            }
+        }
+    }
+
     /// Build the API URL for releases.
     fn releases_url(&self, tool: Tool) -> String {
         format!("{}/repos/{}/releases", self.api_base, tool.github_repo())
     }
 
     /// Build the API URL for a specific release.
+    ///
+    /// `latest` resolves to the newest stable release for tools without a
+    /// rolling `latest` tag.
     fn release_url(&self, tool: Tool, tag: &str) -> String {
+        if tag == "latest" && !tool.has_latest_tag() {
+            return format!(
+                "{}/repos/{}/releases/latest",
+                self.api_base,
+                tool.github_repo()
+            );
+        }
         format!(
             "{}/repos/{}/releases/tags/{}",
             self.api_base,
@@ -80,6 +199,15 @@ impl GitHubBackend {
         )
     }
 
+    /// Fetch the newest stable release of any repository, e.g. for tools
+    /// that update themselves rather than being installed as a [`Tool`].
+    pub(crate) fn fetch_latest_release_of(&self, repo: &str) -> Result<Release> {
+        let url = format!("{}/repos/{repo}/releases/latest", self.api_base);
+        let response: GitHubRelease = self.get_json(&url)?;
+
+        Ok(response.into())
+    }
+
     /// Find the asset matching the platform.
     fn find_asset<'a>(
         &self,
@@ -87,20 +215,53 @@ impl GitHubBackend {
         release: &'a Release,
         platform: &Platform,
     ) -> Result<&'a ReleaseAsset> {
-        let binary_name = tool.binary_name();
-        let expected_name = format!("{}-{}.zst", binary_name, platform.triple);
+        find_platform_asset(tool, release, platform)
+    }
 
-        release
-            .assets
-            .iter()
-            .find(|a| a.name == expected_name)
-            .ok_or_else(|| Error::DownloadFailed {
-                tool: tool.to_string(),
-                message: format!(
-                    "no asset found for platform {} (expected {})",
-                    platform.triple, expected_name
-                ),
-            })
+    /// Download an asset, reporting progress if asked to.
+    fn download(
+        &self,
+        tool: Tool,
+        asset: &ReleaseAsset,
+        progress: Option<&ProgressCallback>,
+    ) -> Result<Vec<u8>> {
+        let Some(progress) = progress else {
+            return self.download_url(tool.name(), &asset.download_url);
+        };
+
+        let mut response = self.get_asset(&asset.download_url)?;
+        let body = response.body_mut();
+        let total = body.content_length();
+        let reader = body.with_config().limit(MAX_BODY_SIZE).reader();
+        progress::read_to_vec(tool, reader, total, progress)
+    }
+
+    /// Download a release asset by URL; `name` labels errors.
+    pub(crate) fn download_url(&self, name: &str, url: &str) -> Result<Vec<u8>> {
+        let mut response = self.get_asset(url)?;
+
+        // Download the asset with increased size limit
+        let bytes = response
+            .body_mut()
+            .with_config()
+            .limit(MAX_BODY_SIZE)
+            .read_to_vec()
+            .map_err(|e| Error::DownloadFailed {
+                tool: name.to_string(),
+                message: e.to_string(),
+            })?;
+
+        Ok(bytes)
+    }
+
+    /// Start downloading a release asset.
+    fn get_asset(&self, url: &str) -> Result<ureq::http::Response<ureq::Body>> {
+        Ok(self
+            .agent
+            .get(url)
+            .header("Accept", "application/octet-stream")
+            .header("User-Agent", "toolchain-rs")
+            .call()?)
     }
 }
 
@@ -112,31 +273,13 @@ impl Default for GitHubBackend {
 
 impl Backend for GitHubBackend {
     fn fetch_releases(&self, tool: Tool) -> Result<Vec<Release>> {
-        let url = self.releases_url(tool);
-
-        let response: Vec<GitHubRelease> = self
-            .agent
-            .get(&url)
-            .header("Accept", "application/vnd.github+json")
-            .header("User-Agent", "toolchain-rs")
-            .call()?
-            .body_mut()
-            .read_json()?;
+        let response: Vec<GitHubRelease> = self.get_json(&self.releases_url(tool))?;
 
         Ok(response.into_iter().map(Into::into).collect())
     }
 
     fn fetch_release(&self, tool: Tool, tag: &str) -> Result<Release> {
-        let url = self.release_url(tool, tag);
-
-        let response: GitHubRelease = self
-            .agent
-            .get(&url)
-            .header("Accept", "application/vnd.github+json")
-            .header("User-Agent", "toolchain-rs")
-            .call()?
-            .body_mut()
-            .read_json()?;
+        let response: GitHubRelease = self.get_json(&self.release_url(tool, tag))?;
 
         Ok(response.into())
     }
@@ -148,27 +291,58 @@ impl Backend for GitHubBackend {
         platform: &Platform,
     ) -> Result<Vec<u8>> {
         let asset = self.find_asset(tool, release, platform)?;
+        self.download_release_asset(tool, asset)
+    }
 
-        // Download the asset with increased size limit
-        let mut response = self
-            .agent
-            .get(&asset.download_url)
-            .header("Accept", "application/octet-stream")
-            .header("User-Agent", "toolchain-rs")
-            .call()?;
+    fn download_release_asset(&self, tool: Tool, asset: &ReleaseAsset) -> Result<Vec<u8>> {
+        self.download(tool, asset, None)
+    }
 
-        let bytes = response
-            .body_mut()
-            .with_config()
-            .limit(MAX_BODY_SIZE)
-            .read_to_vec()
-            .map_err(|e| Error::DownloadFailed {
-                tool: tool.to_string(),
-                message: e.to_string(),
-            })?;
+    fn download_release_asset_with_progress(
+        &self,
+        tool: Tool,
+        asset: &ReleaseAsset,
+        progress: &ProgressCallback,
+    ) -> Result<Vec<u8>> {
+        self.download(tool, asset, Some(progress))
+    }
+}
 
-        Ok(bytes)
+/// An exhausted rate limit.
+#[derive(Debug, PartialEq, Eq)]
+struct RateLimit {
+    /// When the limit resets (Unix time), if known.
+    reset_at: Option<u64>,
+}
+
+/// Whether a failed response means the rate limit is exhausted.
+///
+/// GitHub answers 403 or 429 with `x-ratelimit-remaining: 0` and the reset
+/// time in `x-ratelimit-reset`, or, for secondary rate limits, with a
+/// `retry-after` delay in seconds.
+fn rate_limit(status: u16, header: impl Fn(&str) -> Option<String>, now: u64) -> Option<RateLimit> {
+    if status != 403 && status != 429 {
+        return None;
     }
+    if let Some(retry_after) = header("retry-after").and_then(|v| v.trim().parse::<u64>().ok()) {
+        return Some(RateLimit {
+            reset_at: Some(now + retry_after),
+        });
+    }
+    if header("x-ratelimit-remaining").as_deref() == Some("0") {
+        return Some(RateLimit {
+            reset_at: header("x-ratelimit-reset").and_then(|v| v.trim().parse().ok()),
+        });
+    }
+    // A 429 is a rate limit even without the headers
+    (status == 429).then_some(RateLimit { reset_at: None })
+}
+
+/// Current Unix time in seconds.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
 }
 
 // =============================================================================
@@ -181,6 +355,7 @@ struct GitHubRelease {
     name: Option<String>,
     prerelease: bool,
     published_at: Option<String>,
+    body: Option<String>,
     assets: Vec<GitHubAsset>,
 }
 
@@ -198,6 +373,7 @@ impl From<GitHubRelease> for Release {
             name: r.name.unwrap_or(r.tag_name),
             prerelease: r.prerelease,
             published_at: r.published_at.unwrap_or_default(),
+            notes: r.body.unwrap_or_default(),
             assets: r.assets.into_iter().map(Into::into).collect(),
         }
     }
@@ -235,6 +411,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_release_url_without_latest_tag() {
+        let backend = GitHubBackend::new();
+        assert_eq!(
+            backend.release_url(Tool::Bazel, "latest"),
+            "https://api.github.com/repos/bazelbuild/bazel/releases/latest"
+        );
+        assert_eq!(
+            backend.release_url(Tool::Bazel, "7.4.1"),
+            "https://api.github.com/repos/bazelbuild/bazel/releases/tags/7.4.1"
+        );
+    }
+
+    #[test]
+    fn test_token() {
+        assert!(!GitHubBackend::with_api_base("https://api.github.com").has_token());
+        assert!(
+            GitHubBackend::with_api_base("https://api.github.com")
+                .token("ghp_example")
+                .has_token()
+        );
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let headers = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| (*value).to_string())
+            }
+        };
+
+        let exhausted = headers(&[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "1700000600"),
+        ]);
+        assert_eq!(
+            rate_limit(403, exhausted, 1_700_000_000),
+            Some(RateLimit {
+                reset_at: Some(1_700_000_600)
+            })
+        );
+
+        let secondary = headers(&[("retry-after", "30")]);
+        assert_eq!(
+            rate_limit(403, secondary, 1_700_000_000),
+            Some(RateLimit {
+                reset_at: Some(1_700_000_030)
+            })
+        );
+
+        // A 403 with quota left is a permission problem, not a rate limit
+        let forbidden = headers(&[("x-ratelimit-remaining", "42")]);
+        assert_eq!(rate_limit(403, forbidden, 1_700_000_000), None);
+        assert_eq!(
+            rate_limit(429, headers(&[]), 1_700_000_000),
+            Some(RateLimit { reset_at: None })
+        );
+        assert_eq!(rate_limit(404, exhausted, 1_700_000_000), None);
+    }
+
     #[test]
     fn test_custom_api_base() {
         let backend = GitHubBackend::with_api_base("https://custom.api.com");
@@ -258,6 +497,7 @@ mod tests {
             name: "Release".to_string(),
             prerelease: false,
             published_at: String::new(),
+            notes: String::new(),
             assets: vec![
                 ReleaseAsset {
                     name: "buck2-aarch64-apple-darwin.zst".to_string(),
@@ -290,6 +530,7 @@ mod tests {
             name: "Release".to_string(),
             prerelease: false,
             published_at: String::new(),
+            notes: String::new(),
             assets: vec![ReleaseAsset {
                 name: "buck2-aarch64-apple-darwin.zst".to_string(),
                 download_url: "https://example.com/darwin.zst".to_string(),
@@ -309,6 +550,7 @@ mod tests {
             name: Some("Release 2024-01-15".to_string()),
             prerelease: false,
             published_at: Some("2024-01-15T00:00:00Z".to_string()),
+            body: None,
             assets: vec![GitHubAsset {
                 name: "buck2.zst".to_string(),
                 browser_download_url: "https://example.com/buck2.zst".to_string(),
@@ -330,6 +572,7 @@ mod tests {
             name: None,
             prerelease: true,
             published_at: None,
+            body: None,
             assets: vec![],
         };
 
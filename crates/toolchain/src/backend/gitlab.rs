@@ -0,0 +1,348 @@
+//! GitLab releases backend.
+//!
+//! This module provides the [`GitLabBackend`] implementation for fetching
+//! tool releases from a GitLab instance's Releases API, for tools published
+//! on GitLab (e.g. internal builds of a tool on a self-hosted instance).
+//!
+//! Each tool is looked up in a GitLab project. By default that's the
+//! project with the same path as the tool's GitHub repository, which suits
+//! mirrors; [`GitLabBackend::project`] maps a tool to any other project.
+//!
+//! Release assets are the release's links. Private projects need a token
+//! with `read_api` scope, sent as `PRIVATE-TOKEN` on every request.
+
+use crate::backend::{Backend, find_platform_asset};
+use crate::error::{Error, Result};
+use crate::progress::{self, ProgressCallback};
+use crate::proxy::ProxyConfig;
+use crate::types::{Platform, Release, ReleaseAsset, Tool};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Maximum download size (100 MB should cover most build tools).
+const MAX_BODY_SIZE: u64 = 100 * 1024 * 1024;
+
+/// GitLab releases backend.
+///
+/// # Example
+///
+/// ```no_run
+/// use toolchain::backend::gitlab::GitLabBackend;
+/// use toolchain::{Client, Tool, InstallOptions};
+///
+/// let gitlab = GitLabBackend::new("https://gitlab.example.com")
+///     .project(Tool::Bazel, "build/bazel")
+///     .token(std::env::var("GITLAB_TOKEN").unwrap());
+///
+/// // Bazel comes from GitLab, everything else from GitHub
+/// let client = Client::new().with_tool_backend(Tool::Bazel, Box::new(gitlab));
+/// client.install(Tool::Bazel, InstallOptions::new()).unwrap();
+/// ```
+pub struct GitLabBackend {
+    /// HTTP agent for requests.
+    agent: ureq::Agent,
+    /// Base URL of the GitLab instance.
+    base_url: String,
+    /// Project path per tool, overriding the default.
+    projects: HashMap<Tool, String>,
+    /// Access token, if any.
+    token: Option<String>,
+}
+
+impl GitLabBackend {
+    /// Create a backend for the GitLab instance at `base_url`
+    /// (e.g. `https://gitlab.com`).
+    #[must_use]
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            agent: ureq::Agent::new_with_defaults(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            projects: HashMap::new(),
+            token: None,
+        }
+    }
+
+    /// Look up `tool`'s releases in the project at `path`
+    /// (e.g. `group/subgroup/project`).
+    #[must_use]
+    pub fn project(mut self, tool: Tool, path: impl Into<String>) -> Self {
+        self.projects.insert(tool, path.into());
+        self
+    }
+
+    /// Authenticate with an access token.
+    #[must_use]
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Send requests through `proxy` instead of the proxy from the
+    /// environment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proxy URL is invalid.
+    pub fn with_proxy(mut self, proxy: &ProxyConfig) -> Result<Self> {
+        self.agent = proxy.agent()?;
+        Ok(self)
+    }
+
+    /// Get the base URL of the GitLab instance.
+    #[must_use]
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Project holding a tool's releases.
+    fn project_path(&self, tool: Tool) -> &str {
+        self.projects
+            .get(&tool)
+            .map_or(tool.github_repo(), String::as_str)
+    }
+
+    /// Build the API URL for releases.
+    fn releases_url(&self, tool: Tool) -> String {
+        format!(
+            "{}/api/v4/projects/{}/releases",
+            self.base_url,
+            self.project_path(tool).replace('/', "%2F")
+        )
+    }
+
+    /// Build the API URL for a specific release. `latest` resolves to the
+    /// project's latest release.
+    fn release_url(&self, tool: Tool, tag: &str) -> String {
+        if tag == "latest" {
+            return format!("{}/permalink/latest", self.releases_url(tool));
+        }
+        format!("{}/{}", self.releases_url(tool), tag.replace('/', "%2F"))
+    }
+
+    /// GET `url`, authenticated if a token is set.
+    fn get(&self, url: &str) -> ureq::RequestBuilder<ureq::typestate::WithoutBody> {
+        let request = self.agent.get(url).header("User-Agent", "toolchain-rs");
+        match &self.token {
+            Some(token) => request.header("PRIVATE-TOKEN", token),
+            None => request,
+        }
+    }
+
+    /// Download an asset, reporting progress if asked to.
+    fn download(
+        &self,
+        tool: Tool,
+        asset: &ReleaseAsset,
+        progress: Option<&ProgressCallback>,
+    ) -> Result<Vec<u8>> {
+        // Links to other hosts must not see the token
+        let request = if asset
+            .download_url
+            .starts_with(&format!("{}/", self.base_url))
+        {
+            self.get(&asset.download_url)
+        } else {
+            self.agent
+                .get(&asset.download_url)
+                .header("User-Agent", "toolchain-rs")
+        };
+
+        let mut response = request
+            .header("Accept", "application/octet-stream")
+            .call()?;
+        let body = response.body_mut();
+        if let Some(progress) = progress {
+            let total = body.content_length();
+            let reader = body.with_config().limit(MAX_BODY_SIZE).reader();
+            return progress::read_to_vec(tool, reader, total, progress);
+        }
+
+        body.with_config()
+            .limit(MAX_BODY_SIZE)
+            .read_to_vec()
+            .map_err(|e| Error::DownloadFailed {
+                tool: tool.to_string(),
+                message: e.to_string(),
+            })
+    }
+}
+
+impl Backend for GitLabBackend {
+    fn fetch_releases(&self, tool: Tool) -> Result<Vec<Release>> {
+        let response: Vec<GitLabRelease> = self
+            .get(&self.releases_url(tool))
+            .call()?
+            .body_mut()
+            .read_json()?;
+
+        Ok(response.into_iter().map(Into::into).collect())
+    }
+
+    fn fetch_release(&self, tool: Tool, tag: &str) -> Result<Release> {
+        let response: GitLabRelease = self
+            .get(&self.release_url(tool, tag))
+            .call()?
+            .body_mut()
+            .read_json()?;
+
+        Ok(response.into())
+    }
+
+    fn download_asset(
+        &self,
+        tool: Tool,
+        release: &Release,
+        platform: &Platform,
+    ) -> Result<Vec<u8>> {
+        let asset = find_platform_asset(tool, release, platform)?;
+        self.download_release_asset(tool, asset)
+    }
+
+    fn download_release_asset(&self, tool: Tool, asset: &ReleaseAsset) -> Result<Vec<u8>> {
+        self.download(tool, asset, None)
+    }
+
+    fn download_release_asset_with_progress(
+        &self,
+        tool: Tool,
+        asset: &ReleaseAsset,
+        progress: &ProgressCallback,
+    ) -> Result<Vec<u8>> {
+        self.download(tool, asset, Some(progress))
+    }
+}
+
+// =============================================================================
+// GitLab API response types
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct GitLabRelease {
+    tag_name: String,
+    name: Option<String>,
+    description: Option<String>,
+    released_at: Option<String>,
+    #[serde(default)]
+    upcoming_release: bool,
+    #[serde(default)]
+    assets: GitLabAssets,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GitLabAssets {
+    #[serde(default)]
+    links: Vec<GitLabLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabLink {
+    name: String,
+    url: String,
+    direct_asset_url: Option<String>,
+}
+
+impl From<GitLabRelease> for Release {
+    fn from(r: GitLabRelease) -> Self {
+        Self {
+            tag: r.tag_name.clone(),
+            name: r.name.unwrap_or(r.tag_name),
+            prerelease: r.upcoming_release,
+            published_at: r.released_at.unwrap_or_default(),
+            notes: r.description.unwrap_or_default(),
+            assets: r.assets.links.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<GitLabLink> for ReleaseAsset {
+    fn from(link: GitLabLink) -> Self {
+        Self {
+            name: link.name,
+            download_url: link.direct_asset_url.unwrap_or(link.url),
+            // GitLab doesn't report the size of linked assets
+            size: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_urls() {
+        let backend = GitLabBackend::new("https://gitlab.example.com/")
+            .project(Tool::Bazel, "build/tools/bazel");
+
+        assert_eq!(backend.base_url(), "https://gitlab.example.com");
+        assert_eq!(
+            backend.releases_url(Tool::Bazel),
+            "https://gitlab.example.com/api/v4/projects/build%2Ftools%2Fbazel/releases"
+        );
+        assert_eq!(
+            backend.release_url(Tool::Bazel, "7.4.1"),
+            "https://gitlab.example.com/api/v4/projects/build%2Ftools%2Fbazel/releases/7.4.1"
+        );
+        assert_eq!(
+            backend.release_url(Tool::Bazel, "latest"),
+            "https://gitlab.example.com/api/v4/projects/build%2Ftools%2Fbazel/releases/permalink/latest"
+        );
+
+        // Unmapped tools use their GitHub repository path
+        assert_eq!(
+            backend.releases_url(Tool::Buck2),
+            "https://gitlab.example.com/api/v4/projects/facebook%2Fbuck2/releases"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_release_conversion() {
+        let json = r#"{
+            "tag_name": "7.4.1",
+            "name": "Bazel 7.4.1",
+            "description": "Bug fixes",
+            "released_at": "2024-11-05T00:00:00Z",
+            "upcoming_release": false,
+            "assets": {
+                "count": 2,
+                "sources": [],
+                "links": [
+                    {
+                        "name": "bazel-7.4.1-linux-x86_64",
+                        "url": "https://gitlab.example.com/build/bazel/-/releases/7.4.1/downloads/bazel",
+                        "direct_asset_url": "https://gitlab.example.com/build/bazel/-/releases/7.4.1/downloads/bazel-7.4.1-linux-x86_64"
+                    },
+                    {
+                        "name": "bazel-7.4.1-linux-x86_64.sha256",
+                        "url": "https://mirror.example.com/bazel-7.4.1-linux-x86_64.sha256"
+                    }
+                ]
+            }
+        }"#;
+
+        let release: Release = serde_json::from_str::<GitLabRelease>(json).unwrap().into();
+        assert_eq!(release.tag, "7.4.1");
+        assert_eq!(release.name, "Bazel 7.4.1");
+        assert_eq!(release.notes, "Bug fixes");
+        assert!(!release.prerelease);
+        assert_eq!(release.assets.len(), 2);
+        assert!(
+            release.assets[0]
+                .download_url
+                .ends_with("bazel-7.4.1-linux-x86_64")
+        );
+        assert_eq!(
+            release.assets[1].download_url,
+            "https://mirror.example.com/bazel-7.4.1-linux-x86_64.sha256"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_release_conversion_with_defaults() {
+        let json = r#"{ "tag_name": "v1.0.0", "name": null, "released_at": null }"#;
+        let release: Release = serde_json::from_str::<GitLabRelease>(json).unwrap().into();
+        assert_eq!(release.name, "v1.0.0");
+        assert!(release.notes.is_empty());
+        assert!(release.assets.is_empty());
+    }
+}
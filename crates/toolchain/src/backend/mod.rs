@@ -2,7 +2,9 @@
 //!
 //! This module provides the [`Backend`] trait and implementations for
 //! different release sources. The primary implementation is [`github::GitHubBackend`]
-//! for fetching releases from GitHub.
+//! for fetching releases from GitHub; [`gitlab::GitLabBackend`] fetches them
+//! from a GitLab instance, and [`file::FileBackend`] from a local directory
+//! or internal mirror.
 //!
 //! # Testing
 //!
@@ -18,6 +20,7 @@
 //!     name: "Release 2024-01-15".to_string(),
 //!     prerelease: false,
 //!     published_at: "2024-01-15T00:00:00Z".to_string(),
+//!     notes: String::new(),
 //!     assets: vec![],
 //! });
 //!
@@ -25,9 +28,13 @@
 //! assert_eq!(releases.len(), 1);
 //! ```
 
+pub mod file;
 pub mod github;
+pub mod gitlab;
 
 use crate::error::{Error, Result};
+use crate::progress::ProgressCallback;
+use crate::tools;
 use crate::types::{Platform, Release, ReleaseAsset, Tool};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -58,6 +65,59 @@ pub trait Backend: Send + Sync {
     /// Returns `Error::DownloadFailed` if the asset cannot be downloaded.
     fn download_asset(&self, tool: Tool, release: &Release, platform: &Platform)
     -> Result<Vec<u8>>;
+
+    /// Download a specific, named release asset.
+    ///
+    /// Used for platform-independent assets such as checksums or metadata
+    /// files (e.g. Buck2's `prelude_hash`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::DownloadFailed` if the asset cannot be downloaded.
+    fn download_release_asset(&self, tool: Tool, asset: &ReleaseAsset) -> Result<Vec<u8>>;
+
+    /// Download a specific, named release asset, reporting progress to
+    /// `progress` as it goes.
+    ///
+    /// Backends that can't report intermediate progress only report the
+    /// completed download, which is what the default does.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::DownloadFailed` if the asset cannot be downloaded.
+    fn download_release_asset_with_progress(
+        &self,
+        tool: Tool,
+        asset: &ReleaseAsset,
+        progress: &ProgressCallback,
+    ) -> Result<Vec<u8>> {
+        let bytes = self.download_release_asset(tool, asset)?;
+        progress.report_done(tool, bytes.len());
+        Ok(bytes)
+    }
+}
+
+/// Find the release asset holding `tool`'s binary for `platform`.
+///
+/// # Errors
+///
+/// Returns `Error::DownloadFailed` if the release has no such asset.
+pub(crate) fn find_platform_asset<'a>(
+    tool: Tool,
+    release: &'a Release,
+    platform: &Platform,
+) -> Result<&'a ReleaseAsset> {
+    let expected_name = tools::installer(tool).asset_name(release, platform)?;
+
+    release
+        .find_asset(&expected_name)
+        .ok_or_else(|| Error::DownloadFailed {
+            tool: tool.to_string(),
+            message: format!(
+                "no asset found for platform {} (expected {})",
+                platform.triple, expected_name
+            ),
+        })
 }
 
 /// Mock backend for testing without network access.
@@ -108,6 +168,7 @@ impl MockBackend {
                 name: "Release 2024-01-15".to_string(),
                 prerelease: false,
                 published_at: "2024-01-15T00:00:00Z".to_string(),
+                notes: String::new(),
                 assets: vec![
                     ReleaseAsset {
                         name: "buck2-aarch64-apple-darwin.zst".to_string(),
@@ -161,18 +222,12 @@ impl Backend for MockBackend {
         release: &Release,
         platform: &Platform,
     ) -> Result<Vec<u8>> {
-        let binary_name = tool.binary_name();
-        let expected_name = format!("{}-{}.zst", binary_name, platform.triple);
+        let asset = find_platform_asset(tool, release, platform)?;
 
-        let asset = release
-            .assets
-            .iter()
-            .find(|a| a.name == expected_name)
-            .ok_or_else(|| Error::DownloadFailed {
-                tool: tool.to_string(),
-                message: format!("no asset found for platform {}", platform.triple),
-            })?;
+        self.download_release_asset(tool, asset)
+    }
 
+    fn download_release_asset(&self, tool: Tool, asset: &ReleaseAsset) -> Result<Vec<u8>> {
         let assets = self.assets.lock().unwrap();
         assets
             .get(&asset.name)
@@ -205,6 +260,7 @@ mod tests {
                 name: "Release".to_string(),
                 prerelease: false,
                 published_at: String::new(),
+                notes: String::new(),
                 assets: vec![],
             },
         );
@@ -224,6 +280,7 @@ mod tests {
                 name: "Release".to_string(),
                 prerelease: false,
                 published_at: String::new(),
+                notes: String::new(),
                 assets: vec![],
             },
         );
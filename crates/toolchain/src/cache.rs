@@ -0,0 +1,227 @@
+//! Download cache for release assets.
+//!
+//! Build tool binaries are large (Bazel and Buck2 are 50 MB or more), so
+//! [`DownloadCache`] keeps downloaded assets on disk and reinstalls reuse
+//! them. Entries are keyed by the asset URL and the release's publish date,
+//! so a rolling tag (like Buck2's `latest`) that gets republished misses the
+//! cache. Each entry stores the SHA-256 digest of its content next to it,
+//! and entries that no longer match their digest are discarded.
+//!
+//! The metadata of fetched releases is cached too, for offline installs
+//! (see [`InstallOptions::offline`](crate::InstallOptions::offline)).
+//!
+//! The default location is `$XDG_CACHE_HOME/bossa/toolchain`, falling back
+//! to `~/.cache/bossa/toolchain`.
+
+use crate::checksum;
+use crate::error::{Error, Result};
+use crate::types::{Release, ReleaseAsset, Tool};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// On-disk cache of downloaded release assets.
+#[derive(Debug, Clone)]
+pub struct DownloadCache {
+    dir: PathBuf,
+}
+
+impl DownloadCache {
+    /// Cache stored in `dir`.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Cache in the default location.
+    ///
+    /// Returns `None` if the home directory can't be determined.
+    #[must_use]
+    pub fn default_location() -> Option<Self> {
+        let cache_home = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| dirs::home_dir().map(|home| home.join(".cache")))?;
+        Some(Self::new(cache_home.join("bossa").join("toolchain")))
+    }
+
+    /// Directory the cache is stored in.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Get a cached asset, if present and intact.
+    #[must_use]
+    pub fn get(&self, release: &Release, asset: &ReleaseAsset) -> Option<Vec<u8>> {
+        let path = self.entry_path(release, asset);
+        let bytes = fs::read(&path).ok()?;
+        let digest_path = with_suffix(&path, ".sha256");
+        let digest = fs::read_to_string(&digest_path).ok()?;
+        if checksum::sha256_hex(&bytes) != digest.trim() {
+            // Truncated or tampered with; download it again
+            let _ = fs::remove_file(&digest_path);
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+        Some(bytes)
+    }
+
+    /// Store a downloaded asset.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory or entry can't be written.
+    pub fn put(&self, release: &Release, asset: &ReleaseAsset, bytes: &[u8]) -> Result<()> {
+        fs::create_dir_all(&self.dir).map_err(|e| Error::io(&self.dir, e))?;
+        let path = self.entry_path(release, asset);
+
+        // Write to a temporary file first so readers never see a partial entry
+        let partial = with_suffix(&path, ".partial");
+        fs::write(&partial, bytes).map_err(|e| Error::io(&partial, e))?;
+        fs::rename(&partial, &path).map_err(|e| Error::io(&path, e))?;
+
+        let digest = with_suffix(&path, ".sha256");
+        fs::write(&digest, checksum::sha256_hex(bytes)).map_err(|e| Error::io(&digest, e))
+    }
+
+    /// Get cached release metadata, as fetched for `tag`.
+    #[must_use]
+    pub fn get_release(&self, tool: Tool, tag: &str) -> Option<Release> {
+        let bytes = fs::read(self.release_path(tool, tag)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    /// Store release metadata fetched for `tag`, so it can be resolved
+    /// offline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory or entry can't be written.
+    pub fn put_release(&self, tool: Tool, tag: &str, release: &Release) -> Result<()> {
+        fs::create_dir_all(&self.dir).map_err(|e| Error::io(&self.dir, e))?;
+        let path = self.release_path(tool, tag);
+        let json = serde_json::to_vec_pretty(release)?;
+        let partial = with_suffix(&path, ".partial");
+        fs::write(&partial, json).map_err(|e| Error::io(&partial, e))?;
+        fs::rename(&partial, &path).map_err(|e| Error::io(&path, e))
+    }
+
+    /// Remove every cached asset, returning the number of bytes freed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory exists but can't be removed.
+    pub fn clear(&self) -> Result<u64> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(Error::io(&self.dir, e)),
+        };
+        let freed = entries
+            .filter_map(|entry| entry.ok()?.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        fs::remove_dir_all(&self.dir).map_err(|e| Error::io(&self.dir, e))?;
+        Ok(freed)
+    }
+
+    /// Path of the metadata for a tool's release.
+    fn release_path(&self, tool: Tool, tag: &str) -> PathBuf {
+        self.dir.join(format!(
+            "release-{}-{}.json",
+            tool.name(),
+            tag.replace('/', "_")
+        ))
+    }
+
+    /// Path of the entry for an asset: a digest of its key, followed by the
+    /// asset name to keep the directory readable.
+    fn entry_path(&self, release: &Release, asset: &ReleaseAsset) -> PathBuf {
+        let key = checksum::sha256_hex(
+            format!("{}\n{}", asset.download_url, release.published_at).as_bytes(),
+        );
+        self.dir.join(format!("{}-{}", &key[..16], asset.name))
+    }
+}
+
+/// Path next to an entry, e.g. its digest.
+fn with_suffix(entry: &Path, suffix: &str) -> PathBuf {
+    let mut path = entry.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(published_at: &str) -> Release {
+        Release {
+            tag: "latest".to_string(),
+            name: "latest".to_string(),
+            prerelease: false,
+            published_at: published_at.to_string(),
+            notes: String::new(),
+            assets: Vec::new(),
+        }
+    }
+
+    fn asset() -> ReleaseAsset {
+        ReleaseAsset {
+            name: "buck2-x86_64-unknown-linux-gnu.zst".to_string(),
+            download_url: "https://example.com/buck2-x86_64-unknown-linux-gnu.zst".to_string(),
+            size: 5,
+        }
+    }
+
+    #[test]
+    fn test_cache_roundtrip_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(dir.path().join("toolchain"));
+        let release = release("2024-01-15T00:00:00Z");
+
+        assert!(cache.get(&release, &asset()).is_none());
+        cache.put(&release, &asset(), b"buck2").unwrap();
+        assert_eq!(cache.get(&release, &asset()).unwrap(), b"buck2");
+
+        // A republished rolling release misses the cache
+        assert!(
+            cache
+                .get(&self::release("2024-02-01T00:00:00Z"), &asset())
+                .is_none()
+        );
+
+        assert!(cache.clear().unwrap() > 0);
+        assert!(cache.get(&release, &asset()).is_none());
+        assert_eq!(cache.clear().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_release_metadata_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(dir.path());
+        assert!(cache.get_release(Tool::Buck2, "latest").is_none());
+
+        let mut release = release("2024-01-15T00:00:00Z");
+        release.assets.push(asset());
+        cache.put_release(Tool::Buck2, "latest", &release).unwrap();
+
+        let cached = cache.get_release(Tool::Buck2, "latest").unwrap();
+        assert_eq!(cached.published_at, release.published_at);
+        assert_eq!(cached.assets[0].name, asset().name);
+        assert!(cache.get_release(Tool::Bazel, "latest").is_none());
+    }
+
+    #[test]
+    fn test_cache_discards_corrupt_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(dir.path());
+        let release = release("2024-01-15T00:00:00Z");
+
+        cache.put(&release, &asset(), b"buck2").unwrap();
+        fs::write(cache.entry_path(&release, &asset()), b"buc").unwrap();
+
+        assert!(cache.get(&release, &asset()).is_none());
+        assert!(!cache.entry_path(&release, &asset()).exists());
+    }
+}
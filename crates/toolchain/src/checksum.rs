@@ -0,0 +1,102 @@
+//! SHA-256 verification of downloaded assets.
+//!
+//! Some tools publish a checksum file next to each release asset (e.g.
+//! `bazel-7.4.1-linux-x86_64.sha256`), in the format written by
+//! `sha256sum`:
+//!
+//! ```text
+//! 5d2c7a3b...  bazel-7.4.1-linux-x86_64
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use toolchain::checksum;
+//!
+//! let digest = checksum::sha256_hex(b"hello");
+//! assert!(digest.starts_with("2cf24dba"));
+//! ```
+
+use crate::error::{Error, Result};
+use crate::types::Tool;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+/// SHA-256 digest of `bytes` as lowercase hex.
+#[must_use]
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// Extract the digest from a checksum file.
+///
+/// Accepts `sha256sum` output (`<digest>  <file name>`) as well as a bare
+/// digest. Returns `None` if the first word is not a SHA-256 hex digest.
+#[must_use]
+pub fn parse_checksum_file(content: &str) -> Option<String> {
+    let digest = content.split_whitespace().next()?;
+    (digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| digest.to_ascii_lowercase())
+}
+
+/// Verify `bytes` against the contents of a checksum file.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidResponse` if the checksum file can't be read, and
+/// `Error::ChecksumMismatch` if the digests differ.
+pub fn verify(tool: Tool, bytes: &[u8], checksum_file: &[u8]) -> Result<()> {
+    let expected = parse_checksum_file(&String::from_utf8_lossy(checksum_file))
+        .ok_or_else(|| Error::InvalidResponse(format!("malformed checksum file for {tool}")))?;
+    let actual = sha256_hex(bytes);
+    if actual != expected {
+        return Err(Error::ChecksumMismatch {
+            tool: tool.to_string(),
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(sha256_hex(b"hello"), HELLO);
+    }
+
+    #[test]
+    fn test_parse_checksum_file() {
+        assert_eq!(
+            parse_checksum_file(&format!("{HELLO}  bazel-7.4.1-linux-x86_64\n")).as_deref(),
+            Some(HELLO)
+        );
+        assert_eq!(
+            parse_checksum_file(&HELLO.to_uppercase()).as_deref(),
+            Some(HELLO)
+        );
+        assert!(parse_checksum_file("not a digest").is_none());
+        assert!(parse_checksum_file("").is_none());
+    }
+
+    #[test]
+    fn test_verify() {
+        assert!(verify(Tool::Bazel, b"hello", HELLO.as_bytes()).is_ok());
+
+        let err = verify(Tool::Bazel, b"tampered", HELLO.as_bytes()).unwrap_err();
+        assert!(matches!(err, Error::ChecksumMismatch { .. }));
+
+        let err = verify(Tool::Bazel, b"hello", b"garbage").unwrap_err();
+        assert!(matches!(err, Error::InvalidResponse(_)));
+    }
+}
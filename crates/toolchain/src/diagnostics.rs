@@ -0,0 +1,316 @@
+//! Post-install checks on the user's `PATH`.
+//!
+//! Tools install to `~/.local/bin` by default, which many shells don't put
+//! on `PATH`, so a successful install can still leave `buck2: command not
+//! found`. Every [`InstallResult`](crate::InstallResult) carries a
+//! [`PathDiagnostics`] saying whether the tool is reachable and, if not,
+//! which shell startup file needs updating and with what line.
+//!
+//! ```no_run
+//! use toolchain::{Client, InstallOptions, Tool};
+//!
+//! let result = Client::new().install(Tool::Buck2, InstallOptions::default()).unwrap();
+//! if let Some(advice) = result.path_diagnostics.advice() {
+//!     eprintln!("{advice}");
+//! }
+//! ```
+
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A login shell, as far as `PATH` setup goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    /// Bash.
+    Bash,
+    /// Zsh.
+    Zsh,
+    /// Fish.
+    Fish,
+    /// Any other POSIX shell, configured through `~/.profile`.
+    Posix,
+}
+
+impl Shell {
+    /// Shell from a `$SHELL` value such as `/bin/zsh`.
+    #[must_use]
+    pub fn from_path(shell: &str) -> Self {
+        match Path::new(shell).file_name().and_then(OsStr::to_str) {
+            Some("bash") => Self::Bash,
+            Some("zsh") => Self::Zsh,
+            Some("fish") => Self::Fish,
+            _ => Self::Posix,
+        }
+    }
+
+    /// Shell name.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+            Self::Posix => "sh",
+        }
+    }
+
+    /// Startup file that sets `PATH` for this shell.
+    ///
+    /// macOS terminals start login shells, which read `~/.bash_profile`
+    /// rather than `~/.bashrc`.
+    #[must_use]
+    pub fn rc_file(&self, home: &Path) -> PathBuf {
+        match self {
+            Self::Bash if cfg!(target_os = "macos") => home.join(".bash_profile"),
+            Self::Bash => home.join(".bashrc"),
+            Self::Zsh => std::env::var_os("ZDOTDIR")
+                .map_or_else(|| home.to_path_buf(), PathBuf::from)
+                .join(".zshrc"),
+            Self::Fish => home.join(".config").join("fish").join("config.fish"),
+            Self::Posix => home.join(".profile"),
+        }
+    }
+
+    /// Line adding `dir` to `PATH` in this shell's syntax.
+    #[must_use]
+    pub fn path_line(&self, dir: &Path, home: Option<&Path>) -> String {
+        // Keep the line portable across machines where possible
+        let dir = match home.and_then(|home| dir.strip_prefix(home).ok()) {
+            Some(relative) => format!("$HOME/{}", relative.display()),
+            None => dir.display().to_string(),
+        };
+        match self {
+            Self::Fish => format!("fish_add_path \"{dir}\""),
+            _ => format!("export PATH=\"{dir}:$PATH\""),
+        }
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Whether an installed tool can be run by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathDiagnostics {
+    /// Directory the tool was installed to.
+    pub install_dir: PathBuf,
+    /// Whether `install_dir` is on `PATH`.
+    pub on_path: bool,
+    /// Another binary with the same name that `PATH` finds first.
+    pub shadowed_by: Option<PathBuf>,
+    /// The user's shell, if known.
+    pub shell: Option<Shell>,
+    /// Startup file to add `install_dir` to `PATH` in, if it isn't on it.
+    pub rc_file: Option<PathBuf>,
+    /// Line to add to `rc_file`.
+    pub path_line: Option<String>,
+}
+
+impl PathDiagnostics {
+    /// Check whether `binary_name` in `install_dir` is reachable through the
+    /// current `PATH`.
+    #[must_use]
+    pub fn check(install_dir: &Path, binary_name: &str) -> Self {
+        let path = std::env::var_os("PATH");
+        let shell = std::env::var("SHELL").ok();
+        Self::check_with(
+            install_dir,
+            binary_name,
+            path.as_deref(),
+            shell.as_deref(),
+            dirs::home_dir().as_deref(),
+        )
+    }
+
+    /// [`check`](Self::check) against the given `PATH`, `$SHELL`, and home
+    /// directory.
+    #[must_use]
+    pub fn check_with(
+        install_dir: &Path,
+        binary_name: &str,
+        path: Option<&OsStr>,
+        shell: Option<&str>,
+        home: Option<&Path>,
+    ) -> Self {
+        let dirs: Vec<PathBuf> = path
+            .map(|p| std::env::split_paths(p).collect())
+            .unwrap_or_default();
+        let on_path = dirs.iter().any(|dir| same_dir(dir, install_dir));
+        let shadowed_by = dirs
+            .iter()
+            .take_while(|dir| !same_dir(dir, install_dir))
+            .map(|dir| dir.join(binary_name))
+            .find(|candidate| candidate.is_file());
+
+        let shell = shell.filter(|s| !s.is_empty()).map(Shell::from_path);
+        let (rc_file, path_line) = match (on_path, home) {
+            (false, Some(home)) if !cfg!(windows) => {
+                let shell = shell.unwrap_or(Shell::Posix);
+                (
+                    Some(shell.rc_file(home)),
+                    Some(shell.path_line(install_dir, Some(home))),
+                )
+            }
+            _ => (None, None),
+        };
+
+        Self {
+            install_dir: install_dir.to_path_buf(),
+            on_path,
+            shadowed_by,
+            shell,
+            rc_file,
+            path_line,
+        }
+    }
+
+    /// Whether running the tool by name reaches the installed binary.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.on_path && self.shadowed_by.is_none()
+    }
+
+    /// What the user should do to run the tool by name, if anything.
+    #[must_use]
+    pub fn advice(&self) -> Option<String> {
+        if let Some(other) = &self.shadowed_by {
+            return Some(format!(
+                "{} comes earlier on PATH than {}; move {} ahead of {} or remove it",
+                other.display(),
+                self.install_dir.display(),
+                self.install_dir.display(),
+                other.parent().unwrap_or(other).display()
+            ));
+        }
+        if self.on_path {
+            return None;
+        }
+        Some(match (&self.rc_file, &self.path_line) {
+            (Some(rc_file), Some(line)) => format!(
+                "{} is not on PATH; add this to {}:\n  {line}",
+                self.install_dir.display(),
+                rc_file.display()
+            ),
+            _ => format!("{} is not on PATH", self.install_dir.display()),
+        })
+    }
+}
+
+/// Whether two paths name the same directory.
+fn same_dir(a: &Path, b: &Path) -> bool {
+    if a == b {
+        return true;
+    }
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+    use std::fs;
+
+    fn path_var(dirs: &[&Path]) -> OsString {
+        std::env::join_paths(dirs).unwrap()
+    }
+
+    #[test]
+    fn test_shell_from_path() {
+        assert_eq!(Shell::from_path("/bin/zsh"), Shell::Zsh);
+        assert_eq!(Shell::from_path("/usr/local/bin/fish"), Shell::Fish);
+        assert_eq!(Shell::from_path("bash"), Shell::Bash);
+        assert_eq!(Shell::from_path("/bin/dash"), Shell::Posix);
+    }
+
+    #[test]
+    fn test_path_line() {
+        let home = Path::new("/home/dev");
+        let dir = home.join(".local/bin");
+        assert_eq!(
+            Shell::Bash.path_line(&dir, Some(home)),
+            "export PATH=\"$HOME/.local/bin:$PATH\""
+        );
+        assert_eq!(
+            Shell::Fish.path_line(Path::new("/opt/tools"), Some(home)),
+            "fish_add_path \"/opt/tools\""
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_check_on_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let install_dir = dir.path().join("bin");
+        fs::create_dir(&install_dir).unwrap();
+
+        let path = path_var(&[Path::new("/nonexistent"), &install_dir]);
+        let diagnostics = PathDiagnostics::check_with(
+            &install_dir,
+            "buck2",
+            Some(&path),
+            Some("/bin/zsh"),
+            Some(dir.path()),
+        );
+        assert!(diagnostics.is_ok());
+        assert_eq!(diagnostics.shell, Some(Shell::Zsh));
+        assert_eq!(diagnostics.rc_file, None);
+        assert_eq!(diagnostics.advice(), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_check_not_on_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let install_dir = dir.path().join(".local").join("bin");
+
+        let path = path_var(&[Path::new("/usr/bin")]);
+        let diagnostics = PathDiagnostics::check_with(
+            &install_dir,
+            "buck2",
+            Some(&path),
+            Some("/usr/bin/fish"),
+            Some(dir.path()),
+        );
+        assert!(!diagnostics.on_path);
+        assert_eq!(
+            diagnostics.rc_file,
+            Some(dir.path().join(".config/fish/config.fish"))
+        );
+        assert_eq!(
+            diagnostics.path_line.as_deref(),
+            Some("fish_add_path \"$HOME/.local/bin\"")
+        );
+        assert!(diagnostics.advice().unwrap().contains("config.fish"));
+
+        // Without $SHELL, fall back to ~/.profile
+        let diagnostics =
+            PathDiagnostics::check_with(&install_dir, "buck2", None, None, Some(dir.path()));
+        assert_eq!(diagnostics.rc_file, Some(dir.path().join(".profile")));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_check_shadowed() {
+        let dir = tempfile::tempdir().unwrap();
+        let earlier = dir.path().join("usr-bin");
+        let install_dir = dir.path().join("bin");
+        fs::create_dir(&earlier).unwrap();
+        fs::create_dir(&install_dir).unwrap();
+        fs::write(earlier.join("bazel"), b"old bazel").unwrap();
+
+        let path = path_var(&[&earlier, &install_dir]);
+        let diagnostics =
+            PathDiagnostics::check_with(&install_dir, "bazel", Some(&path), None, None);
+        assert!(diagnostics.on_path);
+        assert_eq!(diagnostics.shadowed_by, Some(earlier.join("bazel")));
+        assert!(!diagnostics.is_ok());
+        assert!(diagnostics.advice().unwrap().contains("earlier on PATH"));
+    }
+}
@@ -28,6 +28,10 @@ pub enum ErrorCategory {
     Format,
     /// Tool already installed (may be ignorable).
     AlreadyInstalled,
+    /// API rate limit exhausted.
+    RateLimited,
+    /// Offline, and what's needed isn't cached.
+    NotCached,
     /// Other/unknown errors.
     Other,
 }
@@ -55,6 +59,8 @@ impl ErrorCategory {
             Self::Permission => "Permission denied",
             Self::Format => "Invalid file format",
             Self::AlreadyInstalled => "Already installed",
+            Self::RateLimited => "API rate limit exceeded",
+            Self::NotCached => "Not available offline",
             Self::Other => "Unexpected error",
         }
     }
@@ -69,6 +75,8 @@ impl ErrorCategory {
             Self::Permission => "Check directory permissions or run with appropriate access",
             Self::Format => "The downloaded file may be corrupted, try again",
             Self::AlreadyInstalled => "Use --force to overwrite the existing installation",
+            Self::RateLimited => "Set GITHUB_TOKEN to raise the limit, or wait for it to reset",
+            Self::NotCached => "Install once while online to cache what's needed",
             Self::Other => "Check the error details for more information",
         }
     }
@@ -141,10 +149,37 @@ pub enum Error {
     #[error("GitHub API error: {0}")]
     GitHubApi(String),
 
+    /// API rate limit exhausted.
+    #[error("GitHub API rate limit exceeded{}", reset_hint(*.reset_at))]
+    RateLimited {
+        /// When the limit resets (Unix time), if known.
+        reset_at: Option<u64>,
+    },
+
+    /// Offline install needs files that aren't cached.
+    #[error("cannot install {tool} offline: {} not cached", .missing.join(", "))]
+    NotCached {
+        /// Tool being installed.
+        tool: String,
+        /// What would have to be fetched, e.g. `release 7.4.1`.
+        missing: Vec<String>,
+    },
+
     /// Invalid response from API.
     #[error("invalid API response: {0}")]
     InvalidResponse(String),
 
+    /// Downloaded asset doesn't match its published checksum.
+    #[error("checksum mismatch for {tool}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        /// Tool being downloaded.
+        tool: String,
+        /// Published SHA-256 digest.
+        expected: String,
+        /// SHA-256 digest of the downloaded bytes.
+        actual: String,
+    },
+
     /// Permission denied during installation.
     #[error("permission denied: {path}")]
     PermissionDenied {
@@ -192,7 +227,10 @@ impl Error {
             Error::ToolNotFound(_) => ErrorCategory::NotFound,
             Error::VersionNotFound { .. } => ErrorCategory::NotFound,
             Error::GitHubApi(_) => ErrorCategory::Network,
+            Error::RateLimited { .. } => ErrorCategory::RateLimited,
+            Error::NotCached { .. } => ErrorCategory::NotCached,
             Error::InvalidResponse(_) => ErrorCategory::Format,
+            Error::ChecksumMismatch { .. } => ErrorCategory::Format,
             Error::PermissionDenied { .. } => ErrorCategory::Permission,
             Error::Other(msg) => {
                 if msg.contains("already installed") {
@@ -217,6 +255,21 @@ impl Error {
     }
 }
 
+/// Describe when a rate limit resets, relative to now.
+fn reset_hint(reset_at: Option<u64>) -> String {
+    let Some(reset_at) = reset_at else {
+        return String::new();
+    };
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    match reset_at.saturating_sub(now) {
+        0 => " (resets now)".to_string(),
+        secs if secs < 60 => format!(" (resets in {secs}s)"),
+        secs => format!(" (resets in {} min)", secs.div_ceil(60)),
+    }
+}
+
 impl From<ureq::Error> for Error {
     fn from(err: ureq::Error) -> Self {
         match err {
@@ -259,6 +312,7 @@ mod tests {
         assert!(!ErrorCategory::Permission.is_retryable());
         assert!(!ErrorCategory::Format.is_retryable());
         assert!(!ErrorCategory::AlreadyInstalled.is_retryable());
+        assert!(!ErrorCategory::RateLimited.is_retryable());
         assert!(!ErrorCategory::Other.is_retryable());
     }
 
@@ -327,6 +381,51 @@ mod tests {
         assert!(!err.is_retryable());
     }
 
+    #[test]
+    fn test_error_checksum_mismatch_category() {
+        let err = Error::ChecksumMismatch {
+            tool: "bazel".to_string(),
+            expected: "abc".to_string(),
+            actual: "def".to_string(),
+        };
+        assert_eq!(err.category(), ErrorCategory::Format);
+        assert!(err.to_string().contains("bazel"));
+    }
+
+    #[test]
+    fn test_error_rate_limited() {
+        let err = Error::RateLimited { reset_at: None };
+        assert_eq!(err.category(), ErrorCategory::RateLimited);
+        assert_eq!(err.to_string(), "GitHub API rate limit exceeded");
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let err = Error::RateLimited {
+            reset_at: Some(now + 600),
+        };
+        assert!(err.to_string().ends_with("(resets in 10 min)"));
+    }
+
+    #[test]
+    fn test_error_not_cached() {
+        let err = Error::NotCached {
+            tool: "bazel".to_string(),
+            missing: vec![
+                "asset bazel-7.4.1-linux-x86_64".to_string(),
+                "asset bazel-7.4.1-linux-x86_64.sha256".to_string(),
+            ],
+        };
+        assert_eq!(err.category(), ErrorCategory::NotCached);
+        assert!(!err.is_retryable());
+        assert_eq!(
+            err.to_string(),
+            "cannot install bazel offline: asset bazel-7.4.1-linux-x86_64, \
+             asset bazel-7.4.1-linux-x86_64.sha256 not cached"
+        );
+    }
+
     #[test]
     fn test_error_io_permission_denied_category() {
         let io_err = io::Error::new(io::ErrorKind::PermissionDenied, "permission denied");
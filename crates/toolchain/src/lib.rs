@@ -3,10 +3,16 @@
 //! Pure Rust library for installing and managing build tools.
 //!
 //! This crate provides functionality for:
-//! - Installing build tools (Buck2, Bazel, etc.) from official releases
+//! - Installing build tools (Buck2, Bazel, Buildifier) from official releases
 //! - Managing multiple versions of tools
 //! - Platform detection for correct binary selection
-//! - Automatic decompression (zstd)
+//! - Automatic extraction (zstd, tar.gz, zip)
+//! - SHA-256 verification against published checksums
+//! - Caching downloads, so reinstalls don't fetch them again
+//! - Lockfiles pinning tool binaries by digest
+//! - Checking that installed tools are on `PATH`
+//! - Proxies, from the environment or set with [`Client::builder`]
+//! - Updating bossa itself, with [`self_update`]
 //!
 //! ## Example
 //!
@@ -33,9 +39,26 @@
 //!
 //! ## Supported Tools
 //!
-//! | Tool  | Source                          | Platforms                    |
-//! |-------|---------------------------------|------------------------------|
-//! | Buck2 | github.com/facebook/buck2       | macOS, Linux, Windows        |
+//! | Tool       | Source                           | Platforms             |
+//! |------------|----------------------------------|-----------------------|
+//! | Buck2      | github.com/facebook/buck2        | macOS, Linux, Windows |
+//! | Bazel      | github.com/bazelbuild/bazel      | macOS, Linux, Windows |
+//! | Buildifier | github.com/bazelbuild/buildtools | macOS, Linux, Windows |
+//!
+//! Bazel downloads are checked against the `.sha256` file published with
+//! each release asset. A release missing it installs unverified (see
+//! `InstallResult::unverified`), or fails with
+//! `InstallOptions::require_checksum`.
+//!
+//! ## Buck2 Project Bootstrap
+//!
+//! A usable Buck2 setup also needs the prelude and a `.buckconfig`:
+//!
+//! ```no_run
+//! // Vendors the prelude matching the installed buck2 and writes minimal config
+//! let init = toolchain::buck2::init_project("./my-project").unwrap();
+//! println!("prelude pinned to {}", init.prelude_hash);
+//! ```
 //!
 //! ## Platform Detection
 //!
@@ -53,22 +76,42 @@
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
+pub mod archive;
 pub mod backend;
+pub mod cache;
+pub mod checksum;
+pub mod diagnostics;
 pub mod error;
+pub mod lockfile;
 pub mod platform;
+pub mod progress;
+pub mod proxy;
+pub mod self_update;
 pub mod tools;
 pub mod types;
+pub mod versions;
 
+pub use archive::ArchiveFormat;
+pub use cache::DownloadCache;
+pub use diagnostics::PathDiagnostics;
 pub use error::{Error, ErrorCategory, Result};
+pub use lockfile::{LockedTool, Lockfile};
+pub use progress::DownloadProgress;
+pub use proxy::ProxyConfig;
+pub use tools::buck2;
 pub use types::{
     InstallOptions, InstallResult, InstalledTool, Platform, Release, ReleaseAsset, Tool,
+    UninstallResult, UpgradeResult,
 };
+pub use versions::VersionStore;
 
 use backend::Backend;
 pub use backend::MockBackend;
 use backend::github::GitHubBackend;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tools::ToolInstaller;
-use tools::buck2::Buck2Installer;
+use versions::is_newer;
 
 /// High-level client for toolchain operations.
 ///
@@ -90,21 +133,87 @@ use tools::buck2::Buck2Installer;
 /// ```
 pub struct Client {
     backend: Box<dyn Backend>,
+    /// Backends used instead of `backend` for specific tools
+    tool_backends: HashMap<Tool, Box<dyn Backend>>,
+    cache: Option<DownloadCache>,
+    versions: Option<VersionStore>,
 }
 
 impl Client {
     /// Create a new Client with the default GitHub backend.
+    ///
+    /// Downloads are cached in [`DownloadCache::default_location`], and
+    /// versioned installs go to [`VersionStore::default_location`].
     #[must_use]
     pub fn new() -> Self {
         Self {
             backend: Box::new(GitHubBackend::new()),
+            tool_backends: HashMap::new(),
+            cache: DownloadCache::default_location(),
+            versions: VersionStore::default_location(),
         }
     }
 
+    /// Start building a client with custom network settings.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use toolchain::Client;
+    ///
+    /// let client = Client::builder()
+    ///     .proxy("http://proxy.corp.example.com:3128")
+    ///     .no_proxy(["localhost", ".corp.example.com"])
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[must_use]
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
     /// Create a client with a custom backend (useful for testing).
+    ///
+    /// Downloads are not cached unless a cache is set with
+    /// [`Client::with_download_cache`]. Versioned installs go to
+    /// [`VersionStore::default_location`] unless a store is set with
+    /// [`Client::with_version_store`].
     #[must_use]
     pub fn with_backend(backend: Box<dyn Backend>) -> Self {
-        Self { backend }
+        Self {
+            backend,
+            tool_backends: HashMap::new(),
+            cache: None,
+            versions: VersionStore::default_location(),
+        }
+    }
+
+    /// Fetch `tool`'s releases from `backend` instead of the client's
+    /// backend, e.g. a [`GitLabBackend`](backend::gitlab::GitLabBackend)
+    /// for a tool published on GitLab.
+    #[must_use]
+    pub fn with_tool_backend(mut self, tool: Tool, backend: Box<dyn Backend>) -> Self {
+        self.tool_backends.insert(tool, backend);
+        self
+    }
+
+    /// Cache downloaded assets in `cache`.
+    #[must_use]
+    pub fn with_download_cache(mut self, cache: DownloadCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Keep versioned installs in `store`.
+    #[must_use]
+    pub fn with_version_store(mut self, store: VersionStore) -> Self {
+        self.versions = Some(store);
+        self
+    }
+
+    /// Remove all cached downloads, returning the number of bytes freed.
+    pub fn clear_cache(&self) -> Result<u64> {
+        self.cache.as_ref().map_or(Ok(0), DownloadCache::clear)
     }
 
     // =========================================================================
@@ -114,7 +223,9 @@ impl Client {
     /// Install a tool.
     ///
     /// Downloads the appropriate binary for the current platform and installs
-    /// it to the specified (or default) location.
+    /// it to the specified (or default) location. If the release publishes a
+    /// checksum for the binary, the download is verified against it first.
+    /// Progress is reported to [`InstallOptions::on_progress`], if set.
     ///
     /// # Example
     ///
@@ -135,16 +246,561 @@ impl Client {
 
         // Fetch the release
         let tag = options.version.as_deref().unwrap_or("latest");
-        let release = self.backend.fetch_release(tool, tag)?;
+        let release = self.fetch_release(tool, tag, &options)?;
 
-        // Download the asset
-        let compressed = self.backend.download_asset(tool, &release, &platform)?;
+        self.install_release(tool, &release, &platform, &options)
+    }
+
+    /// Install several tools, up to `jobs` at a time.
+    ///
+    /// Each tool is installed as by [`Client::install`], on a pool of `jobs`
+    /// worker threads (at least one). Returns one result per entry, in the
+    /// order given; a failure doesn't stop the other installs.
+    ///
+    /// Entries installing the same tool into the same directory race each
+    /// other, so list each tool once.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use toolchain::{Client, Tool, InstallOptions};
+    ///
+    /// let client = Client::new();
+    /// let tools = [
+    ///     (Tool::Bazel, InstallOptions::new().version("7.4.1")),
+    ///     (Tool::Buildifier, InstallOptions::new()),
+    /// ];
+    /// for result in client.install_many(&tools, 4) {
+    ///     match result {
+    ///         Ok(installed) => println!("{} {}", installed.tool, installed.version),
+    ///         Err(e) => eprintln!("{e}"),
+    ///     }
+    /// }
+    /// ```
+    pub fn install_many(
+        &self,
+        tools: &[(Tool, InstallOptions)],
+        jobs: usize,
+    ) -> Vec<Result<InstallResult>> {
+        let install =
+            |(tool, options): &(Tool, InstallOptions)| self.install(*tool, options.clone());
+
+        let workers = jobs.clamp(1, tools.len().max(1));
+        if workers == 1 {
+            return tools.iter().map(install).collect();
+        }
+
+        // Each worker takes the next entry until none are left, and returns
+        // its results with their positions
+        let next = AtomicUsize::new(0);
+        let mut results: Vec<(usize, Result<InstallResult>)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(entry) = tools.get(i) else { break };
+                            done.push((i, install(entry)));
+                        }
+                        done
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        });
 
+        results.sort_by_key(|(i, _)| *i);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Pin tools in the lockfile at `path`, for the current platform.
+    ///
+    /// Each tool's tag (e.g. `"7.4.1"` or `"latest"`) is resolved to a
+    /// release, whose asset is downloaded (or taken from the cache) and
+    /// verified so its digest can be recorded. Entries for other platforms
+    /// in an existing lockfile are kept. Returns the written lockfile.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use toolchain::{Client, Tool};
+    ///
+    /// let client = Client::new();
+    /// client
+    ///     .write_lockfile("toolchain.lock", &[(Tool::Bazel, "7.4.1"), (Tool::Buck2, "latest")])
+    ///     .unwrap();
+    /// ```
+    pub fn write_lockfile(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        tools: &[(Tool, &str)],
+    ) -> Result<Lockfile> {
+        let path = path.as_ref();
+        let platform = platform::detect()?;
+        let mut lockfile = if path.exists() {
+            Lockfile::load(path)?
+        } else {
+            Lockfile::default()
+        };
+
+        let options = InstallOptions::new();
+        for &(tool, tag) in tools {
+            let release = self.fetch_release(tool, tag, &options)?;
+            let installer = self.get_installer(tool);
+            let (bytes, _) =
+                self.download_verified(installer.as_ref(), &release, &platform, &options)?;
+            let asset_name = installer.asset_name(&release, &platform)?;
+            let asset = release
+                .find_asset(&asset_name)
+                .ok_or_else(|| Error::DownloadFailed {
+                    tool: tool.to_string(),
+                    message: format!("no asset {asset_name} in release {}", release.tag),
+                })?;
+
+            lockfile.insert(LockedTool {
+                tool,
+                version: release.tag.clone(),
+                published_at: release.published_at.clone(),
+                platform: platform.triple.clone(),
+                asset: asset.name.clone(),
+                url: asset.download_url.clone(),
+                sha256: checksum::sha256_hex(&bytes),
+            });
+        }
+
+        lockfile.save(path)?;
+        Ok(lockfile)
+    }
+
+    /// Install every tool pinned in the lockfile at `path`.
+    ///
+    /// Installs exactly the asset pinned for the current platform, and fails
+    /// with `Error::ChecksumMismatch` if it no longer has the pinned digest.
+    /// `options.version` is ignored; everything else applies to each tool.
+    /// Stops at the first failure.
+    pub fn install_from_lockfile(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        options: InstallOptions,
+    ) -> Result<Vec<InstallResult>> {
+        let lockfile = Lockfile::load(path)?;
+        let platform = platform::detect()?;
+
+        let mut installed = Vec::new();
+        for tool in lockfile.pinned_tools() {
+            let locked = lockfile.find(tool, &platform.triple).ok_or_else(|| {
+                Error::Other(format!(
+                    "lockfile doesn't pin {tool} for {}; update it on this platform",
+                    platform.triple
+                ))
+            })?;
+
+            let release = locked.release();
+            let installer = self.get_installer(tool);
+            // The pinned digest checks the download, so the release's own
+            // checksum, which the lockfile doesn't keep, isn't required
+            let download_options = InstallOptions {
+                require_checksum: false,
+                ..options.clone()
+            };
+            let (bytes, _) =
+                self.download_verified(installer.as_ref(), &release, &platform, &download_options)?;
+            let actual = checksum::sha256_hex(&bytes);
+            if actual != locked.sha256 {
+                return Err(Error::ChecksumMismatch {
+                    tool: tool.to_string(),
+                    expected: locked.sha256.clone(),
+                    actual,
+                });
+            }
+
+            installed.push(self.install_bytes(
+                installer.as_ref(),
+                &release,
+                &bytes,
+                &platform,
+                &options,
+            )?);
+        }
+        Ok(installed)
+    }
+
+    /// Upgrade a tool to the latest (or requested) release.
+    ///
+    /// Compares the version in the install directory against the release and
+    /// installs it only if it is newer, or if `options.force` is set. Tools
+    /// that aren't installed yet are installed. The result carries the
+    /// release, so callers can show its notes.
+    ///
+    /// Releases without a version in their tag, like Buck2's rolling
+    /// `latest`, can't be compared and are always reinstalled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use toolchain::{Client, Tool, InstallOptions};
+    ///
+    /// let result = Client::new().upgrade(Tool::Bazel, InstallOptions::new()).unwrap();
+    /// println!("{result}");
+    /// if let Some(notes) = result.release_notes() {
+    ///     println!("What's new:\n{notes}");
+    /// }
+    /// ```
+    pub fn upgrade(&self, tool: Tool, options: InstallOptions) -> Result<UpgradeResult> {
+        let platform = platform::detect()?;
+        let tag = options.version.as_deref().unwrap_or("latest");
+        let release = self.fetch_release(tool, tag, &options)?;
+
+        let installer = self.get_installer(tool);
+        let install_dir = match &options.install_dir {
+            Some(dir) => dir.clone(),
+            None => installer.default_install_dir()?,
+        };
+        let previous_version =
+            tools::probe(installer.as_ref(), &install_dir)?.map(|installed| installed.version);
+
+        let up_to_date = previous_version
+            .as_deref()
+            .is_some_and(|installed| !is_newer(&release.tag, installed));
+        if up_to_date && !options.force {
+            return Ok(UpgradeResult {
+                tool,
+                previous_version,
+                release,
+                installed: None,
+            });
+        }
+
+        let installed = self.install_release(tool, &release, &platform, &options.force(true))?;
+        Ok(UpgradeResult {
+            tool,
+            previous_version,
+            release,
+            installed: Some(installed),
+        })
+    }
+
+    /// Fetch a release, from the cache when offline.
+    ///
+    /// Releases fetched online are cached, so they can be installed offline
+    /// later.
+    fn fetch_release(&self, tool: Tool, tag: &str, options: &InstallOptions) -> Result<Release> {
+        if options.offline {
+            return self
+                .cache
+                .as_ref()
+                .and_then(|cache| cache.get_release(tool, tag))
+                .ok_or_else(|| Error::NotCached {
+                    tool: tool.to_string(),
+                    missing: vec![format!("release {tag}")],
+                });
+        }
+
+        let release = self.backend(tool).fetch_release(tool, tag)?;
+        if let Some(cache) = self.cache.as_ref().filter(|_| !options.no_cache) {
+            let _ = cache.put_release(tool, tag, &release);
+        }
+        Ok(release)
+    }
+
+    /// Download, verify and install a release.
+    fn install_release(
+        &self,
+        tool: Tool,
+        release: &Release,
+        platform: &Platform,
+        options: &InstallOptions,
+    ) -> Result<InstallResult> {
         // Get the appropriate installer
         let installer = self.get_installer(tool);
+        let (bytes, unverified) =
+            self.download_verified(installer.as_ref(), release, platform, options)?;
+        let mut result =
+            self.install_bytes(installer.as_ref(), release, &bytes, platform, options)?;
+        result.unverified = unverified;
+        Ok(result)
+    }
 
-        // Install
-        installer.install(&compressed, &platform, &options)
+    /// Download a release's asset for `platform`, or get it from the cache,
+    /// and verify it against its published checksum.
+    ///
+    /// Also returns whether the release lacks the checksum the tool
+    /// publishes, leaving the download unverified. That fails instead with
+    /// [`InstallOptions::require_checksum`].
+    fn download_verified(
+        &self,
+        installer: &dyn ToolInstaller,
+        release: &Release,
+        platform: &Platform,
+        options: &InstallOptions,
+    ) -> Result<(Vec<u8>, bool)> {
+        let tool = installer.tool();
+        let asset_name = installer.asset_name(release, platform)?;
+        let asset = release.find_asset(&asset_name);
+        let checksum_name = installer.checksum_asset_name(&asset_name);
+        let checksum_asset = checksum_name
+            .as_deref()
+            .and_then(|name| release.find_asset(name));
+        let unverified = checksum_name.is_some() && checksum_asset.is_none();
+        if unverified && options.require_checksum {
+            return Err(Error::InvalidResponse(format!(
+                "{asset_name} has no published checksum"
+            )));
+        }
+        // Offline, the cache is all there is
+        let cache = self
+            .cache
+            .as_ref()
+            .filter(|_| options.offline || !options.no_cache);
+
+        let cached = cache
+            .zip(asset)
+            .and_then(|(cache, asset)| cache.get(release, asset));
+        let cached_checksum = cache
+            .zip(checksum_asset)
+            .and_then(|(cache, asset)| cache.get(release, asset));
+
+        if options.offline {
+            let mut missing = Vec::new();
+            if cached.is_none() {
+                missing.push(format!("asset {asset_name}"));
+            }
+            if let (Some(checksum_asset), None) = (checksum_asset, &cached_checksum) {
+                missing.push(format!("asset {}", checksum_asset.name));
+            }
+            if !missing.is_empty() {
+                return Err(Error::NotCached {
+                    tool: tool.to_string(),
+                    missing,
+                });
+            }
+        }
+
+        // Download the asset, unless it's cached
+        let was_cached = cached.is_some();
+        let bytes = match (cached, asset) {
+            (Some(bytes), _) => {
+                if let Some(progress) = &options.progress {
+                    progress.report_done(tool, bytes.len());
+                }
+                bytes
+            }
+            (None, Some(asset)) => match &options.progress {
+                Some(progress) => self
+                    .backend(tool)
+                    .download_release_asset_with_progress(tool, asset, progress)?,
+                None => self.backend(tool).download_release_asset(tool, asset)?,
+            },
+            // Let the backend report the missing asset
+            (None, None) => self.backend(tool).download_asset(tool, release, platform)?,
+        };
+
+        // Verify the download against its published checksum
+        if let Some(checksum_asset) = checksum_asset {
+            let checksum_file = match cached_checksum {
+                Some(checksum_file) => checksum_file,
+                None => {
+                    let checksum_file = self
+                        .backend(tool)
+                        .download_release_asset(tool, checksum_asset)?;
+                    if let Some(cache) = cache {
+                        let _ = cache.put(release, checksum_asset, &checksum_file);
+                    }
+                    checksum_file
+                }
+            };
+            checksum::verify(tool, &bytes, &checksum_file)?;
+        }
+
+        // Only verified downloads are cached. The cache only saves time, so
+        // failing to write it is fine.
+        if let (Some(cache), Some(asset), false) = (cache, asset, was_cached) {
+            let _ = cache.put(release, asset, &bytes);
+        }
+
+        Ok((bytes, unverified))
+    }
+
+    /// Install a downloaded asset.
+    fn install_bytes(
+        &self,
+        installer: &dyn ToolInstaller,
+        release: &Release,
+        bytes: &[u8],
+        platform: &Platform,
+        options: &InstallOptions,
+    ) -> Result<InstallResult> {
+        if options.versioned {
+            return self.install_version(installer, release, bytes, platform, options);
+        }
+        installer.install(bytes, platform, options)
+    }
+
+    /// Install a release into the version store, behind a shim in the
+    /// install directory.
+    fn install_version(
+        &self,
+        installer: &dyn ToolInstaller,
+        release: &Release,
+        bytes: &[u8],
+        platform: &Platform,
+        options: &InstallOptions,
+    ) -> Result<InstallResult> {
+        let tool = installer.tool();
+        let store = self.version_store()?;
+        let shim_dir = match &options.install_dir {
+            Some(dir) => dir.clone(),
+            None => installer.default_install_dir()?,
+        };
+
+        // A binary in the way fails the install before anything is put in
+        // the store; the shim itself is only written once the install worked
+        store.check_shim(tool, &shim_dir, options.force)?;
+
+        let version_options = InstallOptions {
+            install_dir: Some(store.version_dir(tool, &release.tag)),
+            ..options.clone()
+        };
+        let mut result = installer.install(bytes, platform, &version_options)?;
+        store.write_shim(tool, &shim_dir, options.force)?;
+        // The tool is run through its shim, not from the store
+        result.path_diagnostics = PathDiagnostics::check(
+            &shim_dir,
+            &format!("{}{}", tool.binary_name(), platform::executable_extension()),
+        );
+
+        if store.current(tool).is_none() {
+            store.set_current(tool, &release.tag)?;
+        }
+        Ok(result)
+    }
+
+    // =========================================================================
+    // Version Selection
+    // =========================================================================
+
+    /// Select the version of a tool to run everywhere without a
+    /// per-directory selection.
+    ///
+    /// The version must have been installed with
+    /// [`InstallOptions::versioned`]. Also writes the tool's shim to the
+    /// default install directory if it's missing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use toolchain::{Client, Tool, InstallOptions};
+    ///
+    /// let client = Client::new();
+    /// let options = InstallOptions::new().version("7.4.1").versioned(true);
+    /// client.install(Tool::Bazel, options).unwrap();
+    /// client.use_version(Tool::Bazel, "7.4.1").unwrap();
+    /// ```
+    pub fn use_version(&self, tool: Tool, version: &str) -> Result<()> {
+        let store = self.version_store()?;
+        store.set_current(tool, version)?;
+        let install_dir = self.get_installer(tool).default_install_dir()?;
+        store.write_shim(tool, &install_dir, false)?;
+        Ok(())
+    }
+
+    /// Select the version of a tool to run in `dir` and below, by writing
+    /// it to `dir/.tool-versions`. Returns the path of that file.
+    pub fn use_version_in(
+        &self,
+        tool: Tool,
+        version: &str,
+        dir: impl AsRef<std::path::Path>,
+    ) -> Result<std::path::PathBuf> {
+        if !self.version_store()?.has_version(tool, version) {
+            return Err(Error::VersionNotFound {
+                tool: tool.to_string(),
+                version: version.to_string(),
+            });
+        }
+        versions::set_local(dir.as_ref(), tool, version)
+    }
+
+    /// List the versions of a tool in the version store.
+    pub fn installed_versions(&self, tool: Tool) -> Result<Vec<String>> {
+        self.versions
+            .as_ref()
+            .map_or(Ok(Vec::new()), |store| store.versions(tool))
+    }
+
+    /// Uninstall a tool from the default install directory.
+    ///
+    /// Removes the files the installer placed there and returns what was
+    /// removed, along with every version in the version store. Copies found
+    /// elsewhere in `PATH` (e.g. from a package manager) are left alone.
+    /// Succeeds, removing nothing, if the tool
+    /// isn't installed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use toolchain::{Client, Tool};
+    ///
+    /// let result = Client::new().uninstall(Tool::Bazel).unwrap();
+    /// println!("{result}");
+    /// ```
+    pub fn uninstall(&self, tool: Tool) -> Result<UninstallResult> {
+        let installer = self.get_installer(tool);
+        let install_dir = installer.default_install_dir()?;
+        let mut result = tools::uninstall(installer.as_ref(), &install_dir)?;
+
+        // Versioned installs live in the version store
+        if let Some(store) = &self.versions {
+            let tool_dir = store.tool_dir(tool);
+            if tool_dir.exists() {
+                std::fs::remove_dir_all(&tool_dir).map_err(|e| Error::io(&tool_dir, e))?;
+                result.removed.push(tool_dir);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Uninstall a tool that was installed to a custom directory
+    /// (see [`InstallOptions::install_dir`]).
+    pub fn uninstall_from(
+        &self,
+        tool: Tool,
+        install_dir: impl AsRef<std::path::Path>,
+    ) -> Result<UninstallResult> {
+        let installer = self.get_installer(tool);
+        tools::uninstall(installer.as_ref(), install_dir.as_ref())
+    }
+
+    /// List the tools installed in their default install directory.
+    ///
+    /// Each binary found is run to learn its version; its modification time
+    /// is reported as the install date.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use toolchain::Client;
+    ///
+    /// for installed in Client::new().list_installed().unwrap() {
+    ///     println!("{} {} at {}", installed.tool, installed.version, installed.path.display());
+    /// }
+    /// ```
+    pub fn list_installed(&self) -> Result<Vec<InstalledTool>> {
+        let mut installed = Vec::new();
+        for &tool in Tool::all() {
+            let installer = self.get_installer(tool);
+            let install_dir = installer.default_install_dir()?;
+            installed.extend(tools::probe(installer.as_ref(), &install_dir)?);
+        }
+        Ok(installed)
     }
 
     /// Check if a tool is installed.
@@ -159,6 +815,18 @@ impl Client {
         installer.installed_version()
     }
 
+    /// Bootstrap a Buck2 project, vendoring the prelude that matches the
+    /// installed (or requested) Buck2 version.
+    ///
+    /// See [`buck2::init_project`] for details.
+    pub fn init_buck2_project(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        options: &buck2::InitOptions,
+    ) -> Result<buck2::ProjectInit> {
+        buck2::init_project_with(self.backend(Tool::Buck2), path, options)
+    }
+
     // =========================================================================
     // Release Information
     // =========================================================================
@@ -167,23 +835,36 @@ impl Client {
     ///
     /// Returns releases from newest to oldest.
     pub fn list_releases(&self, tool: Tool) -> Result<Vec<Release>> {
-        self.backend.fetch_releases(tool)
+        self.backend(tool).fetch_releases(tool)
     }
 
     /// Get information about a specific release.
     pub fn get_release(&self, tool: Tool, tag: &str) -> Result<Release> {
-        self.backend.fetch_release(tool, tag)
+        self.backend(tool).fetch_release(tool, tag)
     }
 
     // =========================================================================
     // Internal
     // =========================================================================
 
+    /// Get the backend serving a tool.
+    fn backend(&self, tool: Tool) -> &dyn Backend {
+        self.tool_backends
+            .get(&tool)
+            .unwrap_or(&self.backend)
+            .as_ref()
+    }
+
+    /// Get the version store, which versioned installs need.
+    fn version_store(&self) -> Result<&VersionStore> {
+        self.versions.as_ref().ok_or_else(|| {
+            Error::Other("cannot determine the data directory for versioned installs".to_string())
+        })
+    }
+
     /// Get the installer for a tool.
     fn get_installer(&self, tool: Tool) -> Box<dyn ToolInstaller> {
-        match tool {
-            Tool::Buck2 => Box::new(Buck2Installer::new()),
-        }
+        tools::installer(tool)
     }
 }
 
@@ -193,6 +874,54 @@ impl Default for Client {
     }
 }
 
+/// Builder for a [`Client`] using the GitHub backend with custom network
+/// settings.
+///
+/// Without a proxy set here, the standard proxy environment variables apply.
+#[derive(Debug, Default)]
+pub struct ClientBuilder {
+    proxy: Option<String>,
+    no_proxy: Vec<String>,
+}
+
+impl ClientBuilder {
+    /// Send requests through the proxy at `url`.
+    #[must_use]
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Reach `hosts` without the proxy (see [`ProxyConfig::no_proxy`]).
+    /// Only applies with a proxy set through [`ClientBuilder::proxy`].
+    #[must_use]
+    pub fn no_proxy<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.no_proxy
+            .extend(hosts.into_iter().map(|host| host.as_ref().to_string()));
+        self
+    }
+
+    /// Build the client.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proxy URL is invalid.
+    pub fn build(self) -> Result<Client> {
+        let mut backend = GitHubBackend::new();
+        if let Some(url) = self.proxy {
+            backend = backend.with_proxy(&ProxyConfig::new(url).no_proxy(self.no_proxy))?;
+        }
+        Ok(Client {
+            backend: Box::new(backend),
+            ..Client::new()
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -233,6 +962,30 @@ mod tests {
         assert!(!release.assets.is_empty());
     }
 
+    #[test]
+    fn test_client_with_tool_backend() {
+        let mut bazel = MockBackend::new();
+        bazel.add_release(
+            Tool::Bazel,
+            Release {
+                tag: "7.4.1".to_string(),
+                name: "7.4.1".to_string(),
+                prerelease: false,
+                published_at: String::new(),
+                notes: String::new(),
+                assets: vec![],
+            },
+        );
+        let client = Client::with_backend(Box::new(MockBackend::with_buck2_releases()))
+            .with_tool_backend(Tool::Bazel, Box::new(bazel));
+
+        assert_eq!(client.list_releases(Tool::Bazel).unwrap()[0].tag, "7.4.1");
+        assert_eq!(
+            client.list_releases(Tool::Buck2).unwrap()[0].tag,
+            "2024-01-15"
+        );
+    }
+
     #[test]
     fn test_client_get_release_not_found() {
         let mock = MockBackend::new();
@@ -242,6 +995,252 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_client_uninstall_from() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = Client::with_backend(Box::new(MockBackend::new()));
+        let binary = tools::binary_path(Tool::Buildifier, dir.path());
+        std::fs::write(&binary, b"buildifier").unwrap();
+
+        let result = client.uninstall_from(Tool::Buildifier, dir.path()).unwrap();
+        assert_eq!(result.removed, vec![binary]);
+
+        // Safe to repeat once it's gone
+        let result = client.uninstall_from(Tool::Buildifier, dir.path()).unwrap();
+        assert!(!result.was_installed());
+    }
+
+    #[test]
+    fn test_builder_validates_proxy() {
+        assert!(
+            Client::builder()
+                .proxy("http://proxy.example.com:3128")
+                .no_proxy(["localhost,.internal"])
+                .build()
+                .is_ok()
+        );
+        assert!(Client::builder().proxy("ftp://proxy").build().is_err());
+    }
+
+    #[test]
+    fn test_clear_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = Client::with_backend(Box::new(MockBackend::new()));
+        assert_eq!(client.clear_cache().unwrap(), 0);
+
+        let cache = DownloadCache::new(dir.path().join("toolchain"));
+        let release = MockBackend::with_buck2_releases()
+            .fetch_release(Tool::Buck2, "2024-01-15")
+            .unwrap();
+        cache.put(&release, &release.assets[0], b"buck2").unwrap();
+
+        let client = client.with_download_cache(cache);
+        assert_eq!(client.clear_cache().unwrap(), 5 + 64);
+        assert!(!dir.path().join("toolchain").exists());
+    }
+
+    #[test]
+    fn test_install_many_keeps_order() {
+        let client = Client::with_backend(Box::new(MockBackend::new()));
+        let tools = [
+            (Tool::Bazel, InstallOptions::new().version("7.4.1")),
+            (Tool::Buck2, InstallOptions::new().version("2024-01-15")),
+            (Tool::Buildifier, InstallOptions::new().version("8.0.0")),
+        ];
+
+        for jobs in [0, 1, 4] {
+            let results = client.install_many(&tools, jobs);
+            assert_eq!(results.len(), tools.len());
+            for (result, (tool, options)) in results.iter().zip(&tools) {
+                let Err(Error::VersionNotFound {
+                    tool: name,
+                    version,
+                }) = result
+                else {
+                    panic!("expected VersionNotFound, got {result:?}");
+                };
+                assert_eq!(name, tool.name());
+                assert_eq!(Some(version), options.version.as_ref());
+            }
+        }
+        assert!(client.install_many(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn test_lockfile_pins_asset_digest() {
+        let Ok(platform) = platform::detect() else {
+            return;
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("toolchain.lock");
+
+        let mut release = MockBackend::with_buck2_releases()
+            .fetch_release(Tool::Buck2, "2024-01-15")
+            .unwrap();
+        let asset_name = tools::installer(Tool::Buck2)
+            .asset_name(&release, &platform)
+            .unwrap();
+        release.assets = vec![ReleaseAsset {
+            name: asset_name.clone(),
+            download_url: format!("mock://{asset_name}"),
+            size: 5,
+        }];
+        let mut mock = MockBackend::new();
+        mock.add_release(Tool::Buck2, release);
+        mock.add_asset(&asset_name, b"buck2".to_vec());
+        let client = Client::with_backend(Box::new(mock.clone()));
+
+        let lockfile = client
+            .write_lockfile(&path, &[(Tool::Buck2, "2024-01-15")])
+            .unwrap();
+        let locked = lockfile.find(Tool::Buck2, &platform.triple).unwrap();
+        assert_eq!(locked.version, "2024-01-15");
+        assert_eq!(locked.sha256, checksum::sha256_hex(b"buck2"));
+        assert_eq!(Lockfile::load(&path).unwrap(), lockfile);
+
+        // The asset was replaced upstream after locking
+        mock.add_asset(&asset_name, b"buck3".to_vec());
+        let options = InstallOptions::new().install_dir(dir.path().join("bin"));
+        assert!(matches!(
+            client.install_from_lockfile(&path, options),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(!dir.path().join("bin").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_lockfile_digest_satisfies_require_checksum() {
+        let Ok(platform) = platform::detect() else {
+            return;
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("toolchain.lock");
+
+        let mut release = MockBackend::with_buck2_releases()
+            .fetch_release(Tool::Buck2, "2024-01-15")
+            .unwrap();
+        release.tag = "7.4.1".to_string();
+        let asset_name = tools::installer(Tool::Bazel)
+            .asset_name(&release, &platform)
+            .unwrap();
+        let binary = b"#!/bin/sh\necho bazel 7.4.1\n".to_vec();
+        let checksum_name = format!("{asset_name}.sha256");
+        release.assets = [&asset_name, &checksum_name]
+            .into_iter()
+            .map(|name| ReleaseAsset {
+                name: name.clone(),
+                download_url: format!("mock://{name}"),
+                size: 0,
+            })
+            .collect();
+        let mut mock = MockBackend::new();
+        mock.add_release(Tool::Bazel, release);
+        mock.add_asset(&asset_name, binary.clone());
+        mock.add_asset(&checksum_name, checksum::sha256_hex(&binary).into_bytes());
+        let client = Client::with_backend(Box::new(mock));
+
+        client
+            .write_lockfile(&path, &[(Tool::Bazel, "7.4.1")])
+            .unwrap();
+        let options = InstallOptions::new()
+            .install_dir(dir.path().join("bin"))
+            .require_checksum(true);
+        let installed = client.install_from_lockfile(&path, options).unwrap();
+        assert_eq!(installed.len(), 1);
+        assert_eq!(installed[0].version, "7.4.1");
+    }
+
+    #[test]
+    fn test_require_checksum_fails_without_one() {
+        let Ok(platform) = platform::detect() else {
+            return;
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let mut release = MockBackend::with_buck2_releases()
+            .fetch_release(Tool::Buck2, "2024-01-15")
+            .unwrap();
+        release.tag = "7.4.1".to_string();
+        let asset_name = tools::installer(Tool::Bazel)
+            .asset_name(&release, &platform)
+            .unwrap();
+        release.assets = vec![ReleaseAsset {
+            name: asset_name.clone(),
+            download_url: format!("mock://{asset_name}"),
+            size: 5,
+        }];
+        let mut mock = MockBackend::new();
+        mock.add_release(Tool::Bazel, release);
+        mock.add_asset(&asset_name, b"bazel".to_vec());
+        let client = Client::with_backend(Box::new(mock));
+
+        let options = InstallOptions::new()
+            .version("7.4.1")
+            .install_dir(dir.path().join("bin"))
+            .require_checksum(true);
+        let Err(Error::InvalidResponse(message)) = client.install(Tool::Bazel, options) else {
+            panic!("expected InvalidResponse");
+        };
+        assert_eq!(message, format!("{asset_name} has no published checksum"));
+        assert!(!dir.path().join("bin").exists());
+    }
+
+    #[test]
+    fn test_offline_install_lists_uncached_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(dir.path());
+        let client = Client::with_backend(Box::new(MockBackend::with_buck2_releases()))
+            .with_download_cache(cache.clone());
+        let options = InstallOptions::new()
+            .version("2024-01-15")
+            .install_dir(dir.path().join("bin"))
+            .offline(true);
+
+        let Err(Error::NotCached { missing, .. }) = client.install(Tool::Buck2, options.clone())
+        else {
+            panic!("expected NotCached");
+        };
+        assert_eq!(missing, ["release 2024-01-15"]);
+
+        // The release is known, but its binary was never downloaded
+        let release = client.get_release(Tool::Buck2, "2024-01-15").unwrap();
+        cache
+            .put_release(Tool::Buck2, "2024-01-15", &release)
+            .unwrap();
+        match client.install(Tool::Buck2, options) {
+            Err(Error::NotCached { missing, .. }) => {
+                assert_eq!(missing.len(), 1);
+                assert!(missing[0].starts_with("asset buck2-"));
+            }
+            // Platforms without a Buck2 release fail earlier
+            Err(Error::UnsupportedPlatform { .. }) => {}
+            other => panic!("expected NotCached, got {other:?}"),
+        }
+        assert!(!dir.path().join("bin").exists());
+    }
+
+    #[test]
+    fn test_use_version_in_requires_installed_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = VersionStore::new(dir.path().join("store"));
+        let client =
+            Client::with_backend(Box::new(MockBackend::new())).with_version_store(store.clone());
+        assert!(matches!(
+            client.use_version_in(Tool::Bazel, "7.4.1", dir.path()),
+            Err(Error::VersionNotFound { .. })
+        ));
+
+        let version_dir = store.version_dir(Tool::Bazel, "7.4.1");
+        std::fs::create_dir_all(&version_dir).unwrap();
+        std::fs::write(tools::binary_path(Tool::Bazel, &version_dir), b"bazel").unwrap();
+        assert_eq!(client.installed_versions(Tool::Bazel).unwrap(), ["7.4.1"]);
+
+        let file = client
+            .use_version_in(Tool::Bazel, "7.4.1", dir.path())
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(file).unwrap(), "bazel 7.4.1\n");
+    }
+
     #[test]
     fn test_tool_display() {
         assert_eq!(Tool::Buck2.name(), "buck2");
@@ -253,5 +1252,7 @@ mod tests {
         let tools = Tool::all();
         assert!(!tools.is_empty());
         assert!(tools.contains(&Tool::Buck2));
+        assert!(tools.contains(&Tool::Bazel));
+        assert!(tools.contains(&Tool::Buildifier));
     }
 }
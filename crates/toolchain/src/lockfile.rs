@@ -0,0 +1,228 @@
+//! Lockfiles pinning exact tool binaries.
+//!
+//! A lockfile records, per tool and platform, the release that was resolved,
+//! where its asset was downloaded from, and the asset's SHA-256 digest.
+//! [`Client::install_from_lockfile`](crate::Client::install_from_lockfile)
+//! installs exactly those assets and refuses anything with another digest,
+//! so machines bootstrapped from the same lockfile get identical binaries,
+//! even when a rolling tag like Buck2's `latest` has moved on.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "tools": [
+//!     {
+//!       "tool": "bazel",
+//!       "version": "7.4.1",
+//!       "published_at": "2024-11-05T00:00:00Z",
+//!       "platform": "x86_64-unknown-linux-gnu",
+//!       "asset": "bazel-7.4.1-linux-x86_64",
+//!       "url": "https://github.com/bazelbuild/bazel/releases/download/7.4.1/bazel-7.4.1-linux-x86_64",
+//!       "sha256": "c97f02133adce63f0c28678ac1f21d65fa8255c80429b588aeeba8a1fac6202b"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Entries for other platforms are kept when a lockfile is rewritten, so one
+//! lockfile can serve machines on several platforms.
+
+use crate::error::{Error, Result};
+use crate::types::{Release, ReleaseAsset, Tool};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Current lockfile format version.
+pub const LOCKFILE_VERSION: u32 = 1;
+
+/// Tool binaries pinned by digest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    /// Format version.
+    pub version: u32,
+    /// Pinned tools, sorted by tool and platform.
+    #[serde(default)]
+    pub tools: Vec<LockedTool>,
+}
+
+/// A tool binary pinned for one platform.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedTool {
+    /// The tool.
+    pub tool: Tool,
+    /// Release tag.
+    pub version: String,
+    /// When the release was published.
+    #[serde(default)]
+    pub published_at: String,
+    /// Platform triple the asset is for.
+    pub platform: String,
+    /// Release asset name.
+    pub asset: String,
+    /// Where the asset was downloaded from.
+    pub url: String,
+    /// SHA-256 digest of the asset.
+    pub sha256: String,
+}
+
+impl LockedTool {
+    /// The pinned release, holding only the pinned asset.
+    #[must_use]
+    pub fn release(&self) -> Release {
+        Release {
+            tag: self.version.clone(),
+            name: self.version.clone(),
+            prerelease: false,
+            published_at: self.published_at.clone(),
+            notes: String::new(),
+            assets: vec![ReleaseAsset {
+                name: self.asset.clone(),
+                download_url: self.url.clone(),
+                size: 0,
+            }],
+        }
+    }
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCKFILE_VERSION,
+            tools: Vec::new(),
+        }
+    }
+}
+
+impl Lockfile {
+    /// Load a lockfile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or parsed, or was written
+    /// by a newer format version.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|e| Error::io(path, e))?;
+        let lockfile: Self = serde_json::from_slice(&bytes)?;
+        if lockfile.version > LOCKFILE_VERSION {
+            return Err(Error::Other(format!(
+                "{} uses lockfile version {}, this version supports up to {LOCKFILE_VERSION}",
+                path.display(),
+                lockfile.version
+            )));
+        }
+        Ok(lockfile)
+    }
+
+    /// Write the lockfile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        fs::write(path, json).map_err(|e| Error::io(path, e))
+    }
+
+    /// The entry pinning `tool` on `platform`, if any.
+    #[must_use]
+    pub fn find(&self, tool: Tool, platform: &str) -> Option<&LockedTool> {
+        self.tools
+            .iter()
+            .find(|locked| locked.tool == tool && locked.platform == platform)
+    }
+
+    /// Add an entry, replacing the one for the same tool and platform.
+    pub fn insert(&mut self, entry: LockedTool) {
+        self.tools
+            .retain(|locked| !(locked.tool == entry.tool && locked.platform == entry.platform));
+        self.tools.push(entry);
+        self.tools
+            .sort_by(|a, b| (a.tool.name(), &a.platform).cmp(&(b.tool.name(), &b.platform)));
+    }
+
+    /// Tools pinned for any platform, in lockfile order.
+    #[must_use]
+    pub fn pinned_tools(&self) -> Vec<Tool> {
+        let mut tools: Vec<Tool> = Vec::new();
+        for locked in &self.tools {
+            if !tools.contains(&locked.tool) {
+                tools.push(locked.tool);
+            }
+        }
+        tools
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locked(tool: Tool, platform: &str, version: &str) -> LockedTool {
+        LockedTool {
+            tool,
+            version: version.to_string(),
+            published_at: "2024-11-05T00:00:00Z".to_string(),
+            platform: platform.to_string(),
+            asset: format!("{tool}-{version}"),
+            url: format!("https://example.com/{tool}-{version}"),
+            sha256: "0".repeat(64),
+        }
+    }
+
+    #[test]
+    fn test_insert_replaces_same_platform() {
+        let mut lockfile = Lockfile::default();
+        lockfile.insert(locked(Tool::Bazel, "x86_64-unknown-linux-gnu", "7.3.0"));
+        lockfile.insert(locked(Tool::Bazel, "aarch64-apple-darwin", "7.3.0"));
+        lockfile.insert(locked(Tool::Buck2, "x86_64-unknown-linux-gnu", "latest"));
+        lockfile.insert(locked(Tool::Bazel, "x86_64-unknown-linux-gnu", "7.4.1"));
+
+        assert_eq!(lockfile.tools.len(), 3);
+        assert_eq!(
+            lockfile
+                .find(Tool::Bazel, "x86_64-unknown-linux-gnu")
+                .unwrap()
+                .version,
+            "7.4.1"
+        );
+        assert_eq!(
+            lockfile
+                .find(Tool::Bazel, "aarch64-apple-darwin")
+                .unwrap()
+                .version,
+            "7.3.0"
+        );
+        assert!(
+            lockfile
+                .find(Tool::Buildifier, "aarch64-apple-darwin")
+                .is_none()
+        );
+        assert_eq!(lockfile.pinned_tools(), [Tool::Bazel, Tool::Buck2]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("toolchain.lock");
+
+        let mut lockfile = Lockfile::default();
+        lockfile.insert(locked(
+            Tool::Buildifier,
+            "x86_64-unknown-linux-gnu",
+            "v8.0.0",
+        ));
+        lockfile.save(&path).unwrap();
+        assert_eq!(Lockfile::load(&path).unwrap(), lockfile);
+
+        let release = lockfile.tools[0].release();
+        assert_eq!(release.tag, "v8.0.0");
+        assert_eq!(release.assets[0].download_url, lockfile.tools[0].url);
+
+        fs::write(&path, r#"{ "version": 2, "tools": [] }"#).unwrap();
+        assert!(Lockfile::load(&path).is_err());
+    }
+}
@@ -0,0 +1,188 @@
+//! Download progress reporting.
+//!
+//! Build tool binaries are large, so installs can report how a download is
+//! going: set a callback with [`InstallOptions::on_progress`] and it gets
+//! a [`DownloadProgress`] as the asset's bytes arrive.
+//!
+//! [`InstallOptions::on_progress`]: crate::InstallOptions::on_progress
+
+use crate::error::{Error, Result};
+use crate::types::Tool;
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often a download reports progress, at most.
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// State of a download in progress.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadProgress {
+    /// The tool being downloaded.
+    pub tool: Tool,
+    /// Bytes downloaded so far.
+    pub downloaded: u64,
+    /// Size of the download, if the server reported it.
+    pub total: Option<u64>,
+    /// Time since the download started.
+    pub elapsed: Duration,
+}
+
+impl DownloadProgress {
+    /// Average download speed so far, in bytes per second.
+    #[must_use]
+    pub fn bytes_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.downloaded as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Fraction of the download done, between 0 and 1, if the size is known.
+    #[must_use]
+    pub fn fraction(&self) -> Option<f64> {
+        self.total
+            .filter(|&total| total > 0)
+            .map(|total| (self.downloaded as f64 / total as f64).min(1.0))
+    }
+
+    /// Whether the download is complete.
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.total.is_some_and(|total| self.downloaded >= total)
+    }
+}
+
+/// Callback receiving [`DownloadProgress`] updates.
+///
+/// Called at most every 100 ms while a download runs, and once when it
+/// completes.
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(&DownloadProgress) + Send + Sync>);
+
+impl ProgressCallback {
+    /// Wrap a callback.
+    pub fn new(callback: impl Fn(&DownloadProgress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    /// Report progress.
+    pub fn report(&self, progress: &DownloadProgress) {
+        (self.0)(progress);
+    }
+
+    /// Report a download that completed without intermediate updates,
+    /// e.g. one served from the cache.
+    pub fn report_done(&self, tool: Tool, size: usize) {
+        let size = size as u64;
+        self.report(&DownloadProgress {
+            tool,
+            downloaded: size,
+            total: Some(size),
+            elapsed: Duration::ZERO,
+        });
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+/// Read a download to the end, reporting progress as it goes.
+///
+/// # Errors
+///
+/// Returns `Error::DownloadFailed` if reading fails.
+pub(crate) fn read_to_vec(
+    tool: Tool,
+    mut reader: impl Read,
+    total: Option<u64>,
+    progress: &ProgressCallback,
+) -> Result<Vec<u8>> {
+    let start = Instant::now();
+    let mut last_report: Option<Instant> = None;
+    let mut bytes = Vec::with_capacity(usize::try_from(total.unwrap_or(0)).unwrap_or(0));
+    let mut buf = [0; 64 * 1024];
+
+    loop {
+        let n = reader.read(&mut buf).map_err(|e| Error::DownloadFailed {
+            tool: tool.to_string(),
+            message: e.to_string(),
+        })?;
+        bytes.extend_from_slice(&buf[..n]);
+
+        let done = n == 0;
+        if done || last_report.is_none_or(|last| last.elapsed() >= REPORT_INTERVAL) {
+            last_report = Some(Instant::now());
+            progress.report(&DownloadProgress {
+                tool,
+                downloaded: bytes.len() as u64,
+                // Once done, the size is known even if it wasn't reported
+                total: if done {
+                    Some(bytes.len() as u64)
+                } else {
+                    total
+                },
+                elapsed: start.elapsed(),
+            });
+        }
+        if done {
+            return Ok(bytes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_progress_math() {
+        let progress = DownloadProgress {
+            tool: Tool::Bazel,
+            downloaded: 25,
+            total: Some(100),
+            elapsed: Duration::from_secs(5),
+        };
+        assert_eq!(progress.fraction(), Some(0.25));
+        assert!((progress.bytes_per_second() - 5.0).abs() < f64::EPSILON);
+        assert!(!progress.is_done());
+
+        let unknown = DownloadProgress {
+            total: None,
+            elapsed: Duration::ZERO,
+            ..progress
+        };
+        assert_eq!(unknown.fraction(), None);
+        assert!(unknown.bytes_per_second().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_read_to_vec_reports_completion() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let callback = {
+            let reports = Arc::clone(&reports);
+            ProgressCallback::new(move |p| reports.lock().unwrap().push(p.clone()))
+        };
+
+        let data = vec![7u8; 200 * 1024];
+        let bytes = read_to_vec(Tool::Bazel, data.as_slice(), None, &callback).unwrap();
+        assert_eq!(bytes, data);
+
+        let reports = reports.lock().unwrap();
+        let last = reports.last().unwrap();
+        assert!(last.is_done());
+        assert_eq!(last.downloaded, data.len() as u64);
+        assert!(
+            reports
+                .windows(2)
+                .all(|w| w[0].downloaded <= w[1].downloaded)
+        );
+    }
+}
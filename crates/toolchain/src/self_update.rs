@@ -0,0 +1,366 @@
+//! Updating the bossa binary itself.
+//!
+//! bossa is released on GitHub like the tools it installs, as one
+//! `.tar.gz` (or `.zip` on Windows) per platform with a `.sha256` file next
+//! to it. [`SelfUpdate`] finds the newest release, downloads the archive for
+//! the running platform, checks it against the published digest, and swaps
+//! it in for the running executable.
+//!
+//! ```no_run
+//! use toolchain::self_update::SelfUpdate;
+//!
+//! let updater = SelfUpdate::new(env!("CARGO_PKG_VERSION")).unwrap();
+//! if let Some(update) = updater.check().unwrap() {
+//!     updater.apply(&update).unwrap();
+//!     println!("updated to {}", update.version);
+//! }
+//! ```
+
+use crate::archive;
+use crate::backend::github::GitHubBackend;
+use crate::checksum;
+use crate::error::{Error, Result};
+use crate::platform;
+use crate::types::{Release, ReleaseAsset};
+use crate::versions::is_newer;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// GitHub repository bossa is released from.
+pub const REPO: &str = "albertocavalcante/bossa";
+
+/// Name of the binary inside release archives, without extension.
+const BINARY_NAME: &str = "bossa";
+
+/// A newer bossa release for this platform.
+#[derive(Debug, Clone)]
+pub struct AvailableUpdate {
+    /// Version of the release, without a leading `v`.
+    pub version: String,
+    /// The release.
+    pub release: Release,
+    /// Archive for this platform.
+    pub asset: ReleaseAsset,
+    /// Checksum file for the archive.
+    pub checksum: ReleaseAsset,
+}
+
+/// Checks for and installs new bossa releases.
+pub struct SelfUpdate {
+    backend: GitHubBackend,
+    current_version: String,
+    platform: &'static str,
+    executable: PathBuf,
+}
+
+impl SelfUpdate {
+    /// Update the running executable, currently at `current_version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedPlatform` if bossa isn't released for this
+    /// platform, and `Error::Io` if the running executable can't be located.
+    pub fn new(current_version: impl Into<String>) -> Result<Self> {
+        let detected = platform::detect()?;
+        let musl = platform::is_musl();
+        let platform =
+            release_platform(&detected.triple, musl).ok_or_else(|| Error::UnsupportedPlatform {
+                os: detected.os.clone(),
+                arch: detected.arch.clone(),
+            })?;
+        let executable = std::env::current_exe()
+            .and_then(fs::canonicalize)
+            .map_err(|e| Error::io("current executable", e))?;
+
+        Ok(Self {
+            backend: GitHubBackend::new(),
+            current_version: current_version.into(),
+            platform,
+            executable,
+        })
+    }
+
+    /// Use a specific backend, e.g. one with a proxy or token.
+    #[must_use]
+    pub fn with_backend(mut self, backend: GitHubBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// The executable that [`apply`](Self::apply) replaces.
+    #[must_use]
+    pub fn executable(&self) -> &Path {
+        &self.executable
+    }
+
+    /// Look for a release newer than the running version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the latest release can't be fetched, or if it
+    /// has no archive or checksum for this platform.
+    pub fn check(&self) -> Result<Option<AvailableUpdate>> {
+        let release = self.backend.fetch_latest_release_of(REPO)?;
+        let version = release.tag.trim_start_matches('v').to_string();
+        if !is_newer(&version, &self.current_version) {
+            return Ok(None);
+        }
+
+        let (asset, checksum) = find_assets(&release, self.platform)?;
+        Ok(Some(AvailableUpdate {
+            version,
+            asset: asset.clone(),
+            checksum: checksum.clone(),
+            release,
+        }))
+    }
+
+    /// Download `update`, verify it, and replace the running executable.
+    ///
+    /// Returns the path of the replaced executable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ChecksumMismatch` if the download doesn't match its
+    /// published digest, and other errors if it can't be downloaded,
+    /// unpacked, or written. The running executable is left in place on
+    /// any error.
+    pub fn apply(&self, update: &AvailableUpdate) -> Result<PathBuf> {
+        let bytes = self
+            .backend
+            .download_url(BINARY_NAME, &update.asset.download_url)?;
+        let checksum_file = self
+            .backend
+            .download_url(BINARY_NAME, &update.checksum.download_url)?;
+
+        let binary = unpack_verified(&bytes, &checksum_file)?;
+        replace_executable(&self.executable, &binary)?;
+        Ok(self.executable.clone())
+    }
+}
+
+/// Platform name in bossa's release assets, e.g. `linux-aarch64`.
+///
+/// Matches the names `tools/scripts/install.sh` downloads.
+#[must_use]
+pub fn release_platform(triple: &str, musl: bool) -> Option<&'static str> {
+    match triple {
+        "x86_64-unknown-linux-gnu" if musl => Some("linux-amd64-musl"),
+        "x86_64-unknown-linux-gnu" | "x86_64-unknown-linux-musl" => Some("linux-amd64"),
+        "aarch64-unknown-linux-gnu" => Some("linux-aarch64"),
+        "x86_64-apple-darwin" => Some("darwin-amd64"),
+        "aarch64-apple-darwin" => Some("darwin-arm64"),
+        "x86_64-pc-windows-msvc" => Some("windows-amd64"),
+        _ => None,
+    }
+}
+
+/// The archive for `platform` in `release`, and its checksum file.
+fn find_assets<'a>(
+    release: &'a Release,
+    platform: &str,
+) -> Result<(&'a ReleaseAsset, &'a ReleaseAsset)> {
+    let asset = release
+        .assets
+        .iter()
+        .find(|asset| {
+            asset.name.ends_with(&format!("-{platform}.tar.gz"))
+                || asset.name.ends_with(&format!("-{platform}.zip"))
+        })
+        .ok_or_else(|| Error::VersionNotFound {
+            tool: BINARY_NAME.to_string(),
+            version: format!("{} for {platform}", release.tag),
+        })?;
+    let checksum = release
+        .find_asset(&format!("{}.sha256", asset.name))
+        .ok_or_else(|| {
+            Error::InvalidResponse(format!("{} has no published checksum", asset.name))
+        })?;
+    Ok((asset, checksum))
+}
+
+/// Check a downloaded archive against its checksum file and get the binary
+/// out of it.
+fn unpack_verified(bytes: &[u8], checksum_file: &[u8]) -> Result<Vec<u8>> {
+    let expected = checksum::parse_checksum_file(&String::from_utf8_lossy(checksum_file))
+        .ok_or_else(|| {
+            Error::InvalidResponse(format!("malformed checksum file for {BINARY_NAME}"))
+        })?;
+    let actual = checksum::sha256_hex(bytes);
+    if actual != expected {
+        return Err(Error::ChecksumMismatch {
+            tool: BINARY_NAME.to_string(),
+            expected,
+            actual,
+        });
+    }
+
+    let binary_name = format!("{BINARY_NAME}{}", platform::executable_extension());
+    archive::unpack(bytes, &binary_name)
+}
+
+/// Atomically replace the executable at `path` with `binary`.
+///
+/// The new binary is written next to the old one and renamed over it, so
+/// `path` always holds a complete executable. Windows can't replace a
+/// running executable, so there the old one is moved aside to `.old` first.
+///
+/// # Errors
+///
+/// Returns `Error::PermissionDenied` if the directory isn't writable, and
+/// `Error::Io` for other failures.
+pub fn replace_executable(path: &Path, binary: &[u8]) -> Result<()> {
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| BINARY_NAME.to_string());
+    let staged = dir.join(format!(".{file_name}.new-{}", std::process::id()));
+
+    let io_error = |path: &Path, e: std::io::Error| {
+        if e.kind() == std::io::ErrorKind::PermissionDenied {
+            Error::PermissionDenied {
+                path: path.to_path_buf(),
+            }
+        } else {
+            Error::io(path, e)
+        }
+    };
+
+    fs::write(&staged, binary).map_err(|e| io_error(&staged, e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = fs::set_permissions(&staged, fs::Permissions::from_mode(0o755)) {
+            let _ = fs::remove_file(&staged);
+            return Err(io_error(&staged, e));
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        let old = path.with_extension("old");
+        let _ = fs::remove_file(&old);
+        if let Err(e) = fs::rename(path, &old) {
+            let _ = fs::remove_file(&staged);
+            return Err(io_error(path, e));
+        }
+    }
+
+    fs::rename(&staged, path).map_err(|e| {
+        let _ = fs::remove_file(&staged);
+        io_error(path, e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> ReleaseAsset {
+        ReleaseAsset {
+            name: name.to_string(),
+            download_url: format!("https://example.com/{name}"),
+            size: 0,
+        }
+    }
+
+    fn release(names: &[&str]) -> Release {
+        Release {
+            tag: "v0.2.0".to_string(),
+            name: "v0.2.0".to_string(),
+            prerelease: false,
+            published_at: String::new(),
+            notes: String::new(),
+            assets: names.iter().map(|name| asset(name)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_release_platform() {
+        assert_eq!(
+            release_platform("aarch64-unknown-linux-gnu", false),
+            Some("linux-aarch64")
+        );
+        assert_eq!(
+            release_platform("x86_64-unknown-linux-gnu", true),
+            Some("linux-amd64-musl")
+        );
+        assert_eq!(
+            release_platform("aarch64-apple-darwin", false),
+            Some("darwin-arm64")
+        );
+        assert_eq!(release_platform("aarch64-pc-windows-msvc", false), None);
+    }
+
+    #[test]
+    fn test_find_assets() {
+        let release = release(&[
+            "bossa-linux-amd64-musl.tar.gz",
+            "bossa-linux-amd64-musl.tar.gz.sha256",
+            "bossa-linux-amd64.tar.gz",
+            "bossa-linux-amd64.tar.gz.sha256",
+            "bossa-windows-amd64.zip",
+        ]);
+
+        let (asset, checksum) = find_assets(&release, "linux-amd64").unwrap();
+        assert_eq!(asset.name, "bossa-linux-amd64.tar.gz");
+        assert_eq!(checksum.name, "bossa-linux-amd64.tar.gz.sha256");
+
+        assert!(matches!(
+            find_assets(&release, "windows-amd64"),
+            Err(Error::InvalidResponse(_))
+        ));
+        assert!(matches!(
+            find_assets(&release, "darwin-arm64"),
+            Err(Error::VersionNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unpack_verified() {
+        let binary_name = format!("{BINARY_NAME}{}", platform::executable_extension());
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, &binary_name, &b"bossa"[..])
+            .unwrap();
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        let checksum_file = format!("{}  bossa.tar.gz\n", checksum::sha256_hex(&archive));
+        assert_eq!(
+            unpack_verified(&archive, checksum_file.as_bytes()).unwrap(),
+            b"bossa"
+        );
+
+        let wrong = "0".repeat(64);
+        assert!(matches!(
+            unpack_verified(&archive, wrong.as_bytes()),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_replace_executable() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bossa");
+        fs::write(&path, b"old").unwrap();
+
+        replace_executable(&path, b"new").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        // Nothing staged is left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+}
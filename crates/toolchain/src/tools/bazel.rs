@@ -0,0 +1,183 @@
+//! Bazel-specific installation logic.
+//!
+//! This module provides the [`BazelInstaller`] for installing Google's Bazel
+//! build system from GitHub releases.
+//!
+//! Bazel releases are distributed as uncompressed binaries named after the
+//! version and platform (e.g. `bazel-7.4.1-darwin-arm64`), each with a
+//! `.sha256` checksum file next to it.
+
+use crate::error::{Error, Result};
+use crate::tools::{self, ToolInstaller};
+use crate::types::{InstallOptions, InstallResult, Platform, Release, Tool};
+use std::path::{Path, PathBuf};
+
+/// Bazel installer.
+///
+/// # Example
+///
+/// ```no_run
+/// use toolchain::tools::bazel::BazelInstaller;
+/// use toolchain::tools::ToolInstaller;
+///
+/// let installer = BazelInstaller::new();
+/// println!("Installing to: {:?}", installer.default_install_dir().unwrap());
+/// ```
+pub struct BazelInstaller;
+
+impl BazelInstaller {
+    /// Create a new Bazel installer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for BazelInstaller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToolInstaller for BazelInstaller {
+    fn tool(&self) -> Tool {
+        Tool::Bazel
+    }
+
+    fn asset_name(&self, release: &Release, platform: &Platform) -> Result<String> {
+        let os = match platform.os.as_str() {
+            "macos" => "darwin",
+            "linux" => "linux",
+            "windows" => "windows",
+            _ => return Err(unsupported(platform)),
+        };
+        let arch = match platform.arch.as_str() {
+            "x86_64" => "x86_64",
+            "aarch64" => "arm64",
+            _ => return Err(unsupported(platform)),
+        };
+        let extension = if platform.is_windows() { ".exe" } else { "" };
+        Ok(format!("bazel-{}-{os}-{arch}{extension}", release.tag))
+    }
+
+    fn checksum_asset_name(&self, asset_name: &str) -> Option<String> {
+        Some(format!("{asset_name}.sha256"))
+    }
+
+    fn install(
+        &self,
+        bytes: &[u8],
+        _platform: &Platform,
+        options: &InstallOptions,
+    ) -> Result<InstallResult> {
+        tools::install_binary(self, bytes, options)
+    }
+
+    fn is_installed(&self) -> Result<bool> {
+        Ok(tools::find_in_path(Tool::Bazel).is_some())
+    }
+
+    fn installed_version(&self) -> Result<Option<String>> {
+        if let Some(path) = tools::find_in_path(Tool::Bazel) {
+            self.binary_version(&path)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn default_install_dir(&self) -> Result<PathBuf> {
+        Ok(tools::default_install_dir())
+    }
+
+    fn verify(&self, path: &Path) -> Result<()> {
+        tools::verify_runs(Tool::Bazel, path)
+    }
+
+    fn binary_version(&self, path: &Path) -> Result<Option<String>> {
+        Ok(tools::version_output(Tool::Bazel, path)?.and_then(|stdout| parse_version(&stdout)))
+    }
+}
+
+fn unsupported(platform: &Platform) -> Error {
+    Error::UnsupportedPlatform {
+        os: platform.os.clone(),
+        arch: format!("{} (bazel)", platform.arch),
+    }
+}
+
+/// Parse `bazel --version` output: `bazel 7.4.1`.
+fn parse_version(stdout: &str) -> Option<String> {
+    let mut words = stdout.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some("bazel"), Some(version)) => Some(version.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str) -> Release {
+        Release {
+            tag: tag.to_string(),
+            name: tag.to_string(),
+            prerelease: false,
+            published_at: String::new(),
+            notes: String::new(),
+            assets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_asset_name() {
+        let installer = BazelInstaller::new();
+        let release = release("7.4.1");
+
+        let mac = Platform::new("macos", "aarch64", "aarch64-apple-darwin");
+        assert_eq!(
+            installer.asset_name(&release, &mac).unwrap(),
+            "bazel-7.4.1-darwin-arm64"
+        );
+
+        let linux = Platform::new("linux", "x86_64", "x86_64-unknown-linux-gnu");
+        assert_eq!(
+            installer.asset_name(&release, &linux).unwrap(),
+            "bazel-7.4.1-linux-x86_64"
+        );
+
+        let windows = Platform::new("windows", "x86_64", "x86_64-pc-windows-msvc");
+        assert_eq!(
+            installer.asset_name(&release, &windows).unwrap(),
+            "bazel-7.4.1-windows-x86_64.exe"
+        );
+
+        let riscv = Platform::new("linux", "riscv64", "riscv64gc-unknown-linux-gnu");
+        assert!(matches!(
+            installer.asset_name(&release, &riscv),
+            Err(Error::UnsupportedPlatform { .. })
+        ));
+    }
+
+    #[test]
+    fn test_checksum_asset_name() {
+        let installer = BazelInstaller::new();
+        assert_eq!(
+            installer
+                .checksum_asset_name("bazel-7.4.1-linux-x86_64")
+                .as_deref(),
+            Some("bazel-7.4.1-linux-x86_64.sha256")
+        );
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("bazel 7.4.1\n").as_deref(), Some("7.4.1"));
+        assert_eq!(
+            parse_version("bazel no_version\n").as_deref(),
+            Some("no_version")
+        );
+        assert!(parse_version("").is_none());
+        assert!(parse_version("buck2 2024-01-15").is_none());
+    }
+}
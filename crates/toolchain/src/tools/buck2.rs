@@ -5,15 +5,18 @@
 //!
 //! Buck2 releases are distributed as zstd-compressed binaries for each
 //! supported platform.
+//!
+//! It also provides [`init_project`] for bootstrapping a Buck2 project:
+//! vendoring the prelude at the commit matching the installed binary and
+//! writing a minimal `.buckconfig`.
 
+use crate::archive::{self, ArchiveFormat};
+use crate::backend::Backend;
+use crate::backend::github::GitHubBackend;
 use crate::error::{Error, Result};
-use crate::platform;
-use crate::tools::ToolInstaller;
-use crate::types::{InstallOptions, InstallResult, Platform, Tool};
+use crate::tools::{self, ToolInstaller};
+use crate::types::{InstallOptions, InstallResult, Platform, Release, Tool};
 use std::fs;
-use std::io::Cursor;
-#[cfg(unix)]
-use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -40,22 +43,9 @@ impl Buck2Installer {
         Self
     }
 
-    /// Decompress a zstd-compressed binary.
-    fn decompress(&self, compressed: &[u8]) -> Result<Vec<u8>> {
-        let cursor = Cursor::new(compressed);
-        let mut decoder =
-            zstd::Decoder::new(cursor).map_err(|e| Error::DecompressionFailed(e.to_string()))?;
-
-        let mut decompressed = Vec::new();
-        std::io::copy(&mut decoder, &mut decompressed)
-            .map_err(|e| Error::DecompressionFailed(e.to_string()))?;
-
-        Ok(decompressed)
-    }
-
     /// Find buck2 in PATH.
     fn find_in_path(&self) -> Option<PathBuf> {
-        which::which("buck2").ok()
+        tools::find_in_path(Tool::Buck2)
     }
 }
 
@@ -70,73 +60,19 @@ impl ToolInstaller for Buck2Installer {
         Tool::Buck2
     }
 
+    fn asset_name(&self, _release: &Release, platform: &Platform) -> Result<String> {
+        Ok(format!("buck2-{}.zst", platform.triple))
+    }
+
     fn install(
         &self,
         compressed_bytes: &[u8],
         _platform: &Platform,
         options: &InstallOptions,
     ) -> Result<InstallResult> {
-        // Determine install directory
-        let install_dir = options
-            .install_dir
-            .clone()
-            .or_else(|| self.default_install_dir().ok())
-            .ok_or_else(|| Error::Other("cannot determine install directory".to_string()))?;
-
-        // Ensure directory exists
-        fs::create_dir_all(&install_dir).map_err(|e| Error::io(&install_dir, e))?;
-
-        // Determine binary path
-        let binary_name = format!("buck2{}", platform::executable_extension());
-        let binary_path = install_dir.join(&binary_name);
-
-        // Check for existing installation
-        let was_upgrade = binary_path.exists();
-        let previous_version = if was_upgrade {
-            self.installed_version().ok().flatten()
-        } else {
-            None
-        };
-
-        // Check if we should overwrite
-        if was_upgrade && !options.force {
-            return Err(Error::Other(format!(
-                "buck2 already installed at {}. Use --force to overwrite.",
-                binary_path.display()
-            )));
-        }
-
-        // Decompress
-        let decompressed = self.decompress(compressed_bytes)?;
-
-        // Write binary
-        fs::write(&binary_path, &decompressed).map_err(|e| Error::io(&binary_path, e))?;
-
-        // Make executable (Unix only)
-        #[cfg(unix)]
-        {
-            let mut perms = fs::metadata(&binary_path)
-                .map_err(|e| Error::io(&binary_path, e))?
-                .permissions();
-            perms.set_mode(0o755);
-            fs::set_permissions(&binary_path, perms).map_err(|e| Error::io(&binary_path, e))?;
-        }
-
-        // Verify installation
-        self.verify(&binary_path)?;
-
-        // Get installed version
-        let version = self
-            .get_version_from_binary(&binary_path)?
-            .unwrap_or_else(|| options.version.clone().unwrap_or("latest".to_string()));
-
-        Ok(InstallResult {
-            tool: Tool::Buck2,
-            version,
-            path: binary_path,
-            was_upgrade,
-            previous_version,
-        })
+        // Always zstd, so don't let anything else pass for a binary
+        let decompressed = archive::extract(ArchiveFormat::Zstd, compressed_bytes, "buck2")?;
+        tools::install_binary(self, &decompressed, options)
     }
 
     fn is_installed(&self) -> Result<bool> {
@@ -145,53 +81,24 @@ impl ToolInstaller for Buck2Installer {
 
     fn installed_version(&self) -> Result<Option<String>> {
         if let Some(path) = self.find_in_path() {
-            self.get_version_from_binary(&path)
+            self.binary_version(&path)
         } else {
             Ok(None)
         }
     }
 
     fn default_install_dir(&self) -> Result<PathBuf> {
-        // Prefer ~/.local/bin (XDG-compliant)
-        if let Some(home) = dirs::home_dir() {
-            let local_bin = home.join(".local").join("bin");
-            return Ok(local_bin);
-        }
-
-        // Fallback to /usr/local/bin
-        Ok(PathBuf::from("/usr/local/bin"))
+        Ok(tools::default_install_dir())
     }
 
     fn verify(&self, path: &Path) -> Result<()> {
-        let output = Command::new(path)
-            .arg("--version")
-            .output()
-            .map_err(|e| Error::Other(format!("failed to execute buck2: {e}")))?;
-
-        if !output.status.success() {
-            return Err(Error::Other(format!(
-                "buck2 verification failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            )));
-        }
-
-        Ok(())
+        tools::verify_runs(Tool::Buck2, path)
     }
-}
 
-impl Buck2Installer {
-    /// Get version from a buck2 binary.
-    fn get_version_from_binary(&self, path: &Path) -> Result<Option<String>> {
-        let output = Command::new(path)
-            .arg("--version")
-            .output()
-            .map_err(|e| Error::Other(format!("failed to execute buck2: {e}")))?;
-
-        if !output.status.success() {
+    fn binary_version(&self, path: &Path) -> Result<Option<String>> {
+        let Some(stdout) = tools::version_output(Tool::Buck2, path)? else {
             return Ok(None);
-        }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        };
         // Output format: "buck2 <version> <hash> <date>"
         // Example: "buck2 2024-01-15 abc1234 ..."
         let version = stdout
@@ -203,6 +110,266 @@ impl Buck2Installer {
     }
 }
 
+// =============================================================================
+// Project bootstrap
+// =============================================================================
+
+/// Git repository hosting the Buck2 prelude.
+pub const PRELUDE_REPO: &str = "https://github.com/facebook/buck2-prelude.git";
+
+/// Release asset containing the prelude commit a Buck2 release was built against.
+const PRELUDE_HASH_ASSET: &str = "prelude_hash";
+
+/// Directory (relative to the project root) where the prelude is vendored.
+const PRELUDE_DIR: &str = "prelude";
+
+/// Minimal `.buckconfig` for a project using a vendored prelude.
+const BUCKCONFIG: &str = r"[cells]
+root = .
+prelude = prelude
+toolchains = toolchains
+none = none
+
+[cell_aliases]
+config = prelude
+ovr_config = prelude
+fbcode = none
+fbsource = none
+fbcode_macros = none
+buck = none
+
+[parser]
+target_platform_detector_spec = target:root//...->prelude//platforms:default
+";
+
+/// Default toolchains cell using the prelude's system toolchains.
+const TOOLCHAINS_BUCK: &str = r#"load("@prelude//toolchains:demo.bzl", "system_demo_toolchains")
+
+system_demo_toolchains()
+"#;
+
+/// Options for bootstrapping a Buck2 project.
+///
+/// # Example
+///
+/// ```
+/// use toolchain::buck2::InitOptions;
+///
+/// let options = InitOptions::new().version("2024-01-15").force(true);
+/// assert_eq!(options.version, Some("2024-01-15".to_string()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InitOptions {
+    /// Buck2 release whose prelude should be vendored (None = installed version).
+    pub version: Option<String>,
+    /// Overwrite existing config files and non-git prelude directories.
+    pub force: bool,
+}
+
+impl InitOptions {
+    /// Create new init options with defaults.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the Buck2 release to match.
+    ///
+    /// Defaults to the version of the `buck2` binary found in PATH.
+    #[must_use]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Set whether to overwrite existing files.
+    #[must_use]
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+}
+
+/// Result of bootstrapping a Buck2 project.
+#[derive(Debug, Clone)]
+pub struct ProjectInit {
+    /// Project root directory.
+    pub root: PathBuf,
+    /// Buck2 release the prelude was matched against.
+    pub version: String,
+    /// Prelude commit that was vendored.
+    pub prelude_hash: String,
+    /// Whether the prelude checkout was changed (false if already in sync).
+    pub prelude_updated: bool,
+    /// Files that were written.
+    pub written: Vec<PathBuf>,
+    /// Existing files that were left untouched.
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Bootstrap a Buck2 project at `path` using the installed `buck2` version.
+///
+/// Vendors the prelude matching the installed binary into `<path>/prelude`
+/// and writes `.buckconfig`, `.buckroot`, and `toolchains/BUCK` if missing.
+/// Re-running after a Buck2 upgrade moves the prelude to the new commit.
+///
+/// Requires `git` in PATH and network access to GitHub.
+///
+/// # Example
+///
+/// ```no_run
+/// let init = toolchain::buck2::init_project("~/code/my-project").unwrap();
+/// println!("prelude @ {}", init.prelude_hash);
+/// ```
+pub fn init_project(path: impl AsRef<Path>) -> Result<ProjectInit> {
+    init_project_with(&GitHubBackend::new(), path, &InitOptions::default())
+}
+
+/// Bootstrap a Buck2 project using a specific backend and options.
+///
+/// See [`init_project`] for details.
+pub fn init_project_with(
+    backend: &dyn Backend,
+    path: impl AsRef<Path>,
+    options: &InitOptions,
+) -> Result<ProjectInit> {
+    let root = expand_home(path.as_ref());
+
+    let version = match &options.version {
+        Some(version) => version.clone(),
+        None => Buck2Installer::new()
+            .installed_version()?
+            .ok_or_else(|| Error::ToolNotFound("buck2".to_string()))?,
+    };
+
+    let prelude_hash = fetch_prelude_hash(backend, &version)?;
+
+    fs::create_dir_all(&root).map_err(|e| Error::io(&root, e))?;
+    let prelude_updated = vendor_prelude(&root.join(PRELUDE_DIR), &prelude_hash, options.force)?;
+    let (written, skipped) = write_project_files(&root, options.force)?;
+
+    Ok(ProjectInit {
+        root,
+        version,
+        prelude_hash,
+        prelude_updated,
+        written,
+        skipped,
+    })
+}
+
+/// Look up the prelude commit for a Buck2 release.
+fn fetch_prelude_hash(backend: &dyn Backend, version: &str) -> Result<String> {
+    let release = backend.fetch_release(Tool::Buck2, version)?;
+    let asset = release
+        .find_asset(PRELUDE_HASH_ASSET)
+        .ok_or_else(|| Error::DownloadFailed {
+            tool: Tool::Buck2.to_string(),
+            message: format!("release {version} has no {PRELUDE_HASH_ASSET} asset"),
+        })?;
+
+    let bytes = backend.download_release_asset(Tool::Buck2, asset)?;
+    parse_prelude_hash(&bytes)
+}
+
+/// Parse the contents of a `prelude_hash` asset into a commit hash.
+fn parse_prelude_hash(bytes: &[u8]) -> Result<String> {
+    let hash = String::from_utf8_lossy(bytes).trim().to_lowercase();
+
+    if hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(hash)
+    } else {
+        Err(Error::InvalidResponse(format!(
+            "invalid prelude hash: {hash:?}"
+        )))
+    }
+}
+
+/// Check out the prelude at `hash` into `dir`.
+///
+/// Returns `false` if the checkout was already at the requested commit.
+fn vendor_prelude(dir: &Path, hash: &str, force: bool) -> Result<bool> {
+    if dir.exists() {
+        if !dir.join(".git").exists() {
+            if !force {
+                return Err(Error::Other(format!(
+                    "{} exists and is not a git checkout. Use --force to replace it.",
+                    dir.display()
+                )));
+            }
+            fs::remove_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+        } else if run_git(dir, &["rev-parse", "HEAD"])?.trim() == hash {
+            return Ok(false);
+        }
+    }
+
+    if !dir.exists() {
+        fs::create_dir_all(dir).map_err(|e| Error::io(dir, e))?;
+        run_git(dir, &["init", "--quiet"])?;
+        run_git(dir, &["remote", "add", "origin", PRELUDE_REPO])?;
+    }
+
+    run_git(dir, &["fetch", "--quiet", "--depth", "1", "origin", hash])?;
+    run_git(dir, &["checkout", "--quiet", "--detach", "FETCH_HEAD"])?;
+
+    Ok(true)
+}
+
+/// Write the minimal project files, skipping existing ones unless `force`.
+fn write_project_files(root: &Path, force: bool) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let files = [
+        (root.join(".buckconfig"), BUCKCONFIG),
+        (root.join(".buckroot"), ""),
+        (root.join("toolchains").join("BUCK"), TOOLCHAINS_BUCK),
+    ];
+
+    let mut written = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (path, contents) in files {
+        if path.exists() && !force {
+            skipped.push(path);
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| Error::io(parent, e))?;
+        }
+        fs::write(&path, contents).map_err(|e| Error::io(&path, e))?;
+        written.push(path);
+    }
+
+    Ok((written, skipped))
+}
+
+/// Run a git command in `dir`, returning stdout.
+fn run_git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| Error::Other(format!("failed to execute git: {e}")))?;
+
+    if !output.status.success() {
+        return Err(Error::Other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Expand a leading `~/` to the home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~")
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(rest);
+    }
+    path.to_path_buf()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,26 +407,77 @@ mod tests {
     }
 
     #[test]
-    fn test_decompress_invalid_data() {
+    fn test_asset_name() {
         let installer = Buck2Installer::new();
-        let invalid_data = vec![0, 1, 2, 3, 4, 5];
+        let release = crate::backend::MockBackend::with_buck2_releases()
+            .fetch_release(Tool::Buck2, "2024-01-15")
+            .unwrap();
+        let platform = Platform::new("linux", "x86_64", "x86_64-unknown-linux-gnu");
+        assert_eq!(
+            installer.asset_name(&release, &platform).unwrap(),
+            "buck2-x86_64-unknown-linux-gnu.zst"
+        );
+    }
 
-        let result = installer.decompress(&invalid_data);
-        assert!(result.is_err());
+    #[test]
+    fn test_install_rejects_uncompressed_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let installer = Buck2Installer::new();
+        let platform = Platform::new("linux", "x86_64", "x86_64-unknown-linux-gnu");
+        let options = InstallOptions::new().install_dir(dir.path());
 
-        if let Err(Error::DecompressionFailed(msg)) = result {
-            assert!(!msg.is_empty());
-        } else {
-            panic!("Expected DecompressionFailed error");
-        }
+        let result = installer.install(&[0, 1, 2, 3, 4, 5], &platform, &options);
+        assert!(matches!(result, Err(Error::DecompressionFailed(_))));
+        assert!(!dir.path().join("buck2").exists());
     }
 
     #[test]
-    fn test_decompress_empty_data() {
-        let installer = Buck2Installer::new();
-        let empty_data: Vec<u8> = vec![];
+    fn test_parse_prelude_hash() {
+        let hash = "0123456789abcdef0123456789ABCDEF01234567\n";
+        assert_eq!(
+            parse_prelude_hash(hash.as_bytes()).unwrap(),
+            "0123456789abcdef0123456789abcdef01234567"
+        );
+
+        assert!(parse_prelude_hash(b"").is_err());
+        assert!(parse_prelude_hash(b"not-a-hash").is_err());
+    }
+
+    #[test]
+    fn test_fetch_prelude_hash_missing_asset() {
+        let backend = crate::backend::MockBackend::with_buck2_releases();
+        let result = fetch_prelude_hash(&backend, "2024-01-15");
+        assert!(matches!(result, Err(Error::DownloadFailed { .. })));
+    }
+
+    #[test]
+    fn test_write_project_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".buckconfig"), "custom").unwrap();
+
+        let (written, skipped) = write_project_files(dir.path(), false).unwrap();
+        assert_eq!(skipped, vec![dir.path().join(".buckconfig")]);
+        assert_eq!(written.len(), 2);
+        assert!(dir.path().join(".buckroot").exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".buckconfig")).unwrap(),
+            "custom"
+        );
+
+        let (written, skipped) = write_project_files(dir.path(), true).unwrap();
+        assert_eq!(written.len(), 3);
+        assert!(skipped.is_empty());
+        let config = fs::read_to_string(dir.path().join(".buckconfig")).unwrap();
+        assert!(config.contains("prelude = prelude"));
+    }
+
+    #[test]
+    fn test_vendor_prelude_rejects_non_git_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let prelude = dir.path().join("prelude");
+        fs::create_dir_all(&prelude).unwrap();
 
-        let result = installer.decompress(&empty_data);
+        let result = vendor_prelude(&prelude, "0123456789abcdef0123456789abcdef01234567", false);
         assert!(result.is_err());
     }
 
@@ -0,0 +1,136 @@
+//! Buildifier-specific installation logic.
+//!
+//! This module provides the [`BuildifierInstaller`] for installing
+//! buildifier, the formatter and linter for Bazel `BUILD` and `.bzl` files,
+//! from the bazelbuild/buildtools GitHub releases.
+//!
+//! Buildifier is a Go program, so its release assets use Go's platform
+//! names (e.g. `buildifier-linux-amd64`). They are not versioned by name and
+//! come without checksum files.
+
+use crate::error::Result;
+use crate::tools::{self, ToolInstaller};
+use crate::types::{InstallOptions, InstallResult, Platform, Release, Tool};
+use std::path::{Path, PathBuf};
+
+/// Buildifier installer.
+pub struct BuildifierInstaller;
+
+impl BuildifierInstaller {
+    /// Create a new buildifier installer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for BuildifierInstaller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToolInstaller for BuildifierInstaller {
+    fn tool(&self) -> Tool {
+        Tool::Buildifier
+    }
+
+    fn asset_name(&self, _release: &Release, platform: &Platform) -> Result<String> {
+        let (os, arch) = tools::go_platform(Tool::Buildifier, platform)?;
+        let extension = if platform.is_windows() { ".exe" } else { "" };
+        Ok(format!("buildifier-{os}-{arch}{extension}"))
+    }
+
+    fn install(
+        &self,
+        bytes: &[u8],
+        _platform: &Platform,
+        options: &InstallOptions,
+    ) -> Result<InstallResult> {
+        tools::install_binary(self, bytes, options)
+    }
+
+    fn is_installed(&self) -> Result<bool> {
+        Ok(tools::find_in_path(Tool::Buildifier).is_some())
+    }
+
+    fn installed_version(&self) -> Result<Option<String>> {
+        if let Some(path) = tools::find_in_path(Tool::Buildifier) {
+            self.binary_version(&path)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn default_install_dir(&self) -> Result<PathBuf> {
+        Ok(tools::default_install_dir())
+    }
+
+    fn verify(&self, path: &Path) -> Result<()> {
+        tools::verify_runs(Tool::Buildifier, path)
+    }
+
+    fn binary_version(&self, path: &Path) -> Result<Option<String>> {
+        Ok(
+            tools::version_output(Tool::Buildifier, path)?
+                .and_then(|stdout| parse_version(&stdout)),
+        )
+    }
+}
+
+/// Parse `buildifier --version` output:
+///
+/// ```text
+/// buildifier version: 7.3.1
+/// buildifier scm revision: 4b6a1b1a...
+/// ```
+fn parse_version(stdout: &str) -> Option<String> {
+    stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("buildifier version:"))
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_name() {
+        let installer = BuildifierInstaller::new();
+        let release = Release {
+            tag: "v7.3.1".to_string(),
+            name: "v7.3.1".to_string(),
+            prerelease: false,
+            published_at: String::new(),
+            notes: String::new(),
+            assets: Vec::new(),
+        };
+
+        let mac = Platform::new("macos", "aarch64", "aarch64-apple-darwin");
+        assert_eq!(
+            installer.asset_name(&release, &mac).unwrap(),
+            "buildifier-darwin-arm64"
+        );
+
+        let windows = Platform::new("windows", "x86_64", "x86_64-pc-windows-msvc");
+        assert_eq!(
+            installer.asset_name(&release, &windows).unwrap(),
+            "buildifier-windows-amd64.exe"
+        );
+        assert!(
+            installer
+                .checksum_asset_name("buildifier-darwin-arm64")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_parse_version() {
+        let stdout = "buildifier version: 7.3.1 \nbuildifier scm revision: 4b6a1b1a\n";
+        assert_eq!(parse_version(stdout).as_deref(), Some("7.3.1"));
+        assert!(parse_version("buildifier version: \n").is_none());
+        assert!(parse_version("").is_none());
+    }
+}
@@ -2,17 +2,33 @@
 //!
 //! This module provides the [`ToolInstaller`] trait and implementations
 //! for installing specific build tools. Each tool has its own installer
-//! that knows how to decompress, install, and verify the tool.
+//! that knows how its release assets are named, and how to install and
+//! verify the tool. Getting the binary out of an archive is shared, see
+//! [`crate::archive`].
 //!
 //! # Supported Tools
 //!
-//! - [`buck2::Buck2Installer`] - Meta's Buck2 build system
+//! - [`buck2::Buck2Installer`] - Meta's Buck2 build system (plus
+//!   [`buck2::init_project`] for project bootstrap)
+//! - [`bazel::BazelInstaller`] - Google's Bazel build system
+//! - [`buildifier::BuildifierInstaller`] - Bazel's BUILD file formatter
 
+pub mod bazel;
 pub mod buck2;
+pub mod buildifier;
 
-use crate::error::Result;
-use crate::types::{InstallOptions, InstallResult, Platform, Tool};
-use std::path::Path;
+use crate::archive;
+use crate::diagnostics::PathDiagnostics;
+use crate::error::{Error, Result};
+use crate::platform;
+use crate::types::{
+    InstallOptions, InstallResult, InstalledTool, Platform, Release, Tool, UninstallResult,
+};
+use std::fs;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// Trait for tool-specific installation logic.
 ///
@@ -22,10 +38,25 @@ pub trait ToolInstaller: Send + Sync {
     /// Get the tool this installer handles.
     fn tool(&self) -> Tool;
 
+    /// Name of the release asset holding the binary for `platform`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedPlatform` if the tool isn't released for
+    /// the platform.
+    fn asset_name(&self, release: &Release, platform: &Platform) -> Result<String>;
+
+    /// Name of the release asset holding the SHA-256 checksum of `asset_name`.
+    ///
+    /// Returns `None` for tools that don't publish checksums.
+    fn checksum_asset_name(&self, _asset_name: &str) -> Option<String> {
+        None
+    }
+
     /// Install the tool from downloaded bytes.
     ///
-    /// The `bytes` parameter contains the raw (possibly compressed) binary data.
-    /// The installer is responsible for decompression if needed.
+    /// The `bytes` parameter contains the downloaded asset: the binary
+    /// itself, or an archive holding it.
     ///
     /// # Errors
     ///
@@ -60,4 +91,324 @@ pub trait ToolInstaller: Send + Sync {
     ///
     /// Runs the tool with a version flag to ensure it's functional.
     fn verify(&self, path: &Path) -> Result<()>;
+
+    /// Get the version reported by the binary at `path`.
+    ///
+    /// Returns `None` if the binary runs but its version can't be parsed.
+    fn binary_version(&self, path: &Path) -> Result<Option<String>>;
+
+    /// Files an installation places in `install_dir`.
+    ///
+    /// These are what uninstalling removes. Defaults to the binary alone.
+    fn installed_files(&self, install_dir: &Path) -> Vec<PathBuf> {
+        vec![binary_path(self.tool(), install_dir)]
+    }
+}
+
+/// Get the installer for a tool.
+#[must_use]
+pub fn installer(tool: Tool) -> Box<dyn ToolInstaller> {
+    match tool {
+        Tool::Buck2 => Box::new(buck2::Buck2Installer::new()),
+        Tool::Bazel => Box::new(bazel::BazelInstaller::new()),
+        Tool::Buildifier => Box::new(buildifier::BuildifierInstaller::new()),
+    }
+}
+
+/// Default installation directory shared by all tools.
+///
+/// Prefers `~/.local/bin` (XDG-compliant), falling back to `/usr/local/bin`.
+pub(crate) fn default_install_dir() -> PathBuf {
+    dirs::home_dir().map_or_else(
+        || PathBuf::from("/usr/local/bin"),
+        |home| home.join(".local").join("bin"),
+    )
+}
+
+/// Path of a tool's binary inside `install_dir`.
+pub(crate) fn binary_path(tool: Tool, install_dir: &Path) -> PathBuf {
+    install_dir.join(format!(
+        "{}{}",
+        tool.binary_name(),
+        platform::executable_extension()
+    ))
+}
+
+/// Find a tool's binary in PATH.
+pub(crate) fn find_in_path(tool: Tool) -> Option<PathBuf> {
+    which::which(tool.binary_name()).ok()
+}
+
+/// Run `binary --version`, returning stdout if it succeeds.
+///
+/// # Errors
+///
+/// Returns an error if the binary can't be executed.
+pub(crate) fn version_output(tool: Tool, binary: &Path) -> Result<Option<String>> {
+    let output = Command::new(binary)
+        .arg("--version")
+        .output()
+        .map_err(|e| Error::Other(format!("failed to execute {tool}: {e}")))?;
+
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+}
+
+/// Run `binary --version` and fail unless it succeeds.
+pub(crate) fn verify_runs(tool: Tool, binary: &Path) -> Result<()> {
+    let output = Command::new(binary)
+        .arg("--version")
+        .output()
+        .map_err(|e| Error::Other(format!("failed to execute {tool}: {e}")))?;
+
+    if !output.status.success() {
+        return Err(Error::Other(format!(
+            "{tool} verification failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+
+    Ok(())
+}
+
+/// Names Go-style release assets use for the platform: `darwin`/`linux`/
+/// `windows` and `amd64`/`arm64`.
+pub(crate) fn go_platform(tool: Tool, platform: &Platform) -> Result<(&'static str, &'static str)> {
+    let unsupported = || Error::UnsupportedPlatform {
+        os: platform.os.clone(),
+        arch: format!("{} ({tool})", platform.arch),
+    };
+    let os = match platform.os.as_str() {
+        "macos" => "darwin",
+        "linux" => "linux",
+        "windows" => "windows",
+        _ => return Err(unsupported()),
+    };
+    let arch = match platform.arch.as_str() {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        _ => return Err(unsupported()),
+    };
+    Ok((os, arch))
+}
+
+/// Write an executable into the install directory, verify it runs, and
+/// report the version it prints.
+///
+/// `asset` is either the executable or an archive holding it (see
+/// [`archive::unpack`]).
+///
+/// When the binary doesn't report a version, the requested version (or
+/// `"latest"`) is reported instead.
+///
+/// # Errors
+///
+/// Returns an error if the archive doesn't hold the binary, if the tool is
+/// already installed and `options.force` is not set, if writing the binary
+/// fails, or if verification fails.
+pub(crate) fn install_binary(
+    installer: &dyn ToolInstaller,
+    asset: &[u8],
+    options: &InstallOptions,
+) -> Result<InstallResult> {
+    let tool = installer.tool();
+
+    // Determine install directory
+    let install_dir = options
+        .install_dir
+        .clone()
+        .or_else(|| installer.default_install_dir().ok())
+        .ok_or_else(|| Error::Other("cannot determine install directory".to_string()))?;
+
+    // Ensure directory exists
+    fs::create_dir_all(&install_dir).map_err(|e| Error::io(&install_dir, e))?;
+
+    // Determine binary path
+    let binary_path = binary_path(tool, &install_dir);
+
+    // Check for existing installation
+    let was_upgrade = binary_path.exists();
+    let previous_version = if was_upgrade {
+        installer.installed_version().ok().flatten()
+    } else {
+        None
+    };
+
+    // Check if we should overwrite
+    if was_upgrade && !options.force {
+        return Err(Error::Other(format!(
+            "{tool} already installed at {}. Use --force to overwrite.",
+            binary_path.display()
+        )));
+    }
+
+    // Write binary
+    let file_name = binary_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let binary = archive::unpack(asset, &file_name)?;
+    fs::write(&binary_path, &binary).map_err(|e| Error::io(&binary_path, e))?;
+
+    // Make executable (Unix only)
+    #[cfg(unix)]
+    {
+        let mut perms = fs::metadata(&binary_path)
+            .map_err(|e| Error::io(&binary_path, e))?
+            .permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&binary_path, perms).map_err(|e| Error::io(&binary_path, e))?;
+    }
+
+    // Verify installation
+    installer.verify(&binary_path)?;
+
+    // Get installed version
+    let version = installer
+        .binary_version(&binary_path)?
+        .unwrap_or_else(|| options.version.clone().unwrap_or("latest".to_string()));
+
+    Ok(InstallResult {
+        tool,
+        version,
+        path_diagnostics: PathDiagnostics::check(&install_dir, &file_name),
+        path: binary_path,
+        was_upgrade,
+        previous_version,
+        unverified: false,
+    })
+}
+
+/// Look for a tool's binary in `install_dir`.
+///
+/// Runs the binary to learn its version, and takes its modification time
+/// as the install date. Returns `None` if the binary isn't there.
+///
+/// # Errors
+///
+/// Returns an error if the binary's metadata can't be read.
+pub(crate) fn probe(
+    installer: &dyn ToolInstaller,
+    install_dir: &Path,
+) -> Result<Option<InstalledTool>> {
+    let tool = installer.tool();
+    let path = binary_path(tool, install_dir);
+    let metadata = match fs::metadata(&path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(Error::io(&path, e)),
+    };
+
+    // A binary that no longer runs is still installed, just broken
+    let version = installer
+        .binary_version(&path)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let mut installed = InstalledTool::new(tool, version, path);
+    if let Ok(modified) = metadata.modified() {
+        installed.installed_at = chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339();
+    }
+    Ok(Some(installed))
+}
+
+/// Remove the files an installation placed in `install_dir`.
+///
+/// Files that don't exist are skipped, so uninstalling a tool that isn't
+/// installed succeeds and removes nothing.
+///
+/// # Errors
+///
+/// Returns an error if an existing file can't be removed.
+pub(crate) fn uninstall(
+    installer: &dyn ToolInstaller,
+    install_dir: &Path,
+) -> Result<UninstallResult> {
+    let mut removed = Vec::new();
+    for path in installer.installed_files(install_dir) {
+        // symlink_metadata so dangling shims are removed too
+        if fs::symlink_metadata(&path).is_err() {
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => removed.push(path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(Error::io(&path, e)),
+        }
+    }
+
+    Ok(UninstallResult {
+        tool: installer.tool(),
+        removed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_installer_for_every_tool() {
+        for &tool in Tool::all() {
+            assert_eq!(installer(tool).tool(), tool);
+        }
+    }
+
+    #[test]
+    fn test_uninstall() {
+        let dir = tempfile::tempdir().unwrap();
+        let bazel = installer(Tool::Bazel);
+
+        let result = uninstall(bazel.as_ref(), dir.path()).unwrap();
+        assert!(!result.was_installed());
+
+        let binary = binary_path(Tool::Bazel, dir.path());
+        fs::write(&binary, b"bazel").unwrap();
+        fs::write(dir.path().join("buck2"), b"buck2").unwrap();
+
+        let result = uninstall(bazel.as_ref(), dir.path()).unwrap();
+        assert_eq!(result.removed, vec![binary.clone()]);
+        assert!(!binary.exists());
+        assert!(dir.path().join("buck2").exists());
+    }
+
+    #[test]
+    fn test_probe() {
+        let dir = tempfile::tempdir().unwrap();
+        let buildifier = installer(Tool::Buildifier);
+        assert!(probe(buildifier.as_ref(), dir.path()).unwrap().is_none());
+
+        // Not a runnable binary, so the version is unknown
+        let binary = binary_path(Tool::Buildifier, dir.path());
+        fs::write(&binary, b"not a binary").unwrap();
+        let installed = probe(buildifier.as_ref(), dir.path()).unwrap().unwrap();
+        assert_eq!(installed.tool, Tool::Buildifier);
+        assert_eq!(installed.version, "unknown");
+        assert_eq!(installed.path, binary);
+        assert!(!installed.installed_at.is_empty());
+    }
+
+    #[test]
+    fn test_go_platform() {
+        let mac = Platform::new("macos", "aarch64", "aarch64-apple-darwin");
+        assert_eq!(
+            go_platform(Tool::Buildifier, &mac).unwrap(),
+            ("darwin", "arm64")
+        );
+
+        let linux = Platform::new("linux", "x86_64", "x86_64-unknown-linux-gnu");
+        assert_eq!(
+            go_platform(Tool::Buildifier, &linux).unwrap(),
+            ("linux", "amd64")
+        );
+
+        let riscv = Platform::new("linux", "riscv64", "riscv64gc-unknown-linux-gnu");
+        assert!(matches!(
+            go_platform(Tool::Buildifier, &riscv),
+            Err(Error::UnsupportedPlatform { .. })
+        ));
+    }
 }
@@ -4,6 +4,9 @@
 //! the toolchain crate, including tool definitions, platform information,
 //! installation options, and result types.
 
+use crate::archive::ArchiveFormat;
+use crate::diagnostics::PathDiagnostics;
+use crate::progress::{DownloadProgress, ProgressCallback};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::path::PathBuf;
@@ -31,7 +34,18 @@ pub enum Tool {
     /// Buck2 is a fast, hermetic build system from Meta that supports
     /// large-scale monorepos with excellent caching and remote execution.
     Buck2,
-    // Future: Bazel, Pants, Please, etc.
+
+    /// Google's Bazel build system.
+    ///
+    /// Installed from the official releases, which ship one executable per
+    /// platform along with a SHA-256 checksum.
+    Bazel,
+
+    /// Formatter and linter for Bazel `BUILD` and `.bzl` files.
+    ///
+    /// Released as part of bazelbuild/buildtools.
+    Buildifier,
+    // Future: Pants, Please, etc.
 }
 
 impl Tool {
@@ -43,6 +57,8 @@ impl Tool {
     pub fn name(&self) -> &'static str {
         match self {
             Self::Buck2 => "buck2",
+            Self::Bazel => "bazel",
+            Self::Buildifier => "buildifier",
         }
     }
 
@@ -53,6 +69,8 @@ impl Tool {
     pub fn github_repo(&self) -> &'static str {
         match self {
             Self::Buck2 => "facebook/buck2",
+            Self::Bazel => "bazelbuild/bazel",
+            Self::Buildifier => "bazelbuild/buildtools",
         }
     }
 
@@ -63,15 +81,32 @@ impl Tool {
     pub fn binary_name(&self) -> &'static str {
         match self {
             Self::Buck2 => "buck2",
+            Self::Bazel => "bazel",
+            Self::Buildifier => "buildifier",
         }
     }
 
+    /// Whether the repository publishes a rolling release tagged `latest`.
+    ///
+    /// Buck2 does; other tools only mark their newest stable release as
+    /// latest, which has to be looked up separately.
+    #[must_use]
+    pub fn has_latest_tag(&self) -> bool {
+        matches!(self, Self::Buck2)
+    }
+
+    /// Look up a tool by its [`name`](Self::name).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|tool| tool.name() == name)
+    }
+
     /// Get all supported tools.
     ///
     /// Returns an iterator over all tool variants.
     #[must_use]
     pub fn all() -> &'static [Tool] {
-        &[Tool::Buck2]
+        &[Tool::Buck2, Tool::Bazel, Tool::Buildifier]
     }
 }
 
@@ -195,6 +230,9 @@ pub struct Release {
     pub prerelease: bool,
     /// Published date (ISO 8601 format).
     pub published_at: String,
+    /// Release notes (Markdown), empty if the release has none.
+    #[serde(default)]
+    pub notes: String,
     /// Available assets.
     pub assets: Vec<ReleaseAsset>,
 }
@@ -208,10 +246,13 @@ impl Release {
 
     /// Find an asset matching a platform triple.
     ///
-    /// Looks for assets with names containing the platform triple.
+    /// Looks for assets with names containing the platform triple, in any
+    /// [`ArchiveFormat`]. Checksums and signatures are skipped.
     #[must_use]
     pub fn find_asset_for_platform(&self, triple: &str) -> Option<&ReleaseAsset> {
-        self.assets.iter().find(|a| a.name.contains(triple))
+        self.assets
+            .iter()
+            .find(|a| a.name.contains(triple) && !a.is_metadata())
     }
 }
 
@@ -244,7 +285,22 @@ impl ReleaseAsset {
     /// Check if this asset is a zstd-compressed file.
     #[must_use]
     pub fn is_zstd(&self) -> bool {
-        self.name.ends_with(".zst") || self.name.ends_with(".zstd")
+        self.archive_format() == ArchiveFormat::Zstd
+    }
+
+    /// Get the format of this asset, from its name.
+    #[must_use]
+    pub fn archive_format(&self) -> ArchiveFormat {
+        ArchiveFormat::from_name(&self.name)
+    }
+
+    /// Check if this asset describes another one (a checksum or signature)
+    /// rather than holding a binary.
+    #[must_use]
+    pub fn is_metadata(&self) -> bool {
+        [".sha256", ".sha512", ".sig", ".asc", ".pem", ".sbom"]
+            .iter()
+            .any(|suffix| self.name.ends_with(suffix))
     }
 
     /// Get a human-readable size string.
@@ -292,6 +348,16 @@ pub struct InstallOptions {
     pub install_dir: Option<PathBuf>,
     /// Whether to overwrite existing installation.
     pub force: bool,
+    /// Whether to bypass the download cache.
+    pub no_cache: bool,
+    /// Whether to keep this version alongside others, behind a shim.
+    pub versioned: bool,
+    /// Callback receiving download progress.
+    pub progress: Option<ProgressCallback>,
+    /// Whether to install from the download cache alone.
+    pub offline: bool,
+    /// Whether to fail when the release lacks the tool's checksum file.
+    pub require_checksum: bool,
 }
 
 impl InstallOptions {
@@ -328,6 +394,74 @@ impl InstallOptions {
         self
     }
 
+    /// Set whether to bypass the download cache.
+    ///
+    /// When true, the asset is always downloaded, and not cached afterwards.
+    #[must_use]
+    pub fn no_cache(mut self, no_cache: bool) -> Self {
+        self.no_cache = no_cache;
+        self
+    }
+
+    /// Set whether to install side by side with other versions.
+    ///
+    /// When true, the binary goes into the
+    /// [`VersionStore`](crate::versions::VersionStore) and the install
+    /// directory gets a shim running the selected version. The first version
+    /// installed is selected globally.
+    #[must_use]
+    pub fn versioned(mut self, versioned: bool) -> Self {
+        self.versioned = versioned;
+        self
+    }
+
+    /// Set whether to install without network access.
+    ///
+    /// When true, the release and its assets come from the download cache
+    /// alone, so only what an earlier online install cached can be
+    /// installed. Anything missing fails the install with
+    /// `Error::NotCached`, listing what would have to be fetched.
+    #[must_use]
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Set whether a download must be verified against a checksum.
+    ///
+    /// Tools that publish checksums (e.g. Bazel) normally have one next to
+    /// every asset. When true, an asset without one fails the install with
+    /// `Error::InvalidResponse`; otherwise it installs unverified, and
+    /// [`InstallResult::unverified`] says so. Tools that never publish
+    /// checksums are unaffected.
+    #[must_use]
+    pub fn require_checksum(mut self, require: bool) -> Self {
+        self.require_checksum = require;
+        self
+    }
+
+    /// Report download progress to `callback`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use toolchain::InstallOptions;
+    ///
+    /// let options = InstallOptions::new().on_progress(|p| {
+    ///     if let Some(fraction) = p.fraction() {
+    ///         eprint!("\r{:3.0}%", fraction * 100.0);
+    ///     }
+    /// });
+    /// ```
+    #[must_use]
+    pub fn on_progress(
+        mut self,
+        callback: impl Fn(&DownloadProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(ProgressCallback::new(callback));
+        self
+    }
+
     /// Check if a specific version was requested.
     #[must_use]
     pub fn has_version(&self) -> bool {
@@ -351,6 +485,11 @@ pub struct InstallResult {
     pub was_upgrade: bool,
     /// Previous version if this was an upgrade.
     pub previous_version: Option<String>,
+    /// Whether the installed tool can be run by name.
+    pub path_diagnostics: PathDiagnostics,
+    /// Whether the download went unverified, because the release lacks the
+    /// checksum the tool publishes.
+    pub unverified: bool,
 }
 
 impl InstallResult {
@@ -397,6 +536,79 @@ impl fmt::Display for InstallResult {
     }
 }
 
+/// Result of an upgrade operation.
+#[derive(Debug, Clone)]
+pub struct UpgradeResult {
+    /// The tool that was checked.
+    pub tool: Tool,
+    /// Version installed before the upgrade, if any.
+    pub previous_version: Option<String>,
+    /// The release that was checked against.
+    pub release: Release,
+    /// The installation, or `None` if the tool was already up to date.
+    pub installed: Option<InstallResult>,
+}
+
+impl UpgradeResult {
+    /// Check if a new version was installed.
+    #[must_use]
+    pub fn is_upgraded(&self) -> bool {
+        self.installed.is_some()
+    }
+
+    /// Release notes of the installed release ("what's new").
+    ///
+    /// Returns `None` if nothing was installed or the release has no notes.
+    #[must_use]
+    pub fn release_notes(&self) -> Option<&str> {
+        let notes = self.release.notes.trim();
+        (self.is_upgraded() && !notes.is_empty()).then_some(notes)
+    }
+}
+
+impl fmt::Display for UpgradeResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.installed {
+            Some(installed) => write!(f, "{installed}"),
+            None => write!(
+                f,
+                "{} is up to date ({})",
+                self.tool,
+                self.previous_version
+                    .as_deref()
+                    .unwrap_or(&self.release.tag)
+            ),
+        }
+    }
+}
+
+/// Result of an uninstall operation.
+#[derive(Debug, Clone)]
+pub struct UninstallResult {
+    /// The tool that was uninstalled.
+    pub tool: Tool,
+    /// Files that were removed.
+    pub removed: Vec<PathBuf>,
+}
+
+impl UninstallResult {
+    /// Check if anything was removed, i.e. the tool was installed.
+    #[must_use]
+    pub fn was_installed(&self) -> bool {
+        !self.removed.is_empty()
+    }
+}
+
+impl fmt::Display for UninstallResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.removed.as_slice() {
+            [] => write!(f, "{} was not installed", self.tool),
+            [path] => write!(f, "{} removed from {}", self.tool, path.display()),
+            paths => write!(f, "{} removed ({} files)", self.tool, paths.len()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -408,11 +620,17 @@ mod tests {
     #[test]
     fn test_tool_name() {
         assert_eq!(Tool::Buck2.name(), "buck2");
+        assert_eq!(Tool::Bazel.name(), "bazel");
+        assert_eq!(Tool::Buildifier.name(), "buildifier");
+        assert_eq!(Tool::from_name("bazel"), Some(Tool::Bazel));
+        assert_eq!(Tool::from_name("pants"), None);
     }
 
     #[test]
     fn test_tool_github_repo() {
         assert_eq!(Tool::Buck2.github_repo(), "facebook/buck2");
+        assert_eq!(Tool::Bazel.github_repo(), "bazelbuild/bazel");
+        assert_eq!(Tool::Buildifier.github_repo(), "bazelbuild/buildtools");
     }
 
     #[test]
@@ -515,6 +733,7 @@ mod tests {
             name: "Release 2024-01-15".to_string(),
             prerelease: false,
             published_at: "2024-01-15T00:00:00Z".to_string(),
+            notes: String::new(),
             assets: vec![
                 ReleaseAsset {
                     name: "buck2-aarch64-apple-darwin.zst".to_string(),
@@ -557,6 +776,27 @@ mod tests {
         assert!(not_found.is_none());
     }
 
+    #[test]
+    fn test_release_find_asset_for_platform_skips_checksums() {
+        let asset = |name: &str| ReleaseAsset {
+            name: name.to_string(),
+            download_url: format!("https://example.com/{name}"),
+            size: 0,
+        };
+        let mut release = create_test_release();
+        release.assets = vec![
+            asset("rg-14.1.0-x86_64-unknown-linux-musl.tar.gz.sha256"),
+            asset("rg-14.1.0-x86_64-unknown-linux-musl.tar.gz"),
+        ];
+
+        let found = release
+            .find_asset_for_platform("x86_64-unknown-linux-musl")
+            .unwrap();
+        assert_eq!(found.archive_format(), ArchiveFormat::TarGz);
+        assert!(!found.is_metadata());
+        assert!(release.assets[0].is_metadata());
+    }
+
     #[test]
     fn test_release_asset_extension() {
         let asset = ReleaseAsset {
@@ -659,11 +899,13 @@ mod tests {
         let options = InstallOptions::new()
             .version("2024-01-15")
             .install_dir("/usr/local/bin")
-            .force(true);
+            .force(true)
+            .no_cache(true);
 
         assert_eq!(options.version, Some("2024-01-15".to_string()));
         assert_eq!(options.install_dir, Some(PathBuf::from("/usr/local/bin")));
         assert!(options.force);
+        assert!(options.no_cache);
     }
 
     #[test]
@@ -687,6 +929,8 @@ mod tests {
             path: PathBuf::from("/usr/local/bin/buck2"),
             was_upgrade: false,
             previous_version: None,
+            path_diagnostics: PathDiagnostics::default(),
+            unverified: false,
         };
         assert!(!fresh_install.is_version_change());
 
@@ -696,6 +940,8 @@ mod tests {
             path: PathBuf::from("/usr/local/bin/buck2"),
             was_upgrade: true,
             previous_version: Some("2024-01-01".to_string()),
+            path_diagnostics: PathDiagnostics::default(),
+            unverified: false,
         };
         assert!(upgrade.is_version_change());
 
@@ -705,6 +951,8 @@ mod tests {
             path: PathBuf::from("/usr/local/bin/buck2"),
             was_upgrade: true,
             previous_version: Some("2024-01-15".to_string()),
+            path_diagnostics: PathDiagnostics::default(),
+            unverified: false,
         };
         assert!(!reinstall.is_version_change());
     }
@@ -717,6 +965,8 @@ mod tests {
             path: PathBuf::from("/usr/local/bin/buck2"),
             was_upgrade: false,
             previous_version: None,
+            path_diagnostics: PathDiagnostics::default(),
+            unverified: false,
         };
         let display = format!("{result}");
         assert!(display.contains("buck2"));
@@ -732,6 +982,8 @@ mod tests {
             path: PathBuf::from("/usr/local/bin/buck2"),
             was_upgrade: true,
             previous_version: Some("2024-01-01".to_string()),
+            path_diagnostics: PathDiagnostics::default(),
+            unverified: false,
         };
         let display = format!("{result}");
         assert!(display.contains("upgraded"));
@@ -739,6 +991,54 @@ mod tests {
         assert!(display.contains("2024-01-15"));
     }
 
+    #[test]
+    fn test_upgrade_result() {
+        let mut release = create_test_release();
+        release.notes = "## What's new\n\n- Faster builds\n".to_string();
+        let mut result = UpgradeResult {
+            tool: Tool::Buck2,
+            previous_version: Some("2024-01-15".to_string()),
+            release,
+            installed: None,
+        };
+        assert!(!result.is_upgraded());
+        assert!(result.release_notes().is_none());
+        assert_eq!(result.to_string(), "buck2 is up to date (2024-01-15)");
+
+        result.installed = Some(InstallResult {
+            tool: Tool::Buck2,
+            version: "2024-02-01".to_string(),
+            path: PathBuf::from("/usr/local/bin/buck2"),
+            was_upgrade: true,
+            previous_version: Some("2024-01-15".to_string()),
+            path_diagnostics: PathDiagnostics::default(),
+            unverified: false,
+        });
+        assert!(result.is_upgraded());
+        assert_eq!(
+            result.release_notes(),
+            Some("## What's new\n\n- Faster builds")
+        );
+        assert!(result.to_string().contains("upgraded"));
+    }
+
+    #[test]
+    fn test_uninstall_result_display() {
+        let mut result = UninstallResult {
+            tool: Tool::Bazel,
+            removed: Vec::new(),
+        };
+        assert!(!result.was_installed());
+        assert_eq!(result.to_string(), "bazel was not installed");
+
+        result.removed.push(PathBuf::from("/usr/local/bin/bazel"));
+        assert!(result.was_installed());
+        assert_eq!(
+            result.to_string(),
+            "bazel removed from /usr/local/bin/bazel"
+        );
+    }
+
     // =========================================================================
     // InstalledTool tests
     // =========================================================================
@@ -0,0 +1,481 @@
+//! Side-by-side installs of several versions of a tool.
+//!
+//! Versioned installs (see [`InstallOptions::versioned`]) go into a
+//! [`VersionStore`] instead of the install directory:
+//!
+//! ```text
+//! ~/.local/share/bossa/toolchain/
+//!   buck2/
+//!     current            # globally selected version
+//!     2024-01-15/buck2
+//!     latest/buck2
+//! ```
+//!
+//! The install directory gets a shim instead, a small shell script that
+//! runs the selected version. The selection is, in order of precedence:
+//!
+//! 1. `TOOLCHAIN_<TOOL>_VERSION` in the environment (e.g.
+//!    `TOOLCHAIN_BUCK2_VERSION`)
+//! 2. the tool's line in the nearest `.tool-versions` file, searching from
+//!    the working directory upwards (the format asdf and mise use:
+//!    `buck2 2024-01-15`)
+//! 3. the global selection in `current`
+//!
+//! [`Client::use_version`](crate::Client::use_version) sets the global
+//! selection and [`Client::use_version_in`](crate::Client::use_version_in)
+//! a per-directory one.
+//!
+//! [`InstallOptions::versioned`]: crate::InstallOptions::versioned
+
+use crate::error::{Error, Result};
+use crate::platform;
+use crate::types::Tool;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Per-directory version selection file.
+pub const VERSIONS_FILE: &str = ".tool-versions";
+
+/// First lines of every shim, used to recognize them.
+const SHIM_HEADER: &str = "#!/bin/sh\n# bossa toolchain shim";
+
+/// Directory holding the installed versions of each tool.
+#[derive(Debug, Clone)]
+pub struct VersionStore {
+    root: PathBuf,
+}
+
+impl VersionStore {
+    /// Store rooted at `root`.
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Store in the default location: `$XDG_DATA_HOME/bossa/toolchain`,
+    /// falling back to `~/.local/share/bossa/toolchain`.
+    ///
+    /// Returns `None` if the home directory can't be determined.
+    #[must_use]
+    pub fn default_location() -> Option<Self> {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| dirs::home_dir().map(|home| home.join(".local").join("share")))?;
+        Some(Self::new(data_home.join("bossa").join("toolchain")))
+    }
+
+    /// Root directory of the store.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Directory holding all versions of a tool.
+    #[must_use]
+    pub fn tool_dir(&self, tool: Tool) -> PathBuf {
+        self.root.join(tool.name())
+    }
+
+    /// Directory a version of a tool is installed to.
+    #[must_use]
+    pub fn version_dir(&self, tool: Tool, version: &str) -> PathBuf {
+        self.tool_dir(tool).join(version)
+    }
+
+    /// Installed versions of a tool, sorted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tool's directory exists but can't be read.
+    pub fn versions(&self, tool: Tool) -> Result<Vec<String>> {
+        let dir = self.tool_dir(tool);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error::io(&dir, e)),
+        };
+
+        let mut versions: Vec<String> = entries
+            .filter_map(std::result::Result::ok)
+            .filter(|entry| entry.path().join(binary_name(tool)).is_file())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        versions.sort();
+        Ok(versions)
+    }
+
+    /// Whether a version of a tool is installed.
+    #[must_use]
+    pub fn has_version(&self, tool: Tool, version: &str) -> bool {
+        self.version_dir(tool, version)
+            .join(binary_name(tool))
+            .is_file()
+    }
+
+    /// The globally selected version of a tool, if any.
+    #[must_use]
+    pub fn current(&self, tool: Tool) -> Option<String> {
+        let current = fs::read_to_string(self.tool_dir(tool).join("current")).ok()?;
+        let current = current.trim();
+        (!current.is_empty()).then(|| current.to_string())
+    }
+
+    /// Select a version of a tool globally.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::VersionNotFound` if the version isn't installed.
+    pub fn set_current(&self, tool: Tool, version: &str) -> Result<()> {
+        self.require(tool, version)?;
+        let path = self.tool_dir(tool).join("current");
+        fs::write(&path, format!("{version}\n")).map_err(|e| Error::io(&path, e))
+    }
+
+    /// The version of a tool selected for `dir`, following the same rules as
+    /// the shim (environment aside).
+    #[must_use]
+    pub fn resolve(&self, tool: Tool, dir: &Path) -> Option<String> {
+        dir.ancestors()
+            .find_map(|dir| {
+                let content = fs::read_to_string(dir.join(VERSIONS_FILE)).ok()?;
+                local_version(&content, tool)
+            })
+            .or_else(|| self.current(tool))
+    }
+
+    /// Check that the shim for a tool can be written into `install_dir`,
+    /// without writing anything. Returns the shim's path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if shims aren't supported here, or if a binary that
+    /// isn't a shim is in the way and `force` isn't set.
+    pub fn check_shim(&self, tool: Tool, install_dir: &Path, force: bool) -> Result<PathBuf> {
+        if !cfg!(unix) {
+            return Err(Error::Other(
+                "version shims are only supported on Unix".to_string(),
+            ));
+        }
+
+        let path = install_dir.join(binary_name(tool));
+        if path.exists() && !force && !is_shim(&path) {
+            return Err(Error::Other(format!(
+                "{tool} already installed at {}. Use --force to replace it with a shim.",
+                path.display()
+            )));
+        }
+        Ok(path)
+    }
+
+    /// Write the shim for a tool into `install_dir`.
+    ///
+    /// An existing binary that isn't a shim is only replaced if `force` is
+    /// set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a binary is in the way, or if the shim can't be
+    /// written.
+    pub fn write_shim(&self, tool: Tool, install_dir: &Path, force: bool) -> Result<PathBuf> {
+        let path = self.check_shim(tool, install_dir, force)?;
+        fs::create_dir_all(install_dir).map_err(|e| Error::io(install_dir, e))?;
+        fs::write(&path, self.shim_script(tool)).map_err(|e| Error::io(&path, e))?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+                .map_err(|e| Error::io(&path, e))?;
+        }
+
+        Ok(path)
+    }
+
+    /// Shell script dispatching to the selected version.
+    fn shim_script(&self, tool: Tool) -> String {
+        let name = tool.name();
+        let env_var = format!("TOOLCHAIN_{}_VERSION", name.to_ascii_uppercase());
+        let root = self.tool_dir(tool);
+        format!(
+            r#"{SHIM_HEADER} for {name}; regenerated by `use_version`, edits are lost.
+root='{root}'
+version="${{{env_var}:-}}"
+dir="$PWD"
+while [ -z "$version" ]; do
+  if [ -f "$dir/{VERSIONS_FILE}" ]; then
+    version=$(sed -n 's/^{name}[[:space:]][[:space:]]*\([^[:space:]#]*\).*/\1/p' "$dir/{VERSIONS_FILE}" | head -n 1)
+  fi
+  [ "$dir" = / ] && break
+  dir=$(dirname "$dir")
+done
+if [ -z "$version" ] && [ -f "$root/current" ]; then
+  version=$(cat "$root/current")
+fi
+if [ -z "$version" ]; then
+  echo "{name}: no version selected (set one with {VERSIONS_FILE} or use_version)" >&2
+  exit 1
+fi
+if [ ! -x "$root/$version/{name}" ]; then
+  echo "{name}: version $version is not installed" >&2
+  exit 1
+fi
+exec "$root/$version/{name}" "$@"
+"#,
+            root = root.display(),
+        )
+    }
+
+    fn require(&self, tool: Tool, version: &str) -> Result<()> {
+        if self.has_version(tool, version) {
+            Ok(())
+        } else {
+            Err(Error::VersionNotFound {
+                tool: tool.to_string(),
+                version: version.to_string(),
+            })
+        }
+    }
+}
+
+/// Select a version of a tool for `dir` and everything below it, by writing
+/// the tool's line in `dir/.tool-versions`. Other lines are kept.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read or written.
+pub fn set_local(dir: &Path, tool: Tool, version: &str) -> Result<PathBuf> {
+    let path = dir.join(VERSIONS_FILE);
+    let existing = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(Error::io(&path, e)),
+    };
+
+    let line = format!("{} {version}", tool.name());
+    let mut replaced = false;
+    let mut lines: Vec<String> = existing
+        .lines()
+        .map(|l| {
+            if l.split_whitespace().next() == Some(tool.name()) {
+                replaced = true;
+                line.clone()
+            } else {
+                l.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(line);
+    }
+
+    fs::write(&path, lines.join("\n") + "\n").map_err(|e| Error::io(&path, e))?;
+    Ok(path)
+}
+
+/// The version a `.tool-versions` file selects for a tool.
+fn local_version(content: &str, tool: Tool) -> Option<String> {
+    content.lines().find_map(|line| {
+        let mut words = line.split('#').next()?.split_whitespace();
+        (words.next()? == tool.name())
+            .then(|| words.next().map(str::to_string))
+            .flatten()
+    })
+}
+
+/// Whether the file at `path` is a shim written by [`VersionStore`].
+#[must_use]
+pub fn is_shim(path: &Path) -> bool {
+    fs::read(path).is_ok_and(|content| content.starts_with(SHIM_HEADER.as_bytes()))
+}
+
+/// Whether `latest` is a newer version than `current`.
+///
+/// Dotted numeric versions compare by their components, ignoring a leading
+/// `v` (`0.10.0` is newer than `0.9.1`), and a pre-release such as
+/// `1.0.0-rc1` is older than its release. Anything unparseable only counts
+/// as newer if it differs.
+#[must_use]
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    // Numeric components and the pre-release after `-`, if any; build
+    // metadata after `+` doesn't count
+    fn parse(version: &str) -> Option<(Vec<u64>, Option<&str>)> {
+        let version = version.trim_start_matches('v');
+        let version = version.split_once('+').map_or(version, |(v, _)| v);
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let parts = core
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        Some((parts, pre))
+    }
+
+    match (parse(latest), parse(current)) {
+        (Some((latest, latest_pre)), Some((current, current_pre))) => match latest.cmp(&current) {
+            std::cmp::Ordering::Equal => match (latest_pre, current_pre) {
+                (None, Some(_)) => true,
+                (Some(latest_pre), Some(current_pre)) => latest_pre > current_pre,
+                _ => false,
+            },
+            ordering => ordering.is_gt(),
+        },
+        _ => latest.trim_start_matches('v') != current.trim_start_matches('v'),
+    }
+}
+
+fn binary_name(tool: Tool) -> String {
+    format!("{}{}", tool.binary_name(), platform::executable_extension())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("7.4.1", "7.3.2"));
+        assert!(is_newer("v0.10.0", "0.9.1"));
+        assert!(!is_newer("7.4.1", "7.4.1"));
+        assert!(!is_newer("v7.3.1", "7.3.1"));
+        assert!(!is_newer("7.3.1", "7.4.0"));
+        assert!(is_newer("2024-02-01", "2024-01-15"));
+        assert!(!is_newer("2024-01-15", "2024-01-15"));
+        assert!(is_newer("nightly", "0.1.2"));
+        assert!(is_newer("latest", "unknown"));
+    }
+
+    #[test]
+    fn test_is_newer_with_pre_releases() {
+        assert!(is_newer("1.0.0", "1.0.0-rc1"));
+        assert!(!is_newer("1.0.0-rc1", "1.0.0"));
+        assert!(is_newer("1.0.0-rc2", "1.0.0-rc1"));
+        assert!(is_newer("1.0.1-rc1", "1.0.0"));
+        assert!(!is_newer("1.0.0+build.5", "1.0.0"));
+    }
+
+    fn install(store: &VersionStore, tool: Tool, version: &str) {
+        let dir = store.version_dir(tool, version);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(tool.binary_name()), b"binary").unwrap();
+    }
+
+    #[test]
+    fn test_versions_and_current() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = VersionStore::new(dir.path());
+        assert!(store.versions(Tool::Buck2).unwrap().is_empty());
+
+        install(&store, Tool::Buck2, "latest");
+        install(&store, Tool::Buck2, "2024-01-15");
+        assert_eq!(
+            store.versions(Tool::Buck2).unwrap(),
+            ["2024-01-15", "latest"]
+        );
+
+        assert!(store.current(Tool::Buck2).is_none());
+        store.set_current(Tool::Buck2, "latest").unwrap();
+        assert_eq!(store.current(Tool::Buck2).as_deref(), Some("latest"));
+        assert!(matches!(
+            store.set_current(Tool::Buck2, "2023-01-01"),
+            Err(Error::VersionNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_resolve_prefers_nearest_tool_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = VersionStore::new(dir.path().join("store"));
+        install(&store, Tool::Buck2, "latest");
+        install(&store, Tool::Buck2, "2024-01-15");
+        store.set_current(Tool::Buck2, "latest").unwrap();
+
+        let repo = dir.path().join("repo");
+        let nested = repo.join("src").join("lib");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(
+            store.resolve(Tool::Buck2, &nested).as_deref(),
+            Some("latest")
+        );
+
+        fs::write(repo.join(VERSIONS_FILE), "nodejs 20.0.0\n").unwrap();
+        set_local(&repo, Tool::Buck2, "2024-01-15").unwrap();
+        assert_eq!(
+            store.resolve(Tool::Buck2, &nested).as_deref(),
+            Some("2024-01-15")
+        );
+        assert_eq!(
+            fs::read_to_string(repo.join(VERSIONS_FILE)).unwrap(),
+            "nodejs 20.0.0\nbuck2 2024-01-15\n"
+        );
+
+        // Updating keeps a single line for the tool
+        set_local(&repo, Tool::Buck2, "latest").unwrap();
+        assert_eq!(
+            fs::read_to_string(repo.join(VERSIONS_FILE)).unwrap(),
+            "nodejs 20.0.0\nbuck2 latest\n"
+        );
+    }
+
+    #[test]
+    fn test_local_version() {
+        let content = "# pinned\nbuck2 2024-01-15 # for prelude\nbazel 7.4.1\n";
+        assert_eq!(
+            local_version(content, Tool::Buck2).as_deref(),
+            Some("2024-01-15")
+        );
+        assert_eq!(
+            local_version(content, Tool::Bazel).as_deref(),
+            Some("7.4.1")
+        );
+        assert!(local_version(content, Tool::Buildifier).is_none());
+        assert!(local_version("buck2\n", Tool::Buck2).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shim_dispatches_to_selected_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = VersionStore::new(dir.path().join("store"));
+        for version in ["2024-01-15", "latest"] {
+            let version_dir = store.version_dir(Tool::Buck2, version);
+            fs::create_dir_all(&version_dir).unwrap();
+            let binary = version_dir.join("buck2");
+            fs::write(&binary, format!("#!/bin/sh\necho {version}\n")).unwrap();
+            let mut perms = fs::metadata(&binary).unwrap().permissions();
+            std::os::unix::fs::PermissionsExt::set_mode(&mut perms, 0o755);
+            fs::set_permissions(&binary, perms).unwrap();
+        }
+        store.set_current(Tool::Buck2, "latest").unwrap();
+
+        let bin = dir.path().join("bin");
+        let shim = store.write_shim(Tool::Buck2, &bin, false).unwrap();
+        assert!(is_shim(&shim));
+
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        let run = |cwd: &Path| {
+            let output = std::process::Command::new(&shim)
+                .current_dir(cwd)
+                .env_remove("TOOLCHAIN_BUCK2_VERSION")
+                .output()
+                .unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        assert_eq!(run(&repo), "latest");
+
+        set_local(&repo, Tool::Buck2, "2024-01-15").unwrap();
+        assert_eq!(run(&repo), "2024-01-15");
+
+        // A binary that isn't a shim is left alone without force
+        let other = dir.path().join("other");
+        fs::create_dir_all(&other).unwrap();
+        fs::write(other.join("buck2"), b"binary").unwrap();
+        assert!(store.check_shim(Tool::Buck2, &other, false).is_err());
+        assert!(store.write_shim(Tool::Buck2, &other, false).is_err());
+        assert_eq!(fs::read(other.join("buck2")).unwrap(), b"binary");
+        assert!(store.write_shim(Tool::Buck2, &other, true).is_ok());
+    }
+}
@@ -1,5 +1,7 @@
 #![allow(dead_code)]
 
+use crate::ui::output::OutputFormat;
+use crate::ui::{ColorChoice, ThemeChoice};
 use clap::builder::styling::{AnsiColor, Color, Style, Styles};
 use clap::{Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
@@ -42,6 +44,7 @@ fn grouped_commands_help() -> String {
 {}
   brew          Homebrew package management
   tools         Install and manage development tools
+  which         Show which subsystem installed a binary on PATH
   dotfiles      Manage dotfiles repository and reconcile sources
   stow          Manage dotfile symlinks (native stow replacement)
   theme         Apply GNOME/GTK theme presets (Linux only)
@@ -57,6 +60,7 @@ fn grouped_commands_help() -> String {
   disk          Disk management (status, backup, repartition)
 
 {}
+  config        Get, set and validate settings in config.toml
   configs       Manage generated configuration files (git, etc.)
   locations     Manage logical locations for path abstraction
   relocate      Relocate a directory and update all path references
@@ -64,7 +68,11 @@ fn grouped_commands_help() -> String {
 
 {}
   doctor        Check system health and dependencies
+  freeze        Freeze the machine - mutating commands refuse to run
+  unfreeze      Lift a machine freeze
+  self-update   Update bossa to the latest release
   completions   Generate shell completions
+  hook          Print a shell hook for deprecated alias paths
 ",
         "Core:".green().bold(),
         "Resources:".green().bold(),
@@ -99,10 +107,55 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub quiet: bool,
 
+    /// Run mutating commands even while the machine is frozen
+    #[arg(long, global = true)]
+    pub ignore_freeze: bool,
+
+    /// When to color output (auto colors terminals, unless NO_COLOR is set)
+    #[arg(long, value_name = "WHEN", default_value = "auto", global = true)]
+    pub color: ColorChoice,
+
+    /// Never color output; same as --color never
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Print long output directly instead of through $PAGER
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+
+    /// Config profile to merge onto the base config ([profiles.<NAME>])
+    #[arg(long, value_name = "NAME", env = "BOSSA_PROFILE", global = true)]
+    pub profile: Option<String>,
+
+    /// Output format: styled text, or JSON for scripts
+    #[arg(long, value_name = "FORMAT", default_value = "human", global = true)]
+    pub format: OutputFormat,
+
+    /// Color and icon theme
+    #[arg(
+        long,
+        value_name = "THEME",
+        default_value = "dark",
+        env = "BOSSA_THEME",
+        global = true
+    )]
+    pub theme: ThemeChoice,
+
     #[command(subcommand)]
     pub command: Command,
 }
 
+impl Cli {
+    /// The color policy, with `--no-color` taking precedence
+    pub fn color_choice(&self) -> ColorChoice {
+        if self.no_color {
+            ColorChoice::Never
+        } else {
+            self.color
+        }
+    }
+}
+
 #[derive(Subcommand)]
 pub enum Command {
     /// Bootstrap a new machine (bossa nova!)
@@ -190,10 +243,24 @@ pub enum Command {
         shell: Shell,
     },
 
+    /// Print a shell hook that warns when entering a deprecated alias path
+    #[command(after_help = "Add to ~/.zshrc: eval \"$(bossa hook zsh)\"")]
+    Hook {
+        /// Shell to generate the hook for
+        #[arg(value_enum)]
+        shell: HookShell,
+    },
+
     /// Install and manage development tools
     #[command(subcommand)]
     Tools(ToolsCommand),
 
+    /// Show which subsystem installed a binary on PATH
+    Which {
+        /// Binary name (e.g., "rg")
+        name: String,
+    },
+
     /// Manage dotfile symlinks (native stow replacement)
     #[command(subcommand)]
     Stow(StowCommand),
@@ -210,21 +277,149 @@ pub enum Command {
     #[command(subcommand)]
     Locations(LocationsCommand),
 
+    /// Get, set and validate settings in config.toml
+    #[command(subcommand)]
+    Config(ConfigCommand),
+
     /// Manage generated configuration files (git, etc.)
     #[command(subcommand)]
     Configs(ConfigsCommand),
 
     /// Relocate a directory and update all path references
     Relocate(RelocateCommand),
+
+    /// Freeze the machine - mutating commands refuse to run until a date
+    Freeze(FreezeArgs),
+
+    /// Lift a machine freeze
+    Unfreeze,
+
+    /// Update bossa to the latest release
+    #[command(name = "self-update")]
+    SelfUpdate {
+        /// Only check whether a newer release is available
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+impl Command {
+    /// Name of the mutating operation this command performs, if any.
+    ///
+    /// Dry runs are not mutations. This is the single place that decides
+    /// which commands a machine freeze blocks.
+    pub fn mutation(&self) -> Option<&'static str> {
+        match self {
+            Self::Nova(args) if !args.dry_run && !args.list_stages => Some("nova"),
+            Self::Apply(args) if !args.dry_run => Some("apply"),
+            Self::Add(_) => Some("add"),
+            Self::Rm(_) => Some("rm"),
+            Self::Migrate { dry_run: false } => Some("migrate"),
+            Self::Brew(BrewCommand::Apply { dry_run: false, .. }) => Some("brew apply"),
+            Self::Brew(BrewCommand::Capture { .. }) => Some("brew capture"),
+            Self::Brew(BrewCommand::Outdated { upgrade: true, .. }) => Some("brew upgrade"),
+            Self::Brew(BrewCommand::Orphans { remove: true, .. }) => Some("brew orphans"),
+            Self::Brew(BrewCommand::Uninstall { .. }) => Some("brew uninstall"),
+            Self::Collections(CollectionsCommand::Sync { dry_run: false, .. }) => {
+                Some("collections sync")
+            }
+            Self::Collections(CollectionsCommand::Clean { dry_run: false, .. }) => {
+                Some("collections clean")
+            }
+            Self::Collections(CollectionsCommand::Audit { fix: true, .. }) => {
+                Some("collections audit")
+            }
+            Self::Collections(CollectionsCommand::Snapshot { .. }) => Some("collections snapshot"),
+            Self::Collections(CollectionsCommand::Add { .. }) => Some("collections add"),
+            Self::Collections(CollectionsCommand::Rm { .. }) => Some("collections rm"),
+            Self::Manifest(ManifestCommand::Duplicates { delete: true, .. }) => {
+                Some("manifest duplicates")
+            }
+            Self::Disk(DiskCommand::Backup { dry_run: false, .. }) => Some("disk backup"),
+            Self::Disk(DiskCommand::Repartition { dry_run: false, .. }) => Some("disk repartition"),
+            Self::Refs(RefsCommand::Sync(args)) if !args.dry_run => Some("refs sync"),
+            Self::Refs(RefsCommand::Snapshot) => Some("refs snapshot"),
+            Self::Refs(RefsCommand::Audit { fix: true }) => Some("refs audit"),
+            Self::Refs(RefsCommand::Add { .. }) => Some("refs add"),
+            Self::Refs(RefsCommand::Remove { .. }) => Some("refs remove"),
+            Self::Dotfiles(DotfilesCommand::Sync(args)) if !args.dry_run => Some("dotfiles sync"),
+            Self::Dotfiles(DotfilesCommand::Reconcile { dry_run: false, .. }) => {
+                Some("dotfiles reconcile")
+            }
+            Self::Stow(StowCommand::Sync { dry_run: false, .. }) => Some("stow sync"),
+            Self::Stow(StowCommand::Add { .. }) => Some("stow add"),
+            Self::Stow(StowCommand::Rm { .. }) => Some("stow rm"),
+            Self::Stow(StowCommand::Unlink { dry_run: false, .. }) => Some("stow unlink"),
+            Self::Stow(StowCommand::Init { .. }) => Some("stow init"),
+            Self::Caches(CachesCommand::Apply { dry_run: false, .. }) => Some("caches apply"),
+            Self::Caches(CachesCommand::Init { .. }) => Some("caches init"),
+            Self::Caches(CachesCommand::Clean { dry_run: false, .. }) => Some("caches clean"),
+            Self::Cellar(CellarCommand::Init) => Some("cellar init"),
+            Self::Cellar(CellarCommand::Stash { dry_run: false }) => Some("cellar stash"),
+            Self::Cellar(CellarCommand::Trim { dry_run: false }) => Some("cellar trim"),
+            Self::Cellar(CellarCommand::Restore { .. }) => Some("cellar restore"),
+            Self::Cellar(CellarCommand::Sync { dry_run: false, .. }) => Some("cellar sync"),
+            Self::Cellar(CellarCommand::Promote { .. }) => Some("cellar promote"),
+            Self::Cellar(CellarCommand::Demote { .. }) => Some("cellar demote"),
+            Self::Tools(ToolsCommand::Install { .. }) => Some("tools install"),
+            Self::Tools(ToolsCommand::InstallContainer { .. }) => Some("tools install-container"),
+            Self::Tools(ToolsCommand::Apply { dry_run: false, .. }) => Some("tools apply"),
+            Self::Tools(ToolsCommand::Uninstall { dry_run: false, .. }) => Some("tools uninstall"),
+            Self::Configs(ConfigsCommand::Apply { dry_run: false, .. }) => Some("configs apply"),
+            Self::Theme(ThemeCommand::Apply { dry_run: false, .. }) => Some("theme apply"),
+            Self::Defaults(DefaultsCommand::Set { .. }) => Some("defaults set"),
+            Self::Locations(LocationsCommand::Add { .. }) => Some("locations add"),
+            Self::Locations(LocationsCommand::Remove { .. }) => Some("locations remove"),
+            Self::Locations(LocationsCommand::Alias { .. }) => Some("locations alias"),
+            Self::Config(ConfigCommand::Set { .. }) => Some("config set"),
+            Self::Config(ConfigCommand::Unset { .. }) => Some("config unset"),
+            Self::Config(ConfigCommand::Edit) => Some("config edit"),
+            Self::Relocate(args) if !args.dry_run && !args.scan_only => Some("relocate"),
+            Self::ICloud(ICloudCommand::Evict { dry_run: false, .. }) => Some("icloud evict"),
+            Self::ICloud(ICloudCommand::Free { dry_run: false, .. }) => Some("icloud free"),
+            Self::ICloud(ICloudCommand::Download { .. }) => Some("icloud download"),
+            Self::ICloud(ICloudCommand::Pin { .. }) => Some("icloud pin"),
+            Self::ICloud(ICloudCommand::Unpin { .. }) => Some("icloud unpin"),
+            Self::SelfUpdate { check: false } => Some("self-update"),
+            _ => None,
+        }
+    }
+}
+
+/// File order for `bossa icloud free`
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum FreeStrategy {
+    /// Biggest files first (fewest evictions)
+    #[default]
+    Largest,
+    /// Least recently modified files first
+    Coldest,
+}
+
+/// Shells supported by `bossa hook`
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum HookShell {
+    Zsh,
 }
 
 // ============================================================================
 // Command Arguments
 // ============================================================================
 
+#[derive(Parser)]
+pub struct FreezeArgs {
+    /// Date (YYYY-MM-DD, inclusive) or RFC 3339 timestamp the freeze lasts until
+    #[arg(long)]
+    pub until: String,
+
+    /// Why the machine is frozen (shown when a command is refused)
+    #[arg(long)]
+    pub reason: Option<String>,
+}
+
 #[derive(Parser)]
 pub struct StatusArgs {
-    /// Target resource (e.g., "collections", "collections.refs", "workspaces", "storage.t9")
+    /// Target resource (e.g., "collections", "collections.refs", "workspaces", "storage.t9", "brew")
     pub target: Option<String>,
 }
 
@@ -246,6 +441,10 @@ pub struct ApplyArgs {
 pub struct DiffArgs {
     /// Target resource (e.g., "collections", "collections.refs", "workspaces", "storage.t9")
     pub target: Option<String>,
+
+    /// Print the changes and a summary as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Parser)]
@@ -395,6 +594,25 @@ pub enum CachesCommand {
         #[arg(short, long)]
         force: bool,
     },
+
+    /// Reclaim space from Homebrew (old versions, downloads, unneeded deps)
+    Clean {
+        /// Show what would be removed and how much space it frees
+        #[arg(long, short = 'n')]
+        dry_run: bool,
+
+        /// Remove cached downloads older than this many days
+        #[arg(long, value_name = "DAYS")]
+        prune: Option<u32>,
+
+        /// Also remove downloads for the latest versions
+        #[arg(long)]
+        scrub: bool,
+
+        /// Keep dependencies nothing needs anymore (skip `brew autoremove`)
+        #[arg(long)]
+        no_autoremove: bool,
+    },
 }
 
 // ============================================================================
@@ -568,6 +786,10 @@ pub enum ManifestCommand {
     Stats {
         /// Path to show stats for
         path: String,
+
+        /// Only count files under this subtree (relative to the scanned path)
+        #[arg(long = "path", value_name = "PREFIX")]
+        prefix: Option<String>,
     },
 
     /// Find and optionally delete duplicate files
@@ -701,6 +923,18 @@ pub enum BrewCommand {
         /// Path to Brewfile (defaults to ~/dotfiles/Brewfile)
         #[arg(long, short)]
         file: Option<String>,
+
+        /// Only install packages from these Brewfile groups (repeatable)
+        #[arg(long = "group", short = 'g')]
+        groups: Vec<String>,
+
+        /// Machine tag enabling `# only:` blocks (repeatable; the hostname is always one)
+        #[arg(long = "tag", short = 't')]
+        tags: Vec<String>,
+
+        /// Install missing packages N at a time instead of via `brew bundle` (max 128)
+        #[arg(long, short, value_name = "N", value_parser = clap::value_parser!(u16).range(1..=128))]
+        jobs: Option<u16>,
     },
 
     /// Capture installed packages to Brewfile
@@ -708,6 +942,18 @@ pub enum BrewCommand {
         /// Output path (defaults to ~/dotfiles/Brewfile)
         #[arg(long)]
         output: Option<String>,
+
+        /// Add each formula's and cask's description as a trailing comment
+        #[arg(long)]
+        describe: bool,
+
+        /// Write each third-party tap in its own section with its packages
+        #[arg(long)]
+        by_tap: bool,
+
+        /// Capture the Homebrew installed in this prefix (e.g. /usr/local)
+        #[arg(long, value_name = "PATH")]
+        prefix: Option<String>,
     },
 
     /// Detect drift between installed packages and Brewfile
@@ -715,6 +961,26 @@ pub enum BrewCommand {
         /// Path to Brewfile (defaults to ~/dotfiles/Brewfile)
         #[arg(long, short)]
         file: Option<String>,
+
+        /// Only report missing packages from these Brewfile groups (repeatable)
+        #[arg(long = "group", short = 'g')]
+        groups: Vec<String>,
+
+        /// Machine tag enabling `# only:` blocks (repeatable; the hostname is always one)
+        #[arg(long = "tag", short = 't')]
+        tags: Vec<String>,
+
+        /// Output the drift report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Ignore cached package metadata and query Homebrew again
+        #[arg(long)]
+        refresh: bool,
+
+        /// Audit the Homebrew installed in this prefix (e.g. /usr/local)
+        #[arg(long, value_name = "PATH")]
+        prefix: Option<String>,
     },
 
     /// List installed Homebrew packages
@@ -723,6 +989,77 @@ pub enum BrewCommand {
         #[arg(long, short)]
         r#type: Option<String>,
     },
+
+    /// Show formulas and casks with newer versions available
+    ///
+    /// Examples:
+    ///   bossa brew outdated                  # All outdated packages
+    ///   bossa brew outdated --type cask      # Only casks
+    ///   bossa brew outdated git node --upgrade
+    Outdated {
+        /// Only show these packages (if empty, shows all)
+        #[arg(value_name = "PACKAGE")]
+        packages: Vec<String>,
+
+        /// Filter by package type (brew, cask)
+        #[arg(long, short)]
+        r#type: Option<String>,
+
+        /// Upgrade the listed packages
+        #[arg(long)]
+        upgrade: bool,
+
+        /// Output as JSON
+        #[arg(long, conflicts_with = "upgrade")]
+        json: bool,
+
+        /// Ignore cached package metadata and query Homebrew again
+        #[arg(long)]
+        refresh: bool,
+    },
+
+    /// Find formulas installed as dependencies that nothing needs anymore
+    ///
+    /// Examples:
+    ///   bossa brew orphans                # List orphaned formulas
+    ///   bossa brew orphans --remove       # Uninstall them (asks first)
+    ///   bossa brew orphans --remove --yes
+    Orphans {
+        /// Uninstall the orphaned formulas
+        #[arg(long)]
+        remove: bool,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Output as JSON
+        #[arg(long, conflicts_with = "remove")]
+        json: bool,
+    },
+
+    /// Uninstall formulas or casks
+    ///
+    /// Examples:
+    ///   bossa brew uninstall wget
+    ///   bossa brew uninstall --cask slack --zap   # Also remove its app data
+    Uninstall {
+        /// Packages to uninstall
+        #[arg(required = true, value_name = "PACKAGE")]
+        packages: Vec<String>,
+
+        /// Uninstall casks instead of formulas
+        #[arg(long)]
+        cask: bool,
+
+        /// Also remove the casks' preferences, caches and support files
+        #[arg(long, requires = "cask")]
+        zap: bool,
+
+        /// Uninstall formulas even if other formulas depend on them
+        #[arg(long, conflicts_with = "cask")]
+        ignore_dependencies: bool,
+    },
 }
 
 // ============================================================================
@@ -749,6 +1086,10 @@ pub enum ICloudCommand {
         /// Show only cloud-only files (evicted)
         #[arg(long)]
         cloud: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Find large local files that could be evicted
@@ -759,6 +1100,18 @@ pub enum ICloudCommand {
         /// Minimum file size to consider (e.g., "100MB", "1GB")
         #[arg(long, short, default_value = "100MB")]
         min_size: String,
+
+        /// Only files with this extension (repeatable, e.g., "mov", "zip")
+        #[arg(long = "ext", value_name = "EXT")]
+        extensions: Vec<String>,
+
+        /// Also skip the contents of packages with this extension (repeatable)
+        #[arg(long = "exclude-bundle", value_name = "EXT")]
+        exclude_bundles: Vec<String>,
+
+        /// Look inside packages with this extension, e.g., "photoslibrary" (repeatable)
+        #[arg(long = "allow-bundle", value_name = "EXT")]
+        allow_bundles: Vec<String>,
     },
 
     /// Evict files to free local space (keeps files in iCloud)
@@ -774,9 +1127,48 @@ pub enum ICloudCommand {
         #[arg(long)]
         min_size: Option<String>,
 
+        /// Only evict files not modified for this long (e.g., "90d", "12w", "6mo")
+        #[arg(long)]
+        older_than: Option<String>,
+
+        /// Skip files matching this glob (repeatable, e.g., "*.key", "Archive/**")
+        #[arg(long)]
+        exclude: Vec<String>,
+
+        /// Only evict files whose content is in this manifest database
+        /// (e.g., a backup drive scanned with `bossa manifest scan`)
+        #[arg(long, value_name = "MANIFEST_DB")]
+        verified_in: Option<String>,
+
         /// Preview what would be evicted without doing it
         #[arg(long)]
         dry_run: bool,
+
+        /// Output as JSON (candidates plus the bulk result)
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Evict files until a target amount of space is freed
+    Free {
+        /// Bytes to free (e.g., "50GB")
+        #[arg(long)]
+        target: String,
+
+        /// Directory to pick files from (defaults to iCloud Drive root)
+        path: Option<String>,
+
+        /// Which files go first
+        #[arg(long, value_enum, default_value_t)]
+        strategy: FreeStrategy,
+
+        /// Show the plan without evicting
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output the plan (and result) as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Download files from iCloud to local storage
@@ -788,6 +1180,48 @@ pub enum ICloudCommand {
         #[arg(long, short)]
         recursive: bool,
     },
+
+    /// Pin a file or directory so it is never evicted
+    Pin {
+        /// Path to pin (file or directory)
+        path: String,
+    },
+
+    /// Remove a pin added with `pin`
+    Unpin {
+        /// Path to unpin
+        path: String,
+    },
+
+    /// List pinned paths
+    ListPins,
+
+    /// List iCloud Drive and app-specific containers
+    Containers,
+
+    /// Watch files and print sync state changes until interrupted
+    Watch {
+        /// Path to watch (defaults to iCloud Drive root)
+        path: Option<String>,
+
+        /// Seconds between polls
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+
+        /// Print one JSON object per event
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Find sync conflict copies to review
+    Conflicts {
+        /// Path to search (defaults to iCloud Drive root)
+        path: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 // ============================================================================
@@ -891,6 +1325,48 @@ pub struct NovaArgs {
     /// Number of parallel jobs (max 128)
     #[arg(short, long, value_parser = clap::value_parser!(u16).range(1..=128))]
     pub jobs: Option<u16>,
+
+    /// Only install these brew package groups in the packages stage (comma-separated)
+    #[arg(long)]
+    pub group: Option<String>,
+
+    /// Only apply resources with one of these tags (comma-separated)
+    ///
+    /// Resources are tagged with their stage, and brew packages with their group.
+    #[arg(long)]
+    pub tags: Option<String>,
+
+    /// Don't apply resources with any of these tags (comma-separated)
+    #[arg(long)]
+    pub skip_tags: Option<String>,
+
+    /// Give up on a resource still applying after this many seconds (0 waits forever)
+    #[arg(long, value_name = "SECS", default_value_t = 1800)]
+    pub timeout: u64,
+
+    /// Continue an interrupted run, skipping resources it already applied
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Adopt what's already configured and suggest config entries for the rest
+    ///
+    /// Nothing is changed. Prints config snippets that would keep values
+    /// already set on this machine, e.g. defaults changed by hand.
+    #[arg(long, conflicts_with_all = ["dry_run", "resume"])]
+    pub import: bool,
+
+    /// Write a report of the run for CI: JUnit XML if PATH ends in .xml, else JSON
+    ///
+    /// On a dry run, each change that would be made counts as a failure.
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<std::path::PathBuf>,
+
+    /// Stream apply events as NDJSON instead of the interactive UI
+    ///
+    /// Only events go to stdout; messages and tool output go to stderr.
+    /// Requires --yes or --dry-run, as there is no one to confirm.
+    #[arg(long)]
+    pub json: bool,
 }
 
 // ============================================================================
@@ -988,6 +1464,7 @@ pub enum NovaStage {
     Essential,
     Brew,
     Cellar,
+    Tools,
     Pnpm,
     Dock,
     Ecosystem,
@@ -1011,6 +1488,7 @@ impl NovaStage {
             Self::Essential,
             Self::Brew,
             Self::Cellar,
+            Self::Tools,
             Self::Pnpm,
             Self::Dock,
             Self::Ecosystem,
@@ -1034,6 +1512,7 @@ impl NovaStage {
             Self::Essential => "essential",
             Self::Brew => "brew",
             Self::Cellar => "cellar",
+            Self::Tools => "tools",
             Self::Pnpm => "pnpm",
             Self::Dock => "dock",
             Self::Ecosystem => "ecosystem",
@@ -1057,6 +1536,7 @@ impl NovaStage {
             Self::Essential => "Essential packages (stow, jq, gh, etc.)",
             Self::Brew => "Full Brewfile packages",
             Self::Cellar => "Homebrew cellar sync to external SSD",
+            Self::Tools => "Build tools from [toolchain], in parallel",
             Self::Pnpm => "Node packages via pnpm",
             Self::Dock => "Dock configuration",
             Self::Ecosystem => "Ecosystem extensions",
@@ -1080,6 +1560,7 @@ impl NovaStage {
             "essential" => Some(Self::Essential),
             "brew" => Some(Self::Brew),
             "cellar" => Some(Self::Cellar),
+            "tools" => Some(Self::Tools),
             "pnpm" => Some(Self::Pnpm),
             "dock" => Some(Self::Dock),
             "ecosystem" => Some(Self::Ecosystem),
@@ -1475,6 +1956,13 @@ pub enum LocationsCommand {
         /// Location name this should resolve to
         location: String,
     },
+
+    /// Print the canonical path for a path under a deprecated alias
+    #[command(after_help = "With --quiet, prints only the canonical path (nothing if not aliased)")]
+    Resolve {
+        /// Path to check (defaults to the current directory)
+        path: Option<String>,
+    },
 }
 
 // ============================================================================
@@ -1519,6 +2007,46 @@ pub struct RelocateCommand {
     pub force: bool,
 }
 
+// ============================================================================
+// Config Commands
+// ============================================================================
+
+/// Read and change config.toml
+#[derive(Debug, Subcommand)]
+#[command(after_help = "Keys are dotted paths into config.toml, e.g. packages.brew.formulas")]
+pub enum ConfigCommand {
+    /// Print a setting
+    Get {
+        /// Dotted key (e.g., "nova.skip", "collections.refs.path")
+        key: String,
+    },
+
+    /// Change a setting
+    #[command(after_help = "\
+VALUE is read as TOML when it parses as TOML, and as a string otherwise:
+  bossa config set packages.brew.formulas '[\"git\", \"ripgrep\"]'
+  bossa config set collections.refs.path ~/dev/refs")]
+    Set {
+        /// Dotted key
+        key: String,
+
+        /// New value
+        value: String,
+    },
+
+    /// Remove a setting, so its default applies
+    Unset {
+        /// Dotted key
+        key: String,
+    },
+
+    /// Open config.toml in $VISUAL or $EDITOR, then validate it
+    Edit,
+
+    /// Check config.toml against the schema
+    Validate,
+}
+
 // ============================================================================
 // Configs Commands
 // ============================================================================
@@ -1604,6 +2132,104 @@ impl fmt::Display for Target {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_command_mutation() {
+        let mutation = |args: &[&str]| Cli::parse_from(args).command.mutation();
+
+        assert_eq!(mutation(&["bossa", "apply"]), Some("apply"));
+        assert_eq!(mutation(&["bossa", "apply", "--dry-run"]), None);
+        assert_eq!(mutation(&["bossa", "brew", "apply"]), Some("brew apply"));
+        assert_eq!(mutation(&["bossa", "brew", "audit"]), None);
+        assert_eq!(mutation(&["bossa", "brew", "outdated"]), None);
+        assert_eq!(
+            mutation(&["bossa", "brew", "outdated", "--upgrade"]),
+            Some("brew upgrade")
+        );
+        assert_eq!(
+            mutation(&["bossa", "icloud", "evict", "Movies"]),
+            Some("icloud evict")
+        );
+        assert_eq!(
+            mutation(&["bossa", "icloud", "evict", "Movies", "--dry-run"]),
+            None
+        );
+        assert_eq!(
+            mutation(&["bossa", "icloud", "free", "--target", "50GB"]),
+            Some("icloud free")
+        );
+        assert_eq!(
+            mutation(&["bossa", "icloud", "free", "--target", "50GB", "--dry-run"]),
+            None
+        );
+        assert_eq!(
+            mutation(&["bossa", "icloud", "pin", "Documents"]),
+            Some("icloud pin")
+        );
+        assert_eq!(
+            mutation(&["bossa", "icloud", "unpin", "Documents"]),
+            Some("icloud unpin")
+        );
+        assert_eq!(mutation(&["bossa", "icloud", "list-pins"]), None);
+        assert_eq!(
+            mutation(&[
+                "bossa",
+                "tools",
+                "install",
+                "rg",
+                "--url",
+                "https://example.com/rg.tar.gz"
+            ]),
+            Some("tools install")
+        );
+        assert_eq!(
+            mutation(&[
+                "bossa",
+                "tools",
+                "install-container",
+                "rg",
+                "--image",
+                "alpine",
+                "--binary-path",
+                "/usr/bin/rg"
+            ]),
+            Some("tools install-container")
+        );
+        assert_eq!(
+            mutation(&["bossa", "tools", "uninstall", "rg"]),
+            Some("tools uninstall")
+        );
+        assert_eq!(
+            mutation(&["bossa", "tools", "uninstall", "rg", "--dry-run"]),
+            None
+        );
+        assert_eq!(mutation(&["bossa", "tools", "list"]), None);
+        assert_eq!(
+            mutation(&["bossa", "config", "set", "nova.skip", "[]"]),
+            Some("config set")
+        );
+        assert_eq!(
+            mutation(&["bossa", "config", "unset", "nova.skip"]),
+            Some("config unset")
+        );
+        assert_eq!(mutation(&["bossa", "config", "edit"]), Some("config edit"));
+        assert_eq!(mutation(&["bossa", "config", "get", "nova.skip"]), None);
+        assert_eq!(mutation(&["bossa", "config", "validate"]), None);
+        assert_eq!(
+            mutation(&[
+                "bossa",
+                "relocate",
+                "~/dev",
+                "/Volumes/T9/dev",
+                "--scan-only"
+            ]),
+            None
+        );
+        assert_eq!(mutation(&["bossa", "status"]), None);
+        assert_eq!(mutation(&["bossa", "unfreeze"]), None);
+        assert_eq!(mutation(&["bossa", "self-update"]), Some("self-update"));
+        assert_eq!(mutation(&["bossa", "self-update", "--check"]), None);
+    }
+
     #[test]
     fn test_target_parse_simple() {
         let target = Target::parse("collections");
@@ -1710,9 +2336,9 @@ mod tests {
     #[test]
     fn test_nova_stage_all() {
         let stages = NovaStage::all();
-        assert_eq!(stages.len(), 18);
+        assert_eq!(stages.len(), 19);
         assert_eq!(stages[0], NovaStage::Defaults);
-        assert_eq!(stages[17], NovaStage::Workspaces);
+        assert_eq!(stages[18], NovaStage::Workspaces);
     }
 
     #[test]
@@ -2,23 +2,75 @@
 
 use anyhow::{Context, Result};
 use colored::Colorize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::Context as AppContext;
 use crate::cli::BrewCommand;
+use crate::paths;
 use crate::progress;
+use crate::runner;
+use crate::schema::BossaConfig;
 use crate::ui;
 
-pub fn run(_ctx: &AppContext, cmd: BrewCommand) -> Result<()> {
+pub fn run(ctx: &AppContext, cmd: BrewCommand) -> Result<()> {
     match cmd {
         BrewCommand::Apply {
             essential,
             dry_run,
             file,
-        } => apply(essential, dry_run, file),
-        BrewCommand::Capture { output } => capture(output),
-        BrewCommand::Audit { file } => audit(file),
+            groups,
+            tags,
+            jobs,
+        } => apply(
+            essential,
+            dry_run,
+            file,
+            &groups,
+            &tags,
+            jobs.map(usize::from),
+        ),
+        BrewCommand::Capture {
+            output,
+            describe,
+            by_tap,
+            prefix,
+        } => capture(output, describe, by_tap, prefix.as_deref()),
+        BrewCommand::Audit {
+            file,
+            groups,
+            tags,
+            json,
+            refresh,
+            prefix,
+        } => {
+            if json || ctx.is_json() {
+                audit_json(file, &groups, &tags, refresh, prefix.as_deref())
+            } else {
+                audit(file, &groups, &tags, refresh, prefix.as_deref())
+            }
+        }
         BrewCommand::List { r#type } => list(r#type),
+        BrewCommand::Outdated {
+            packages,
+            r#type,
+            upgrade,
+            json,
+            refresh,
+        } => outdated(&packages, r#type, upgrade, json, refresh),
+        BrewCommand::Orphans { remove, yes, json } => orphans(remove, yes, json),
+        BrewCommand::Uninstall {
+            packages,
+            cask,
+            zap,
+            ignore_dependencies,
+        } => uninstall(
+            &packages,
+            cask,
+            &brewkit::UninstallOptions {
+                zap,
+                ignore_dependencies,
+            },
+        ),
     }
 }
 
@@ -35,6 +87,82 @@ fn get_brewfile_path(file: Option<String>) -> PathBuf {
     file.map_or_else(default_brewfile_path, PathBuf::from)
 }
 
+/// Tags of this machine for `# only:` blocks: its short hostname plus `extra`.
+fn machine_tags(extra: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = runner::run_capture("hostname", &["-s"])
+        .ok()
+        .filter(|h| !h.is_empty())
+        .into_iter()
+        .collect();
+    tags.extend(extra.iter().cloned());
+    tags
+}
+
+/// Homebrew section of `bossa status`: Brewfile entries not installed.
+///
+/// Uses `brew bundle check` rather than a full audit to stay quick. Skipped
+/// when there is no Brewfile or Homebrew isn't installed.
+pub fn show_status(ctx: &AppContext) -> Result<()> {
+    let brewfile_path = default_brewfile_path();
+    if !brewfile_path.exists() {
+        return Ok(());
+    }
+    let Ok(client) = brewkit::Client::new() else {
+        return Ok(());
+    };
+    let client = client.with_tags(machine_tags(&[]));
+
+    if ctx.is_json() {
+        let record = ui::output::Record::untitled().field("type", "brew");
+        let record = match client.bundle_check(&brewfile_path) {
+            Ok(missing) => record.field("missing", missing),
+            Err(e) => record.field("error", e.to_string()),
+        };
+        return ctx.output().record(&record);
+    }
+
+    ui::section("Homebrew");
+    match client.bundle_check(&brewfile_path) {
+        Ok(missing) if missing.is_empty() => {
+            println!("  {} {}", "✓".green(), "Brewfile".bold());
+        }
+        Ok(missing) => {
+            println!(
+                "  {} {} {}",
+                "⚠".yellow(),
+                "Brewfile".bold(),
+                format!("({} missing)", missing.len()).dimmed()
+            );
+            if !ctx.quiet {
+                for pkg in &missing {
+                    println!("    {} {}", colored_type(&pkg.package_type), pkg.name);
+                }
+                ui::dim("    Run 'bossa brew apply' to install them");
+            }
+        }
+        Err(e) => {
+            println!(
+                "  {} {} {}",
+                "✗".red(),
+                "Brewfile".bold(),
+                format!("- {e}").dimmed()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Warn about requested groups that don't appear in the Brewfile.
+fn warn_unknown_groups(brewfile: &brewkit::Brewfile, groups: &[String]) {
+    let known = brewfile.groups();
+    for group in groups {
+        if !known.contains(&group.as_str()) {
+            ui::warn(&format!("Group '{group}' not found in Brewfile"));
+        }
+    }
+}
+
 /// Format a package type with color.
 fn colored_type(pkg_type: &brewkit::PackageType) -> colored::ColoredString {
     match pkg_type {
@@ -43,6 +171,7 @@ fn colored_type(pkg_type: &brewkit::PackageType) -> colored::ColoredString {
         brewkit::PackageType::Cask => "cask".magenta(),
         brewkit::PackageType::Mas => "mas".yellow(),
         brewkit::PackageType::Vscode => "vscode".cyan(),
+        brewkit::PackageType::Custom => "custom".white(),
     }
 }
 
@@ -81,14 +210,14 @@ fn print_package_summary(brewfile: &brewkit::Brewfile) {
 /// When Homebrew is not installed, offers to install it interactively
 /// and retries.
 fn create_client() -> Result<brewkit::Client, String> {
-    match brewkit::Client::new() {
+    match brewkit::Client::with_info_cache(info_cache(None)) {
         Ok(c) => Ok(c),
         Err(brewkit::Error::BrewNotFound) => {
             // Offer to install Homebrew
             match super::nova::install_homebrew(false) {
                 Ok(()) => {
                     // Retry after install
-                    brewkit::Client::new().map_err(|e| {
+                    brewkit::Client::with_info_cache(info_cache(None)).map_err(|e| {
                         format!("Failed to initialize Homebrew client after install: {e}")
                     })
                 }
@@ -99,8 +228,72 @@ fn create_client() -> Result<brewkit::Client, String> {
     }
 }
 
-fn apply(essential: bool, dry_run: bool, file: Option<String>) -> Result<()> {
-    if essential {
+/// Client for the Homebrew installed in `prefix`, or the default one.
+fn create_client_for(prefix: Option<&str>) -> Result<brewkit::Client, String> {
+    let Some(prefix) = prefix.map(Path::new) else {
+        return create_client();
+    };
+    brewkit::Client::with_prefix(prefix, info_cache(Some(prefix)))
+        .map_err(|e| format!("No Homebrew in {}: {e}", prefix.display()))
+}
+
+/// Cache of `brew info` metadata shared between bossa runs.
+///
+/// Kept in the state directory for `packages.brew.info_cache_ttl` seconds,
+/// in a separate file for each explicitly chosen prefix.
+fn info_cache(prefix: Option<&Path>) -> brewkit::InfoCache {
+    let ttl = BossaConfig::load()
+        .map(|config| config.packages.brew.info_cache_ttl())
+        .unwrap_or_else(|_| brewkit::InfoCache::default().ttl());
+    let file = prefix.map_or_else(
+        || "brew-info.json".to_string(),
+        |p| format!("brew-info{}.json", p.to_string_lossy().replace('/', "-")),
+    );
+    match paths::state_dir() {
+        Ok(dir) if !ttl.is_zero() => brewkit::InfoCache::on_disk(dir.join(file), ttl),
+        _ => brewkit::InfoCache::in_memory(ttl),
+    }
+}
+
+/// Point out other Homebrew installations when the default one is used.
+fn note_other_prefixes(prefix: Option<&str>) {
+    if prefix.is_some() {
+        return;
+    }
+    let prefixes = brewkit::detect_prefixes();
+    if let [default, others @ ..] = prefixes.as_slice()
+        && !others.is_empty()
+    {
+        let others: Vec<_> = others.iter().map(|p| p.display().to_string()).collect();
+        ui::dim(&format!(
+            "Using Homebrew in {}; also installed in {} (select with --prefix)",
+            default.display(),
+            others.join(", ")
+        ));
+    }
+}
+
+/// Fetch `brew info` metadata again when `--refresh` was passed.
+fn refresh_info(client: &brewkit::Client, refresh: bool) -> Result<()> {
+    if refresh {
+        client
+            .refresh_info()
+            .context("Failed to refresh Homebrew package metadata")?;
+    }
+    Ok(())
+}
+
+fn apply(
+    essential: bool,
+    dry_run: bool,
+    file: Option<String>,
+    groups: &[String],
+    tags: &[String],
+    jobs: Option<usize>,
+) -> Result<()> {
+    if !groups.is_empty() {
+        ui::header(&format!("Installing Groups: {}", groups.join(", ")));
+    } else if essential {
         ui::header("Installing Essential Packages");
         ui::dim(
             "Only taps and formulas will be installed (no casks, mas apps, or vscode extensions)",
@@ -127,7 +320,7 @@ fn apply(essential: bool, dry_run: bool, file: Option<String>) -> Result<()> {
 
     // Create brewkit client
     let client = match create_client() {
-        Ok(c) => c,
+        Ok(c) => c.with_tags(machine_tags(tags)),
         Err(msg) => {
             ui::error(&msg);
             return Ok(());
@@ -140,6 +333,15 @@ fn apply(essential: bool, dry_run: bool, file: Option<String>) -> Result<()> {
         .parse_brewfile(&brewfile_path)
         .context("Failed to parse Brewfile")?;
 
+    // Drop `# only:` packages meant for other machines
+    brewfile = brewfile.for_tags(client.tags());
+
+    // Filter to the requested groups
+    if !groups.is_empty() {
+        warn_unknown_groups(&brewfile, groups);
+        brewfile = brewfile.select_groups(groups);
+    }
+
     // Filter to essential packages if requested
     if essential {
         brewfile.packages.retain(|p| {
@@ -172,31 +374,46 @@ fn apply(essential: bool, dry_run: bool, file: Option<String>) -> Result<()> {
             for pkg in &audit_result.missing {
                 println!("    {} {}", colored_type(&pkg.package_type), pkg.name);
             }
+
+            let taps = client.missing_taps(&audit_result.missing)?;
+            if !taps.is_empty() {
+                println!();
+                ui::info("Taps that would be added for namespaced packages:");
+                for tap in &taps {
+                    println!("    {} {}", colored_type(&tap.package_type), tap.name);
+                }
+            }
         }
 
         return Ok(());
     }
 
-    // For essential mode, we need to write a temporary Brewfile
-    let bundle_path = if essential {
-        let temp_dir = std::env::temp_dir();
-        let temp_path = temp_dir.join("bossa_essential_brewfile");
-        client.write_brewfile(&brewfile, &temp_path)?;
-        temp_path
+    let result = if let Some(jobs) = jobs {
+        install_missing(&client, &brewfile, jobs)?
     } else {
-        brewfile_path
-    };
+        // When filtering, we need to write a temporary Brewfile
+        let filtered = essential || !groups.is_empty();
+        let bundle_path = if filtered {
+            let temp_dir = std::env::temp_dir();
+            let temp_path = temp_dir.join("bossa_filtered_brewfile");
+            client.write_brewfile(&brewfile, &temp_path)?;
+            temp_path
+        } else {
+            brewfile_path
+        };
 
-    // Run brew bundle
-    let pb = progress::spinner("Running brew bundle...");
-    let result = client.bundle(&bundle_path)?;
+        // Run brew bundle
+        let pb = progress::spinner("Running brew bundle...");
+        let result = client.bundle_with_progress(&bundle_path, &progress::BrewBar(pb.clone()))?;
 
-    // Clean up temp file if we created one
-    if essential {
-        let _ = std::fs::remove_file(&bundle_path);
-    }
+        // Clean up temp file if we created one
+        if filtered {
+            let _ = std::fs::remove_file(&bundle_path);
+        }
 
-    progress::finish_success(&pb, "Bundle complete");
+        progress::finish_success(&pb, "Bundle complete");
+        result
+    };
 
     // Report results
     println!();
@@ -208,9 +425,12 @@ fn apply(essential: bool, dry_run: bool, file: Option<String>) -> Result<()> {
             "Installed:".green().bold(),
             result.installed.len()
         );
-        for name in &result.installed {
-            println!("    {}", name.green());
-        }
+        let names: Vec<_> = result
+            .installed
+            .iter()
+            .map(|n| n.green().to_string())
+            .collect();
+        ui::print_columns(&names);
         println!();
     }
 
@@ -221,9 +441,12 @@ fn apply(essential: bool, dry_run: bool, file: Option<String>) -> Result<()> {
             "Upgraded:".blue().bold(),
             result.upgraded.len()
         );
-        for name in &result.upgraded {
-            println!("    {}", name.blue());
-        }
+        let names: Vec<_> = result
+            .upgraded
+            .iter()
+            .map(|n| n.blue().to_string())
+            .collect();
+        ui::print_columns(&names);
         println!();
     }
 
@@ -272,13 +495,58 @@ fn apply(essential: bool, dry_run: bool, file: Option<String>) -> Result<()> {
     Ok(())
 }
 
-fn capture(output: Option<String>) -> Result<()> {
+/// Install the packages missing from `brewfile`, `jobs` at a time.
+fn install_missing(
+    client: &brewkit::Client,
+    brewfile: &brewkit::Brewfile,
+    jobs: usize,
+) -> Result<brewkit::BundleResult> {
+    let missing = client.audit(brewfile)?.missing;
+    let mut result = brewkit::BundleResult {
+        skipped: brewfile
+            .packages
+            .iter()
+            .filter(|p| !missing.contains(p))
+            .map(|p| p.name.clone())
+            .collect(),
+        ..Default::default()
+    };
+
+    let pb = progress::clone_bar(
+        missing.len() as u64,
+        &format!("Installing ({jobs} at a time)"),
+    );
+    let options = brewkit::InstallManyOptions {
+        jobs,
+        ..Default::default()
+    };
+    let outcomes =
+        client.install_many_with_progress(&missing, &options, &progress::BrewBar(pb.clone()));
+    for (pkg, outcome) in missing.iter().zip(outcomes) {
+        match outcome {
+            Ok(()) => result.installed.push(pkg.name.clone()),
+            Err(e) if e.is_ignorable() => result.skipped.push(pkg.name.clone()),
+            Err(e) => result.failed.push((pkg.name.clone(), e.to_string())),
+        }
+    }
+    progress::finish_success(&pb, "Install complete");
+
+    Ok(result)
+}
+
+fn capture(
+    output: Option<String>,
+    describe: bool,
+    by_tap: bool,
+    prefix: Option<&str>,
+) -> Result<()> {
     ui::header("Capturing Brew Packages");
+    note_other_prefixes(prefix);
 
     let output_path = output.map_or_else(default_brewfile_path, PathBuf::from);
 
     // Create brewkit client
-    let client = match create_client() {
+    let client = match create_client_for(prefix) {
         Ok(c) => c,
         Err(msg) => {
             ui::error(&msg);
@@ -289,10 +557,18 @@ fn capture(output: Option<String>) -> Result<()> {
     let pb = progress::spinner("Capturing installed packages...");
 
     // Capture current state
-    let brewfile = client.capture_brewfile()?;
+    let brewfile = client.capture_brewfile_with(&brewkit::CaptureOptions {
+        descriptions: describe,
+    })?;
 
     // Write to file
-    client.write_brewfile(&brewfile, &output_path)?;
+    let options = brewkit::brewfile::WriteOptions {
+        include_versions: true,
+        group_by_type: true,
+        sort_packages: true,
+        group_by_tap: by_tap,
+    };
+    client.write_brewfile_with(&brewfile, &output_path, &options)?;
 
     progress::finish_success(
         &pb,
@@ -310,7 +586,39 @@ fn capture(output: Option<String>) -> Result<()> {
     Ok(())
 }
 
-fn audit(file: Option<String>) -> Result<()> {
+/// Print the audit as JSON for CI; problems fail the command instead of
+/// printing hints.
+fn audit_json(
+    file: Option<String>,
+    groups: &[String],
+    tags: &[String],
+    refresh: bool,
+    prefix: Option<&str>,
+) -> Result<()> {
+    let brewfile_path = get_brewfile_path(file);
+    if !brewfile_path.exists() {
+        anyhow::bail!("Brewfile not found at {}", brewfile_path.display());
+    }
+
+    let client = create_client_for(prefix)
+        .map_err(anyhow::Error::msg)?
+        .with_tags(machine_tags(tags));
+    refresh_info(&client, refresh)?;
+    let brewfile = client
+        .parse_brewfile(&brewfile_path)
+        .context("Failed to parse Brewfile")?;
+    println!("{}", client.audit_json(&brewfile, groups)?);
+
+    Ok(())
+}
+
+fn audit(
+    file: Option<String>,
+    groups: &[String],
+    tags: &[String],
+    refresh: bool,
+    prefix: Option<&str>,
+) -> Result<()> {
     ui::header("Brew Audit - Drift Detection");
 
     let brewfile_path = get_brewfile_path(file);
@@ -325,11 +633,12 @@ fn audit(file: Option<String>) -> Result<()> {
     }
 
     ui::dim(&format!("Comparing against: {}", brewfile_path.display()));
+    note_other_prefixes(prefix);
     println!();
 
     // Create brewkit client
-    let client = match create_client() {
-        Ok(c) => c,
+    let client = match create_client_for(prefix) {
+        Ok(c) => c.with_tags(machine_tags(tags)),
         Err(msg) => {
             ui::error(&msg);
             return Ok(());
@@ -337,12 +646,18 @@ fn audit(file: Option<String>) -> Result<()> {
     };
 
     let pb = progress::spinner("Auditing packages...");
+    refresh_info(&client, refresh)?;
 
     // Parse Brewfile
     let brewfile = client.parse_brewfile(&brewfile_path)?;
 
     // Run audit
-    let result = client.audit(&brewfile)?;
+    let result = if groups.is_empty() {
+        client.audit(&brewfile)?
+    } else {
+        warn_unknown_groups(&brewfile, groups);
+        client.audit_groups(&brewfile, groups)?
+    };
 
     progress::finish_success(&pb, "Audit complete");
 
@@ -354,6 +669,7 @@ fn audit(file: Option<String>) -> Result<()> {
             "  {} packages in sync",
             brewfile.packages.len().to_string().green()
         );
+        print_removable(&result.removable);
         return Ok(());
     }
 
@@ -422,6 +738,8 @@ fn audit(file: Option<String>) -> Result<()> {
         println!();
     }
 
+    print_removable(&result.removable);
+
     // Summary
     println!("{}", "─".repeat(50).dimmed());
     println!(
@@ -466,6 +784,30 @@ fn audit(file: Option<String>) -> Result<()> {
     Ok(())
 }
 
+/// Suggest removing dependencies nothing needs anymore.
+fn print_removable(removable: &[String]) {
+    if removable.is_empty() {
+        return;
+    }
+
+    println!();
+    println!(
+        "{} {} ({})",
+        "♻".dimmed(),
+        "Removable dependencies".bold(),
+        removable.len()
+    );
+    println!();
+    let names: Vec<_> = removable.iter().map(|n| n.dimmed().to_string()).collect();
+    ui::print_columns(&names);
+    println!();
+    println!(
+        "    Run {} to uninstall them",
+        "bossa brew orphans --remove".cyan()
+    );
+    println!();
+}
+
 fn list(filter_type: Option<String>) -> Result<()> {
     ui::header("Installed Homebrew Packages");
 
@@ -542,18 +884,23 @@ fn list(filter_type: Option<String>) -> Result<()> {
             brewkit::PackageType::Cask => "Casks".magenta(),
             brewkit::PackageType::Mas => "Mac App Store".yellow(),
             brewkit::PackageType::Vscode => "VS Code Extensions".cyan(),
+            brewkit::PackageType::Custom => "Custom".white(),
         };
 
         println!("{} ({})", type_label.bold(), packages.len());
         println!();
 
-        for pkg in packages {
-            if pkg.version.is_empty() {
-                println!("    {}", pkg.name);
-            } else {
-                println!("    {} {}", pkg.name, format!("({})", pkg.version).dimmed());
-            }
-        }
+        let names: Vec<_> = packages
+            .iter()
+            .map(|pkg| {
+                if pkg.version.is_empty() {
+                    pkg.name.clone()
+                } else {
+                    format!("{} {}", pkg.name, format!("({})", pkg.version).dimmed())
+                }
+            })
+            .collect();
+        ui::print_columns(&names);
         println!();
     }
 
@@ -568,6 +915,7 @@ fn list(filter_type: Option<String>) -> Result<()> {
             brewkit::PackageType::Cask => format!("{} casks", count.magenta()),
             brewkit::PackageType::Mas => format!("{} mas", count.yellow()),
             brewkit::PackageType::Vscode => format!("{} vscode", count.cyan()),
+            brewkit::PackageType::Custom => format!("{count} custom"),
         };
         summary_parts.push(label);
     }
@@ -575,3 +923,260 @@ fn list(filter_type: Option<String>) -> Result<()> {
 
     Ok(())
 }
+
+fn outdated(
+    packages: &[String],
+    filter_type: Option<String>,
+    upgrade: bool,
+    as_json: bool,
+    refresh: bool,
+) -> Result<()> {
+    // Parse filter type
+    let filter = match filter_type.as_deref() {
+        None => None,
+        Some(t) => match brewkit::PackageType::from_directive(t) {
+            Some(pt @ (brewkit::PackageType::Brew | brewkit::PackageType::Cask)) => Some(pt),
+            _ => {
+                ui::error(&format!("Unknown package type: {t}"));
+                ui::info("Valid types: brew, cask");
+                return Ok(());
+            }
+        },
+    };
+    let selected = |pkg: &brewkit::OutdatedPackage| {
+        filter.is_none_or(|t| pkg.package_type == t)
+            && (packages.is_empty() || packages.contains(&pkg.name))
+    };
+
+    if !as_json {
+        ui::header("Outdated Homebrew Packages");
+    }
+
+    // Create brewkit client
+    let client = match create_client() {
+        Ok(c) => c,
+        Err(msg) => {
+            ui::error(&msg);
+            return Ok(());
+        }
+    };
+
+    let pb = (!as_json).then(|| progress::spinner("Checking for updates..."));
+    refresh_info(&client, refresh)?;
+    let outdated: Vec<_> = client
+        .outdated()
+        .context("Failed to list outdated packages")?
+        .into_iter()
+        .filter(|pkg| selected(pkg))
+        .collect();
+    if let Some(pb) = pb {
+        progress::finish_success(&pb, &format!("Found {} outdated packages", outdated.len()));
+    }
+
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&outdated)?);
+        return Ok(());
+    }
+
+    println!();
+    if outdated.is_empty() {
+        ui::success("All packages are up to date!");
+        return Ok(());
+    }
+
+    for pkg in &outdated {
+        let pinned = if pkg.pinned {
+            " (pinned)".dimmed().to_string()
+        } else {
+            String::new()
+        };
+        println!(
+            "    {} {} {} → {}{}",
+            colored_type(&pkg.package_type),
+            pkg.name,
+            pkg.installed_version.dimmed(),
+            pkg.latest_version.yellow(),
+            pinned
+        );
+    }
+    println!();
+
+    if !upgrade {
+        ui::info(&format!(
+            "Run {} to upgrade them",
+            "bossa brew outdated --upgrade".cyan()
+        ));
+        return Ok(());
+    }
+
+    let pb = progress::spinner(&format!("Upgrading {} packages...", outdated.len()));
+    let result = client.upgrade_outdated(|pkg| selected(pkg))?;
+    progress::finish_success(&pb, "Upgrade complete");
+    println!();
+
+    for name in &result.skipped {
+        println!("    {} {}", name.dimmed(), "- pinned, skipped".dimmed());
+    }
+    for (name, err) in &result.failed {
+        println!("    {} {}", name.red(), format!("- {err}").dimmed());
+    }
+
+    println!("{}", "─".repeat(50).dimmed());
+    println!(
+        "  {} upgraded, {} skipped, {} failed",
+        result.upgraded.len().to_string().blue(),
+        result.skipped.len().to_string().dimmed(),
+        if result.failed.is_empty() {
+            result.failed.len().to_string().dimmed()
+        } else {
+            result.failed.len().to_string().red()
+        }
+    );
+
+    Ok(())
+}
+
+fn orphans(remove: bool, yes: bool, as_json: bool) -> Result<()> {
+    if !as_json {
+        ui::header("Orphaned Formulas");
+    }
+
+    // Create brewkit client
+    let client = match create_client() {
+        Ok(c) => c,
+        Err(msg) => {
+            ui::error(&msg);
+            return Ok(());
+        }
+    };
+
+    let pb = (!as_json).then(|| progress::spinner("Building dependency graph..."));
+    let graph = client
+        .dependency_graph()
+        .context("Failed to read installed formulas")?;
+    let orphans = graph.orphans();
+    if let Some(pb) = pb {
+        progress::finish_success(&pb, &format!("Checked {} installed formulas", graph.len()));
+    }
+
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&orphans)?);
+        return Ok(());
+    }
+
+    println!();
+    if orphans.is_empty() {
+        ui::success("No orphaned formulas!");
+        return Ok(());
+    }
+
+    ui::dim("Installed as dependencies, no longer needed by anything:");
+    println!();
+    for name in &orphans {
+        println!("    {} {}", colored_type(&brewkit::PackageType::Brew), name);
+    }
+    println!();
+
+    if !remove {
+        ui::info(&format!(
+            "Run {} to uninstall them",
+            "bossa brew orphans --remove".cyan()
+        ));
+        return Ok(());
+    }
+
+    if !yes {
+        let confirmed =
+            ui::prompt::confirm(&format!("Uninstall {} formulas?", orphans.len()), false)
+                .unwrap_or(false);
+        if !confirmed {
+            ui::info("Aborted.");
+            return Ok(());
+        }
+    }
+
+    // Orphans come dependents first, so each uninstall leaves nothing broken
+    let mut removed = 0;
+    let mut failed = 0;
+    for name in &orphans {
+        match client.uninstall(
+            &brewkit::Package::brew(*name),
+            &brewkit::UninstallOptions::default(),
+        ) {
+            Ok(()) => {
+                removed += 1;
+                println!("    {} {}", "✓".green(), name);
+            }
+            Err(e) => {
+                failed += 1;
+                println!(
+                    "    {} {} {}",
+                    "✗".red(),
+                    name.red(),
+                    format!("- {e}").dimmed()
+                );
+            }
+        }
+    }
+
+    println!();
+    println!("{}", "─".repeat(50).dimmed());
+    println!(
+        "  {} removed, {} failed",
+        removed.to_string().green(),
+        if failed == 0 {
+            failed.to_string().dimmed()
+        } else {
+            failed.to_string().red()
+        }
+    );
+
+    Ok(())
+}
+
+fn uninstall(packages: &[String], cask: bool, options: &brewkit::UninstallOptions) -> Result<()> {
+    ui::header("Uninstalling Homebrew Packages");
+
+    let client = match create_client() {
+        Ok(c) => c,
+        Err(msg) => {
+            ui::error(&msg);
+            return Ok(());
+        }
+    };
+
+    let mut failed = 0;
+    for name in packages {
+        let package = if cask {
+            brewkit::Package::cask(name)
+        } else {
+            brewkit::Package::brew(name)
+        };
+        match client.uninstall(&package, options) {
+            Ok(()) => println!("    {} {}", "✓".green(), name),
+            Err(e) => {
+                failed += 1;
+                println!(
+                    "    {} {} {}",
+                    "✗".red(),
+                    name.red(),
+                    format!("- {e}").dimmed()
+                );
+            }
+        }
+    }
+
+    println!();
+    if failed > 0 {
+        anyhow::bail!(
+            "{failed} of {} packages failed to uninstall",
+            packages.len()
+        );
+    }
+    if options.zap {
+        ui::success("Uninstalled, along with their app data");
+    } else {
+        ui::success("Uninstalled");
+    }
+    Ok(())
+}
@@ -24,6 +24,17 @@ pub fn run(cmd: CachesCommand) -> Result<()> {
         CachesCommand::Audit => audit(),
         CachesCommand::Doctor => doctor(),
         CachesCommand::Init { force } => init(force),
+        CachesCommand::Clean {
+            dry_run,
+            prune,
+            scrub,
+            no_autoremove,
+        } => clean(&brewkit::CleanupOptions {
+            dry_run,
+            autoremove: !no_autoremove,
+            prune_days: prune,
+            scrub,
+        }),
     }
 }
 
@@ -550,6 +561,53 @@ fn init(force: bool) -> Result<()> {
 // Helpers
 // ============================================================================
 
+/// Reclaim space from Homebrew's Cellar and download cache
+fn clean(options: &brewkit::CleanupOptions) -> Result<()> {
+    ui::header("Cleaning Homebrew Caches");
+
+    let client = brewkit::Client::new().context("Homebrew is not installed")?;
+    let result = client.cleanup(options).context("Homebrew cleanup failed")?;
+
+    if result.is_empty() {
+        ui::success("Nothing to clean up");
+        return Ok(());
+    }
+
+    let (verb, freed) = if result.dry_run {
+        ("Would remove", "would free")
+    } else {
+        ("Removed", "freed")
+    };
+
+    if !result.autoremoved.is_empty() {
+        println!("{}", "Unneeded dependencies:".bold());
+        for name in &result.autoremoved {
+            println!("  {} {}", "−".red(), name);
+        }
+        println!();
+    }
+
+    if !result.removed_files.is_empty() {
+        println!("{}", "Old versions and downloads:".bold());
+        for path in &result.removed_files {
+            println!("  {} {}", "−".red(), path.display().to_string().dimmed());
+        }
+        println!();
+    }
+
+    ui::success(&format!(
+        "{verb} {} formulas and {} files, {freed} {}",
+        result.autoremoved.len(),
+        result.removed_files.len(),
+        format_size(result.bytes_reclaimed)
+    ));
+    if result.dry_run {
+        ui::dim("Run without --dry-run to clean up");
+    }
+
+    Ok(())
+}
+
 /// Track a symlink in the unified inventory.
 /// This is called after successful symlink creation to record it in BossaState.
 /// Errors are logged but do not fail the operation (non-critical).
@@ -357,7 +357,7 @@ fn status(_ctx: &AppContext) -> Result<()> {
 fn promote(_ctx: &AppContext, pkg: &str) -> Result<()> {
     ui::header("Cellar Promote");
 
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     if config.cellar.local.iter().any(|p| p == pkg) {
         ui::info(&format!("{pkg} is already in the local keep-list"));
@@ -382,7 +382,7 @@ fn promote(_ctx: &AppContext, pkg: &str) -> Result<()> {
 fn demote(_ctx: &AppContext, pkg: &str) -> Result<()> {
     ui::header("Cellar Demote");
 
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     let before_len = config.cellar.local.len();
     config.cellar.local.retain(|p| p != pkg);
@@ -298,8 +298,8 @@ fn sync(
         return Ok(());
     }
 
-    // Clone in parallel with progress bar
-    let pb = progress::clone_bar(repos_to_clone.len() as u64, "Cloning");
+    // Clone in parallel, with a spinner per running clone
+    let bars = progress::MultiProgress::new(repos_to_clone.len() as u64, "Cloning");
     let cloned = Arc::new(AtomicUsize::new(0));
     let failed = Arc::new(AtomicUsize::new(0));
     let failed_repos: Arc<std::sync::Mutex<Vec<(String, String)>>> =
@@ -315,12 +315,13 @@ fn sync(
 
     pool.install(|| {
         repos_to_clone.par_iter().for_each(|repo| {
+            let task = bars.add(&repo.name, None);
             let result = clone_repo_with_retry(&root, repo, clone_settings, retries);
 
             match result {
                 Ok(()) => {
                     cloned.fetch_add(1, Ordering::Relaxed);
-                    pb.set_message(format!("{} ✓", repo.name));
+                    task.finish_success();
                 }
                 Err(e) => {
                     failed.fetch_add(1, Ordering::Relaxed);
@@ -330,15 +331,13 @@ fn sync(
                             .into_inner()
                             .push((repo.name.clone(), e.to_string())),
                     }
-                    pb.set_message(format!("{} ✗", repo.name));
+                    task.finish_error(&e.to_string());
                 }
             }
-
-            pb.inc(1);
         });
     });
 
-    pb.finish_and_clear();
+    bars.finish();
 
     // Summary
     let cloned_count = cloned.load(Ordering::Relaxed);
@@ -483,7 +482,7 @@ fn is_network_error(error: &str) -> bool {
 fn audit(_ctx: &Context, collection_name: &str, fix: bool) -> Result<()> {
     ui::header(&format!("Collection Audit: {collection_name}"));
 
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
     let collection = config
         .find_collection(collection_name)
         .with_context(|| format!("Collection '{collection_name}' not found"))?
@@ -612,7 +611,7 @@ fn audit(_ctx: &Context, collection_name: &str, fix: bool) -> Result<()> {
 fn snapshot(_ctx: &Context, collection_name: &str) -> Result<()> {
     ui::header(&format!("Capturing Snapshot: {collection_name}"));
 
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
     let collection = config
         .find_collection(collection_name)
         .with_context(|| format!("Collection '{collection_name}' not found"))?
@@ -726,7 +725,7 @@ fn add(
     progress::finish_success(&pb, &format!("Default branch: {default_branch}"));
 
     // Load config
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     // Check if collection exists, create if not
     if !config.collections.contains_key(collection_name) {
@@ -811,7 +810,7 @@ fn rm(_ctx: &Context, collection_name: &str, repo_name: &str, delete: bool) -> R
         "Removing Repo from {collection_name}: {repo_name}"
     ));
 
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     let collection = config
         .find_collection_mut(collection_name)
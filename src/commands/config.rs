@@ -0,0 +1,424 @@
+//! Config command - read and change config.toml by key
+//!
+//! Keys are dotted paths into the file, like `packages.brew.formulas`;
+//! segments with dots in them are quoted, as in TOML: `hosts."mbp.local"`.
+//!
+//! Changes are made to the file's own tables, so settings left at their
+//! defaults stay out of it, and are checked against [`BossaConfig`] before
+//! anything is written. The file is edited in place: comments, ordering and
+//! formatting outside the changed key are kept. Keys the schema doesn't know
+//! are refused rather than silently ignored.
+
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use toml::{Table, Value};
+use toml_edit::{DocumentMut, Item, TableLike};
+
+use crate::Context as AppContext;
+use crate::cli::ConfigCommand;
+use crate::paths;
+use crate::schema::BossaConfig;
+use crate::ui;
+
+pub fn run(ctx: &AppContext, cmd: ConfigCommand) -> Result<()> {
+    match cmd {
+        ConfigCommand::Get { key } => get(ctx, &key),
+        ConfigCommand::Set { key, value } => set(&key, &value),
+        ConfigCommand::Unset { key } => unset(&key),
+        ConfigCommand::Edit => edit(),
+        ConfigCommand::Validate => validate(),
+    }
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Print a setting, with defaults filled in
+fn get(ctx: &AppContext, key: &str) -> Result<()> {
+    let segments = parse_key(key)?;
+    let config = Table::try_from(BossaConfig::load()?).context("Failed to serialize config")?;
+    let Some(value) = lookup(&config, &segments) else {
+        bail!("'{key}' is not set");
+    };
+
+    if ctx.is_json() {
+        return ctx.output().json(value);
+    }
+    match value {
+        Value::String(s) => println!("{s}"),
+        Value::Table(table) => print!("{}", toml::to_string_pretty(table)?),
+        other => println!("{other}"),
+    }
+    Ok(())
+}
+
+fn set(key: &str, raw_value: &str) -> Result<()> {
+    let segments = parse_key(key)?;
+    let mut document = read_document()?;
+    insert(document.as_table_mut(), &segments, parse_value(raw_value))?;
+
+    let config = check(&document)?;
+    let known = Table::try_from(&config).context("Failed to serialize config")?;
+    if lookup(&known, &segments).is_none() {
+        bail!("Unknown config key '{key}'");
+    }
+
+    write_document(&document)?;
+    ui::success(&format!("{key} = {raw_value}"));
+    Ok(())
+}
+
+fn unset(key: &str) -> Result<()> {
+    let segments = parse_key(key)?;
+    let mut document = read_document()?;
+    if remove(document.as_table_mut(), &segments).is_none() {
+        bail!("'{key}' is not set in config.toml");
+    }
+
+    check(&document)?;
+    write_document(&document)?;
+    ui::success(&format!("Unset {key}"));
+    Ok(())
+}
+
+/// Open config.toml in the user's editor, then validate it
+fn edit() -> Result<()> {
+    let path = config_path()?;
+    if !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, "")?;
+    }
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+
+    loop {
+        let status = Command::new(program)
+            .args(words.clone())
+            .arg(&path)
+            .status()
+            .with_context(|| format!("Failed to run editor '{editor}'"))?;
+        if !status.success() {
+            bail!("Editor '{editor}' exited with {status}");
+        }
+
+        match validate_file(&path) {
+            Ok(()) => break,
+            Err(e) => {
+                ui::error(&format!("{e:#}"));
+                if !ui::prompt::is_interactive() || !ui::prompt::confirm("Edit again?", true)? {
+                    bail!("{} is not valid", path.display());
+                }
+            }
+        }
+    }
+
+    ui::success(&format!("{} is valid", path.display()));
+    Ok(())
+}
+
+fn validate() -> Result<()> {
+    let path = config_path()?;
+    if !path.exists() {
+        ui::info(&format!("No config at {}; defaults apply", path.display()));
+        return Ok(());
+    }
+    validate_file(&path)?;
+    ui::success(&format!("{} is valid", path.display()));
+    Ok(())
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn config_path() -> Result<PathBuf> {
+    Ok(paths::config_dir()?.join("config.toml"))
+}
+
+/// The config file, formatting and all; empty when there's no file yet
+fn read_document() -> Result<DocumentMut> {
+    let path = config_path()?;
+    if !path.exists() {
+        return Ok(DocumentMut::new());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Could not read config file: {}", path.display()))?;
+    content
+        .parse()
+        .with_context(|| format!("Invalid TOML in {}", path.display()))
+}
+
+fn write_document(document: &DocumentMut) -> Result<()> {
+    let path = config_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, document.to_string())
+        .with_context(|| format!("Could not write {}", path.display()))
+}
+
+/// Check a document against the schema and the config's own rules
+fn check(document: &DocumentMut) -> Result<BossaConfig> {
+    let table: Table = toml::from_str(&document.to_string())?;
+    let config: BossaConfig = table
+        .try_into()
+        .context("Config doesn't match the schema")?;
+    config.validate()?;
+    Ok(config)
+}
+
+/// Check a config file, pointing at the line of any schema error and
+/// listing keys the schema doesn't know
+fn validate_file(path: &std::path::Path) -> Result<()> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Could not read config file: {}", path.display()))?;
+    let config: BossaConfig = toml::from_str(&content)?;
+    config.validate()?;
+
+    let table: Table = toml::from_str(&content)?;
+    let known = Table::try_from(&config).context("Failed to serialize config")?;
+    let unknown = unknown_keys(&table, &known, "");
+    if !unknown.is_empty() {
+        for key in &unknown {
+            ui::warn(&format!("Unknown key '{key}'"));
+        }
+        bail!("{} unknown key(s) in {}", unknown.len(), path.display());
+    }
+    Ok(())
+}
+
+/// Split a dotted key into segments; quoted segments may contain dots
+fn parse_key(key: &str) -> Result<Vec<String>> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut was_quoted = false;
+
+    for c in key.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                was_quoted = true;
+            }
+            '.' if !quoted => {
+                if current.is_empty() && !was_quoted {
+                    bail!("Invalid key '{key}': empty segment");
+                }
+                segments.push(std::mem::take(&mut current));
+                was_quoted = false;
+            }
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        bail!("Invalid key '{key}': unterminated quote");
+    }
+    if current.is_empty() && !was_quoted {
+        bail!("Invalid key '{key}': empty segment");
+    }
+    segments.push(current);
+    Ok(segments)
+}
+
+/// A value as TOML when it parses as one (`true`, `42`, `["a", "b"]`),
+/// otherwise as a string
+fn parse_value(raw: &str) -> toml_edit::Value {
+    raw.parse().unwrap_or_else(|_| toml_edit::Value::from(raw))
+}
+
+fn lookup<'a>(table: &'a Table, segments: &[String]) -> Option<&'a Value> {
+    let (last, parents) = segments.split_last()?;
+    let mut table = table;
+    for segment in parents {
+        table = table.get(segment)?.as_table()?;
+    }
+    table.get(last)
+}
+
+/// Set a value, creating the tables on its path
+///
+/// A value replacing another keeps its comments.
+fn insert(
+    table: &mut dyn TableLike,
+    segments: &[String],
+    mut value: toml_edit::Value,
+) -> Result<()> {
+    let Some((last, parents)) = segments.split_last() else {
+        bail!("Empty key");
+    };
+    let mut table = table;
+    for (i, segment) in parents.iter().enumerate() {
+        let entry = table.entry(segment).or_insert_with(|| {
+            let mut child = toml_edit::Table::new();
+            child.set_implicit(true);
+            Item::Table(child)
+        });
+        let Some(child) = entry.as_table_like_mut() else {
+            bail!("'{}' is not a table", segments[..=i].join("."));
+        };
+        table = child;
+    }
+    if let Some(old) = table.get(last).and_then(Item::as_value) {
+        *value.decor_mut() = old.decor().clone();
+    }
+    table.insert(last, Item::Value(value));
+    Ok(())
+}
+
+/// Remove a value, and the tables it leaves empty
+fn remove(table: &mut dyn TableLike, segments: &[String]) -> Option<Item> {
+    match segments {
+        [] => None,
+        [last] => table.remove(last),
+        [first, rest @ ..] => {
+            let child = table.get_mut(first)?.as_table_like_mut()?;
+            let removed = remove(child, rest)?;
+            if child.is_empty() {
+                table.remove(first);
+            }
+            Some(removed)
+        }
+    }
+}
+
+/// Keys in `table` that didn't survive a round trip through the schema
+fn unknown_keys(table: &Table, known: &Table, prefix: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match (value, known.get(key)) {
+            (_, None) => unknown.push(path),
+            (Value::Table(child), Some(Value::Table(known_child))) => {
+                unknown.extend(unknown_keys(child, known_child, &path));
+            }
+            (Value::Array(items), Some(Value::Array(known_items))) => {
+                for (i, (item, known_item)) in items.iter().zip(known_items).enumerate() {
+                    if let (Value::Table(item), Value::Table(known_item)) = (item, known_item) {
+                        unknown.extend(unknown_keys(item, known_item, &format!("{path}[{i}]")));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: &str) -> Vec<String> {
+        parse_key(key).unwrap()
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(
+            key("packages.brew.formulas"),
+            ["packages", "brew", "formulas"]
+        );
+        assert_eq!(
+            key("hosts.\"mbp.local\".nova"),
+            ["hosts", "mbp.local", "nova"]
+        );
+        assert!(parse_key("packages..brew").is_err());
+        assert!(parse_key("hosts.\"mbp").is_err());
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("true").as_bool(), Some(true));
+        let list = parse_value("[\"git\", \"fd\"]");
+        let items: Vec<_> = list
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        assert_eq!(items, ["git", "fd"]);
+        assert_eq!(parse_value("~/dev/refs").as_str(), Some("~/dev/refs"));
+    }
+
+    #[test]
+    fn test_set_unset_and_check() {
+        let mut document = DocumentMut::new();
+        insert(
+            document.as_table_mut(),
+            &key("packages.brew.formulas"),
+            parse_value("[\"git\"]"),
+        )
+        .unwrap();
+        let config = check(&document).unwrap();
+        assert_eq!(config.packages.brew.formulas, ["git"]);
+        assert_eq!(
+            document.to_string(),
+            "[packages.brew]\nformulas = [\"git\"]\n"
+        );
+
+        assert!(remove(document.as_table_mut(), &key("packages.brew.formulas")).is_some());
+        assert!(document.is_empty());
+
+        // Schema errors name the key
+        insert(
+            document.as_table_mut(),
+            &key("packages.brew.formulas"),
+            parse_value("git"),
+        )
+        .unwrap();
+        let error = format!("{:#}", check(&document).unwrap_err());
+        assert!(error.contains("packages.brew.formulas"), "{error}");
+    }
+
+    #[test]
+    fn test_edits_keep_comments_and_layout() {
+        let content = "# My machine\n\n[variables]\nhost = \"mbp\" # short name\nuser = \"me\"\n\n# Packages\n[packages.brew]\nformulas = [\"git\"]\n";
+        let mut document: DocumentMut = content.parse().unwrap();
+
+        insert(
+            document.as_table_mut(),
+            &key("variables.host"),
+            parse_value("studio"),
+        )
+        .unwrap();
+        assert_eq!(
+            document.to_string(),
+            content.replace("\"mbp\"", "\"studio\"")
+        );
+
+        remove(document.as_table_mut(), &key("variables.user")).unwrap();
+        insert(
+            document.as_table_mut(),
+            &key("packages.brew.casks"),
+            parse_value("[\"firefox\"]"),
+        )
+        .unwrap();
+        assert_eq!(
+            document.to_string(),
+            "# My machine\n\n[variables]\nhost = \"studio\" # short name\n\n# Packages\n[packages.brew]\nformulas = [\"git\"]\ncasks = [\"firefox\"]\n"
+        );
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let table: Table =
+            toml::from_str("[packages.brew]\nformula = [\"git\"]\n[variables]\nhost = \"mbp\"\n")
+                .unwrap();
+        let config: BossaConfig = table.clone().try_into().unwrap();
+        let known = Table::try_from(&config).unwrap();
+        assert_eq!(unknown_keys(&table, &known, ""), ["packages.brew.formula"]);
+    }
+}
@@ -52,10 +52,13 @@ fn apply_git(
 
     if dry_run {
         println!();
-        println!("{}", "Would generate:".yellow());
-        println!("{}", "─".repeat(60).dimmed());
-        println!("{content}");
-        println!("{}", "─".repeat(60).dimmed());
+        match generators::git::diff(git_config, &config.locations)? {
+            Some(diff) => {
+                println!("{}", "Would change:".yellow());
+                print!("{diff}");
+            }
+            None => println!("{}", "Already up to date.".green()),
+        }
         println!();
         println!("{}", "Dry run - no changes made.".dimmed());
     } else {
@@ -106,7 +109,7 @@ fn diff_git(config: &BossaConfig, git_config: &crate::schema::GitConfig) -> Resu
 
     match generators::git::diff(git_config, &config.locations)? {
         Some(diff_output) => {
-            println!("{diff_output}");
+            print!("{diff_output}");
         }
         None => {
             println!("{}", "No changes - config is up to date.".green());
@@ -21,7 +21,7 @@ pub fn add_collection(
     ui::header(&format!("Adding Collection: {name}"));
 
     // Load or create config
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     // Check if already exists
     if config.collections.contains_key(name) {
@@ -74,7 +74,7 @@ pub fn add_repo(_ctx: &Context, collection: &str, url: &str, name: Option<&str>)
     ui::kv("Default branch", &default_branch);
 
     // Load config
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     // Get or create collection
     let coll = config
@@ -128,7 +128,7 @@ pub fn add_workspace(
     ui::kv("Category", category_str);
 
     // Load config
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     // Check if already exists
     if config.workspaces.find_repo(&workspace_name).is_some() {
@@ -176,7 +176,7 @@ pub fn add_storage(
     ui::kv("Type", &format!("{st:?}").to_lowercase());
 
     // Load config
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     // Check if already exists
     if config.storage.contains_key(name) {
@@ -212,7 +212,7 @@ pub fn rm_collection(_ctx: &Context, name: &str) -> Result<()> {
     ui::header(&format!("Removing Collection: {name}"));
 
     // Load config
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     // Remove collection
     if config.collections.remove(name).is_none() {
@@ -236,7 +236,7 @@ pub fn rm_repo(_ctx: &Context, collection: &str, name: &str) -> Result<()> {
     ui::kv("Repo", name);
 
     // Load config
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     // Get collection
     let coll = config
@@ -268,7 +268,7 @@ pub fn rm_workspace(_ctx: &Context, name: &str) -> Result<()> {
     ui::header(&format!("Removing Workspace: {name}"));
 
     // Load config
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     // Remove workspace
     if !config.workspaces.remove_repo(name) {
@@ -290,7 +290,7 @@ pub fn rm_storage(_ctx: &Context, name: &str) -> Result<()> {
     ui::header(&format!("Removing Storage: {name}"));
 
     // Load config
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     // Remove storage
     if config.storage.remove(name).is_none() {
@@ -313,6 +313,10 @@ pub fn rm_storage(_ctx: &Context, name: &str) -> Result<()> {
 
 /// List resources of a given type
 pub fn list(ctx: &Context, resource_type: ResourceType) -> Result<()> {
+    if ctx.is_json() {
+        return list_json(ctx, resource_type);
+    }
+
     match resource_type {
         ResourceType::Collections => list_collections(ctx),
         ResourceType::Repos => list_repos(ctx, None),
@@ -321,6 +325,67 @@ pub fn list(ctx: &Context, resource_type: ResourceType) -> Result<()> {
     }
 }
 
+/// Print a record per configured resource, as JSON lines
+fn list_json(ctx: &Context, resource_type: ResourceType) -> Result<()> {
+    let config = BossaConfig::load()?;
+    let mut output = ctx.output();
+
+    match resource_type {
+        ResourceType::Collections => {
+            for (name, collection) in &config.collections {
+                let path = collection.expanded_path()?;
+                output.record(
+                    &ui::output::Record::untitled()
+                        .field("name", name)
+                        .field("path", &path)
+                        .field("exists", path.exists())
+                        .field("repos", collection.repos.len())
+                        .field("storage", &collection.storage),
+                )?;
+            }
+        }
+        ResourceType::Repos => {
+            for (coll_name, collection) in &config.collections {
+                let path = collection.expanded_path()?;
+                for repo in &collection.repos {
+                    output.record(
+                        &ui::output::Record::untitled()
+                            .field("collection", coll_name)
+                            .field("name", &repo.name)
+                            .field("url", &repo.url)
+                            .field("cloned", path.join(&repo.name).exists()),
+                    )?;
+                }
+            }
+        }
+        ResourceType::Workspaces => {
+            for repo in &config.workspaces.repos {
+                output.record(
+                    &ui::output::Record::untitled()
+                        .field("name", &repo.name)
+                        .field("url", &repo.url)
+                        .field("category", &repo.category)
+                        .field("worktrees", &repo.worktrees),
+                )?;
+            }
+        }
+        ResourceType::Storage => {
+            for (name, storage) in &config.storage {
+                let mount = storage.expanded_mount()?;
+                output.record(
+                    &ui::output::Record::untitled()
+                        .field("name", name)
+                        .field("mount", &mount)
+                        .field("mounted", mount.exists())
+                        .field("symlinks", &storage.symlinks),
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn list_collections(_ctx: &Context) -> Result<()> {
     ui::header("Collections");
 
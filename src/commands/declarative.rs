@@ -9,9 +9,9 @@
 
 use anyhow::{Context as AnyhowContext, Result};
 use colored::Colorize;
+use declarative::{DiffSummary, PlannedAction, ResourceDiff, ResourceState};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -197,6 +197,12 @@ fn load_config() -> Result<BossaConfig> {
 
     let config_dir = crate::config::config_dir()?;
 
+    // Overlays don't change collections, workspaces or storage, but an
+    // unknown --profile is still an error
+    if crate::schema::selected_profile().is_some() {
+        crate::schema::BossaConfig::load()?;
+    }
+
     // Try to load the new unified config format
     if let Ok((config, _)) = crate::config::load_config::<BossaConfig>(&config_dir, "config") {
         return Ok(config);
@@ -266,8 +272,20 @@ fn parse_target(target: &str) -> (Option<ResourceType>, Option<String>) {
 // ============================================================================
 
 pub fn status(ctx: &Context, target: Option<&str>) -> Result<()> {
+    if ctx.is_json() {
+        return status_json(ctx, target);
+    }
+
     ui::header("Bossa Status");
 
+    if let Ok(machine_state) = crate::state::BossaState::load() {
+        super::freeze::print_status_banner(&machine_state);
+    }
+
+    if target == Some("brew") {
+        return super::brew::show_status(ctx);
+    }
+
     let config = load_config()?;
     let state = compute_state(&config)?;
 
@@ -288,6 +306,11 @@ pub fn status(ctx: &Context, target: Option<&str>) -> Result<()> {
         show_storage_status(&config, &state, name_filter.as_deref(), ctx)?;
     }
 
+    // Show Homebrew drift
+    if target.is_none() {
+        super::brew::show_status(ctx)?;
+    }
+
     // Show hint when nothing is configured
     if resource_filter.is_none()
         && config.collections.is_empty()
@@ -301,6 +324,84 @@ pub fn status(ctx: &Context, target: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// Print a record per resource, and one for Homebrew, as JSON lines
+fn status_json(ctx: &Context, target: Option<&str>) -> Result<()> {
+    if target == Some("brew") {
+        return super::brew::show_status(ctx);
+    }
+
+    let config = load_config()?;
+    let state = compute_state(&config)?;
+    let (resource_filter, name_filter) = target.map_or((None, None), parse_target);
+    let wanted = |resource_type: ResourceType, name: &str| {
+        resource_filter.is_none_or(|r| r == resource_type)
+            && name_filter.as_deref().is_none_or(|n| n == name)
+    };
+    let mut output = ctx.output();
+
+    for collection in &config.collections {
+        if !wanted(ResourceType::Collections, &collection.name) {
+            continue;
+        }
+        let collection_state = state.collections.iter().find(|s| s.name == collection.name);
+        let failed: serde_json::Map<_, _> = collection_state
+            .map(|s| s.failed_repos.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, error)| (name, error.into()))
+            .collect();
+        output.record(
+            &ui::output::Record::untitled()
+                .field("type", "collection")
+                .field("name", &collection.name)
+                .field("path", &collection.path)
+                .field("repos", collection.repositories.len())
+                .field(
+                    "cloned",
+                    collection_state.map_or(0, |s| s.cloned_repos.len()),
+                )
+                .field("failed", failed),
+        )?;
+    }
+
+    for workspace in &config.workspaces {
+        if !wanted(ResourceType::Workspaces, &workspace.name) {
+            continue;
+        }
+        let ws_state = state.workspaces.iter().find(|s| s.name == workspace.name);
+        output.record(
+            &ui::output::Record::untitled()
+                .field("type", "workspace")
+                .field("name", &workspace.name)
+                .field("bare_setup", ws_state.is_some_and(|s| s.bare_setup))
+                .field("worktrees", ws_state.map_or(0, |s| s.worktrees.len()))
+                .field("expected_worktrees", workspace.worktrees.len()),
+        )?;
+    }
+
+    for stor in &config.storage {
+        if !wanted(ResourceType::Storage, &stor.name) {
+            continue;
+        }
+        let stor_state = state.storage.iter().find(|s| s.name == stor.name);
+        output.record(
+            &ui::output::Record::untitled()
+                .field("type", "storage")
+                .field("name", &stor.name)
+                .field("mount_point", &stor.mount_point)
+                .field("mounted", stor_state.is_some_and(|s| s.mounted))
+                .field("symlinks", stor_state.map_or(0, |s| s.symlinks.len()))
+                .field("expected_symlinks", stor.symlinks.len()),
+        )?;
+    }
+
+    if target.is_none() {
+        super::brew::show_status(ctx)?;
+    }
+
+    Ok(())
+}
+
 fn show_collections_status(
     config: &BossaConfig,
     state: &BossaState,
@@ -590,7 +691,9 @@ fn apply_collection(
 
     if dry_run {
         for repo in &repos_to_clone {
-            println!("  {} {}", "→".cyan(), repo.name);
+            let path = root.join(&repo.name).to_string_lossy().into_owned();
+            let clone = PlannedAction::run("git", &["clone", "--depth", "1", &repo.url, &path]);
+            println!("  {} {clone}", "→".cyan());
         }
         return Ok(());
     }
@@ -729,7 +832,9 @@ fn apply_workspace(
         ui::info("Bare repository needs setup");
 
         if dry_run {
-            println!("  {} Clone bare repository", "→".cyan());
+            let bare_path = bare_path.to_string_lossy();
+            let clone = PlannedAction::run("git", &["clone", "--bare", &workspace.url, &bare_path]);
+            println!("  {} {clone}", "→".cyan());
         } else {
             let pb = progress::spinner("Cloning bare repository...");
 
@@ -770,12 +875,10 @@ fn apply_workspace(
             ui::info(&format!("Creating worktree: {}", worktree.branch));
 
             if dry_run {
-                println!(
-                    "  {} git worktree add {} {}",
-                    "→".cyan(),
-                    wt_path.display(),
-                    worktree.branch
-                );
+                let wt_path = wt_path.to_string_lossy();
+                let add =
+                    PlannedAction::run("git", &["worktree", "add", &wt_path, &worktree.branch]);
+                println!("  {} {add}", "→".cyan());
             } else if !ctx.quiet {
                 ui::dim(&format!("  {}", wt_path.display()));
             }
@@ -857,12 +960,8 @@ fn apply_storage_item(
             ui::info(&format!("Creating symlink: {}", target.display()));
 
             if dry_run {
-                println!(
-                    "  {} ln -s {} {}",
-                    "→".cyan(),
-                    source.display(),
-                    target.display()
-                );
+                let link = PlannedAction::symlink(&target, &source);
+                println!("  {} {link}", "→".cyan());
             } else {
                 // Ensure parent directory exists
                 if let Some(parent) = target.parent() {
@@ -894,32 +993,37 @@ fn apply_storage_item(
 // Diff Command
 // ============================================================================
 
-pub fn diff(ctx: &Context, target: Option<&str>) -> Result<()> {
-    ui::header("Configuration Diff");
+pub fn diff(ctx: &Context, target: Option<&str>, json: bool) -> Result<()> {
+    #[derive(Serialize)]
+    struct DiffReport {
+        diffs: Vec<ResourceDiff>,
+        summary: DiffSummary,
+    }
 
     let config = load_config()?;
     let state = compute_state(&config)?;
+    let diffs = pending_diffs(&config, &state, target)?;
 
-    let (resource_filter, name_filter) = target.map_or((None, None), parse_target);
-
-    let mut has_changes = false;
-
-    // Diff collections
-    if resource_filter.is_none() || resource_filter == Some(ResourceType::Collections) {
-        has_changes |= diff_collections(&config, &state, name_filter.as_deref(), ctx)?;
-    }
-
-    // Diff workspaces
-    if resource_filter.is_none() || resource_filter == Some(ResourceType::Workspaces) {
-        has_changes |= diff_workspaces(&config, &state, name_filter.as_deref(), ctx)?;
+    if json || ctx.is_json() {
+        let report = DiffReport {
+            summary: DiffSummary::from_diffs(&diffs),
+            diffs,
+        };
+        // The legacy --json flag keeps its pretty output; --format json is
+        // one object per line like every other command
+        if ctx.is_json() {
+            return ctx.output().json(&report);
+        }
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
     }
 
-    // Diff storage
-    if resource_filter.is_none() || resource_filter == Some(ResourceType::Storage) {
-        has_changes |= diff_storage(&config, &state, name_filter.as_deref(), ctx)?;
-    }
+    ui::header("Configuration Diff");
+    print_collection_diffs(&config, &diffs, ctx);
+    print_workspace_diffs(&config, &diffs, ctx);
+    print_storage_diffs(&config, &diffs);
 
-    if !has_changes {
+    if diffs.is_empty() {
         let mut checked_parts = Vec::new();
         if !config.collections.is_empty() {
             checked_parts.push(format!(
@@ -956,48 +1060,215 @@ pub fn diff(ctx: &Context, target: Option<&str>) -> Result<()> {
     Ok(())
 }
 
-fn diff_collections(
+/// Every change `bossa apply` would make to the resources `target` picks
+fn pending_diffs(
     config: &BossaConfig,
-    _state: &BossaState,
-    name_filter: Option<&str>,
-    ctx: &Context,
-) -> Result<bool> {
-    let collections: Vec<_> = config
+    state: &BossaState,
+    target: Option<&str>,
+) -> Result<Vec<ResourceDiff>> {
+    let (resource_filter, name_filter) = target.map_or((None, None), parse_target);
+    let name_filter = name_filter.as_deref();
+
+    let mut diffs = Vec::new();
+    if resource_filter.is_none() || resource_filter == Some(ResourceType::Collections) {
+        diffs.extend(collection_diffs(config, name_filter)?);
+    }
+    if resource_filter.is_none() || resource_filter == Some(ResourceType::Workspaces) {
+        diffs.extend(workspace_diffs(config, state, name_filter));
+    }
+    if resource_filter.is_none() || resource_filter == Some(ResourceType::Storage) {
+        diffs.extend(storage_diffs(config, state, name_filter));
+    }
+    Ok(diffs)
+}
+
+fn collection_diffs(config: &BossaConfig, name_filter: Option<&str>) -> Result<Vec<ResourceDiff>> {
+    let mut diffs = Vec::new();
+    for collection in config
         .collections
         .iter()
         .filter(|c| name_filter.is_none() || Some(c.name.as_str()) == name_filter)
-        .collect();
+    {
+        let cloned_repos = get_cloned_repos(&crate::paths::expand(&collection.path))?;
+
+        for repo in &collection.repositories {
+            if !cloned_repos.contains(&repo.name) {
+                diffs.push(pending_diff(
+                    "collection_repo",
+                    format!("{}/{}", collection.name, repo.name),
+                    format!("Clone {}", repo.url),
+                    ResourceState::Absent,
+                    ResourceState::Present {
+                        details: Some(repo.url.clone()),
+                    },
+                ));
+            }
+        }
 
-    if collections.is_empty() {
-        return Ok(false);
+        // Drift: cloned, but no longer in the config
+        for name in &cloned_repos {
+            if !collection.repositories.iter().any(|r| &r.name == name) {
+                diffs.push(pending_diff(
+                    "collection_repo",
+                    format!("{}/{name}", collection.name),
+                    "Cloned but not in config".to_string(),
+                    ResourceState::Present { details: None },
+                    ResourceState::Absent,
+                ));
+            }
+        }
     }
+    Ok(diffs)
+}
 
-    let mut has_changes = false;
+fn workspace_diffs(
+    config: &BossaConfig,
+    state: &BossaState,
+    name_filter: Option<&str>,
+) -> Vec<ResourceDiff> {
+    let mut diffs = Vec::new();
+    for workspace in config
+        .workspaces
+        .iter()
+        .filter(|w| name_filter.is_none() || Some(w.name.as_str()) == name_filter)
+    {
+        let ws_state = state.workspaces.iter().find(|s| s.name == workspace.name);
+        let bare_setup = ws_state.is_some_and(|s| s.bare_setup);
+        let has_worktrees = ws_state.is_some_and(|s| !s.worktrees.is_empty());
 
-    for collection in collections {
-        let root = crate::paths::expand(&collection.path);
+        if !bare_setup {
+            diffs.push(pending_diff(
+                "workspace_bare",
+                workspace.name.clone(),
+                format!("Set up bare repository from {}", workspace.url),
+                ResourceState::Absent,
+                ResourceState::Present {
+                    details: Some(workspace.url.clone()),
+                },
+            ));
+        }
 
-        let cloned_repos = get_cloned_repos(&root)?;
-        let configured_repos: HashSet<_> = collection
-            .repositories
-            .iter()
-            .map(|r| r.name.as_str())
-            .collect();
+        if !has_worktrees {
+            for wt in &workspace.worktrees {
+                diffs.push(pending_diff(
+                    "workspace_worktree",
+                    format!("{}/{}", workspace.name, wt.branch),
+                    format!("Create worktree for {}", wt.branch),
+                    ResourceState::Absent,
+                    ResourceState::Present {
+                        details: Some(wt.path.clone()),
+                    },
+                ));
+            }
+        }
+    }
+    diffs
+}
 
-        // Repos in config but not cloned
-        let missing: Vec<_> = collection
-            .repositories
+fn storage_diffs(
+    config: &BossaConfig,
+    state: &BossaState,
+    name_filter: Option<&str>,
+) -> Vec<ResourceDiff> {
+    let mut diffs = Vec::new();
+    for stor in config
+        .storage
+        .iter()
+        .filter(|s| name_filter.is_none() || Some(s.name.as_str()) == name_filter)
+    {
+        let mounted = state
+            .storage
             .iter()
-            .filter(|r| !cloned_repos.contains(&r.name))
-            .collect();
+            .find(|s| s.name == stor.name)
+            .is_some_and(|s| s.mounted);
 
-        // Repos cloned but not in config (drift)
-        let extra: Vec<_> = cloned_repos
-            .iter()
-            .filter(|name| !configured_repos.contains(name.as_str()))
-            .collect();
+        for symlink in &stor.symlinks {
+            if mounted && crate::paths::expand(&symlink.target).exists() {
+                continue;
+            }
+            let mut diff = pending_diff(
+                "storage_symlink",
+                symlink.target.clone(),
+                format!("Link {} -> {}", symlink.target, symlink.source),
+                ResourceState::Absent,
+                ResourceState::Present {
+                    details: Some(symlink.source.clone()),
+                },
+            );
+            // Symlinks into an unmounted volume can't be created
+            if !mounted {
+                diff.current = ResourceState::Unknown;
+                diff.skipped = Some(format!("{} is not mounted", stor.mount_point));
+            }
+            diffs.push(diff);
+        }
+    }
+    diffs
+}
+
+/// A change `bossa apply` would make
+fn pending_diff(
+    resource_type: &str,
+    resource_id: String,
+    description: String,
+    current: ResourceState,
+    desired: ResourceState,
+) -> ResourceDiff {
+    ResourceDiff {
+        resource_id,
+        resource_type: resource_type.to_string(),
+        description,
+        current,
+        desired,
+        requires_sudo: false,
+        skipped: None,
+        planned: Vec::new(),
+    }
+}
+
+/// What a state was found or is wanted with, or nothing
+fn details(state: &ResourceState) -> &str {
+    match state {
+        ResourceState::Present {
+            details: Some(details),
+        } => details,
+        _ => "",
+    }
+}
 
-        if missing.is_empty() && extra.is_empty() {
+/// `diffs` of `resource_type` whose ID starts with `group/`, or is `group`
+fn group_diffs<'a>(
+    diffs: &'a [ResourceDiff],
+    resource_type: &str,
+    group: &str,
+) -> Vec<&'a ResourceDiff> {
+    diffs
+        .iter()
+        .filter(|diff| diff.resource_type == resource_type)
+        .filter(|diff| {
+            diff.resource_id == group
+                || diff
+                    .resource_id
+                    .strip_prefix(group)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+        .collect()
+}
+
+/// A grouped resource's name within `group`: its ID without the `group/`
+/// prefix, or the whole ID for the group's own resource.
+fn member_name<'a>(diff: &'a ResourceDiff, group: &str) -> &'a str {
+    diff.resource_id
+        .strip_prefix(&format!("{group}/"))
+        .unwrap_or(&diff.resource_id)
+}
+
+fn print_collection_diffs(config: &BossaConfig, diffs: &[ResourceDiff], ctx: &Context) {
+    let mut has_changes = false;
+
+    for collection in &config.collections {
+        let repos = group_diffs(diffs, "collection_repo", &collection.name);
+        if repos.is_empty() {
             continue;
         }
 
@@ -1009,52 +1280,38 @@ fn diff_collections(
         println!();
         println!("  {}", collection.name.bold());
 
+        let name = |diff: &ResourceDiff| member_name(diff, &collection.name).to_string();
+        // Repos in config but not cloned, and cloned but not in config (drift)
+        let (missing, extra): (Vec<_>, Vec<_>) = repos
+            .into_iter()
+            .partition(|diff| diff.desired.is_present());
+
         if !missing.is_empty() {
             println!("    {} Missing (will be cloned):", "+".green());
             for repo in missing {
-                println!("      {} {}", "+".green(), repo.name);
+                println!("      {} {}", "+".green(), name(repo));
                 if !ctx.quiet {
-                    ui::dim(&format!("        {}", repo.url));
+                    ui::dim(&format!("        {}", details(&repo.desired)));
                 }
             }
         }
 
         if !extra.is_empty() {
             println!("    {} Drift (cloned but not in config):", "!".yellow());
-            for name in extra {
-                println!("      {} {}", "!".yellow(), name);
+            for repo in extra {
+                println!("      {} {}", "!".yellow(), name(repo));
             }
         }
     }
-
-    Ok(has_changes)
 }
 
-fn diff_workspaces(
-    config: &BossaConfig,
-    state: &BossaState,
-    name_filter: Option<&str>,
-    ctx: &Context,
-) -> Result<bool> {
-    let workspaces: Vec<_> = config
-        .workspaces
-        .iter()
-        .filter(|w| name_filter.is_none() || Some(w.name.as_str()) == name_filter)
-        .collect();
-
-    if workspaces.is_empty() {
-        return Ok(false);
-    }
-
+fn print_workspace_diffs(config: &BossaConfig, diffs: &[ResourceDiff], ctx: &Context) {
     let mut has_changes = false;
 
-    for workspace in workspaces {
-        let ws_state = state.workspaces.iter().find(|s| s.name == workspace.name);
-
-        let bare_setup = ws_state.is_some_and(|s| s.bare_setup);
-        let has_worktrees = ws_state.is_some_and(|s| !s.worktrees.is_empty());
-
-        if bare_setup && has_worktrees {
+    for workspace in &config.workspaces {
+        let bare = group_diffs(diffs, "workspace_bare", &workspace.name);
+        let worktrees = group_diffs(diffs, "workspace_worktree", &workspace.name);
+        if bare.is_empty() && worktrees.is_empty() {
             continue;
         }
 
@@ -1066,73 +1323,39 @@ fn diff_workspaces(
         println!();
         println!("  {}", workspace.name.bold());
 
-        if !bare_setup {
+        for diff in bare {
             println!("    {} Bare repository not setup", "+".green());
             if !ctx.quiet {
-                ui::dim(&format!("      {}", workspace.url));
+                ui::dim(&format!("      {}", details(&diff.desired)));
             }
         }
 
-        if !has_worktrees {
+        if !worktrees.is_empty() {
             println!(
                 "    {} Worktrees not created: {}",
                 "+".green(),
-                workspace.worktrees.len()
+                worktrees.len()
             );
             if !ctx.quiet {
-                for wt in &workspace.worktrees {
-                    ui::dim(&format!("      {} -> {}", wt.branch, wt.path));
+                for wt in worktrees {
+                    let branch = member_name(wt, &workspace.name);
+                    ui::dim(&format!("      {branch} -> {}", details(&wt.desired)));
                 }
             }
         }
     }
-
-    Ok(has_changes)
 }
 
-fn diff_storage(
-    config: &BossaConfig,
-    state: &BossaState,
-    name_filter: Option<&str>,
-    _ctx: &Context,
-) -> Result<bool> {
-    let storage: Vec<_> = config
-        .storage
-        .iter()
-        .filter(|s| name_filter.is_none() || Some(s.name.as_str()) == name_filter)
-        .collect();
-
-    if storage.is_empty() {
-        return Ok(false);
-    }
-
+fn print_storage_diffs(config: &BossaConfig, diffs: &[ResourceDiff]) {
     let mut has_changes = false;
 
-    for stor in storage {
-        let stor_state = state.storage.iter().find(|s| s.name == stor.name);
-
-        let mounted = stor_state.is_some_and(|s| s.mounted);
-
-        if !mounted {
-            if !has_changes {
-                ui::section("Storage");
-                has_changes = true;
-            }
-
-            println!();
-            println!("  {}", stor.name.bold());
-            println!("    {} Not mounted: {}", "!".yellow(), stor.mount_point);
-            continue;
-        }
-
-        // Check symlinks
-        let missing_symlinks: Vec<_> = stor
-            .symlinks
+    for stor in &config.storage {
+        let symlinks: Vec<_> = diffs
             .iter()
-            .filter(|s| !crate::paths::expand(&s.target).exists())
+            .filter(|diff| diff.resource_type == "storage_symlink")
+            .filter(|diff| stor.symlinks.iter().any(|s| s.target == diff.resource_id))
             .collect();
-
-        if missing_symlinks.is_empty() {
+        if symlinks.is_empty() {
             continue;
         }
 
@@ -1143,18 +1366,23 @@ fn diff_storage(
 
         println!();
         println!("  {}", stor.name.bold());
+
+        // Symlinks into an unmounted volume are skipped
+        if symlinks.iter().any(|diff| diff.is_skipped()) {
+            println!("    {} Not mounted: {}", "!".yellow(), stor.mount_point);
+            continue;
+        }
+
         println!("    {} Missing symlinks:", "+".green());
-        for symlink in missing_symlinks {
+        for symlink in symlinks {
             println!(
                 "      {} {} -> {}",
                 "+".green(),
-                symlink.target,
-                symlink.source
+                symlink.resource_id,
+                details(&symlink.desired)
             );
         }
     }
-
-    Ok(has_changes)
 }
 
 // ============================================================================
@@ -1288,7 +1516,8 @@ fn snapshot_failed_repos(
 
 #[cfg(test)]
 mod tests {
-    use super::{push_failed_repo, snapshot_failed_repos};
+    use super::{group_diffs, member_name, pending_diff, push_failed_repo, snapshot_failed_repos};
+    use declarative::ResourceState;
     use std::sync::{Arc, Mutex};
 
     #[test]
@@ -1329,4 +1558,32 @@ mod tests {
         assert_eq!(snapshot.len(), 1);
         assert_eq!(snapshot[0].0, "repo-b");
     }
+
+    #[test]
+    fn group_diffs_matches_whole_names() {
+        let diffs: Vec<_> = ["dots/zsh", "dotfiles/vim", "dots"]
+            .into_iter()
+            .map(|id| {
+                pending_diff(
+                    "collection_repo",
+                    id.to_string(),
+                    String::new(),
+                    ResourceState::Absent,
+                    ResourceState::Present { details: None },
+                )
+            })
+            .collect();
+        let ids: Vec<_> = group_diffs(&diffs, "collection_repo", "dots")
+            .into_iter()
+            .map(|diff| diff.resource_id.as_str())
+            .collect();
+        assert_eq!(ids, ["dots/zsh", "dots"]);
+
+        // The group's own resource has no member name to strip down to
+        let names: Vec<_> = group_diffs(&diffs, "collection_repo", "dots")
+            .into_iter()
+            .map(|diff| member_name(diff, "dots"))
+            .collect();
+        assert_eq!(names, ["zsh", "dots"]);
+    }
 }
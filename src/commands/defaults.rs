@@ -1,6 +1,7 @@
 use crate::Context;
 use crate::cli::{DefaultsCommand, DefaultsType};
 use crate::resource::{ApplyContext, ApplyResult, DefaultValue, MacOSDefault, Resource};
+use crate::ui;
 use anyhow::{Context as _, Result};
 use colored::Colorize;
 
@@ -66,11 +67,7 @@ fn set_default(
     // Special handling for Finder to make changes take effect immediately
     if domain == "com.apple.finder" {
         let should_restart = ctx.quiet
-            || dialoguer::Confirm::new()
-                .with_prompt("Restart Finder to apply changes?")
-                .default(true)
-                .interact()
-                .unwrap_or(false);
+            || ui::prompt::confirm("Restart Finder to apply changes?", true).unwrap_or(false);
         if should_restart {
             if !ctx.quiet {
                 println!("{}", "  Restarting Finder to apply changes...".dimmed());
@@ -11,6 +11,7 @@ use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 use walkdir::WalkDir;
 
 use crate::ui;
@@ -279,17 +280,17 @@ fn check_destination_space(_dest: &Path, _required: u64) -> Result<()> {
 fn perform_backup(entries: &[CopyEntry], source_base: &Path, total_size: u64) -> Result<()> {
     let pb = ProgressBar::new(total_size);
     let style = ProgressStyle::default_bar();
-    let style = match style
-        .template("{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta}) {msg}")
-    {
-        Ok(style) => style.progress_chars("=>-"),
-        Err(_) => ProgressStyle::default_bar(),
-    };
+    let style =
+        match style.template("{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} {msg}") {
+            Ok(style) => style.progress_chars("=>-"),
+            Err(_) => ProgressStyle::default_bar(),
+        };
     pb.set_style(style);
 
     let mut copied_count = 0;
     let mut error_count = 0;
     let mut copied_bytes = 0u64;
+    let started = Instant::now();
 
     // First pass: create directories
     for entry in entries.iter().filter(|e| e.is_dir) {
@@ -317,7 +318,14 @@ fn perform_backup(entries: &[CopyEntry], source_base: &Path, total_size: u64) ->
             .strip_prefix(source_base)
             .unwrap_or(&entry.source);
 
-        pb.set_message(ui::truncate_path(&rel_path.display().to_string(), 40));
+        let elapsed = started.elapsed();
+        let remaining = ui::eta(pb.position(), total_size, elapsed)
+            .map_or_else(|| "-".to_string(), ui::human_duration);
+        pb.set_message(format!(
+            "({}, {remaining} remaining) {}",
+            ui::rate(pb.position(), elapsed),
+            ui::truncate_path(&rel_path.display().to_string(), 40)
+        ));
 
         // Ensure parent directory exists
         if let Some(parent) = entry.dest.parent()
@@ -354,16 +362,22 @@ fn perform_backup(entries: &[CopyEntry], source_base: &Path, total_size: u64) ->
     pb.finish_and_clear();
 
     // Summary
+    let elapsed = started.elapsed();
+    let took = format!(
+        "in {}, {}",
+        ui::human_duration(elapsed),
+        ui::rate(copied_bytes, elapsed)
+    );
     println!();
     if error_count == 0 {
         ui::success(&format!(
-            "Copied {} files ({})",
+            "Copied {} files ({}) {took}",
             copied_count,
             ui::format_size(copied_bytes)
         ));
     } else {
         ui::warn(&format!(
-            "Copied {} files ({}) with {} errors",
+            "Copied {} files ({}) {took} with {} errors",
             copied_count,
             ui::format_size(copied_bytes),
             error_count
@@ -10,7 +10,6 @@
 
 use anyhow::{Context, Result};
 use colored::Colorize;
-use dialoguer::{Confirm, Input, Select};
 use std::process::Command;
 
 use super::plist as plist_util;
@@ -123,12 +122,7 @@ pub fn run(disk: &str, dry_run: bool, confirm: bool) -> Result<()> {
         println!();
 
         // Interactive confirmation
-        if !Confirm::new()
-            .with_prompt("Do you want to execute this command now?")
-            .default(false)
-            .interact()
-            .context("Failed to read user input")?
-        {
+        if !ui::prompt::confirm("Do you want to execute this command now?", false)? {
             ui::info("Aborted. No changes made.");
             return Ok(());
         }
@@ -143,14 +137,10 @@ pub fn run(disk: &str, dry_run: bool, confirm: bool) -> Result<()> {
     );
     println!();
 
-    if !Confirm::new()
-        .with_prompt(format!(
-            "Are you ABSOLUTELY SURE you want to repartition {disk_id}?"
-        ))
-        .default(false)
-        .interact()
-        .context("Failed to read user input")?
-    {
+    if !ui::prompt::confirm(
+        &format!("Are you ABSOLUTELY SURE you want to repartition {disk_id}?"),
+        false,
+    )? {
         ui::info("Aborted. No changes made.");
         return Ok(());
     }
@@ -358,23 +348,14 @@ fn get_partition_specs(disk: &CurrentDiskInfo) -> Result<Vec<PartitionSpec>> {
         println!("  {} Partition {}:", "->".dimmed(), partition_num);
 
         // Partition name
-        let name: String = Input::new()
-            .with_prompt("    Name (empty to finish)")
-            .allow_empty(true)
-            .interact_text()
-            .context("Failed to read partition name")?;
+        let name = ui::prompt::input("    Name (empty to finish)", Some(""))?;
 
         if name.is_empty() {
             break;
         }
 
         // Filesystem type
-        let fs_idx = Select::new()
-            .with_prompt("    Format")
-            .items(&fs_options)
-            .default(0)
-            .interact()
-            .context("Failed to read format selection")?;
+        let fs_idx = ui::prompt::select("    Format", &fs_options, Some(0))?;
 
         let fs_type = fs_options[fs_idx].to_string();
 
@@ -396,10 +377,8 @@ fn get_partition_specs(disk: &CurrentDiskInfo) -> Result<Vec<PartitionSpec>> {
             ui::format_size(remaining_size).green()
         );
 
-        let size_str: String = Input::new()
-            .with_prompt("    Size (e.g., 1TB, 500GB, or 'rest' for remaining)")
-            .interact_text()
-            .context("Failed to read size")?;
+        let size_str =
+            ui::prompt::input("    Size (e.g., 1TB, 500GB, or 'rest' for remaining)", None)?;
 
         let size_spec = if size_str.to_lowercase() == "rest" || size_str.to_lowercase() == "r" {
             "0b".to_string() // diskutil uses 0b for "use remaining space"
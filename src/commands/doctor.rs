@@ -76,7 +76,11 @@ fn print_issue_summary(issues: &[Issue]) {
             }
         }
         if let Some(fix) = &issue.fix {
-            println!("      {} {}", "Fix:".cyan(), fix);
+            println!(
+                "      {} {}",
+                "Fix:".cyan(),
+                ui::markdown::render(fix).trim_end()
+            );
         }
         if let Some(cmd) = &issue.fix_cmd {
             println!("      {} {}", "$".dimmed(), cmd.bold());
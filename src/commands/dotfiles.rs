@@ -90,6 +90,7 @@ pub fn sync_for_nova(config: &BossaConfig) -> Result<()> {
     let ctx = AppContext {
         verbose: 0,
         quiet: true,
+        format: ui::output::OutputFormat::Human,
     };
     let args = DotfilesSyncArgs {
         dry_run: false,
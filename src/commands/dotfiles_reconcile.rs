@@ -6,7 +6,6 @@
 
 use anyhow::{Context, Result, bail};
 use colored::Colorize;
-use dialoguer::{Confirm, Input, MultiSelect};
 use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::io::IsTerminal;
@@ -945,7 +944,7 @@ fn display_diff(report: &ReconcileReport, config: &DotfilesReconcileConfig) {
     println!();
 }
 
-/// Show a text diff between two files using the `similar` crate
+/// Show a text diff between two files
 fn show_text_diff(a: &Path, b: &Path) {
     let Ok(text_a) = fs::read_to_string(a) else {
         return;
@@ -954,26 +953,13 @@ fn show_text_diff(a: &Path, b: &Path) {
         return;
     };
 
-    let diff = similar::TextDiff::from_lines(&text_a, &text_b);
-    let mut has_changes = false;
-
-    for change in diff.iter_all_changes() {
-        match change.tag() {
-            similar::ChangeTag::Delete => {
-                has_changes = true;
-                print!("    {}", format!("- {change}").red());
-            }
-            similar::ChangeTag::Insert => {
-                has_changes = true;
-                print!("    {}", format!("+ {change}").green());
-            }
-            similar::ChangeTag::Equal => {}
-        }
-    }
-
-    if !has_changes {
+    let diff = ui::diff::unified(&text_a, &text_b);
+    if diff.is_empty() {
         println!("    {}", "(files are identical)".dimmed());
     }
+    for line in diff.lines() {
+        println!("    {line}");
+    }
 }
 
 // ============================================================================
@@ -982,7 +968,7 @@ fn show_text_diff(a: &Path, b: &Path) {
 
 /// Load reconciliation config from main config, launching setup wizard if missing
 fn load_config() -> Result<DotfilesReconcileConfig> {
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
     if let Some(reconcile) = config.dotfiles_reconcile {
         return Ok(reconcile);
     }
@@ -1016,22 +1002,14 @@ fn setup_reconcile_config(config: &mut BossaConfig) -> Result<DotfilesReconcileC
         .unwrap_or_default();
 
     println!();
-    let source_a: String = Input::new()
-        .with_prompt("Source A (primary dotfiles directory)")
-        .with_initial_text(&default_a)
-        .interact_text()
-        .context("Failed to read source_a")?;
-
-    let source_b: String = Input::new()
-        .with_prompt("Source B (secondary dotfiles directory)")
-        .interact_text()
-        .context("Failed to read source_b")?;
-
-    let target: String = Input::new()
-        .with_prompt("Target (where dotfiles are deployed)")
-        .with_initial_text("~")
-        .interact_text()
-        .context("Failed to read target")?;
+    let source_a = ui::prompt::input(
+        "Source A (primary dotfiles directory)",
+        Some(&default_a)
+            .filter(|a| !a.is_empty())
+            .map(String::as_str),
+    )?;
+    let source_b = ui::prompt::input("Source B (secondary dotfiles directory)", None)?;
+    let target = ui::prompt::input("Target (where dotfiles are deployed)", Some("~"))?;
 
     // Validate that source directories exist
     let path_a = expand_path(&source_a);
@@ -1044,15 +1022,8 @@ fn setup_reconcile_config(config: &mut BossaConfig) -> Result<DotfilesReconcileC
     if b_missing {
         ui::warn(&format!("Source B does not exist: {}", path_b.display()));
     }
-    if a_missing || b_missing {
-        let cont = Confirm::new()
-            .with_prompt("Continue anyway?")
-            .default(false)
-            .interact()
-            .context("Failed to read confirmation")?;
-        if !cont {
-            bail!("Setup cancelled");
-        }
+    if (a_missing || b_missing) && !ui::prompt::confirm("Continue anyway?", false)? {
+        bail!("Setup cancelled");
     }
 
     // Auto-discover packages from both source directories, tracking provenance
@@ -1107,12 +1078,8 @@ fn setup_reconcile_config(config: &mut BossaConfig) -> Result<DotfilesReconcileC
 
         ui::section("Package Selection");
 
-        let selected_indices = MultiSelect::new()
-            .with_prompt("Select packages to manage")
-            .items(&labels)
-            .defaults(&defaults)
-            .interact()
-            .context("Failed to read package selection")?;
+        let selected_indices =
+            ui::prompt::multi_select("Select packages to manage", &labels, &defaults)?;
 
         if selected_indices.len() == sorted_packages.len() {
             // All selected -> save empty vec so runtime auto-discovery kicks in
@@ -1155,13 +1122,7 @@ fn setup_reconcile_config(config: &mut BossaConfig) -> Result<DotfilesReconcileC
     }
     println!();
 
-    let confirmed = Confirm::new()
-        .with_prompt("Save to config?")
-        .default(true)
-        .interact()
-        .context("Failed to read confirmation")?;
-
-    if !confirmed {
+    if !ui::prompt::confirm("Save to config?", true)? {
         bail!("Setup cancelled");
     }
 
@@ -0,0 +1,140 @@
+//! Freeze command - lock the machine against mutations until a date
+//!
+//! While frozen, mutating commands (see [`crate::cli::Command::mutation`])
+//! refuse to run unless `--ignore-freeze` is passed. Non-interactive runs,
+//! such as scheduled jobs, skip quietly instead of failing.
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use colored::Colorize;
+use std::io::IsTerminal;
+
+use crate::Context as AppContext;
+use crate::cli::FreezeArgs;
+use crate::state::BossaState;
+use crate::ui;
+
+pub fn run(_ctx: &AppContext, args: &FreezeArgs) -> Result<()> {
+    let until = parse_until(&args.until)?;
+    if until <= Utc::now() {
+        bail!("Freeze date must be in the future (got: {})", args.until);
+    }
+
+    let mut state = BossaState::load()?;
+    state.freeze(until, args.reason.clone());
+    state.touch()?;
+
+    ui::success(&format!(
+        "Machine frozen until {}",
+        until.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+    ));
+    ui::dim("Mutating commands will refuse to run. Lift with 'bossa unfreeze'.");
+    Ok(())
+}
+
+pub fn unfreeze(_ctx: &AppContext) -> Result<()> {
+    let mut state = BossaState::load()?;
+    if state.unfreeze() {
+        state.touch()?;
+        ui::success("Machine unfrozen");
+    } else {
+        ui::info("Machine is not frozen");
+    }
+    Ok(())
+}
+
+/// Decide whether a mutating operation may run.
+///
+/// Returns `Ok(true)` to proceed and `Ok(false)` when a non-interactive run
+/// should be skipped. Interactive runs against a frozen machine fail.
+pub fn guard(operation: &str, ignore_freeze: bool) -> Result<bool> {
+    let state = BossaState::load()?;
+    let Some(freeze) = state.active_freeze() else {
+        return Ok(true);
+    };
+
+    let until = freeze.until.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+    let reason = freeze
+        .reason
+        .as_deref()
+        .map(|r| format!(" ({r})"))
+        .unwrap_or_default();
+
+    if ignore_freeze {
+        ui::warn(&format!(
+            "Machine is frozen until {until}{reason} - running '{operation}' anyway"
+        ));
+        return Ok(true);
+    }
+
+    if !std::io::stdin().is_terminal() {
+        ui::dim(&format!(
+            "Skipping '{operation}': machine is frozen until {until}{reason}"
+        ));
+        return Ok(false);
+    }
+
+    bail!(
+        "Machine is frozen until {until}{reason}; refusing to run '{operation}'.\n\
+         Pass --ignore-freeze to override, or run 'bossa unfreeze'."
+    )
+}
+
+/// Print a banner if the machine is frozen (used by `bossa status`).
+pub fn print_status_banner(state: &BossaState) {
+    if let Some(freeze) = state.active_freeze() {
+        let until = freeze.until.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+        println!(
+            "  {} {}",
+            "❄".cyan().bold(),
+            format!("FROZEN until {until}").cyan().bold()
+        );
+        if let Some(reason) = &freeze.reason {
+            println!("    {}", reason.dimmed());
+        }
+        println!();
+    }
+}
+
+/// Parse `--until`: a date (frozen through the end of that local day) or an
+/// RFC 3339 timestamp.
+fn parse_until(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let end_of_day = date
+            .succ_opt()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .and_then(|dt| dt.and_local_timezone(Local).earliest())
+            .with_context(|| format!("Invalid freeze date: {value}"))?;
+        return Ok(end_of_day.with_timezone(&Utc));
+    }
+
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .with_context(|| format!("Invalid freeze date '{value}' (expected YYYY-MM-DD or RFC 3339)"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_until_date_is_inclusive() {
+        let until = parse_until("2030-06-15").unwrap();
+        let local = until.with_timezone(&Local);
+        assert_eq!(
+            local.date_naive(),
+            NaiveDate::from_ymd_opt(2030, 6, 16).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_until_rfc3339() {
+        let until = parse_until("2030-06-15T12:00:00Z").unwrap();
+        assert_eq!(until.to_rfc3339(), "2030-06-15T12:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_until_invalid() {
+        assert!(parse_until("next week").is_err());
+    }
+}
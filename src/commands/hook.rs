@@ -0,0 +1,53 @@
+//! Shell hook - warn when entering a deprecated alias path
+//!
+//! `eval "$(bossa hook zsh)"` installs a `chpwd` hook that asks
+//! `bossa locations resolve --quiet` whether the new directory sits under a
+//! `locations.aliases` entry, and prints the canonical path if it does.
+//! Set `BOSSA_HOOK_REDIRECT=1` to `cd` there automatically instead.
+
+use crate::cli::HookShell;
+
+const ZSH_HOOK: &str = r#"# bossa shell hook (zsh)
+# Add to ~/.zshrc: eval "$(bossa hook zsh)"
+_bossa_chpwd() {
+  local canonical
+  canonical="$(command bossa locations resolve --quiet -- "$PWD" 2>/dev/null)" || return 0
+  [[ -n "$canonical" ]] || return 0
+
+  if [[ -n "$BOSSA_HOOK_REDIRECT" && -d "$canonical" ]]; then
+    print -u2 -r -- "bossa: $PWD is a deprecated path, moving to $canonical"
+    builtin cd -- "$canonical"
+  else
+    print -u2 -r -- "bossa: $PWD is a deprecated path"
+    print -u2 -r -- "bossa: canonical location: $canonical"
+  fi
+}
+
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd _bossa_chpwd
+"#;
+
+/// Shell source for the hook
+pub const fn script(shell: HookShell) -> &'static str {
+    match shell {
+        HookShell::Zsh => ZSH_HOOK,
+    }
+}
+
+/// Print the hook for `shell` to stdout
+pub fn run(shell: HookShell) {
+    print!("{}", script(shell));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zsh_hook_uses_chpwd_and_quiet_resolve() {
+        let hook = script(HookShell::Zsh);
+        assert!(hook.contains("add-zsh-hook chpwd _bossa_chpwd"));
+        assert!(hook.contains("bossa locations resolve --quiet"));
+        assert!(hook.contains("BOSSA_HOOK_REDIRECT"));
+    }
+}
@@ -5,14 +5,29 @@
 //! - list: List files with their status
 //! - find-evictable: Find large local files that could be evicted
 //! - evict: Remove local copy, keep cloud copy
+//! - free: Evict files until a target amount of space is freed
 //! - download: Fetch cloud copy to local
+//! - pin / unpin / list-pins: Protect paths from eviction
+//! - containers: List iCloud Drive and app containers
+//! - conflicts: Find sync conflict copies
+//! - watch: Print sync state changes as they happen
 
 use anyhow::{Context, Result};
 use colored::Colorize;
-use icloud::{Client, DownloadState, FileStatus};
-use indicatif::{ProgressBar, ProgressStyle};
+use icloud::{
+    BulkResult, Client, ConflictKind, DownloadOptions, DownloadState, EvictOptions, EvictionGuard,
+    EvictionPlan, EvictionPolicy, EvictionStrategy, FileFilter, FileStatus, PolicyMismatch,
+    WalkOptions, WatchEvent, WatchOptions,
+};
+use manifest::Manifest;
+use serde::Serialize;
+use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
 
+use crate::cli::FreeStrategy;
+use crate::progress;
 use crate::ui;
 
 /// iCloud command variants (matches cli::ICloudCommand)
@@ -24,47 +39,132 @@ pub enum ICloudCommand {
         path: Option<String>,
         local: bool,
         cloud: bool,
+        json: bool,
     },
     FindEvictable {
         path: Option<String>,
         min_size: String,
+        extensions: Vec<String>,
+        exclude_bundles: Vec<String>,
+        allow_bundles: Vec<String>,
     },
     Evict {
         path: String,
         recursive: bool,
         min_size: Option<String>,
+        older_than: Option<String>,
+        exclude: Vec<String>,
+        verified_in: Option<String>,
         dry_run: bool,
+        json: bool,
+    },
+    Free {
+        target: String,
+        path: Option<String>,
+        strategy: FreeStrategy,
+        dry_run: bool,
+        json: bool,
     },
     Download {
         path: String,
         recursive: bool,
     },
+    Pin {
+        path: String,
+    },
+    Unpin {
+        path: String,
+    },
+    ListPins,
+    Containers,
+    Conflicts {
+        path: Option<String>,
+        json: bool,
+    },
+    Watch {
+        path: Option<String>,
+        interval: u64,
+        json: bool,
+    },
 }
 
 impl From<crate::cli::ICloudCommand> for ICloudCommand {
     fn from(cmd: crate::cli::ICloudCommand) -> Self {
         match cmd {
             crate::cli::ICloudCommand::Status { path } => Self::Status { path },
-            crate::cli::ICloudCommand::List { path, local, cloud } => {
-                Self::List { path, local, cloud }
-            }
-            crate::cli::ICloudCommand::FindEvictable { path, min_size } => {
-                Self::FindEvictable { path, min_size }
-            }
+            crate::cli::ICloudCommand::List {
+                path,
+                local,
+                cloud,
+                json,
+            } => Self::List {
+                path,
+                local,
+                cloud,
+                json,
+            },
+            crate::cli::ICloudCommand::FindEvictable {
+                path,
+                min_size,
+                extensions,
+                exclude_bundles,
+                allow_bundles,
+            } => Self::FindEvictable {
+                path,
+                min_size,
+                extensions,
+                exclude_bundles,
+                allow_bundles,
+            },
             crate::cli::ICloudCommand::Evict {
                 path,
                 recursive,
                 min_size,
+                older_than,
+                exclude,
+                verified_in,
                 dry_run,
+                json,
             } => Self::Evict {
                 path,
                 recursive,
                 min_size,
+                older_than,
+                exclude,
+                verified_in,
+                dry_run,
+                json,
+            },
+            crate::cli::ICloudCommand::Free {
+                target,
+                path,
+                strategy,
                 dry_run,
+                json,
+            } => Self::Free {
+                target,
+                path,
+                strategy,
+                dry_run,
+                json,
             },
             crate::cli::ICloudCommand::Download { path, recursive } => {
                 Self::Download { path, recursive }
             }
+            crate::cli::ICloudCommand::Pin { path } => Self::Pin { path },
+            crate::cli::ICloudCommand::Unpin { path } => Self::Unpin { path },
+            crate::cli::ICloudCommand::ListPins => Self::ListPins,
+            crate::cli::ICloudCommand::Containers => Self::Containers,
+            crate::cli::ICloudCommand::Conflicts { path, json } => Self::Conflicts { path, json },
+            crate::cli::ICloudCommand::Watch {
+                path,
+                interval,
+                json,
+            } => Self::Watch {
+                path,
+                interval,
+                json,
+            },
         }
     }
 }
@@ -73,24 +173,83 @@ impl From<crate::cli::ICloudCommand> for ICloudCommand {
 pub fn run(cmd: ICloudCommand) -> Result<()> {
     match cmd {
         ICloudCommand::Status { path } => status(path),
-        ICloudCommand::List { path, local, cloud } => list(path, local, cloud),
-        ICloudCommand::FindEvictable { path, min_size } => find_evictable(path, &min_size),
+        ICloudCommand::List {
+            path,
+            local,
+            cloud,
+            json,
+        } => list(path, local, cloud, json),
+        ICloudCommand::FindEvictable {
+            path,
+            min_size,
+            extensions,
+            exclude_bundles,
+            allow_bundles,
+        } => find_evictable(
+            path,
+            &min_size,
+            FileFilter {
+                extensions,
+                exclude_bundles,
+                allow_bundles,
+                ..Default::default()
+            },
+        ),
         ICloudCommand::Evict {
             path,
             recursive,
             min_size,
+            older_than,
+            exclude,
+            verified_in,
+            dry_run,
+            json,
+        } => evict(
+            &path,
+            recursive,
+            min_size.as_deref(),
+            older_than.as_deref(),
+            exclude,
+            verified_in.as_deref(),
+            dry_run,
+            json,
+        ),
+        ICloudCommand::Free {
+            target,
+            path,
+            strategy,
             dry_run,
-        } => evict(&path, recursive, min_size.as_deref(), dry_run),
+            json,
+        } => free(&target, path, strategy, dry_run, json),
         ICloudCommand::Download { path, recursive } => download(&path, recursive),
+        ICloudCommand::Pin { path } => pin(&path),
+        ICloudCommand::Unpin { path } => unpin(&path),
+        ICloudCommand::ListPins => list_pins(),
+        ICloudCommand::Containers => containers(),
+        ICloudCommand::Conflicts { path, json } => conflicts(path, json),
+        ICloudCommand::Watch {
+            path,
+            interval,
+            json,
+        } => watch(path, interval, json),
     }
 }
 
+/// Create a client that honours the persisted pin list
+fn new_client() -> Result<Client> {
+    let pin_file = crate::paths::state_dir()?.join("icloud-pins");
+    Client::new()
+        .context("Failed to initialize iCloud client")?
+        .with_pin_file(&pin_file)
+        .with_context(|| format!("Failed to load pin list: {}", pin_file.display()))
+}
+
 // ============================================================================
 // Status Command
 // ============================================================================
 
 fn status(path: Option<String>) -> Result<()> {
-    let client = Client::new().context("Failed to initialize iCloud client")?;
+    let client = new_client()?;
     let icloud_root = client.icloud_root()?;
 
     let target_path = path.map_or_else(|| icloud_root.clone(), |p| expand_path(&p));
@@ -124,8 +283,8 @@ fn status(path: Option<String>) -> Result<()> {
 // List Command
 // ============================================================================
 
-fn list(path: Option<String>, local_only: bool, cloud_only: bool) -> Result<()> {
-    let client = Client::new().context("Failed to initialize iCloud client")?;
+fn list(path: Option<String>, local_only: bool, cloud_only: bool, json: bool) -> Result<()> {
+    let client = new_client()?;
     let icloud_root = client.icloud_root()?;
 
     let target_path = path.map_or_else(|| icloud_root.clone(), |p| expand_path(&p));
@@ -149,6 +308,11 @@ fn list(path: Option<String>, local_only: bool, cloud_only: bool) -> Result<()>
         })
         .collect();
 
+    if json {
+        println!("{}", serde_json::to_string_pretty(&filtered)?);
+        return Ok(());
+    }
+
     ui::header(&format!("iCloud Files: {}", target_path.display()));
     println!();
 
@@ -171,8 +335,8 @@ fn list(path: Option<String>, local_only: bool, cloud_only: bool) -> Result<()>
 // Find Evictable Command
 // ============================================================================
 
-fn find_evictable(path: Option<String>, min_size_str: &str) -> Result<()> {
-    let client = Client::new().context("Failed to initialize iCloud client")?;
+fn find_evictable(path: Option<String>, min_size_str: &str, filter: FileFilter) -> Result<()> {
+    let client = new_client()?;
     let icloud_root = client.icloud_root()?;
 
     let target_path = path.map_or_else(|| icloud_root.clone(), |p| expand_path(&p));
@@ -191,7 +355,7 @@ fn find_evictable(path: Option<String>, min_size_str: &str) -> Result<()> {
     ));
     println!();
 
-    let evictable = client.find_evictable(&target_path, min_size)?;
+    let evictable = client.find_evictable_filtered(&target_path, min_size, &filter)?;
 
     if evictable.is_empty() {
         ui::success(&format!(
@@ -205,6 +369,7 @@ fn find_evictable(path: Option<String>, min_size_str: &str) -> Result<()> {
     sorted.sort_by(|a, b| b.size.cmp(&a.size));
 
     let total_size: u64 = sorted.iter().filter_map(|f| f.size).sum();
+    let reclaimable: u64 = sorted.iter().filter_map(FileStatus::reclaimable).sum();
 
     for file in &sorted {
         let size_str = file.size.map_or_else(|| "?".to_string(), ui::format_size);
@@ -215,6 +380,7 @@ fn find_evictable(path: Option<String>, min_size_str: &str) -> Result<()> {
     println!();
     ui::kv("Files found", &sorted.len().to_string());
     ui::kv("Total size", &ui::format_size(total_size));
+    ui::kv("Space to free", &ui::format_size(reclaimable));
     println!();
     ui::dim("Run 'bossa icloud evict <path>' to free space");
 
@@ -225,17 +391,38 @@ fn find_evictable(path: Option<String>, min_size_str: &str) -> Result<()> {
 // Evict Command
 // ============================================================================
 
-fn evict(path: &str, recursive: bool, min_size: Option<&str>, dry_run: bool) -> Result<()> {
-    let client = Client::new().context("Failed to initialize iCloud client")?;
+#[allow(clippy::too_many_arguments)]
+fn evict(
+    path: &str,
+    recursive: bool,
+    min_size: Option<&str>,
+    older_than: Option<&str>,
+    exclude: Vec<String>,
+    verified_in: Option<&str>,
+    dry_run: bool,
+    json: bool,
+) -> Result<()> {
+    let client = new_client()?;
     let target_path = expand_path(path);
+    let guard = verified_in.map(ManifestGuard::open).transpose()?;
+    let guard = guard.as_ref();
 
     if !client.is_in_icloud(&target_path) {
         anyhow::bail!("Path is not in iCloud Drive: {}", target_path.display());
     }
 
-    let min_bytes = min_size
-        .map(|s| ui::parse_size(s).map_err(|e| anyhow::anyhow!("Invalid size '{s}': {e}")))
-        .transpose()?;
+    let policy = EvictionPolicy {
+        min_size: min_size
+            .map(|s| ui::parse_size(s).map_err(|e| anyhow::anyhow!("Invalid size '{s}': {e}")))
+            .transpose()?,
+        older_than: older_than.map(parse_age).transpose()?,
+        exclude,
+        ..Default::default()
+    };
+
+    if json {
+        return evict_json(&client, &target_path, &policy, guard, recursive, dry_run);
+    }
 
     if dry_run {
         ui::header("Evict (Dry Run)");
@@ -243,10 +430,16 @@ fn evict(path: &str, recursive: bool, min_size: Option<&str>, dry_run: bool) ->
         ui::header("Evict");
     }
     ui::dim("Removing local copies, files stay in iCloud");
+    if let Some(guard) = guard {
+        ui::dim(&format!(
+            "Only files backed up in {}",
+            guard.db_path.display()
+        ));
+    }
     println!();
 
     if target_path.is_file() {
-        evict_single_file(&client, &target_path, min_bytes, dry_run)
+        evict_single_file(&client, &target_path, &policy, guard, dry_run)
     } else if target_path.is_dir() {
         if !recursive {
             anyhow::bail!(
@@ -254,16 +447,83 @@ fn evict(path: &str, recursive: bool, min_size: Option<&str>, dry_run: bool) ->
                 target_path.display()
             );
         }
-        evict_directory(&client, &target_path, min_bytes, dry_run)
+        evict_directory(&client, &target_path, &policy, guard, dry_run)
     } else {
         anyhow::bail!("Path not found: {}", target_path.display());
     }
 }
 
+/// Allows eviction only of files whose content is in a manifest database
+struct ManifestGuard {
+    db_path: PathBuf,
+    manifest: Mutex<Manifest>,
+}
+
+impl ManifestGuard {
+    fn open(db_path: &str) -> Result<Self> {
+        let db_path = expand_path(db_path);
+        // Manifest::open would create an empty database and verify nothing
+        if !db_path.is_file() {
+            anyhow::bail!("Manifest database not found: {}", db_path.display());
+        }
+        let manifest = Manifest::open(&db_path)
+            .with_context(|| format!("Failed to open manifest: {}", db_path.display()))?;
+        Ok(Self {
+            db_path,
+            manifest: Mutex::new(manifest),
+        })
+    }
+}
+
+impl EvictionGuard for ManifestGuard {
+    fn allows(&self, path: &Path) -> icloud::Result<bool> {
+        let to_icloud = |e: manifest::Error| icloud::Error::Other(e.to_string());
+        // Hash outside the lock so concurrent checks don't serialize on I/O
+        let hash = manifest::content_hash(path).map_err(to_icloud)?;
+        self.manifest
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains_hash(&hash)
+            .map_err(to_icloud)
+    }
+}
+
+/// Split files into those `guard` allows and those it holds back
+fn split_verified(
+    files: Vec<FileStatus>,
+    guard: &ManifestGuard,
+) -> (Vec<FileStatus>, Vec<FileStatus>) {
+    files
+        .into_iter()
+        .partition(|f| guard.allows(&f.path).unwrap_or(false))
+}
+
+/// A single file's path for matching the policy's globs: relative to the
+/// iCloud Drive root, as a directory eviction of the whole drive would see it
+fn policy_path(client: &Client, path: &Path) -> PathBuf {
+    client
+        .icloud_root()
+        .ok()
+        .and_then(|root| path.strip_prefix(root).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.file_name().map_or(path, Path::new).to_path_buf())
+}
+
+/// Why a file doesn't match the eviction filters, for "Skipping: file ..."
+fn describe_mismatch(mismatch: PolicyMismatch) -> &'static str {
+    match mismatch {
+        PolicyMismatch::NotLocal => "is not downloaded locally",
+        PolicyMismatch::TooSmall => "is smaller than --min-size",
+        PolicyMismatch::TooRecent => "was modified more recently than --older-than",
+        PolicyMismatch::NotIncluded => "matches no include pattern",
+        PolicyMismatch::Excluded => "matches an --exclude pattern",
+    }
+}
+
 fn evict_single_file(
     client: &Client,
     path: &Path,
-    min_bytes: Option<u64>,
+    policy: &EvictionPolicy,
+    guard: Option<&ManifestGuard>,
     dry_run: bool,
 ) -> Result<()> {
     let status = client.status(path)?;
@@ -273,20 +533,26 @@ fn evict_single_file(
         return Ok(());
     }
 
-    if let Some(min) = min_bytes
-        && status.size.is_none_or(|s| s < min)
+    let relative = policy_path(client, path);
+    if let Some(mismatch) = policy.mismatch(&relative, &status) {
+        ui::info(&format!("Skipping: file {}", describe_mismatch(mismatch)));
+        return Ok(());
+    }
+
+    if let Some(guard) = guard
+        && !guard.allows(path)?
     {
-        ui::info(&format!(
-            "Skipping: file smaller than {}",
-            ui::format_size(min)
-        ));
+        ui::warn("Skipping: file content not found in the manifest");
         return Ok(());
     }
 
     let size_str = status.size.map_or_else(|| "?".to_string(), ui::format_size);
 
     if dry_run {
-        println!("  Would evict: {} ({})", path.display(), size_str);
+        let frees = status
+            .reclaimable()
+            .map_or_else(|| "?".to_string(), ui::format_size);
+        println!("  Would evict: {} (frees {})", path.display(), frees);
     } else {
         client.evict(path)?;
         ui::success(&format!("Evicted: {} ({})", path.display(), size_str));
@@ -298,51 +564,67 @@ fn evict_single_file(
 fn evict_directory(
     client: &Client,
     path: &Path,
-    min_bytes: Option<u64>,
+    policy: &EvictionPolicy,
+    guard: Option<&ManifestGuard>,
     dry_run: bool,
 ) -> Result<()> {
-    let files = collect_files(client, path, |status| {
-        if !status.state.is_local() {
-            return false;
-        }
-        if let Some(min) = min_bytes {
-            status.size.is_some_and(|s| s >= min)
-        } else {
-            true
-        }
-    })?;
+    let mut files = client.find_by_policy(path, policy)?;
 
     if files.is_empty() {
         ui::info("No evictable files found");
         return Ok(());
     }
 
-    let total_size: u64 = files.iter().filter_map(|f| f.size).sum();
+    // Files not found in the manifest are kept, so they count for nothing
+    let mut unverified = Vec::new();
+    if let Some(guard) = guard {
+        (files, unverified) = split_verified(files, guard);
+    }
+
+    let total_size: u64 = files.iter().filter_map(FileStatus::reclaimable).sum();
 
     println!(
-        "  Found {} files ({}) to evict",
+        "  Found {} files to evict ({} to free)",
         files.len(),
         ui::format_size(total_size)
     );
     println!();
 
     if dry_run {
+        for file in &unverified {
+            let rel_path = file.path.strip_prefix(path).unwrap_or(&file.path);
+            println!("  Not backed up: {}", rel_path.display());
+        }
+        if !unverified.is_empty() {
+            println!();
+        }
         for file in &files {
-            let size_str = file.size.map_or_else(|| "?".to_string(), ui::format_size);
+            let size_str = file
+                .reclaimable()
+                .map_or_else(|| "?".to_string(), ui::format_size);
             let rel_path = file.path.strip_prefix(path).unwrap_or(&file.path);
-            println!("  Would evict: {} ({})", rel_path.display(), size_str);
+            println!("  Would evict: {} (frees {})", rel_path.display(), size_str);
         }
         println!();
         ui::dim("(dry run - no files evicted)");
     } else {
-        let result = process_files_with_progress(&files, path, |file| client.evict(&file.path));
+        let paths: Vec<&Path> = files.iter().map(|f| f.path.as_path()).collect();
+        let mut progress = progress::BulkBar::new(path);
+        let result =
+            client.evict_bulk_with_progress(&paths, &EvictOptions::default(), &mut progress)?;
 
         println!();
+        if !unverified.is_empty() {
+            ui::warn(&format!(
+                "Kept {} files not found in the manifest",
+                unverified.len()
+            ));
+        }
         if result.failed == 0 {
             ui::success(&format!(
                 "Evicted {} files, freed {}",
                 result.succeeded,
-                ui::format_size(total_size)
+                ui::format_size(result.bytes)
             ));
         } else {
             ui::warn(&format!(
@@ -357,12 +639,186 @@ fn evict_directory(
     Ok(())
 }
 
+/// Machine-readable output of `icloud evict --json`
+#[derive(Serialize)]
+struct EvictReport {
+    dry_run: bool,
+    /// Files selected by the path and filters
+    candidates: Vec<FileStatus>,
+    /// Dry-run candidates held back by `--verified-in`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unverified: Vec<PathBuf>,
+    /// Outcome of the eviction (absent on dry runs)
+    result: Option<BulkResult>,
+}
+
+fn evict_json(
+    client: &Client,
+    path: &Path,
+    policy: &EvictionPolicy,
+    guard: Option<&ManifestGuard>,
+    recursive: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let mut candidates = if path.is_dir() {
+        if !recursive {
+            anyhow::bail!(
+                "Cannot evict directory without --recursive flag: {}",
+                path.display()
+            );
+        }
+        client.find_by_policy(path, policy)?
+    } else {
+        let status = client.status(path)?;
+        if !status.state.is_cloud_only() && policy.matches(&policy_path(client, path), &status) {
+            vec![status]
+        } else {
+            Vec::new()
+        }
+    };
+
+    let mut unverified = Vec::new();
+    let result = if dry_run {
+        if let Some(guard) = guard {
+            let (verified, rejected) = split_verified(candidates, guard);
+            candidates = verified;
+            unverified = rejected.into_iter().map(|f| f.path).collect();
+        }
+        None
+    } else {
+        let paths: Vec<&Path> = candidates.iter().map(|f| f.path.as_path()).collect();
+        let options = EvictOptions::default();
+        Some(match guard {
+            Some(guard) => client.evict_verified(&paths, &options, guard)?,
+            None => client.evict_bulk(&paths, &options)?,
+        })
+    };
+
+    let report = EvictReport {
+        dry_run,
+        candidates,
+        unverified,
+        result,
+    };
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+// ============================================================================
+// Free Command
+// ============================================================================
+
+/// Machine-readable output of `icloud free --json`
+#[derive(Serialize)]
+struct FreeReport {
+    plan: EvictionPlan,
+    /// Outcome of executing the plan (absent on dry runs)
+    result: Option<BulkResult>,
+}
+
+fn free(
+    target: &str,
+    path: Option<String>,
+    strategy: FreeStrategy,
+    dry_run: bool,
+    json: bool,
+) -> Result<()> {
+    let client = new_client()?;
+    let icloud_root = client.icloud_root()?;
+    let target_path = path.map_or_else(|| icloud_root.clone(), |p| expand_path(&p));
+
+    if !client.is_in_icloud(&target_path) {
+        anyhow::bail!("Path is not in iCloud Drive: {}", target_path.display());
+    }
+
+    let target_bytes =
+        ui::parse_size(target).map_err(|e| anyhow::anyhow!("Invalid size '{target}': {e}"))?;
+    let strategy = match strategy {
+        FreeStrategy::Largest => EvictionStrategy::LargestFirst,
+        FreeStrategy::Coldest => EvictionStrategy::ColdestFirst,
+    };
+    let plan = client.plan_eviction(&target_path, target_bytes, strategy)?;
+
+    if json {
+        let result = if dry_run {
+            None
+        } else {
+            Some(client.execute_plan(&plan)?)
+        };
+        let report = FreeReport { plan, result };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    if dry_run {
+        ui::header(&format!("Free {} (Dry Run)", ui::format_size(target_bytes)));
+    } else {
+        ui::header(&format!("Free {}", ui::format_size(target_bytes)));
+    }
+    ui::dim("Removing local copies, files stay in iCloud");
+    println!();
+
+    if plan.files.is_empty() {
+        ui::info("No evictable files found");
+        return Ok(());
+    }
+
+    println!(
+        "  Plan: {} files ({})",
+        plan.files.len(),
+        ui::format_size(plan.total_bytes)
+    );
+    if !plan.meets_target() {
+        ui::warn(&format!(
+            "Only {} can be freed here ({} short of the target)",
+            ui::format_size(plan.total_bytes),
+            ui::format_size(plan.shortfall())
+        ));
+    }
+    println!();
+
+    if dry_run {
+        for file in &plan.files {
+            let size_str = file
+                .reclaimable()
+                .map_or_else(|| "?".to_string(), ui::format_size);
+            let rel_path = file.path.strip_prefix(&target_path).unwrap_or(&file.path);
+            println!("  Would evict: {} (frees {})", rel_path.display(), size_str);
+        }
+        println!();
+        ui::dim("(dry run - no files evicted)");
+        return Ok(());
+    }
+
+    let mut progress = progress::BulkBar::new(&target_path);
+    let result = client.execute_plan_with_progress(&plan, &mut progress)?;
+
+    println!();
+    if result.failed == 0 {
+        ui::success(&format!(
+            "Evicted {} files, freed {}",
+            result.succeeded,
+            ui::format_size(result.bytes)
+        ));
+    } else {
+        ui::warn(&format!(
+            "Evicted {}/{} files ({} failed), freed {}",
+            result.succeeded,
+            result.succeeded + result.failed,
+            result.failed,
+            ui::format_size(result.bytes)
+        ));
+    }
+
+    Ok(())
+}
+
 // ============================================================================
 // Download Command
 // ============================================================================
 
 fn download(path: &str, recursive: bool) -> Result<()> {
-    let client = Client::new().context("Failed to initialize iCloud client")?;
+    let client = new_client()?;
     let target_path = expand_path(path);
 
     if !client.is_in_icloud(&target_path) {
@@ -412,7 +868,10 @@ fn download_directory(client: &Client, path: &Path) -> Result<()> {
     println!("  Found {} cloud-only files to download", files.len());
     println!();
 
-    let result = process_files_with_progress(&files, path, |file| client.download(&file.path));
+    let paths: Vec<&Path> = files.iter().map(|f| f.path.as_path()).collect();
+    let mut progress = progress::BulkBar::new(path);
+    let result =
+        client.download_bulk_with_progress(&paths, &DownloadOptions::default(), &mut progress)?;
 
     println!();
     if result.failed == 0 {
@@ -429,6 +888,198 @@ fn download_directory(client: &Client, path: &Path) -> Result<()> {
     Ok(())
 }
 
+// ============================================================================
+// Pin Commands
+// ============================================================================
+
+fn pin(path: &str) -> Result<()> {
+    let client = new_client()?;
+    let target_path = expand_path(path);
+
+    if client.pin(&target_path)? {
+        ui::success(&format!("Pinned: {}", target_path.display()));
+    } else {
+        ui::info(&format!("Already pinned: {}", target_path.display()));
+    }
+    Ok(())
+}
+
+fn unpin(path: &str) -> Result<()> {
+    let client = new_client()?;
+    let target_path = expand_path(path);
+
+    if client.unpin(&target_path)? {
+        ui::success(&format!("Unpinned: {}", target_path.display()));
+    } else {
+        ui::warn(&format!("Not pinned: {}", target_path.display()));
+    }
+    Ok(())
+}
+
+fn list_pins() -> Result<()> {
+    let client = new_client()?;
+    let pins = client.pins();
+
+    ui::header("Pinned Paths");
+    println!();
+
+    if pins.is_empty() {
+        ui::dim("No pinned paths");
+        return Ok(());
+    }
+
+    for path in &pins {
+        println!("  {}", path.display());
+    }
+    println!();
+    ui::dim(&format!("{} pinned path(s), never evicted", pins.len()));
+    Ok(())
+}
+
+// ============================================================================
+// Containers Command
+// ============================================================================
+
+fn containers() -> Result<()> {
+    let client = new_client()?;
+    let containers = client.containers()?;
+
+    ui::header("iCloud Containers");
+    println!();
+
+    if containers.is_empty() {
+        ui::dim("No containers found");
+        return Ok(());
+    }
+
+    for container in &containers {
+        println!("  {} {}", container.name.bold(), container.id.dimmed());
+        println!("    {}", container.documents().display());
+    }
+    println!();
+    ui::dim(&format!("{} container(s)", containers.len()));
+    Ok(())
+}
+
+// ============================================================================
+// Conflicts Command
+// ============================================================================
+
+fn conflicts(path: Option<String>, json: bool) -> Result<()> {
+    let client = new_client()?;
+    let icloud_root = client.icloud_root()?;
+
+    let target_path = path.map_or_else(|| icloud_root.clone(), |p| expand_path(&p));
+
+    if !client.is_in_icloud(&target_path) {
+        anyhow::bail!("Path is not in iCloud Drive: {}", target_path.display());
+    }
+
+    let conflicts = client.find_conflicts(&target_path)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&conflicts)?);
+        return Ok(());
+    }
+
+    ui::header(&format!("iCloud Conflicts: {}", target_path.display()));
+    println!();
+
+    if conflicts.is_empty() {
+        ui::success("No conflict copies found");
+        return Ok(());
+    }
+
+    for conflict in &conflicts {
+        let rel_path = conflict
+            .path
+            .strip_prefix(&target_path)
+            .unwrap_or(&conflict.path);
+        let size = conflict
+            .size
+            .map_or_else(|| "-".to_string(), ui::format_size);
+        println!(
+            "  {} {} {}",
+            "!".yellow(),
+            rel_path.display(),
+            format!("({size})").dimmed()
+        );
+
+        let original = conflict.original.as_deref().map_or_else(
+            || "original not found".to_string(),
+            |o| {
+                format!(
+                    "conflicts with {}",
+                    o.strip_prefix(&target_path).unwrap_or(o).display()
+                )
+            },
+        );
+        let kind = match conflict.kind {
+            ConflictKind::ConflictedCopy => "conflicted copy",
+            ConflictKind::NumberedDuplicate => "numbered duplicate",
+        };
+        ui::dim(&format!("    {kind}, {original}"));
+    }
+
+    println!();
+    ui::dim(&format!(
+        "{} conflict copies; compare each with its original, then keep one",
+        conflicts.len()
+    ));
+    Ok(())
+}
+
+// ============================================================================
+// Watch Command
+// ============================================================================
+
+fn watch(path: Option<String>, interval: u64, json: bool) -> Result<()> {
+    let client = new_client()?;
+    let icloud_root = client.icloud_root()?;
+
+    let target_path = path.map_or_else(|| icloud_root.clone(), |p| expand_path(&p));
+
+    if !client.is_in_icloud(&target_path) {
+        anyhow::bail!("Path is not in iCloud Drive: {}", target_path.display());
+    }
+
+    let options = WatchOptions {
+        interval: Duration::from_secs(interval.max(1)),
+        ..Default::default()
+    };
+
+    if !json {
+        ui::header(&format!("Watching: {}", target_path.display()));
+        ui::dim("Press Ctrl-C to stop");
+        println!();
+    }
+
+    client.watch(&target_path, &options, |event| {
+        if json {
+            if let Ok(line) = serde_json::to_string(event) {
+                println!("{line}");
+            }
+        } else {
+            print_watch_event(event, &target_path);
+        }
+        ControlFlow::Continue(())
+    })?;
+
+    Ok(())
+}
+
+fn print_watch_event(event: &WatchEvent, base_path: &Path) {
+    let rel_path = event.path.strip_prefix(base_path).unwrap_or(&event.path);
+    let time = chrono::Local::now().format("%H:%M:%S").to_string();
+    let change = match (&event.previous, &event.current) {
+        (None, Some(state)) => format!("added, {}", format_state(state)),
+        (Some(_), None) => "removed".dimmed().to_string(),
+        (Some(old), Some(new)) => format!("{} -> {}", format_state(old), format_state(new)),
+        (None, None) => return,
+    };
+    println!("  {} {} {}", time.dimmed(), rel_path.display(), change);
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -520,108 +1171,46 @@ fn collect_files<F>(client: &Client, path: &Path, filter: F) -> Result<Vec<FileS
 where
     F: Fn(&FileStatus) -> bool,
 {
-    use walkdir::WalkDir;
-
-    let mut results = Vec::new();
-    let mut errors = Vec::new();
-
-    for entry in WalkDir::new(path) {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(e) => {
-                errors.push(format!("{e}"));
-                continue;
-            }
-        };
-
-        if !entry.file_type().is_file() {
-            continue;
-        }
-
-        // Skip hidden files
-        if let Some(name) = entry.file_name().to_str()
-            && name.starts_with('.')
-        {
-            continue;
-        }
-
-        if let Ok(status) = client.status(entry.path())
-            && filter(&status)
-        {
-            results.push(status);
-        }
-    }
-
-    // Report errors but don't fail
-    if !errors.is_empty() {
-        ui::warn(&format!("Skipped {} paths due to errors", errors.len()));
-    }
-
-    Ok(results)
-}
+    let files = client.walk(path, &WalkOptions::default())?;
 
-/// Result of processing files
-struct ProcessResult {
-    succeeded: usize,
-    failed: usize,
+    Ok(files
+        .into_iter()
+        .filter(|status| !status.is_dir && filter(status))
+        .collect())
 }
 
-/// Process files with progress bar
-fn process_files_with_progress<F>(
-    files: &[FileStatus],
-    base_path: &Path,
-    operation: F,
-) -> ProcessResult
-where
-    F: Fn(&FileStatus) -> icloud::Result<()>,
-{
-    let pb = ProgressBar::new(files.len() as u64);
-    let style = ProgressStyle::default_bar();
-    let style = match style.template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} {msg}") {
-        Ok(style) => style.progress_chars("=>-"),
-        Err(_) => ProgressStyle::default_bar(),
+/// Parse an age like "90d", "12w", "6mo", "1y" or "36h".
+fn parse_age(s: &str) -> Result<Duration> {
+    const HOUR: u64 = 60 * 60;
+    const DAY: u64 = 24 * HOUR;
+
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid age '{s}' (expected e.g. 90d, 12w, 6mo)"))?;
+
+    let unit_secs = match unit.trim().to_lowercase().as_str() {
+        "h" => HOUR,
+        "d" | "" => DAY,
+        "w" => 7 * DAY,
+        "mo" => 30 * DAY,
+        "y" => 365 * DAY,
+        other => anyhow::bail!("Invalid age unit '{other}' in '{s}' (use h, d, w, mo or y)"),
     };
-    pb.set_style(style);
-
-    let mut succeeded = 0;
-    let mut failed = 0;
-
-    for file in files {
-        let rel_path = file.path.strip_prefix(base_path).unwrap_or(&file.path);
-        pb.set_message(ui::truncate_path(&rel_path.display().to_string(), 30));
-
-        match operation(file) {
-            Ok(()) => {
-                succeeded += 1;
-            }
-            Err(e) => {
-                failed += 1;
-                pb.suspend(|| {
-                    println!("  {} {} ({})", "✗".red(), rel_path.display(), e);
-                });
-            }
-        }
 
-        pb.inc(1);
-    }
-
-    pb.finish_and_clear();
-
-    // Print successes after progress bar is done
-    if failed == 0 {
-        for file in files {
-            let rel_path = file.path.strip_prefix(base_path).unwrap_or(&file.path);
-            println!("  {} {}", "✓".green(), rel_path.display());
-        }
-    }
-
-    ProcessResult { succeeded, failed }
+    let secs = number
+        .checked_mul(unit_secs)
+        .ok_or_else(|| anyhow::anyhow!("Age '{s}' is too long"))?;
+    Ok(Duration::from_secs(secs))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ICloudCommand;
+    use super::{ICloudCommand, parse_age};
     use crate::cli::ICloudCommand as CliICloudCommand;
+    use std::time::Duration;
 
     #[test]
     fn cli_icloud_evict_maps_fields() {
@@ -629,7 +1218,11 @@ mod tests {
             path: "~/Library/Mobile Documents".to_string(),
             recursive: true,
             min_size: Some("500MB".to_string()),
+            older_than: Some("90d".to_string()),
+            exclude: vec!["*.key".to_string()],
+            verified_in: Some("/Volumes/T9/manifest.db".to_string()),
             dry_run: true,
+            json: true,
         };
 
         let mapped: ICloudCommand = cli_cmd.into();
@@ -638,14 +1231,35 @@ mod tests {
                 path,
                 recursive,
                 min_size,
+                older_than,
+                exclude,
+                verified_in,
                 dry_run,
+                json,
             } => {
                 assert_eq!(path, "~/Library/Mobile Documents");
                 assert!(recursive);
                 assert_eq!(min_size.as_deref(), Some("500MB"));
+                assert_eq!(older_than.as_deref(), Some("90d"));
+                assert_eq!(exclude, vec!["*.key"]);
+                assert_eq!(verified_in.as_deref(), Some("/Volumes/T9/manifest.db"));
                 assert!(dry_run);
+                assert!(json);
             }
             _ => panic!("expected evict mapping"),
         }
     }
+
+    #[test]
+    fn parse_age_units() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        assert_eq!(parse_age("90d").unwrap(), 90 * day);
+        assert_eq!(parse_age("90").unwrap(), 90 * day);
+        assert_eq!(parse_age("2w").unwrap(), 14 * day);
+        assert_eq!(parse_age("6mo").unwrap(), 180 * day);
+        assert_eq!(parse_age("36h").unwrap(), Duration::from_secs(36 * 60 * 60));
+        assert!(parse_age("soon").is_err());
+        assert!(parse_age("5x").is_err());
+        assert!(parse_age("99999999999999999y").is_err());
+    }
 }
@@ -8,13 +8,14 @@ use crate::cli::LocationsCommand;
 use crate::schema::BossaConfig;
 use crate::ui;
 
-pub fn run(_ctx: &AppContext, cmd: LocationsCommand) -> Result<()> {
+pub fn run(ctx: &AppContext, cmd: LocationsCommand) -> Result<()> {
     match cmd {
         LocationsCommand::List => list(),
         LocationsCommand::Add { name, path } => add(&name, &path),
         LocationsCommand::Remove { name } => remove(&name),
         LocationsCommand::Show { name } => show(&name),
         LocationsCommand::Alias { path, location } => alias(&path, &location),
+        LocationsCommand::Resolve { path } => resolve(ctx, path.as_deref()),
     }
 }
 
@@ -53,7 +54,7 @@ fn list() -> Result<()> {
 }
 
 fn add(name: &str, path: &str) -> Result<()> {
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     // Validate name
     if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
@@ -78,7 +79,7 @@ fn add(name: &str, path: &str) -> Result<()> {
 }
 
 fn remove(name: &str) -> Result<()> {
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     if config.locations.paths.remove(name).is_none() {
         anyhow::bail!("Location '{name}' not found");
@@ -110,7 +111,7 @@ fn show(name: &str) -> Result<()> {
 }
 
 fn alias(path: &str, location: &str) -> Result<()> {
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     // Verify location exists
     if !config.locations.paths.contains_key(location) {
@@ -129,3 +130,34 @@ fn alias(path: &str, location: &str) -> Result<()> {
 
     Ok(())
 }
+
+/// Report the canonical path when `path` sits under a deprecated alias.
+///
+/// In quiet mode only the canonical path is printed (nothing otherwise), which
+/// is what the shell hook consumes.
+fn resolve(ctx: &AppContext, path: Option<&str>) -> Result<()> {
+    let config = BossaConfig::load()?;
+    let path = match path {
+        Some(p) => crate::paths::expand(p),
+        None => std::env::current_dir().context("Failed to read current directory")?,
+    };
+
+    let canonical = crate::paths::canonical_for_alias(&path, &config.locations);
+
+    if ctx.quiet {
+        if let Some(canonical) = canonical {
+            println!("{}", canonical.display());
+        }
+        return Ok(());
+    }
+
+    match canonical {
+        Some(canonical) => {
+            ui::warn(&format!("{} is a deprecated alias path", path.display()));
+            ui::kv("Canonical", &canonical.display().to_string());
+        }
+        None => ui::dim(&format!("{} is not under a location alias", path.display())),
+    }
+
+    Ok(())
+}
@@ -7,12 +7,14 @@
 
 use anyhow::Result;
 use colored::Colorize;
-use indicatif::{ProgressBar, ProgressStyle};
-use manifest::{DuplicateGroup, Manifest, ProgressCallback, ScanResult};
+use manifest::{DuplicateGroup, Manifest};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
+use crate::Context;
 use crate::config;
+use crate::progress;
 use crate::ui;
 
 // ============================================================================
@@ -27,6 +29,7 @@ pub enum ManifestCommand {
     },
     Stats {
         path: String,
+        prefix: Option<String>,
     },
     Duplicates {
         path: String,
@@ -39,7 +42,7 @@ impl From<crate::cli::ManifestCommand> for ManifestCommand {
     fn from(cmd: crate::cli::ManifestCommand) -> Self {
         match cmd {
             crate::cli::ManifestCommand::Scan { path, force } => Self::Scan { path, force },
-            crate::cli::ManifestCommand::Stats { path } => Self::Stats { path },
+            crate::cli::ManifestCommand::Stats { path, prefix } => Self::Stats { path, prefix },
             crate::cli::ManifestCommand::Duplicates {
                 path,
                 min_size,
@@ -53,58 +56,15 @@ impl From<crate::cli::ManifestCommand> for ManifestCommand {
     }
 }
 
-pub fn run(cmd: ManifestCommand) -> Result<()> {
+pub fn run(ctx: &Context, cmd: ManifestCommand) -> Result<()> {
     match cmd {
         ManifestCommand::Scan { path, force } => scan(&path, force),
-        ManifestCommand::Stats { path } => stats(&path),
+        ManifestCommand::Stats { path, prefix } => stats(ctx, &path, prefix.as_deref()),
         ManifestCommand::Duplicates {
             path,
             min_size,
             delete,
-        } => duplicates(&path, min_size, delete),
-    }
-}
-
-// ============================================================================
-// Progress Adapter
-// ============================================================================
-
-struct IndicatifProgress {
-    pb: ProgressBar,
-}
-
-impl IndicatifProgress {
-    fn new() -> Self {
-        Self {
-            pb: ProgressBar::hidden(),
-        }
-    }
-}
-
-impl ProgressCallback for IndicatifProgress {
-    fn on_start(&mut self, total_files: u64, _total_size: u64) {
-        self.pb = ProgressBar::new(total_files);
-        let style = ProgressStyle::default_bar();
-        let style = match style
-            .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} ({percent}%) {msg}")
-        {
-            Ok(style) => style.progress_chars("=>-"),
-            Err(_) => ProgressStyle::default_bar(),
-        };
-        self.pb.set_style(style);
-    }
-
-    fn on_file(&mut self, path: &Path, _size: u64) {
-        let path_str = path.to_string_lossy();
-        self.pb.set_message(ui::truncate_path(&path_str, 30));
-    }
-
-    fn on_file_complete(&mut self, _success: bool) {
-        self.pb.inc(1);
-    }
-
-    fn on_complete(&mut self, _result: &ScanResult) {
-        self.pb.finish_and_clear();
+        } => duplicates(ctx, &path, min_size, delete),
     }
 }
 
@@ -139,17 +99,30 @@ fn scan(path_str: &str, force: bool) -> Result<()> {
     }
 
     // Scan with progress
-    let mut progress = IndicatifProgress::new();
+    let mut progress = progress::ScanBar::new();
+    let started = Instant::now();
     let result = manifest_db.scan(&path, force, &mut progress)?;
+    let elapsed = started.elapsed();
 
     println!();
-    ui::success(&format!("Scan complete: {} files hashed", result.hashed));
-    if result.errors > 0 {
-        ui::warn(&format!("  Errors: {} (could not read)", result.errors));
-    }
-    if result.pruned > 0 {
-        ui::dim(&format!("  Pruned: {} (no longer exist)", result.pruned));
-    }
+    let mut card = ui::SummaryCard::new("Scan complete");
+    card.row(ui::Status::Success, "Hashed", result.hashed)
+        .count(ui::Status::Error, "Could not read", result.errors)
+        .count(
+            ui::Status::Skipped,
+            "Pruned, no longer exist",
+            result.pruned,
+        )
+        .row(
+            ui::Status::Info,
+            "Time",
+            format!(
+                "{} ({})",
+                ui::human_duration(elapsed),
+                ui::rate(result.hashed_bytes, elapsed)
+            ),
+        );
+    card.print();
 
     // Show duplicate summary
     if result.duplicates.duplicate_groups > 0 {
@@ -170,16 +143,30 @@ fn scan(path_str: &str, force: bool) -> Result<()> {
 // Stats Command
 // ============================================================================
 
-fn stats(path_str: &str) -> Result<()> {
+fn stats(ctx: &Context, path_str: &str, prefix: Option<&str>) -> Result<()> {
     let path = crate::paths::expand(path_str);
     let name = manifest::path_to_name(&path);
 
-    ui::header(&format!("Manifest Stats: {}", path.display()));
-
     let db_path = manifest_db_path(&name)?;
     let manifest_db = Manifest::open(&db_path)?;
 
-    let stats = manifest_db.stats()?;
+    let stats = match prefix {
+        Some(prefix) => manifest_db.stats_for_prefix(prefix)?,
+        None => manifest_db.stats()?,
+    };
+
+    ctx.output()
+        .emit(&stats, || print_stats(&path, prefix, &stats))
+}
+
+fn print_stats(path: &Path, prefix: Option<&str>, stats: &manifest::ManifestStats) {
+    match prefix {
+        Some(prefix) => ui::header(&format!(
+            "Manifest Stats: {}",
+            path.join(prefix.trim_matches('/')).display()
+        )),
+        None => ui::header(&format!("Manifest Stats: {}", path.display())),
+    }
 
     println!();
     ui::kv("Total files", &stats.file_count.to_string());
@@ -204,18 +191,26 @@ fn stats(path_str: &str) -> Result<()> {
             &format!("{:.1}%", stats.savings_percentage()),
         );
     }
-
-    Ok(())
 }
 
 // ============================================================================
 // Duplicates Command
 // ============================================================================
 
-fn duplicates(path_str: &str, min_size: u64, delete: bool) -> Result<()> {
+fn duplicates(ctx: &Context, path_str: &str, min_size: u64, delete: bool) -> Result<()> {
     let path = crate::paths::expand(path_str);
     let name = manifest::path_to_name(&path);
 
+    // Deleting is interactive, so it always gets the human listing
+    if ctx.is_json() && !delete {
+        let manifest_db = Manifest::open(&manifest_db_path(&name)?)?;
+        let mut output = ctx.output();
+        for group in manifest_db.find_duplicates(min_size)? {
+            output.json(&group)?;
+        }
+        return Ok(());
+    }
+
     ui::header(&format!("Duplicates: {}", path.display()));
 
     let db_path = manifest_db_path(&name)?;
@@ -228,24 +223,26 @@ fn duplicates(path_str: &str, min_size: u64, delete: bool) -> Result<()> {
         return Ok(());
     }
 
-    let mut total_wasted: u64 = 0;
-
-    println!();
-    for (i, group) in groups.iter().enumerate() {
-        let wasted = group.wasted_space();
-        total_wasted += wasted;
+    let total_wasted: u64 = groups.iter().map(DuplicateGroup::wasted_space).sum();
 
-        print_duplicate_group(i + 1, group);
-
-        // Limit output
-        if i >= 19 && !delete {
-            let remaining = groups.len() - 20;
-            if remaining > 0 {
-                ui::dim(&format!("... and {remaining} more duplicate groups"));
-            }
-            break;
-        }
+    // A pager can scroll through every group; otherwise show the first few
+    let shown = if delete || ui::pager::enabled() {
+        groups.len()
+    } else {
+        20
+    };
+    let mut listing = String::from("\n");
+    for (i, group) in groups.iter().take(shown).enumerate() {
+        listing.push_str(&format_duplicate_group(i + 1, group));
     }
+    if groups.len() > shown {
+        let remaining = groups.len() - shown;
+        listing.push_str(&format!(
+            "{}\n",
+            format!("... and {remaining} more duplicate groups").dimmed()
+        ));
+    }
+    ui::pager::maybe_page(&listing)?;
 
     println!();
     ui::kv("Total duplicate groups", &groups.len().to_string());
@@ -260,11 +257,11 @@ fn duplicates(path_str: &str, min_size: u64, delete: bool) -> Result<()> {
     Ok(())
 }
 
-fn print_duplicate_group(index: usize, group: &DuplicateGroup) {
+fn format_duplicate_group(index: usize, group: &DuplicateGroup) -> String {
     let wasted = group.wasted_space();
 
-    println!(
-        "{}. {} ({} each, {} copies, {} wasted)",
+    let mut out = format!(
+        "{}. {} ({} each, {} copies, {} wasted)\n",
         index.to_string().bold(),
         manifest::format_size(group.size_each).yellow(),
         manifest::format_size(group.size_each),
@@ -273,15 +270,14 @@ fn print_duplicate_group(index: usize, group: &DuplicateGroup) {
     );
 
     for (j, file_path) in group.paths.iter().enumerate() {
-        let prefix = if j == 0 { "  ★" } else { "  ✗" };
-        let color = if j == 0 { "green" } else { "red" };
-        if color == "green" {
-            println!("  {} {}", prefix.green(), file_path);
+        if j == 0 {
+            out.push_str(&format!("  {} {}\n", "  ★".green(), file_path));
         } else {
-            println!("  {} {}", prefix.red(), file_path.dimmed());
+            out.push_str(&format!("  {} {}\n", "  ✗".red(), file_path.dimmed()));
         }
     }
-    println!();
+    out.push('\n');
+    out
 }
 
 fn delete_duplicates(
@@ -9,19 +9,24 @@ pub mod brew;
 pub mod caches;
 pub mod cellar;
 pub mod collections;
+pub mod config;
 pub mod configs;
 pub mod defaults;
 pub mod disk;
 pub mod doctor;
 pub mod dotfiles;
 pub mod dotfiles_reconcile;
+pub mod freeze;
+pub mod hook;
 pub mod icloud;
 pub mod locations;
 pub mod manifest;
 pub mod migrate;
 pub mod nova;
 pub mod relocate;
+pub mod self_update;
 pub mod storage;
 pub mod stow;
 pub mod theme;
 pub mod tools;
+pub mod which;
@@ -4,12 +4,17 @@
 
 use anyhow::{Context, Result};
 use colored::Colorize;
+use declarative::{RetryPolicy, TagFilter, Variables};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
 
 use crate::Context as AppContext;
 use crate::cli::NovaArgs;
 use crate::config;
 use crate::engine::planner::ExecutionPlanExt;
 use crate::engine::{self, ExecuteOptions, ExecutionPlan};
+use crate::progress;
 use crate::resource::{
     BrewPackage, DefaultValue as ResDefaultValue, DockApp, DockFolder, FileHandler, GHExtension,
     MacOSDefault, PnpmPackage, Symlink, VSCodeExtension,
@@ -20,15 +25,25 @@ use crate::sudo::SudoConfig;
 use crate::ui;
 
 pub fn run(ctx: &AppContext, args: NovaArgs) -> Result<()> {
-    ui::banner();
-
     if args.list_stages {
+        ui::banner();
         list_stages();
         return Ok(());
     }
 
-    ui::header("Bossa Nova - System Bootstrap");
-    println!();
+    if args.json && !args.yes && !args.dry_run && !args.import {
+        anyhow::bail!("--json needs --yes or --dry-run, as it can't prompt for confirmation");
+    }
+    if args.json {
+        // Events only on stdout; stage messages and tool output go to stderr
+        ui::output::reserve_stdout()?;
+    }
+
+    if !args.json {
+        ui::banner();
+        ui::header("Bossa Nova - System Bootstrap");
+        println!();
+    }
 
     // Load config
     let config = load_config()?;
@@ -37,29 +52,65 @@ pub fn run(ctx: &AppContext, args: NovaArgs) -> Result<()> {
     let plan = build_plan(ctx, &config, &args)?;
 
     if plan.is_empty() {
-        ui::success("Nothing to do - system is already configured!");
+        if !args.json {
+            ui::success("Nothing to do - system is already configured!");
+        }
         return Ok(());
     }
 
+    if args.import {
+        return print_import(&plan, args.json);
+    }
+
     // Show what we're going to do
-    println!(
-        "  {} resources to apply ({} unprivileged, {} privileged)",
-        plan.total_resources().to_string().bold(),
-        plan.unprivileged.len().to_string().green(),
-        plan.privileged.len().to_string().yellow()
-    );
-    println!();
+    if !args.json {
+        println!(
+            "  {} resources to apply ({} unprivileged, {} privileged)",
+            plan.total_resources().to_string().bold(),
+            plan.unprivileged.len().to_string().green(),
+            plan.privileged.len().to_string().yellow()
+        );
+        println!();
+    }
+
+    // Progress is checkpointed, so an interrupted run can be resumed
+    let state_dir = crate::paths::state_dir()?;
+    let checkpoint = state_dir.join("nova-checkpoint.json");
+    if !args.resume && !args.dry_run && checkpoint.exists() {
+        if !args.json {
+            ui::info("Starting over; pass --resume to continue the interrupted run instead");
+        }
+        std::fs::remove_file(&checkpoint)
+            .with_context(|| format!("Failed to remove {}", checkpoint.display()))?;
+    }
+
+    // Missing Homebrew packages install in bulk first; the plan then finds
+    // them present, and retries and reports only the ones that failed
+    if !args.dry_run {
+        install_brew_packages(&plan, &args)?;
+    }
 
     // Execute
     let opts = ExecuteOptions {
         dry_run: args.dry_run,
         jobs: args.jobs.map_or(4, |j| j as usize),
+        concurrency: concurrency_limits(),
+        concurrency_groups: concurrency_groups(),
         yes: args.yes,
         verbose: ctx.verbose > 0,
+        json: args.json,
+        retry: retry_policy(),
+        timeout: (args.timeout > 0).then(|| Duration::from_secs(args.timeout)),
+        checkpoint: (args.resume || !args.dry_run).then_some(checkpoint),
+        audit_log: Some(state_dir.join("audit.jsonl")),
     };
 
     let summary = engine::execute(plan, opts)?;
 
+    if let Some(path) = &args.report {
+        write_report(path, &summary.to_event_summary())?;
+    }
+
     if !summary.is_success() {
         anyhow::bail!("{} resource(s) failed to apply", summary.failed);
     }
@@ -67,6 +118,123 @@ pub fn run(ctx: &AppContext, args: NovaArgs) -> Result<()> {
     Ok(())
 }
 
+/// Homebrew packages the plan installs without sudo.
+fn brew_packages(plan: &ExecutionPlan) -> Vec<brewkit::Package> {
+    plan.unprivileged
+        .iter()
+        .filter_map(|resource| match resource.resource_type() {
+            "brew_tap" => Some(brewkit::Package::tap(resource.id())),
+            "brew_formula" => Some(brewkit::Package::brew(resource.id())),
+            "brew_cask" => Some(brewkit::Package::cask(resource.id())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Install the plan's missing Homebrew packages concurrently.
+///
+/// Installing them one resource at a time takes as long as every install
+/// added up; [`brewkit::Client::install_many`] overlaps downloads and
+/// builds, keeping casks on a lane of their own.
+fn install_brew_packages(plan: &ExecutionPlan, args: &NovaArgs) -> Result<()> {
+    let packages = brew_packages(plan);
+    if packages.is_empty() {
+        return Ok(());
+    }
+    let Ok(client) = brewkit::Client::new() else {
+        return Ok(());
+    };
+    let brewfile = brewkit::Brewfile {
+        path: None,
+        packages,
+    };
+    let missing = client.audit(&brewfile)?.missing;
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let jobs = args.jobs.map_or(4, usize::from);
+    if !args.yes
+        && !ui::prompt::confirm(
+            &format!(
+                "Install {} missing Homebrew packages, {jobs} at a time?",
+                missing.len()
+            ),
+            true,
+        )?
+    {
+        return Ok(());
+    }
+
+    let pb = progress::clone_bar(
+        missing.len() as u64,
+        &format!("Installing ({jobs} at a time)"),
+    );
+    let options = brewkit::InstallManyOptions {
+        jobs,
+        ..Default::default()
+    };
+    let failed = client
+        .install_many_with_progress(&missing, &options, &progress::BrewBar(pb.clone()))
+        .iter()
+        .filter(|outcome| outcome.as_ref().is_err_and(|e| !e.is_ignorable()))
+        .count();
+    progress::finish_success(&pb, "Install complete");
+    if failed > 0 {
+        ui::warn(&format!(
+            "{failed} Homebrew package(s) failed to install — retrying them one at a time"
+        ));
+    }
+    Ok(())
+}
+
+/// Write a run's summary as JUnit XML or JSON, by the file's extension.
+fn write_report(path: &Path, summary: &declarative::ExecuteSummary) -> Result<()> {
+    let report = if path.extension().is_some_and(|ext| ext == "xml") {
+        summary.to_junit_xml()
+    } else {
+        summary.to_json()?
+    };
+    std::fs::write(path, report).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Retry resources that download things, which often fail transiently.
+fn retry_policy() -> RetryPolicy {
+    [
+        "brew_formula",
+        "brew_cask",
+        "brew_tap",
+        "gh_extension",
+        "pnpm_package",
+        "vscode_extension",
+    ]
+    .into_iter()
+    .fold(RetryPolicy::default(), |policy, resource_type| {
+        policy.with_override(resource_type, 3)
+    })
+}
+
+/// Parallel jobs for resource types (or groups of them) that need other
+/// than the default.
+///
+/// Homebrew takes one lock for every install, so concurrent installs only
+/// wait on each other, while symlinks and defaults writes are cheap.
+fn concurrency_limits() -> HashMap<String, usize> {
+    [("brew", 1), ("symlink", 16), ("macos_default", 8)]
+        .into_iter()
+        .map(|(pool, jobs)| (pool.to_string(), jobs))
+        .collect()
+}
+
+/// Resource types sharing a pool: formulae, casks and taps all go through
+/// the Homebrew lock, so they take turns in one pool.
+fn concurrency_groups() -> HashMap<String, String> {
+    ["brew_formula", "brew_cask", "brew_tap"]
+        .into_iter()
+        .map(|resource_type| (resource_type.to_string(), "brew".to_string()))
+        .collect()
+}
+
 /// Install Homebrew if it's not already present.
 ///
 /// Returns `Ok(())` immediately if brew is already on `$PATH` (idempotent).
@@ -80,11 +248,7 @@ pub(crate) fn install_homebrew(yes: bool) -> Result<()> {
     ui::info("Homebrew is not installed.");
 
     if !yes {
-        let confirmed = dialoguer::Confirm::new()
-            .with_prompt("Install Homebrew now?")
-            .default(true)
-            .interact()
-            .context("Failed to read confirmation")?;
+        let confirmed = ui::prompt::confirm("Install Homebrew now?", true)?;
 
         if !confirmed {
             anyhow::bail!("Homebrew installation declined — cannot continue without brew");
@@ -119,22 +283,28 @@ pub(crate) fn install_homebrew(yes: bool) -> Result<()> {
 }
 
 fn load_config() -> Result<BossaConfig> {
-    let config_dir = config::config_dir()?;
+    load_config_from(&config::config_dir()?, crate::schema::selected_profile())
+}
 
-    // Try to load unified config
-    match config::load_config::<BossaConfig>(&config_dir, "config") {
-        Ok((config, _format)) => Ok(config),
+/// Load the unified config from `config_dir`, with the host and `profile`
+/// overlays merged on
+fn load_config_from(config_dir: &Path, profile: Option<&str>) -> Result<BossaConfig> {
+    let config = match config::load_config::<BossaConfig>(config_dir, "config") {
+        Ok((config, _format)) => config,
         Err(_) => {
             // Return default config if none exists
             ui::warn("No config found at ~/.config/bossa/config.toml");
             ui::info("Run 'bossa add' commands or create config manually");
-            Ok(BossaConfig::default())
+            BossaConfig::default()
         }
-    }
+    };
+    config.resolve(profile)
 }
 
 fn build_plan(ctx: &AppContext, config: &BossaConfig, args: &NovaArgs) -> Result<ExecutionPlan> {
     let mut plan = ExecutionPlan::new();
+    plan.variables = Variables::from_system();
+    plan.variables.extend(config.variables.clone());
 
     // Convert schema::SudoConfig to sudo::SudoConfig
     let sudo_config = SudoConfig {
@@ -153,12 +323,17 @@ fn build_plan(ctx: &AppContext, config: &BossaConfig, args: &NovaArgs) -> Result
 
     // Stage: defaults
     if stages.contains(&"defaults") {
-        add_defaults_resources(&mut plan, config, &sudo_config)?;
+        let mut stage = ExecutionPlan::new();
+        add_defaults_resources(&mut stage, config, &sudo_config)?;
+        plan.append(stage.with_tags(&["defaults"]));
     }
 
     // Stage: packages (brew)
     if stages.contains(&"packages") {
-        add_brew_resources(&mut plan, config, &sudo_config)?;
+        let groups = split_list(args.group.as_deref());
+        let mut stage = ExecutionPlan::new();
+        add_brew_resources(&mut stage, config, &sudo_config, &groups)?;
+        plan.append(stage.with_tags(&["packages"]));
     }
 
     // Stage: cellar (sync homebrew packages to external SSD)
@@ -168,6 +343,13 @@ fn build_plan(ctx: &AppContext, config: &BossaConfig, args: &NovaArgs) -> Result
         ui::warn(&format!("Cellar stage failed: {e} — continuing"));
     }
 
+    // Stage: tools (build tools from [toolchain], installed in parallel)
+    if stages.contains(&"tools")
+        && let Err(e) = install_toolchain(config, args)
+    {
+        ui::warn(&format!("Tools stage failed: {e} — continuing"));
+    }
+
     // Stage: dotfiles (must run before symlinks — stow depends on ~/.dotfiles)
     if stages.contains(&"dotfiles")
         && let Err(e) = super::dotfiles::sync_for_nova(config)
@@ -177,25 +359,96 @@ fn build_plan(ctx: &AppContext, config: &BossaConfig, args: &NovaArgs) -> Result
 
     // Stage: symlinks
     if stages.contains(&"symlinks") {
-        add_symlink_resources(&mut plan, config)?;
+        let mut stage = ExecutionPlan::new();
+        add_symlink_resources(&mut stage, config, &plan.variables)?;
+        plan.append(stage.with_tags(&["symlinks"]));
     }
 
     // Stage: dock
     if stages.contains(&"dock") {
-        add_dock_resources(&mut plan, config)?;
+        let mut stage = ExecutionPlan::new();
+        add_dock_resources(&mut stage, config)?;
+        plan.append(stage.with_tags(&["dock"]));
     }
 
     // Stage: handlers
     if stages.contains(&"handlers") {
-        add_handler_resources(&mut plan, config)?;
+        let mut stage = ExecutionPlan::new();
+        add_handler_resources(&mut stage, config)?;
+        plan.append(stage.with_tags(&["handlers"]));
     }
 
     // Stage: ecosystem (pnpm, gh, vscode)
     if stages.contains(&"ecosystem") {
-        add_ecosystem_resources(&mut plan, config)?;
+        let mut stage = ExecutionPlan::new();
+        add_ecosystem_resources(&mut stage, config)?;
+        plan.append(stage.with_tags(&["ecosystem"]));
+    }
+
+    // Only the tagged subset, if asked
+    let tags = TagFilter::new()
+        .include(split_list(args.tags.as_deref()))
+        .exclude(split_list(args.skip_tags.as_deref()));
+    Ok(plan.filter_by_tags(&tags))
+}
+
+/// Install the missing build tools listed in `[toolchain]`, all at once.
+fn install_toolchain(config: &BossaConfig, args: &NovaArgs) -> Result<()> {
+    let client = super::tools::toolchain_client(Some(config))?;
+    let mut missing = Vec::new();
+    for (name, version) in &config.toolchain.tools {
+        let tool = toolchain::Tool::from_name(name)
+            .with_context(|| format!("Unknown toolchain tool '{name}'"))?;
+        if !client.is_installed(tool)? {
+            let options = toolchain::InstallOptions::new()
+                .version(version)
+                .require_checksum(config.toolchain.require_checksums);
+            missing.push((tool, options));
+        }
+    }
+    if missing.is_empty() {
+        return Ok(());
     }
 
-    Ok(plan)
+    let names: Vec<_> = missing.iter().map(|(tool, _)| tool.name()).collect();
+    if args.dry_run {
+        ui::info(&format!("Would install build tools: {}", names.join(", ")));
+        return Ok(());
+    }
+    ui::info(&format!("Installing build tools: {}", names.join(", ")));
+
+    let jobs = args.jobs.map_or(4, usize::from);
+    let mut failed = 0;
+    for ((tool, _), result) in missing.iter().zip(client.install_many(&missing, jobs)) {
+        match result {
+            Ok(installed) => {
+                ui::success(&format!("{tool} {}", installed.version));
+                if installed.unverified {
+                    ui::warn(&format!(
+                        "{tool}: the release has no checksum, so the download wasn't verified"
+                    ));
+                }
+            }
+            Err(e) => {
+                ui::error(&format!("{tool}: {e}"));
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{failed} build tool(s) failed to install");
+    }
+    Ok(())
+}
+
+/// Split a comma-separated flag value.
+fn split_list(list: Option<&str>) -> Vec<String> {
+    list.map_or_else(Vec::new, |l| {
+        l.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    })
 }
 
 /// Map user-facing stage aliases to internal canonical names.
@@ -209,6 +462,21 @@ fn normalize_stage(name: &str) -> &str {
 }
 
 fn determine_stages(args: &NovaArgs) -> Vec<&'static str> {
+    let stages = selected_stages(args);
+
+    // An import only reads the system, so the stages that change it while
+    // the plan is built don't run
+    if args.import {
+        return stages
+            .into_iter()
+            .filter(|s| !EAGER_STAGES.contains(s))
+            .collect();
+    }
+    stages
+}
+
+/// Stages picked by `--only` and `--skip`.
+fn selected_stages(args: &NovaArgs) -> Vec<&'static str> {
     // If --only specified, use only those
     if let Some(ref only) = args.only {
         let only_set: Vec<&str> = only.split(',').map(|s| normalize_stage(s.trim())).collect();
@@ -232,6 +500,38 @@ fn determine_stages(args: &NovaArgs) -> Vec<&'static str> {
     IMPLEMENTED_STAGES.to_vec()
 }
 
+/// Print what an import found, without changing anything
+fn print_import(plan: &ExecutionPlan, json: bool) -> Result<()> {
+    let report = declarative::import(plan);
+    if json {
+        ui::output::write_line(&serde_json::to_string(&report)?)?;
+        return Ok(());
+    }
+
+    ui::success(&format!(
+        "{} resources already configured",
+        report.adopted.len()
+    ));
+    if !report.suggestions.is_empty() {
+        println!();
+        ui::section("Suggested config entries");
+        for (section, suggestions) in report.by_section() {
+            println!("  [{section}]");
+            for suggestion in suggestions {
+                println!("  {}", suggestion.entry);
+            }
+            println!();
+        }
+    }
+    if !report.pending.is_empty() {
+        ui::info(&format!(
+            "{} resources would still change; run without --import to apply them",
+            report.pending.len()
+        ));
+    }
+    Ok(())
+}
+
 fn add_defaults_resources(
     plan: &mut ExecutionPlan,
     config: &BossaConfig,
@@ -259,10 +559,16 @@ fn add_defaults_resources(
     Ok(())
 }
 
+/// Add brew resources to the plan.
+///
+/// With no `groups`, installs the base lists followed by every configured
+/// group. Otherwise only the base taps and the named groups are installed, in
+/// the order given.
 fn add_brew_resources(
     plan: &mut ExecutionPlan,
     config: &BossaConfig,
     sudo_config: &SudoConfig,
+    groups: &[String],
 ) -> Result<()> {
     let brew = &config.packages.brew;
 
@@ -272,20 +578,79 @@ fn add_brew_resources(
         plan.add_resource(Box::new(resource), sudo_config);
     }
 
+    // Only the requested groups
+    if !groups.is_empty() {
+        for name in groups {
+            let group = brew
+                .group(name)
+                .with_context(|| format!("Unknown brew group: {name}"))?;
+            let mut group_plan = ExecutionPlan::new();
+            add_brew_lists(
+                &mut group_plan,
+                sudo_config,
+                &group.taps,
+                &group.formulas,
+                &group.casks,
+                &group.fonts,
+            );
+            plan.append(group_plan.with_tags(&[&group.name]));
+        }
+        return Ok(());
+    }
+
     // Essential formulas (with retry - TODO: implement retry in executor)
     for pkg in &brew.essential.packages {
         let resource = BrewPackage::formula(pkg);
         plan.add_resource(Box::new(resource), sudo_config);
     }
 
+    add_brew_lists(
+        plan,
+        sudo_config,
+        &[],
+        &brew.formulas,
+        &brew.casks,
+        &brew.fonts,
+    );
+
+    // Groups, progressively in declaration order
+    for group in &brew.groups {
+        let mut group_plan = ExecutionPlan::new();
+        add_brew_lists(
+            &mut group_plan,
+            sudo_config,
+            &group.taps,
+            &group.formulas,
+            &group.casks,
+            &group.fonts,
+        );
+        plan.append(group_plan.with_tags(&[&group.name]));
+    }
+
+    Ok(())
+}
+
+fn add_brew_lists(
+    plan: &mut ExecutionPlan,
+    sudo_config: &SudoConfig,
+    taps: &[String],
+    formulas: &[String],
+    casks: &[String],
+    fonts: &[String],
+) {
+    for tap in taps {
+        let resource = BrewPackage::tap(tap);
+        plan.add_resource(Box::new(resource), sudo_config);
+    }
+
     // Regular formulas
-    for formula in &brew.formulas {
+    for formula in formulas {
         let resource = BrewPackage::formula(formula);
         plan.add_resource(Box::new(resource), sudo_config);
     }
 
     // Casks (check sudo allowlist)
-    for cask in &brew.casks {
+    for cask in casks {
         let mut resource = BrewPackage::cask(cask);
         if sudo_config.cask_requires_sudo(cask) {
             resource = resource.with_sudo(true);
@@ -294,15 +659,17 @@ fn add_brew_resources(
     }
 
     // Fonts (also casks)
-    for font in &brew.fonts {
+    for font in fonts {
         let resource = BrewPackage::cask(font);
         plan.add_resource(Box::new(resource), sudo_config);
     }
-
-    Ok(())
 }
 
-fn add_symlink_resources(plan: &mut ExecutionPlan, config: &BossaConfig) -> Result<()> {
+fn add_symlink_resources(
+    plan: &mut ExecutionPlan,
+    config: &BossaConfig,
+    variables: &Variables,
+) -> Result<()> {
     let symlinks_opt = &config.symlinks;
 
     // Check if symlinks config exists
@@ -315,10 +682,10 @@ fn add_symlink_resources(plan: &mut ExecutionPlan, config: &BossaConfig) -> Resu
         return Ok(());
     }
 
-    let source_base = crate::paths::expand(&symlinks.source)
+    let source_base = crate::paths::expand(&variables.expand_known(&symlinks.source)?)
         .to_string_lossy()
         .to_string();
-    let target_base = crate::paths::expand(&symlinks.target)
+    let target_base = crate::paths::expand(&variables.expand_known(&symlinks.target)?)
         .to_string_lossy()
         .to_string();
 
@@ -460,6 +827,7 @@ const IMPLEMENTED_STAGES: &[&str] = &[
     "homebrew",
     "packages",
     "cellar",
+    "tools",
     "dotfiles",
     "symlinks",
     "dock",
@@ -467,6 +835,10 @@ const IMPLEMENTED_STAGES: &[&str] = &[
     "ecosystem",
 ];
 
+/// Stages that change the system while the plan is built, rather than
+/// adding resources to it.
+const EAGER_STAGES: &[&str] = &["homebrew", "cellar", "tools", "dotfiles"];
+
 fn list_stages() {
     use crate::cli::NovaStage;
 
@@ -506,3 +878,113 @@ fn list_stages() {
         "bossa nova --dry-run".bold()
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn test_build_plan_with_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("config.toml"),
+            r#"
+[packages.brew]
+casks = ["firefox"]
+
+[profiles.work.packages.brew]
+casks = ["slack"]
+"#,
+        )
+        .unwrap();
+
+        let ctx = AppContext {
+            verbose: 0,
+            quiet: true,
+            format: ui::output::OutputFormat::Human,
+        };
+        let args = NovaArgs::try_parse_from(["nova", "--only", "packages", "--dry-run"]).unwrap();
+        let ids = |profile| {
+            let config = load_config_from(dir.path(), profile).unwrap();
+            let plan = build_plan(&ctx, &config, &args).unwrap();
+            plan.unprivileged
+                .iter()
+                .chain(&plan.privileged)
+                .map(|r| r.id())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(None), ["firefox"]);
+        assert_eq!(ids(Some("work")), ["firefox", "slack"]);
+        assert!(load_config_from(dir.path(), Some("home")).is_err());
+    }
+
+    #[test]
+    fn test_import_skips_eager_stages() {
+        let args = NovaArgs::try_parse_from(["nova", "--import"]).unwrap();
+        let stages = determine_stages(&args);
+        assert!(stages.iter().all(|s| !EAGER_STAGES.contains(s)));
+        assert!(stages.contains(&"packages"));
+
+        let args =
+            NovaArgs::try_parse_from(["nova", "--import", "--only", "homebrew,tools"]).unwrap();
+        assert!(determine_stages(&args).is_empty());
+
+        let args = NovaArgs::try_parse_from(["nova", "--dry-run"]).unwrap();
+        assert_eq!(determine_stages(&args), IMPLEMENTED_STAGES);
+    }
+
+    #[test]
+    fn test_brew_packages_leave_privileged_casks_out() {
+        let mut config = BossaConfig::default();
+        config.packages.brew.taps = vec!["owner/tap".to_string()];
+        config.packages.brew.formulas = vec!["git".to_string()];
+        config.packages.brew.casks = vec!["firefox".to_string(), "docker".to_string()];
+        config.sudo.casks = vec!["docker".to_string()];
+
+        let ctx = AppContext {
+            verbose: 0,
+            quiet: true,
+            format: ui::output::OutputFormat::Human,
+        };
+        let args = NovaArgs::try_parse_from(["nova", "--only", "packages", "--dry-run"]).unwrap();
+        let plan = build_plan(&ctx, &config, &args).unwrap();
+        let packages: Vec<_> = brew_packages(&plan)
+            .into_iter()
+            .map(|p| (p.package_type, p.name))
+            .collect();
+        assert_eq!(
+            packages,
+            [
+                (brewkit::PackageType::Tap, "owner/tap".to_string()),
+                (brewkit::PackageType::Brew, "git".to_string()),
+                (brewkit::PackageType::Cask, "firefox".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_symlinks_expand_environment_references() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("dotfiles/zsh")).unwrap();
+        std::fs::write(dir.path().join("dotfiles/zsh/.zshrc"), "").unwrap();
+
+        let mut config = BossaConfig::default();
+        config.symlinks = Some(crate::schema::SymlinksConfig {
+            source: "${dotfiles}".to_string(),
+            target: "${HOME}".to_string(),
+            packages: vec!["zsh".to_string()],
+            ignore: Vec::new(),
+        });
+        let variables =
+            Variables::new().with("dotfiles", dir.path().join("dotfiles").to_string_lossy());
+
+        // ${HOME} isn't a bossa variable; it's left for the environment
+        let mut plan = ExecutionPlan::new();
+        add_symlink_resources(&mut plan, &config, &variables).unwrap();
+        let home = std::env::var("HOME").unwrap();
+        let ids: Vec<_> = plan.unprivileged.iter().map(|r| r.id()).collect();
+        assert_eq!(ids, [format!("{home}/.zshrc")]);
+    }
+}
@@ -110,11 +110,7 @@ pub fn run(ctx: &AppContext, cmd: RelocateCommand) -> Result<()> {
     // Confirm
     if !cmd.yes && !ctx.quiet {
         println!();
-        let confirmed = dialoguer::Confirm::new()
-            .with_prompt("Proceed with updates?")
-            .default(false)
-            .interact()
-            .unwrap_or(false);
+        let confirmed = ui::prompt::confirm("Proceed with updates?", false).unwrap_or(false);
         if !confirmed {
             println!("Aborted.");
             return Ok(());
@@ -0,0 +1,61 @@
+//! Self-update command - replace the running bossa with the latest release
+//!
+//! Downloads go through the `[network]` proxy, like tool installs, and the
+//! archive is checked against its published SHA-256 before anything is
+//! replaced.
+
+use anyhow::{Context, Result};
+use toolchain::backend::github::GitHubBackend;
+use toolchain::self_update::SelfUpdate;
+
+use crate::Context as AppContext;
+use crate::commands::tools::network_proxy;
+use crate::schema::BossaConfig;
+use crate::ui;
+
+pub fn run(_ctx: &AppContext, check: bool) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let mut updater =
+        SelfUpdate::new(current).context("Self-update is not available on this platform")?;
+
+    let config = BossaConfig::load().ok();
+    if let Some(proxy) = network_proxy(config.as_ref()) {
+        let backend = GitHubBackend::new()
+            .with_proxy(&proxy)
+            .context("Invalid proxy in [network] config")?;
+        updater = updater.with_backend(backend);
+    }
+
+    let Some(update) = updater
+        .check()
+        .context("Failed to check for bossa releases")?
+    else {
+        ui::success(&format!("bossa {current} is up to date"));
+        return Ok(());
+    };
+
+    if check {
+        ui::info(&format!(
+            "bossa {} is available (current: {current})",
+            update.version
+        ));
+        ui::dim("Run 'bossa self-update' to install it.");
+        return Ok(());
+    }
+
+    ui::info(&format!(
+        "Downloading {} ({})",
+        update.asset.name,
+        update.asset.human_size()
+    ));
+    let path = updater
+        .apply(&update)
+        .with_context(|| format!("Failed to update {}", updater.executable().display()))?;
+
+    ui::success(&format!(
+        "Updated bossa {current} -> {} at {}",
+        update.version,
+        path.display()
+    ));
+    Ok(())
+}
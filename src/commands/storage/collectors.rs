@@ -31,7 +31,10 @@ pub fn collect_icloud_stats() -> Option<ICloudStats> {
     let client = icloud::Client::new().ok()?;
     let root = client.icloud_root().ok()?;
 
-    let mut stats = ICloudStats::default();
+    let mut stats = ICloudStats {
+        quota: client.account_usage().ok(),
+        ..Default::default()
+    };
 
     for entry in WalkDir::new(&root)
         .max_depth(ICLOUD_MAX_DEPTH)
@@ -83,6 +83,19 @@ pub fn show_icloud(stats: &Option<ICloudStats>) {
             ),
         );
     }
+
+    if let Some(quota) = &stats.quota {
+        let account = match (quota.total, quota.used_fraction()) {
+            (Some(total), Some(fraction)) => format!(
+                "{} free of {} ({:.0}% used)",
+                ui::format_size(quota.available),
+                ui::format_size(total),
+                fraction * 100.0
+            ),
+            _ => format!("{} free", ui::format_size(quota.available)),
+        };
+        ui::kv("Account", &account);
+    }
 }
 
 // ============================================================================
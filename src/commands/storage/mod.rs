@@ -20,11 +20,14 @@ mod duplicates;
 mod types;
 
 use anyhow::Result;
+use std::path::Path;
 
+use crate::Context;
 use crate::ui;
 
 use collectors::{collect_icloud_stats, collect_manifest_stats};
-use display::{show_hints, show_icloud, show_local_ssd, show_manifests, show_t9};
+use disk::get_disk_space;
+use display::{T9_MOUNT, show_hints, show_icloud, show_local_ssd, show_manifests, show_t9};
 
 // ============================================================================
 // Public API
@@ -38,7 +41,23 @@ use display::{show_hints, show_icloud, show_local_ssd, show_manifests, show_t9};
 /// - T9 external drive status
 /// - Scanned manifest statistics
 /// - Optimization hints
-pub fn status() -> Result<()> {
+///
+/// With `--format json`, prints the same figures as one JSON record.
+pub fn status(ctx: &Context) -> Result<()> {
+    if ctx.is_json() {
+        let t9 = Path::new(T9_MOUNT)
+            .exists()
+            .then(|| get_disk_space(T9_MOUNT).ok())
+            .flatten();
+        return ctx.output().record(
+            &ui::output::Record::untitled()
+                .field("local_ssd", get_disk_space("/")?)
+                .field("icloud", collect_icloud_stats())
+                .field("t9", t9)
+                .field("manifests", collect_manifest_stats()?),
+        );
+    }
+
     ui::header("Storage Overview");
 
     // Local SSD
@@ -1,9 +1,10 @@
 //! Data types for storage module
 
+use serde::Serialize;
 use std::path::PathBuf;
 
 /// iCloud statistics collected in a single pass
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct ICloudStats {
     pub local_bytes: u64,
     pub cloud_bytes: u64,
@@ -11,10 +12,12 @@ pub struct ICloudStats {
     pub cloud_count: usize,
     pub evictable_bytes: u64,
     pub evictable_count: usize,
+    /// Account quota (None if the backend can't report it)
+    pub quota: Option<icloud::AccountUsage>,
 }
 
 /// Manifest statistics for display
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ManifestInfo {
     pub name: String,
     pub file_count: u64,
@@ -31,7 +34,7 @@ pub struct ManifestEntry {
 }
 
 /// Disk space information
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct DiskSpace {
     pub total: u64,
     pub available: u64,
@@ -512,7 +512,7 @@ fn list() -> Result<()> {
 
 /// Add a package to config
 fn add(package: &str) -> Result<()> {
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     let symlinks = config.symlinks.get_or_insert_with(Default::default);
 
@@ -546,7 +546,7 @@ fn add(package: &str) -> Result<()> {
 
 /// Remove a package from config
 fn rm(package: &str, do_unlink: bool) -> Result<()> {
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     let symlinks = match &mut config.symlinks {
         Some(s) => s,
@@ -681,7 +681,7 @@ fn unlink(packages: &[String], dry_run: bool) -> Result<()> {
 
 /// Initialize symlinks config
 fn init(source: Option<&str>, target: Option<&str>, force: bool) -> Result<()> {
-    let mut config = BossaConfig::load()?;
+    let mut config = BossaConfig::load_base()?;
 
     if config.symlinks.is_some() && !force {
         bail!("Symlinks already configured. Use --force to overwrite.");
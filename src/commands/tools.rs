@@ -187,7 +187,7 @@ pub fn run(ctx: &Context, cmd: ToolsCommand) -> Result<()> {
         ToolsCommand::List { all } => list(ctx, all),
         ToolsCommand::Status { name } => status(ctx, &name),
         ToolsCommand::Uninstall { name, dry_run, yes } => uninstall(ctx, &name, dry_run, yes),
-        ToolsCommand::Outdated { tools, json } => outdated(ctx, &tools, json),
+        ToolsCommand::Outdated { tools, json } => outdated(ctx, &tools, json || ctx.is_json()),
     }
 }
 
@@ -297,7 +297,9 @@ fn apply(ctx: &Context, filter_tools: &[String], dry_run: bool, force: bool) ->
                     ui::success(&format!("  ✓ {name} installed"));
                     if let Some(ref msg) = def.post_install {
                         println!();
-                        ui::dim(msg);
+                        for line in ui::markdown::render(msg).lines() {
+                            println!("  {line}");
+                        }
                         println!();
                     }
                 }
@@ -312,12 +314,11 @@ fn apply(ctx: &Context, filter_tools: &[String], dry_run: bool, force: bool) ->
 
     if !ctx.quiet && !dry_run {
         println!();
-        ui::header("Summary");
-        ui::kv("Installed", &installed.to_string());
-        ui::kv("Skipped", &skipped.to_string());
-        if failed > 0 {
-            ui::kv("Failed", &failed.to_string());
-        }
+        let mut card = ui::SummaryCard::new("Summary");
+        card.row(ui::Status::Success, "Installed", installed)
+            .row(ui::Status::Skipped, "Skipped", skipped)
+            .count(ui::Status::Error, "Failed", failed);
+        card.print();
     }
 
     if failed > 0 {
@@ -854,8 +855,33 @@ fn install_from_container(
 // List, Status, Uninstall
 // =============================================================================
 
+/// Proxy from the `[network]` settings, if one is set.
+///
+/// Release downloads are HTTPS, so `https_proxy` wins over `http_proxy`.
+pub fn network_proxy(config: Option<&BossaConfig>) -> Option<toolchain::ProxyConfig> {
+    let network = config.map(|c| &c.network)?;
+    let url = network
+        .https_proxy
+        .as_ref()
+        .or(network.http_proxy.as_ref())?;
+    Some(toolchain::ProxyConfig::new(url).no_proxy(network.no_proxy.as_deref()))
+}
+
+/// Toolchain client honoring the `[network]` proxy settings.
+pub fn toolchain_client(config: Option<&BossaConfig>) -> Result<toolchain::Client> {
+    let Some(proxy) = network_proxy(config) else {
+        return Ok(toolchain::Client::new());
+    };
+
+    toolchain::Client::builder()
+        .proxy(proxy.url)
+        .no_proxy(proxy.no_proxy)
+        .build()
+        .context("Invalid proxy in [network] config")
+}
+
 /// List installed tools.
-fn list(_ctx: &Context, show_all: bool) -> Result<()> {
+fn list(ctx: &Context, show_all: bool) -> Result<()> {
     let state = ToolsConfig::load()?;
     let config = BossaConfig::load().ok();
 
@@ -865,7 +891,21 @@ fn list(_ctx: &Context, show_all: bool) -> Result<()> {
         .map(|c| c.tools.definitions.keys().cloned().collect())
         .unwrap_or_default();
 
-    if state.tools.is_empty() && (!show_all || defined_tools.is_empty()) {
+    // Build tools installed by the toolchain crate, unless tools.toml
+    // already tracks them
+    let build_tools: Vec<_> = toolchain_client(config.as_ref())
+        .unwrap_or_default()
+        .list_installed()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|t| !state.tools.contains_key(t.tool.name()))
+        .collect();
+
+    if ctx.is_json() {
+        return list_json(ctx, &state, config.as_ref(), &build_tools, show_all);
+    }
+
+    if state.tools.is_empty() && build_tools.is_empty() && (!show_all || defined_tools.is_empty()) {
         ui::info("No tools installed yet.");
         ui::info(
             "Use 'bossa tools install', 'bossa tools install-container', or 'bossa tools apply' to install tools.",
@@ -903,6 +943,16 @@ fn list(_ctx: &Context, show_all: bool) -> Result<()> {
         println!();
     }
 
+    for tool in &build_tools {
+        println!("  ✓ {} [toolchain]", tool.tool);
+        ui::kv("    Version", &tool.version);
+        ui::kv("    Path", &tool.path.display().to_string());
+        if !tool.installed_at.is_empty() {
+            ui::kv("    Installed", &tool.installed_at);
+        }
+        println!();
+    }
+
     // Show tools from config that aren't installed
     if show_all {
         let installed_names: HashSet<_> = state.tools.keys().cloned().collect();
@@ -936,6 +986,64 @@ fn list(_ctx: &Context, show_all: bool) -> Result<()> {
     Ok(())
 }
 
+/// Print a record per tool, as JSON lines; with `show_all`, tools defined
+/// in the config but not installed too
+fn list_json(
+    ctx: &Context,
+    state: &ToolsConfig,
+    config: Option<&BossaConfig>,
+    build_tools: &[toolchain::InstalledTool],
+    show_all: bool,
+) -> Result<()> {
+    let mut output = ctx.output();
+    let defined = |name: &str| config.is_some_and(|c| c.tools.get(name).is_some());
+
+    for (name, tool) in &state.tools {
+        output.record(
+            &ui::output::Record::untitled()
+                .field("name", name)
+                .field("source", &tool.source)
+                .field("installed", PathBuf::from(&tool.install_path).exists())
+                .field("binary", &tool.binary)
+                .field("path", &tool.install_path)
+                .field("url", &tool.url)
+                .field("image", tool.container.as_ref().map(|c| &c.image))
+                .field("installed_at", &tool.installed_at)
+                .field("in_config", defined(name)),
+        )?;
+    }
+
+    for tool in build_tools {
+        output.record(
+            &ui::output::Record::untitled()
+                .field("name", tool.tool.name())
+                .field("source", "toolchain")
+                .field("installed", true)
+                .field("version", &tool.version)
+                .field("path", &tool.path)
+                .field("installed_at", &tool.installed_at),
+        )?;
+    }
+
+    if show_all && let Some(cfg) = config {
+        for (name, def) in &cfg.tools.definitions {
+            if state.tools.contains_key(name) {
+                continue;
+            }
+            output.record(
+                &ui::output::Record::untitled()
+                    .field("name", name)
+                    .field("source", format!("{:?}", def.source).to_lowercase())
+                    .field("installed", false)
+                    .field("enabled", def.enabled)
+                    .field("in_config", true),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
 /// Show status of a specific tool.
 fn status(_ctx: &Context, name: &str) -> Result<()> {
     let state = ToolsConfig::load()?;
@@ -1097,11 +1205,8 @@ fn uninstall(ctx: &Context, name: &str, dry_run: bool, yes: bool) -> Result<()>
 
     // Confirmation prompt
     if !yes && !ctx.quiet {
-        let confirmed = dialoguer::Confirm::new()
-            .with_prompt(format!("Uninstall '{name}'?"))
-            .default(false)
-            .interact()
-            .unwrap_or(false);
+        let confirmed =
+            ui::prompt::confirm(&format!("Uninstall '{name}'?"), false).unwrap_or(false);
         if !confirmed {
             ui::info("Aborted.");
             return Ok(());
@@ -1153,12 +1258,7 @@ struct VersionInfo {
 impl VersionInfo {
     fn is_outdated(&self) -> bool {
         match (&self.current, &self.latest) {
-            (Some(current), Some(latest)) => {
-                // Normalize versions for comparison
-                let c = current.trim_start_matches('v');
-                let l = latest.trim_start_matches('v');
-                c != l
-            }
+            (Some(current), Some(latest)) => toolchain::versions::is_newer(latest, current),
             _ => false,
         }
     }
@@ -1396,7 +1496,7 @@ fn get_current_version(name: &str) -> Option<String> {
 }
 
 /// Extract version number from a string
-fn extract_version(s: &str) -> Option<String> {
+pub fn extract_version(s: &str) -> Option<String> {
     // Try to find a semver-like pattern
     let re = regex::Regex::new(r"v?(\d+\.\d+(?:\.\d+)?(?:-[\w.]+)?)").ok()?;
     re.captures(s.lines().next()?)
@@ -0,0 +1,369 @@
+//! Which command - find out which subsystem installed a binary
+//!
+//! Resolves every copy of `<name>` on PATH, then cross-references Homebrew,
+//! `tools.toml`, toolchain installs, cargo and npm to name the owner of each.
+//! The first hit is the active binary; the rest are shadows.
+
+use anyhow::{Result, bail};
+use colored::Colorize;
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use crate::schema::ToolsConfig;
+use crate::ui;
+
+/// Subsystem that put a binary on PATH
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Owner {
+    /// Installed by `bossa tools` (recorded in tools.toml)
+    Tools { name: String, source: String },
+    /// Homebrew formula in the Cellar
+    Brew { formula: String, version: String },
+    /// Homebrew cask in the Caskroom
+    BrewCask { cask: String, version: String },
+    /// Installed by the toolchain crate (e.g., buck2)
+    Toolchain,
+    /// `cargo install` into `$CARGO_HOME/bin`
+    Cargo,
+    /// Global npm/pnpm/bun package
+    Npm,
+    /// Shipped with the operating system
+    System,
+    /// None of the above
+    Manual,
+}
+
+impl Owner {
+    /// Version implied by the install location, if any
+    fn version(&self) -> Option<&str> {
+        match self {
+            Self::Brew { version, .. } | Self::BrewCask { version, .. } => Some(version),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Owner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tools { name, source } => write!(f, "bossa tools ({name}, {source})"),
+            Self::Brew { formula, .. } => write!(f, "brew ({formula})"),
+            Self::BrewCask { cask, .. } => write!(f, "brew cask ({cask})"),
+            Self::Toolchain => write!(f, "toolchain"),
+            Self::Cargo => write!(f, "cargo install"),
+            Self::Npm => write!(f, "npm"),
+            Self::System => write!(f, "system"),
+            Self::Manual => write!(f, "manual"),
+        }
+    }
+}
+
+/// Install roots used to classify a binary
+struct Roots {
+    cargo_bin: Option<PathBuf>,
+    local_bin: Option<PathBuf>,
+}
+
+impl Roots {
+    fn detect() -> Self {
+        let cargo_bin = std::env::var_os("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|h| h.join(".cargo")))
+            .map(|c| c.join("bin"));
+        let local_bin = dirs::home_dir().map(|h| h.join(".local").join("bin"));
+        Self {
+            cargo_bin,
+            local_bin,
+        }
+    }
+}
+
+/// One copy of the binary found on PATH
+struct Candidate {
+    path: PathBuf,
+    resolved: PathBuf,
+    owner: Owner,
+    version: Option<String>,
+}
+
+pub fn run(name: &str) -> Result<()> {
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    let hits = find_on_path(name, &path_var);
+    if hits.is_empty() {
+        bail!("'{name}' not found on PATH");
+    }
+
+    let tools = ToolsConfig::load().unwrap_or_default();
+    let roots = Roots::detect();
+
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for path in hits {
+        let resolved = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        // The same file reached through two PATH entries (e.g., /bin -> /usr/bin)
+        if candidates.iter().any(|c| c.resolved == resolved) {
+            continue;
+        }
+        let owner = classify(name, &path, &resolved, &tools, &roots);
+        let version = owner
+            .version()
+            .map(str::to_string)
+            .or_else(|| probe_version(&path));
+        candidates.push(Candidate {
+            path,
+            resolved,
+            owner,
+            version,
+        });
+    }
+
+    let (active, shadows) = candidates.split_first().expect("at least one hit");
+
+    ui::header(&format!("which {name}"));
+    println!();
+    ui::kv("Path", &active.path.display().to_string());
+    if active.resolved != active.path {
+        ui::kv("Resolves to", &active.resolved.display().to_string());
+    }
+    ui::kv("Installed by", &active.owner.to_string());
+    ui::kv("Version", active.version.as_deref().unwrap_or("unknown"));
+
+    if shadows.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    ui::section("Shadowed on PATH");
+    for shadow in shadows {
+        let version = shadow.version.as_deref().unwrap_or("unknown");
+        let differs = shadow.version.is_some() && shadow.version != active.version;
+        let line = format!(
+            "  {} - {} {}",
+            shadow.path.display(),
+            shadow.owner,
+            format!("({version})").dimmed()
+        );
+        if differs {
+            println!("{} {}", line, "version differs".yellow());
+        } else {
+            println!("{line}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Every executable named `name` in `path_var`, in PATH order
+fn find_on_path(name: &str, path_var: &OsStr) -> Vec<PathBuf> {
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(name))
+        .filter(|candidate| is_executable(candidate))
+        .collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Decide which subsystem owns `path` (which resolves to `resolved`)
+fn classify(name: &str, path: &Path, resolved: &Path, tools: &ToolsConfig, roots: &Roots) -> Owner {
+    if let Some((tool_name, tool)) = tools.tools.iter().find(|(_, t)| {
+        let install = Path::new(&t.install_path);
+        install == path || install == resolved
+    }) {
+        return Owner::Tools {
+            name: tool_name.clone(),
+            source: tool.source.clone(),
+        };
+    }
+
+    if let Some((formula, version)) = after_component(resolved, "Cellar") {
+        return Owner::Brew { formula, version };
+    }
+    if let Some((cask, version)) = after_component(resolved, "Caskroom") {
+        return Owner::BrewCask { cask, version };
+    }
+
+    if resolved
+        .components()
+        .any(|c| c.as_os_str() == "node_modules")
+    {
+        return Owner::Npm;
+    }
+
+    if roots
+        .cargo_bin
+        .as_deref()
+        .is_some_and(|bin| path.starts_with(bin))
+    {
+        return Owner::Cargo;
+    }
+
+    if toolchain::Tool::all().iter().any(|t| t.name() == name)
+        && roots.local_bin.as_deref() == path.parent()
+    {
+        return Owner::Toolchain;
+    }
+
+    if ["/usr/bin", "/bin", "/usr/sbin", "/sbin", "/System"]
+        .iter()
+        .any(|dir| path.starts_with(dir))
+    {
+        return Owner::System;
+    }
+
+    Owner::Manual
+}
+
+/// The two path components after `marker` (e.g., `Cellar/<formula>/<version>`)
+fn after_component(path: &Path, marker: &str) -> Option<(String, String)> {
+    let mut components = path.components().skip_while(|c| c.as_os_str() != marker);
+    components.next()?;
+    let mut next = || match components.next()? {
+        Component::Normal(s) => Some(s.to_string_lossy().to_string()),
+        _ => None,
+    };
+    Some((next()?, next()?))
+}
+
+/// Run `<path> --version` and pull out the version number
+fn probe_version(path: &Path) -> Option<String> {
+    let output = Command::new(path).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    super::tools::extract_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::InstalledTool;
+
+    fn roots() -> Roots {
+        Roots {
+            cargo_bin: Some(PathBuf::from("/home/me/.cargo/bin")),
+            local_bin: Some(PathBuf::from("/home/me/.local/bin")),
+        }
+    }
+
+    fn owner(name: &str, path: &str, resolved: &str, tools: &ToolsConfig) -> Owner {
+        classify(name, Path::new(path), Path::new(resolved), tools, &roots())
+    }
+
+    #[test]
+    fn classifies_install_locations() {
+        let none = ToolsConfig::default();
+
+        assert_eq!(
+            owner(
+                "rg",
+                "/opt/homebrew/bin/rg",
+                "/opt/homebrew/Cellar/ripgrep/14.1.0/bin/rg",
+                &none
+            ),
+            Owner::Brew {
+                formula: "ripgrep".to_string(),
+                version: "14.1.0".to_string()
+            }
+        );
+        assert_eq!(
+            owner(
+                "rg",
+                "/home/me/.cargo/bin/rg",
+                "/home/me/.cargo/bin/rg",
+                &none
+            ),
+            Owner::Cargo
+        );
+        assert_eq!(
+            owner(
+                "tsc",
+                "/usr/local/bin/tsc",
+                "/usr/local/lib/node_modules/typescript/bin/tsc",
+                &none
+            ),
+            Owner::Npm
+        );
+        assert_eq!(
+            owner(
+                "buck2",
+                "/home/me/.local/bin/buck2",
+                "/home/me/.local/bin/buck2",
+                &none
+            ),
+            Owner::Toolchain
+        );
+        assert_eq!(owner("ls", "/bin/ls", "/usr/bin/ls", &none), Owner::System);
+        assert_eq!(
+            owner("foo", "/opt/foo/bin/foo", "/opt/foo/bin/foo", &none),
+            Owner::Manual
+        );
+    }
+
+    #[test]
+    fn tools_toml_takes_precedence() {
+        let mut tools = ToolsConfig::default();
+        tools.insert(
+            "buck2".to_string(),
+            InstalledTool {
+                url: "https://example.com/buck2".to_string(),
+                binary: "buck2".to_string(),
+                install_path: "/home/me/.local/bin/buck2".to_string(),
+                installed_at: "2026-01-01T00:00:00Z".to_string(),
+                source: "github-release".to_string(),
+                container: None,
+            },
+        );
+
+        assert_eq!(
+            owner(
+                "buck2",
+                "/home/me/.local/bin/buck2",
+                "/home/me/.local/bin/buck2",
+                &tools
+            ),
+            Owner::Tools {
+                name: "buck2".to_string(),
+                source: "github-release".to_string()
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn finds_every_executable_in_path_order() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (first, second, empty) = (
+            dir.path().join("a"),
+            dir.path().join("b"),
+            dir.path().join("c"),
+        );
+        for d in [&first, &second, &empty] {
+            std::fs::create_dir(d).unwrap();
+        }
+        for d in [&first, &second] {
+            let bin = d.join("tool");
+            std::fs::write(&bin, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        // Not executable: ignored
+        std::fs::write(empty.join("tool"), "").unwrap();
+
+        let path_var = std::env::join_paths([&second, &empty, &first]).unwrap();
+        assert_eq!(
+            find_on_path("tool", &path_var),
+            vec![second.join("tool"), first.join("tool")]
+        );
+    }
+}
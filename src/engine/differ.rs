@@ -1,6 +1,7 @@
 //! Diff computation and display - bossa-specific UI
 
 use crate::resource::Resource;
+use crate::ui;
 use colored::Colorize;
 use declarative::{ResourceDiff, ResourceState};
 
@@ -50,6 +51,18 @@ pub fn display_diff(diffs: &[ResourceDiff]) {
         println!("│ {}", type_name.bold());
 
         for diff in type_diffs {
+            // Long ids, like file paths, would push the states out of line
+            let id = ui::truncate_middle(&diff.resource_id, 30);
+            if let Some(reason) = &diff.skipped {
+                println!(
+                    "│   {} {:<30} {}",
+                    "⊘".yellow(),
+                    id,
+                    format!("(skipped: {reason})").dimmed()
+                );
+                continue;
+            }
+
             let symbol = match (&diff.current, &diff.desired) {
                 (ResourceState::Absent, ResourceState::Present { .. }) => "+".green(),
                 (ResourceState::Present { .. }, ResourceState::Absent) => "-".red(),
@@ -66,58 +79,99 @@ pub fn display_diff(diffs: &[ResourceDiff]) {
             };
 
             let state_desc = match (&diff.current, &diff.desired) {
-                (ResourceState::Absent, ResourceState::Present { details }) => {
-                    format!(
-                        "(not installed){}",
-                        details
-                            .as_ref()
-                            .map(|d| format!(" → {d}"))
-                            .unwrap_or_default()
-                    )
-                }
+                (ResourceState::Absent, ResourceState::Present { details }) => format!(
+                    "(not installed){}",
+                    details
+                        .as_ref()
+                        .map(|d| format!(" → {d}"))
+                        .unwrap_or_default()
+                )
+                .dimmed()
+                .to_string(),
                 (
                     ResourceState::Present { details: from },
                     ResourceState::Present { details: to },
-                ) => {
-                    format!(
-                        "{} → {}",
-                        from.as_deref().unwrap_or("current"),
-                        to.as_deref().unwrap_or("desired")
-                    )
-                }
+                ) => ui::diff::inline(
+                    from.as_deref().unwrap_or("current"),
+                    to.as_deref().unwrap_or("desired"),
+                ),
                 (ResourceState::Present { .. }, ResourceState::Absent) => {
-                    "(will remove)".to_string()
+                    "(will remove)".dimmed().to_string()
                 }
                 _ => String::new(),
             };
 
-            println!(
-                "│   {} {:<30} {}{}",
-                symbol,
-                diff.resource_id,
-                state_desc.dimmed(),
-                sudo_indicator
-            );
+            println!("│   {symbol} {id:<30} {state_desc}{sudo_indicator}");
+
+            // Multi-line states, like file contents, get a line by line diff
+            if is_multiline(&diff.current) || is_multiline(&diff.desired) {
+                let hunks = ui::diff::unified(
+                    state_text(&diff.current, false),
+                    state_text(&diff.desired, true),
+                );
+                for line in hunks.lines() {
+                    println!("│       {line}");
+                }
+            }
         }
         println!("│");
     }
 
     // Summary
-    let sudo_count = diffs.iter().filter(|d| d.requires_sudo).count();
-    let regular_count = diffs.len() - sudo_count;
+    let skipped_count = diffs.iter().filter(|d| d.is_skipped()).count();
+    let change_count = diffs.len() - skipped_count;
+    let sudo_count = diffs
+        .iter()
+        .filter(|d| d.requires_sudo && !d.is_skipped())
+        .count();
+    let regular_count = change_count - sudo_count;
 
     println!("├─────────────────────────────────────────────────────┤");
     println!(
         "│ Summary: {} changes ({} unprivileged, {} require sudo)",
-        diffs.len().to_string().bold(),
+        change_count.to_string().bold(),
         regular_count.to_string().green(),
         sudo_count.to_string().red()
     );
+    if skipped_count > 0 {
+        println!(
+            "│          {} skipped by conditions",
+            skipped_count.to_string().yellow()
+        );
+    }
     println!("└─────────────────────────────────────────────────────┘");
 }
 
+fn is_multiline(state: &ResourceState) -> bool {
+    match state {
+        ResourceState::Present {
+            details: Some(details),
+        } => details.contains('\n'),
+        ResourceState::Modified { from, to } => from.contains('\n') || to.contains('\n'),
+        _ => false,
+    }
+}
+
+/// A state's text for a line diff, as it is now or as it's desired to be;
+/// an absent resource has none
+fn state_text(state: &ResourceState, desired: bool) -> &str {
+    match state {
+        ResourceState::Present {
+            details: Some(details),
+        } => details,
+        ResourceState::Modified { to, .. } if desired => to,
+        ResourceState::Modified { from, .. } => from,
+        _ => "",
+    }
+}
+
 /// Display the sudo boundary warning
 pub fn display_sudo_boundary(privileged_diffs: &[ResourceDiff]) {
+    // Skipped resources won't need sudo
+    let privileged_diffs: Vec<&ResourceDiff> = privileged_diffs
+        .iter()
+        .filter(|d| !d.is_skipped())
+        .collect();
     if privileged_diffs.is_empty() {
         return;
     }
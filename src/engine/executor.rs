@@ -3,14 +3,24 @@
 use anyhow::{Context as AnyhowContext, Result};
 use colored::Colorize;
 use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use crate::progress;
-use crate::resource::{ApplyContext, ApplyResult, Resource};
+use crate::resource::{ApplyResult, Resource};
 use crate::sudo::SudoContext;
-use declarative::{ExecutionPlan, SudoProvider};
+use crate::ui;
+use crate::ui::prompt::ConfirmPrompt;
+use declarative::{
+    AuditLog, Checkpoint, ConfirmCallback, ExecutionPlan, ExecutorEvent, ResourceDiff, ResourceExt,
+    ResourceOutcome, RetryPolicy, SudoProvider, Variables, apply_with_retry, cache_key,
+    render_planned,
+};
 
 use super::differ::{compute_diffs, display_diff, display_sudo_boundary};
+use super::triage::{FailedResource, TriageReport};
 
 /// Options for execution (bossa-specific, includes `yes` for confirmation skip)
 #[derive(Debug, Clone)]
@@ -19,10 +29,27 @@ pub struct ExecuteOptions {
     pub dry_run: bool,
     /// Number of parallel jobs
     pub jobs: usize,
+    /// Parallel jobs by resource type, each run in a pool of its own
+    /// alongside the `jobs` shared by all other types
+    pub concurrency: HashMap<String, usize>,
+    /// Resource types that share a pool, by the name of its limit in
+    /// `concurrency`
+    pub concurrency_groups: HashMap<String, String>,
     /// Skip confirmation prompts
     pub yes: bool,
     /// Verbose output
     pub verbose: bool,
+    /// Print executor events as NDJSON instead of the human UI
+    pub json: bool,
+    /// How often to attempt failing resources
+    pub retry: RetryPolicy,
+    /// How long a resource may apply before it fails, unless it sets its
+    /// own timeout; `None` waits forever
+    pub timeout: Option<Duration>,
+    /// Checkpoint file to record progress in, and resume from
+    pub checkpoint: Option<PathBuf>,
+    /// JSON lines file to append every applied resource to
+    pub audit_log: Option<PathBuf>,
 }
 
 impl Default for ExecuteOptions {
@@ -30,8 +57,15 @@ impl Default for ExecuteOptions {
         Self {
             dry_run: false,
             jobs: 4,
+            concurrency: HashMap::new(),
+            concurrency_groups: HashMap::new(),
             yes: false,
             verbose: false,
+            json: false,
+            retry: RetryPolicy::default(),
+            timeout: None,
+            checkpoint: None,
+            audit_log: None,
         }
     }
 }
@@ -45,6 +79,16 @@ pub struct ExecuteSummary {
     pub skipped: usize,
     pub failed: usize,
     pub no_change: usize,
+    /// Attempts taken by resources that were retried, by ID
+    pub attempts: BTreeMap<String, u32>,
+    /// Resources left out because an interrupted run already applied them
+    pub resumed: usize,
+    /// Failures grouped by probable root cause
+    pub triage: TriageReport,
+    /// What happened to each resource, in the order they finished
+    pub outcomes: Vec<ResourceOutcome>,
+    /// On a dry run, the changes that would have been made
+    pub planned: Vec<ResourceDiff>,
 }
 
 impl ExecuteSummary {
@@ -55,10 +99,117 @@ impl ExecuteSummary {
     pub fn is_success(&self) -> bool {
         self.failed == 0
     }
+
+    /// The summary as reported in a `plan_finished` event, and exported
+    /// with `--report`
+    pub fn to_event_summary(&self) -> declarative::ExecuteSummary {
+        declarative::ExecuteSummary {
+            created: self.created,
+            modified: self.modified,
+            removed: self.removed,
+            skipped: self.skipped,
+            failed: self.failed,
+            no_change: self.no_change,
+            attempts: self.attempts.clone(),
+            resumed: self.resumed,
+            planned: self.planned.clone(),
+            outcomes: self.outcomes.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Writes executor events to stdout as NDJSON, one object per line (see
+/// [`ui::output::write_line`])
+///
+/// Does nothing unless enabled, so the engine can emit events
+/// unconditionally.
+#[derive(Debug, Clone, Copy)]
+struct EventLog {
+    enabled: bool,
+}
+
+impl EventLog {
+    fn emit(self, event: &ExecutorEvent) {
+        if !self.enabled {
+            return;
+        }
+        if let Ok(line) = serde_json::to_string(event) {
+            let _ = ui::output::write_line(&line);
+        }
+    }
+
+    /// Report failures grouped by cause, as a `triage` event, if any
+    fn triage(self, report: &TriageReport) {
+        if !self.enabled || report.is_empty() {
+            return;
+        }
+        if let Ok(line) = report.to_json_event() {
+            let _ = ui::output::write_line(&line);
+        }
+    }
+
+    fn started(self, resource: &dyn Resource) {
+        self.emit(&ExecutorEvent::ResourceStarted {
+            id: resource.id(),
+            resource_type: resource.resource_type().to_string(),
+            description: resource.description(),
+        });
+    }
+
+    fn finished(self, resource: &dyn Resource, result: &ApplyResult, duration: Duration) {
+        self.emit(&ExecutorEvent::ResourceFinished {
+            id: resource.id(),
+            resource_type: resource.resource_type().to_string(),
+            result: result.clone(),
+            duration,
+        });
+    }
 }
 
 /// Execute the plan with bossa's UI integration
+///
+/// Resources run in dependency order; a resource whose dependency failed
+/// is skipped, and one that hangs past its timeout fails. With
+/// [`ExecuteOptions::json`], progress is reported as [`ExecutorEvent`] lines
+/// on stdout instead, with failures in a `triage` event (see
+/// [`TriageReport::to_json_event`]) just before `plan_finished`.
+///
+/// With [`ExecuteOptions::checkpoint`], resources an interrupted run already
+/// applied are left out, and progress is recorded for the next resume.
+/// With [`ExecuteOptions::audit_log`], every applied resource is appended
+/// to the audit log.
 pub fn execute(plan: ExecutionPlan, opts: ExecuteOptions) -> Result<ExecuteSummary> {
+    let events = EventLog { enabled: opts.json };
+
+    // Pick up where an interrupted run left off
+    let checkpoint = opts
+        .checkpoint
+        .as_ref()
+        .map(|path| Checkpoint::load(path.as_path()))
+        .transpose()?;
+    let mut resumed = 0;
+    let plan = match &checkpoint {
+        Some(checkpoint) if !checkpoint.is_empty() => {
+            let total = plan.total_resources();
+            let plan = plan.filter(|r| !checkpoint.is_converged(&cache_key(r)));
+            resumed = total - plan.total_resources();
+            plan
+        }
+        _ => plan,
+    };
+    if resumed > 0 && !opts.json {
+        let t = ui::theme();
+        println!(
+            "  {} Resuming: {} resources already applied by an interrupted run",
+            t.info(t.icons.info),
+            resumed
+        );
+    }
+
+    // Order by dependencies first, so a cycle fails before anything runs
+    let stages = plan.stages()?;
+
     // 1. Compute diffs for all resources
     let unprivileged_diffs = compute_diffs(&plan.unprivileged);
     let privileged_diffs = compute_diffs(&plan.privileged);
@@ -69,145 +220,512 @@ pub fn execute(plan: ExecutionPlan, opts: ExecuteOptions) -> Result<ExecuteSumma
         .collect();
 
     // 2. Display what will change
-    display_diff(&all_diffs);
+    if !opts.json {
+        display_diff(&all_diffs);
+    }
 
-    if all_diffs.is_empty() {
-        return Ok(ExecuteSummary::default());
+    // Resources whose precondition isn't met are skipped, not applied
+    let unmet: HashMap<String, String> = all_diffs
+        .iter()
+        .filter_map(|diff| {
+            let key = format!("{}:{}", diff.resource_type, diff.resource_id);
+            diff.skipped.clone().map(|reason| (key, reason))
+        })
+        .collect();
+    let changes = all_diffs.len() - unmet.len();
+
+    // Every run is a started and finished plan on the event stream, even
+    // one with nothing to change
+    let started = Instant::now();
+    events.emit(&ExecutorEvent::PlanStarted {
+        resources: plan.total_resources(),
+        changes,
+    });
+
+    if changes == 0 {
+        if let Some(checkpoint) = &checkpoint
+            && !opts.dry_run
+        {
+            checkpoint.remove()?;
+        }
+        let summary = ExecuteSummary {
+            resumed,
+            ..Default::default()
+        };
+        events.emit(&ExecutorEvent::PlanFinished {
+            summary: summary.to_event_summary(),
+            duration: started.elapsed(),
+        });
+        return Ok(summary);
     }
 
     // 3. Confirm (unless --yes)
-    if !opts.yes && !opts.dry_run && !confirm_proceed()? {
-        println!();
-        println!("  {} Aborted", "✗".red());
-        return Ok(ExecuteSummary {
-            skipped: all_diffs.len(),
+    if !opts.yes && !opts.dry_run && !ConfirmPrompt.confirm(CONTINUE)? {
+        if !opts.json {
+            let t = ui::theme();
+            println!();
+            println!("  {} Aborted", t.error(t.icons.error));
+        }
+        let summary = ExecuteSummary {
+            skipped: changes,
             ..Default::default()
+        };
+        events.emit(&ExecutorEvent::PlanFinished {
+            summary: summary.to_event_summary(),
+            duration: started.elapsed(),
         });
+        return Ok(summary);
     }
 
     if opts.dry_run {
-        println!();
-        println!("  {} Dry run - no changes made", "ℹ".blue());
-        return Ok(ExecuteSummary::default());
+        let planned: Vec<_> = all_diffs
+            .iter()
+            .filter(|diff| !diff.is_skipped())
+            .cloned()
+            .collect();
+        if !opts.json {
+            let t = ui::theme();
+            println!();
+            println!("  {}", "Planned actions".bold());
+            for line in render_planned(&planned).lines() {
+                println!("    {line}");
+            }
+            println!();
+            println!("  {} Dry run - no changes made", t.info(t.icons.info));
+        }
+        let summary = ExecuteSummary {
+            resumed,
+            planned,
+            ..Default::default()
+        };
+        events.emit(&ExecutorEvent::PlanFinished {
+            summary: summary.to_event_summary(),
+            duration: started.elapsed(),
+        });
+        return Ok(summary);
     }
 
-    let mut summary = ExecuteSummary::default();
-
-    // 4. Execute unprivileged in parallel
-    if !plan.unprivileged.is_empty() {
-        println!();
-        println!(
-            "  {} Applying {} unprivileged resources...",
-            "→".cyan(),
-            plan.unprivileged.len()
-        );
-
-        let results = execute_parallel(&plan.unprivileged, opts.jobs, opts.verbose, None)?;
-        merge_summary(&mut summary, &results);
+    // Shared, so a resource that times out can be left applying
+    let all_unprivileged: Vec<Arc<dyn Resource>> =
+        plan.unprivileged.into_iter().map(Arc::from).collect();
+    let all_privileged: Vec<Arc<dyn Resource>> =
+        plan.privileged.into_iter().map(Arc::from).collect();
+    let variables = Arc::new(plan.variables);
+    let audit = opts
+        .audit_log
+        .as_ref()
+        .map(AuditLog::open)
+        .transpose()?
+        .map(Arc::new);
+    if let Some(audit) = &audit {
+        audit.remember(&all_diffs);
     }
 
-    // 5. If any privileged operations, acquire sudo ONCE
-    if !plan.privileged.is_empty() {
-        display_sudo_boundary(&privileged_diffs);
-
-        if !opts.yes && !confirm_proceed()? {
-            summary.skipped += plan.privileged.len();
-            return Ok(summary);
+    let checkpoint = checkpoint.map(std::sync::Mutex::new);
+    let mut summary = ExecuteSummary {
+        resumed,
+        ..Default::default()
+    };
+    let mut failures = Vec::new();
+    let mut sudo: Option<Arc<dyn SudoProvider>> = None;
+    let mut sudo_declined = false;
+    // Resources that failed or were skipped, so their dependents are skipped
+    let mut blocked = HashSet::new();
+
+    for stage in &stages {
+        // 4. Execute unprivileged in parallel
+        let unprivileged: Vec<&Arc<dyn Resource>> = stage
+            .unprivileged
+            .iter()
+            .map(|&i| &all_unprivileged[i])
+            .collect();
+        if !unprivileged.is_empty() {
+            if !opts.json {
+                let t = ui::theme();
+                println!();
+                println!(
+                    "  {} Applying {} unprivileged resources...",
+                    t.accent(t.icons.arrow),
+                    unprivileged.len()
+                );
+            }
+
+            let (results, failed) = execute_parallel(
+                &unprivileged,
+                opts.jobs,
+                &opts,
+                None,
+                &variables,
+                audit.as_ref(),
+                &unmet,
+                &mut blocked,
+                &mut summary.attempts,
+                checkpoint.as_ref(),
+            )?;
+            merge_summary(&mut summary, results);
+            failures.extend(failed);
         }
 
-        let sudo = SudoContext::acquire("Apply privileged system configuration")?;
+        let privileged: Vec<&Arc<dyn Resource>> = stage
+            .privileged
+            .iter()
+            .map(|&i| &all_privileged[i])
+            .collect();
+        if privileged.is_empty() {
+            continue;
+        }
 
-        println!();
-        println!(
-            "  {} Applying {} privileged resources...",
-            "→".cyan(),
-            plan.privileged.len()
-        );
+        // 5. If any privileged operations, acquire sudo ONCE
+        if sudo.is_none() && !sudo_declined {
+            if !opts.json {
+                display_sudo_boundary(&privileged_diffs);
+            }
+            if !opts.yes && !ConfirmPrompt.confirm(CONTINUE)? {
+                sudo_declined = true;
+            } else {
+                events.emit(&ExecutorEvent::SudoRequested {
+                    resources: all_privileged.len(),
+                });
+                sudo = Some(Arc::new(SudoContext::acquire(
+                    "Apply privileged system configuration",
+                )?));
+            }
+        }
+        let Some(sudo) = &sudo else {
+            let declined: Vec<_> = privileged
+                .iter()
+                .map(|resource| ResourceOutcome {
+                    resource_type: resource.resource_type().to_string(),
+                    resource_id: resource.id(),
+                    result: ApplyResult::Skipped {
+                        reason: "Sudo declined".to_string(),
+                    },
+                })
+                .collect();
+            merge_summary(&mut summary, declined);
+            blocked.extend(privileged.iter().map(|r| r.id()));
+            continue;
+        };
 
-        let results = execute_parallel(&plan.privileged, 1, opts.verbose, Some(&sudo))?; // Sequential for sudo
-        merge_summary(&mut summary, &results);
+        if !opts.json {
+            let t = ui::theme();
+            println!();
+            println!(
+                "  {} Applying {} privileged resources...",
+                t.accent(t.icons.arrow),
+                privileged.len()
+            );
+        }
 
-        // sudo dropped here automatically
+        let (results, failed) = execute_parallel(
+            &privileged,
+            1, // Sequential for sudo
+            &opts,
+            Some(sudo),
+            &variables,
+            audit.as_ref(),
+            &unmet,
+            &mut blocked,
+            &mut summary.attempts,
+            checkpoint.as_ref(),
+        )?;
+        merge_summary(&mut summary, results);
+        failures.extend(failed);
+    }
+    // Release sudo before restarting services (once any resource that timed
+    // out finishes, too)
+    drop(sudo);
+
+    if sudo_declined {
+        summary.triage = TriageReport::from_failures(failures);
+        if !opts.json {
+            summary.triage.print();
+        }
+        events.triage(&summary.triage);
+        events.emit(&ExecutorEvent::PlanFinished {
+            summary: summary.to_event_summary(),
+            duration: started.elapsed(),
+        });
+        return Ok(summary);
     }
 
     // 6. Restart services
     if !plan.post_actions.is_empty() {
-        println!();
-        println!("  {} Restarting services...", "→".cyan());
+        if !opts.json {
+            let t = ui::theme();
+            println!();
+            println!("  {} Restarting services...", t.accent(t.icons.arrow));
+        }
         for service in &plan.post_actions {
-            restart_service(service)?;
+            let restarted = restart_service(service)?;
+            if !opts.json {
+                print_restart(service, restarted);
+            }
         }
     }
 
-    // 7. Summary
-    print_summary(&summary);
+    // Everything converged, so the next run starts over
+    if let Some(checkpoint) = checkpoint
+        && summary.is_success()
+    {
+        checkpoint
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove()?;
+    }
+
+    // 7. Summary, with failures grouped by root cause
+    summary.triage = TriageReport::from_failures(failures);
+    if !opts.json {
+        print_summary(&summary);
+        summary.triage.print();
+    }
+    events.triage(&summary.triage);
+    events.emit(&ExecutorEvent::PlanFinished {
+        summary: summary.to_event_summary(),
+        duration: started.elapsed(),
+    });
 
     Ok(summary)
 }
 
 /// Execute resources in parallel
+///
+/// Resources depending on one in `blocked`, or with an unmet precondition
+/// in `unmet`, are skipped, and failing ones retried per
+/// [`ExecuteOptions::retry`]. Returns every result along with the failed
+/// resources and their errors, adds the IDs of failed and dependency-skipped
+/// resources to `blocked`, and records retried resources in `attempts` and
+/// converged ones in `checkpoint`.
+#[allow(clippy::too_many_arguments)]
 fn execute_parallel(
-    resources: &[Box<dyn Resource>],
+    resources: &[&Arc<dyn Resource>],
     jobs: usize,
-    verbose: bool,
-    sudo: Option<&SudoContext>,
-) -> Result<Vec<ApplyResult>> {
-    let pb = progress::clone_bar(resources.len() as u64, "Applying");
-    let results: Arc<std::sync::Mutex<Vec<ApplyResult>>> =
-        Arc::new(std::sync::Mutex::new(Vec::new()));
-
-    let pool = rayon::ThreadPoolBuilder::new()
-        .num_threads(jobs)
-        .build()
-        .context("Failed to create apply thread pool")?;
-
-    pool.install(|| {
-        resources.par_iter().for_each(|resource| {
-            // Convert SudoContext to trait object for ApplyContext
-            let sudo_provider: Option<&dyn SudoProvider> = sudo.map(|s| s as &dyn SudoProvider);
-
-            let mut ctx = ApplyContext {
-                dry_run: false,
-                verbose,
-                sudo: sudo_provider,
+    opts: &ExecuteOptions,
+    sudo: Option<&Arc<dyn SudoProvider>>,
+    variables: &Arc<Variables>,
+    audit: Option<&Arc<AuditLog>>,
+    unmet: &HashMap<String, String>,
+    blocked: &mut HashSet<String>,
+    attempts: &mut BTreeMap<String, u32>,
+    checkpoint: Option<&std::sync::Mutex<Checkpoint>>,
+) -> Result<(Vec<ResourceOutcome>, Vec<FailedResource>)> {
+    let events = EventLog { enabled: opts.json };
+    let mut skipped = Vec::new();
+    let mut results = Vec::new();
+    let mut runnable = Vec::with_capacity(resources.len());
+    for &resource in resources {
+        if let Some(reason) = unmet.get(&cache_key(resource.as_ref())) {
+            let result = ApplyResult::Skipped {
+                reason: reason.clone(),
             };
+            if events.enabled {
+                events.finished(resource.as_ref(), &result, Duration::ZERO);
+            } else {
+                let t = ui::theme();
+                println!(
+                    "  {} {} skipped: {reason}",
+                    t.warn(t.icons.skipped),
+                    resource.id()
+                );
+            }
+            results.push(outcome(resource.as_ref(), result));
+            continue;
+        }
+        match resource.blocked_by(blocked) {
+            Some(dependency) => skipped.push((resource, dependency)),
+            None => runnable.push(resource),
+        }
+    }
+    let resources = runnable;
 
-            let result = match resource.apply(&mut ctx) {
-                Ok(r) => r,
-                Err(e) => ApplyResult::Failed {
-                    error: e.to_string(),
-                },
-            };
+    // A checklist line per resource type, finished once all its resources are
+    let tasks = if events.enabled {
+        progress::TaskList::hidden()
+    } else {
+        progress::TaskList::new()
+    };
+    let mut by_type: HashMap<&str, (progress::Task, usize)> = HashMap::new();
+    for resource in &resources {
+        let resource_type = resource.resource_type();
+        by_type
+            .entry(resource_type)
+            .or_insert_with(|| (tasks.add(resource_type), 0))
+            .1 += 1;
+    }
+    for (task, count) in by_type.values() {
+        task.start(Some(*count as u64));
+    }
+    // Resources finished and failed so far, per type
+    let finished: std::sync::Mutex<HashMap<&str, (usize, usize)>> =
+        std::sync::Mutex::new(HashMap::new());
+    let applied: Arc<std::sync::Mutex<Vec<ResourceOutcome>>> =
+        Arc::new(std::sync::Mutex::new(Vec::new()));
+    let failures: std::sync::Mutex<Vec<FailedResource>> = std::sync::Mutex::new(Vec::new());
+    let retried: std::sync::Mutex<Vec<(String, u32)>> = std::sync::Mutex::new(Vec::new());
+
+    let apply = |resource: &&Arc<dyn Resource>| {
+        let timeout = resource.timeout().or(opts.timeout);
+
+        events.started(resource.as_ref());
+        let start = Instant::now();
+        let (result, tries) = apply_with_retry(
+            resource,
+            sudo,
+            variables,
+            audit,
+            opts.verbose,
+            timeout,
+            &opts.retry,
+        );
+        events.finished(resource.as_ref(), &result, start.elapsed());
+        if let Some(checkpoint) = checkpoint {
+            // A checkpoint that can't be saved only costs rechecking on resume
+            let _ = checkpoint
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .record(&cache_key(resource.as_ref()), &result);
+        }
+        if tries > 1 {
+            retried
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .push((resource.id(), tries));
+        }
 
-            let symbol = match &result {
-                ApplyResult::NoChange => "○",
-                ApplyResult::Created | ApplyResult::Modified | ApplyResult::Removed => "✓",
-                ApplyResult::Failed { .. } => "✗",
-                ApplyResult::Skipped { .. } => "⊘",
-            };
+        let resource_type = resource.resource_type();
+        if let Some((task, count)) = by_type.get(resource_type) {
+            task.inc(1);
+            let mut finished = finished
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let (done, failed) = finished.entry(resource_type).or_default();
+            *done += 1;
+            if matches!(result, ApplyResult::Failed { .. }) {
+                *failed += 1;
+            }
+            if done == count {
+                match *failed {
+                    0 => task.success(),
+                    failed => task.fail(&format!("{failed} failed")),
+                }
+            }
+        }
 
-            pb.set_message(format!("{} {}", symbol, resource.id()));
-            pb.inc(1);
+        if let ApplyResult::Failed { error } = &result {
+            failures
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .push(FailedResource {
+                    id: resource.id(),
+                    error: error.clone(),
+                });
+        }
 
-            push_apply_result(&results, result);
-        });
+        push_apply_result(&applied, outcome(resource.as_ref(), result));
+    };
+
+    // Resource types with a concurrency limit run in pools of their own,
+    // alongside everything else
+    let pools = concurrency_groups(&resources, jobs, opts)
+        .into_iter()
+        .map(|(jobs, group)| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(jobs)
+                .build()
+                .context("Failed to create apply thread pool")?;
+            Ok((pool, group))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    std::thread::scope(|scope| {
+        for (pool, group) in &pools {
+            let apply = &apply;
+            scope.spawn(move || pool.install(|| group.par_iter().for_each(apply)));
+        }
     });
 
-    pb.finish_and_clear();
+    let failures = failures
+        .into_inner()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    results.extend(into_apply_results(applied)?);
+    blocked.extend(failures.iter().map(|f| f.id.clone()));
+    attempts.extend(
+        retried
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner),
+    );
+    for (resource, dependency) in skipped {
+        let result = ApplyResult::Skipped {
+            reason: format!("Dependency {dependency} failed"),
+        };
+        if events.enabled {
+            events.finished(resource.as_ref(), &result, Duration::ZERO);
+        } else {
+            let t = ui::theme();
+            println!(
+                "  {} {} skipped: dependency {dependency} failed",
+                t.warn(t.icons.skipped),
+                resource.id()
+            );
+        }
+        results.push(outcome(resource.as_ref(), result));
+        blocked.insert(resource.id());
+    }
+    Ok((results, failures))
+}
+
+/// Split resources into groups that each run in a pool of their own: one
+/// per resource type (or group in [`ExecuteOptions::concurrency_groups`])
+/// with a limit in [`ExecuteOptions::concurrency`], and one of `jobs`
+/// threads for the rest
+///
+/// Sequential batches (`jobs` of 1) stay sequential.
+fn concurrency_groups<'a>(
+    resources: &[&'a Arc<dyn Resource>],
+    jobs: usize,
+    opts: &ExecuteOptions,
+) -> Vec<(usize, Vec<&'a Arc<dyn Resource>>)> {
+    if jobs == 1 {
+        return vec![(1, resources.to_vec())];
+    }
+    let mut groups: Vec<(Option<&str>, usize, Vec<_>)> = Vec::new();
+    for &resource in resources {
+        let resource_type = resource.resource_type();
+        let pool = opts
+            .concurrency_groups
+            .get(resource_type)
+            .map_or(resource_type, String::as_str);
+        let (key, limit) = match opts.concurrency.get(pool) {
+            Some(&limit) => (Some(pool), limit.max(1)),
+            None => (None, jobs),
+        };
+        match groups.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((_, _, group)) => group.push(resource),
+            None => groups.push((key, limit, vec![resource])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(_, limit, group)| (limit, group))
+        .collect()
+}
 
-    into_apply_results(results)
+fn outcome(resource: &dyn Resource, result: ApplyResult) -> ResourceOutcome {
+    ResourceOutcome {
+        resource_type: resource.resource_type().to_string(),
+        resource_id: resource.id(),
+        result,
+    }
 }
 
-fn push_apply_result(results: &Arc<std::sync::Mutex<Vec<ApplyResult>>>, result: ApplyResult) {
+fn push_apply_result<T>(results: &Arc<std::sync::Mutex<Vec<T>>>, result: T) {
     match results.lock() {
         Ok(mut locked) => locked.push(result),
         Err(poisoned) => poisoned.into_inner().push(result),
     }
 }
 
-fn into_apply_results(
-    results: Arc<std::sync::Mutex<Vec<ApplyResult>>>,
-) -> Result<Vec<ApplyResult>> {
+fn into_apply_results<T>(results: Arc<std::sync::Mutex<Vec<T>>>) -> Result<Vec<T>> {
     let mutex = Arc::try_unwrap(results)
         .map_err(|_| anyhow::anyhow!("Failed to collect apply results: shared result state"))?;
 
@@ -218,9 +736,9 @@ fn into_apply_results(
 }
 
 /// Merge results into summary
-fn merge_summary(summary: &mut ExecuteSummary, results: &[ApplyResult]) {
-    for result in results {
-        match result {
+fn merge_summary(summary: &mut ExecuteSummary, outcomes: Vec<ResourceOutcome>) {
+    for outcome in &outcomes {
+        match outcome.result {
             ApplyResult::NoChange => summary.no_change += 1,
             ApplyResult::Created => summary.created += 1,
             ApplyResult::Modified => summary.modified += 1,
@@ -229,76 +747,94 @@ fn merge_summary(summary: &mut ExecuteSummary, results: &[ApplyResult]) {
             ApplyResult::Skipped { .. } => summary.skipped += 1,
         }
     }
+    summary.outcomes.extend(outcomes);
 }
 
-/// Confirm with user
-fn confirm_proceed() -> Result<bool> {
-    use dialoguer::Confirm;
-
-    let confirmed = Confirm::new()
-        .with_prompt("Continue?")
-        .default(true)
-        .interact()?;
-
-    Ok(confirmed)
-}
+/// What the user confirms before changes are made
+const CONTINUE: &str = "Continue?";
 
 /// Restart a macOS service
-fn restart_service(service: &str) -> Result<()> {
+///
+/// Returns whether the service was running and got restarted.
+fn restart_service(service: &str) -> Result<bool> {
     use std::process::Command;
 
     let status = Command::new("killall").arg(service).status()?;
+    Ok(status.success())
+}
 
-    if status.success() {
-        println!("    {} Restarted {}", "✓".green(), service);
+/// Print the outcome of restarting a service
+fn print_restart(service: &str, restarted: bool) {
+    let t = ui::theme();
+    if restarted {
+        println!("    {} Restarted {}", t.success(t.icons.success), service);
     } else {
         println!(
             "    {} Could not restart {} (may not be running)",
-            "⚠".yellow(),
+            t.warn(t.icons.warn),
             service
         );
     }
-
-    Ok(())
 }
 
 /// Print final summary
 fn print_summary(summary: &ExecuteSummary) {
     println!();
-    if summary.is_success() {
-        println!(
-            "  {} Configuration applied successfully!",
-            "✓".green().bold()
-        );
+    let mut card = ui::SummaryCard::new(if summary.is_success() {
+        "Configuration applied successfully"
     } else {
-        println!(
-            "  {} Configuration applied with errors",
-            "⚠".yellow().bold()
-        );
-    }
-
-    if summary.created > 0 {
-        println!("    • {} resources created", summary.created);
-    }
-    if summary.modified > 0 {
-        println!("    • {} resources modified", summary.modified);
-    }
-    if summary.removed > 0 {
-        println!("    • {} resources removed", summary.removed);
-    }
-    if summary.skipped > 0 {
-        println!("    • {} resources skipped", summary.skipped);
-    }
-    if summary.failed > 0 {
-        println!("    • {} {} failed", summary.failed, "resources".red());
-    }
+        "Configuration applied with errors"
+    });
+    card.count(ui::Status::Success, "Created", summary.created)
+        .count(ui::Status::Success, "Modified", summary.modified)
+        .count(ui::Status::Success, "Removed", summary.removed)
+        .count(ui::Status::Skipped, "Skipped", summary.skipped)
+        .count(
+            ui::Status::Info,
+            "Applied by an interrupted run",
+            summary.resumed,
+        )
+        .count(ui::Status::Warn, "Retried", summary.attempts.len())
+        .count(ui::Status::Error, "Failed", summary.failed);
+    card.print();
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{into_apply_results, push_apply_result};
+    use super::{ExecuteSummary, into_apply_results, push_apply_result};
     use crate::resource::ApplyResult;
+    use declarative::ExecutorEvent;
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[test]
+    fn events_serialize_as_json_lines() {
+        let event = ExecutorEvent::ResourceFinished {
+            id: "ripgrep".to_string(),
+            resource_type: "brew_formula".to_string(),
+            result: ApplyResult::Created,
+            duration: Duration::from_millis(1520),
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"resource_finished","id":"ripgrep","resource_type":"brew_formula","result":"Created","duration_ms":1520}"#
+        );
+
+        let summary = ExecuteSummary {
+            created: 2,
+            failed: 1,
+            ..Default::default()
+        };
+        let event = ExecutorEvent::PlanFinished {
+            summary: summary.to_event_summary(),
+            duration: Duration::from_secs(3),
+        };
+        let json: serde_json::Value = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "plan_finished");
+        assert_eq!(json["summary"]["created"], 2);
+        assert_eq!(json["summary"]["failed"], 1);
+        assert_eq!(json["duration_ms"], 3000);
+    }
 
     #[test]
     fn push_apply_result_handles_poisoned_mutex() {
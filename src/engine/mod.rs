@@ -12,5 +12,6 @@ pub use declarative::ExecutionPlan;
 pub mod differ;
 pub mod executor;
 pub mod planner;
+pub mod triage;
 
 pub use executor::{ExecuteOptions, execute};
@@ -0,0 +1,293 @@
+//! Failure triage - group apply failures by probable root cause
+//!
+//! When many resources fail it is usually one underlying problem (no network,
+//! Homebrew missing). Failures are classified from their error messages and
+//! reported once per cause instead of once per resource.
+
+use colored::Colorize;
+use serde::Serialize;
+
+/// Classified cause of a resource failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureCause {
+    Network,
+    Authentication,
+    BrewMissing,
+    DiskFull,
+    PermissionDenied,
+    Timeout,
+    NotFound,
+    Other,
+}
+
+impl FailureCause {
+    /// Classify an error message
+    pub fn classify(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|n| error.contains(n));
+
+        if has(&[
+            "could not resolve host",
+            "temporary failure in name resolution",
+            "network is unreachable",
+            "no route to host",
+            "connection refused",
+            "connection reset",
+            "failed to connect",
+            "connection timed out",
+        ]) {
+            Self::Network
+        } else if has(&[
+            "authentication failed",
+            "could not read username",
+            "permission denied (publickey)",
+            "bad credentials",
+        ]) {
+            Self::Authentication
+        } else if error.contains("brew")
+            && has(&[
+                "command not found",
+                "not found in path",
+                "no such file or directory",
+            ])
+            || has(&["homebrew is not installed", "brew not found"])
+        {
+            Self::BrewMissing
+        } else if has(&["no space left", "disk full", "disk quota exceeded"]) {
+            Self::DiskFull
+        } else if has(&[
+            "permission denied",
+            "operation not permitted",
+            "access denied",
+        ]) {
+            Self::PermissionDenied
+        } else if has(&["timed out", "timeout"]) {
+            Self::Timeout
+        } else if has(&[
+            "not found",
+            "no such file",
+            "does not exist",
+            "no available formula",
+            "no cask with this name",
+        ]) {
+            Self::NotFound
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Short label for the cause
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Network => "Network unavailable",
+            Self::Authentication => "Authentication failed",
+            Self::BrewMissing => "Homebrew not installed",
+            Self::DiskFull => "Disk full",
+            Self::PermissionDenied => "Permission denied",
+            Self::Timeout => "Timed out",
+            Self::NotFound => "Not found",
+            Self::Other => "Other errors",
+        }
+    }
+
+    /// Suggested next step
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::Network => "Check your internet connection (or VPN/proxy) and re-run",
+            Self::Authentication => {
+                "Check your git credentials or SSH keys (ssh -T git@github.com)"
+            }
+            Self::BrewMissing => "Install Homebrew with 'bossa nova --only homebrew'",
+            Self::DiskFull => "Free up disk space and re-run",
+            Self::PermissionDenied => "Check file ownership or whether the operation needs sudo",
+            Self::Timeout => "The remote may be slow - re-run, or reduce --jobs",
+            Self::NotFound => "Check for typos in your config (package or path names)",
+            Self::Other => "See the individual errors below",
+        }
+    }
+}
+
+/// A resource that failed to apply
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedResource {
+    pub id: String,
+    pub error: String,
+}
+
+/// Failures sharing the same classified cause
+#[derive(Debug, Clone, Serialize)]
+pub struct TriageGroup {
+    pub cause: FailureCause,
+    pub label: &'static str,
+    pub hint: &'static str,
+    pub resources: Vec<FailedResource>,
+}
+
+/// Failures grouped by cause, largest group first
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TriageReport {
+    pub groups: Vec<TriageGroup>,
+}
+
+impl TriageReport {
+    /// Group failures by classified cause
+    pub fn from_failures(failures: impl IntoIterator<Item = FailedResource>) -> Self {
+        let mut groups: Vec<TriageGroup> = Vec::new();
+
+        for failure in failures {
+            let cause = FailureCause::classify(&failure.error);
+            match groups.iter_mut().find(|g| g.cause == cause) {
+                Some(group) => group.resources.push(failure),
+                None => groups.push(TriageGroup {
+                    cause,
+                    label: cause.label(),
+                    hint: cause.hint(),
+                    resources: vec![failure],
+                }),
+            }
+        }
+
+        for group in &mut groups {
+            group.resources.sort_by(|a, b| a.id.cmp(&b.id));
+        }
+        groups.sort_by(|a, b| {
+            b.resources
+                .len()
+                .cmp(&a.resources.len())
+                .then(a.cause.cmp(&b.cause))
+        });
+
+        Self { groups }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Total number of failed resources
+    pub fn failed(&self) -> usize {
+        self.groups.iter().map(|g| g.resources.len()).sum()
+    }
+
+    /// Serialize as a `triage` JSON event
+    pub fn to_json_event(&self) -> serde_json::Result<String> {
+        serde_json::to_string(&serde_json::json!({
+            "event": "triage",
+            "failed": self.failed(),
+            "groups": self.groups,
+        }))
+    }
+
+    /// Print one section per cause with the affected resources
+    pub fn print(&self) {
+        if self.is_empty() {
+            return;
+        }
+
+        println!();
+        println!("  {}", "Failure triage".red().bold());
+
+        for group in &self.groups {
+            println!();
+            println!(
+                "  {} {} ({} {})",
+                "✗".red(),
+                group.label.bold(),
+                group.resources.len(),
+                if group.resources.len() == 1 {
+                    "resource"
+                } else {
+                    "resources"
+                }
+            );
+            println!("    {} {}", "→".cyan(), group.hint.dimmed());
+
+            for resource in &group.resources {
+                if group.cause == FailureCause::Other {
+                    println!(
+                        "      • {} {}",
+                        resource.id,
+                        format!("- {}", resource.error).dimmed()
+                    );
+                } else {
+                    println!("      • {}", resource.id);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed(id: &str, error: &str) -> FailedResource {
+        FailedResource {
+            id: id.to_string(),
+            error: error.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            FailureCause::classify("fatal: unable to access: Could not resolve host: github.com"),
+            FailureCause::Network
+        );
+        assert_eq!(
+            FailureCause::classify("sh: brew: command not found"),
+            FailureCause::BrewMissing
+        );
+        assert_eq!(
+            FailureCause::classify("git@github.com: Permission denied (publickey)."),
+            FailureCause::Authentication
+        );
+        assert_eq!(
+            FailureCause::classify("Permission denied (os error 13)"),
+            FailureCause::PermissionDenied
+        );
+        assert_eq!(
+            FailureCause::classify("Error: No available formula with the name \"gti\""),
+            FailureCause::NotFound
+        );
+        assert_eq!(
+            FailureCause::classify("No space left on device"),
+            FailureCause::DiskFull
+        );
+        assert_eq!(FailureCause::classify("exit status 1"), FailureCause::Other);
+    }
+
+    #[test]
+    fn test_report_groups_by_cause() {
+        let report = TriageReport::from_failures(vec![
+            failed("brew:jq", "Could not resolve host: ghcr.io"),
+            failed("brew:git", "Could not resolve host: ghcr.io"),
+            failed("symlink:~/.zshrc", "Permission denied (os error 13)"),
+            failed("brew:fd", "curl: (7) Failed to connect to ghcr.io"),
+        ]);
+
+        assert_eq!(report.failed(), 4);
+        assert_eq!(report.groups.len(), 2);
+        assert_eq!(report.groups[0].cause, FailureCause::Network);
+        let ids: Vec<_> = report.groups[0]
+            .resources
+            .iter()
+            .map(|r| r.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["brew:fd", "brew:git", "brew:jq"]);
+        assert_eq!(report.groups[1].cause, FailureCause::PermissionDenied);
+    }
+
+    #[test]
+    fn test_report_json_event() {
+        let report = TriageReport::from_failures(vec![failed("brew:jq", "timed out")]);
+        let json: serde_json::Value =
+            serde_json::from_str(&report.to_json_event().unwrap()).unwrap();
+
+        assert_eq!(json["event"], "triage");
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["groups"][0]["cause"], "timeout");
+        assert_eq!(json["groups"][0]["resources"][0]["id"], "brew:jq");
+    }
+}
@@ -126,26 +126,22 @@ pub fn diff(
     let target = target_path(config, locations);
     let generated = generate(config, locations)?;
 
-    if !target.exists() {
-        return Ok(Some(format!(
-            "File does not exist: {}\n\nWould create:\n{}",
-            target.display(),
-            generated
-        )));
-    }
-
-    let existing = std::fs::read_to_string(&target)
-        .with_context(|| format!("Failed to read {}", target.display()))?;
+    // A missing file diffs as empty, so every generated line shows as added
+    let existing = if target.exists() {
+        std::fs::read_to_string(&target)
+            .with_context(|| format!("Failed to read {}", target.display()))?
+    } else {
+        String::new()
+    };
 
     if existing == generated {
         return Ok(None);
     }
 
-    // Simple diff output
-    Ok(Some(format!(
-        "--- {}\n+++ generated\n\nExisting:\n{}\n\nGenerated:\n{}",
-        target.display(),
-        existing,
-        generated
+    Ok(Some(crate::ui::diff::labeled(
+        &target.display().to_string(),
+        &existing,
+        "generated",
+        &generated,
     )))
 }
@@ -23,10 +23,32 @@ use std::io;
 pub struct Context {
     pub verbose: u8,
     pub quiet: bool,
+    pub format: ui::output::OutputFormat,
+}
+
+impl Context {
+    /// Where command results go, in the format picked with `--format`
+    pub fn output(&self) -> ui::output::Output {
+        ui::output::Output::new(self.format, io::stdout())
+    }
+
+    /// Whether results should be printed as JSON
+    pub fn is_json(&self) -> bool {
+        self.format == ui::output::OutputFormat::Json
+    }
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    ui::set_color_choice(cli.color_choice());
+    ui::set_theme(cli.theme.into());
+    if cli.no_pager {
+        ui::pager::disable();
+    }
+    ui::output::set_output_format(cli.format);
+    if let Some(profile) = &cli.profile {
+        schema::set_profile(profile);
+    }
 
     // Initialize logging based on verbosity
     let log_level = match cli.verbose {
@@ -36,20 +58,25 @@ fn main() -> Result<()> {
         _ => log::LevelFilter::Trace,
     };
 
-    env_logger::Builder::new()
-        .filter_level(if cli.quiet {
-            log::LevelFilter::Error
-        } else {
-            log_level
-        })
-        .format_timestamp(None)
-        .init();
+    ui::logger::init(if cli.quiet {
+        log::LevelFilter::Error
+    } else {
+        log_level
+    })?;
 
     let ctx = Context {
         verbose: cli.verbose,
         quiet: cli.quiet,
+        format: cli.format,
     };
 
+    // Machine freeze: the single chokepoint for every mutating command
+    if let Some(operation) = cli.command.mutation()
+        && !commands::freeze::guard(operation, cli.ignore_freeze)?
+    {
+        return Ok(());
+    }
+
     match cli.command {
         Command::Nova(args) => commands::nova::run(&ctx, args),
         Command::Status(args) => commands::declarative::status(&ctx, args.target.as_deref()),
@@ -59,7 +86,7 @@ fn main() -> Result<()> {
             args.dry_run,
             args.jobs as usize,
         ),
-        Command::Diff(args) => commands::declarative::diff(&ctx, args.target.as_deref()),
+        Command::Diff(args) => commands::declarative::diff(&ctx, args.target.as_deref(), args.json),
         Command::Add(cmd) => match cmd {
             AddCommand::Collection {
                 name,
@@ -98,10 +125,10 @@ fn main() -> Result<()> {
         Command::Caches(cmd) => commands::caches::run(cmd),
         Command::Cellar(cmd) => commands::cellar::run(&ctx, cmd),
         Command::Collections(cmd) => commands::collections::run(&ctx, cmd.into()),
-        Command::Manifest(cmd) => commands::manifest::run(cmd.into()),
+        Command::Manifest(cmd) => commands::manifest::run(&ctx, cmd.into()),
         Command::ICloud(cmd) => commands::icloud::run(cmd.into()),
         Command::Storage(cmd) => match cmd {
-            StorageCommand::Status => commands::storage::status(),
+            StorageCommand::Status => commands::storage::status(&ctx),
             StorageCommand::Duplicates {
                 manifests,
                 list,
@@ -125,12 +152,21 @@ fn main() -> Result<()> {
             generate(shell, &mut cmd, "bossa", &mut io::stdout());
             Ok(())
         }
+        Command::Hook { shell } => {
+            commands::hook::run(shell);
+            Ok(())
+        }
         Command::Tools(cmd) => commands::tools::run(&ctx, cmd),
+        Command::Which { name } => commands::which::run(&name),
         Command::Stow(cmd) => commands::stow::run(&ctx, cmd),
         Command::Theme(cmd) => commands::theme::run(&ctx, cmd),
         Command::Defaults(cmd) => commands::defaults::run(&ctx, cmd),
         Command::Locations(cmd) => commands::locations::run(&ctx, cmd),
+        Command::Config(cmd) => commands::config::run(&ctx, cmd),
         Command::Configs(cmd) => commands::configs::run(&ctx, cmd),
         Command::Relocate(cmd) => commands::relocate::run(&ctx, cmd),
+        Command::Freeze(args) => commands::freeze::run(&ctx, &args),
+        Command::Unfreeze => commands::freeze::unfreeze(&ctx),
+        Command::SelfUpdate { check } => commands::self_update::run(&ctx, check),
     }
 }
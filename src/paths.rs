@@ -265,6 +265,48 @@ pub fn identify_location(path: &Path, locations: &LocationsConfig) -> Option<Str
     None
 }
 
+/// Map a path under a deprecated alias to its canonical location.
+///
+/// Aliases map historical paths (e.g., `~/dev`) to location names. If `path`
+/// is the alias itself or lives below it, the same relative path under the
+/// resolved location is returned. The longest matching alias wins. Returns
+/// `None` when the path is not under an alias, the alias points at an unknown
+/// location, or the alias already resolves to the same place.
+///
+/// # Examples
+/// ```
+/// use bossa::paths;
+/// use bossa::schema::LocationsConfig;
+/// use std::collections::HashMap;
+/// use std::path::{Path, PathBuf};
+///
+/// let locations = LocationsConfig {
+///     paths: HashMap::from([("dev".to_string(), "/Volumes/T9/dev".to_string())]),
+///     aliases: HashMap::from([("/Users/me/dev".to_string(), "dev".to_string())]),
+/// };
+///
+/// let canonical = paths::canonical_for_alias(Path::new("/Users/me/dev/ws/bossa"), &locations);
+/// assert_eq!(canonical, Some(PathBuf::from("/Volumes/T9/dev/ws/bossa")));
+/// ```
+pub fn canonical_for_alias(path: &Path, locations: &LocationsConfig) -> Option<PathBuf> {
+    let (alias, location) = locations
+        .aliases
+        .iter()
+        .map(|(alias, location)| (expand(alias), location))
+        .filter(|(alias, _)| path.starts_with(alias))
+        .max_by_key(|(alias, _)| alias.components().count())?;
+
+    let target = resolve(locations.paths.get(location)?, locations);
+    let rest = path.strip_prefix(&alias).ok()?;
+    let canonical = if rest.as_os_str().is_empty() {
+        target
+    } else {
+        target.join(rest)
+    };
+
+    (canonical != path).then_some(canonical)
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -392,6 +434,43 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_canonical_for_alias() {
+        let locations = LocationsConfig {
+            paths: HashMap::from([
+                ("dev".to_string(), "/Volumes/T9/dev".to_string()),
+                ("ws".to_string(), "${locations.dev}/ws".to_string()),
+            ]),
+            aliases: HashMap::from([
+                ("/Users/me/dev".to_string(), "dev".to_string()),
+                ("/Users/me/dev/ws".to_string(), "ws".to_string()),
+                ("/Users/me/old".to_string(), "missing".to_string()),
+            ]),
+        };
+
+        assert_eq!(
+            canonical_for_alias(Path::new("/Users/me/dev"), &locations),
+            Some(PathBuf::from("/Volumes/T9/dev"))
+        );
+        assert_eq!(
+            canonical_for_alias(Path::new("/Users/me/dev/ws/bossa"), &locations),
+            Some(PathBuf::from("/Volumes/T9/dev/ws/bossa"))
+        );
+        // Component-wise prefix only, not string prefix
+        assert_eq!(
+            canonical_for_alias(Path::new("/Users/me/devtools"), &locations),
+            None
+        );
+        assert_eq!(
+            canonical_for_alias(Path::new("/Users/me/old/file"), &locations),
+            None
+        );
+        assert_eq!(
+            canonical_for_alias(Path::new("/Volumes/T9/dev"), &locations),
+            None
+        );
+    }
+
     #[test]
     fn test_legacy_config_dir() {
         let result = legacy_config_dir().unwrap();
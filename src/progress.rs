@@ -1,9 +1,22 @@
 //! Progress indicators for bossa CLI.
 //!
-//! This module re-exports pintui progress functions.
+//! This module re-exports pintui progress functions, and adds a manager for
+//! several bars at once, a live checklist of tasks, and bars for the
+//! workspace crates' progress callbacks.
 
 #[allow(unused_imports)]
 pub use pintui::progress::{
     StageProgress, bar as clone_bar, finish_clear, finish_error, finish_success, finish_warn,
     spinner,
 };
+
+mod bridge;
+mod multi;
+mod task_list;
+
+#[allow(unused_imports)]
+pub use bridge::{ApplyBar, BrewBar, BulkBar, ScanBar};
+#[allow(unused_imports)]
+pub use multi::{MultiProgress, TaskBar};
+#[allow(unused_imports)]
+pub use task_list::{Task, TaskList, TaskState};
@@ -0,0 +1,206 @@
+//! Progress bars for the workspace crates' progress callbacks.
+//!
+//! manifest, icloud, brewkit and declarative each report progress through
+//! a trait of their own. The types here implement them with themed bars,
+//! so a command only has to create one and pass it along:
+//!
+//! ```ignore
+//! let result = manifest_db.scan(&path, force, &mut ScanBar::new())?;
+//! ```
+
+#![allow(dead_code)]
+
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::path::{Path, PathBuf};
+
+use super::multi::{bar_chars, color_name};
+use crate::ui::{self, theme};
+
+/// A themed bar of `len` steps, with `counts` drawn after it, like
+/// `{pos}/{len}`
+fn themed_bar(len: u64, counts: &str) -> ProgressBar {
+    let accent = color_name(theme().accent);
+    let pb = ProgressBar::new(len);
+    pb.set_style(
+        ProgressStyle::with_template(&format!(
+            "{{spinner:.{accent}}} [{{bar:40.{accent}/{accent}}}] {counts} {{msg}}"
+        ))
+        .unwrap_or_else(|_| ProgressStyle::default_bar())
+        .progress_chars(bar_chars()),
+    );
+    pb
+}
+
+/// Print a failure above a bar
+fn print_failure(pb: &ProgressBar, name: &str, error: &str) {
+    let t = theme();
+    pb.suspend(|| {
+        println!("  {} {name} ({error})", t.error(t.icons.error));
+    });
+}
+
+/// Progress of a manifest scan: files hashed, and the file being hashed
+pub struct ScanBar {
+    pb: ProgressBar,
+}
+
+impl ScanBar {
+    pub fn new() -> Self {
+        Self {
+            pb: ProgressBar::hidden(),
+        }
+    }
+}
+
+impl Default for ScanBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl manifest::ProgressCallback for ScanBar {
+    fn on_start(&mut self, total_files: u64, _total_size: u64) {
+        self.pb = themed_bar(total_files, "{pos}/{len} ({percent}%)");
+    }
+
+    fn on_file(&mut self, path: &Path, _size: u64) {
+        self.pb
+            .set_message(ui::truncate_path(&path.to_string_lossy(), 30));
+    }
+
+    fn on_file_complete(&mut self, _success: bool) {
+        self.pb.inc(1);
+    }
+
+    fn on_complete(&mut self, _result: &manifest::ScanResult) {
+        self.pb.finish_and_clear();
+    }
+}
+
+/// Progress of a bulk iCloud evict or download.
+///
+/// Failures are printed as they happen; successes are listed once the bar
+/// is cleared, and only when nothing failed.
+pub struct BulkBar {
+    pb: ProgressBar,
+    base_path: PathBuf,
+    succeeded: Vec<PathBuf>,
+    failed: usize,
+}
+
+impl BulkBar {
+    /// Paths are shown relative to `base_path`
+    pub fn new(base_path: &Path) -> Self {
+        Self {
+            pb: ProgressBar::hidden(),
+            base_path: base_path.to_path_buf(),
+            succeeded: Vec::new(),
+            failed: 0,
+        }
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.base_path).unwrap_or(path)
+    }
+}
+
+impl icloud::BulkProgress for BulkBar {
+    fn on_start(&mut self, total_files: usize) {
+        self.pb = themed_bar(total_files as u64, "{pos}/{len}");
+    }
+
+    fn on_item(&mut self, path: &Path, outcome: &icloud::ItemOutcome) {
+        let rel_path = self.relative(path).display().to_string();
+        self.pb.set_message(ui::truncate_path(&rel_path, 30));
+
+        match outcome {
+            icloud::ItemOutcome::Succeeded { .. } => self.succeeded.push(path.to_path_buf()),
+            icloud::ItemOutcome::Skipped { .. } => {}
+            icloud::ItemOutcome::Failed { error } => {
+                self.failed += 1;
+                print_failure(&self.pb, &rel_path, error);
+            }
+        }
+
+        self.pb.inc(1);
+    }
+
+    fn on_complete(&mut self, _result: &icloud::BulkResult) {
+        self.pb.finish_and_clear();
+
+        // Print successes after progress bar is done
+        if self.failed == 0 {
+            let t = theme();
+            for path in &self.succeeded {
+                println!(
+                    "  {} {}",
+                    t.success(t.icons.success),
+                    self.relative(path).display()
+                );
+            }
+        }
+    }
+}
+
+/// Shows the Homebrew package being installed on a bar or spinner, and
+/// failures above it
+pub struct BrewBar(pub ProgressBar);
+
+impl brewkit::BrewProgress for BrewBar {
+    fn started(&self, name: &str) {
+        self.0.set_message(name.to_string());
+    }
+
+    fn finished(&self, name: &str, error: Option<&str>) {
+        self.0.inc(1);
+        if let Some(error) = error {
+            print_failure(&self.0, name, error.lines().next().unwrap_or(""));
+        }
+    }
+}
+
+/// Progress of the declarative executor: a bar per batch of resources,
+/// with failures printed above it
+pub struct ApplyBar {
+    pb: ProgressBar,
+}
+
+impl ApplyBar {
+    pub fn new() -> Self {
+        Self {
+            pb: ProgressBar::hidden(),
+        }
+    }
+}
+
+impl Default for ApplyBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl declarative::ProgressCallback for ApplyBar {
+    fn on_batch_start(&mut self, count: usize, privileged: bool) {
+        self.pb = themed_bar(count as u64, "{pos}/{len}");
+        if privileged {
+            self.pb
+                .println(format!("  {}", "Privileged resources".bold()));
+        }
+    }
+
+    fn on_resource_start(&mut self, id: &str, _description: &str) {
+        self.pb.set_message(id.to_string());
+    }
+
+    fn on_resource_complete(&mut self, id: &str, result: &declarative::ApplyResult) {
+        self.pb.inc(1);
+        if let declarative::ApplyResult::Failed { error } = result {
+            print_failure(&self.pb, id, error);
+        }
+    }
+
+    fn on_batch_complete(&mut self) {
+        self.pb.finish_and_clear();
+    }
+}
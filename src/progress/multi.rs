@@ -0,0 +1,185 @@
+//! Several progress bars at once, for parallel work.
+//!
+//! A [`MultiProgress`] shows a bar per running task above an aggregate bar
+//! counting finished tasks. Tasks can be added and finished from any thread;
+//! a finished task's bar goes away, and failures are printed above the bars
+//! so they stay visible once the run is over.
+
+#![allow(dead_code)]
+
+use colored::Color;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::time::Duration;
+
+use crate::ui::{Icons, theme};
+
+/// Per-task bars plus an aggregate bar
+#[derive(Clone)]
+pub struct MultiProgress {
+    multi: indicatif::MultiProgress,
+    total: ProgressBar,
+}
+
+/// One task's bar, or spinner when its length isn't known
+pub struct TaskBar {
+    multi: indicatif::MultiProgress,
+    bar: ProgressBar,
+    total: ProgressBar,
+    name: String,
+}
+
+impl MultiProgress {
+    /// Start an aggregate bar for `tasks` tasks, drawn to stderr
+    pub fn new(tasks: u64, message: &str) -> Self {
+        Self::with_draw_target(tasks, message, ProgressDrawTarget::stderr())
+    }
+
+    fn with_draw_target(tasks: u64, message: &str, target: ProgressDrawTarget) -> Self {
+        let multi = indicatif::MultiProgress::with_draw_target(target);
+        let accent = color_name(theme().accent);
+        let total = multi.add(ProgressBar::new(tasks));
+        total.set_style(
+            ProgressStyle::with_template(&format!(
+                "{{msg}} [{{bar:30.{accent}/{accent}}}] {{pos}}/{{len}} ({{elapsed}})"
+            ))
+            .unwrap_or_else(|_| ProgressStyle::default_bar())
+            .progress_chars(bar_chars()),
+        );
+        total.set_message(message.to_string());
+        Self { multi, total }
+    }
+
+    /// Add a task; with a `len` it gets a bar, otherwise a spinner
+    pub fn add(&self, name: &str, len: Option<u64>) -> TaskBar {
+        let accent = color_name(theme().accent);
+        let bar = match len {
+            Some(len) => {
+                let bar = ProgressBar::new(len);
+                bar.set_style(
+                    ProgressStyle::with_template(&format!(
+                        "  {{msg}} [{{bar:20.{accent}/{accent}}}] {{pos}}/{{len}}"
+                    ))
+                    .unwrap_or_else(|_| ProgressStyle::default_bar())
+                    .progress_chars(bar_chars()),
+                );
+                bar
+            }
+            None => {
+                let bar = ProgressBar::new_spinner();
+                bar.set_style(
+                    ProgressStyle::with_template(&format!("  {{spinner:.{accent}}} {{msg}}"))
+                        .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+                );
+                bar.enable_steady_tick(Duration::from_millis(100));
+                bar
+            }
+        };
+        // Running tasks are listed above the aggregate bar
+        let bar = self.multi.insert_before(&self.total, bar);
+        bar.set_message(name.to_string());
+        TaskBar {
+            multi: self.multi.clone(),
+            bar,
+            total: self.total.clone(),
+            name: name.to_string(),
+        }
+    }
+
+    /// Print a line above the bars
+    pub fn println(&self, line: &str) {
+        let _ = self.multi.println(line);
+    }
+
+    /// Number of tasks finished so far
+    pub fn finished(&self) -> u64 {
+        self.total.position()
+    }
+
+    /// Remove the aggregate bar, once every task is done
+    pub fn finish(&self) {
+        self.total.finish_and_clear();
+    }
+}
+
+impl TaskBar {
+    /// Advance the task's bar
+    pub fn inc(&self, delta: u64) {
+        self.bar.inc(delta);
+    }
+
+    /// Show a message next to the task's name
+    pub fn set_message(&self, message: &str) {
+        self.bar.set_message(format!("{} {message}", self.name));
+    }
+
+    /// The task succeeded; its bar goes away
+    pub fn finish_success(self) {
+        self.done();
+    }
+
+    /// The task failed; `error` is printed above the bars
+    pub fn finish_error(self, error: &str) {
+        let t = theme();
+        let _ = self.multi.println(format!(
+            "  {} {}: {error}",
+            t.error(t.icons.error),
+            self.name
+        ));
+        self.done();
+    }
+
+    fn done(&self) {
+        self.bar.finish_and_clear();
+        self.multi.remove(&self.bar);
+        self.total.inc(1);
+    }
+}
+
+/// Characters bars are drawn with, ASCII for the ASCII theme
+pub(super) fn bar_chars() -> &'static str {
+    if theme().icons == Icons::ASCII {
+        "=>-"
+    } else {
+        "━╸─"
+    }
+}
+
+/// The name indicatif's templates use for a color
+pub(super) fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::Black | Color::BrightBlack => "black",
+        Color::Red | Color::BrightRed => "red",
+        Color::Green | Color::BrightGreen => "green",
+        Color::Yellow | Color::BrightYellow => "yellow",
+        Color::Blue | Color::BrightBlue => "blue",
+        Color::Magenta | Color::BrightMagenta => "magenta",
+        Color::Cyan | Color::BrightCyan => "cyan",
+        _ => "white",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tasks_finish_across_threads() {
+        let progress = MultiProgress::with_draw_target(8, "Cloning", ProgressDrawTarget::hidden());
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let progress = &progress;
+                scope.spawn(move || {
+                    let task = progress.add(&format!("repo-{i}"), Some(2));
+                    task.inc(2);
+                    if i % 2 == 0 {
+                        task.finish_success();
+                    } else {
+                        task.finish_error("clone failed");
+                    }
+                });
+            }
+        });
+        assert_eq!(progress.finished(), 8);
+        progress.finish();
+    }
+}
@@ -0,0 +1,274 @@
+//! A live checklist for work done in stages.
+//!
+//! A [`TaskList`] shows one line per task, updated in place as tasks go from
+//! pending to running to done:
+//!
+//! ```text
+//!   ✓ macos_default
+//!   ⠙ brew_formula (12/80)
+//!     ⠙ ripgrep
+//!   ○ symlink
+//! ```
+//!
+//! Tasks can have sub-tasks, drawn indented under them, and can be updated
+//! from any thread. Finished tasks stay on screen.
+
+#![allow(dead_code)]
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use super::multi::color_name;
+use crate::ui::theme;
+
+/// Where a task is at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    Pending,
+    Running,
+    Success,
+    Failed,
+}
+
+/// A checklist of tasks
+pub struct TaskList {
+    multi: indicatif::MultiProgress,
+    next_id: Arc<AtomicUsize>,
+}
+
+/// The last line of a task's sub-tree, where its next sub-task goes
+type Tail = Arc<Mutex<(usize, ProgressBar)>>;
+
+/// A line of a [`TaskList`]
+#[derive(Clone)]
+pub struct Task {
+    multi: indicatif::MultiProgress,
+    next_id: Arc<AtomicUsize>,
+    bar: ProgressBar,
+    name: String,
+    depth: usize,
+    state: Arc<Mutex<TaskState>>,
+    /// The tails of the task's ancestors, outermost first, then its own
+    tails: Vec<Tail>,
+}
+
+impl TaskList {
+    /// An empty checklist, drawn to stderr
+    pub fn new() -> Self {
+        Self::with_draw_target(ProgressDrawTarget::stderr())
+    }
+
+    /// A checklist that draws nothing, for when output is for programs
+    pub fn hidden() -> Self {
+        Self::with_draw_target(ProgressDrawTarget::hidden())
+    }
+
+    fn with_draw_target(target: ProgressDrawTarget) -> Self {
+        Self {
+            multi: indicatif::MultiProgress::with_draw_target(target),
+            next_id: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Add a pending task at the end of the list
+    pub fn add(&self, name: &str) -> Task {
+        let bar = self.multi.add(ProgressBar::new(0));
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        Task::new(
+            self.multi.clone(),
+            Arc::clone(&self.next_id),
+            bar.clone(),
+            name,
+            0,
+            vec![Arc::new(Mutex::new((id, bar)))],
+        )
+    }
+
+    /// Print a line above the list
+    pub fn println(&self, line: &str) {
+        let _ = self.multi.println(line);
+    }
+}
+
+impl Default for TaskList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Task {
+    fn new(
+        multi: indicatif::MultiProgress,
+        next_id: Arc<AtomicUsize>,
+        bar: ProgressBar,
+        name: &str,
+        depth: usize,
+        tails: Vec<Tail>,
+    ) -> Self {
+        let task = Self {
+            multi,
+            next_id,
+            bar,
+            name: name.to_string(),
+            depth,
+            state: Arc::new(Mutex::new(TaskState::Pending)),
+            tails,
+        };
+        task.draw(TaskState::Pending, None);
+        task
+    }
+
+    /// Add a pending sub-task, after this task's other sub-tasks
+    pub fn subtask(&self, name: &str) -> Task {
+        let own = self.tails.last().expect("a task has its own tail");
+        let (old_id, after) = lock(own).clone();
+        let bar = self.multi.insert_after(&after, ProgressBar::new(0));
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        // The sub-task now ends this task's sub-tree, and that of every
+        // ancestor whose sub-tree ended with this one's
+        for tail in &self.tails {
+            let mut tail = lock(tail);
+            if tail.0 == old_id {
+                *tail = (id, bar.clone());
+            }
+        }
+
+        let mut tails = self.tails.clone();
+        tails.push(Arc::new(Mutex::new((id, bar.clone()))));
+
+        Task::new(
+            self.multi.clone(),
+            Arc::clone(&self.next_id),
+            bar,
+            name,
+            self.depth + 1,
+            tails,
+        )
+    }
+
+    /// Start the task; with a `len`, it shows how far along it is
+    pub fn start(&self, len: Option<u64>) {
+        if let Some(len) = len {
+            self.bar.set_length(len);
+        }
+        self.set_state(TaskState::Running, None);
+    }
+
+    /// Advance a running task
+    pub fn inc(&self, delta: u64) {
+        self.bar.inc(delta);
+    }
+
+    /// The task finished successfully
+    pub fn success(&self) {
+        self.set_state(TaskState::Success, None);
+    }
+
+    /// The task failed, with `error` shown next to its name
+    pub fn fail(&self, error: &str) {
+        self.set_state(TaskState::Failed, Some(error));
+    }
+
+    pub fn state(&self) -> TaskState {
+        *lock(&self.state)
+    }
+
+    fn set_state(&self, state: TaskState, error: Option<&str>) {
+        *lock(&self.state) = state;
+        self.draw(state, error);
+    }
+
+    fn draw(&self, state: TaskState, error: Option<&str>) {
+        let t = theme();
+        let indent = "  ".repeat(self.depth + 1);
+        let (template, message) = match state {
+            TaskState::Pending => (
+                format!("{indent}{{msg}}"),
+                t.dim(&format!("{} {}", t.icons.pending, self.name))
+                    .to_string(),
+            ),
+            TaskState::Running => {
+                let counts = if self.bar.length().unwrap_or(0) > 0 {
+                    " ({pos}/{len})"
+                } else {
+                    ""
+                };
+                let accent = color_name(t.accent);
+                (
+                    format!("{indent}{{spinner:.{accent}}} {{msg}}{counts}"),
+                    self.name.clone(),
+                )
+            }
+            TaskState::Success => (
+                format!("{indent}{{msg}}"),
+                format!("{} {}", t.success(t.icons.success), self.name),
+            ),
+            TaskState::Failed => (
+                format!("{indent}{{msg}}"),
+                format!(
+                    "{} {}{}",
+                    t.error(t.icons.error),
+                    self.name,
+                    error.map(|e| format!(": {e}")).unwrap_or_default()
+                ),
+            ),
+        };
+
+        self.bar.set_style(
+            ProgressStyle::with_template(&template)
+                .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+        );
+        match state {
+            TaskState::Running => {
+                self.bar.set_message(message);
+                self.bar.enable_steady_tick(Duration::from_millis(100));
+            }
+            TaskState::Pending => self.bar.set_message(message),
+            TaskState::Success | TaskState::Failed => {
+                self.bar.disable_steady_tick();
+                self.bar.abandon_with_message(message);
+            }
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_states_and_subtasks() {
+        let list = TaskList::hidden();
+        let defaults = list.add("defaults");
+        let brew = list.add("brew");
+        assert_eq!(brew.state(), TaskState::Pending);
+
+        brew.start(Some(2));
+        let ripgrep = brew.subtask("ripgrep");
+        let fd = brew.subtask("fd");
+        let fd_docs = fd.subtask("docs");
+        assert_eq!(brew.state(), TaskState::Running);
+        assert_eq!(fd_docs.depth, 2);
+
+        // Sub-tasks extend their ancestors' sub-trees, not earlier tasks'
+        let tail_id = |task: &Task| lock(task.tails.last().unwrap()).0;
+        assert_eq!(tail_id(&brew), tail_id(&fd_docs));
+        assert_eq!(tail_id(&fd), tail_id(&fd_docs));
+        assert_ne!(tail_id(&ripgrep), tail_id(&fd_docs));
+        assert_ne!(tail_id(&defaults), tail_id(&brew));
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| ripgrep.success());
+            scope.spawn(|| fd.fail("checksum mismatch"));
+        });
+        assert_eq!(ripgrep.state(), TaskState::Success);
+        assert_eq!(fd.state(), TaskState::Failed);
+    }
+}
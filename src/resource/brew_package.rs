@@ -4,7 +4,7 @@ use anyhow::{Context, Result, bail};
 use serde::Deserialize;
 use std::process::Command;
 
-use super::{ApplyContext, ApplyResult, Resource, ResourceState, SudoRequirement};
+use super::{ApplyContext, ApplyResult, PlannedAction, Resource, ResourceState, SudoRequirement};
 
 /// Type of brew package
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -109,33 +109,27 @@ impl BrewPackage {
         }
     }
 
-    /// Install the package
-    fn install(&self, ctx: &ApplyContext) -> Result<()> {
-        let args: Vec<&str> = match self.package_type {
+    /// Arguments to `brew` that install the package
+    fn install_args(&self) -> Vec<&str> {
+        match self.package_type {
             BrewPackageType::Tap => vec!["tap", &self.name],
             BrewPackageType::Formula => vec!["install", "--formula", &self.name],
             BrewPackageType::Cask => vec!["install", "--cask", &self.name],
-        };
+        }
+    }
+
+    /// Install the package
+    fn install(&self, ctx: &ApplyContext) -> Result<()> {
+        let args = self.install_args();
 
-        let (success, stderr) = if self.requires_sudo {
-            let output = ctx
-                .sudo
-                .ok_or_else(|| anyhow::anyhow!("Sudo required but not available"))?
-                .run("brew", &args)?;
-            (output.success, output.stderr_str())
+        let output = if self.requires_sudo {
+            ctx.run_sudo("brew", &args)?
         } else {
-            let output = Command::new("brew")
-                .args(&args)
-                .output()
-                .context("Failed to run brew install")?;
-            (
-                output.status.success(),
-                String::from_utf8_lossy(&output.stderr).to_string(),
-            )
+            ctx.run("brew", &args)?
         };
 
-        if !success {
-            bail!("brew install failed: {}", stderr.trim());
+        if !output.success {
+            bail!("brew install failed: {}", output.stderr_str().trim());
         }
 
         Ok(())
@@ -200,4 +194,13 @@ impl Resource for BrewPackage {
         self.install(ctx)?;
         Ok(ApplyResult::Created)
     }
+
+    fn plan(&self) -> Vec<PlannedAction> {
+        let args = self.install_args();
+        if self.requires_sudo {
+            vec![PlannedAction::run_sudo("brew", &args)]
+        } else {
+            vec![PlannedAction::run("brew", &args)]
+        }
+    }
 }
@@ -3,7 +3,7 @@
 use anyhow::{Context, Result, bail};
 use std::process::Command;
 
-use super::{ApplyContext, ApplyResult, Resource, ResourceState};
+use super::{ApplyContext, ApplyResult, PlannedAction, Resource, ResourceState};
 
 /// A Dock app resource
 #[derive(Debug, Clone)]
@@ -40,23 +40,27 @@ impl DockApp {
         Ok(stdout.contains(&self.app_path))
     }
 
-    /// Add app to dock using dockutil
-    fn add_to_dock(&self, _ctx: &ApplyContext) -> Result<()> {
-        let mut args = vec!["--add", &self.app_path, "--no-restart"];
-
-        let position_str;
+    /// Arguments to `dockutil` that add the app
+    fn add_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--add".to_string(),
+            self.app_path.clone(),
+            "--no-restart".to_string(),
+        ];
         if let Some(pos) = self.position {
-            position_str = pos.to_string();
-            args.extend_from_slice(&["--position", &position_str]);
+            args.extend(["--position".to_string(), pos.to_string()]);
         }
+        args
+    }
 
-        let output = Command::new("dockutil")
-            .args(&args)
-            .output()
-            .context("Failed to run dockutil")?;
+    /// Add app to dock using dockutil
+    fn add_to_dock(&self, ctx: &ApplyContext) -> Result<()> {
+        let args = self.add_args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = ctx.run("dockutil", &args)?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.success {
+            let stderr = output.stderr_str();
             bail!("dockutil add failed: {}", stderr.trim());
         }
 
@@ -108,6 +112,12 @@ impl Resource for DockApp {
         Ok(ApplyResult::Created)
     }
 
+    fn plan(&self) -> Vec<PlannedAction> {
+        let args = self.add_args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        vec![PlannedAction::run("dockutil", &args)]
+    }
+
     fn can_parallelize(&self) -> bool {
         false // Dock modifications should be sequential
     }
@@ -148,24 +158,35 @@ impl DockFolder {
         Ok(stdout.contains(expanded_path.to_string_lossy().as_ref()))
     }
 
-    /// Add folder to dock using dockutil
-    fn add_to_dock(&self, _ctx: &ApplyContext) -> Result<()> {
+    /// Arguments to `dockutil` that add the folder
+    fn add_args(&self) -> Vec<String> {
         let expanded_path = crate::paths::expand(&self.path)
             .to_string_lossy()
             .to_string();
 
-        let mut args = vec!["--add", &expanded_path, "--no-restart"];
-        args.extend_from_slice(&["--view", &self.view]);
-        args.extend_from_slice(&["--display", &self.display]);
-        args.extend_from_slice(&["--sort", &self.sort]);
+        [
+            "--add",
+            &expanded_path,
+            "--no-restart",
+            "--view",
+            &self.view,
+            "--display",
+            &self.display,
+            "--sort",
+            &self.sort,
+        ]
+        .map(ToString::to_string)
+        .to_vec()
+    }
 
-        let output = Command::new("dockutil")
-            .args(&args)
-            .output()
-            .context("Failed to run dockutil")?;
+    /// Add folder to dock using dockutil
+    fn add_to_dock(&self, ctx: &ApplyContext) -> Result<()> {
+        let args = self.add_args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = ctx.run("dockutil", &args)?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.success {
+            let stderr = output.stderr_str();
             bail!("dockutil add folder failed: {}", stderr.trim());
         }
 
@@ -213,6 +234,12 @@ impl Resource for DockFolder {
         Ok(ApplyResult::Created)
     }
 
+    fn plan(&self) -> Vec<PlannedAction> {
+        let args = self.add_args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        vec![PlannedAction::run("dockutil", &args)]
+    }
+
     fn can_parallelize(&self) -> bool {
         false // Dock modifications should be sequential
     }
@@ -3,7 +3,7 @@
 use anyhow::{Context, Result, bail};
 use std::process::Command;
 
-use super::{ApplyContext, ApplyResult, Resource, ResourceState};
+use super::{ApplyContext, ApplyResult, PlannedAction, Resource, ResourceState};
 
 /// A file handler association
 #[derive(Debug, Clone)]
@@ -35,15 +35,17 @@ impl FileHandler {
         Ok(stdout.contains(&self.bundle_id))
     }
 
+    /// Arguments to `duti` that set the handler
+    fn set_args(&self) -> [&str; 4] {
+        ["-s", &self.bundle_id, &self.uti, "all"]
+    }
+
     /// Set the handler
-    fn set_handler(&self, _ctx: &ApplyContext) -> Result<()> {
-        let output = Command::new("duti")
-            .args(["-s", &self.bundle_id, &self.uti, "all"])
-            .output()
-            .context("Failed to run duti")?;
+    fn set_handler(&self, ctx: &ApplyContext) -> Result<()> {
+        let output = ctx.run("duti", &self.set_args())?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.success {
+            let stderr = output.stderr_str();
             bail!("duti set failed: {}", stderr.trim());
         }
 
@@ -90,4 +92,8 @@ impl Resource for FileHandler {
         self.set_handler(ctx)?;
         Ok(ApplyResult::Created)
     }
+
+    fn plan(&self) -> Vec<PlannedAction> {
+        vec![PlannedAction::run("duti", &self.set_args())]
+    }
 }
@@ -3,7 +3,7 @@
 use anyhow::{Context, Result, bail};
 use std::process::Command;
 
-use super::{ApplyContext, ApplyResult, Resource, ResourceState};
+use super::{ApplyContext, ApplyResult, PlannedAction, Resource, ResourceState};
 
 /// A GitHub CLI extension
 #[derive(Debug, Clone)]
@@ -33,15 +33,17 @@ impl GHExtension {
         Ok(stdout.contains(&self.name))
     }
 
+    /// Arguments to `gh` that install the extension
+    fn install_args(&self) -> [&str; 3] {
+        ["extension", "install", &self.name]
+    }
+
     /// Install the extension
-    fn install(&self, _ctx: &ApplyContext) -> Result<()> {
-        let output = Command::new("gh")
-            .args(["extension", "install", &self.name])
-            .output()
-            .context("Failed to run gh extension install")?;
+    fn install(&self, ctx: &ApplyContext) -> Result<()> {
+        let output = ctx.run("gh", &self.install_args())?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.success {
+            let stderr = output.stderr_str();
             bail!("gh extension install failed: {}", stderr.trim());
         }
 
@@ -88,4 +90,8 @@ impl Resource for GHExtension {
         self.install(ctx)?;
         Ok(ApplyResult::Created)
     }
+
+    fn plan(&self) -> Vec<PlannedAction> {
+        vec![PlannedAction::run("gh", &self.install_args())]
+    }
 }
@@ -4,7 +4,10 @@ use anyhow::{Context, Result, bail};
 use std::fmt;
 use std::process::Command;
 
-use super::{ApplyContext, ApplyResult, Resource, ResourceState, SudoRequirement};
+use super::{
+    ApplyContext, ApplyResult, ConfigSuggestion, PlannedAction, Resource, ResourceState,
+    SudoRequirement,
+};
 
 /// A macOS default preference
 #[derive(Debug, Clone)]
@@ -39,6 +42,18 @@ impl fmt::Display for DefaultValue {
     }
 }
 
+impl DefaultValue {
+    /// The value as it's written in the config
+    fn to_toml(&self) -> String {
+        match self {
+            DefaultValue::Bool(b) => toml::Value::Boolean(*b).to_string(),
+            DefaultValue::Int(i) => toml::Value::Integer(*i).to_string(),
+            DefaultValue::Float(v) => toml::Value::Float(*v).to_string(),
+            DefaultValue::String(s) => toml::Value::String(s.clone()).to_string(),
+        }
+    }
+}
+
 impl MacOSDefault {
     pub fn new(domain: &str, key: &str, value: DefaultValue) -> Self {
         Self {
@@ -99,8 +114,8 @@ impl MacOSDefault {
         Ok(parsed)
     }
 
-    /// Write value to defaults
-    fn write_value(&self, ctx: &ApplyContext) -> Result<()> {
+    /// Arguments to `defaults` that write the value
+    fn write_args(&self) -> Vec<String> {
         let type_flag = match &self.value {
             DefaultValue::Bool(_) => "-bool",
             DefaultValue::Int(_) => "-int",
@@ -115,28 +130,28 @@ impl MacOSDefault {
             DefaultValue::String(s) => s.clone(),
         };
 
-        let args = ["write", &self.domain, &self.key, type_flag, &value_str];
+        vec![
+            "write".to_string(),
+            self.domain.clone(),
+            self.key.clone(),
+            type_flag.to_string(),
+            value_str,
+        ]
+    }
+
+    /// Write value to defaults
+    fn write_value(&self, ctx: &ApplyContext) -> Result<()> {
+        let args = self.write_args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
 
-        let (success, stderr) = if self.requires_sudo {
-            let output = ctx
-                .sudo
-                .as_ref()
-                .ok_or_else(|| anyhow::anyhow!("Sudo required but not available"))?
-                .run("defaults", &args)?;
-            (output.success, output.stderr_str())
+        let output = if self.requires_sudo {
+            ctx.run_sudo("defaults", &args)?
         } else {
-            let output = Command::new("defaults")
-                .args(args)
-                .output()
-                .context("Failed to execute defaults write")?;
-            (
-                output.status.success(),
-                String::from_utf8_lossy(&output.stderr).to_string(),
-            )
+            ctx.run("defaults", &args)?
         };
 
-        if !success {
-            bail!("defaults write failed: {}", stderr.trim());
+        if !output.success {
+            bail!("defaults write failed: {}", output.stderr_str().trim());
         }
 
         Ok(())
@@ -202,6 +217,26 @@ impl Resource for MacOSDefault {
             }
         }
     }
+
+    fn plan(&self) -> Vec<PlannedAction> {
+        let args = self.write_args();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        if self.requires_sudo {
+            vec![PlannedAction::run_sudo("defaults", &args)]
+        } else {
+            vec![PlannedAction::run("defaults", &args)]
+        }
+    }
+
+    fn adopt(&self) -> Option<ConfigSuggestion> {
+        let current = self.read_current().ok()??;
+        Some(ConfigSuggestion {
+            resource_type: self.resource_type().to_string(),
+            resource_id: self.id(),
+            section: "defaults".to_string(),
+            entry: format!("\"{}.{}\" = {}", self.domain, self.key, current.to_toml()),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -217,6 +252,17 @@ mod tests {
         assert_eq!(pref.key, "KeyRepeat");
     }
 
+    #[test]
+    fn to_toml_matches_config_syntax() {
+        assert_eq!(DefaultValue::Bool(true).to_toml(), "true");
+        assert_eq!(DefaultValue::Int(2).to_toml(), "2");
+        assert_eq!(DefaultValue::Float(1.0).to_toml(), "1.0");
+        assert_eq!(
+            DefaultValue::String("Nlsv".to_string()).to_toml(),
+            "\"Nlsv\""
+        );
+    }
+
     #[test]
     fn from_domain_key_rejects_missing_separator() {
         let result = MacOSDefault::from_domain_key("invalid", DefaultValue::Bool(true));
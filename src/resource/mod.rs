@@ -6,7 +6,10 @@
 #![allow(dead_code)]
 
 // Re-export core types from declarative crate
-pub use declarative::{ApplyContext, ApplyResult, Resource, ResourceState, SudoRequirement};
+pub use declarative::{
+    ApplyContext, ApplyResult, ConfigSuggestion, PlannedAction, Resource, ResourceState,
+    SudoRequirement,
+};
 
 // Bossa-specific resource implementations
 pub mod brew_package;
@@ -3,7 +3,7 @@
 use anyhow::{Context, Result, bail};
 use std::process::Command;
 
-use super::{ApplyContext, ApplyResult, Resource, ResourceState};
+use super::{ApplyContext, ApplyResult, PlannedAction, Resource, ResourceState};
 
 /// A pnpm global package
 #[derive(Debug, Clone)]
@@ -33,15 +33,17 @@ impl PnpmPackage {
         Ok(stdout.contains(&self.name))
     }
 
+    /// Arguments to `pnpm` that install the package
+    fn install_args(&self) -> [&str; 3] {
+        ["add", "-g", &self.name]
+    }
+
     /// Install the package globally
-    fn install(&self, _ctx: &ApplyContext) -> Result<()> {
-        let output = Command::new("pnpm")
-            .args(["add", "-g", &self.name])
-            .output()
-            .context("Failed to run pnpm add")?;
+    fn install(&self, ctx: &ApplyContext) -> Result<()> {
+        let output = ctx.run("pnpm", &self.install_args())?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.success {
+            let stderr = output.stderr_str();
             bail!("pnpm add failed: {}", stderr.trim());
         }
 
@@ -88,4 +90,8 @@ impl Resource for PnpmPackage {
         self.install(ctx)?;
         Ok(ApplyResult::Created)
     }
+
+    fn plan(&self) -> Vec<PlannedAction> {
+        vec![PlannedAction::run("pnpm", &self.install_args())]
+    }
 }
@@ -3,7 +3,7 @@
 use anyhow::{Context, Result};
 use std::process::Command;
 
-use super::{ApplyContext, ApplyResult, Resource, ResourceState, SudoRequirement};
+use super::{ApplyContext, ApplyResult, PlannedAction, Resource, ResourceState, SudoRequirement};
 
 /// A macOS service to restart
 #[derive(Debug, Clone)]
@@ -89,6 +89,10 @@ impl Resource for Service {
         }
     }
 
+    fn plan(&self) -> Vec<PlannedAction> {
+        vec![PlannedAction::run("killall", &[&self.name])]
+    }
+
     fn can_parallelize(&self) -> bool {
         false // Services should be restarted sequentially
     }
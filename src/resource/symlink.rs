@@ -4,7 +4,10 @@ use anyhow::{Context, Result, bail};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use super::{ApplyContext, ApplyResult, Resource, ResourceState, SudoRequirement};
+use super::{
+    ApplyContext, ApplyResult, ConfigSuggestion, PlannedAction, Resource, ResourceState,
+    SudoRequirement,
+};
 
 /// A symlink to create
 #[derive(Debug, Clone)]
@@ -211,4 +214,26 @@ impl Resource for Symlink {
             }
         }
     }
+
+    fn plan(&self) -> Vec<PlannedAction> {
+        // An existing file is left alone, so there's nothing to plan
+        match (self.check_current(), self.expand_paths()) {
+            (Ok(SymlinkState::FileExists), _) | (_, Err(_)) => Vec::new(),
+            (_, Ok((source, target))) => vec![PlannedAction::symlink(target, source)],
+        }
+    }
+
+    fn adopt(&self) -> Option<ConfigSuggestion> {
+        // A link pointing elsewhere is kept by ignoring it
+        let SymlinkState::WrongTarget(actual) = self.check_current().ok()? else {
+            return None;
+        };
+        let name = self.source.file_name()?.to_string_lossy();
+        Some(ConfigSuggestion {
+            resource_type: self.resource_type().to_string(),
+            resource_id: self.id(),
+            section: "symlinks".to_string(),
+            entry: format!("ignore = [\"{name}\"]  # points to {}", actual.display()),
+        })
+    }
 }
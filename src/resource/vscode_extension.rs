@@ -3,7 +3,7 @@
 use anyhow::{Context, Result, bail};
 use std::process::Command;
 
-use super::{ApplyContext, ApplyResult, Resource, ResourceState};
+use super::{ApplyContext, ApplyResult, PlannedAction, Resource, ResourceState};
 
 /// A VS Code extension
 #[derive(Debug, Clone)]
@@ -33,15 +33,17 @@ impl VSCodeExtension {
         Ok(stdout.lines().any(|line| line.trim() == self.extension_id))
     }
 
+    /// Arguments to `code` that install the extension
+    fn install_args(&self) -> [&str; 3] {
+        ["--install-extension", &self.extension_id, "--force"]
+    }
+
     /// Install the extension
-    fn install(&self, _ctx: &ApplyContext) -> Result<()> {
-        let output = Command::new("code")
-            .args(["--install-extension", &self.extension_id, "--force"])
-            .output()
-            .context("Failed to run code --install-extension")?;
+    fn install(&self, ctx: &ApplyContext) -> Result<()> {
+        let output = ctx.run("code", &self.install_args())?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.success {
+            let stderr = output.stderr_str();
             bail!("code --install-extension failed: {}", stderr.trim());
         }
 
@@ -88,4 +90,8 @@ impl Resource for VSCodeExtension {
         self.install(ctx)?;
         Ok(ApplyResult::Created)
     }
+
+    fn plan(&self) -> Vec<PlannedAction> {
+        vec![PlannedAction::run("code", &self.install_args())]
+    }
 }
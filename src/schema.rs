@@ -2,10 +2,12 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 use crate::paths;
+use crate::runner;
 
 // ============================================================================
 // Main Config Schema
@@ -85,13 +87,53 @@ pub struct BossaConfig {
     /// Homebrew cellar warehousing on external SSD
     #[serde(default)]
     pub cellar: CellarConfig,
+
+    /// Build tools installed by `bossa nova`
+    #[serde(default)]
+    pub toolchain: ToolchainConfig,
+
+    /// Custom values for `${name}` references, on top of `hostname`, `user`,
+    /// `os`, `arch` and `home`
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+
+    /// Overlays selected with `--profile` (or `BOSSA_PROFILE`)
+    #[serde(default)]
+    pub profiles: HashMap<String, ConfigOverlay>,
+
+    /// Overlays for machines, keyed by full or short hostname
+    #[serde(default)]
+    pub hosts: HashMap<String, ConfigOverlay>,
+
+    /// Whether a profile or host overlay was merged in by [`Self::load`]
+    #[serde(skip)]
+    overlaid: bool,
 }
 
 impl BossaConfig {
-    /// Load the unified bossa config from the config directory
+    /// Load the unified bossa config from the config directory, with the
+    /// overlay for this machine's hostname and then the one for the
+    /// selected profile merged on
     ///
     /// See [`crate::paths::config_dir`] for path resolution details.
     pub fn load() -> Result<Self> {
+        Self::load_base()?.resolve(selected_profile())
+    }
+
+    /// Merge on the overlay for this machine's hostname, then the one for
+    /// `profile`; for configs loaded some other way than [`Self::load`]
+    pub fn resolve(self, profile: Option<&str>) -> Result<Self> {
+        let host = if self.hosts.is_empty() {
+            None
+        } else {
+            hostname()
+        };
+        self.with_overlays(host, profile)
+    }
+
+    /// Load the config as written, without overlays; the config to change
+    /// and [`save`](Self::save)
+    pub fn load_base() -> Result<Self> {
         let config_dir = paths::config_dir()?;
         let config_path = config_dir.join("config.toml");
 
@@ -110,6 +152,9 @@ impl BossaConfig {
     ///
     /// See [`crate::paths::config_dir`] for path resolution details.
     pub fn save(&self) -> Result<PathBuf> {
+        if self.overlaid {
+            anyhow::bail!("Refusing to save config with profile or host overlays merged in");
+        }
         let config_dir = paths::config_dir()?;
         std::fs::create_dir_all(&config_dir)?;
 
@@ -145,9 +190,78 @@ impl BossaConfig {
         // Validate locations
         self.locations.validate()?;
 
+        // Validate toolchain
+        self.toolchain.validate()?;
+
+        // Validate that every overlay merges into a valid config
+        if !self.overlaid {
+            let overlays = self
+                .profiles
+                .iter()
+                .map(|(name, overlay)| (format!("profile '{name}'"), overlay))
+                .chain(
+                    self.hosts
+                        .iter()
+                        .map(|(name, overlay)| (format!("host '{name}'"), overlay)),
+                );
+            for (label, overlay) in overlays {
+                self.merged(&[(label.clone(), overlay)])?
+                    .validate()
+                    .with_context(|| format!("Invalid config with {label}"))?;
+            }
+        }
+
         Ok(())
     }
 
+    /// The config with the overlay for `host` (matched by its full name,
+    /// then its short one) and then the one for `profile` merged on
+    pub fn with_overlays(self, host: Option<&Hostname>, profile: Option<&str>) -> Result<Self> {
+        let mut overlays = Vec::new();
+        if let Some(host) = host
+            && let Some(overlay) = self
+                .hosts
+                .get(&host.full)
+                .or_else(|| self.hosts.get(host.short()))
+        {
+            overlays.push((format!("host '{}'", host.full), overlay));
+        }
+        if let Some(profile) = profile {
+            let Some(overlay) = self.profiles.get(profile) else {
+                let mut known: Vec<_> = self.profiles.keys().map(String::as_str).collect();
+                known.sort_unstable();
+                anyhow::bail!(
+                    "Unknown profile '{profile}' (defined: {})",
+                    if known.is_empty() {
+                        "none".to_string()
+                    } else {
+                        known.join(", ")
+                    }
+                );
+            };
+            overlays.push((format!("profile '{profile}'"), overlay));
+        }
+
+        if overlays.is_empty() {
+            return Ok(self);
+        }
+        self.merged(&overlays)
+    }
+
+    /// This config with `overlays` merged on, in order
+    fn merged(&self, overlays: &[(String, &ConfigOverlay)]) -> Result<Self> {
+        let mut table = toml::Table::try_from(self).context("Failed to serialize config")?;
+        for (_, overlay) in overlays {
+            overlay.merge_into(&mut table);
+        }
+        let mut config: Self = table.try_into().with_context(|| {
+            let labels: Vec<_> = overlays.iter().map(|(label, _)| label.as_str()).collect();
+            format!("Invalid config with {}", labels.join(" and "))
+        })?;
+        config.overlaid = true;
+        Ok(config)
+    }
+
     /// Find a collection by name
     pub fn find_collection(&self, name: &str) -> Option<&Collection> {
         self.collections.get(name)
@@ -169,6 +283,128 @@ impl BossaConfig {
     }
 }
 
+// ============================================================================
+// Profiles and Host Overlays
+// ============================================================================
+
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Select the profile whose overlay [`BossaConfig::load`] merges on, once
+/// at startup
+pub fn set_profile(name: &str) {
+    let _ = PROFILE.set(name.to_string());
+}
+
+/// The profile selected with `--profile`, if any
+pub fn selected_profile() -> Option<&'static str> {
+    PROFILE.get().map(String::as_str)
+}
+
+/// Settings a profile or host changes on top of the base config.
+///
+/// Sections are kept as plain TOML so an overlay only has to name what it
+/// changes: tables are merged key by key, arrays gain the items they don't
+/// already have, and other values are replaced. For example:
+///
+/// ```toml
+/// [profiles.work.packages.brew]
+/// casks = ["slack", "zoom"]
+///
+/// [hosts."studio.local".defaults]
+/// "com.apple.dock.autohide" = false
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigOverlay {
+    /// Merged onto `[packages]`
+    #[serde(default)]
+    pub packages: Option<toml::Table>,
+
+    /// Merged onto `[tools]`
+    #[serde(default)]
+    pub tools: Option<toml::Table>,
+
+    /// Merged onto `[defaults]`
+    #[serde(default)]
+    pub defaults: Option<toml::Table>,
+
+    /// Merged onto `[symlinks]`
+    #[serde(default)]
+    pub symlinks: Option<toml::Table>,
+}
+
+impl ConfigOverlay {
+    /// Merge the overlay's sections onto a serialized config
+    fn merge_into(&self, config: &mut toml::Table) {
+        let sections = [
+            ("packages", &self.packages),
+            ("tools", &self.tools),
+            ("defaults", &self.defaults),
+            ("symlinks", &self.symlinks),
+        ];
+        for (name, section) in sections {
+            let Some(section) = section else {
+                continue;
+            };
+            let base = config
+                .entry(name)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            match base {
+                toml::Value::Table(base) => merge_tables(base, section),
+                other => *other = toml::Value::Table(section.clone()),
+            }
+        }
+    }
+}
+
+fn merge_tables(base: &mut toml::Table, overlay: &toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge_tables(base, overlay);
+            }
+            (Some(toml::Value::Array(base)), toml::Value::Array(overlay)) => {
+                for item in overlay {
+                    if !base.contains(item) {
+                        base.push(item.clone());
+                    }
+                }
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// This machine's hostname, as overlays in `[hosts]` are keyed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hostname {
+    /// The full name, e.g. `studio.local`
+    pub full: String,
+}
+
+impl Hostname {
+    /// The name up to the first dot, e.g. `studio`
+    pub fn short(&self) -> &str {
+        self.full.split('.').next().unwrap_or(&self.full)
+    }
+}
+
+/// This machine's hostname, looked up once
+fn hostname() -> Option<&'static Hostname> {
+    static HOSTNAME: OnceLock<Option<Hostname>> = OnceLock::new();
+    HOSTNAME
+        .get_or_init(|| {
+            runner::run_capture("hostname", &[])
+                .ok()
+                .or_else(|| std::env::var("HOSTNAME").ok())
+                .filter(|name| !name.is_empty())
+                .map(|full| Hostname { full })
+        })
+        .as_ref()
+}
+
 // ============================================================================
 // Collection - Group of git repos in a folder
 // ============================================================================
@@ -294,7 +530,7 @@ fn default_branch() -> String {
 // Workspaces - Bare repo + worktree structure
 // ============================================================================
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WorkspacesConfig {
     /// Root directory for all workspaces
     #[serde(default = "default_workspaces_root")]
@@ -309,6 +545,16 @@ pub struct WorkspacesConfig {
     pub repos: Vec<WorkspaceRepo>,
 }
 
+impl Default for WorkspacesConfig {
+    fn default() -> Self {
+        Self {
+            root: default_workspaces_root(),
+            structure: default_structure(),
+            repos: Vec::new(),
+        }
+    }
+}
+
 impl WorkspacesConfig {
     /// Get the expanded root path
     pub fn expanded_root(&self) -> Result<PathBuf> {
@@ -693,6 +939,38 @@ pub struct BrewConfig {
     pub fonts: Vec<String>,
     #[serde(default)]
     pub essential: BrewEssentialConfig,
+    /// Named package groups, installed in declaration order after the base lists
+    #[serde(default)]
+    pub groups: Vec<BrewGroupConfig>,
+    /// Seconds cached `brew info` metadata stays valid (0 disables the cache)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info_cache_ttl: Option<u64>,
+}
+
+impl BrewConfig {
+    /// How long cached `brew info` metadata stays valid, five minutes by default.
+    pub fn info_cache_ttl(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.info_cache_ttl.unwrap_or(300))
+    }
+
+    /// Find a group by name.
+    pub fn group(&self, name: &str) -> Option<&BrewGroupConfig> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+}
+
+/// A named group of brew packages (`[[packages.brew.groups]]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BrewGroupConfig {
+    pub name: String,
+    #[serde(default)]
+    pub taps: Vec<String>,
+    #[serde(default)]
+    pub formulas: Vec<String>,
+    #[serde(default)]
+    pub casks: Vec<String>,
+    #[serde(default)]
+    pub fonts: Vec<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -1965,6 +2243,49 @@ pub struct CellarConfig {
     pub local: Vec<String>,
 }
 
+// ============================================================================
+// Toolchain Config (build tools installed by nova)
+// ============================================================================
+
+/// Build tools (buck2, bazel, buildifier) that `bossa nova` installs when
+/// missing, in parallel
+///
+/// Example config:
+/// ```toml
+/// [toolchain]
+/// tools = { buck2 = "latest", bazel = "7.4.1" }
+/// require_checksums = true
+/// ```
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct ToolchainConfig {
+    /// Release tag to install, by tool name
+    #[serde(default)]
+    pub tools: BTreeMap<String, String>,
+    /// Fail, rather than warn, when a release lacks the checksum its tool
+    /// publishes
+    #[serde(default)]
+    pub require_checksums: bool,
+}
+
+impl ToolchainConfig {
+    /// Check that every tool is one the toolchain crate can install
+    pub fn validate(&self) -> Result<()> {
+        for name in self.tools.keys() {
+            if toolchain::Tool::from_name(name).is_none() {
+                let known: Vec<_> = toolchain::Tool::all()
+                    .iter()
+                    .map(toolchain::Tool::name)
+                    .collect();
+                anyhow::bail!(
+                    "Unknown toolchain tool '{name}' (known: {})",
+                    known.join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -2040,6 +2361,32 @@ stages = ["defaults", "homebrew", "essential", "stow", "collections", "workspace
         assert!(config.nova.has_stage("homebrew"));
     }
 
+    #[test]
+    fn test_parse_brew_groups() {
+        let toml = r#"
+[packages.brew]
+formulas = ["git"]
+
+[[packages.brew.groups]]
+name = "base"
+formulas = ["jq", "ripgrep"]
+
+[[packages.brew.groups]]
+name = "gui"
+taps = ["homebrew/cask-fonts"]
+casks = ["firefox"]
+"#;
+
+        let config: BossaConfig = toml::from_str(toml).expect("Failed to parse config");
+        let brew = &config.packages.brew;
+
+        assert_eq!(brew.formulas, vec!["git"]);
+        assert_eq!(brew.groups.len(), 2);
+        assert_eq!(brew.group("base").unwrap().formulas, vec!["jq", "ripgrep"]);
+        assert_eq!(brew.group("gui").unwrap().taps, vec!["homebrew/cask-fonts"]);
+        assert!(brew.group("missing").is_none());
+    }
+
     #[test]
     fn test_collection_validation() {
         let mut collection = Collection {
@@ -2711,4 +3058,51 @@ name = "rust"
         };
         assert!(with_proxy.has_proxy());
     }
+
+    #[test]
+    fn test_profile_and_host_overlays() {
+        let toml = r#"
+[packages.brew]
+formulas = ["git"]
+casks = ["firefox"]
+
+[profiles.work.packages.brew]
+casks = ["slack"]
+
+[profiles.personal.packages.brew]
+casks = ["steam"]
+
+[hosts.studio.packages.brew]
+formulas = ["ffmpeg", "git"]
+"#;
+        // Hosts match by their short name too
+        let studio = Hostname {
+            full: "studio.local".to_string(),
+        };
+        let laptop = Hostname {
+            full: "laptop.local".to_string(),
+        };
+        let load = || toml::from_str::<BossaConfig>(toml).unwrap();
+
+        let config = load().with_overlays(Some(&studio), Some("work")).unwrap();
+        assert_eq!(config.packages.brew.formulas, ["git", "ffmpeg"]);
+        assert_eq!(config.packages.brew.casks, ["firefox", "slack"]);
+        assert!(config.save().is_err());
+
+        let config = load().with_overlays(Some(&laptop), None).unwrap();
+        assert_eq!(config.packages.brew.formulas, ["git"]);
+        assert_eq!(config.packages.brew.casks, ["firefox"]);
+
+        let error = load().with_overlays(None, Some("home")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown profile 'home' (defined: personal, work)"
+        );
+    }
+
+    #[test]
+    fn test_overlay_rejects_other_sections() {
+        let toml = "[profiles.work.collections.refs]\npath = \"~/refs\"\n";
+        assert!(toml::from_str::<BossaConfig>(toml).is_err());
+    }
 }
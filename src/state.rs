@@ -99,6 +99,10 @@ pub struct BossaState {
     #[serde(default)]
     pub dotfiles: DotfilesState,
 
+    /// Machine freeze (set by `bossa freeze`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freeze: Option<FreezeState>,
+
     /// Last time the state was updated
     pub last_updated: DateTime<Utc>,
 }
@@ -171,6 +175,27 @@ pub struct DotfilesState {
     pub private_initialized: bool,
 }
 
+/// A machine freeze - mutating commands refuse to run until it expires
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FreezeState {
+    /// When the freeze expires
+    pub until: DateTime<Utc>,
+
+    /// Why the machine was frozen
+    #[serde(default)]
+    pub reason: Option<String>,
+
+    /// When the freeze was set
+    pub frozen_at: DateTime<Utc>,
+}
+
+impl FreezeState {
+    /// Whether the freeze is still in effect at `now`
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        now < self.until
+    }
+}
+
 // ============================================================================
 // BossaState Implementation
 // ============================================================================
@@ -315,6 +340,29 @@ impl BossaState {
         self.workspaces.last_sync = Some(Utc::now());
     }
 
+    // ========================================================================
+    // Freeze Helpers
+    // ========================================================================
+
+    /// Freeze the machine until the given time
+    pub fn freeze(&mut self, until: DateTime<Utc>, reason: Option<String>) {
+        self.freeze = Some(FreezeState {
+            until,
+            reason,
+            frozen_at: Utc::now(),
+        });
+    }
+
+    /// Lift the freeze. Returns true if the machine was frozen.
+    pub fn unfreeze(&mut self) -> bool {
+        self.freeze.take().is_some()
+    }
+
+    /// Get the freeze if it's still in effect (expired freezes are ignored)
+    pub fn active_freeze(&self) -> Option<&FreezeState> {
+        self.freeze.as_ref().filter(|f| f.is_active_at(Utc::now()))
+    }
+
     // ========================================================================
     // Storage State Helpers
     // ========================================================================
@@ -392,6 +440,7 @@ impl Default for BossaState {
             storage: HashMap::new(),
             symlinks: SymlinkInventory::default(),
             dotfiles: DotfilesState::default(),
+            freeze: None,
             last_updated: Utc::now(),
         }
     }
@@ -516,6 +565,31 @@ mod tests {
         assert!(!state.storage.get("t9").unwrap().is_mounted);
     }
 
+    #[test]
+    fn test_freeze_state() {
+        let mut state = BossaState::default();
+        assert!(state.active_freeze().is_none());
+
+        state.freeze(
+            Utc::now() + chrono::Duration::days(1),
+            Some("demo".to_string()),
+        );
+        let freeze = state.active_freeze().expect("freeze should be active");
+        assert_eq!(freeze.reason.as_deref(), Some("demo"));
+
+        // Survives a round-trip through TOML
+        let toml_str = toml::to_string_pretty(&state).unwrap();
+        let loaded: BossaState = toml::from_str(&toml_str).unwrap();
+        assert!(loaded.active_freeze().is_some());
+
+        // Expired freezes are ignored
+        state.freeze(Utc::now() - chrono::Duration::hours(1), None);
+        assert!(state.active_freeze().is_none());
+
+        assert!(state.unfreeze());
+        assert!(!state.unfreeze());
+    }
+
     #[test]
     fn test_serialization() {
         let mut state = BossaState::default();
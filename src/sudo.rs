@@ -3,13 +3,14 @@
 //! Sudo is never requested for the entire process. Instead:
 //! 1. Config defines which operations need sudo (allowlist)
 //! 2. All changes are computed first (no sudo needed)
-//! 3. Sudo is acquired once for privileged batch
-//! 4. Sudo is released immediately after
+//! 3. Sudo is acquired once, for the first privileged batch, and kept alive
+//!    for later ones
+//! 4. Sudo is released when the plan ends
 
 #![allow(dead_code)]
 
 use anyhow::{Context, Result, bail};
-use declarative::{CommandOutput, SudoClassifier, SudoProvider};
+use declarative::{CommandOutput, SudoClassifier, SudoProvider, SudoSession};
 use serde::{Deserialize, Serialize};
 use std::process::{Command, Output};
 
@@ -59,7 +60,7 @@ impl SudoClassifier for SudoConfig {
 
 /// Scoped sudo context - automatically invalidates on drop
 pub struct SudoContext {
-    validated: bool,
+    session: SudoSession,
 }
 
 impl SudoContext {
@@ -70,17 +71,10 @@ impl SudoContext {
         eprintln!("  Sudo required: {reason}");
         eprintln!();
 
-        // Validate sudo (will prompt for password)
-        let status = Command::new("sudo")
-            .args(["-v"])
-            .status()
-            .context("Failed to execute sudo")?;
-
-        if !status.success() {
-            bail!("Failed to acquire sudo privileges");
-        }
+        // Validate sudo (will prompt for password), then keep it alive
+        let session = SudoSession::start()?;
 
-        Ok(Self { validated: true })
+        Ok(Self { session })
     }
 
     /// Check if sudo is currently valid (without prompting)
@@ -94,10 +88,6 @@ impl SudoContext {
 
     /// Run a command with sudo (internal)
     fn run_internal(&self, cmd: &str, args: &[&str]) -> Result<Output> {
-        if !self.validated {
-            bail!("Sudo context not validated");
-        }
-
         let output = Command::new("sudo")
             .arg(cmd)
             .args(args)
@@ -134,13 +124,6 @@ impl SudoProvider for SudoContext {
     }
 }
 
-impl Drop for SudoContext {
-    fn drop(&mut self) {
-        // Invalidate sudo timestamp to release privileges
-        let _ = Command::new("sudo").args(["-k"]).status();
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,18 +1,41 @@
 //! UI utilities for bossa CLI.
 //!
-//! This module re-exports pintui functions and adds bossa-specific utilities.
+//! This module re-exports pintui's formatting and progress helpers, and
+//! draws messages and layouts itself so they follow the color policy and
+//! theme chosen at startup.
 
-// Re-export all pintui functionality
+// Re-export pintui formatting
 #[allow(unused_imports)]
 pub use pintui::format::human_size as format_size;
 #[allow(unused_imports)]
 pub use pintui::format::parse_size;
 #[allow(unused_imports)]
 pub use pintui::format::truncate_path;
+
+mod color;
+pub mod diff;
+mod format;
+mod layout;
+pub mod logger;
+pub mod markdown;
+mod messages;
+pub mod output;
+pub mod pager;
+pub mod prompt;
+mod theme;
+
+#[allow(unused_imports)]
+pub use color::{ColorChoice, set_color_choice};
+#[allow(unused_imports)]
+pub use format::{eta, human_duration, rate, truncate_middle, wrap};
+#[allow(unused_imports)]
+pub use layout::{
+    ColumnOptions, Status, SummaryCard, columns, header, kv, panel, print_columns, section, step,
+};
 #[allow(unused_imports)]
-pub use pintui::layout::{header, kv, section, step};
+pub use messages::{dim, error, info, success, warn};
 #[allow(unused_imports)]
-pub use pintui::messages::{dim, error, info, success, warn};
+pub use theme::{Icons, Theme, ThemeChoice, set_theme, theme};
 
 /// Print the bossa banner.
 pub fn banner() {
@@ -27,7 +50,7 @@ pub fn banner() {
   ██████╔╝╚██████╔╝███████║███████║██║  ██║
   ╚═════╝  ╚═════╝ ╚══════╝╚══════╝╚═╝  ╚═╝
 "
-        .cyan()
+        .color(theme().accent)
     );
 }
 
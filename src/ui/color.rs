@@ -0,0 +1,62 @@
+//! Color policy - when bossa's output is colored.
+//!
+//! Colors are on by default only when writing to a terminal and `NO_COLOR`
+//! isn't set, so output piped to a file or another program stays plain.
+//! `--color` and `--no-color` override that. The choice is applied to every
+//! library that draws for bossa: `colored` for messages and layouts,
+//! `console` for progress bars and prompts.
+
+use std::io::IsTerminal;
+
+/// When to emit ANSI colors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal, unless NO_COLOR is set
+    #[default]
+    Auto,
+    /// Always color, even when piped
+    Always,
+    /// Never color
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to color a stream, given `NO_COLOR` and whether it's a terminal
+    fn should_color(self, no_color: bool, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => !no_color && is_terminal,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// Set the color policy for all output, once at startup.
+pub fn set_color_choice(choice: ColorChoice) {
+    // NO_COLOR counts when set to anything but an empty string
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let stdout = choice.should_color(no_color, std::io::stdout().is_terminal());
+    let stderr = choice.should_color(no_color, std::io::stderr().is_terminal());
+
+    colored::control::set_override(stdout);
+    console::set_colors_enabled(stdout);
+    console::set_colors_enabled_stderr(stderr);
+    // Prompts draw with dialoguer's own version of console
+    dialoguer::console::set_colors_enabled(stdout);
+    dialoguer::console::set_colors_enabled_stderr(stderr);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_color() {
+        assert!(ColorChoice::Auto.should_color(false, true));
+        assert!(!ColorChoice::Auto.should_color(false, false));
+        assert!(!ColorChoice::Auto.should_color(true, true));
+
+        assert!(ColorChoice::Always.should_color(true, false));
+        assert!(!ColorChoice::Never.should_color(false, true));
+    }
+}
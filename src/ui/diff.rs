@@ -0,0 +1,199 @@
+//! Diffs - colored unified diffs and compact `old → new` changes.
+//!
+//! Removed lines are drawn in the error color and added lines in the
+//! success color. Within a changed line, the words that actually changed are
+//! highlighted, so a one-character edit in a long line is easy to spot.
+
+#![allow(dead_code)]
+
+use colored::{ColoredString, Colorize};
+use similar::{ChangeTag, TextDiff};
+
+use super::theme::theme;
+
+/// Unchanged lines shown around each change
+const CONTEXT: usize = 3;
+
+/// What a line of a unified diff is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Hunk,
+    Context,
+    Removed,
+    Added,
+}
+
+/// A line of a unified diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Line {
+    kind: Kind,
+    segments: Segments,
+}
+
+/// Pieces of text, `true` marking the ones that changed
+type Segments = Vec<(bool, String)>;
+
+/// A colored unified diff of `old` and `new`, hunks only; empty when they're
+/// the same
+pub fn unified(old: &str, new: &str) -> String {
+    lines(old, new).iter().map(render_line).collect()
+}
+
+/// A colored unified diff under `---`/`+++` headers naming both sides;
+/// empty when they're the same
+pub fn labeled(old_label: &str, old: &str, new_label: &str, new: &str) -> String {
+    let hunks = unified(old, new);
+    if hunks.is_empty() {
+        return hunks;
+    }
+    format!(
+        "{}\n{}\n{hunks}",
+        format!("--- {old_label}").bold(),
+        format!("+++ {new_label}").bold()
+    )
+}
+
+/// A single-line change, `old → new`, with the changed words highlighted
+pub fn inline(old: &str, new: &str) -> String {
+    let t = theme();
+    let (old, new) = words(old, new);
+    format!(
+        "{} {} {}",
+        paint(&old, |text| t.error(text)),
+        t.dim(t.icons.arrow),
+        paint(&new, |text| t.success(text))
+    )
+}
+
+/// A named single-line change, `key: old → new`
+pub fn change(key: &str, old: &str, new: &str) -> String {
+    format!("{key}: {}", inline(old, new))
+}
+
+/// The hunks of a line diff of `old` and `new`
+fn lines(old: &str, new: &str) -> Vec<Line> {
+    let diff = TextDiff::from_lines(old, new);
+    let mut unified = diff.unified_diff();
+    unified.context_radius(CONTEXT);
+
+    let mut out = Vec::new();
+    for hunk in unified.iter_hunks() {
+        out.push(Line {
+            kind: Kind::Hunk,
+            segments: vec![(false, hunk.header().to_string())],
+        });
+        for op in hunk.ops() {
+            for change in diff.iter_inline_changes(op) {
+                let kind = match change.tag() {
+                    ChangeTag::Equal => Kind::Context,
+                    ChangeTag::Delete => Kind::Removed,
+                    ChangeTag::Insert => Kind::Added,
+                };
+                let segments = change
+                    .iter_strings_lossy()
+                    .map(|(emphasized, text)| (emphasized, text.trim_end_matches('\n').to_string()))
+                    .filter(|(_, text)| !text.is_empty())
+                    .collect();
+                out.push(Line { kind, segments });
+            }
+        }
+    }
+    out
+}
+
+/// Both sides of a word diff, with the words only on one side marked
+fn words(old: &str, new: &str) -> (Segments, Segments) {
+    let diff = TextDiff::from_words(old, new);
+    let (mut old, mut new) = (Vec::new(), Vec::new());
+    for change in diff.iter_all_changes() {
+        let text = change.value().to_string();
+        match change.tag() {
+            ChangeTag::Equal => {
+                old.push((false, text.clone()));
+                new.push((false, text));
+            }
+            ChangeTag::Delete => old.push((true, text)),
+            ChangeTag::Insert => new.push((true, text)),
+        }
+    }
+    (old, new)
+}
+
+fn render_line(line: &Line) -> String {
+    let (prefix, color): (&str, fn(&str) -> ColoredString) = match line.kind {
+        Kind::Hunk => ("", |text| theme().accent(text)),
+        Kind::Context => (" ", |text| theme().dim(text)),
+        Kind::Removed => ("-", |text| theme().error(text)),
+        Kind::Added => ("+", |text| theme().success(text)),
+    };
+    format!("{}{}\n", color(prefix), paint(&line.segments, color))
+}
+
+/// Draw segments in a color, the marked ones highlighted
+fn paint(segments: &[(bool, String)], color: impl Fn(&str) -> ColoredString) -> String {
+    segments
+        .iter()
+        .map(|(emphasized, text)| {
+            let text = color(text);
+            if *emphasized {
+                text.bold().reversed().to_string()
+            } else {
+                text.to_string()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(segments: &[(bool, String)]) -> String {
+        segments.iter().map(|(_, text)| text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lines_highlight_changed_words() {
+        let old = "[user]\n  email = ada@example.com\n  name = Ada Lovelace\n";
+        let new = "[user]\n  email = ada@example.com\n  name = Ada King\n";
+        let lines = lines(old, new);
+
+        let kinds: Vec<_> = lines.iter().map(|line| line.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                Kind::Hunk,
+                Kind::Context,
+                Kind::Context,
+                Kind::Removed,
+                Kind::Added
+            ]
+        );
+        assert_eq!(plain(&lines[0].segments), "@@ -1,3 +1,3 @@");
+        assert_eq!(plain(&lines[3].segments), "  name = Ada Lovelace");
+
+        let changed: Vec<_> = lines[4]
+            .segments
+            .iter()
+            .filter(|(emphasized, _)| *emphasized)
+            .map(|(_, text)| text.as_str())
+            .collect();
+        assert_eq!(changed, ["King"]);
+    }
+
+    #[test]
+    fn test_same_text_has_no_hunks() {
+        assert!(lines("a\nb\n", "a\nb\n").is_empty());
+        assert!(labeled("a", "same\n", "b", "same\n").is_empty());
+    }
+
+    #[test]
+    fn test_words() {
+        let (old, new) = words("4 GB free", "12 GB free");
+        assert_eq!(plain(&old), "4 GB free");
+        assert_eq!(plain(&new), "12 GB free");
+        assert_eq!(old[0], (true, "4".to_string()));
+        assert_eq!(new[0], (true, "12".to_string()));
+        assert!(old[1..].iter().all(|(emphasized, _)| !emphasized));
+    }
+}
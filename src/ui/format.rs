@@ -0,0 +1,177 @@
+//! Formatting - fit text to a width as the terminal displays it, and show
+//! durations and speeds.
+//!
+//! Widths count terminal columns, not bytes or chars: CJK characters and
+//! most emoji take two columns, combining marks none.
+
+use std::time::Duration;
+use unicode_width::UnicodeWidthChar;
+
+const ELLIPSIS: &str = "...";
+
+/// Shorten `text` to `width` columns by replacing its middle with `...`,
+/// keeping both ends, e.g. `com.apple.Safari...Extensions.plist`.
+pub fn truncate_middle(text: &str, width: usize) -> String {
+    if console::measure_text_width(text) <= width {
+        return text.to_string();
+    }
+    let Some(budget) = width.checked_sub(ELLIPSIS.len()) else {
+        return ELLIPSIS[..width].to_string();
+    };
+
+    // The start gets the extra column when the budget is odd
+    let head = take_width(text.chars(), budget - budget / 2);
+    let mut tail: Vec<char> = take_width(text.chars().rev(), budget / 2).chars().collect();
+    tail.reverse();
+    format!("{head}{ELLIPSIS}{}", tail.into_iter().collect::<String>())
+}
+
+/// Wrap `text` into lines of at most `width` columns, breaking between
+/// words. Words wider than a line are broken wherever they fill it, and
+/// existing line breaks are kept.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut line_width = 0;
+        for word in paragraph.split_whitespace() {
+            let word_width = console::measure_text_width(word);
+            let needed = usize::from(!line.is_empty()) + word_width;
+            if line_width + needed <= width {
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                line_width += needed;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            // Start the word on a fresh line, breaking it if it's too wide
+            let mut rest = word;
+            while console::measure_text_width(rest) > width {
+                let piece = take_width(rest.chars(), width);
+                let piece = if piece.is_empty() {
+                    // A single character wider than the line
+                    rest.chars().next().map(String::from).unwrap_or_default()
+                } else {
+                    piece
+                };
+                rest = &rest[piece.len()..];
+                lines.push(piece);
+            }
+            line = rest.to_string();
+            line_width = console::measure_text_width(rest);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// A duration the way people say it: `850ms`, `3.2s`, `4m 12s`, `2h 5m`,
+/// `3d 4h`; only the two largest units are shown.
+pub fn human_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0 => format!("{}ms", duration.as_millis()),
+        1..10 => format!("{:.1}s", duration.as_secs_f64()),
+        10..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {}s", secs / 60, secs % 60),
+        3600..86400 => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}d {}h", secs / 86400, secs % 86400 / 3600),
+    }
+}
+
+/// Time left for `total` units of work, `done` of them having taken
+/// `elapsed`, assuming the pace holds. `None` until there's a pace to go by.
+pub fn eta(done: u64, total: u64, elapsed: Duration) -> Option<Duration> {
+    if done >= total {
+        return Some(Duration::ZERO);
+    }
+    if done == 0 {
+        return None;
+    }
+    Some(elapsed.mul_f64((total - done) as f64 / done as f64))
+}
+
+/// Bytes per second, e.g. `3.2 GB/s`; `-` before any time has passed.
+pub fn rate(bytes: u64, elapsed: Duration) -> String {
+    let secs = elapsed.as_secs_f64();
+    if secs == 0.0 {
+        return "-".to_string();
+    }
+    format!(
+        "{}/s",
+        pintui::format::human_size((bytes as f64 / secs) as u64)
+    )
+}
+
+/// As many of `chars` as fit in `width` columns
+fn take_width(chars: impl Iterator<Item = char>, width: usize) -> String {
+    let mut used = 0;
+    chars
+        .take_while(|c| {
+            used += c.width().unwrap_or(0);
+            used <= width
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_middle() {
+        assert_eq!(truncate_middle("short", 10), "short");
+        assert_eq!(
+            truncate_middle("Application Support/Code/User", 15),
+            "Applic...e/User"
+        );
+        assert_eq!(truncate_middle("abcdef", 2), "..");
+        // Wide characters count as two columns
+        assert_eq!(truncate_middle("日本語のファイル", 9), "日...ル");
+    }
+
+    #[test]
+    fn test_human_duration() {
+        assert_eq!(human_duration(Duration::from_millis(850)), "850ms");
+        assert_eq!(human_duration(Duration::from_millis(3240)), "3.2s");
+        assert_eq!(human_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(human_duration(Duration::from_secs(252)), "4m 12s");
+        assert_eq!(human_duration(Duration::from_secs(7500)), "2h 5m");
+        assert_eq!(human_duration(Duration::from_secs(273_600)), "3d 4h");
+    }
+
+    #[test]
+    fn test_eta() {
+        let minute = Duration::from_secs(60);
+        assert_eq!(eta(25, 100, minute), Some(minute * 3));
+        assert_eq!(eta(0, 100, minute), None);
+        assert_eq!(eta(100, 100, minute), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_rate() {
+        let gib = 1024 * 1024 * 1024;
+        assert_eq!(
+            rate(2 * gib, Duration::from_secs(2)),
+            format!("{}/s", pintui::format::human_size(gib))
+        );
+        assert_eq!(rate(gib, Duration::ZERO), "-");
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(
+            wrap("the quick brown fox jumps", 10),
+            ["the quick", "brown fox", "jumps"]
+        );
+        assert_eq!(wrap("abcdefghij klm", 4), ["abcd", "efgh", "ij", "klm"]);
+        assert_eq!(wrap("one\n\ntwo", 10), ["one", "", "two"]);
+        assert_eq!(wrap("日本語の文章", 5), ["日本", "語の", "文章"]);
+    }
+}
@@ -0,0 +1,359 @@
+//! Layout helpers - headers, sections, key/value lines, columns and panels.
+
+use super::format::wrap;
+use super::theme::{Icons, theme};
+use colored::Colorize;
+use console::measure_text_width;
+use std::fmt::Display;
+
+/// Print a command's header: the title with a rule under it.
+pub fn header(title: &str) {
+    let t = theme();
+    println!("{}", t.accent(title).bold());
+    println!("{}", t.dim(&t.icons.rule.repeat(title.chars().count())));
+}
+
+/// Print a section title.
+pub fn section(title: &str) {
+    println!("{}", title.bold());
+}
+
+/// Print a label and its value.
+pub fn kv(key: &str, value: &str) {
+    println!("  {} {value}", theme().dim(&format!("{key}:")));
+}
+
+/// Print a numbered step, e.g. `[2/5] Pull`.
+pub fn step(number: usize, total: usize, title: &str) {
+    let t = theme();
+    println!(
+        "{} {}",
+        t.accent(&format!("[{number}/{total}]")),
+        title.bold()
+    );
+}
+
+/// How [`columns`] lays out items
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnOptions {
+    /// Width to fit in; the terminal's width when `None`, and a single
+    /// column when stdout isn't a terminal
+    pub width: Option<usize>,
+    /// Spaces between columns
+    pub gap: usize,
+    /// Spaces before the first column
+    pub indent: usize,
+}
+
+impl Default for ColumnOptions {
+    fn default() -> Self {
+        Self {
+            width: None,
+            gap: 2,
+            indent: 4,
+        }
+    }
+}
+
+/// Arrange short items in as many columns as fit, top to bottom then left
+/// to right like `ls`; one line per row.
+///
+/// Items may be colored: widths are measured without ANSI codes. Without
+/// a width, output that isn't going to a terminal gets one item per line,
+/// for scripts to read.
+pub fn columns<T: AsRef<str>>(items: &[T], opts: &ColumnOptions) -> String {
+    let widths: Vec<usize> = items
+        .iter()
+        .map(|item| measure_text_width(item.as_ref()))
+        .collect();
+    let (rows, column_widths) = match opts.width.or_else(terminal_width) {
+        Some(width) => fit_columns(&widths, width.saturating_sub(opts.indent), opts.gap),
+        None => single_column(&widths),
+    };
+
+    let mut out = String::new();
+    for row in 0..rows {
+        let mut line = " ".repeat(opts.indent);
+        for (column, column_width) in column_widths.iter().enumerate() {
+            let index = column * rows + row;
+            let Some(item) = items.get(index) else {
+                break;
+            };
+            if column > 0 {
+                line.push_str(&" ".repeat(opts.gap));
+            }
+            line.push_str(item.as_ref());
+            line.push_str(&" ".repeat(column_width - widths[index]));
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Print items in columns, see [`columns`].
+pub fn print_columns<T: AsRef<str>>(items: &[T]) {
+    print!("{}", columns(items, &ColumnOptions::default()));
+}
+
+/// The layout with the fewest rows that fits: the number of rows and the
+/// width of each column. A single column when nothing else fits.
+fn fit_columns(widths: &[usize], width: usize, gap: usize) -> (usize, Vec<usize>) {
+    for rows in 1..widths.len() {
+        let column_widths: Vec<usize> = widths
+            .chunks(rows)
+            .map(|column| column.iter().copied().max().unwrap_or(0))
+            .collect();
+        let total = column_widths.iter().sum::<usize>() + gap * (column_widths.len() - 1);
+        if total <= width {
+            return (rows, column_widths);
+        }
+    }
+    single_column(widths)
+}
+
+/// One item per row
+fn single_column(widths: &[usize]) -> (usize, Vec<usize>) {
+    (
+        widths.len(),
+        vec![widths.iter().copied().max().unwrap_or(0)],
+    )
+}
+
+/// The terminal's width, if stdout is one
+fn terminal_width() -> Option<usize> {
+    console::Term::stdout()
+        .size_checked()
+        .map(|(_, columns)| usize::from(columns))
+}
+
+/// Characters boxes are drawn with: corners, then horizontal and vertical
+/// edges; ASCII for the ASCII theme
+struct Border {
+    top_left: &'static str,
+    top_right: &'static str,
+    bottom_left: &'static str,
+    bottom_right: &'static str,
+    horizontal: &'static str,
+    vertical: &'static str,
+}
+
+fn border() -> Border {
+    if theme().icons == Icons::ASCII {
+        Border {
+            top_left: "+",
+            top_right: "+",
+            bottom_left: "+",
+            bottom_right: "+",
+            horizontal: "-",
+            vertical: "|",
+        }
+    } else {
+        Border {
+            top_left: "┌",
+            top_right: "┐",
+            bottom_left: "└",
+            bottom_right: "┘",
+            horizontal: "─",
+            vertical: "│",
+        }
+    }
+}
+
+/// `body` in a box with `title` in its top edge, as wide as its widest line.
+///
+/// Lines too wide for the terminal are wrapped.
+pub fn panel(title: &str, body: &str) -> String {
+    let t = theme();
+    let b = border();
+    // 80 columns when not writing to a terminal
+    let max_inner = terminal_width()
+        .unwrap_or(80)
+        .saturating_sub(4)
+        .max(measure_text_width(title) + 2);
+    let lines: Vec<String> = body
+        .lines()
+        .flat_map(|line| {
+            if measure_text_width(line) > max_inner {
+                wrap(line, max_inner)
+            } else {
+                vec![line.to_string()]
+            }
+        })
+        .collect();
+    let inner = lines
+        .iter()
+        .map(|line| measure_text_width(line))
+        .max()
+        .unwrap_or(0)
+        .max(measure_text_width(title) + 2);
+
+    let mut out = format!(
+        "{}{} {} {}{}\n",
+        t.dim(b.top_left),
+        t.dim(b.horizontal),
+        title.bold(),
+        t.dim(&b.horizontal.repeat(inner - measure_text_width(title) - 1)),
+        t.dim(b.top_right)
+    );
+    for line in &lines {
+        let padding = " ".repeat(inner - measure_text_width(line));
+        out.push_str(&format!(
+            "{} {line}{padding} {}\n",
+            t.dim(b.vertical),
+            t.dim(b.vertical)
+        ));
+    }
+    out.push_str(&format!(
+        "{}{}{}\n",
+        t.dim(b.bottom_left),
+        t.dim(&b.horizontal.repeat(inner + 2)),
+        t.dim(b.bottom_right)
+    ));
+    out
+}
+
+/// How a [`SummaryCard`] row turned out, shown as its icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Warn,
+    Error,
+    Info,
+    Skipped,
+}
+
+impl Status {
+    fn icon(self) -> String {
+        let t = theme();
+        match self {
+            Status::Success => t.success(t.icons.success),
+            Status::Warn => t.warn(t.icons.warn),
+            Status::Error => t.error(t.icons.error),
+            Status::Info => t.info(t.icons.info),
+            Status::Skipped => t.dim(t.icons.skipped),
+        }
+        .to_string()
+    }
+}
+
+/// The end-of-run summary of a command: a panel of labeled values, each
+/// with a status icon
+///
+/// ```text
+/// ┌─ Summary ──────────┐
+/// │ ✓ Installed  12    │
+/// │ ⊘ Skipped    3     │
+/// │ ✗ Failed     1     │
+/// └────────────────────┘
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SummaryCard {
+    title: String,
+    rows: Vec<(Status, String, String)>,
+}
+
+impl SummaryCard {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a row
+    pub fn row(&mut self, status: Status, label: &str, value: impl Display) -> &mut Self {
+        self.rows
+            .push((status, label.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add a row for a count, unless it's zero
+    pub fn count<T>(&mut self, status: Status, label: &str, count: T) -> &mut Self
+    where
+        T: Display + Default + PartialEq,
+    {
+        if count != T::default() {
+            self.row(status, label, count);
+        }
+        self
+    }
+
+    /// The card as text, labels aligned
+    pub fn render(&self) -> String {
+        let width = self
+            .rows
+            .iter()
+            .map(|(_, label, _)| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let body: Vec<String> = self
+            .rows
+            .iter()
+            .map(|(status, label, value)| format!("{} {label:<width$}  {value}", status.icon()))
+            .collect();
+        panel(&self.title, &body.join("\n"))
+    }
+
+    /// Print the card
+    pub fn print(&self) {
+        print!("{}", self.render());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opts(width: usize) -> ColumnOptions {
+        ColumnOptions {
+            width: Some(width),
+            gap: 2,
+            indent: 0,
+        }
+    }
+
+    #[test]
+    fn test_columns_fill_top_to_bottom() {
+        let items = ["git", "node", "ripgrep", "fd", "jq"];
+        assert_eq!(columns(&items, &opts(20)), "git   ripgrep  jq\nnode  fd\n");
+    }
+
+    #[test]
+    fn test_columns_narrow_width_is_one_per_line() {
+        let items = ["ripgrep", "coreutils"];
+        assert_eq!(columns(&items, &opts(5)), "ripgrep\ncoreutils\n");
+        assert_eq!(columns::<&str>(&[], &opts(80)), "");
+    }
+
+    #[test]
+    fn test_columns_ignore_color_codes() {
+        let items = ["\u{1b}[32mgit\u{1b}[0m", "node"];
+        assert_eq!(columns(&items, &opts(9)), "\u{1b}[32mgit\u{1b}[0m  node\n");
+    }
+
+    #[test]
+    fn test_panel_fits_widest_line() {
+        let rendered =
+            console::strip_ansi_codes(&panel("Disk", "free: 12 GB\nused: 4 GB")).to_string();
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].contains(" Disk "));
+        assert!(lines[1].contains(" free: 12 GB "));
+        let width = lines[0].chars().count();
+        assert!(lines.iter().all(|line| line.chars().count() == width));
+    }
+
+    #[test]
+    fn test_summary_card_skips_zero_counts() {
+        let mut card = SummaryCard::new("Summary");
+        card.count(Status::Success, "Installed", 12)
+            .count(Status::Error, "Failed", 0)
+            .row(Status::Info, "Time", "3s");
+        let rendered = console::strip_ansi_codes(&card.render()).to_string();
+        let icons = theme().icons;
+        assert!(rendered.contains(&format!("{} Installed  12", icons.success)));
+        assert!(rendered.contains(&format!("{} Time       3s", icons.info)));
+        assert!(!rendered.contains("Failed"));
+    }
+}
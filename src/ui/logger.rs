@@ -0,0 +1,88 @@
+//! Logger - `log` records drawn like the rest of bossa's output.
+//!
+//! Warnings and errors from bossa and its crates get the same icons and
+//! colors as [`warn`](super::warn) and [`error`](super::error); info lines
+//! get the info icon; debug and trace lines are dimmed and name the module
+//! they came from. Everything goes to stderr, so logging never mixes with
+//! output meant for pipes.
+
+use colored::Colorize;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use super::theme::theme;
+
+struct Logger {
+    level: LevelFilter,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}", format_record(record));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Send `log` records up to `level` to stderr, once at startup.
+pub fn init(level: LevelFilter) -> Result<(), log::SetLoggerError> {
+    log::set_boxed_logger(Box::new(Logger { level }))?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+/// A record as a themed line
+fn format_record(record: &Record) -> String {
+    let t = theme();
+    let message = record.args().to_string();
+    match record.level() {
+        Level::Error => format!("{} {}", t.error(t.icons.error), message.color(t.error)),
+        Level::Warn => format!("{} {}", t.warn(t.icons.warn), message.color(t.warn)),
+        Level::Info => format!("{} {message}", t.info(t.icons.info)),
+        level @ (Level::Debug | Level::Trace) => t
+            .dim(&format!(
+                "{} {}: {message}",
+                level.as_str().to_lowercase(),
+                record.target()
+            ))
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(level: Level, message: &str) -> String {
+        let line = format_record(
+            &Record::builder()
+                .level(level)
+                .target("brewkit::client")
+                .args(format_args!("{message}"))
+                .build(),
+        );
+        console::strip_ansi_codes(&line).to_string()
+    }
+
+    #[test]
+    fn test_format_record() {
+        let icons = theme().icons;
+        assert_eq!(
+            line(Level::Warn, "tap is deprecated"),
+            format!("{} tap is deprecated", icons.warn)
+        );
+        assert_eq!(
+            line(Level::Info, "fetching"),
+            format!("{} fetching", icons.info)
+        );
+        assert_eq!(
+            line(Level::Debug, "running brew"),
+            "debug brewkit::client: running brew"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Markdown - draw the markdown-ish text tools and checks come with.
+//!
+//! Not a full CommonMark renderer: it handles what short notes use, which
+//! is headings, `**bold**`, `*italic*`, `` `code` ``, fenced code blocks,
+//! lists and quotes. Anything else is shown as written.
+
+use colored::Colorize;
+
+use super::theme::theme;
+
+/// How a piece of inline text is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Plain,
+    Bold,
+    Italic,
+    Code,
+}
+
+/// Render markdown for the terminal, one output line per input line
+pub fn render(text: &str) -> String {
+    let t = theme();
+    let mut out = String::new();
+    let mut in_code = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        let rendered = if in_code {
+            format!("    {}", t.accent(line))
+        } else if let Some(heading) = heading(trimmed) {
+            t.accent(&plain(heading)).bold().to_string()
+        } else if let Some(item) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            format!("{indent}{} {}", t.accent(t.icons.bullet), inline(item))
+        } else if let Some((number, item)) = numbered(trimmed) {
+            format!(
+                "{indent}{} {}",
+                t.accent(&format!("{number}.")),
+                inline(item)
+            )
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            format!(
+                "{indent}{} {}",
+                t.dim("│"),
+                inline(quote.trim_start()).italic()
+            )
+        } else {
+            format!("{indent}{}", inline(trimmed))
+        };
+        out.push_str(&rendered);
+        out.push('\n');
+    }
+    out
+}
+
+/// The text of a `#` heading
+fn heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    ((1..=6).contains(&level) && text.starts_with(' ')).then(|| text.trim())
+}
+
+/// The number and text of a `1. item` list item
+fn numbered(line: &str) -> Option<(&str, &str)> {
+    let (number, item) = line.split_once(". ")?;
+    (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit())).then_some((number, item))
+}
+
+/// A line with its inline markup drawn
+fn inline(text: &str) -> String {
+    let t = theme();
+    parse_inline(text)
+        .into_iter()
+        .map(|(style, text)| match style {
+            Style::Plain => text,
+            Style::Bold => text.bold().to_string(),
+            Style::Italic => text.italic().to_string(),
+            Style::Code => t.accent(&text).to_string(),
+        })
+        .collect()
+}
+
+/// A line's text without inline markup
+fn plain(text: &str) -> String {
+    parse_inline(text)
+        .into_iter()
+        .map(|(_, text)| text)
+        .collect()
+}
+
+/// Split a line into styled pieces. Unclosed markers are kept as text, and
+/// `_` only counts at the start of a word, so `snake_case` stays as it is.
+fn parse_inline(text: &str) -> Vec<(Style, String)> {
+    let mut pieces = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        let at_word_start = !plain.chars().last().is_some_and(char::is_alphanumeric);
+        let marker = match c {
+            '`' => Some(("`", Style::Code)),
+            '*' if rest.starts_with("**") => Some(("**", Style::Bold)),
+            '*' => Some(("*", Style::Italic)),
+            '_' if at_word_start && rest.starts_with("__") => Some(("__", Style::Bold)),
+            '_' if at_word_start => Some(("_", Style::Italic)),
+            _ => None,
+        };
+        let span = marker.and_then(|(marker, style)| {
+            let inner = &rest[marker.len()..];
+            let end = inner.find(marker).filter(|&end| end > 0)?;
+            Some((style, &inner[..end], &inner[end + marker.len()..]))
+        });
+        match span {
+            Some((style, inner, after)) => {
+                if !plain.is_empty() {
+                    pieces.push((Style::Plain, std::mem::take(&mut plain)));
+                }
+                pieces.push((style, inner.to_string()));
+                rest = after;
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        pieces.push((Style::Plain, plain));
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_inline() {
+        assert_eq!(
+            parse_inline("run `bossa doctor` **now**, *please*"),
+            [
+                (Style::Plain, "run ".to_string()),
+                (Style::Code, "bossa doctor".to_string()),
+                (Style::Plain, " ".to_string()),
+                (Style::Bold, "now".to_string()),
+                (Style::Plain, ", ".to_string()),
+                (Style::Italic, "please".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_inline("set snake_case_name and 2 * 3"),
+            [(Style::Plain, "set snake_case_name and 2 * 3".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_render_blocks() {
+        let text = "## Next steps\n\n1. Add to `PATH`:\n```sh\nexport PATH=\"$HOME/bin:$PATH\"\n```\n- restart the shell\n> see the docs";
+        let rendered = console::strip_ansi_codes(&render(text)).to_string();
+        let bullet = theme().icons.bullet;
+        assert_eq!(
+            rendered,
+            format!(
+                "Next steps\n\n1. Add to PATH:\n    export PATH=\"$HOME/bin:$PATH\"\n{bullet} restart the shell\n│ see the docs\n"
+            )
+        );
+    }
+}
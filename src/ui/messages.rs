@@ -0,0 +1,33 @@
+//! Status messages, drawn with the current theme.
+
+use super::theme::theme;
+use colored::Colorize;
+
+/// Print a success message.
+pub fn success(msg: &str) {
+    let t = theme();
+    println!("{} {msg}", t.success(t.icons.success));
+}
+
+/// Print an error message to stderr.
+pub fn error(msg: &str) {
+    let t = theme();
+    eprintln!("{} {}", t.error(t.icons.error), msg.color(t.error));
+}
+
+/// Print a warning.
+pub fn warn(msg: &str) {
+    let t = theme();
+    println!("{} {}", t.warn(t.icons.warn), msg.color(t.warn));
+}
+
+/// Print an informational message.
+pub fn info(msg: &str) {
+    let t = theme();
+    println!("{} {msg}", t.info(t.icons.info));
+}
+
+/// Print secondary text, like hints.
+pub fn dim(msg: &str) {
+    println!("{}", theme().dim(msg));
+}
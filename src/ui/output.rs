@@ -0,0 +1,275 @@
+//! Output - commands write records, the chosen format draws them.
+//!
+//! Instead of printing, a command hands [`Record`]s (or any serializable
+//! value) to an [`Output`]. The same records come out as styled text for
+//! people, as JSON lines for programs, or as tab-separated `key value`
+//! lines for shell scripts, depending on the format picked at startup with
+//! [`set_output_format`].
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use serde::ser::SerializeMap;
+use serde_json::Value;
+use std::fs::File;
+use std::io::Write;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use super::theme::theme;
+
+/// How command output is written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Styled text for people
+    #[default]
+    Human,
+    /// One JSON object per line
+    Json,
+    /// Tab-separated `key value` lines, no styling
+    ///
+    /// Not offered by `--format` until every command renders through the sink.
+    #[value(skip)]
+    Plain,
+}
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Set the output format for all commands, once at startup.
+pub fn set_output_format(format: OutputFormat) {
+    let _ = FORMAT.set(format);
+}
+
+/// The output format, human unless another was set.
+pub fn output_format() -> OutputFormat {
+    FORMAT.get().copied().unwrap_or_default()
+}
+
+/// The real stdout, once [`reserve_stdout`] pointed fd 1 at stderr
+static RESERVED: OnceLock<Mutex<File>> = OnceLock::new();
+
+/// Keep stdout for machine-readable lines, for the rest of the run.
+///
+/// Anything else written to stdout afterwards, from status messages to
+/// stray `println!`s and the output of child processes, goes to stderr;
+/// only [`write_line`] still reaches stdout.
+#[cfg(unix)]
+#[allow(unsafe_code)] // dup/dup2 require unsafe FFI
+pub fn reserve_stdout() -> Result<()> {
+    use std::os::fd::FromRawFd;
+
+    if RESERVED.get().is_some() {
+        return Ok(());
+    }
+    std::io::stdout().flush()?;
+
+    // SAFETY: dup and dup2 only touch this process's standard descriptors.
+    // The duplicate is checked, then owned (and closed) by the File.
+    let saved = unsafe {
+        let fd = libc::dup(libc::STDOUT_FILENO);
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let saved = File::from_raw_fd(fd);
+        if libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        saved
+    };
+    let _ = RESERVED.set(Mutex::new(saved));
+    Ok(())
+}
+
+/// Keep stdout for machine-readable lines (only on Unix; a no-op elsewhere)
+#[cfg(not(unix))]
+pub fn reserve_stdout() -> Result<()> {
+    Ok(())
+}
+
+/// Write one machine-readable line to stdout, even a reserved one
+///
+/// Lines from parallel threads don't interleave.
+pub fn write_line(line: &str) -> std::io::Result<()> {
+    match RESERVED.get() {
+        Some(stdout) => {
+            let mut stdout = stdout.lock().unwrap_or_else(PoisonError::into_inner);
+            writeln!(stdout, "{line}")
+        }
+        None => writeln!(std::io::stdout().lock(), "{line}"),
+    }
+}
+
+/// A titled set of named fields, kept in the order they were added
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Record {
+    title: Option<String>,
+    fields: Vec<(String, Value)>,
+}
+
+impl Record {
+    /// Start a record with a title, shown as a heading to people
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: Some(title.to_string()),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Start a record without a title
+    pub fn untitled() -> Self {
+        Self::default()
+    }
+
+    /// Add a field; values that can't be serialized become null
+    pub fn field(mut self, key: &str, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).unwrap_or(Value::Null);
+        self.fields.push((key.to_string(), value));
+        self
+    }
+}
+
+impl Serialize for Record {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.fields.len()))?;
+        for (key, value) in &self.fields {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Where commands write their results
+pub struct Output {
+    format: OutputFormat,
+    writer: Box<dyn Write + Send>,
+}
+
+impl Output {
+    /// Write to stdout in the format set at startup
+    pub fn stdout() -> Self {
+        Self::new(output_format(), std::io::stdout())
+    }
+
+    /// Write to `writer` in `format`
+    pub fn new(format: OutputFormat, writer: impl Write + Send + 'static) -> Self {
+        Self {
+            format,
+            writer: Box::new(writer),
+        }
+    }
+
+    /// The format being written
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    /// Whether output is for programs rather than people
+    pub fn is_json(&self) -> bool {
+        self.format == OutputFormat::Json
+    }
+
+    /// Write a record
+    pub fn record(&mut self, record: &Record) -> Result<()> {
+        match self.format {
+            OutputFormat::Human => {
+                let t = theme();
+                if let Some(title) = &record.title {
+                    writeln!(self.writer, "{}", title.bold())?;
+                }
+                for (key, value) in &record.fields {
+                    writeln!(
+                        self.writer,
+                        "  {} {}",
+                        t.dim(&format!("{key}:")),
+                        display(value)
+                    )?;
+                }
+            }
+            OutputFormat::Json => {
+                writeln!(self.writer, "{}", serde_json::to_string(record)?)?;
+            }
+            OutputFormat::Plain => {
+                for (key, value) in &record.fields {
+                    writeln!(self.writer, "{key}\t{}", display(value))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write any serializable value: as JSON for programs, and by calling
+    /// `human` otherwise, for commands with a layout of their own
+    pub fn emit<T: Serialize>(&mut self, value: &T, human: impl FnOnce()) -> Result<()> {
+        if self.is_json() {
+            self.json(value)
+        } else {
+            human();
+            Ok(())
+        }
+    }
+
+    /// Write a value as one line of JSON, whatever the format
+    pub fn json<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        writeln!(self.writer, "{}", serde_json::to_string(value)?)?;
+        Ok(())
+    }
+}
+
+/// A JSON value as plain text: strings unquoted, arrays comma-separated
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(display).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A writer tests can read back
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn render(format: OutputFormat, record: &Record) -> String {
+        let buffer = Buffer::default();
+        Output::new(format, buffer.clone()).record(record).unwrap();
+        let bytes = buffer.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_record_formats() {
+        let record = Record::new("Homebrew")
+            .field("formulas", 42)
+            .field("outdated", ["git", "node"])
+            .field("prefix", "/opt/homebrew");
+
+        assert_eq!(
+            render(OutputFormat::Json, &record),
+            "{\"formulas\":42,\"outdated\":[\"git\",\"node\"],\"prefix\":\"/opt/homebrew\"}\n"
+        );
+        assert_eq!(
+            render(OutputFormat::Plain, &record),
+            "formulas\t42\noutdated\tgit, node\nprefix\t/opt/homebrew\n"
+        );
+        let human = render(OutputFormat::Human, &record);
+        assert!(human.contains("Homebrew"));
+        assert!(human.contains("/opt/homebrew"));
+    }
+}
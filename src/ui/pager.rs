@@ -0,0 +1,100 @@
+//! Pager - long output through `$PAGER`, so it can be scrolled and searched.
+//!
+//! Output is paged only when stdout is a terminal and it wouldn't fit on
+//! the screen. `--no-pager` turns paging off, as does an empty `PAGER` or
+//! `PAGER=cat`.
+
+use anyhow::{Context, Result};
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The pager when `PAGER` isn't set: quit if it fits on one screen, keep
+/// colors, and leave the output on screen after quitting
+const DEFAULT_PAGER: &str = "less -FRX";
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn paging off for all output, as `--no-pager` does.
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether output would go through a pager, if it were long enough
+pub fn enabled() -> bool {
+    !DISABLED.load(Ordering::Relaxed)
+        && pager_command().is_some()
+        && std::io::stdout().is_terminal()
+}
+
+/// Print `content`, through the pager when it's taller than the terminal.
+///
+/// Falls back to printing directly if the pager can't be started.
+pub fn maybe_page(content: &str) -> Result<()> {
+    let fits = console::Term::stdout()
+        .size_checked()
+        .is_none_or(|(rows, _)| content.lines().count() < usize::from(rows));
+    if fits || !enabled() {
+        print!("{content}");
+        return Ok(());
+    }
+
+    let Some((program, args)) = pager_command() else {
+        print!("{content}");
+        return Ok(());
+    };
+    let Ok(mut child) = Command::new(&program)
+        .args(&args)
+        .stdin(Stdio::piped())
+        .spawn()
+    else {
+        print!("{content}");
+        return Ok(());
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closes its input when quit early; that's not an error
+        match stdin.write_all(content.as_bytes()) {
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
+            result => result.with_context(|| format!("Failed to write to {program}"))?,
+        }
+    }
+    child
+        .wait()
+        .with_context(|| format!("Failed to wait for {program}"))?;
+    Ok(())
+}
+
+/// The pager program and its arguments, from `PAGER`; `None` when paging
+/// is turned off with an empty `PAGER` or `cat`
+fn pager_command() -> Option<(String, Vec<String>)> {
+    parse_pager(std::env::var("PAGER").ok().as_deref())
+}
+
+fn parse_pager(pager: Option<&str>) -> Option<(String, Vec<String>)> {
+    let mut words = pager.unwrap_or(DEFAULT_PAGER).split_whitespace();
+    let program = words.next()?;
+    if program == "cat" {
+        return None;
+    }
+    Some((program.to_string(), words.map(String::from).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pager() {
+        assert_eq!(
+            parse_pager(None),
+            Some(("less".to_string(), vec!["-FRX".to_string()]))
+        );
+        assert_eq!(
+            parse_pager(Some("most")),
+            Some(("most".to_string(), vec![]))
+        );
+        assert_eq!(parse_pager(Some("")), None);
+        assert_eq!(parse_pager(Some("cat")), None);
+    }
+}
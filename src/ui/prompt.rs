@@ -0,0 +1,183 @@
+//! Prompts - confirm, select, multi-select and text input.
+//!
+//! Thin wrappers over dialoguer, drawn with the current theme. When there's
+//! no terminal to ask on (CI, pipes, cron) nothing blocks: confirmations are
+//! declined, selections fall back to their defaults, and a prompt with no
+//! default fails with an error naming it.
+
+use anyhow::{Context, Result, bail};
+use dialoguer::console;
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme as DialoguerTheme};
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+use std::io::IsTerminal;
+
+use super::messages::dim;
+use super::theme::theme;
+
+/// Whether prompts can be answered: stdin and stderr are both terminals.
+pub fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// Ask a yes/no question; `default` is the answer Enter picks.
+///
+/// Without a terminal the answer is no, whatever the default, so nothing
+/// destructive happens unattended.
+pub fn confirm(prompt: &str, default: bool) -> Result<bool> {
+    if !is_interactive() {
+        dim(&format!("{prompt} (no terminal, answering no)"));
+        return Ok(false);
+    }
+    Confirm::with_theme(dialoguer_theme().as_ref())
+        .with_prompt(prompt)
+        .default(default)
+        .interact()
+        .with_context(|| format!("Failed to read answer to: {prompt}"))
+}
+
+/// Pick one of `items`, returning its index.
+///
+/// Without a terminal the default is picked, if there is one.
+pub fn select<T: ToString>(prompt: &str, items: &[T], default: Option<usize>) -> Result<usize> {
+    if !is_interactive() {
+        return match default {
+            Some(index) => Ok(index),
+            None => bail!("Can't ask \"{prompt}\" without a terminal"),
+        };
+    }
+    let theme = dialoguer_theme();
+    let mut select = Select::with_theme(theme.as_ref())
+        .with_prompt(prompt)
+        .items(items);
+    if let Some(index) = default {
+        select = select.default(index);
+    }
+    select
+        .interact()
+        .with_context(|| format!("Failed to read answer to: {prompt}"))
+}
+
+/// Pick any of `items`, returning their indices; `defaults` are checked to
+/// begin with.
+///
+/// Without a terminal the defaults are picked.
+pub fn multi_select<T: ToString>(
+    prompt: &str,
+    items: &[T],
+    defaults: &[bool],
+) -> Result<Vec<usize>> {
+    if !is_interactive() {
+        return Ok(defaults
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &checked)| checked.then_some(index))
+            .collect());
+    }
+    MultiSelect::with_theme(dialoguer_theme().as_ref())
+        .with_prompt(prompt)
+        .items(items)
+        .defaults(defaults)
+        .interact()
+        .with_context(|| format!("Failed to read answer to: {prompt}"))
+}
+
+/// Ask for a line of text, with `default` prefilled.
+///
+/// An empty answer is only accepted with an empty default, `Some("")`.
+/// Without a terminal the default is the answer, if there is one.
+pub fn input(prompt: &str, default: Option<&str>) -> Result<String> {
+    if !is_interactive() {
+        return match default {
+            Some(value) => Ok(value.to_string()),
+            None => bail!("Can't ask \"{prompt}\" without a terminal"),
+        };
+    }
+    let theme = dialoguer_theme();
+    let mut input = Input::<String>::with_theme(theme.as_ref())
+        .with_prompt(prompt)
+        .allow_empty(default == Some(""));
+    if let Some(value) = default.filter(|value| !value.is_empty()) {
+        input = input.with_initial_text(value);
+    }
+    input
+        .interact_text()
+        .with_context(|| format!("Failed to read answer to: {prompt}"))
+}
+
+/// Asks the user to confirm the declarative executor's changes.
+///
+/// Without a terminal the changes are declined.
+pub struct ConfirmPrompt;
+
+impl declarative::ConfirmCallback for ConfirmPrompt {
+    fn confirm(&mut self, prompt: &str) -> Result<bool> {
+        confirm(prompt, true)
+    }
+}
+
+/// dialoguer's theme in the current theme's colors and icons, or plain
+/// when colors are off
+fn dialoguer_theme() -> Box<dyn DialoguerTheme> {
+    if !console::colors_enabled_stderr() {
+        return Box::new(SimpleTheme);
+    }
+    let t = theme();
+    let icons = t.icons;
+    Box::new(ColorfulTheme {
+        prompt_prefix: style(t.accent).apply_to("?".to_string()),
+        success_prefix: style(t.success).apply_to(icons.success.to_string()),
+        error_prefix: style(t.error).apply_to(icons.error.to_string()),
+        error_style: style(t.error),
+        hint_style: style(t.dim),
+        values_style: style(t.accent),
+        active_item_style: style(t.accent),
+        active_item_prefix: style(t.accent).apply_to(icons.arrow.to_string()),
+        checked_item_prefix: style(t.success).apply_to(icons.success.to_string()),
+        unchecked_item_prefix: style(t.dim).apply_to(icons.pending.to_string()),
+        ..ColorfulTheme::default()
+    })
+}
+
+/// A console style for a theme color
+fn style(color: colored::Color) -> console::Style {
+    use colored::Color as C;
+    let style = console::Style::new().for_stderr();
+    match color {
+        C::Black => style.black(),
+        C::Red => style.red(),
+        C::Green => style.green(),
+        C::Yellow => style.yellow(),
+        C::Blue => style.blue(),
+        C::Magenta => style.magenta(),
+        C::Cyan => style.cyan(),
+        C::White => style.white(),
+        C::BrightBlack => style.black().bright(),
+        C::BrightRed => style.red().bright(),
+        C::BrightGreen => style.green().bright(),
+        C::BrightYellow => style.yellow().bright(),
+        C::BrightBlue => style.blue().bright(),
+        C::BrightMagenta => style.magenta().bright(),
+        C::BrightCyan => style.cyan().bright(),
+        C::BrightWhite => style.white().bright(),
+        C::AnsiColor(n) => style.color256(n),
+        C::TrueColor { r, g, b } => style.color256(ansi256(r, g, b)),
+    }
+}
+
+/// The nearest color in the 6x6x6 cube of the 256-color palette
+fn ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |c: u8| (u16::from(c) * 5 / 255) as u8;
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi256() {
+        assert_eq!(ansi256(0, 0, 0), 16);
+        assert_eq!(ansi256(255, 255, 255), 231);
+        assert_eq!(ansi256(255, 0, 0), 196);
+    }
+}
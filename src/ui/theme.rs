@@ -0,0 +1,199 @@
+//! Themes - the colors and icons bossa's output is drawn with.
+//!
+//! A [`Theme`] is picked once at startup with [`set_theme`] and read by
+//! every `ui` module through [`theme`]. The dark theme is the classic
+//! cyan/green/yellow scheme; the light theme trades the colors that wash
+//! out on a light background for darker ones; the ASCII theme draws icons
+//! any terminal or log file can show.
+
+use colored::{Color, ColoredString, Colorize};
+use std::sync::OnceLock;
+
+/// Named themes, as chosen with `--theme`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ThemeChoice {
+    /// Bright colors for dark backgrounds
+    #[default]
+    Dark,
+    /// Darker colors for light backgrounds
+    Light,
+    /// ASCII icons only, for limited terminals and logs
+    Ascii,
+}
+
+/// Icons shown before messages and statuses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Icons {
+    pub success: &'static str,
+    pub error: &'static str,
+    pub warn: &'static str,
+    pub info: &'static str,
+    pub arrow: &'static str,
+    pub skipped: &'static str,
+    pub pending: &'static str,
+    pub bullet: &'static str,
+    /// Drawn repeatedly for horizontal rules
+    pub rule: &'static str,
+}
+
+impl Icons {
+    /// Unicode symbols
+    pub const UNICODE: Self = Self {
+        success: "✓",
+        error: "✗",
+        warn: "⚠",
+        info: "ℹ",
+        arrow: "→",
+        skipped: "⊘",
+        pending: "○",
+        bullet: "•",
+        rule: "─",
+    };
+
+    /// Plain ASCII
+    pub const ASCII: Self = Self {
+        success: "+",
+        error: "x",
+        warn: "!",
+        info: "i",
+        arrow: "->",
+        skipped: "-",
+        pending: "o",
+        bullet: "*",
+        rule: "-",
+    };
+}
+
+/// Colors and icons for all output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub success: Color,
+    pub error: Color,
+    pub warn: Color,
+    pub info: Color,
+    /// Headings, counters and other highlights
+    pub accent: Color,
+    /// Secondary text, like hints and labels
+    pub dim: Color,
+    pub icons: Icons,
+}
+
+impl Theme {
+    /// Bright colors for dark backgrounds
+    pub const fn dark() -> Self {
+        Self {
+            success: Color::Green,
+            error: Color::Red,
+            warn: Color::Yellow,
+            info: Color::Blue,
+            accent: Color::Cyan,
+            dim: Color::BrightBlack,
+            icons: Icons::UNICODE,
+        }
+    }
+
+    /// Darker colors for light backgrounds, where yellow and cyan are hard
+    /// to read
+    pub const fn light() -> Self {
+        Self {
+            success: Color::Green,
+            error: Color::Red,
+            warn: Color::Magenta,
+            info: Color::Blue,
+            accent: Color::Blue,
+            dim: Color::BrightBlack,
+            icons: Icons::UNICODE,
+        }
+    }
+
+    /// The dark theme's colors with ASCII icons
+    pub const fn ascii() -> Self {
+        Self {
+            icons: Icons::ASCII,
+            ..Self::dark()
+        }
+    }
+
+    /// `text` in the success color
+    pub fn success(&self, text: &str) -> ColoredString {
+        text.color(self.success)
+    }
+
+    /// `text` in the error color
+    pub fn error(&self, text: &str) -> ColoredString {
+        text.color(self.error)
+    }
+
+    /// `text` in the warning color
+    pub fn warn(&self, text: &str) -> ColoredString {
+        text.color(self.warn)
+    }
+
+    /// `text` in the info color
+    pub fn info(&self, text: &str) -> ColoredString {
+        text.color(self.info)
+    }
+
+    /// `text` in the accent color
+    pub fn accent(&self, text: &str) -> ColoredString {
+        text.color(self.accent)
+    }
+
+    /// `text` in the dim color
+    pub fn dim(&self, text: &str) -> ColoredString {
+        text.color(self.dim)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl From<ThemeChoice> for Theme {
+    fn from(choice: ThemeChoice) -> Self {
+        match choice {
+            ThemeChoice::Dark => Self::dark(),
+            ThemeChoice::Light => Self::light(),
+            ThemeChoice::Ascii => Self::ascii(),
+        }
+    }
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Set the theme for all output, once at startup.
+///
+/// Later calls are ignored, so output never changes style midway.
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// The current theme, dark unless another was set.
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes() {
+        assert_eq!(Theme::from(ThemeChoice::Dark), Theme::default());
+        assert_eq!(Theme::from(ThemeChoice::Ascii).icons, Icons::ASCII);
+        assert_ne!(Theme::light().accent, Theme::dark().accent);
+
+        let ascii = Icons::ASCII;
+        for icon in [
+            ascii.success,
+            ascii.error,
+            ascii.warn,
+            ascii.info,
+            ascii.rule,
+        ] {
+            assert!(icon.is_ascii());
+        }
+    }
+}
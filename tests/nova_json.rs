@@ -0,0 +1,98 @@
+//! `bossa nova --json` keeps stdout for events
+
+#![cfg(unix)]
+
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn nova_json_stdout_is_only_json_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("config")).unwrap();
+    fs::create_dir_all(root.join("dotfiles/zsh")).unwrap();
+    fs::create_dir_all(root.join("home")).unwrap();
+    fs::write(root.join("dotfiles/zsh/.zshrc"), "").unwrap();
+    fs::write(
+        root.join("config/config.toml"),
+        format!(
+            "[symlinks]\nsource = \"{}\"\ntarget = \"${{HOME}}\"\npackages = [\"zsh\"]\n",
+            root.join("dotfiles").display()
+        ),
+    )
+    .unwrap();
+
+    // The dotfiles stage has nothing configured, and says so
+    let output = Command::new(env!("CARGO_BIN_EXE_bossa"))
+        .args(["nova", "--json", "--dry-run", "--only", "symlinks,dotfiles"])
+        .env("HOME", root.join("home"))
+        .env("BOSSA_CONFIG_DIR", root.join("config"))
+        .env("BOSSA_STATE_DIR", root.join("state"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let events: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("{e}: {line:?}")))
+        .collect();
+    assert_eq!(events.first().unwrap()["event"], "plan_started");
+    assert_eq!(events.last().unwrap()["event"], "plan_finished");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No [dotfiles] config"));
+}
+
+#[test]
+fn nova_json_reports_failures_in_a_triage_event() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("config")).unwrap();
+    fs::create_dir_all(root.join("dotfiles/zsh")).unwrap();
+    fs::write(root.join("dotfiles/zsh/.zshrc"), "").unwrap();
+    // The link's parent directory can't be created where a file is
+    fs::write(root.join("blocker"), "").unwrap();
+    fs::write(
+        root.join("config/config.toml"),
+        format!(
+            "[symlinks]\nsource = \"{}\"\ntarget = \"{}\"\npackages = [\"zsh\"]\n",
+            root.join("dotfiles").display(),
+            root.join("blocker").display()
+        ),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bossa"))
+        .args(["nova", "--json", "--yes", "--only", "symlinks"])
+        .env("HOME", root)
+        .env("BOSSA_CONFIG_DIR", root.join("config"))
+        .env("BOSSA_STATE_DIR", root.join("state"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let events: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let names: Vec<_> = events
+        .iter()
+        .map(|e| e["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "plan_started",
+            "resource_started",
+            "resource_finished",
+            "triage",
+            "plan_finished"
+        ]
+    );
+    let triage = &events[3];
+    assert_eq!(triage["failed"], 1);
+    assert_eq!(
+        triage["groups"][0]["resources"][0]["id"],
+        root.join("blocker/.zshrc").display().to_string()
+    );
+}